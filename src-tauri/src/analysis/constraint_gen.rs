@@ -1,4 +1,21 @@
-use super::PathNode;
+use super::slicer::{BranchCondition, Condition};
+use super::{PathNode, SinkType};
+
+/// Substrings whose presence at a sink demonstrates injection, per sink type
+pub fn injection_markers(sink_type: &SinkType) -> &'static [&'static str] {
+    match sink_type {
+        SinkType::SqlInjection => &["' OR '1'='1"],
+        SinkType::CommandInjection => &[";", "|", "&", "`", "$("],
+        SinkType::CodeInjection => &["__import__"],
+        SinkType::PathTraversal => &["../"],
+        SinkType::Xss => &["<"],
+        SinkType::OpenRedirect => &["//"],
+        SinkType::HeaderInjection => &["\r", "\n"],
+        SinkType::NoSqlInjection => &["$"],
+        SinkType::LdapInjection => &["*", ")("],
+        _ => &[],
+    }
+}
 
 /// Generates SMT-LIB constraints from an attack path
 pub struct ConstraintGenerator;
@@ -10,6 +27,19 @@ impl ConstraintGenerator {
 
     /// Convert a sequence of path nodes into an SMT-LIB script
     pub fn generate_smt(&self, nodes: &[PathNode], sink_var: &str) -> String {
+        self.generate_smt_for(nodes, sink_var, &SinkType::SqlInjection, &[])
+    }
+
+    /// Like `generate_smt`, with the injection goal for `sink_type` and the
+    /// branch conditions guarding the sink asserted, so paths the conditions
+    /// rule out come back UNSAT
+    pub fn generate_smt_for(
+        &self,
+        nodes: &[PathNode],
+        sink_var: &str,
+        sink_type: &SinkType,
+        conditions: &[BranchCondition],
+    ) -> String {
         let mut script = String::new();
         script.push_str("(set-logic QF_S)\n"); // Logic for Strings
         
@@ -44,19 +74,107 @@ impl ConstraintGenerator {
             }
         }
 
-        let target = if declared.contains(&sink_var.to_string()) {
-            sink_var.to_string()
+        for branch in conditions {
+            let mut vars = Vec::new();
+            condition_vars(&branch.condition, &mut vars);
+            for var in vars {
+                let name = smt_name(&var);
+                if !declared.contains(&name) && is_valid_var_name(&name) {
+                    script.push_str(&format!("(declare-const {} String)\n", name));
+                    declared.push(name);
+                }
+            }
+            if let Some(expr) = self.condition_smt(&branch.condition, branch.holds) {
+                script.push_str(&format!("(assert {})\n", expr));
+            }
+        }
+
+        let sink_name = smt_name(sink_var);
+        let target = if declared.contains(&sink_name) {
+            sink_name
         } else {
             declared.last().cloned().unwrap_or(sink_var.to_string())
         };
 
-        script.push_str(&format!("(assert (str.contains {} \"' OR '1'='1\"))\n", target));
+        match injection_markers(sink_type) {
+            [] => {}
+            [marker] => script.push_str(&format!("(assert (str.contains {} {}))\n", target, smt_string(marker))),
+            markers => {
+                let options: Vec<String> = markers
+                    .iter()
+                    .map(|m| format!("(str.contains {} {})", target, smt_string(m)))
+                    .collect();
+                script.push_str(&format!("(assert (or {}))\n", options.join(" ")));
+            }
+        }
         script.push_str("(check-sat)\n");
         script.push_str("(get-model)\n");
 
         script
     }
 
+    /// SMT expression for `condition` (negated when it does not hold), or
+    /// `None` when it cannot be modelled and so constrains nothing
+    fn condition_smt(&self, condition: &Condition, holds: bool) -> Option<String> {
+        let expr = match condition {
+            Condition::Equals { var, value } => format!("(= {} {})", smt_name(var), smt_string(value)),
+            Condition::OneOf { var, values } => match values.as_slice() {
+                [] => "false".to_string(),
+                values => {
+                    let options: Vec<String> = values
+                        .iter()
+                        .map(|v| format!("(= {} {})", smt_name(var), smt_string(v)))
+                        .collect();
+                    format!("(or {})", options.join(" "))
+                }
+            },
+            Condition::StartsWith { var, prefix } => format!("(str.prefixof {} {})", smt_string(prefix), smt_name(var)),
+            Condition::EndsWith { var, suffix } => format!("(str.suffixof {} {})", smt_string(suffix), smt_name(var)),
+            Condition::Contains { var, needle } => format!("(str.contains {} {})", smt_name(var), smt_string(needle)),
+            Condition::IsDigits { var } => format!("(str.in_re {} (re.+ (re.range \"0\" \"9\")))", smt_name(var)),
+            Condition::IsAlnum { var } => format!(
+                "(str.in_re {} (re.+ (re.union (re.range \"a\" \"z\") (re.range \"A\" \"Z\") (re.range \"0\" \"9\"))))",
+                smt_name(var)
+            ),
+            Condition::Not(inner) => return self.condition_smt(inner, !holds),
+            // Under negation `and` becomes `or` (De Morgan)
+            Condition::And(parts) | Condition::Or(parts) => {
+                let conjunction = matches!(condition, Condition::And(_)) == holds;
+                let parts: Vec<Option<String>> = parts.iter().map(|p| self.condition_smt(p, holds)).collect();
+                return if conjunction {
+                    // Unknown conjuncts are dropped, which only widens the path
+                    let known: Vec<String> = parts.into_iter().flatten().collect();
+                    match known.len() {
+                        0 => None,
+                        1 => known.into_iter().next(),
+                        _ => Some(format!("(and {})", known.join(" "))),
+                    }
+                } else {
+                    // One unknown disjunct could always be true
+                    let known: Option<Vec<String>> = parts.into_iter().collect();
+                    known.map(|k| format!("(or {})", k.join(" ")))
+                };
+            }
+            Condition::Opaque(_) => return None,
+        };
+        Some(if holds { expr } else { format!("(not {})", expr) })
+    }
+
+    /// The finite set of values the conditions allow for `var`, if they pin it
+    /// down (e.g. `if cmd in ["ls", "whoami"]`)
+    pub fn allowed_values(&self, conditions: &[BranchCondition], var: &str) -> Option<Vec<String>> {
+        let mut allowed: Option<Vec<String>> = None;
+        for branch in conditions {
+            if let Some(values) = finite_values(&branch.condition, branch.holds, var) {
+                allowed = Some(match allowed {
+                    Some(current) => current.into_iter().filter(|v| values.contains(v)).collect(),
+                    None => values,
+                });
+            }
+        }
+        allowed
+    }
+
     fn parse_f_string(&self, expr: &str) -> String {
         let content = expr.trim_start_matches('f').trim_matches(|c| c == '"' || c == '\'');
         
@@ -85,6 +203,64 @@ impl ConstraintGenerator {
     }
 }
 
+fn finite_values(condition: &Condition, holds: bool, var: &str) -> Option<Vec<String>> {
+    match condition {
+        Condition::Equals { var: v, value } if holds && v == var => Some(vec![value.clone()]),
+        Condition::OneOf { var: v, values } if holds && v == var => Some(values.clone()),
+        Condition::Not(inner) => finite_values(inner, !holds, var),
+        Condition::And(parts) | Condition::Or(parts) => {
+            let sets = parts.iter().map(|p| finite_values(p, holds, var));
+            if matches!(condition, Condition::And(_)) == holds {
+                // Any pinned conjunct bounds the whole
+                sets.flatten().reduce(|a, b| a.into_iter().filter(|v| b.contains(v)).collect())
+            } else {
+                // Every disjunct must be pinned for the union to be finite
+                let sets: Option<Vec<Vec<String>>> = sets.collect();
+                sets.map(|sets| {
+                    let mut union: Vec<String> = Vec::new();
+                    for value in sets.into_iter().flatten() {
+                        if !union.contains(&value) {
+                            union.push(value);
+                        }
+                    }
+                    union
+                })
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Variables a condition tests; unmodelled conditions contribute none
+pub fn condition_vars(condition: &Condition, vars: &mut Vec<String>) {
+    match condition {
+        Condition::Equals { var, .. }
+        | Condition::OneOf { var, .. }
+        | Condition::StartsWith { var, .. }
+        | Condition::EndsWith { var, .. }
+        | Condition::Contains { var, .. }
+        | Condition::IsDigits { var }
+        | Condition::IsAlnum { var } => {
+            if !vars.contains(var) {
+                vars.push(var.clone());
+            }
+        }
+        Condition::Not(inner) => condition_vars(inner, vars),
+        Condition::And(parts) | Condition::Or(parts) => parts.iter().for_each(|p| condition_vars(p, vars)),
+        Condition::Opaque(_) => {}
+    }
+}
+
+/// SMT identifier for an access path: `cfg[host]` -> `cfg_host_`
+fn smt_name(path: &str) -> String {
+    path.chars().map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' }).collect()
+}
+
+/// SMT-LIB string literal; quotes are escaped by doubling
+fn smt_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
 fn is_valid_var_name(name: &str) -> bool {
     if name.is_empty() {
         return false;
@@ -107,11 +283,13 @@ mod tests {
         let nodes = vec![
             PathNode {
                 line: 1,
+                cell: None,
                 code: "user_id = request.args.get('id')".to_string(),
                 description: "User input".to_string(),
             },
             PathNode {
                 line: 2,
+                cell: None,
                 code: "query = f\"SELECT * FROM users WHERE id = {user_id}\"".to_string(),
                 description: "Query construction".to_string(),
             },
@@ -127,6 +305,7 @@ mod tests {
         let gen = ConstraintGenerator::new();
         let nodes = vec![PathNode {
             line: 1,
+            cell: None,
             code: "user_id = input()".to_string(),
             description: "Input".to_string(),
         }];
@@ -139,6 +318,7 @@ mod tests {
         let gen = ConstraintGenerator::new();
         let nodes = vec![PathNode {
             line: 1,
+            cell: None,
             code: "query = f\"SELECT {id}\"".to_string(),
             description: "Query".to_string(),
         }];
@@ -184,11 +364,13 @@ mod tests {
         let nodes = vec![
             PathNode {
                 line: 1,
+                cell: None,
                 code: "x = input()".to_string(),
                 description: "Input".to_string(),
             },
             PathNode {
                 line: 2,
+                cell: None,
                 code: "y = x".to_string(),
                 description: "Assign".to_string(),
             },
@@ -203,6 +385,7 @@ mod tests {
         let gen = ConstraintGenerator::new();
         let nodes = vec![PathNode {
             line: 1,
+            cell: None,
             code: "status = \"active\"".to_string(),
             description: "Literal".to_string(),
         }];
@@ -216,11 +399,13 @@ mod tests {
         let nodes = vec![
             PathNode {
                 line: 1,
+                cell: None,
                 code: "a = input()".to_string(),
                 description: "Input".to_string(),
             },
             PathNode {
                 line: 2,
+                cell: None,
                 code: "b = a".to_string(),
                 description: "Chain".to_string(),
             },
@@ -234,6 +419,7 @@ mod tests {
         let gen = ConstraintGenerator::new();
         let nodes = vec![PathNode {
             line: 1,
+            cell: None,
             code: "query = input()".to_string(),
             description: "Input".to_string(),
         }];
@@ -263,16 +449,19 @@ mod tests {
         let nodes = vec![
             PathNode {
                 line: 1,
+                cell: None,
                 code: "user_id = request.args.get('id')".to_string(),
                 description: "Input".to_string(),
             },
             PathNode {
                 line: 2,
+                cell: None,
                 code: "sanitized = user_id".to_string(),
                 description: "Pass through".to_string(),
             },
             PathNode {
                 line: 3,
+                cell: None,
                 code: "query = f\"SELECT * WHERE id = {sanitized}\"".to_string(),
                 description: "Query".to_string(),
             },
@@ -282,4 +471,62 @@ mod tests {
         assert!(result.contains("sanitized"));
         assert!(result.contains("query"));
     }
+
+    fn branch(condition: Condition, holds: bool) -> BranchCondition {
+        BranchCondition { line: 1, code: String::new(), condition, holds }
+    }
+
+    fn one_of(var: &str, values: &[&str]) -> Condition {
+        Condition::OneOf { var: var.to_string(), values: values.iter().map(|v| v.to_string()).collect() }
+    }
+
+    #[test]
+    fn test_branch_condition_asserted() {
+        let gen = ConstraintGenerator::new();
+        let conditions = vec![branch(one_of("cmd", &["ls", "whoami"]), true)];
+        let result = gen.generate_smt_for(&[], "cmd", &SinkType::CommandInjection, &conditions);
+        assert!(result.contains("(declare-const cmd String)"));
+        assert!(result.contains("(assert (or (= cmd \"ls\") (= cmd \"whoami\")))"));
+        assert!(result.contains("(str.contains cmd \";\")"));
+    }
+
+    #[test]
+    fn test_false_condition_negated() {
+        let gen = ConstraintGenerator::new();
+        let conditions = vec![branch(Condition::Not(Box::new(one_of("cmd", &["ls"]))), false)];
+        let result = gen.generate_smt_for(&[], "cmd", &SinkType::CommandInjection, &conditions);
+        assert!(result.contains("(assert (or (= cmd \"ls\")))"));
+    }
+
+    #[test]
+    fn test_opaque_conditions_constrain_nothing() {
+        let gen = ConstraintGenerator::new();
+        let opaque = Condition::Opaque("is_admin(user)".to_string());
+        let digits = Condition::IsDigits { var: "uid".to_string() };
+        // A known conjunct survives; a disjunction with an unknown part is dropped
+        let and = gen.condition_smt(&Condition::And(vec![opaque.clone(), digits.clone()]), true);
+        assert_eq!(and, Some("(str.in_re uid (re.+ (re.range \"0\" \"9\")))".to_string()));
+        assert_eq!(gen.condition_smt(&Condition::Or(vec![opaque.clone(), digits.clone()]), true), None);
+        // ...and De Morgan swaps the two under negation
+        assert_eq!(gen.condition_smt(&Condition::And(vec![opaque, digits]), false), None);
+    }
+
+    #[test]
+    fn test_smt_strings_escaped() {
+        let gen = ConstraintGenerator::new();
+        let condition = Condition::Equals { var: "cfg[mode]".to_string(), value: "say \"hi\"".to_string() };
+        assert_eq!(gen.condition_smt(&condition, true), Some("(= cfg_mode_ \"say \"\"hi\"\"\")".to_string()));
+    }
+
+    #[test]
+    fn test_allowed_values_intersection() {
+        let gen = ConstraintGenerator::new();
+        let conditions = vec![
+            branch(one_of("cmd", &["ls", "id", "whoami"]), true),
+            branch(Condition::Not(Box::new(one_of("cmd", &["id", "ls"]))), false),
+            branch(one_of("other", &["x"]), true),
+        ];
+        assert_eq!(gen.allowed_values(&conditions, "cmd"), Some(vec!["ls".to_string(), "id".to_string()]));
+        assert_eq!(gen.allowed_values(&conditions, "missing"), None);
+    }
 }
@@ -2,6 +2,8 @@
 //! 
 //! Extends the backward slicer to support cross-file taint analysis
 //! by resolving function calls to their definitions in other files.
+//! Callees are not re-analyzed per query: each file is reduced once to
+//! function summaries (see `summaries`), which callers then consult.
 
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -9,8 +11,9 @@ use std::collections::{HashMap, HashSet};
 use tree_sitter::Parser;
 
 use super::indexer::{ProjectIndexer, Symbol, SymbolKind};
-use super::slicer::{BackwardSlicer, ValueSource};
-use super::{Sink, SinkType, PathNode};
+use super::slicer::{access_path, BackwardSlicer, TaintConfig};
+use super::summaries::{fingerprint, FileSummary, FunctionSummary, ParamSink, SummaryStore};
+use super::Sink;
 
 extern "C" { fn tree_sitter_python() -> tree_sitter::Language; }
 
@@ -28,6 +31,43 @@ pub struct CrossFileFlow {
     
     /// Tainted arguments passed
     pub tainted_args: Vec<String>,
+
+    /// Caller variable tainted by the call's return value, for flows back
+    /// out of the callee
+    pub tainted_return: Option<String>,
+}
+
+/// A call and the identifiers in each of its arguments
+struct CallSite {
+    function: String,
+    line: usize,
+    /// Identifiers in each positional argument
+    args: Vec<Vec<String>>,
+    /// Identifiers in each keyword argument, by keyword
+    keyword_args: Vec<(String, Vec<String>)>,
+}
+
+impl CallSite {
+    fn identifiers(&self) -> impl Iterator<Item = &String> {
+        self.args.iter().flatten().chain(self.keyword_args.iter().flat_map(|(_, ids)| ids))
+    }
+
+    /// Identifiers passed to each parameter of `callee`, by parameter position
+    fn arguments_by_param<'a>(&'a self, callee: &FunctionSummary) -> Vec<(usize, &'a [String])> {
+        let positional = self.args.iter().enumerate().map(|(i, ids)| (i, ids.as_slice()));
+        let keywords = self
+            .keyword_args
+            .iter()
+            .filter_map(|(name, ids)| Some((callee.param_index(name)?, ids.as_slice())));
+        positional.chain(keywords).collect()
+    }
+}
+
+/// A `target = function(args)` assignment in the caller
+struct CallAssignment {
+    call: CallSite,
+    code: String,
+    targets: Vec<String>,
 }
 
 /// Cross-file analysis result
@@ -56,10 +96,11 @@ pub struct CrossFilePathNode {
 pub struct CrossFileSlicer {
     indexer: ProjectIndexer,
     parser: Parser,
-    /// Cache of already-analyzed files to prevent infinite recursion
-    analyzed_files: HashSet<PathBuf>,
-    /// Maximum recursion depth for cross-file analysis
-    max_depth: usize,
+    taint_config: TaintConfig,
+    /// Function summaries by file, computed on first use
+    store: SummaryStore,
+    /// Files whose summary is being built, to cut import cycles
+    summarizing: HashSet<PathBuf>,
 }
 
 impl CrossFileSlicer {
@@ -73,48 +114,66 @@ impl CrossFileSlicer {
         Ok(Self {
             indexer,
             parser,
-            analyzed_files: HashSet::new(),
-            max_depth: 3, // Limit depth to prevent explosion
+            taint_config: TaintConfig::default(),
+            store: SummaryStore::default(),
+            summarizing: HashSet::new(),
         })
     }
 
-    /// Index the workspace before analysis
-    pub fn index_workspace(&mut self) -> Result<usize, String> {
-        self.indexer.index_workspace()
+    /// Choose which optional sources (environment, config files) count as user input
+    pub fn with_taint_config(mut self, config: TaintConfig) -> Self {
+        self.taint_config = config;
+        self
     }
 
-    /// Analyze a file with cross-file taint tracking
-    pub fn analyze_file(&mut self, file_path: &Path) -> Result<CrossFileAnalysisResult, String> {
-        self.analyzed_files.clear();
-        self.analyze_file_internal(file_path, 0)
+    /// Start from previously saved summaries; stale ones are dropped by `index_workspace`
+    pub fn with_summaries(mut self, store: SummaryStore) -> Self {
+        self.store = store;
+        self
     }
 
-    fn analyze_file_internal(&mut self, file_path: &Path, depth: usize) -> Result<CrossFileAnalysisResult, String> {
-        if depth > self.max_depth {
-            return Ok(CrossFileAnalysisResult {
-                sinks: vec![],
-                cross_file_flows: vec![],
-                attack_path: vec![],
-            });
-        }
+    /// Summaries computed so far, for saving with the index
+    pub fn summaries(&self) -> &SummaryStore {
+        &self.store
+    }
 
-        if self.analyzed_files.contains(file_path) {
-            return Ok(CrossFileAnalysisResult {
-                sinks: vec![],
-                cross_file_flows: vec![],
-                attack_path: vec![],
-            });
+    pub fn indexer(&self) -> &ProjectIndexer {
+        &self.indexer
+    }
+
+    /// Index the workspace before analysis, dropping summaries of files that
+    /// changed since they were computed
+    pub fn index_workspace(&mut self) -> Result<usize, String> {
+        let count = self.indexer.index_workspace()?;
+        self.store.invalidate(&self.taint_config);
+        Ok(count)
+    }
+
+    /// Summarize every indexed file that has no current summary; returns how
+    /// many files were summarized
+    pub fn summarize_workspace(&mut self) -> usize {
+        let mut files = self.indexer.indexed_files();
+        files.sort();
+        let mut computed = 0;
+        for file in files {
+            if self.store.get(&file).is_none() && self.summary(&file).is_some() {
+                computed += 1;
+            }
         }
-        self.analyzed_files.insert(file_path.to_path_buf());
+        computed
+    }
 
+    /// Analyze a file with cross-file taint tracking
+    pub fn analyze_file(&mut self, file_path: &Path) -> Result<CrossFileAnalysisResult, String> {
         // Read and parse the file
         let source = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
         let tree = self.parser.parse(&source, None).ok_or("Parse failed")?;
         let source_bytes = source.as_bytes();
 
         // Run the basic backward slicer on this file
-        let mut slicer = BackwardSlicer::new();
+        let mut slicer = BackwardSlicer::with_config(self.taint_config);
         slicer.analyze(&source, &tree);
+        let return_flows = self.propagate_returns(file_path, &tree, source_bytes, &mut slicer);
 
         // Find sinks in this file
         let mut python_parser = super::python_parser::PythonParser::new()?;
@@ -138,96 +197,90 @@ impl CrossFileSlicer {
             // Ideally we parse the sink code.
             
             // Let's use a token-based approach like Prover likely does
-            let tokens: Vec<&str> = sink.code_snippet.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|s| !s.is_empty()).collect();
-            for token in tokens {
-                if slicer.is_tainted(token) {
-                    sink.tainted_vars.push(token.to_string());
+            for token in identifiers_in(&sink.code_snippet) {
+                if slicer.is_tainted(&token) {
+                    sink.tainted_vars.push(token);
                 }
             }
         }
 
         // Look for cross-file function calls
         let mut cross_file_flows = Vec::new();
-        let mut attack_path = Vec::new();
+        let mut attack_path: Vec<CrossFilePathNode> = return_flows
+            .iter()
+            .map(|(flow, code)| CrossFilePathNode {
+                file_path: file_path.to_path_buf(),
+                line: flow.caller_line,
+                code: code.clone(),
+                node_type: "CROSS_FILE_RETURN".to_string(),
+                is_entry_point: false,
+                is_sink: false,
+            })
+            .collect();
 
         // Find all function calls in the file
-        let function_calls = self.find_function_calls(tree.root_node(), source_bytes);
+        let calls = self.find_call_sites(tree.root_node(), source_bytes);
 
-        for (call_name, call_line, args) in function_calls {
+        for call in calls {
             // Try to resolve this call to another file
-            // Clone the symbol data to avoid borrow conflict with recursive call
-            let resolved = self.indexer.resolve_symbol(file_path, &call_name)
-                .filter(|s| s.file_path != file_path && s.kind == SymbolKind::Function)
-                .map(|s| (s.file_path.clone(), s.line));
-            
-            if let Some((callee_file, callee_line)) = resolved {
-                // This is a cross-file call!
-                
-                // Check if any arguments are tainted
-                let tainted_args: Vec<String> = args
-                    .iter()
-                    .filter(|arg| slicer.is_tainted(arg))
-                    .cloned()
-                    .collect();
+            let Some((callee_file, callee_line, function)) = self.resolve_call(file_path, &call.function) else {
+                continue;
+            };
+
+            // This is a cross-file call! Check if any arguments are tainted
+            let tainted_args: Vec<String> = call.identifiers().filter(|arg| slicer.is_tainted(arg)).cloned().collect();
+            if tainted_args.is_empty() {
+                continue;
+            }
 
-                if !tainted_args.is_empty() {
-                    cross_file_flows.push(CrossFileFlow {
-                        caller_file: file_path.to_path_buf(),
-                        caller_line: call_line,
-                        function_called: call_name.clone(),
-                        callee_file: callee_file.clone(),
-                        callee_line,
-                        tainted_args: tainted_args.clone(),
-                    });
+            cross_file_flows.push(CrossFileFlow {
+                caller_file: file_path.to_path_buf(),
+                caller_line: call.line,
+                function_called: call.function.clone(),
+                callee_file: callee_file.clone(),
+                callee_line,
+                tainted_args,
+                tainted_return: None,
+            });
 
-                    // Add to attack path
+            // Add to attack path
+            attack_path.push(CrossFilePathNode {
+                file_path: file_path.to_path_buf(),
+                line: call.line,
+                code: format!("{}(...)", call.function),
+                node_type: "CROSS_FILE_CALL".to_string(),
+                is_entry_point: false,
+                is_sink: false,
+            });
+
+            // The callee's summary says which parameters reach which sinks,
+            // including sinks further down its own calls
+            let Some(callee) = self
+                .summary(&callee_file)
+                .and_then(|summary| summary.functions.get(&function))
+                .cloned()
+            else {
+                continue;
+            };
+            for (param, ids) in call.arguments_by_param(&callee) {
+                if !ids.iter().any(|id| slicer.is_tainted(id)) {
+                    continue;
+                }
+                for sink in callee.param_sinks.iter().filter(|s| s.param == param) {
                     attack_path.push(CrossFilePathNode {
-                        file_path: file_path.to_path_buf(),
-                        line: call_line,
-                        code: format!("{}(...)", call_name),
-                        node_type: "CROSS_FILE_CALL".to_string(),
+                        file_path: sink.file.clone(),
+                        line: sink.line,
+                        code: sink.code.clone(),
+                        node_type: format!("{:?}", sink.sink_type),
                         is_entry_point: false,
-                        is_sink: false,
+                        is_sink: true,
                     });
-
-                    // Recursively analyze the callee file
-                    if let Ok(sub_result) = self.analyze_file_internal(&callee_file, depth + 1) {
-                        // Only include sinks that are connected to the tainted arguments
-                        // The tainted args become parameters in the callee function
-                        for sink in sub_result.sinks {
-                            // Check if any of the sink's tainted_vars could come from our tainted args
-                            // Simplified: we check if the sink uses any form of the passed argument names
-                            let sink_is_reachable = tainted_args.iter().any(|arg| {
-                                sink.tainted_vars.iter().any(|tv| {
-                                    // Match if the tainted var contains or relates to the passed arg
-                                    tv.contains(arg) || arg.contains(tv) || 
-                                    // Also check if sink has any tainted vars at all (conservative)
-                                    !sink.tainted_vars.is_empty()
-                                })
-                            });
-                            
-                            // Skip parameterized queries (safe pattern)
-                            let is_parameterized = sink.code_snippet.contains(", params") || 
-                                                   sink.code_snippet.contains(", (") ||
-                                                   sink.code_snippet.contains("?");
-                            
-                            if sink_is_reachable && !is_parameterized {
-                                attack_path.push(CrossFilePathNode {
-                                    file_path: callee_file.clone(),
-                                    line: sink.line,
-                                    code: sink.code_snippet.clone(),
-                                    node_type: format!("{:?}", sink.sink_type),
-                                    is_entry_point: false,
-                                    is_sink: true,
-                                });
-                            }
-                        }
-                        cross_file_flows.extend(sub_result.cross_file_flows);
-                    }
                 }
             }
         }
 
+        cross_file_flows.extend(return_flows.into_iter().map(|(flow, _)| flow));
+
         // Add local sinks to attack path
         for sink in &sinks {
             attack_path.push(CrossFilePathNode {
@@ -247,38 +300,310 @@ impl CrossFileSlicer {
         })
     }
 
-    /// Find all function calls in a node
-    fn find_function_calls(&self, node: tree_sitter::Node, source: &[u8]) -> Vec<(String, usize, Vec<String>)> {
-        let mut calls = Vec::new();
+    /// Taint variables assigned from calls into other files whose return value
+    /// carries user input, either from a source in the callee or from a tainted
+    /// argument it passes through. Repeats until nothing new is tainted, since a
+    /// marked variable may feed a later call.
+    fn propagate_returns(
+        &mut self,
+        file_path: &Path,
+        tree: &tree_sitter::Tree,
+        source: &[u8],
+        slicer: &mut BackwardSlicer,
+    ) -> Vec<(CrossFileFlow, String)> {
+        let assignments = self.find_call_assignments(tree.root_node(), source);
+        let mut flows = Vec::new();
+        let mut marked = HashSet::new();
+
+        loop {
+            let mut changed = false;
+            for assignment in &assignments {
+                let call = &assignment.call;
+                if assignment.targets.iter().all(|t| marked.contains(t)) {
+                    continue;
+                }
+                let Some((callee_file, callee_line, function)) = self.resolve_call(file_path, &call.function) else {
+                    continue;
+                };
+                let Some(callee) = self
+                    .summary(&callee_file)
+                    .and_then(|summary| summary.functions.get(&function))
+                    .cloned()
+                else {
+                    continue;
+                };
+
+                let tainted_args: Vec<String> = call
+                    .arguments_by_param(&callee)
+                    .into_iter()
+                    .filter(|(param, _)| callee.returns.from_params.contains(param))
+                    .flat_map(|(_, ids)| ids)
+                    .filter(|arg| slicer.is_tainted(arg))
+                    .cloned()
+                    .collect();
+                if !callee.returns.from_source && tainted_args.is_empty() {
+                    continue;
+                }
 
-        if node.kind() == "call" {
-            if let Some(func_node) = node.child_by_field_name("function") {
-                let func_name = func_node.utf8_text(source).unwrap_or("").to_string();
-                let line = node.start_position().row + 1;
-                
-                // Extract arguments
-                let mut args = Vec::new();
-                if let Some(args_node) = node.child_by_field_name("arguments") {
-                    let mut cursor = args_node.walk();
-                    for child in args_node.children(&mut cursor) {
-                        // Recursively find identifiers in this argument
-                        self.extract_identifiers_from_node(child, source, &mut args);
+                let targets: Vec<String> = assignment
+                    .targets
+                    .iter()
+                    .filter(|t| marked.insert(t.to_string()))
+                    .cloned()
+                    .collect();
+                for target in &targets {
+                    slicer.mark_tainted(target);
+                }
+                changed = true;
+                flows.push((
+                    CrossFileFlow {
+                        caller_file: file_path.to_path_buf(),
+                        caller_line: call.line,
+                        function_called: call.function.clone(),
+                        callee_file,
+                        callee_line,
+                        tainted_args,
+                        tainted_return: Some(targets.join(", ")),
+                    },
+                    assignment.code.clone(),
+                ));
+            }
+            if !changed {
+                break;
+            }
+        }
+        flows
+    }
+
+    /// Resolve a call to a function defined in another file. `module.func`
+    /// falls back to `func` when it lives in a module of that name.
+    fn resolve_call(&self, file_path: &Path, call_name: &str) -> Option<(PathBuf, usize, String)> {
+        let elsewhere = |s: &&Symbol| s.file_path != file_path && s.kind == SymbolKind::Function;
+        if let Some(sym) = self.indexer.resolve_symbol(file_path, call_name).filter(elsewhere) {
+            return Some((sym.file_path.clone(), sym.line, sym.name.clone()));
+        }
+
+        let (qualifier, function) = call_name.rsplit_once('.')?;
+        let module = qualifier.rsplit('.').next()?;
+        self.indexer
+            .resolve_symbol(file_path, function)
+            .filter(elsewhere)
+            .filter(|s| s.module_path == module || s.module_path.ends_with(&format!(".{}", module)))
+            .map(|s| (s.file_path.clone(), s.line, s.name.clone()))
+    }
+
+    /// Summary of a file, computed the first time it is needed. A file on an
+    /// import cycle is summarized without the summary of the file that
+    /// started the cycle.
+    fn summary(&mut self, file_path: &Path) -> Option<&FileSummary> {
+        if self.store.get(file_path).is_none() {
+            if !self.summarizing.insert(file_path.to_path_buf()) {
+                return None;
+            }
+            let summary = self.build_summary(file_path);
+            self.summarizing.remove(file_path);
+            self.store.insert(file_path.to_path_buf(), summary?);
+        }
+        self.store.get(file_path)
+    }
+
+    /// Summarize the functions of a file, after summarizing the files it calls into
+    fn build_summary(&mut self, file_path: &Path) -> Option<FileSummary> {
+        let source = fs::read_to_string(file_path).ok()?;
+        let tree = self.parser.parse(&source, None)?;
+        let source_bytes = source.as_bytes();
+        let mut slicer = BackwardSlicer::with_config(self.taint_config);
+        slicer.analyze(&source, &tree);
+        self.propagate_returns(file_path, &tree, source_bytes, &mut slicer);
+
+        let mut definitions = Vec::new();
+        collect_function_ranges(tree.root_node(), source_bytes, &mut definitions);
+        // Innermost function containing a line
+        let enclosing = |line: usize| {
+            definitions
+                .iter()
+                .filter(|(_, start, end)| *start <= line && line <= *end)
+                .max_by_key(|(_, start, _)| *start)
+                .map(|(name, _, _)| name.clone())
+        };
+
+        let mut functions: HashMap<String, FunctionSummary> = definitions
+            .iter()
+            .filter_map(|(name, line, _)| {
+                let summary = FunctionSummary {
+                    line: *line,
+                    params: slicer.params_of(name)?.to_vec(),
+                    returns: slicer.return_taint(name)?,
+                    param_sinks: Vec::new(),
+                };
+                Some((name.clone(), summary))
+            })
+            .collect();
+
+        // Sinks in the function body
+        let mut python_parser = super::python_parser::PythonParser::new().ok()?;
+        for sink in python_parser.find_sinks(&source).unwrap_or_default() {
+            if is_parameterized(&sink.code_snippet) {
+                continue;
+            }
+            let Some(function) = enclosing(sink.line) else {
+                continue;
+            };
+            let params = slicer.param_flow(&function, &identifiers_in(&sink.code_snippet));
+            if let Some(summary) = functions.get_mut(&function) {
+                for param in params {
+                    summary.param_sinks.push(ParamSink {
+                        param,
+                        file: file_path.to_path_buf(),
+                        line: sink.line,
+                        code: sink.code_snippet.clone(),
+                        sink_type: sink.sink_type.clone(),
+                        via: None,
+                    });
+                }
+            }
+        }
+
+        // Sinks reached by passing a parameter on to another function. Calls
+        // within the file may chain, so repeat until nothing is added.
+        let calls = self.find_call_sites(tree.root_node(), source_bytes);
+        let resolved: Vec<_> = calls.iter().map(|call| self.resolve_call(file_path, &call.function)).collect();
+        let dependencies: HashSet<PathBuf> = resolved.iter().flatten().map(|(file, _, _)| file.clone()).collect();
+        loop {
+            let mut changed = false;
+            for (call, target) in calls.iter().zip(&resolved) {
+                let Some(caller) = enclosing(call.line) else {
+                    continue;
+                };
+                let callee = match target {
+                    Some((callee_file, _, name)) => self
+                        .summary(callee_file)
+                        .and_then(|summary| summary.functions.get(name))
+                        .cloned(),
+                    None => functions.get(&call.function).cloned(),
+                };
+                let Some(callee) = callee else {
+                    continue;
+                };
+
+                for (index, ids) in call.arguments_by_param(&callee) {
+                    let params = slicer.param_flow(&caller, ids);
+                    let Some(summary) = functions.get_mut(&caller) else {
+                        continue;
+                    };
+                    for reached in callee.param_sinks.iter().filter(|s| s.param == index) {
+                        for &param in &params {
+                            let sink = ParamSink {
+                                param,
+                                via: Some(call.function.clone()),
+                                ..reached.clone()
+                            };
+                            if !summary.param_sinks.contains(&sink) {
+                                summary.param_sinks.push(sink);
+                                changed = true;
+                            }
+                        }
                     }
                 }
-                
-                calls.push((func_name, line, args));
+            }
+            if !changed {
+                break;
             }
         }
 
+        Some(FileSummary {
+            fingerprint: fingerprint(&source),
+            dependencies: dependencies.into_iter().collect(),
+            functions,
+        })
+    }
+
+    /// Find all `target = call(...)` assignments in a node
+    fn find_call_assignments(&self, node: tree_sitter::Node, source: &[u8]) -> Vec<CallAssignment> {
+        let mut assignments = Vec::new();
+
+        if node.kind() == "assignment" {
+            let call = node.child_by_field_name("right").and_then(|right| match right.kind() {
+                "call" => Some(right),
+                "await" => right.named_child(0).filter(|n| n.kind() == "call"),
+                _ => None,
+            });
+            if let (Some(left), Some(call)) = (node.child_by_field_name("left"), call) {
+                let targets: Vec<String> = match left.kind() {
+                    "pattern_list" | "tuple_pattern" | "list_pattern" => {
+                        let mut cursor = left.walk();
+                        let items: Vec<_> = left.named_children(&mut cursor).collect();
+                        items.into_iter().filter_map(|item| access_path(item, source)).collect()
+                    }
+                    _ => access_path(left, source).into_iter().collect(),
+                };
+
+                if let Some(call) = self.call_site(call, source) {
+                    if !targets.is_empty() {
+                        assignments.push(CallAssignment {
+                            call,
+                            code: node.utf8_text(source).unwrap_or("").to_string(),
+                            targets,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            assignments.extend(self.find_call_assignments(child, source));
+        }
+
+        assignments
+    }
+
+    /// Find all function calls in a node
+    fn find_call_sites(&self, node: tree_sitter::Node, source: &[u8]) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if node.kind() == "call" {
+            calls.extend(self.call_site(node, source));
+        }
+
         // Recurse
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            calls.extend(self.find_function_calls(child, source));
+            calls.extend(self.find_call_sites(child, source));
         }
 
         calls
     }
 
+    fn call_site(&self, node: tree_sitter::Node, source: &[u8]) -> Option<CallSite> {
+        let func_node = node.child_by_field_name("function")?;
+        let mut call = CallSite {
+            function: func_node.utf8_text(source).unwrap_or("").to_string(),
+            line: node.start_position().row + 1,
+            args: Vec::new(),
+            keyword_args: Vec::new(),
+        };
+
+        if let Some(args_node) = node.child_by_field_name("arguments") {
+            let mut cursor = args_node.walk();
+            for arg in args_node.named_children(&mut cursor) {
+                let mut identifiers = Vec::new();
+                if arg.kind() == "keyword_argument" {
+                    let (Some(name), Some(value)) = (arg.child_by_field_name("name"), arg.child_by_field_name("value")) else {
+                        continue;
+                    };
+                    self.extract_identifiers_from_node(value, source, &mut identifiers);
+                    call.keyword_args.push((name.utf8_text(source).unwrap_or("").to_string(), identifiers));
+                } else {
+                    self.extract_identifiers_from_node(arg, source, &mut identifiers);
+                    call.args.push(identifiers);
+                }
+            }
+        }
+
+        Some(call)
+    }
+
     /// Recursively extract identifiers from a node
     fn extract_identifiers_from_node(&self, node: tree_sitter::Node, source: &[u8], identifiers: &mut Vec<String>) {
         if node.kind() == "identifier" {
@@ -293,6 +618,36 @@ impl CrossFileSlicer {
     }
 }
 
+/// Name, first line and last line of every function definition
+fn collect_function_ranges(node: tree_sitter::Node, source: &[u8], out: &mut Vec<(String, usize, usize)>) {
+    if node.kind() == "function_definition" {
+        if let Some(name) = node.child_by_field_name("name") {
+            out.push((
+                name.utf8_text(source).unwrap_or("").to_string(),
+                node.start_position().row + 1,
+                node.end_position().row + 1,
+            ));
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_function_ranges(child, source, out);
+    }
+}
+
+/// Identifier-like tokens of a code snippet
+fn identifiers_in(code: &str) -> Vec<String> {
+    code.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Queries passing their values separately are the safe pattern
+fn is_parameterized(code: &str) -> bool {
+    code.contains(", params") || code.contains(", (") || code.contains('?')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,4 +793,99 @@ mod tests {
         
         std::fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_tainted_return_value_reaches_caller_sink() {
+        let temp_dir = std::env::temp_dir().join("test_cross_return");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        std::fs::write(
+            temp_dir.join("main.py"),
+            "import os\nimport helpers\ndef main():\n    cmd = helpers.get_user_input()\n    label = helpers.banner()\n    os.system(cmd)\n",
+        ).unwrap();
+        std::fs::write(
+            temp_dir.join("helpers.py"),
+            "def get_user_input():\n    data = input('> ')\n    return data\n\ndef banner():\n    return 'hello'\n",
+        ).unwrap();
+
+        let mut slicer = CrossFileSlicer::new(temp_dir.clone()).unwrap();
+        slicer.index_workspace().unwrap();
+        let result = slicer.analyze_file(&temp_dir.join("main.py")).unwrap();
+
+        let returns: Vec<_> = result.cross_file_flows.iter().filter(|f| f.tainted_return.is_some()).collect();
+        assert_eq!(returns.len(), 1, "Only get_user_input returns user input");
+        assert_eq!(returns[0].tainted_return.as_deref(), Some("cmd"));
+        assert!(returns[0].callee_file.ends_with("helpers.py"));
+        assert!(result.sinks.iter().any(|s| s.tainted_vars.contains(&"cmd".to_string())));
+        assert!(result.attack_path.iter().any(|n| n.node_type == "CROSS_FILE_RETURN" && n.line == 4));
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_return_taint_through_passed_argument() {
+        let temp_dir = std::env::temp_dir().join("test_cross_return_arg");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        std::fs::write(
+            temp_dir.join("main.py"),
+            "import os\nfrom helpers import clean\nraw = input()\nsafe = clean('ls')\nout = clean(raw)\nos.system(out)\n",
+        ).unwrap();
+        std::fs::write(
+            temp_dir.join("helpers.py"),
+            "def clean(value):\n    return value.strip()\n",
+        ).unwrap();
+
+        let mut slicer = CrossFileSlicer::new(temp_dir.clone()).unwrap();
+        slicer.index_workspace().unwrap();
+        let result = slicer.analyze_file(&temp_dir.join("main.py")).unwrap();
+
+        let returns: Vec<_> = result.cross_file_flows.iter().filter_map(|f| f.tainted_return.as_deref()).collect();
+        assert_eq!(returns, vec!["out"]);
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_summary_reaches_sink_through_intermediate_file() {
+        let temp_dir = std::env::temp_dir().join("test_cross_summary_chain");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        std::fs::write(
+            temp_dir.join("main.py"),
+            "from service import handle\nname = input()\nhandle('fixed', name)\n",
+        ).unwrap();
+        std::fs::write(
+            temp_dir.join("service.py"),
+            "from db import lookup\ndef handle(label, value):\n    return lookup(value)\n",
+        ).unwrap();
+        std::fs::write(
+            temp_dir.join("db.py"),
+            "def lookup(term):\n    cursor.execute(\"SELECT * FROM t WHERE name = '\" + term + \"'\")\n",
+        ).unwrap();
+        std::fs::write(temp_dir.join("other.py"), "def noop():\n    pass\n").unwrap();
+
+        let mut slicer = CrossFileSlicer::new(temp_dir.clone()).unwrap();
+        slicer.index_workspace().unwrap();
+        assert_eq!(slicer.summarize_workspace(), 4);
+
+        let handle = &slicer.summaries().get(&temp_dir.join("service.py")).unwrap().functions["handle"];
+        assert_eq!(handle.param_sinks.len(), 1);
+        assert_eq!(handle.param_sinks[0].param, 1, "Only `value` reaches the query");
+        assert_eq!(handle.param_sinks[0].via.as_deref(), Some("lookup"));
+
+        let result = slicer.analyze_file(&temp_dir.join("main.py")).unwrap();
+        assert!(result.attack_path.iter().any(|n| n.is_sink && n.file_path.ends_with("db.py") && n.line == 2));
+
+        // Unchanged files keep their summaries; editing db.py invalidates its callers too
+        slicer.index_workspace().unwrap();
+        assert_eq!(slicer.summarize_workspace(), 0);
+        std::fs::write(temp_dir.join("db.py"), "def lookup(term):\n    return term\n").unwrap();
+        slicer.index_workspace().unwrap();
+        assert!(slicer.summaries().get(&temp_dir.join("other.py")).is_some());
+        assert_eq!(slicer.summarize_workspace(), 3);
+        assert!(slicer.summaries().get(&temp_dir.join("service.py")).unwrap().functions["handle"].param_sinks.is_empty());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
 }
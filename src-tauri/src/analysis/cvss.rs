@@ -0,0 +1,234 @@
+//! CVSS Scoring
+//!
+//! CVSS 3.1 base vectors for proven findings. Impact and user interaction
+//! follow from the sink type; the attack vector from where the tainted input
+//! enters (a request vs the command line, environment or local files) and
+//! the attack complexity from whether the path needs conditions the attacker
+//! does not control.
+
+use serde::{Deserialize, Serialize};
+
+use super::SinkType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackVector {
+    Network,
+    Local,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackComplexity {
+    Low,
+    High,
+}
+
+/// Confidentiality, integrity or availability impact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Impact {
+    None,
+    Low,
+    High,
+}
+
+impl Impact {
+    fn code(self) -> char {
+        match self {
+            Impact::None => 'N',
+            Impact::Low => 'L',
+            Impact::High => 'H',
+        }
+    }
+
+    fn weight(self) -> f64 {
+        match self {
+            Impact::None => 0.0,
+            Impact::Low => 0.22,
+            Impact::High => 0.56,
+        }
+    }
+}
+
+/// A CVSS 3.1 base vector. Findings are proven from unauthenticated input,
+/// so privileges required is always None.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CvssVector {
+    attack_vector: AttackVector,
+    attack_complexity: AttackComplexity,
+    user_interaction: bool,
+    scope_changed: bool,
+    confidentiality: Impact,
+    integrity: Impact,
+    availability: Impact,
+}
+
+/// Score of one finding, as exposed on sinks and analysis results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CvssScore {
+    /// e.g. "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+    pub vector: String,
+    pub score: f32,
+    /// None, Low, Medium, High or Critical
+    pub severity: String,
+}
+
+impl CvssVector {
+    pub fn for_sink(sink_type: &SinkType, attack_vector: AttackVector, attack_complexity: AttackComplexity) -> Self {
+        use Impact::{High, Low, None};
+        // (user interaction, scope changed, C, I, A)
+        let (user_interaction, scope_changed, confidentiality, integrity, availability) = match sink_type {
+            SinkType::SqlInjection
+            | SinkType::CommandInjection
+            | SinkType::CodeInjection
+            | SinkType::Deserialization => (false, false, High, High, High),
+            SinkType::NoSqlInjection => (false, false, High, High, None),
+            SinkType::LdapInjection => (false, false, High, Low, None),
+            SinkType::PathTraversal => (false, false, High, None, None),
+            SinkType::Xxe => (false, false, High, None, Low),
+            // Requests are made from the server, into other systems
+            SinkType::Ssrf => (false, true, High, None, None),
+            // Run in, or redirect, a victim's browser
+            SinkType::Xss | SinkType::OpenRedirect | SinkType::HeaderInjection => (true, true, Low, Low, None),
+        };
+        Self {
+            attack_vector,
+            attack_complexity,
+            user_interaction,
+            scope_changed,
+            confidentiality,
+            integrity,
+            availability,
+        }
+    }
+
+    pub fn vector_string(&self) -> String {
+        format!(
+            "CVSS:3.1/AV:{}/AC:{}/PR:N/UI:{}/S:{}/C:{}/I:{}/A:{}",
+            match self.attack_vector {
+                AttackVector::Network => 'N',
+                AttackVector::Local => 'L',
+            },
+            match self.attack_complexity {
+                AttackComplexity::Low => 'L',
+                AttackComplexity::High => 'H',
+            },
+            if self.user_interaction { 'R' } else { 'N' },
+            if self.scope_changed { 'C' } else { 'U' },
+            self.confidentiality.code(),
+            self.integrity.code(),
+            self.availability.code(),
+        )
+    }
+
+    /// Base score per the CVSS 3.1 specification
+    pub fn base_score(&self) -> f32 {
+        let iss = 1.0
+            - (1.0 - self.confidentiality.weight())
+                * (1.0 - self.integrity.weight())
+                * (1.0 - self.availability.weight());
+        let impact = if self.scope_changed {
+            7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+        } else {
+            6.42 * iss
+        };
+        if impact <= 0.0 {
+            return 0.0;
+        }
+
+        let attack_vector = match self.attack_vector {
+            AttackVector::Network => 0.85,
+            AttackVector::Local => 0.55,
+        };
+        let attack_complexity = match self.attack_complexity {
+            AttackComplexity::Low => 0.77,
+            AttackComplexity::High => 0.44,
+        };
+        let privileges_required = 0.85;
+        let user_interaction = if self.user_interaction { 0.62 } else { 0.85 };
+        let exploitability = 8.22 * attack_vector * attack_complexity * privileges_required * user_interaction;
+
+        let total = if self.scope_changed {
+            1.08 * (impact + exploitability)
+        } else {
+            impact + exploitability
+        };
+        round_up(total.min(10.0)) as f32
+    }
+
+    pub fn score(&self) -> CvssScore {
+        let score = self.base_score();
+        CvssScore {
+            vector: self.vector_string(),
+            score,
+            severity: severity(score).to_string(),
+        }
+    }
+}
+
+/// The specification's Roundup: smallest one-decimal number not below `value`,
+/// computed on integers to avoid floating point artifacts
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+/// Qualitative severity rating of a base score
+pub fn severity(score: f32) -> &'static str {
+    match score {
+        s if s >= 9.0 => "Critical",
+        s if s >= 7.0 => "High",
+        s if s >= 4.0 => "Medium",
+        s if s > 0.0 => "Low",
+        _ => "None",
+    }
+}
+
+/// The most severe of several scores, to rank a result by its worst finding
+pub fn highest<'a>(scores: impl IntoIterator<Item = &'a CvssScore>) -> Option<CvssScore> {
+    scores
+        .into_iter()
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_scores() {
+        let sqli = CvssVector::for_sink(&SinkType::SqlInjection, AttackVector::Network, AttackComplexity::Low);
+        assert_eq!(sqli.vector_string(), "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H");
+        assert_eq!(sqli.base_score(), 9.8);
+
+        let guarded = CvssVector::for_sink(&SinkType::CommandInjection, AttackVector::Network, AttackComplexity::High);
+        assert_eq!(guarded.base_score(), 8.1);
+
+        let local = CvssVector::for_sink(&SinkType::CommandInjection, AttackVector::Local, AttackComplexity::Low);
+        assert_eq!(local.base_score(), 8.4);
+
+        let xss = CvssVector::for_sink(&SinkType::Xss, AttackVector::Network, AttackComplexity::Low);
+        assert_eq!(xss.vector_string(), "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N");
+        assert_eq!(xss.base_score(), 6.1);
+
+        let ssrf = CvssVector::for_sink(&SinkType::Ssrf, AttackVector::Network, AttackComplexity::Low);
+        assert_eq!(ssrf.base_score(), 8.6);
+
+        let traversal = CvssVector::for_sink(&SinkType::PathTraversal, AttackVector::Network, AttackComplexity::Low);
+        assert_eq!(traversal.score().severity, "High");
+        assert_eq!(traversal.base_score(), 7.5);
+    }
+
+    #[test]
+    fn test_highest_score() {
+        let scores = [
+            CvssVector::for_sink(&SinkType::Xss, AttackVector::Network, AttackComplexity::Low).score(),
+            CvssVector::for_sink(&SinkType::SqlInjection, AttackVector::Network, AttackComplexity::Low).score(),
+        ];
+        assert_eq!(highest(&scores).map(|s| s.score), Some(9.8));
+        assert_eq!(highest(&[]), None);
+    }
+}
@@ -0,0 +1,664 @@
+//! Go Source Analysis
+//!
+//! Finds sinks in Go code (os/exec commands, database/sql queries,
+//! html/template casts, file access) and traces user input to them within
+//! each function. Sources are the inputs of a net/http request (`r.URL`,
+//! `r.FormValue`, `r.Header`, `mux.Vars(r)`, ...), `os.Args`, and, as in
+//! the Python slicer, string parameters of ordinary functions.
+
+use std::collections::{HashMap, HashSet};
+use tree_sitter::{Node, Parser};
+
+use super::{PathNode, Sink, SinkType};
+
+/// database/sql methods taking the query as their first argument
+const SQL_METHODS: &[&str] = &["Query", "QueryRow", "Exec", "Prepare"];
+
+/// The same methods with a context first and the query second
+const SQL_CONTEXT_METHODS: &[&str] = &["QueryContext", "QueryRowContext", "ExecContext", "PrepareContext"];
+
+/// html/template types whose conversion marks a string as safe to emit unescaped
+const TEMPLATE_CASTS: &[&str] = &["template.HTML", "template.HTMLAttr", "template.JS", "template.CSS", "template.URL"];
+
+/// File access taking the path as the first argument
+const PATH_CALLS: &[&str] = &[
+    "os.Open",
+    "os.OpenFile",
+    "os.ReadFile",
+    "os.WriteFile",
+    "os.Create",
+    "os.Remove",
+    "os.RemoveAll",
+    "os.ReadDir",
+    "ioutil.ReadFile",
+    "ioutil.WriteFile",
+];
+
+/// Programs that interpret their arguments as a shell script
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "/bin/sh", "/bin/bash", "cmd", "cmd.exe", "powershell"];
+
+/// Fields and methods of *http.Request carrying client-controlled data
+const REQUEST_INPUTS: &[&str] = &[
+    "URL",
+    "Form",
+    "PostForm",
+    "MultipartForm",
+    "FormValue",
+    "PostFormValue",
+    "FormFile",
+    "Header",
+    "Body",
+    "Cookie",
+    "Cookies",
+    "PathValue",
+    "Referer",
+    "UserAgent",
+];
+
+/// Conversions whose result can no longer carry an injection
+const SANITIZERS: &[&str] = &[
+    "strconv.Atoi",
+    "strconv.ParseInt",
+    "strconv.ParseUint",
+    "strconv.ParseFloat",
+    "strconv.ParseBool",
+    "html.EscapeString",
+    "template.HTMLEscapeString",
+    "template.JSEscapeString",
+    "url.QueryEscape",
+    "url.PathEscape",
+    "filepath.Base",
+    "path.Base",
+];
+
+/// Parameter types treated as caller-controlled text
+const STRING_TYPES: &[&str] = &["string", "[]string", "[]byte"];
+
+/// A sink reached by user input, with the path back to the input
+#[derive(Debug, Clone)]
+pub struct GoFlow {
+    pub sink: Sink,
+    /// Sink first, then each step back to the entry point
+    pub path: Vec<PathNode>,
+    /// Whether the input comes from the command line rather than a request
+    /// or a caller
+    pub local: bool,
+}
+
+/// Every sink in a file, and those user input reaches
+#[derive(Debug, Clone, Default)]
+pub struct GoAnalysis {
+    pub sinks: Vec<Sink>,
+    pub flows: Vec<GoFlow>,
+}
+
+/// How a value came to be tainted
+#[derive(Debug, Clone)]
+struct Taint {
+    /// Steps back to the entry point, most recent first
+    trace: Vec<PathNode>,
+    /// Input expression (request accessor or `os.Args`), when the value is read from it directly
+    origin: Option<String>,
+    local: bool,
+}
+
+/// Variables of the function being walked
+#[derive(Default)]
+struct Scope {
+    /// Names of *http.Request parameters
+    requests: HashSet<String>,
+    tainted: HashMap<String, Taint>,
+}
+
+pub struct GoAnalyzer {
+    parser: Parser,
+}
+
+impl GoAnalyzer {
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_go::language())
+            .map_err(|e| format!("Failed to load Go grammar: {}", e))?;
+        Ok(Self { parser })
+    }
+
+    pub fn find_sinks(&mut self, source: &str) -> Result<Vec<Sink>, String> {
+        Ok(self.analyze(source)?.sinks)
+    }
+
+    /// Find the sinks of a Go file and trace user input to them
+    pub fn analyze(&mut self, source: &str) -> Result<GoAnalysis, String> {
+        let tree = self.parser.parse(source, None).ok_or("Failed to parse Go source")?;
+        let walker = Walker {
+            source: source.as_bytes(),
+            packages: imported_packages(tree.root_node(), source.as_bytes()),
+        };
+
+        let mut analysis = GoAnalysis::default();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for item in root.named_children(&mut cursor) {
+            if matches!(item.kind(), "function_declaration" | "method_declaration") {
+                let mut scope = Scope::default();
+                walker.walk(item, &mut scope, &mut analysis);
+            }
+        }
+        Ok(analysis)
+    }
+}
+
+/// Local names of imported packages, so `exec` in `exec.Command` is not
+/// mistaken for a variable
+fn imported_packages(root: Node, source: &[u8]) -> HashSet<String> {
+    let mut packages = HashSet::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "import_spec" {
+            let alias = node.child_by_field_name("name").and_then(|n| n.utf8_text(source).ok());
+            let path = node
+                .child_by_field_name("path")
+                .and_then(|n| n.utf8_text(source).ok())
+                .map(|p| p.trim_matches(['"', '`']));
+            if let Some(name) = alias.or_else(|| path.and_then(|p| p.rsplit('/').next())) {
+                packages.insert(name.to_string());
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    packages
+}
+
+/// Literals, and expressions built only from literals
+fn is_constant(node: Node) -> bool {
+    match node.kind() {
+        "interpreted_string_literal" | "raw_string_literal" | "int_literal" | "float_literal" | "true" | "false" | "nil" => true,
+        "binary_expression" | "parenthesized_expression" => {
+            let mut cursor = node.walk();
+            let constant = node.named_children(&mut cursor).all(is_constant);
+            constant
+        }
+        _ => false,
+    }
+}
+
+struct Walker<'a> {
+    source: &'a [u8],
+    packages: HashSet<String>,
+}
+
+impl Walker<'_> {
+    fn text(&self, node: Node) -> String {
+        node.utf8_text(self.source).unwrap_or("").to_string()
+    }
+
+    fn line_code(&self, node: Node) -> String {
+        self.text(node).lines().next().unwrap_or("").trim().to_string()
+    }
+
+    /// Visit a function body in source order, tracking assignments and
+    /// checking calls as they are reached
+    fn walk(&self, node: Node, scope: &mut Scope, analysis: &mut GoAnalysis) {
+        match node.kind() {
+            "function_declaration" | "method_declaration" | "func_literal" => {
+                if let Some(params) = node.child_by_field_name("parameters") {
+                    self.declare_parameters(params, scope);
+                }
+            }
+            "call_expression" | "type_conversion_expression" => {
+                self.check_sink(node, scope, analysis);
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.walk(child, scope, analysis);
+        }
+
+        match node.kind() {
+            "short_var_declaration" | "assignment_statement" => {
+                let accumulate = node
+                    .child_by_field_name("operator")
+                    .is_some_and(|op| self.text(op) == "+=");
+                if let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) {
+                    self.assign(node, left, right, accumulate, scope);
+                }
+            }
+            "var_spec" => {
+                if let Some(value) = node.child_by_field_name("value") {
+                    let mut cursor = node.walk();
+                    let names: Vec<Node> = node
+                        .children_by_field_name("name", &mut cursor)
+                        .filter(|n| n.kind() == "identifier")
+                        .collect();
+                    self.bind(node, &names, value, false, scope);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn declare_parameters(&self, params: Node, scope: &mut Scope) {
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            let Some(ty) = param.child_by_field_name("type").map(|t| self.text(t)) else {
+                continue;
+            };
+            let mut names_cursor = param.walk();
+            for name in param.children_by_field_name("name", &mut names_cursor) {
+                let name = self.text(name);
+                if ty.ends_with("http.Request") {
+                    scope.requests.insert(name);
+                } else if STRING_TYPES.contains(&ty.as_str()) {
+                    let node = PathNode {
+                        line: param.start_position().row + 1,
+                        cell: None,
+                        code: self.text(param),
+                        description: "ENTRY: Function parameter (potentially user-controlled)".to_string(),
+                    };
+                    scope.tainted.insert(name, Taint { trace: vec![node], origin: None, local: false });
+                }
+            }
+        }
+    }
+
+    fn assign(&self, statement: Node, left: Node, right: Node, accumulate: bool, scope: &mut Scope) {
+        let mut cursor = left.walk();
+        let targets: Vec<Node> = left
+            .named_children(&mut cursor)
+            .filter(|n| n.kind() == "identifier")
+            .collect();
+        self.bind(statement, &targets, right, accumulate, scope);
+    }
+
+    /// Taint or clear each target from its value. A single value assigned to
+    /// several targets (`id, err := ...`) only flows into the first.
+    fn bind(&self, statement: Node, targets: &[Node], values: Node, accumulate: bool, scope: &mut Scope) {
+        let mut cursor = values.walk();
+        let values: Vec<Node> = values.named_children(&mut cursor).collect();
+        for (i, target) in targets.iter().enumerate() {
+            let name = self.text(*target);
+            if name == "_" {
+                continue;
+            }
+            let value = if values.len() == targets.len() {
+                values.get(i)
+            } else if i == 0 {
+                values.first()
+            } else {
+                None
+            };
+
+            match value.and_then(|v| self.taint_of(*v, scope)) {
+                Some(taint) => {
+                    let description = match &taint.origin {
+                        Some(origin) => format!("ENTRY: User input from {}", origin),
+                        None => "FLOW: Variable derivation".to_string(),
+                    };
+                    let mut trace = vec![PathNode {
+                        line: statement.start_position().row + 1,
+                        cell: None,
+                        code: self.line_code(statement),
+                        description,
+                    }];
+                    trace.extend(taint.trace);
+                    scope.tainted.insert(name, Taint { trace, origin: None, local: taint.local });
+                }
+                None if !accumulate => {
+                    scope.tainted.remove(&name);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// The taint an expression carries, if any
+    fn taint_of(&self, node: Node, scope: &Scope) -> Option<Taint> {
+        match node.kind() {
+            "func_literal" | "interpreted_string_literal" | "raw_string_literal" | "int_literal" => return None,
+            "identifier" => return scope.tainted.get(&self.text(node)).cloned(),
+            "call_expression" => {
+                let function = node.child_by_field_name("function").map(|f| self.text(f)).unwrap_or_default();
+                if SANITIZERS.contains(&function.as_str()) {
+                    return None;
+                }
+                // gorilla/mux route variables
+                if function.ends_with("Vars") && self.mentions_request(node, scope) {
+                    return Some(Taint { trace: vec![], origin: Some(function), local: false });
+                }
+            }
+            "selector_expression" if self.text(node) == "os.Args" => {
+                return Some(Taint { trace: vec![], origin: Some("os.Args".to_string()), local: true });
+            }
+            "selector_expression" => {
+                let operand = node.child_by_field_name("operand");
+                let field = node.child_by_field_name("field").map(|f| self.text(f));
+                if let (Some(operand), Some(field)) = (operand, field) {
+                    if operand.kind() == "identifier"
+                        && scope.requests.contains(&self.text(operand))
+                        && REQUEST_INPUTS.contains(&field.as_str())
+                    {
+                        return Some(Taint { trace: vec![], origin: Some(self.text(node)), local: false });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        children.into_iter().find_map(|child| self.taint_of(child, scope))
+    }
+
+    fn mentions_request(&self, node: Node, scope: &Scope) -> bool {
+        node.child_by_field_name("arguments").is_some_and(|args| {
+            let mut cursor = args.walk();
+            let found = args
+                .named_children(&mut cursor)
+                .any(|arg| arg.kind() == "identifier" && scope.requests.contains(&self.text(arg)));
+            found
+        })
+    }
+
+    /// Sink type and the arguments that must not carry user input
+    fn classify<'t>(&self, node: Node<'t>) -> Option<(SinkType, Vec<Node<'t>>)> {
+        if node.kind() == "type_conversion_expression" {
+            let ty = self.text(node.child_by_field_name("type")?);
+            let operand = node.child_by_field_name("operand")?;
+            return TEMPLATE_CASTS.contains(&ty.as_str()).then(|| (SinkType::Xss, vec![operand]));
+        }
+
+        let function_node = node.child_by_field_name("function")?;
+        let function = self.text(function_node);
+        let mut cursor = node.walk();
+        let args: Vec<Node> = node
+            .child_by_field_name("arguments")?
+            .named_children(&mut cursor)
+            .collect();
+
+        if function == "exec.Command" || function == "exec.CommandContext" {
+            let start = usize::from(function == "exec.CommandContext");
+            let program = *args.get(start)?;
+            let shell = SHELLS.contains(&self.text(program).trim_matches('"'));
+            // The program itself, everything passed to a shell, and strings built for either
+            let relevant = args[start..]
+                .iter()
+                .enumerate()
+                .filter(|(i, arg)| *i == 0 || shell || self.builds_string(**arg))
+                .map(|(_, arg)| *arg)
+                .collect();
+            return Some((SinkType::CommandInjection, relevant));
+        }
+        if TEMPLATE_CASTS.contains(&function.as_str()) {
+            return Some((SinkType::Xss, args.into_iter().take(1).collect()));
+        }
+        if PATH_CALLS.contains(&function.as_str()) {
+            return Some((SinkType::PathTraversal, args.into_iter().take(1).collect()));
+        }
+        if function == "http.ServeFile" {
+            return Some((SinkType::PathTraversal, args.into_iter().skip(2).take(1).collect()));
+        }
+
+        if function_node.kind() == "selector_expression" {
+            let method = self.text(function_node.child_by_field_name("field")?);
+            let index = if SQL_METHODS.contains(&method.as_str()) {
+                0
+            } else if SQL_CONTEXT_METHODS.contains(&method.as_str()) {
+                1
+            } else {
+                return None;
+            };
+            // exec.Cmd and the like have no query argument
+            let receiver = function_node.child_by_field_name("operand")?;
+            if self.packages.contains(&self.text(receiver)) {
+                return None;
+            }
+            return Some((SinkType::SqlInjection, args.into_iter().skip(index).take(1).collect()));
+        }
+        None
+    }
+
+    /// Concatenation or formatting that assembles a string from parts
+    fn builds_string(&self, node: Node) -> bool {
+        match node.kind() {
+            "binary_expression" => node.child_by_field_name("operator").is_some_and(|op| self.text(op) == "+"),
+            "call_expression" => node
+                .child_by_field_name("function")
+                .map(|f| self.text(f))
+                .is_some_and(|f| matches!(f.as_str(), "fmt.Sprintf" | "strings.Join" | "strings.Replace" | "strings.ReplaceAll")),
+            _ => false,
+        }
+    }
+
+    /// Variables an argument reads, leaving out package names
+    fn variables(&self, node: Node, out: &mut Vec<String>) {
+        if node.kind() == "identifier" {
+            let name = self.text(node);
+            if !self.packages.contains(&name) && !out.contains(&name) {
+                out.push(name);
+            }
+            return;
+        }
+        if node.kind() == "func_literal" {
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.variables(child, out);
+        }
+    }
+
+    fn check_sink(&self, node: Node, scope: &Scope, analysis: &mut GoAnalysis) {
+        let Some((sink_type, relevant)) = self.classify(node) else {
+            return;
+        };
+        // Constant arguments cannot be influenced
+        if relevant.iter().all(|arg| is_constant(*arg)) {
+            return;
+        }
+        let mut tainted_vars = Vec::new();
+        for arg in &relevant {
+            self.variables(*arg, &mut tainted_vars);
+        }
+
+        let sink = Sink {
+            sink_type,
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            cell: None,
+            code_snippet: self.text(node),
+            tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
+        };
+
+        if let Some(taint) = relevant.iter().find_map(|arg| self.taint_of(*arg, scope)) {
+            let mut path = vec![PathNode {
+                line: sink.line,
+                cell: None,
+                code: self.line_code(node),
+                description: format!("SINK: {}", sink.sink_type.description()),
+            }];
+            if let Some(origin) = &taint.origin {
+                path.push(PathNode {
+                    line: sink.line,
+                    cell: None,
+                    code: origin.clone(),
+                    description: format!("ENTRY: User input from {}", origin),
+                });
+            }
+            path.extend(taint.trace);
+            analysis.flows.push(GoFlow { sink: sink.clone(), path, local: taint.local });
+        }
+        analysis.sinks.push(sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str) -> GoAnalysis {
+        GoAnalyzer::new().unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_shell_command_from_query_parameter() {
+        let analysis = analyze(
+            r#"
+package main
+
+import (
+    "net/http"
+    "os/exec"
+)
+
+func ping(w http.ResponseWriter, r *http.Request) {
+    host := r.URL.Query().Get("host")
+    out, _ := exec.Command("sh", "-c", "ping -c 1 "+host).Output()
+    w.Write(out)
+}
+"#,
+        );
+        assert_eq!(analysis.flows.len(), 1);
+        let flow = &analysis.flows[0];
+        assert_eq!(flow.sink.sink_type, SinkType::CommandInjection);
+        assert_eq!(flow.sink.line, 11);
+        assert!(!flow.local);
+        assert!(flow.path.iter().any(|n| n.line == 10 && n.description.contains("r.URL")), "{:?}", flow.path);
+    }
+
+    #[test]
+    fn test_argument_list_command_is_not_injection() {
+        let analysis = analyze(
+            r#"
+package main
+
+import (
+    "net/http"
+    "os/exec"
+)
+
+func ping(w http.ResponseWriter, r *http.Request) {
+    exec.Command("ping", "-c", "1", r.FormValue("host")).Run()
+}
+"#,
+        );
+        assert!(analysis.sinks.is_empty(), "Only the fixed program is executed");
+    }
+
+    #[test]
+    fn test_sql_concatenation_and_parameters() {
+        let analysis = analyze(
+            r#"
+package main
+
+import (
+    "database/sql"
+    "fmt"
+    "net/http"
+)
+
+func user(db *sql.DB, w http.ResponseWriter, r *http.Request) {
+    name := r.FormValue("name")
+    db.Query("SELECT * FROM users WHERE name = ?", name)
+    query := fmt.Sprintf("SELECT * FROM users WHERE name = '%s'", name)
+    rows, err := db.QueryContext(r.Context(), query)
+    _ = rows
+    _ = err
+}
+"#,
+        );
+        assert_eq!(analysis.sinks.len(), 1, "The parameterized query has a constant query string");
+        assert_eq!(analysis.flows.len(), 1);
+        assert_eq!(analysis.flows[0].sink.sink_type, SinkType::SqlInjection);
+        assert_eq!(analysis.flows[0].sink.line, 14);
+    }
+
+    #[test]
+    fn test_template_cast_and_numeric_sanitizer() {
+        let analysis = analyze(
+            r#"
+package main
+
+import (
+    "html/template"
+    "net/http"
+    "strconv"
+)
+
+func page(w http.ResponseWriter, r *http.Request) {
+    bio := template.HTML(r.PostFormValue("bio"))
+    n, _ := strconv.Atoi(r.FormValue("n"))
+    count := template.HTML(strconv.Itoa(n))
+    render(w, bio, count)
+}
+"#,
+        );
+        assert_eq!(analysis.sinks.len(), 2);
+        assert_eq!(analysis.flows.len(), 1);
+        assert_eq!(analysis.flows[0].sink.sink_type, SinkType::Xss);
+        assert_eq!(analysis.flows[0].sink.line, 11);
+    }
+
+    #[test]
+    fn test_mux_vars_reach_file_open() {
+        let analysis = analyze(
+            r#"
+package main
+
+import (
+    "net/http"
+    "os"
+    "path/filepath"
+
+    "github.com/gorilla/mux"
+)
+
+func download(w http.ResponseWriter, r *http.Request) {
+    vars := mux.Vars(r)
+    path := filepath.Join("/srv/files", vars["name"])
+    f, err := os.Open(path)
+    _ = f
+    _ = err
+    safe := filepath.Base(vars["name"])
+    os.ReadFile(safe)
+}
+"#,
+        );
+        assert_eq!(analysis.flows.len(), 1);
+        let flow = &analysis.flows[0];
+        assert_eq!(flow.sink.sink_type, SinkType::PathTraversal);
+        assert_eq!(flow.sink.line, 15);
+        assert_eq!(flow.path.len(), 3, "{:?}", flow.path);
+    }
+
+    #[test]
+    fn test_parameters_and_command_line_are_sources() {
+        let analysis = analyze(
+            r#"
+package main
+
+import (
+    "os"
+    "os/exec"
+)
+
+func run(cmd string) error {
+    return exec.Command(cmd).Run()
+}
+
+func main() {
+    exec.Command("bash", "-c", os.Args[1]).Run()
+}
+"#,
+        );
+        assert_eq!(analysis.flows.len(), 2);
+        assert!(!analysis.flows[0].local, "Callers of run are unknown");
+        assert!(analysis.flows[1].local);
+    }
+}
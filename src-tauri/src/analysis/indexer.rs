@@ -321,6 +321,11 @@ impl ProjectIndexer {
     pub fn get_file_imports(&self, file_path: &Path) -> Option<&Vec<ImportStatement>> {
         self.imports.get(file_path)
     }
+
+    /// Every file indexed so far
+    pub fn indexed_files(&self) -> Vec<PathBuf> {
+        self.imports.keys().cloned().collect()
+    }
 }
 
 #[cfg(test)]
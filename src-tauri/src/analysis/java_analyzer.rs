@@ -0,0 +1,671 @@
+//! Java Source Analysis
+//!
+//! Finds sinks in Java code (JDBC and JPA queries, Runtime.exec and
+//! ProcessBuilder, ObjectInputStream deserialization, XML parsers) and traces
+//! user input to them within each method. Sources are Spring MVC handler
+//! parameters (`@RequestParam`, `@PathVariable`, ...), the accessors of a
+//! servlet request, and, as in the Python slicer, text parameters of
+//! ordinary methods.
+
+use std::collections::{HashMap, HashSet};
+use tree_sitter::{Node, Parser};
+
+use super::{PathNode, Sink, SinkType};
+
+/// Spring MVC annotations binding a handler parameter to the request
+const REQUEST_ANNOTATIONS: &[&str] = &[
+    "RequestParam",
+    "PathVariable",
+    "RequestHeader",
+    "CookieValue",
+    "RequestBody",
+    "RequestPart",
+    "MatrixVariable",
+    "ModelAttribute",
+];
+
+/// Servlet request types whose accessors return client-controlled data
+const REQUEST_TYPES: &[&str] = &["HttpServletRequest", "ServletRequest", "MultipartHttpServletRequest"];
+
+/// Accessors of a servlet request carrying client-controlled data
+const REQUEST_INPUTS: &[&str] = &[
+    "getParameter",
+    "getParameterValues",
+    "getParameterMap",
+    "getHeader",
+    "getHeaders",
+    "getCookies",
+    "getQueryString",
+    "getInputStream",
+    "getReader",
+    "getRequestURI",
+    "getRequestURL",
+    "getPathInfo",
+    "getPart",
+    "getParts",
+];
+
+/// Statement and JPA methods taking SQL as their first argument
+const SQL_METHODS: &[&str] = &[
+    "executeQuery",
+    "executeUpdate",
+    "executeLargeUpdate",
+    "execute",
+    "addBatch",
+    "prepareStatement",
+    "prepareCall",
+    "createQuery",
+    "createNativeQuery",
+    "queryForList",
+    "queryForObject",
+    "queryForMap",
+    "queryForRowSet",
+];
+
+/// JdbcTemplate methods with names too common to match on any receiver
+const JDBC_TEMPLATE_METHODS: &[&str] = &["query", "update", "batchUpdate"];
+
+/// XML parser types and the method reading a document
+const XML_PARSERS: &[(&str, &str)] = &[
+    ("DocumentBuilder", "parse"),
+    ("SAXParser", "parse"),
+    ("XMLReader", "parse"),
+    ("SAXBuilder", "build"),
+    ("SAXReader", "read"),
+    ("Unmarshaller", "unmarshal"),
+    ("XMLInputFactory", "createXMLStreamReader"),
+    ("XMLInputFactory", "createXMLEventReader"),
+];
+
+/// Factory methods returning an XML parser, for chained calls
+const XML_FACTORIES: &[&str] = &["newDocumentBuilder", "newSAXParser", "getXMLReader", "createUnmarshaller"];
+
+/// Parser settings that disable DTDs or external entities
+const XML_HARDENING: &[&str] = &[
+    "disallow-doctype-decl",
+    "external-general-entities",
+    "ACCESS_EXTERNAL_DTD",
+    "SUPPORT_DTD",
+    "setExpandEntityReferences",
+];
+
+/// Calls whose result can no longer carry an injection
+const SANITIZERS: &[&str] = &[
+    "parseInt",
+    "parseLong",
+    "parseShort",
+    "parseDouble",
+    "parseFloat",
+    "parseBoolean",
+    "fromString",
+    "htmlEscape",
+    "escapeHtml4",
+    "forHtml",
+    "encodeForHTML",
+    "encodeForSQL",
+    "encodeForOS",
+];
+
+/// Methods returning a number or boolean about a value rather than the value
+const SCALAR_METHODS: &[&str] = &[
+    "length",
+    "size",
+    "isEmpty",
+    "isBlank",
+    "equals",
+    "equalsIgnoreCase",
+    "contains",
+    "startsWith",
+    "endsWith",
+    "matches",
+    "hashCode",
+    "indexOf",
+];
+
+/// Parameter and cast types that cannot carry an injection
+const SCALAR_TYPES: &[&str] = &[
+    "int", "long", "short", "byte", "double", "float", "boolean", "char", "Integer", "Long", "Short", "Byte",
+    "Double", "Float", "Boolean", "Character", "UUID", "BigDecimal", "BigInteger",
+];
+
+/// Unannotated parameter types treated as caller-controlled text
+const TEXT_TYPES: &[&str] = &["String", "String[]", "CharSequence", "byte[]", "char[]", "InputStream", "Reader"];
+
+/// A sink reached by user input, with the path back to the input
+#[derive(Debug, Clone)]
+pub struct JavaFlow {
+    pub sink: Sink,
+    /// Sink first, then each step back to the entry point
+    pub path: Vec<PathNode>,
+}
+
+/// Every sink in a file, and those user input reaches
+#[derive(Debug, Clone, Default)]
+pub struct JavaAnalysis {
+    pub sinks: Vec<Sink>,
+    pub flows: Vec<JavaFlow>,
+}
+
+/// How a value came to be tainted
+#[derive(Debug, Clone)]
+struct Taint {
+    /// Steps back to the entry point, most recent first
+    trace: Vec<PathNode>,
+    /// Request accessor, when the value is read from it directly
+    origin: Option<String>,
+}
+
+/// Variables of the method being walked
+#[derive(Default)]
+struct Scope {
+    /// Declared type of each parameter and local, without generics
+    types: HashMap<String, String>,
+    /// Names of servlet request parameters
+    requests: HashSet<String>,
+    tainted: HashMap<String, Taint>,
+    /// Whether the method configures its XML parser against external entities
+    xml_hardened: bool,
+}
+
+pub struct JavaAnalyzer {
+    parser: Parser,
+}
+
+impl JavaAnalyzer {
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_java::language())
+            .map_err(|e| format!("Failed to load Java grammar: {}", e))?;
+        Ok(Self { parser })
+    }
+
+    pub fn find_sinks(&mut self, source: &str) -> Result<Vec<Sink>, String> {
+        Ok(self.analyze(source)?.sinks)
+    }
+
+    /// Find the sinks of a Java file and trace user input to them
+    pub fn analyze(&mut self, source: &str) -> Result<JavaAnalysis, String> {
+        let tree = self.parser.parse(source, None).ok_or("Failed to parse Java source")?;
+        let walker = Walker {
+            source: source.as_bytes(),
+            fields: field_types(tree.root_node(), source.as_bytes()),
+        };
+        let mut analysis = JavaAnalysis::default();
+        walker.walk(tree.root_node(), &mut Scope::default(), &mut analysis);
+        Ok(analysis)
+    }
+}
+
+/// A type name without generic arguments or package
+fn base_type(ty: &str) -> String {
+    let ty = ty.split('<').next().unwrap_or(ty).trim();
+    ty.rsplit('.').next().unwrap_or(ty).to_string()
+}
+
+/// Declared types of the fields of every class in the file, so a
+/// `JdbcTemplate` or `DocumentBuilder` field is recognized in any method
+fn field_types(root: Node, source: &[u8]) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "field_declaration" {
+            let ty = node.child_by_field_name("type").and_then(|t| t.utf8_text(source).ok());
+            let mut cursor = node.walk();
+            for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                let name = declarator.child_by_field_name("name").and_then(|n| n.utf8_text(source).ok());
+                if let (Some(name), Some(ty)) = (name, ty) {
+                    fields.insert(name.to_string(), base_type(ty));
+                }
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    fields
+}
+
+/// Literals, and expressions built only from literals
+fn is_constant(node: Node) -> bool {
+    match node.kind() {
+        "string_literal" | "decimal_integer_literal" | "decimal_floating_point_literal" | "true" | "false"
+        | "null_literal" | "character_literal" => true,
+        "binary_expression" | "parenthesized_expression" => {
+            let mut cursor = node.walk();
+            let constant = node.named_children(&mut cursor).all(is_constant);
+            constant
+        }
+        "array_creation_expression" | "array_initializer" => {
+            let mut cursor = node.walk();
+            let constant = node
+                .named_children(&mut cursor)
+                .filter(|n| !n.kind().ends_with("type") && n.kind() != "dimensions")
+                .all(is_constant);
+            constant
+        }
+        _ => false,
+    }
+}
+
+struct Walker<'a> {
+    source: &'a [u8],
+    fields: HashMap<String, String>,
+}
+
+impl Walker<'_> {
+    fn text(&self, node: Node) -> String {
+        node.utf8_text(self.source).unwrap_or("").to_string()
+    }
+
+    fn line_code(&self, node: Node) -> String {
+        self.text(node).lines().next().unwrap_or("").trim().to_string()
+    }
+
+    /// Visit statements in source order, tracking assignments and checking
+    /// calls as they are reached. Each method and constructor gets its own scope.
+    fn walk(&self, node: Node, scope: &mut Scope, analysis: &mut JavaAnalysis) {
+        match node.kind() {
+            "method_declaration" | "constructor_declaration" => {
+                let mut inner = Scope::default();
+                if let Some(params) = node.child_by_field_name("parameters") {
+                    self.declare_parameters(params, &mut inner);
+                }
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.walk(body, &mut inner, analysis);
+                }
+                return;
+            }
+            "method_invocation" | "object_creation_expression" => {
+                if self.hardens_xml(node) {
+                    scope.xml_hardened = true;
+                }
+                self.check_sink(node, scope, analysis);
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.walk(child, scope, analysis);
+        }
+
+        match node.kind() {
+            "local_variable_declaration" => {
+                let ty = node.child_by_field_name("type").map(|t| base_type(&self.text(t)));
+                let mut cursor = node.walk();
+                for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                    let Some(name) = declarator.child_by_field_name("name").map(|n| self.text(n)) else {
+                        continue;
+                    };
+                    if let Some(ty) = &ty {
+                        scope.types.insert(name.clone(), ty.clone());
+                    }
+                    if let Some(value) = declarator.child_by_field_name("value") {
+                        self.bind(node, name, value, false, scope);
+                    }
+                }
+            }
+            "assignment_expression" => {
+                let left = node.child_by_field_name("left").filter(|l| l.kind() == "identifier");
+                if let (Some(left), Some(right)) = (left, node.child_by_field_name("right")) {
+                    let accumulate = node
+                        .child_by_field_name("operator")
+                        .is_some_and(|op| self.text(op) == "+=");
+                    self.bind(node, self.text(left), right, accumulate, scope);
+                }
+            }
+            // `sb.append(input)` taints the builder
+            "method_invocation" => {
+                let name = node.child_by_field_name("name").map(|n| self.text(n));
+                let receiver = node.child_by_field_name("object").filter(|o| o.kind() == "identifier");
+                if let (Some("append" | "add" | "put"), Some(receiver), Some(args)) =
+                    (name.as_deref(), receiver, node.child_by_field_name("arguments"))
+                {
+                    self.bind(node, self.text(receiver), args, true, scope);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn declare_parameters(&self, params: Node, scope: &mut Scope) {
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            let (Some(ty), Some(name)) = (param.child_by_field_name("type"), param.child_by_field_name("name")) else {
+                continue;
+            };
+            let (ty, name) = (base_type(&self.text(ty)), self.text(name));
+            scope.types.insert(name.clone(), ty.clone());
+            if REQUEST_TYPES.contains(&ty.as_str()) {
+                scope.requests.insert(name);
+                continue;
+            }
+            if SCALAR_TYPES.contains(&ty.as_str()) {
+                continue;
+            }
+
+            let annotation = self.request_annotation(param);
+            let description = match &annotation {
+                Some(annotation) => format!("ENTRY: User input from @{} {}", annotation, name),
+                None if TEXT_TYPES.contains(&ty.as_str()) || ty.starts_with("List") || ty.starts_with("Map") => {
+                    "ENTRY: Function parameter (potentially user-controlled)".to_string()
+                }
+                None => continue,
+            };
+            let node = PathNode {
+                line: param.start_position().row + 1,
+                cell: None,
+                code: self.text(param),
+                description,
+            };
+            scope.tainted.insert(name, Taint { trace: vec![node], origin: None });
+        }
+    }
+
+    /// The Spring annotation binding a parameter to the request, if any
+    fn request_annotation(&self, param: Node) -> Option<String> {
+        let mut cursor = param.walk();
+        let modifiers = param.named_children(&mut cursor).find(|c| c.kind() == "modifiers")?;
+        let mut cursor = modifiers.walk();
+        let annotation = modifiers
+            .named_children(&mut cursor)
+            .filter_map(|a| a.child_by_field_name("name").map(|n| base_type(&self.text(n))))
+            .find(|name| REQUEST_ANNOTATIONS.contains(&name.as_str()));
+        annotation
+    }
+
+    /// Taint or clear a variable from its value
+    fn bind(&self, statement: Node, name: String, value: Node, accumulate: bool, scope: &mut Scope) {
+        match self.taint_of(value, scope) {
+            Some(taint) => {
+                let description = match &taint.origin {
+                    Some(origin) => format!("ENTRY: User input from {}", origin),
+                    None => "FLOW: Variable derivation".to_string(),
+                };
+                let mut trace = vec![PathNode {
+                    line: statement.start_position().row + 1,
+                    cell: None,
+                    code: self.line_code(statement),
+                    description,
+                }];
+                trace.extend(taint.trace);
+                scope.tainted.insert(name, Taint { trace, origin: None });
+            }
+            None if !accumulate => {
+                scope.tainted.remove(&name);
+            }
+            None => {}
+        }
+    }
+
+    /// The taint an expression carries, if any
+    fn taint_of(&self, node: Node, scope: &Scope) -> Option<Taint> {
+        match node.kind() {
+            "string_literal" | "decimal_integer_literal" | "lambda_expression" | "class_literal" => return None,
+            "identifier" => return scope.tainted.get(&self.text(node)).cloned(),
+            "method_invocation" => {
+                let name = node.child_by_field_name("name").map(|n| self.text(n)).unwrap_or_default();
+                let receiver = node.child_by_field_name("object");
+                if SANITIZERS.contains(&name.as_str()) || SCALAR_METHODS.contains(&name.as_str()) {
+                    return None;
+                }
+                if name == "valueOf" && receiver.is_some_and(|r| SCALAR_TYPES.contains(&self.text(r).as_str())) {
+                    return None;
+                }
+                let from_request = receiver.is_some_and(|r| scope.requests.contains(&self.text(r)));
+                if from_request && REQUEST_INPUTS.contains(&name.as_str()) {
+                    return Some(Taint { trace: vec![], origin: Some(self.text(node)) });
+                }
+            }
+            "cast_expression" => {
+                let ty = node.child_by_field_name("type").map(|t| self.text(t));
+                if ty.is_some_and(|t| SCALAR_TYPES.contains(&t.as_str())) {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        children.into_iter().find_map(|child| self.taint_of(child, scope))
+    }
+
+    /// Declared type of a receiver: a local or parameter, a field, or the
+    /// class itself for static calls
+    fn receiver_type(&self, receiver: Node, scope: &Scope) -> Option<String> {
+        match receiver.kind() {
+            "identifier" => {
+                let name = self.text(receiver);
+                scope.types.get(&name).or_else(|| self.fields.get(&name)).cloned().or(Some(name))
+            }
+            "field_access" => {
+                let field = receiver.child_by_field_name("field").map(|f| self.text(f))?;
+                self.fields.get(&field).cloned()
+            }
+            "object_creation_expression" => receiver.child_by_field_name("type").map(|t| base_type(&self.text(t))),
+            _ => None,
+        }
+    }
+
+    /// Whether a call configures a parser against external entities
+    fn hardens_xml(&self, node: Node) -> bool {
+        let name = node.child_by_field_name("name").map(|n| self.text(n));
+        if !matches!(name.as_deref(), Some("setFeature" | "setProperty" | "setAttribute" | "setExpandEntityReferences")) {
+            return false;
+        }
+        let text = self.text(node);
+        XML_HARDENING.iter().any(|setting| text.contains(setting))
+    }
+
+    fn arguments<'t>(&self, node: Node<'t>) -> Vec<Node<'t>> {
+        let Some(args) = node.child_by_field_name("arguments") else {
+            return vec![];
+        };
+        let mut cursor = args.walk();
+        let arguments = args.named_children(&mut cursor).collect();
+        arguments
+    }
+
+    /// Sink type and the expressions that must not carry user input
+    fn classify<'t>(&self, node: Node<'t>, scope: &Scope) -> Option<(SinkType, Vec<Node<'t>>)> {
+        let args = self.arguments(node);
+
+        if node.kind() == "object_creation_expression" {
+            let ty = base_type(&self.text(node.child_by_field_name("type")?));
+            return match ty.as_str() {
+                "ProcessBuilder" => Some((SinkType::CommandInjection, args)),
+                // The stream is only read by readObject, but a decoder over
+                // request data is already a finding
+                "XMLDecoder" => Some((SinkType::Deserialization, args)),
+                _ => None,
+            };
+        }
+
+        let name = self.text(node.child_by_field_name("name")?);
+        let receiver = node.child_by_field_name("object");
+        let receiver_type = receiver.and_then(|r| self.receiver_type(r, scope));
+        let receiver_text = receiver.map(|r| self.text(r)).unwrap_or_default();
+
+        if name == "exec" && (receiver_text.ends_with("getRuntime()") || receiver_type.as_deref() == Some("Runtime")) {
+            return Some((SinkType::CommandInjection, args));
+        }
+        if name == "command" && receiver_type.as_deref() == Some("ProcessBuilder") {
+            return Some((SinkType::CommandInjection, args));
+        }
+        if matches!(name.as_str(), "readObject" | "readUnshared") {
+            let stream = receiver?;
+            let ty = receiver_type.unwrap_or_default();
+            return (ty == "ObjectInputStream" || ty == "XMLDecoder").then(|| (SinkType::Deserialization, vec![stream]));
+        }
+        let from_factory = receiver.is_some_and(|r| {
+            r.kind() == "method_invocation"
+                && r.child_by_field_name("name").is_some_and(|n| XML_FACTORIES.contains(&self.text(n).as_str()))
+        });
+        let parses_xml = XML_PARSERS
+            .iter()
+            .any(|(parser, method)| *method == name && (from_factory || receiver_type.as_deref() == Some(*parser)));
+        if parses_xml {
+            return args.first().map(|arg| (SinkType::Xxe, vec![*arg]));
+        }
+        let jdbc_template = receiver_type.as_deref() == Some("JdbcTemplate")
+            || receiver_type.as_deref() == Some("NamedParameterJdbcTemplate");
+        if SQL_METHODS.contains(&name.as_str()) || (jdbc_template && JDBC_TEMPLATE_METHODS.contains(&name.as_str())) {
+            return args.first().map(|arg| (SinkType::SqlInjection, vec![*arg]));
+        }
+        None
+    }
+
+    fn check_sink(&self, node: Node, scope: &Scope, analysis: &mut JavaAnalysis) {
+        let Some((sink_type, arguments)) = self.classify(node, scope) else {
+            return;
+        };
+        // Constant arguments cannot be influenced
+        if arguments.is_empty() || arguments.iter().all(|a| is_constant(*a)) {
+            return;
+        }
+        let xxe_hardened = sink_type == SinkType::Xxe && scope.xml_hardened;
+
+        let mut tainted_vars = Vec::new();
+        for argument in &arguments {
+            self.identifiers(*argument, &mut tainted_vars);
+        }
+        let sink = Sink {
+            sink_type,
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            cell: None,
+            code_snippet: self.text(node),
+            tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
+        };
+
+        let taint = arguments.iter().find_map(|a| self.taint_of(*a, scope));
+        if let Some(taint) = taint.filter(|_| !xxe_hardened) {
+            let mut path = vec![PathNode {
+                line: sink.line,
+                cell: None,
+                code: self.line_code(node),
+                description: format!("SINK: {}", sink.sink_type.description()),
+            }];
+            if let Some(origin) = &taint.origin {
+                path.push(PathNode {
+                    line: sink.line,
+                    cell: None,
+                    code: origin.clone(),
+                    description: format!("ENTRY: User input from {}", origin),
+                });
+            }
+            path.extend(taint.trace);
+            analysis.flows.push(JavaFlow { sink: sink.clone(), path });
+        }
+        analysis.sinks.push(sink);
+    }
+
+    fn identifiers(&self, node: Node, out: &mut Vec<String>) {
+        if node.kind() == "identifier" {
+            let name = self.text(node);
+            if !out.contains(&name) {
+                out.push(name);
+            }
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.identifiers(child, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str) -> JavaAnalysis {
+        JavaAnalyzer::new().unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_spring_sql_concatenation() {
+        let analysis = analyze(
+            r#"
+@RestController
+public class UserController {
+    private JdbcTemplate jdbc;
+
+    @GetMapping("/users")
+    public String find(@RequestParam("name") String name, @PathVariable int id) throws SQLException {
+        String query = "SELECT * FROM users WHERE name = '" + name + "'";
+        Statement stmt = conn.createStatement();
+        ResultSet rs = stmt.executeQuery(query);
+        jdbc.queryForList("SELECT * FROM users WHERE id = " + id);
+        jdbc.query("SELECT * FROM users", mapper);
+        return "ok";
+    }
+}
+"#,
+        );
+        assert_eq!(analysis.sinks.len(), 2, "The constant query is not a sink");
+        assert_eq!(analysis.flows.len(), 1, "The int path variable cannot inject");
+        let flow = &analysis.flows[0];
+        assert_eq!(flow.sink.sink_type, SinkType::SqlInjection);
+        assert_eq!(flow.sink.line, 10);
+        assert!(flow.path.last().unwrap().description.contains("@RequestParam name"));
+    }
+
+    #[test]
+    fn test_servlet_command_execution() {
+        let analysis = analyze(
+            r#"
+public class PingServlet extends HttpServlet {
+    protected void doGet(HttpServletRequest request, HttpServletResponse response) {
+        String host = request.getParameter("host");
+        Runtime.getRuntime().exec("ping -c 1 " + host);
+        new ProcessBuilder("sh", "-c", "nslookup " + host).start();
+        int count = Integer.parseInt(request.getParameter("count"));
+        Runtime.getRuntime().exec("ping -c " + count + " localhost");
+        Runtime.getRuntime().exec("uptime");
+    }
+}
+"#,
+        );
+        let lines: Vec<usize> = analysis.flows.iter().map(|f| f.sink.line).collect();
+        assert_eq!(lines, vec![5, 6]);
+        assert_eq!(analysis.sinks.len(), 3);
+        assert!(analysis.flows.iter().all(|f| f.sink.sink_type == SinkType::CommandInjection));
+    }
+
+    #[test]
+    fn test_deserialization_and_xxe() {
+        let analysis = analyze(
+            r#"
+public class ImportController {
+    @PostMapping("/import")
+    public void load(HttpServletRequest request, @RequestBody String xml) throws Exception {
+        ObjectInputStream in = new ObjectInputStream(request.getInputStream());
+        Object state = in.readObject();
+        ObjectInputStream local = new ObjectInputStream(new FileInputStream(path));
+        local.readObject();
+
+        DocumentBuilderFactory factory = DocumentBuilderFactory.newInstance();
+        factory.newDocumentBuilder().parse(new InputSource(new StringReader(xml)));
+    }
+
+    @PostMapping("/safe")
+    public void safe(@RequestBody String xml) throws Exception {
+        DocumentBuilderFactory factory = DocumentBuilderFactory.newInstance();
+        factory.setFeature("http://apache.org/xml/features/disallow-doctype-decl", true);
+        DocumentBuilder builder = factory.newDocumentBuilder();
+        builder.parse(new InputSource(new StringReader(xml)));
+    }
+}
+"#,
+        );
+        let flows: Vec<(usize, SinkType)> = analysis.flows.iter().map(|f| (f.sink.line, f.sink.sink_type.clone())).collect();
+        assert_eq!(flows, vec![(6, SinkType::Deserialization), (11, SinkType::Xxe)]);
+        assert_eq!(analysis.sinks.len(), 4, "The file stream and hardened parser are sinks without flows");
+    }
+}
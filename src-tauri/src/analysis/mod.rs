@@ -5,14 +5,20 @@
 //! and generate working Proof-of-Concept payloads.
 
 pub mod python_parser;
+pub mod go_analyzer;
+pub mod java_analyzer;
+pub mod php_analyzer;
 pub mod slicer;
 pub mod prover;
 pub mod constraint_gen;
 pub mod solver;
+pub mod notebook;
+pub mod cvss;
 
 pub mod indexer;
 pub use indexer::{ProjectIndexer, Symbol, SymbolKind};
 
+pub mod summaries;
 pub mod cross_slicer;
 pub use cross_slicer::{CrossFileSlicer, CrossFileAnalysisResult, CrossFileFlow};
 
@@ -21,6 +27,19 @@ pub mod integration_tests;
 
 use serde::{Deserialize, Serialize};
 
+/// Version of the sink rules and prover output. Bump it whenever either
+/// changes so cached results from older versions are not reused.
+pub const ANALYSIS_VERSION: u32 = 3;
+
+/// Source languages the prover can analyze
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Python,
+    Go,
+    Php,
+    Java,
+}
+
 /// Represents a detected sink (dangerous function call)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sink {
@@ -30,10 +49,22 @@ pub struct Sink {
     pub line: usize,
     /// Column number
     pub column: usize,
+    /// Notebook cell index when analyzing an .ipynb; `line` is then within the cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell: Option<usize>,
     /// The actual code at this location
     pub code_snippet: String,
     /// Variables used in the sink that need taint analysis
     pub tainted_vars: Vec<String>,
+    /// Identity of the finding for false-positive triage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Who marked this finding as a false positive, and why
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<String>,
+    /// Severity of the finding, set once it is proven exploitable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvss: Option<cvss::CvssScore>,
 }
 
 /// Types of dangerous sinks we detect
@@ -46,6 +77,11 @@ pub enum SinkType {
     Deserialization,   // pickle.loads
     Ssrf,              // requests.get
     Xxe,               // lxml.etree
+    Xss,               // HttpResponse, Markup, returned HTML
+    OpenRedirect,      // redirect(url)
+    HeaderInjection,   // response.headers[...] =, set_cookie
+    NoSqlInjection,    // pymongo collection.find
+    LdapInjection,     // ldap3 / python-ldap search filters
 }
 
 impl SinkType {
@@ -58,6 +94,29 @@ impl SinkType {
             SinkType::Deserialization => "Insecure Deserialization - Untrusted data in pickle",
             SinkType::Ssrf => "Server-Side Request Forgery - User input in network request",
             SinkType::Xxe => "XML External Entity - User input in XML parser",
+            SinkType::Xss => "Cross-Site Scripting - User input rendered as HTML without escaping",
+            SinkType::OpenRedirect => "Open Redirect - User input in redirect target",
+            SinkType::HeaderInjection => "HTTP Header Injection - User input in response header or cookie",
+            SinkType::NoSqlInjection => "NoSQL Injection - User input in MongoDB query",
+            SinkType::LdapInjection => "LDAP Injection - User input in LDAP search filter",
+        }
+    }
+
+    /// CWE identifier of the weakness
+    pub fn cwe(&self) -> &'static str {
+        match self {
+            SinkType::SqlInjection => "CWE-89",
+            SinkType::CommandInjection => "CWE-78",
+            SinkType::CodeInjection => "CWE-94",
+            SinkType::PathTraversal => "CWE-22",
+            SinkType::Deserialization => "CWE-502",
+            SinkType::Ssrf => "CWE-918",
+            SinkType::Xxe => "CWE-611",
+            SinkType::Xss => "CWE-79",
+            SinkType::OpenRedirect => "CWE-601",
+            SinkType::HeaderInjection => "CWE-113",
+            SinkType::NoSqlInjection => "CWE-943",
+            SinkType::LdapInjection => "CWE-90",
         }
     }
 }
@@ -79,6 +138,9 @@ pub struct AnalysisResult {
     pub attack_path: Vec<PathNode>,
     /// Time taken for analysis in milliseconds
     pub analysis_time_ms: u64,
+    /// Severity of the most severe exploitable sink
+    #[serde(default)]
+    pub cvss: Option<cvss::CvssScore>,
 }
 
 /// Status of exploit analysis
@@ -98,6 +160,9 @@ pub enum ExploitStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathNode {
     pub line: usize,
+    /// Notebook cell index, as for `Sink::cell`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell: Option<usize>,
     pub code: String,
     pub description: String,
 }
@@ -112,6 +177,7 @@ impl Default for AnalysisResult {
             explanation: String::new(),
             attack_path: vec![],
             analysis_time_ms: 0,
+            cvss: None,
         }
     }
 }
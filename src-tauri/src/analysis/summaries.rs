@@ -0,0 +1,177 @@
+//! Function Taint Summaries
+//!
+//! What each function in the workspace does with its parameters: which reach
+//! a sink, directly or through calls into other files, and what its return
+//! value can carry. Summaries are computed bottom-up by the cross-file slicer
+//! and kept per file under a fingerprint of the file's source, so a saved
+//! store only needs the files that changed (and their callers) recomputed.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::slicer::{ReturnTaint, TaintConfig};
+use super::{SinkType, ANALYSIS_VERSION};
+
+/// A sink that a parameter's value can reach
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamSink {
+    /// Position of the parameter (excluding self/cls)
+    pub param: usize,
+    /// File containing the sink, which is another file for sinks reached through calls
+    pub file: PathBuf,
+    pub line: usize,
+    pub code: String,
+    pub sink_type: SinkType,
+    /// Function called to get there, for sinks outside this function
+    pub via: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionSummary {
+    pub line: usize,
+    pub params: Vec<String>,
+    pub returns: ReturnTaint,
+    pub param_sinks: Vec<ParamSink>,
+}
+
+impl FunctionSummary {
+    /// Position of a parameter by name, for keyword arguments
+    pub fn param_index(&self, name: &str) -> Option<usize> {
+        self.params.iter().position(|p| p == name)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileSummary {
+    /// Fingerprint of the source the summary was computed from
+    pub fingerprint: String,
+    /// Other files whose summaries this one was built from
+    pub dependencies: Vec<PathBuf>,
+    pub functions: HashMap<String, FunctionSummary>,
+}
+
+/// Summaries of every analyzed file in a workspace
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SummaryStore {
+    /// `ANALYSIS_VERSION` and taint options the summaries were computed with
+    #[serde(default)]
+    options: String,
+    #[serde(default)]
+    files: HashMap<PathBuf, FileSummary>,
+}
+
+fn options_key(config: &TaintConfig) -> String {
+    format!(
+        "v{}|env={}|config_files={}",
+        ANALYSIS_VERSION, config.untrusted_environment, config.untrusted_config_files
+    )
+}
+
+/// Fingerprint of a file's source
+pub fn fingerprint(source: &str) -> String {
+    hex::encode(Sha256::digest(source.as_bytes()))
+}
+
+impl SummaryStore {
+    pub fn get(&self, file_path: &Path) -> Option<&FileSummary> {
+        self.files.get(file_path)
+    }
+
+    pub fn insert(&mut self, file_path: PathBuf, summary: FileSummary) {
+        self.files.insert(file_path, summary);
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Drop summaries that no longer describe the workspace: all of them if
+    /// the analysis options changed, otherwise those of files that changed or
+    /// disappeared and of every file built on them. Returns how many were dropped.
+    pub fn invalidate(&mut self, config: &TaintConfig) -> usize {
+        let before = self.files.len();
+        let options = options_key(config);
+        if self.options != options {
+            self.options = options;
+            self.files.clear();
+            return before;
+        }
+
+        let mut stale: HashSet<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(path, summary)| {
+                fs::read_to_string(path).map_or(true, |source| fingerprint(&source) != summary.fingerprint)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        // Callers of a stale file were built from its old summary
+        loop {
+            let dependents: Vec<PathBuf> = self
+                .files
+                .iter()
+                .filter(|(path, summary)| {
+                    !stale.contains(*path) && summary.dependencies.iter().any(|dep| stale.contains(dep))
+                })
+                .map(|(path, _)| path.clone())
+                .collect();
+            if dependents.is_empty() {
+                break;
+            }
+            stale.extend(dependents);
+        }
+
+        self.files.retain(|path, _| !stale.contains(path));
+        before - self.files.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_file_and_callers_are_invalidated() {
+        let temp_dir = std::env::temp_dir().join("test_summaries_invalidate");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let callee = temp_dir.join("callee.py");
+        let caller = temp_dir.join("caller.py");
+        let other = temp_dir.join("other.py");
+        for path in [&callee, &caller, &other] {
+            std::fs::write(path, "def f(x):\n    return x\n").unwrap();
+        }
+
+        let config = TaintConfig::default();
+        let mut store = SummaryStore::default();
+        store.invalidate(&config);
+        let summary = |deps: Vec<PathBuf>| FileSummary {
+            fingerprint: fingerprint("def f(x):\n    return x\n"),
+            dependencies: deps,
+            functions: HashMap::new(),
+        };
+        store.insert(callee.clone(), summary(vec![]));
+        store.insert(caller.clone(), summary(vec![callee.clone()]));
+        store.insert(other.clone(), summary(vec![]));
+
+        assert_eq!(store.invalidate(&config), 0, "Nothing changed");
+
+        std::fs::write(&callee, "def f(x):\n    return 1\n").unwrap();
+        assert_eq!(store.invalidate(&config), 2);
+        assert!(store.get(&other).is_some());
+        assert!(store.get(&caller).is_none(), "Caller depends on the edited file");
+
+        let strict = TaintConfig { untrusted_environment: true, ..TaintConfig::default() };
+        assert_eq!(store.invalidate(&strict), 1, "New options drop everything");
+        assert!(store.is_empty());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+}
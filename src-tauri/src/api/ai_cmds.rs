@@ -1,8 +1,20 @@
-// AI commands placeholder
-// To be implemented with local LLM or API integration
+// AI commands: code completion through the provider configured in the ai.*
+// settings, local model downloads and finding triage; chat and explanation are
+// still placeholders
 
 use serde::{Deserialize, Serialize};
 
+use tauri::{AppHandle, Emitter};
+
+use crate::services::ai::manager::{self, CatalogListing, LocalModel};
+use crate::services::ai::triage::{self, TriageReport};
+use crate::services::ai::{engine, rag};
+use crate::services::project::trust;
+
+const COMPLETION_SYSTEM_PROMPT: &str = "You are the code completion engine of a security-focused IDE. \
+Reply with only the code to insert at <CURSOR>, without explanations or Markdown fences. \
+Follow the style of the surrounding code and never introduce injection, path traversal or other vulnerabilities.";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
@@ -16,10 +28,53 @@ pub async fn ai_chat(_messages: Vec<ChatMessage>) -> Result<String, String> {
     Ok("AI integration coming soon. This will support local LLMs and cloud APIs.".to_string())
 }
 
+/// Complete `code` at the cursor (1-based line, 0-based column; the end of
+/// the code by default). The request carries the file's imports, imported
+/// project definitions, user-controlled variables at the cursor and nearby
+/// signatures along with the code. Refused in untrusted workspaces, as it
+/// sends file contents to the provider.
 #[tauri::command]
-pub async fn ai_code_completion(_code: String, _language: String) -> Result<String, String> {
-    // TODO: Implement code-specific completions
-    Err("AI code completion coming soon".to_string())
+pub async fn ai_code_completion(
+    code: String,
+    language: String,
+    file_path: Option<String>,
+    cursor_line: Option<usize>,
+    cursor_column: Option<usize>,
+    workspace_root: Option<String>,
+) -> Result<String, String> {
+    let folder = workspace_root.clone().or_else(|| {
+        let parent = std::path::Path::new(file_path.as_deref()?).parent()?;
+        Some(parent.to_string_lossy().to_string()).filter(|p| !p.is_empty())
+    });
+    if let Some(folder) = folder {
+        trust::require_trusted(&folder, "AI completion")?;
+    }
+    let cursor_line = cursor_line.unwrap_or_else(|| code.lines().count().max(1));
+    let cursor_column = cursor_column.unwrap_or_else(|| {
+        code.lines().nth(cursor_line.saturating_sub(1)).map(|l| l.chars().count()).unwrap_or(0)
+    });
+    let root = workspace_root.clone();
+    let prompt = tokio::task::spawn_blocking(move || {
+        let context = rag::completion_context(&code, &language, file_path.as_deref(), cursor_line, root.as_deref());
+        rag::completion_prompt(&context, &code, cursor_line, cursor_column)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    let completion = engine::complete(COMPLETION_SYSTEM_PROMPT, &prompt, 256, workspace_root.as_deref()).await?;
+    Ok(strip_code_fence(&completion))
+}
+
+/// Models often wrap code in a Markdown fence despite being asked not to
+fn strip_code_fence(text: &str) -> String {
+    let trimmed = text.trim_matches('\n');
+    match trimmed.strip_prefix("```") {
+        Some(rest) => {
+            let body = rest.split_once('\n').map(|(_, body)| body).unwrap_or("");
+            body.trim_end().trim_end_matches("```").trim_end_matches('\n').to_string()
+        }
+        None => trimmed.to_string(),
+    }
 }
 
 #[tauri::command]
@@ -27,3 +82,58 @@ pub async fn ai_code_explain(_code: String) -> Result<String, String> {
     // TODO: Explain code using AI
     Err("AI code explanation coming soon".to_string())
 }
+
+/// Recommended local models with their download state
+#[tauri::command]
+pub async fn ai_list_model_catalog() -> Result<Vec<CatalogListing>, String> {
+    tokio::task::spawn_blocking(manager::list_catalog)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn ai_list_local_models() -> Result<Vec<LocalModel>, String> {
+    tokio::task::spawn_blocking(manager::list_local)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Download a catalog model into ~/.ctr/models, resuming a previous partial
+/// download; emits `model-download-progress`
+#[tauri::command]
+pub async fn ai_download_model(app_handle: AppHandle, model_id: String) -> Result<LocalModel, String> {
+    manager::download(&model_id, &|progress| {
+        let _ = app_handle.emit("model-download-progress", progress);
+    })
+    .await
+}
+
+/// Pause a download; downloading the model again resumes it
+#[tauri::command]
+pub async fn ai_cancel_model_download(model_id: String) -> Result<bool, String> {
+    Ok(manager::cancel_download(&model_id))
+}
+
+#[tauri::command]
+pub async fn ai_delete_model(model_id: String) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || manager::delete(&model_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Make a downloaded model available on the local Ollama server; returns
+/// the name to use as `ai.model`
+#[tauri::command]
+pub async fn ai_register_model(model_id: String, name: Option<String>) -> Result<String, String> {
+    manager::register(&model_id, name.as_deref()).await
+}
+
+/// Ask the AI to group duplicate findings, flag likely false positives and
+/// order the rest; the suggestions are stored as annotations on the findings
+/// (all open ones when `finding_ids` is empty) and statuses stay unchanged.
+/// Findings carry code snippets, so untrusted workspaces are refused.
+#[tauri::command]
+pub async fn ai_triage_findings(workspace_root: String, finding_ids: Option<Vec<String>>) -> Result<TriageReport, String> {
+    trust::require_trusted(&workspace_root, "AI triage")?;
+    triage::triage_findings(&workspace_root, &finding_ids.unwrap_or_default()).await
+}
@@ -0,0 +1,25 @@
+use crate::services::audit::{self, AuditEntry, AuditQuery};
+
+#[tauri::command]
+pub async fn get_audit_enabled() -> Result<bool, String> {
+    Ok(audit::is_enabled())
+}
+
+#[tauri::command]
+pub async fn set_audit_enabled(enabled: bool) -> Result<(), String> {
+    audit::set_enabled(enabled)
+}
+
+#[tauri::command]
+pub async fn query_audit_log(query: Option<AuditQuery>) -> Result<Vec<AuditEntry>, String> {
+    audit::query(&query.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn export_audit_log(
+    dest_path: String,
+    format: String,
+    query: Option<AuditQuery>,
+) -> Result<usize, String> {
+    audit::export(&query.unwrap_or_default(), &dest_path, &format)
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use crate::services::binary::strings::{Indicator, StringEncoding};
+use crate::services::binary::{self, BinaryInfo, StringPage};
+
+fn existing_file(path: String) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err("File does not exist".into());
+    }
+    Ok(path)
+}
+
+/// Hashes, ELF/PE headers, sections, imports and triage notes of a binary
+#[tauri::command]
+pub async fn get_binary_info(path: String) -> Result<BinaryInfo, String> {
+    let path = existing_file(path)?;
+    tokio::task::spawn_blocking(move || binary::info(&path))
+        .await
+        .map_err(|e| format!("Binary analysis task failed: {}", e))?
+}
+
+/// A page of a binary's printable strings with their offsets and encoding
+#[tauri::command]
+pub async fn get_binary_strings(
+    path: String,
+    min_length: Option<usize>,
+    encoding: Option<StringEncoding>,
+    filter: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<StringPage, String> {
+    let path = existing_file(path)?;
+    tokio::task::spawn_blocking(move || binary::strings(&path, min_length, encoding, filter.as_deref(), offset.unwrap_or(0), limit))
+        .await
+        .map_err(|e| format!("Binary analysis task failed: {}", e))?
+}
+
+/// URLs and IP addresses embedded in a binary
+#[tauri::command]
+pub async fn get_binary_indicators(path: String) -> Result<Vec<Indicator>, String> {
+    let path = existing_file(path)?;
+    tokio::task::spawn_blocking(move || binary::indicators(&path))
+        .await
+        .map_err(|e| format!("Binary analysis task failed: {}", e))?
+}
@@ -3,6 +3,9 @@ use std::process::Command;
 use std::path::Path;
 use std::fs;
 
+use crate::services::audit;
+use crate::services::langdetect::{self, LanguageId};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeRunResult {
     pub output: String,
@@ -20,87 +23,80 @@ pub struct LanguageConfig {
     pub repl_cmd: Option<String>,
 }
 
-/// Get language configuration based on file extension
-fn get_language_config(extension: &str) -> Option<LanguageConfig> {
-    match extension.to_lowercase().as_str() {
-        "py" => Some(LanguageConfig {
+/// Get the run configuration of a language
+fn get_language_config(language: LanguageId) -> Option<LanguageConfig> {
+    match language {
+        LanguageId::Python => Some(LanguageConfig {
             name: "Python".to_string(),
             extensions: vec!["py".to_string()],
             compile_cmd: None,
             run_cmd: "python".to_string(),
             repl_cmd: Some("python -i".to_string()),
         }),
-        "js" | "mjs" => Some(LanguageConfig {
+        LanguageId::JavaScript => Some(LanguageConfig {
             name: "JavaScript".to_string(),
-            extensions: vec!["js".to_string(), "mjs".to_string()],
+            extensions: vec!["js".to_string(), "mjs".to_string(), "jsx".to_string()],
             compile_cmd: None,
             run_cmd: "node".to_string(),
             repl_cmd: Some("node".to_string()),
         }),
-        "ts" | "tsx" => Some(LanguageConfig {
+        LanguageId::TypeScript => Some(LanguageConfig {
             name: "TypeScript".to_string(),
             extensions: vec!["ts".to_string(), "tsx".to_string()],
             compile_cmd: None,
             run_cmd: "ts-node".to_string(),
             repl_cmd: Some("ts-node".to_string()),
         }),
-        "jsx" => Some(LanguageConfig {
-            name: "JavaScript".to_string(),
-            extensions: vec!["jsx".to_string()],
-            compile_cmd: None,
-            run_cmd: "node".to_string(),
-            repl_cmd: Some("node".to_string()),
-        }),
-        "rs" => Some(LanguageConfig {
+        LanguageId::Rust => Some(LanguageConfig {
             name: "Rust".to_string(),
             extensions: vec!["rs".to_string()],
             compile_cmd: Some("rustc".to_string()),
             run_cmd: "".to_string(), // Binary will be run directly
             repl_cmd: None,
         }),
-        "c" | "h" => Some(LanguageConfig {
+        LanguageId::C => Some(LanguageConfig {
             name: "C".to_string(),
             extensions: vec!["c".to_string(), "h".to_string()],
             compile_cmd: Some("gcc".to_string()),
             run_cmd: "".to_string(), // Binary will be run directly
             repl_cmd: None,
         }),
-        "cpp" | "cc" | "cxx" | "hpp" | "hh" => Some(LanguageConfig {
+        LanguageId::Cpp => Some(LanguageConfig {
             name: "C++".to_string(),
             extensions: vec!["cpp".to_string(), "cc".to_string(), "cxx".to_string(), "hpp".to_string(), "hh".to_string()],
             compile_cmd: Some("g++".to_string()),
             run_cmd: "".to_string(), // Binary will be run directly
             repl_cmd: None,
         }),
-        "java" => Some(LanguageConfig {
+        LanguageId::Java => Some(LanguageConfig {
             name: "Java".to_string(),
             extensions: vec!["java".to_string()],
             compile_cmd: Some("javac".to_string()),
             run_cmd: "java".to_string(),
             repl_cmd: Some("jshell".to_string()),
         }),
-        "go" => Some(LanguageConfig {
+        LanguageId::Go => Some(LanguageConfig {
             name: "Go".to_string(),
             extensions: vec!["go".to_string()],
             compile_cmd: Some("go build".to_string()),
             run_cmd: "".to_string(), // Binary will be run directly
             repl_cmd: None,
         }),
-        "rb" => Some(LanguageConfig {
+        LanguageId::Ruby => Some(LanguageConfig {
             name: "Ruby".to_string(),
             extensions: vec!["rb".to_string()],
             compile_cmd: None,
             run_cmd: "ruby".to_string(),
             repl_cmd: Some("irb".to_string()),
         }),
-        "php" => Some(LanguageConfig {
+        LanguageId::Php => Some(LanguageConfig {
             name: "PHP".to_string(),
             extensions: vec!["php".to_string()],
             compile_cmd: None,
             run_cmd: "php".to_string(),
             repl_cmd: Some("php -a".to_string()),
         }),
-        "sh" | "bash" => Some(LanguageConfig {
+        LanguageId::Shell => Some(LanguageConfig {
             name: "Shell".to_string(),
             extensions: vec!["sh".to_string(), "bash".to_string()],
             compile_cmd: None,
@@ -140,6 +136,11 @@ fn run_with_go_run(file_path: &str, start_time: std::time::Instant) -> Result<Co
 /// Run a code file
 #[tauri::command]
 pub async fn run_code_file(file_path: String) -> Result<CodeRunResult, String> {
+    audit::record("code_runner", None, &file_path, None);
+    execute_code_file(file_path).await
+}
+
+async fn execute_code_file(file_path: String) -> Result<CodeRunResult, String> {
     use std::time::Instant;
 
     let start_time = Instant::now();
@@ -149,14 +150,16 @@ pub async fn run_code_file(file_path: String) -> Result<CodeRunResult, String> {
         return Err("File does not exist".to_string());
     }
 
-    // Get file extension
     let extension = Path::new(&file_path)
         .extension()
         .and_then(|e| e.to_str())
-        .ok_or("File has no extension")?;
+        .unwrap_or("");
 
-    let config = get_language_config(extension)
-        .ok_or(format!("Unsupported language: .{}", extension))?;
+    // Extensionless scripts are recognized by their shebang
+    let detection = langdetect::detect_file(Path::new(&file_path))
+        .ok_or_else(|| format!("Could not detect the language of {}", file_path))?;
+    let config = get_language_config(detection.language)
+        .ok_or(format!("Unsupported language: {}", detection.language.name()))?;
 
     let mut output = String::new();
     let mut error_output = String::new();
@@ -324,26 +327,18 @@ pub async fn run_code_snippet(language: String, code: String) -> Result<CodeRunR
 
     // Create a temporary file
     let temp_dir = std::env::temp_dir();
-    let file_extension = match language.to_lowercase().as_str() {
-        "python" => "py",
-        "javascript" => "js",
-        "typescript" => "ts",
-        "rust" => "rs",
-        "c" => "c",
-        "cpp" => "cpp",
-        "java" => "java",
-        "go" => "go",
-        "ruby" => "rb",
-        "php" => "php",
-        "shell" => "sh",
-        _ => return Err(format!("Unsupported language: {}", language)),
-    };
+    let file_extension = LanguageId::from_name(&language)
+        .filter(|l| get_language_config(*l).is_some())
+        .map(|l| l.extension())
+        .ok_or_else(|| format!("Unsupported language: {}", language))?;
 
     let temp_file = temp_dir.join(format!("temp_code_{}.{}", std::process::id(), file_extension));
     fs::write(&temp_file, &code).map_err(|e| format!("Failed to write temp file: {}", e))?;
 
+    audit::record("code_runner", None, &format!("[{} snippet]\n{}", language, code), None);
+
     // Run the temp file
-    let result = run_code_file(temp_file.to_string_lossy().to_string()).await;
+    let result = execute_code_file(temp_file.to_string_lossy().to_string()).await;
 
     // Clean up temp file
     let _ = fs::remove_file(&temp_file);
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::services::crypto_tools::hashes::{
+    self, CrackControl, CrackEvent, HashCandidate, NativeAlgorithm,
+};
+use crate::utils::fs_utils::ctr_dir;
+
+lazy_static::lazy_static! {
+    static ref CRACK_JOBS: Arc<Mutex<HashMap<String, Arc<CrackControl>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "engine", rename_all = "lowercase")]
+pub enum CrackEngine {
+    Native { algorithm: NativeAlgorithm },
+    Hashcat { mode: u32 },
+    John { format: Option<String> },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrackTools {
+    pub hashcat: bool,
+    pub john: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CrackEventPayload {
+    job_id: String,
+    event: CrackEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CrackCompletePayload {
+    job_id: String,
+    cracked: usize,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn identify_hash(sample: String) -> Result<Vec<HashCandidate>, String> {
+    Ok(hashes::identify(&sample))
+}
+
+#[tauri::command]
+pub async fn detect_crack_tools() -> Result<CrackTools, String> {
+    tokio::task::spawn_blocking(|| CrackTools {
+        hashcat: hashes::tool_available("hashcat"),
+        john: hashes::tool_available("john"),
+    })
+    .await
+    .map_err(|e| format!("Tool detection failed: {}", e))
+}
+
+/// Start a dictionary attack. Progress and cracked hashes stream as `crack-event`,
+/// and `crack-complete` fires when the job ends.
+#[tauri::command]
+pub async fn start_crack(
+    app_handle: AppHandle,
+    hashes: Vec<String>,
+    engine: CrackEngine,
+    wordlist: String,
+) -> Result<String, String> {
+    let hashes: Vec<String> = hashes
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect();
+    if hashes.is_empty() {
+        return Err("No hashes to crack".to_string());
+    }
+
+    let wordlist = PathBuf::from(&wordlist);
+    if !wordlist.is_file() {
+        return Err("Wordlist does not exist".into());
+    }
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let work_dir = ctr_dir()?.join("crack");
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("Failed to create crack directory: {}", e))?;
+    let hash_file = work_dir.join(format!("{}.txt", job_id));
+
+    let control = Arc::new(CrackControl::default());
+    CRACK_JOBS
+        .lock()
+        .unwrap()
+        .insert(job_id.clone(), control.clone());
+
+    let id = job_id.clone();
+    tokio::task::spawn_blocking(move || {
+        let emit = |event: CrackEvent| {
+            let _ = app_handle.emit(
+                "crack-event",
+                CrackEventPayload {
+                    job_id: id.clone(),
+                    event,
+                },
+            );
+        };
+
+        let result = match &engine {
+            CrackEngine::Native { algorithm } => {
+                hashes::crack_native(&hashes, *algorithm, &wordlist, &control, emit)
+            }
+            CrackEngine::Hashcat { mode } => {
+                hashes::crack_hashcat(&hashes, &hash_file, *mode, &wordlist, &control, emit)
+            }
+            CrackEngine::John { format } => {
+                hashes::crack_john(&hashes, &hash_file, format.as_deref(), &wordlist, &control, emit)
+            }
+        };
+
+        let _ = std::fs::remove_file(&hash_file);
+        CRACK_JOBS.lock().unwrap().remove(&id);
+
+        let (cracked, error) = match result {
+            Ok(n) => (n, None),
+            Err(e) => (0, Some(e)),
+        };
+        let _ = app_handle.emit(
+            "crack-complete",
+            CrackCompletePayload {
+                job_id: id.clone(),
+                cracked,
+                cancelled: control.cancelled.load(std::sync::atomic::Ordering::SeqCst),
+                error,
+            },
+        );
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub async fn cancel_crack(job_id: String) -> Result<(), String> {
+    let control = CRACK_JOBS
+        .lock()
+        .unwrap()
+        .get(&job_id)
+        .cloned()
+        .ok_or_else(|| format!("Crack job {} not found", job_id))?;
+    control.cancel();
+    Ok(())
+}
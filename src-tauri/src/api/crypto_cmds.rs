@@ -0,0 +1,45 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+
+use crate::services::crypto_tools::{self, Operation};
+
+#[derive(Debug, Serialize)]
+pub struct TransformOutput {
+    /// Text output, or base64 when `output_base64` is set
+    pub output: String,
+    pub output_base64: bool,
+    pub hex: String,
+}
+
+/// Run input through a chain of encode/decode/crypto operations.
+/// `input_format` is "utf8" (default), "hex" or "base64".
+#[tauri::command]
+pub async fn crypto_transform(
+    input: String,
+    recipe: Vec<Operation>,
+    input_format: Option<String>,
+) -> Result<TransformOutput, String> {
+    let bytes = match input_format.as_deref().unwrap_or("utf8") {
+        "utf8" | "text" => input.into_bytes(),
+        "hex" => hex::decode(input.trim()).map_err(|e| format!("Invalid hex input: {}", e))?,
+        "base64" => BASE64
+            .decode(input.trim())
+            .map_err(|e| format!("Invalid base64 input: {}", e))?,
+        other => return Err(format!("Unknown input format: {}", other)),
+    };
+
+    let output = crypto_tools::transform(&bytes, &recipe)?;
+    let hex = hex::encode(&output);
+    Ok(match String::from_utf8(output) {
+        Ok(text) => TransformOutput {
+            output: text,
+            output_base64: false,
+            hex,
+        },
+        Err(e) => TransformOutput {
+            output: BASE64.encode(e.as_bytes()),
+            output_base64: true,
+            hex,
+        },
+    })
+}
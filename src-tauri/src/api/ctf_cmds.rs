@@ -0,0 +1,67 @@
+use crate::services::ctf::{self, ChallengeHint, ChallengePack, ChallengeStatus, Completion, FlagResult};
+use crate::services::labs::{self, LabInstance};
+
+#[tauri::command]
+pub async fn load_ctf_pack(path: String) -> Result<ChallengePack, String> {
+    ctf::load_pack(&path)
+}
+
+#[tauri::command]
+pub async fn list_ctf_packs() -> Result<Vec<ChallengePack>, String> {
+    Ok(ctf::list_packs())
+}
+
+#[tauri::command]
+pub async fn remove_ctf_pack(pack_id: String) -> Result<(), String> {
+    ctf::remove_pack(&pack_id)
+}
+
+/// Start the challenge's target environment through the labs subsystem
+#[tauri::command]
+pub async fn start_ctf_challenge(pack_id: String, challenge_id: String) -> Result<LabInstance, String> {
+    let (challenge, pack_dir) = ctf::find_challenge(&pack_id, &challenge_id)?;
+    let setup = challenge
+        .target
+        .ok_or_else(|| format!("Challenge {} has no target environment", challenge_id))?;
+    let name = format!("{}/{}", pack_id, challenge.title);
+
+    tokio::task::spawn_blocking(move || labs::start(&name, &setup, Some(&pack_dir)))
+        .await
+        .map_err(|e| format!("Lab task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn submit_ctf_flag(
+    user: String,
+    pack_id: String,
+    challenge_id: String,
+    flag: String,
+) -> Result<FlagResult, String> {
+    ctf::submit_flag(&user, &pack_id, &challenge_id, &flag)
+}
+
+#[tauri::command]
+pub async fn reveal_ctf_hint(
+    user: String,
+    pack_id: String,
+    challenge_id: String,
+    index: usize,
+) -> Result<ChallengeHint, String> {
+    ctf::reveal_hint(&user, &pack_id, &challenge_id, index)
+}
+
+#[tauri::command]
+pub async fn get_ctf_progress(user: String, pack_id: Option<String>) -> Result<Vec<ChallengeStatus>, String> {
+    Ok(ctf::progress(&user, pack_id.as_deref()))
+}
+
+#[tauri::command]
+pub async fn list_ctf_completions() -> Result<Vec<Completion>, String> {
+    Ok(ctf::completions())
+}
+
+/// Hash a flag the way pack manifests expect, for pack authors
+#[tauri::command]
+pub async fn hash_ctf_flag(flag: String) -> Result<String, String> {
+    Ok(ctf::hash_flag(&flag))
+}
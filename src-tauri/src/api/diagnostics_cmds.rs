@@ -0,0 +1,35 @@
+use crate::services::logging::{self, LogEntry, LogLevels, LogQuery};
+
+#[tauri::command]
+pub async fn get_log_levels() -> Result<LogLevels, String> {
+    Ok(logging::levels())
+}
+
+/// Change the log level at runtime; with `target`, only for that module
+/// and those under it, where a level of `reset` returns it to the global one
+#[tauri::command]
+pub async fn set_log_level(level: String, target: Option<String>) -> Result<LogLevels, String> {
+    logging::set_level(&level, target.as_deref())
+}
+
+/// Recent log entries for the output panel, oldest first. Pass the id of
+/// the last entry shown as `after` to fetch only new ones.
+#[tauri::command]
+pub async fn get_recent_logs(
+    after: Option<u64>,
+    min_level: Option<String>,
+    target: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    logging::recent(&LogQuery { after, min_level, target, limit })
+}
+
+/// Bundle logs, versions and settings into a zip for a bug report and
+/// return its path
+#[tauri::command]
+pub async fn export_diagnostics(dest_path: Option<String>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || logging::export_diagnostics(dest_path.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map(|path| path.to_string_lossy().to_string())
+}
@@ -1,6 +1,16 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::services::langdetect::{self, Detection};
+use crate::services::project::batch::{self, BatchResult, FileOperation};
+use crate::services::project::drafts::{self, Draft, DraftInfo};
+use crate::services::project::permissions::{self, PermissionInfo};
+use crate::services::project::templates::{self, CreatedFile, FileTemplateInfo};
+use crate::utils::blocking::{self, CancelToken};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileNode {
@@ -10,83 +20,137 @@ pub struct FileNode {
     pub node_type: String,
     pub extension: Option<String>,
     pub children: Option<Vec<FileNode>>,
+    /// Whether the entry is a symlink; `type` describes what it points to
+    #[serde(default)]
+    pub is_symlink: bool,
+    pub symlink_target: Option<String>,
+    /// A symlink whose target does not exist
+    #[serde(default)]
+    pub broken_link: bool,
+    #[serde(default)]
+    pub size: u64,
+    /// Unix timestamp in milliseconds
+    pub modified: Option<u64>,
+    #[serde(default)]
+    pub permissions: PermissionInfo,
 }
 
+/// Read a file; `operation_id` lets `cancel_fs_operation` abandon a read
+/// stuck on a slow drive
 #[tauri::command]
-pub async fn read_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+pub async fn read_file(path: String, operation_id: Option<String>) -> Result<String, String> {
+    blocking::run(operation_id.as_deref(), move |_| {
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read file: {}", e))
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn write_file(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+    blocking::run(None, move |_| {
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        // The saved file supersedes any crash-recovery draft
+        if let Err(e) = drafts::discard(&path) {
+            log::warn!("Failed to discard draft of {}: {}", path, e);
+        }
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn create_file(path: String) -> Result<(), String> {
-    if let Some(parent) = Path::new(&path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-    }
-    
-    fs::File::create(&path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
-    Ok(())
+    blocking::run(None, move |_| {
+        if let Some(parent) = Path::new(&path).parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+        }
+        
+        fs::File::create(&path)
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn delete_file(path: String) -> Result<(), String> {
-    let metadata = fs::metadata(&path)
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
-    
-    if metadata.is_dir() {
-        fs::remove_dir_all(&path)
-            .map_err(|e| format!("Failed to delete directory: {}", e))
-    } else {
-        fs::remove_file(&path)
-            .map_err(|e| format!("Failed to delete file: {}", e))
-    }
+    blocking::run(None, move |_| {
+        let metadata = fs::metadata(&path)
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+        
+        if metadata.is_dir() {
+            fs::remove_dir_all(&path)
+                .map_err(|e| format!("Failed to delete directory: {}", e))
+        } else {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete file: {}", e))
+        }
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn create_directory(path: String) -> Result<(), String> {
-    fs::create_dir_all(&path)
-        .map_err(|e| format!("Failed to create directory: {}", e))
+    blocking::run(None, move |_| {
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create directory: {}", e))
+    })
+    .await
 }
 
+/// List a directory; `operation_id` lets `cancel_fs_operation` stop the
+/// listing of a huge or slow directory
 #[tauri::command]
-pub async fn list_directory(path: String) -> Result<Vec<FileNode>, String> {
+pub async fn list_directory(path: String, operation_id: Option<String>) -> Result<Vec<FileNode>, String> {
     let path_buf = PathBuf::from(&path);
-    
-    if !path_buf.exists() {
-        return Err("Path does not exist".to_string());
-    }
-    
-    if !path_buf.is_dir() {
-        return Err("Path is not a directory".to_string());
-    }
-    
-    read_directory(&path_buf)
+
+    blocking::run(operation_id.as_deref(), move |cancel| {
+        if !path_buf.exists() {
+            return Err("Path does not exist".to_string());
+        }
+        
+        if !path_buf.is_dir() {
+            return Err("Path is not a directory".to_string());
+        }
+        
+        read_directory(&path_buf, &cancel)
+    })
+    .await
 }
 
-fn read_directory(path: &Path) -> Result<Vec<FileNode>, String> {
+/// Stop a read or listing started with `operation_id`; false when it
+/// already finished
+#[tauri::command]
+pub async fn cancel_fs_operation(operation_id: String) -> Result<bool, String> {
+    Ok(blocking::cancel(&operation_id))
+}
+
+fn read_directory(path: &Path, cancel: &CancelToken) -> Result<Vec<FileNode>, String> {
     let mut nodes = Vec::new();
     
     let entries = fs::read_dir(path)
         .map_err(|e| format!("Failed to read directory: {}", e))?;
     
     for entry in entries {
+        cancel.check()?;
         let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let metadata = entry.metadata()
+        let entry_path = entry.path();
+        let link_metadata = entry.metadata()
             .map_err(|e| format!("Failed to get metadata: {}", e))?;
+        let is_symlink = link_metadata.file_type().is_symlink();
+        // Symlinks are listed as what they point to; broken ones as files
+        let target_metadata = if is_symlink { fs::metadata(&entry_path).ok() } else { None };
+        let broken_link = is_symlink && target_metadata.is_none();
+        let metadata = target_metadata.unwrap_or(link_metadata);
         
         let name = entry.file_name()
             .to_string_lossy()
             .to_string();
         
-        let path_str = entry.path()
+        let path_str = entry_path
             .to_string_lossy()
             .to_string();
         
@@ -101,8 +165,8 @@ fn read_directory(path: &Path) -> Result<Vec<FileNode>, String> {
             "file".to_string()
         };
         
-        let extension = if metadata.is_file() {
-            entry.path()
+        let extension = if !metadata.is_dir() {
+            entry_path
                 .extension()
                 .and_then(|ext| ext.to_str())
                 .map(|s| s.to_string())
@@ -115,6 +179,17 @@ fn read_directory(path: &Path) -> Result<Vec<FileNode>, String> {
         } else {
             None
         };
+
+        let symlink_target = if is_symlink {
+            fs::read_link(&entry_path).ok().map(|t| t.to_string_lossy().to_string())
+        } else {
+            None
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64);
         
         nodes.push(FileNode {
             name,
@@ -122,6 +197,12 @@ fn read_directory(path: &Path) -> Result<Vec<FileNode>, String> {
             node_type,
             extension,
             children,
+            is_symlink,
+            symlink_target,
+            broken_link,
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified,
+            permissions: permissions::describe(&metadata),
         });
     }
     
@@ -146,6 +227,89 @@ pub async fn get_home_directory() -> Result<String, String> {
 
 #[tauri::command]
 pub async fn rename_file(old_path: String, new_path: String) -> Result<(), String> {
-    fs::rename(&old_path, &new_path)
-        .map_err(|e| format!("Failed to rename file: {}", e))
+    blocking::run(None, move |_| {
+        fs::rename(&old_path, &new_path)
+            .map_err(|e| format!("Failed to rename file: {}", e))
+    })
+    .await
+}
+
+/// Detect the language of a file from its name, extension, shebang or
+/// content; pass `content` for unsaved buffers. None when unrecognized.
+#[tauri::command]
+pub async fn detect_language(path: String, content: Option<String>) -> Result<Option<Detection>, String> {
+    let path = PathBuf::from(path);
+    blocking::run(None, move |_| {
+        Ok(match content {
+            Some(content) => langdetect::detect(&path, Some(&content)),
+            None => langdetect::detect_file(&path),
+        })
+    })
+    .await
+}
+
+/// Keep the unsaved contents of a buffer for crash recovery; the frontend
+/// calls this on a timer while a buffer is modified
+#[tauri::command]
+pub async fn save_draft(path: String, content: String) -> Result<(), String> {
+    blocking::run(None, move |_| drafts::save(&path, content)).await
+}
+
+/// Drafts left behind by a previous session, most recent first
+#[tauri::command]
+pub async fn list_drafts() -> Result<Vec<DraftInfo>, String> {
+    blocking::run(None, |_| drafts::list()).await
+}
+
+#[tauri::command]
+pub async fn recover_draft(path: String) -> Result<Draft, String> {
+    blocking::run(None, move |_| drafts::recover(&path)).await
+}
+
+#[tauri::command]
+pub async fn discard_draft(path: String) -> Result<bool, String> {
+    blocking::run(None, move |_| drafts::discard(&path)).await
+}
+
+/// Built-in file templates and those under ~/.ctr/templates
+#[tauri::command]
+pub async fn list_file_templates() -> Result<Vec<FileTemplateInfo>, String> {
+    blocking::run(None, |_| templates::list()).await
+}
+
+/// Create `dest_path` from a template, filling its `{{name}}` placeholders
+/// from `variables` and the template's defaults
+#[tauri::command]
+pub async fn create_from_template(
+    template_id: String,
+    dest_path: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<CreatedFile, String> {
+    blocking::run(None, move |_| {
+        templates::create_from_template(&template_id, &dest_path, variables.unwrap_or_default())
+    })
+    .await
+}
+
+/// Run create/copy/move/delete operations as one unit, rolling back the
+/// completed ones if any fails. Emits `batch-progress` after each step.
+#[tauri::command]
+pub async fn batch_operation(
+    app_handle: AppHandle,
+    operations: Vec<FileOperation>,
+    batch_id: Option<String>,
+) -> Result<BatchResult, String> {
+    let batch_id = batch_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    blocking::run(None, move |_| {
+        batch::run(&batch_id, &operations, |progress| {
+            let _ = app_handle.emit("batch-progress", progress);
+        })
+    })
+    .await
+}
+
+/// chmod-style permission change: octal ("755") or symbolic ("+x", "go-w")
+#[tauri::command]
+pub async fn set_permissions(path: String, mode: String) -> Result<PermissionInfo, String> {
+    blocking::run(None, move |_| permissions::set(Path::new(&path), &mode)).await
 }
@@ -0,0 +1,49 @@
+use tauri::WebviewWindow;
+
+use crate::services::evidence::{self, CaptureRegion, EvidenceItem};
+
+async fn capture_blocking(region: Option<CaptureRegion>, label: String) -> Result<EvidenceItem, String> {
+    tokio::task::spawn_blocking(move || evidence::capture(region, &label))
+        .await
+        .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+/// Capture the IDE window as it currently appears on screen
+#[tauri::command]
+pub async fn capture_window_evidence(window: WebviewWindow, label: String) -> Result<EvidenceItem, String> {
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?;
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    let region = CaptureRegion {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+    capture_blocking(Some(region), label).await
+}
+
+/// Capture a screen region, or the whole screen when `region` is omitted
+#[tauri::command]
+pub async fn capture_region_evidence(region: Option<CaptureRegion>, label: String) -> Result<EvidenceItem, String> {
+    capture_blocking(region, label).await
+}
+
+#[tauri::command]
+pub async fn list_evidence() -> Result<Vec<EvidenceItem>, String> {
+    Ok(evidence::list())
+}
+
+/// Check that an evidence file still matches the hash recorded at capture time
+#[tauri::command]
+pub async fn verify_evidence(evidence_id: String) -> Result<bool, String> {
+    evidence::verify(&evidence_id)
+}
+
+#[tauri::command]
+pub async fn delete_evidence(evidence_id: String) -> Result<(), String> {
+    evidence::delete(&evidence_id)
+}
@@ -1,18 +1,32 @@
-use tauri::command;
+use tauri::{command, AppHandle};
 use crate::services::exploit_sandbox::{
     get_exploit_templates, simulate_exploit, ExploitPayload, AttackResult
 };
+use crate::services::crypto_tools::evasion::{self, EvasionOptions, EvasionVariant};
+use crate::services::crypto_tools::{transform_str, Operation};
+use crate::services::listeners::{self, ReverseShellPayload};
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::payload_verifier::{self, VerificationResult};
 
 #[derive(serde::Serialize)]
 pub struct ExploitPayloadResponse {
     pub payloads: Vec<ExploitPayload>,
+    /// Evasion variants per payload, parallel to `payloads` (empty without options)
+    pub variants: Vec<Vec<EvasionVariant>>,
 }
 
 #[command]
-pub fn get_exploit_payloads() -> ExploitPayloadResponse {
-    ExploitPayloadResponse {
-        payloads: get_exploit_templates()
-    }
+pub fn get_exploit_payloads(evasion: Option<EvasionOptions>) -> ExploitPayloadResponse {
+    let payloads = get_exploit_templates();
+    let variants = match &evasion {
+        Some(options) => payloads
+            .iter()
+            .map(|p| evasion::variants(&p.payload, &p.attack_type, options))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    ExploitPayloadResponse { payloads, variants }
 }
 
 #[command]
@@ -55,3 +69,77 @@ pub fn run_exploit_with_custom_payload(
     
     Ok(simulate_exploit(&code, &custom_payload))
 }
+
+#[command]
+pub fn encode_exploit_payload(payload_index: usize, recipe: Vec<Operation>) -> Result<String, String> {
+    let payloads = get_exploit_templates();
+
+    let payload = payloads
+        .get(payload_index)
+        .ok_or_else(|| format!("Invalid payload index: {}", payload_index))?;
+
+    transform_str(&payload.payload, &recipe)
+}
+
+/// Reverse shell one-liners; host/port default to the given listener's address
+#[command]
+pub fn get_reverse_shell_payloads(
+    listener_id: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+) -> Result<Vec<ReverseShellPayload>, String> {
+    let listener = match listener_id {
+        Some(id) => Some(listeners::get_listener(&id).ok_or_else(|| format!("Listener {} not found", id))?),
+        None => None,
+    };
+
+    let host = host
+        .or_else(|| {
+            listener
+                .as_ref()
+                .map(|l| l.host.clone())
+                .filter(|h| h != "0.0.0.0" && h != "::")
+        })
+        .unwrap_or_else(listeners::local_ip);
+    let port = port
+        .or(listener.map(|l| l.port))
+        .ok_or("A port or listener is required")?;
+
+    Ok(listeners::reverse_shell_payloads(&host, port))
+}
+
+/// Send a payload to an allowlisted lab target and check for real success signals
+#[command]
+pub async fn verify_payload(
+    app_handle: AppHandle,
+    target_url: String,
+    parameter: String,
+    payload: String,
+    method: Option<String>,
+) -> Result<VerificationResult, String> {
+    let result = payload_verifier::verify(&target_url, &parameter, &payload, method.as_deref().unwrap_or("GET")).await?;
+    if result.verified {
+        notifications::notify_or_warn(
+            &app_handle,
+            NotificationKind::ExploitVerified,
+            "Exploit verified",
+            &format!("Payload in {} succeeded against {}", parameter, target_url),
+        );
+    }
+    Ok(result)
+}
+
+#[command]
+pub fn get_payload_verifications() -> Vec<VerificationResult> {
+    payload_verifier::history()
+}
+
+#[command]
+pub fn get_lab_targets() -> Vec<String> {
+    payload_verifier::lab_targets()
+}
+
+#[command]
+pub fn set_lab_targets(targets: Vec<String>) -> Result<(), String> {
+    payload_verifier::set_lab_targets(targets)
+}
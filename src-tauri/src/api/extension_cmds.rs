@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::utils::time::now_millis;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io::{Write, Read};
 
+use crate::services::extensions::grammars::{self, Grammar, LanguageContribution, LanguageSupport};
+use crate::services::extensions::themes::{self, ThemeData, ThemeInfo};
+
 // Open VSX API response types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenVSXSearchResponse {
@@ -26,12 +32,16 @@ pub struct OpenVSXExtension {
     #[serde(default)]
     pub categories: Vec<String>,
     pub files: Option<OpenVSXFiles>,
+    #[serde(default)]
+    pub verified: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenVSXFiles {
     pub download: Option<String>,
     pub icon: Option<String>,
+    /// URL of a text file holding the .vsix SHA-256
+    pub sha256: Option<String>,
 }
 
 // Simplified extension for frontend
@@ -48,6 +58,41 @@ pub struct MarketplaceExtension {
     pub rating: f64,
     pub icon: Option<String>,
     pub download_url: Option<String>,
+    /// Publisher namespace is verified on Open VSX
+    #[serde(default)]
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketplaceSort {
+    #[default]
+    Relevance,
+    Downloads,
+    Rating,
+    Updated,
+}
+
+// Paging, filters and sorting for marketplace searches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketplaceQuery {
+    pub offset: Option<u32>,
+    /// Page size, at most 100
+    pub size: Option<u32>,
+    pub category: Option<String>,
+    /// e.g. linux-x64, darwin-arm64, win32-x64, universal
+    pub target_platform: Option<String>,
+    pub sort_by: Option<MarketplaceSort>,
+    /// Defaults to descending
+    #[serde(default)]
+    pub ascending: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketplacePage {
+    pub extensions: Vec<MarketplaceExtension>,
+    pub offset: i32,
+    pub total: i32,
 }
 
 // Local extension info (from manifest.json)
@@ -76,6 +121,72 @@ pub struct InstalledExtension {
     pub path: String,
     pub categories: Vec<String>,
     pub icon: Option<String>,
+    /// Version the extension is held at; pinned extensions are skipped by update checks
+    #[serde(default)]
+    pub pinned_version: Option<String>,
+    /// SHA-256 of the downloaded .vsix, for marketplace installs
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+// Hashes recorded at install time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityRecord {
+    pub vsix_sha256: String,
+    /// Whether the download matched a hash published by Open VSX
+    pub publisher_verified: bool,
+    /// Hash over the extracted files, for tamper detection
+    pub content_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub id: String,
+    pub vsix_sha256: String,
+    pub publisher_verified: bool,
+    pub expected_content_sha256: String,
+    pub actual_content_sha256: String,
+    pub intact: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionUpdate {
+    pub id: String,
+    pub installed_version: String,
+    pub latest_version: String,
+}
+
+const PROFILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    pub id: String,
+    pub version: String,
+    pub enabled: bool,
+    /// Replace an installed extension of another version with `version`;
+    /// unpinned entries keep whatever version is installed
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+// A shareable set of installed extensions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionProfile {
+    pub version: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub exported_at: u64,
+    pub extensions: Vec<ProfileEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileApplyReport {
+    pub installed: Vec<String>,
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    /// Extension id and error for entries that could not be applied
+    pub failed: Vec<(String, String)>,
 }
 
 // Get extensions directory
@@ -129,24 +240,179 @@ fn save_disabled_extensions(disabled: &[String]) -> Result<(), String> {
     Ok(())
 }
 
+// Pinned versions, keyed by extension id
+fn get_pins_file() -> Result<PathBuf, String> {
+    Ok(get_state_file()?.with_file_name("extension_pins.json"))
+}
+
+fn load_pins() -> HashMap<String, String> {
+    get_pins_file()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_pins(pins: &HashMap<String, String>) -> Result<(), String> {
+    let path = get_pins_file()?;
+    let json = serde_json::to_string_pretty(pins)
+        .map_err(|e| format!("Failed to serialize pins: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write pins file: {}", e))?;
+    Ok(())
+}
+
+fn get_integrity_file() -> Result<PathBuf, String> {
+    Ok(get_state_file()?.with_file_name("extension_integrity.json"))
+}
+
+fn load_integrity() -> HashMap<String, IntegrityRecord> {
+    get_integrity_file()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_integrity(records: &HashMap<String, IntegrityRecord>) -> Result<(), String> {
+    let path = get_integrity_file()?;
+    let json = serde_json::to_string_pretty(records)
+        .map_err(|e| format!("Failed to serialize integrity records: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write integrity file: {}", e))?;
+    Ok(())
+}
+
+// Hash of every file under `dir` (sorted relative paths and contents)
+fn hash_extension_dir(dir: &Path) -> Result<String, String> {
+    fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+    
+    let mut files = Vec::new();
+    collect(dir, &mut files)?;
+    files.sort();
+    
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative = file.strip_prefix(dir).unwrap_or(&file);
+        let content = fs::read(&file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        hasher.update(relative.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+// Hash published by Open VSX, if the release has one
+async fn fetch_published_sha256(files: Option<&OpenVSXFiles>) -> Result<Option<String>, String> {
+    let Some(url) = files.and_then(|f| f.sha256.as_ref()) else {
+        return Ok(None);
+    };
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("Failed to fetch published checksum: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch published checksum: status {}", response.status()));
+    }
+    let text = response.text()
+        .await
+        .map_err(|e| format!("Failed to read published checksum: {}", e))?;
+    // "<hex>" or "<hex>  <file name>"
+    Ok(text.split_whitespace().next().map(|h| h.to_lowercase()))
+}
+
+fn split_extension_id(id: &str) -> Result<(String, String), String> {
+    match id.split_once('.') {
+        Some((namespace, name)) if !namespace.is_empty() && !name.is_empty() => {
+            Ok((namespace.to_string(), name.to_string()))
+        }
+        _ => Err("Invalid extension ID format. Expected: namespace.name".to_string()),
+    }
+}
+
+// Numeric comparison of dotted versions; pre-release suffixes sort before the release
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    fn parts(v: &str) -> (Vec<u64>, bool) {
+        let (core, pre) = match v.split_once('-') {
+            Some((core, _)) => (core, true),
+            None => (v, false),
+        };
+        let nums = core
+            .split('.')
+            .map(|p| p.trim_start_matches('v').parse().unwrap_or(0))
+            .collect();
+        (nums, pre)
+    }
+    let (a_nums, a_pre) = parts(a);
+    let (b_nums, b_pre) = parts(b);
+    let len = a_nums.len().max(b_nums.len());
+    for i in 0..len {
+        let x = a_nums.get(i).copied().unwrap_or(0);
+        let y = b_nums.get(i).copied().unwrap_or(0);
+        if x != y {
+            return x.cmp(&y);
+        }
+    }
+    b_pre.cmp(&a_pre)
+}
+
 /// Search Open VSX marketplace
 #[tauri::command]
 pub async fn fetch_marketplace() -> Result<Vec<MarketplaceExtension>, String> {
-    search_marketplace("".to_string()).await
+    Ok(search_marketplace("".to_string(), None).await?.extensions)
 }
 
-/// Search Open VSX with query
-#[tauri::command]
-pub async fn search_marketplace(query: String) -> Result<Vec<MarketplaceExtension>, String> {
-    let search_url = if query.is_empty() {
-        "https://open-vsx.org/api/-/search?size=50&sortBy=downloadCount&sortOrder=desc".to_string()
-    } else {
-        format!(
-            "https://open-vsx.org/api/-/search?query={}&size=50&sortBy=relevance",
-            urlencoding::encode(&query)
-        )
+fn search_url(query: &str, options: &MarketplaceQuery) -> String {
+    // Without a query, relevance means nothing; most downloaded first
+    let sort = match options.sort_by {
+        Some(sort) => sort,
+        None if query.is_empty() => MarketplaceSort::Downloads,
+        None => MarketplaceSort::Relevance,
+    };
+    let sort_by = match sort {
+        MarketplaceSort::Relevance => "relevance",
+        MarketplaceSort::Downloads => "downloadCount",
+        MarketplaceSort::Rating => "averageRating",
+        MarketplaceSort::Updated => "timestamp",
     };
     
+    let mut url = format!(
+        "https://open-vsx.org/api/-/search?size={}&offset={}&sortBy={}&sortOrder={}",
+        options.size.unwrap_or(50).clamp(1, 100),
+        options.offset.unwrap_or(0),
+        sort_by,
+        if options.ascending { "asc" } else { "desc" }
+    );
+    if !query.is_empty() {
+        url.push_str(&format!("&query={}", urlencoding::encode(query)));
+    }
+    if let Some(category) = options.category.as_deref().filter(|c| !c.is_empty()) {
+        url.push_str(&format!("&category={}", urlencoding::encode(category)));
+    }
+    if let Some(platform) = options.target_platform.as_deref().filter(|p| !p.is_empty()) {
+        url.push_str(&format!("&targetPlatform={}", urlencoding::encode(platform)));
+    }
+    url
+}
+
+/// Search Open VSX with query, returning one page of results and the total count
+#[tauri::command]
+pub async fn search_marketplace(query: String, options: Option<MarketplaceQuery>) -> Result<MarketplacePage, String> {
+    let search_url = search_url(&query, &options.unwrap_or_default());
+    
     let response = reqwest::get(&search_url)
         .await
         .map_err(|e| format!("Failed to fetch from Open VSX: {}", e))?;
@@ -173,10 +439,15 @@ pub async fn search_marketplace(query: String) -> Result<Vec<MarketplaceExtensio
             rating: ext.averageRating.unwrap_or(0.0),
             icon: ext.files.and_then(|f| f.icon),
             download_url: None, // Will be fetched when installing
+            verified: ext.verified,
         })
         .collect();
     
-    Ok(extensions)
+    Ok(MarketplacePage {
+        extensions,
+        offset: search_result.offset,
+        total: search_result.totalSize,
+    })
 }
 
 /// Get extension details from Open VSX
@@ -208,33 +479,69 @@ pub async fn get_extension_details(namespace: String, name: String) -> Result<Ma
         rating: ext.averageRating.unwrap_or(0.0),
         icon: ext.files.as_ref().and_then(|f| f.icon.clone()),
         download_url: ext.files.and_then(|f| f.download),
+        verified: ext.verified,
     })
 }
 
-/// Install extension from Open VSX
+/// List published versions of an extension, newest first
 #[tauri::command]
-pub async fn install_from_marketplace(id: String) -> Result<InstalledExtension, String> {
-    // Parse namespace.name
-    let parts: Vec<&str> = id.split('.').collect();
-    if parts.len() < 2 {
-        return Err("Invalid extension ID format. Expected: namespace.name".to_string());
+pub async fn list_versions(namespace: String, name: String) -> Result<Vec<String>, String> {
+    let url = format!("https://open-vsx.org/api/{}/{}/versions?size=200", namespace, name);
+    
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Failed to fetch extension versions: {}", e))?;
+    
+    if !response.status().is_success() {
+        return Err(format!("Extension not found: {}.{}", namespace, name));
     }
-    let namespace = parts[0];
-    let name = parts[1..].join(".");
+    
+    let json: serde_json::Value = response.json()
+        .await
+        .map_err(|e| format!("Failed to parse versions: {}", e))?;
+    
+    // { "versions": { "<version>": "<url>", ... } }
+    let mut versions: Vec<String> = json.get("versions")
+        .and_then(|v| v.as_object())
+        .map(|map| map.keys().filter(|k| k.as_str() != "latest").cloned().collect())
+        .unwrap_or_default();
+    versions.sort_by(|a, b| compare_versions(b, a));
+    
+    Ok(versions)
+}
+
+/// Install extension from Open VSX. Installing a specific version pins the
+/// extension at it; installing without one clears any pin.
+#[tauri::command]
+pub async fn install_from_marketplace(id: String, version: Option<String>) -> Result<InstalledExtension, String> {
+    let (namespace, name) = split_extension_id(&id)?;
+    let namespace = namespace.as_str();
     
     // Get extension details to get download URL
-    let url = format!("https://open-vsx.org/api/{}/{}", namespace, name);
+    let url = match &version {
+        Some(v) => format!("https://open-vsx.org/api/{}/{}/{}", namespace, name, v),
+        None => format!("https://open-vsx.org/api/{}/{}", namespace, name),
+    };
     let response = reqwest::get(&url)
         .await
         .map_err(|e| format!("Failed to fetch extension: {}", e))?;
     
+    if !response.status().is_success() {
+        return Err(match &version {
+            Some(v) => format!("Version {} of {} not found", v, id),
+            None => format!("Extension not found: {}", id),
+        });
+    }
+    
     let ext: OpenVSXExtension = response.json()
         .await
         .map_err(|e| format!("Failed to parse extension: {}", e))?;
     
     let download_url = ext.files
-        .and_then(|f| f.download)
+        .as_ref()
+        .and_then(|f| f.download.clone())
         .ok_or("Extension has no download URL")?;
+    let published_sha256 = fetch_published_sha256(ext.files.as_ref()).await?;
     
     let ext_dir = get_extensions_dir()?;
     let target_dir = ext_dir.join(&id);
@@ -252,6 +559,16 @@ pub async fn install_from_marketplace(id: String) -> Result<InstalledExtension,
         .await
         .map_err(|e| format!("Failed to read download: {}", e))?;
     
+    let vsix_sha256 = hex::encode(Sha256::digest(&bytes));
+    if let Some(expected) = &published_sha256 {
+        if expected != &vsix_sha256 {
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, downloaded {}. The extension was not installed.",
+                id, expected, vsix_sha256
+            ));
+        }
+    }
+    
     // .vsix is just a zip file
     let temp_zip = ext_dir.join(format!("{}.vsix", id));
     let mut file = fs::File::create(&temp_zip)
@@ -304,6 +621,15 @@ pub async fn install_from_marketplace(id: String) -> Result<InstalledExtension,
     let manifest_path = target_dir.join("extension").join("package.json");
     let alt_manifest_path = target_dir.join("package.json");
     
+    let mut integrity = load_integrity();
+    integrity.insert(id.clone(), IntegrityRecord {
+        vsix_sha256: vsix_sha256.clone(),
+        publisher_verified: published_sha256.is_some(),
+        content_sha256: hash_extension_dir(&target_dir)?,
+    });
+    save_integrity(&integrity)?;
+    
+    let pinned_version = version;
     let (display_name, version, description, author, categories) = 
         if manifest_path.exists() {
             parse_vscode_manifest(&manifest_path)?
@@ -314,6 +640,13 @@ pub async fn install_from_marketplace(id: String) -> Result<InstalledExtension,
              ext.description.unwrap_or_default(), namespace.to_string(), vec![])
         };
     
+    let mut pins = load_pins();
+    match &pinned_version {
+        Some(v) => { pins.insert(id.clone(), v.clone()); }
+        None => { pins.remove(&id); }
+    }
+    save_pins(&pins)?;
+    
     Ok(InstalledExtension {
         id: id.clone(),
         name: name.clone(),
@@ -325,6 +658,8 @@ pub async fn install_from_marketplace(id: String) -> Result<InstalledExtension,
         path: target_dir.to_string_lossy().to_string(),
         categories,
         icon: None,
+        pinned_version,
+        sha256: Some(vsix_sha256),
     })
 }
 
@@ -370,6 +705,8 @@ fn parse_vscode_manifest(path: &PathBuf) -> Result<(String, String, String, Stri
 pub async fn list_installed_extensions() -> Result<Vec<InstalledExtension>, String> {
     let ext_dir = get_extensions_dir()?;
     let disabled = load_disabled_extensions();
+    let pins = load_pins();
+    let integrity = load_integrity();
     let mut extensions = Vec::new();
     
     if let Ok(entries) = fs::read_dir(&ext_dir) {
@@ -404,6 +741,8 @@ pub async fn list_installed_extensions() -> Result<Vec<InstalledExtension>, Stri
                                 path: path.to_string_lossy().to_string(),
                                 categories,
                                 icon: None,
+                                pinned_version: pins.get(&id).cloned(),
+                                sha256: integrity.get(&id).map(|r| r.vsix_sha256.clone()),
                             });
                             found = true;
                             break;
@@ -424,6 +763,8 @@ pub async fn list_installed_extensions() -> Result<Vec<InstalledExtension>, Stri
                         path: path.to_string_lossy().to_string(),
                         categories: vec![],
                         icon: None,
+                        pinned_version: pins.get(&id).cloned(),
+                        sha256: integrity.get(&id).map(|r| r.vsix_sha256.clone()),
                     });
                 }
             }
@@ -468,5 +809,216 @@ pub async fn uninstall_extension(id: String) -> Result<(), String> {
     disabled.retain(|x| x != &id);
     save_disabled_extensions(&disabled)?;
     
+    let mut pins = load_pins();
+    if pins.remove(&id).is_some() {
+        save_pins(&pins)?;
+    }
+    
+    let mut integrity = load_integrity();
+    if integrity.remove(&id).is_some() {
+        save_integrity(&integrity)?;
+    }
+    
     Ok(())
 }
+
+/// Re-hash an installed extension's files and compare with the install-time record
+#[tauri::command]
+pub async fn verify_extension_integrity(id: String) -> Result<IntegrityReport, String> {
+    let record = load_integrity()
+        .remove(&id)
+        .ok_or_else(|| format!("No integrity record for {}; it was not installed from the marketplace", id))?;
+    let target_dir = get_extensions_dir()?.join(&id);
+    if !target_dir.is_dir() {
+        return Err(format!("Extension {} is not installed", id));
+    }
+    
+    let actual = tokio::task::spawn_blocking(move || hash_extension_dir(&target_dir))
+        .await
+        .map_err(|e| format!("Integrity check task failed: {}", e))??;
+    
+    Ok(IntegrityReport {
+        intact: actual == record.content_sha256,
+        id,
+        vsix_sha256: record.vsix_sha256,
+        publisher_verified: record.publisher_verified,
+        expected_content_sha256: record.content_sha256,
+        actual_content_sha256: actual,
+    })
+}
+
+/// Hold an installed extension at a version so update checks skip it
+#[tauri::command]
+pub async fn pin_extension(id: String, version: String) -> Result<(), String> {
+    let mut pins = load_pins();
+    pins.insert(id, version);
+    save_pins(&pins)
+}
+
+/// Remove a version pin
+#[tauri::command]
+pub async fn unpin_extension(id: String) -> Result<(), String> {
+    let mut pins = load_pins();
+    if pins.remove(&id).is_some() {
+        save_pins(&pins)?;
+    }
+    Ok(())
+}
+
+/// Compare installed marketplace extensions against the latest Open VSX
+/// release, skipping pinned extensions
+#[tauri::command]
+pub async fn check_updates() -> Result<Vec<ExtensionUpdate>, String> {
+    let mut updates = Vec::new();
+    
+    for ext in list_installed_extensions().await? {
+        if ext.pinned_version.is_some() {
+            continue;
+        }
+        let Ok((namespace, name)) = split_extension_id(&ext.id) else {
+            continue;
+        };
+        // Locally sideloaded extensions are not on the marketplace
+        let Ok(latest) = get_extension_details(namespace, name).await else {
+            continue;
+        };
+        if compare_versions(&latest.version, &ext.version) == std::cmp::Ordering::Greater {
+            updates.push(ExtensionUpdate {
+                id: ext.id,
+                installed_version: ext.version,
+                latest_version: latest.version,
+            });
+        }
+    }
+    
+    Ok(updates)
+}
+
+/// List color and file icon themes contributed by enabled extensions
+#[tauri::command]
+pub async fn list_available_themes() -> Result<Vec<ThemeInfo>, String> {
+    tokio::task::spawn_blocking(themes::list)
+        .await
+        .map_err(|e| format!("Theme listing task failed: {}", e))
+}
+
+/// Load a theme's colors, token rules and icon definitions in normalized form
+#[tauri::command]
+pub async fn get_theme_tokens(id: String) -> Result<ThemeData, String> {
+    tokio::task::spawn_blocking(move || themes::load(&id))
+        .await
+        .map_err(|e| format!("Theme loading task failed: {}", e))?
+}
+
+/// List languages contributed by enabled extensions
+#[tauri::command]
+pub async fn list_extension_languages() -> Result<Vec<LanguageContribution>, String> {
+    tokio::task::spawn_blocking(grammars::list_languages)
+        .await
+        .map_err(|e| format!("Language listing task failed: {}", e))
+}
+
+/// Grammar, scope mappings and language configuration for a contributed language
+#[tauri::command]
+pub async fn get_language_support(language_id: String) -> Result<LanguageSupport, String> {
+    tokio::task::spawn_blocking(move || grammars::language_support(&language_id))
+        .await
+        .map_err(|e| format!("Language loading task failed: {}", e))?
+}
+
+/// Grammar by TextMate scope name, for embedded languages and injections
+#[tauri::command]
+pub async fn get_grammar_by_scope(scope_name: String) -> Result<Grammar, String> {
+    tokio::task::spawn_blocking(move || grammars::grammar_for_scope(&scope_name))
+        .await
+        .map_err(|e| format!("Grammar loading task failed: {}", e))?
+}
+
+/// Write the installed extensions (ids, versions, enabled state) to a JSON profile
+#[tauri::command]
+pub async fn export_extension_profile(path: String, name: Option<String>) -> Result<ExtensionProfile, String> {
+    let extensions = list_installed_extensions()
+        .await?
+        .into_iter()
+        .map(|ext| ProfileEntry {
+            pinned: ext.pinned_version.is_some(),
+            version: ext.pinned_version.unwrap_or(ext.version),
+            id: ext.id,
+            enabled: ext.enabled,
+        })
+        .collect();
+    
+    let profile = ExtensionProfile {
+        version: PROFILE_VERSION,
+        name: name.unwrap_or_default(),
+        exported_at: now_millis(),
+        extensions,
+    };
+    let json = serde_json::to_string_pretty(&profile)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write profile: {}", e))?;
+    Ok(profile)
+}
+
+/// Apply a profile: install missing extensions, match enabled state and
+/// disable installed extensions the profile does not list
+#[tauri::command]
+pub async fn apply_extension_profile(path: String) -> Result<ProfileApplyReport, String> {
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read profile: {}", e))?;
+    let profile: ExtensionProfile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse profile: {}", e))?;
+    if profile.version > PROFILE_VERSION {
+        return Err(format!("Profile version {} is newer than this IDE supports", profile.version));
+    }
+    
+    let installed = list_installed_extensions().await?;
+    let mut report = ProfileApplyReport::default();
+    
+    for entry in &profile.extensions {
+        let current = installed.iter().find(|ext| ext.id == entry.id);
+        let needs_install = match current {
+            None => true,
+            Some(ext) => entry.pinned && ext.version != entry.version,
+        };
+        if needs_install {
+            // The version the profile recorded, so applying it reproduces the setup
+            let version = Some(entry.version.clone()).filter(|v| !v.is_empty());
+            match install_from_marketplace(entry.id.clone(), version).await {
+                Ok(_) => report.installed.push(entry.id.clone()),
+                Err(e) => {
+                    report.failed.push((entry.id.clone(), e));
+                    continue;
+                }
+            }
+        }
+        
+        // Installing can change the enabled state, so read it back
+        let was_enabled = if needs_install {
+            list_installed_extensions()
+                .await?
+                .into_iter()
+                .find(|ext| ext.id == entry.id)
+                .map_or(true, |ext| ext.enabled)
+        } else {
+            current.map_or(true, |ext| ext.enabled)
+        };
+        if entry.enabled && !was_enabled {
+            enable_extension(entry.id.clone()).await?;
+            report.enabled.push(entry.id.clone());
+        } else if !entry.enabled && was_enabled {
+            disable_extension(entry.id.clone()).await?;
+            report.disabled.push(entry.id.clone());
+        }
+    }
+    
+    for ext in installed.iter().filter(|ext| ext.enabled) {
+        if !profile.extensions.iter().any(|entry| entry.id == ext.id) {
+            disable_extension(ext.id.clone()).await?;
+            report.disabled.push(ext.id.clone());
+        }
+    }
+    
+    Ok(report)
+}
@@ -0,0 +1,40 @@
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::services::extensions::host::{self, HostEvent, HostStatus};
+
+/// Start the extension host; events arrive as `extension-host-ui`,
+/// `extension-host-log` and `extension-host-exit`
+#[tauri::command]
+pub async fn start_extension_host(app_handle: AppHandle, workspace_root: Option<String>) -> Result<HostStatus, String> {
+    host::start(workspace_root, move |event| {
+        let name = match &event {
+            HostEvent::Ui { .. } => "extension-host-ui",
+            HostEvent::Log { .. } => "extension-host-log",
+            HostEvent::Exited { .. } => "extension-host-exit",
+        };
+        let _ = app_handle.emit(name, &event);
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn stop_extension_host() -> Result<(), String> {
+    host::stop()
+}
+
+#[tauri::command]
+pub async fn get_extension_host_status() -> Result<HostStatus, String> {
+    Ok(host::status())
+}
+
+#[tauri::command]
+pub async fn activate_hosted_extension(id: String) -> Result<HostStatus, String> {
+    host::activate_extension(&id).await
+}
+
+/// Execute a command registered (or contributed) by an extension
+#[tauri::command]
+pub async fn execute_extension_command(command: String, args: Option<Vec<Value>>) -> Result<Value, String> {
+    host::execute_command(&command, args.unwrap_or_default()).await
+}
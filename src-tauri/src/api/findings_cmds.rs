@@ -0,0 +1,23 @@
+use crate::services::findings::{self, Finding, FindingQuery, FindingStatus, FindingsSummary};
+
+/// Stored findings of every engine; `query` narrows them by source, status,
+/// severity or file
+#[tauri::command]
+pub async fn list_findings(workspace_root: String, query: Option<FindingQuery>) -> Result<Vec<Finding>, String> {
+    findings::list(&workspace_root, &query.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn set_finding_status(
+    workspace_root: String,
+    finding_fingerprint: String,
+    status: FindingStatus,
+    note: Option<String>,
+) -> Result<Finding, String> {
+    findings::set_status(&workspace_root, &finding_fingerprint, status, note)
+}
+
+#[tauri::command]
+pub async fn get_findings_summary(workspace_root: String) -> Result<FindingsSummary, String> {
+    findings::summary(&workspace_root)
+}
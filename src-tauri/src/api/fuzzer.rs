@@ -0,0 +1,443 @@
+//! HTTP Fuzzer
+//!
+//! Intruder-style engine: insertion points are marked in a request template with
+//! `§` pairs (e.g. `/item?id=§1§`). Each payload is substituted in, requests are
+//! fired with bounded concurrency and every result is streamed as a `fuzz-result`
+//! event, annotated with how it differs from a baseline request.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+
+use crate::services::http_client::{self, HttpRequestSpec};
+use crate::services::crypto_tools::{transform_str, Operation};
+use crate::services::exploit_sandbox::get_exploit_templates;
+
+const MARKER: char = '§';
+const DEFAULT_CONCURRENCY: usize = 10;
+const MAX_CONCURRENCY: usize = 100;
+/// Upper bound on the payloads a number range may generate
+const MAX_RANGE_PAYLOADS: i128 = 100_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PayloadSource {
+    /// Inline list of payloads
+    List { items: Vec<String> },
+    /// One payload per line of a wordlist file
+    Wordlist { path: String },
+    /// Payloads from the exploit sandbox templates, optionally filtered by attack type
+    Exploit { attack_type: Option<String> },
+    /// Numbers from `start` to `end` inclusive, zero-padded to `pad` digits
+    NumberRange {
+        start: i64,
+        end: i64,
+        step: Option<i64>,
+        pad: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FuzzMode {
+    /// Each insertion point in turn, others keep their original value
+    #[default]
+    Sniper,
+    /// The same payload in every insertion point at once
+    BatteringRam,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzConfig {
+    pub request: HttpRequestSpec,
+    pub payloads: PayloadSource,
+    #[serde(default)]
+    pub mode: FuzzMode,
+    pub concurrency: Option<usize>,
+    /// Delay applied by each worker before sending, for rate limiting
+    pub delay_ms: Option<u64>,
+    /// Encoding chain applied to every payload before it is inserted
+    #[serde(default)]
+    pub processing: Vec<Operation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FuzzResult {
+    pub index: usize,
+    pub payload: String,
+    /// Insertion point the payload went into (None in battering ram mode)
+    pub position: Option<usize>,
+    pub status: Option<u16>,
+    pub length: usize,
+    pub time_ms: u128,
+    pub error: Option<String>,
+    pub status_changed: bool,
+    pub length_delta: i64,
+    /// Took more than three times as long as the baseline
+    pub slow: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FuzzBaseline {
+    pub status: Option<u16>,
+    pub length: usize,
+    pub time_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FuzzResultEvent {
+    job_id: String,
+    result: FuzzResult,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FuzzCompleteEvent {
+    job_id: String,
+    sent: usize,
+    total: usize,
+    cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FuzzJobInfo {
+    pub job_id: String,
+    pub total: usize,
+    pub baseline: FuzzBaseline,
+}
+
+struct FuzzJob {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+lazy_static::lazy_static! {
+    static ref FUZZ_JOBS: Arc<Mutex<HashMap<String, Arc<FuzzJob>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+fn load_payloads(source: &PayloadSource) -> Result<Vec<String>, String> {
+    match source {
+        PayloadSource::List { items } => Ok(items.clone()),
+        PayloadSource::Wordlist { path } => {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read wordlist: {}", e))?;
+            Ok(content
+                .lines()
+                .map(|l| l.trim_end_matches('\r'))
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect())
+        }
+        PayloadSource::Exploit { attack_type } => {
+            let wanted = attack_type.as_ref().map(|t| t.to_lowercase());
+            Ok(get_exploit_templates()
+                .into_iter()
+                .filter(|p| {
+                    wanted
+                        .as_ref()
+                        .map_or(true, |t| format!("{:?}", p.attack_type).to_lowercase() == *t)
+                })
+                .map(|p| p.payload)
+                .collect())
+        }
+        PayloadSource::NumberRange {
+            start,
+            end,
+            step,
+            pad,
+        } => {
+            let step = step.unwrap_or(1);
+            if step == 0 || (step > 0 && start > end) || (step < 0 && start < end) {
+                return Err("Invalid number range".to_string());
+            }
+            let count = (*end as i128 - *start as i128) / step as i128 + 1;
+            if count > MAX_RANGE_PAYLOADS {
+                return Err(format!(
+                    "Number range yields {} payloads; at most {} are allowed",
+                    count, MAX_RANGE_PAYLOADS
+                ));
+            }
+            let width = pad.unwrap_or(0);
+            let mut values = Vec::with_capacity(count as usize);
+            let mut n = *start;
+            while (step > 0 && n <= *end) || (step < 0 && n >= *end) {
+                values.push(format!("{:0width$}", n, width = width));
+                // Stepping past i64's range ends the sequence
+                match n.checked_add(step) {
+                    Some(next) => n = next,
+                    None => break,
+                }
+            }
+            Ok(values)
+        }
+    }
+}
+
+/// Rewrite every `§...§` region in `text`, numbering points across calls via `counter`
+fn fill_points<F>(text: &str, counter: &mut usize, fill: &F) -> String
+where
+    F: Fn(usize, &str) -> String,
+{
+    let mut result = String::new();
+    for (i, part) in text.split(MARKER).enumerate() {
+        if i % 2 == 1 {
+            result.push_str(&fill(*counter, part));
+            *counter += 1;
+        } else {
+            result.push_str(part);
+        }
+    }
+    result
+}
+
+/// Produce a concrete request by filling insertion points in url, header values and body
+fn render<F>(template: &HttpRequestSpec, fill: F) -> HttpRequestSpec
+where
+    F: Fn(usize, &str) -> String,
+{
+    let mut counter = 0;
+    let mut spec = template.clone();
+    spec.url = fill_points(&template.url, &mut counter, &fill);
+    for header in spec.headers.iter_mut() {
+        header.value = fill_points(&header.value, &mut counter, &fill);
+    }
+    if !template.body_base64 {
+        spec.body = template
+            .body
+            .as_ref()
+            .map(|b| fill_points(b, &mut counter, &fill));
+    }
+    spec
+}
+
+fn count_points(template: &HttpRequestSpec) -> usize {
+    let count = std::cell::Cell::new(0);
+    render(template, |i, original| {
+        count.set(i + 1);
+        original.to_string()
+    });
+    count.get()
+}
+
+#[tauri::command]
+pub async fn start_fuzz(app_handle: AppHandle, config: FuzzConfig) -> Result<FuzzJobInfo, String> {
+    let points = count_points(&config.request);
+    if points == 0 {
+        return Err(format!("No insertion points found; wrap values in {}...{}", MARKER, MARKER));
+    }
+
+    let payloads = load_payloads(&config.payloads)?
+        .iter()
+        .map(|p| transform_str(p, &config.processing))
+        .collect::<Result<Vec<_>, _>>()?;
+    if payloads.is_empty() {
+        return Err("Payload set is empty".to_string());
+    }
+
+    // Build the full work list up front: (payload, position)
+    let work: Vec<(String, Option<usize>)> = match config.mode {
+        FuzzMode::Sniper => (0..points)
+            .flat_map(|pos| payloads.iter().map(move |p| (p.clone(), Some(pos))))
+            .collect(),
+        FuzzMode::BatteringRam => payloads.into_iter().map(|p| (p, None)).collect(),
+    };
+    let total = work.len();
+
+    let baseline_spec = render(&config.request, |_, original| original.to_string());
+    let baseline = match http_client::send(&baseline_spec).await {
+        Ok(resp) => FuzzBaseline {
+            status: Some(resp.status),
+            length: resp.size_bytes,
+            time_ms: resp.time_ms,
+        },
+        Err(_) => FuzzBaseline {
+            status: None,
+            length: 0,
+            time_ms: 0,
+        },
+    };
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let job = Arc::new(FuzzJob {
+        paused: AtomicBool::new(false),
+        cancelled: AtomicBool::new(false),
+    });
+    FUZZ_JOBS.lock().unwrap().insert(job_id.clone(), job.clone());
+
+    let concurrency = config
+        .concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY);
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let template = Arc::new(config.request);
+    let delay = config.delay_ms.map(Duration::from_millis);
+    let sent = Arc::new(AtomicUsize::new(0));
+
+    let info = FuzzJobInfo {
+        job_id: job_id.clone(),
+        total,
+        baseline: baseline.clone(),
+    };
+
+    tokio::spawn(async move {
+        let mut handles = Vec::with_capacity(total);
+
+        for (index, (payload, position)) in work.into_iter().enumerate() {
+            while job.paused.load(Ordering::SeqCst) && !job.cancelled.load(Ordering::SeqCst) {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            if job.cancelled.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let permit = match semaphore.clone().acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => break,
+            };
+
+            let template = template.clone();
+            let baseline = baseline.clone();
+            let app_handle = app_handle.clone();
+            let job_id = job_id.clone();
+            let sent = sent.clone();
+
+            handles.push(tokio::spawn(async move {
+                let _permit = permit;
+                if let Some(delay) = delay {
+                    tokio::time::sleep(delay).await;
+                }
+
+                let spec = render(&template, |i, original| match position {
+                    Some(pos) if pos != i => original.to_string(),
+                    _ => payload.clone(),
+                });
+
+                let result = match http_client::send(&spec).await {
+                    Ok(resp) => FuzzResult {
+                        index,
+                        payload,
+                        position,
+                        status: Some(resp.status),
+                        length: resp.size_bytes,
+                        time_ms: resp.time_ms,
+                        error: None,
+                        status_changed: baseline.status != Some(resp.status),
+                        length_delta: resp.size_bytes as i64 - baseline.length as i64,
+                        slow: baseline.time_ms > 0 && resp.time_ms > baseline.time_ms * 3,
+                    },
+                    Err(e) => FuzzResult {
+                        index,
+                        payload,
+                        position,
+                        status: None,
+                        length: 0,
+                        time_ms: 0,
+                        error: Some(e),
+                        status_changed: baseline.status.is_some(),
+                        length_delta: -(baseline.length as i64),
+                        slow: false,
+                    },
+                };
+
+                sent.fetch_add(1, Ordering::SeqCst);
+                let _ = app_handle.emit("fuzz-result", FuzzResultEvent { job_id, result });
+            }));
+        }
+
+        for handle in handles {
+            let _ = handle.await;
+        }
+
+        let cancelled = job.cancelled.load(Ordering::SeqCst);
+        FUZZ_JOBS.lock().unwrap().remove(&job_id);
+        let _ = app_handle.emit(
+            "fuzz-complete",
+            FuzzCompleteEvent {
+                job_id,
+                sent: sent.load(Ordering::SeqCst),
+                total,
+                cancelled,
+            },
+        );
+    });
+
+    Ok(info)
+}
+
+fn get_job(job_id: &str) -> Result<Arc<FuzzJob>, String> {
+    FUZZ_JOBS
+        .lock()
+        .unwrap()
+        .get(job_id)
+        .cloned()
+        .ok_or_else(|| format!("Fuzz job {} not found", job_id))
+}
+
+#[tauri::command]
+pub async fn pause_fuzz(job_id: String) -> Result<(), String> {
+    get_job(&job_id)?.paused.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resume_fuzz(job_id: String) -> Result<(), String> {
+    get_job(&job_id)?.paused.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_fuzz(job_id: String) -> Result<(), String> {
+    get_job(&job_id)?.cancelled.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: i64, end: i64, step: Option<i64>, pad: Option<usize>) -> Result<Vec<String>, String> {
+        load_payloads(&PayloadSource::NumberRange { start, end, step, pad })
+    }
+
+    #[test]
+    fn test_number_range() {
+        assert_eq!(range(1, 5, None, None).unwrap(), ["1", "2", "3", "4", "5"]);
+        assert_eq!(range(0, 10, Some(4), None).unwrap(), ["0", "4", "8"]);
+        assert_eq!(range(10, 0, Some(-5), None).unwrap(), ["10", "5", "0"]);
+        assert_eq!(range(7, 7, None, None).unwrap(), ["7"]);
+        // Stepping past i64::MAX ends the range instead of wrapping
+        assert_eq!(range(i64::MAX - 1, i64::MAX, None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_number_range_padding() {
+        assert_eq!(range(8, 11, None, Some(3)).unwrap(), ["008", "009", "010", "011"]);
+        // Padding never truncates wider numbers
+        assert_eq!(range(99, 100, None, Some(2)).unwrap(), ["99", "100"]);
+        assert_eq!(range(-1, 0, None, Some(3)).unwrap(), ["-01", "000"]);
+    }
+
+    #[test]
+    fn test_invalid_number_range() {
+        assert!(range(1, 5, Some(0), None).is_err());
+        assert!(range(5, 1, None, None).is_err());
+        assert!(range(1, 5, Some(-1), None).is_err());
+        assert_eq!(range(1, 100_000, None, None).unwrap().len(), 100_000);
+        assert!(range(0, 100_000, None, None).is_err());
+        assert!(range(i64::MIN, i64::MAX, None, None).is_err());
+    }
+
+    #[test]
+    fn test_fill_points() {
+        let mut counter = 0;
+        let fill = |i: usize, original: &str| format!("{}:{}", i, original);
+        assert_eq!(fill_points("/a/§x§/b?q=§y§", &mut counter, &fill), "/a/0:x/b?q=1:y");
+        // Numbering continues across the url, headers and body
+        assert_eq!(fill_points("§z§", &mut counter, &fill), "2:z");
+        assert_eq!(fill_points("no markers", &mut counter, &fill), "no markers");
+        assert_eq!(counter, 3);
+    }
+}
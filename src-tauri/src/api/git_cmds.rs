@@ -1,7 +1,16 @@
 // Git commands implementation using git2 crate
 use git2::{Repository, StatusOptions, IndexAddOption};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use crate::services::project::clone::{self, CloneOptions};
+use crate::services::project::conflicts::{self, ConflictVersions};
+use crate::services::project::signing::{self, CommitSignature, SigningConfig};
+use crate::services::project::submodules::{self, SubmoduleInfo, SubmoduleUpdateResult};
+use crate::services::project::worktrees::{self, WorktreeInfo};
+use crate::services::project::vcs::{self, RebaseCommit, RebaseStatus, RebaseStep};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitStatus {
@@ -22,6 +31,9 @@ pub struct GitCommitInfo {
     pub message: String,
     pub author: String,
     pub timestamp: i64,
+    /// None for unsigned commits
+    #[serde(default)]
+    pub signature: Option<CommitSignature>,
 }
 
 /// Get the git status for a repository
@@ -100,9 +112,88 @@ pub async fn git_status(repo_path: String) -> Result<GitStatus, String> {
     })
 }
 
-/// Commit staged changes
+/// Status of each changed file, for explorer decorations: repo-relative path
+/// -> "conflicted", "added", "modified", "deleted", "untracked" or "ignored".
+/// `paths` (absolute or repo-relative files or directories) limits the scan
+/// to the part of the tree being refreshed. Unchanged files are left out and
+/// an ignored directory is reported once rather than per file.
+#[tauri::command]
+pub async fn git_file_statuses(
+    repo_path: String,
+    paths: Option<Vec<String>>,
+) -> Result<HashMap<String, String>, String> {
+    tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        let workdir = repo.workdir().ok_or("Repository has no working directory")?.to_path_buf();
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(true)
+            .recurse_ignored_dirs(false)
+            .exclude_submodules(true);
+        let requested = paths.as_ref().is_some_and(|p| !p.is_empty());
+        let specs: Vec<String> = paths
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|path| {
+                let path = Path::new(path);
+                let relative = path.strip_prefix(&workdir).unwrap_or(path);
+                // Paths outside the repository have no status
+                (!relative.is_absolute()).then(|| relative.to_string_lossy().replace('\\', "/"))
+            })
+            .collect();
+        if requested && specs.is_empty() {
+            return Ok(HashMap::new());
+        }
+        // Asking for the root is the same as asking for everything
+        if !specs.iter().any(|spec| spec.is_empty()) {
+            for spec in specs {
+                opts.pathspec(spec);
+            }
+        }
+
+        let statuses = repo.statuses(Some(&mut opts))
+            .map_err(|e| format!("Failed to get statuses: {}", e))?;
+        Ok(statuses
+            .iter()
+            .filter_map(|entry| {
+                let status = entry.status();
+                let label = if status.is_conflicted() {
+                    "conflicted"
+                } else if status.is_ignored() {
+                    "ignored"
+                } else if status.is_wt_new() {
+                    "untracked"
+                } else if status.is_index_new() || status.is_index_renamed() {
+                    "added"
+                } else if status.is_wt_deleted() || status.is_index_deleted() {
+                    "deleted"
+                } else if status.intersects(
+                    git2::Status::WT_MODIFIED
+                        | git2::Status::INDEX_MODIFIED
+                        | git2::Status::WT_TYPECHANGE
+                        | git2::Status::INDEX_TYPECHANGE
+                        | git2::Status::WT_RENAMED,
+                ) {
+                    "modified"
+                } else {
+                    return None;
+                };
+                let path = entry.path()?.trim_end_matches('/').to_string();
+                Some((path, label.to_string()))
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Commit staged changes. `sign` overrides commit.gpgsign; signing uses the
+/// key and format configured for git.
 #[tauri::command]
-pub async fn git_commit(repo_path: String, message: String) -> Result<String, String> {
+pub async fn git_commit(repo_path: String, message: String, sign: Option<bool>) -> Result<String, String> {
     let repo = Repository::open(&repo_path)
         .map_err(|e| format!("Failed to open repository: {}", e))?;
     
@@ -131,6 +222,15 @@ pub async fn git_commit(repo_path: String, message: String) -> Result<String, St
         vec![]
     };
     
+    let sign = match sign {
+        Some(sign) => sign,
+        None => signing::signing_config(&repo)?.sign_by_default,
+    };
+    if sign {
+        let oid = signing::commit_signed(&repo, &sig, &message, &tree, &parents)?;
+        return Ok(oid.to_string());
+    }
+    
     let oid = repo.commit(
         Some("HEAD"),
         &sig,
@@ -285,9 +385,24 @@ pub async fn git_checkout_branch(repo_path: String, branch_name: String) -> Resu
     Ok(())
 }
 
-/// Get commit history
+/// Get commit history. With `verify_signatures` the signatures are checked
+/// by git; otherwise signed commits are reported as unverified.
 #[tauri::command]
-pub async fn git_log(repo_path: String, limit: Option<usize>) -> Result<Vec<GitCommitInfo>, String> {
+pub async fn git_log(
+    repo_path: String,
+    limit: Option<usize>,
+    verify_signatures: Option<bool>,
+) -> Result<Vec<GitCommitInfo>, String> {
+    let limit = limit.unwrap_or(50);
+    let mut verified = if verify_signatures.unwrap_or(false) {
+        let path = repo_path.clone();
+        tokio::task::spawn_blocking(move || signing::verify_log(&path, limit))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??
+    } else {
+        Default::default()
+    };
+    
     let repo = Repository::open(&repo_path)
         .map_err(|e| format!("Failed to open repository: {}", e))?;
     
@@ -296,7 +411,6 @@ pub async fn git_log(repo_path: String, limit: Option<usize>) -> Result<Vec<GitC
     revwalk.push_head()
         .map_err(|e| format!("Failed to push HEAD: {}", e))?;
     
-    let limit = limit.unwrap_or(50);
     let mut commits = Vec::new();
     
     for (i, oid) in revwalk.enumerate() {
@@ -313,12 +427,27 @@ pub async fn git_log(repo_path: String, limit: Option<usize>) -> Result<Vec<GitC
             message: commit.message().unwrap_or("").to_string(),
             author: commit.author().name().unwrap_or("").to_string(),
             timestamp: commit.time().seconds(),
+            signature: verified.remove(&oid.to_string()).or_else(|| {
+                signing::is_signed(&repo, oid).then(|| CommitSignature {
+                    status: "unverified".to_string(),
+                    signer: None,
+                    key: None,
+                })
+            }),
         });
     }
     
     Ok(commits)
 }
 
+/// The signing key, format and program configured for git
+#[tauri::command]
+pub async fn git_signing_config(repo_path: String) -> Result<SigningConfig, String> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+    signing::signing_config(&repo)
+}
+
 /// Initialize a new git repository
 #[tauri::command]
 pub async fn git_init(repo_path: String) -> Result<(), String> {
@@ -327,12 +456,185 @@ pub async fn git_init(repo_path: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Clone a repository
+/// Clone a repository, optionally shallow (`depth`), of one branch and with
+/// its submodules; emits `git-clone-progress` and can be stopped with
+/// `git_cancel_clone` using `clone_id`
 #[tauri::command]
-pub async fn git_clone(url: String, dest_path: String) -> Result<(), String> {
-    Repository::clone(&url, &dest_path)
-        .map_err(|e| format!("Failed to clone repository: {}", e))?;
-    Ok(())
+#[allow(clippy::too_many_arguments)]
+pub async fn git_clone(
+    app_handle: AppHandle,
+    url: String,
+    dest_path: String,
+    depth: Option<u32>,
+    branch: Option<String>,
+    single_branch: Option<bool>,
+    recurse_submodules: Option<bool>,
+    clone_id: Option<String>,
+) -> Result<(), String> {
+    let options = CloneOptions {
+        depth,
+        branch,
+        single_branch: single_branch.unwrap_or(false),
+        recurse_submodules: recurse_submodules.unwrap_or(false),
+    };
+    let clone_id = clone_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tokio::task::spawn_blocking(move || {
+        let mut emit = |progress| {
+            let _ = app_handle.emit("git-clone-progress", progress);
+        };
+        clone::clone(&url, &dest_path, &options, &clone_id, &mut emit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stop a clone started with `clone_id`; false when it already finished
+#[tauri::command]
+pub async fn git_cancel_clone(clone_id: String) -> Result<bool, String> {
+    Ok(clone::cancel(&clone_id))
+}
+
+/// Commits a rebase onto `onto` would replay, oldest first, for building a plan
+#[tauri::command]
+pub async fn git_rebase_plan(repo_path: String, onto: String) -> Result<Vec<RebaseCommit>, String> {
+    tokio::task::spawn_blocking(move || vcs::rebase_commits(&repo_path, &onto))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Start an interactive rebase onto `onto`, applying `plan` (commits in
+/// their new order with pick/reword/squash/fixup/drop actions) if given
+#[tauri::command]
+pub async fn git_rebase_start(
+    repo_path: String,
+    onto: String,
+    plan: Option<Vec<RebaseStep>>,
+) -> Result<RebaseStatus, String> {
+    tokio::task::spawn_blocking(move || vcs::rebase_start(&repo_path, &onto, plan.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn git_rebase_status(repo_path: String) -> Result<RebaseStatus, String> {
+    vcs::rebase_status(&repo_path)
+}
+
+/// Continue a stopped rebase once the conflicts are resolved and staged
+#[tauri::command]
+pub async fn git_rebase_continue(repo_path: String) -> Result<RebaseStatus, String> {
+    tokio::task::spawn_blocking(move || vcs::rebase_continue(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn git_rebase_skip(repo_path: String) -> Result<RebaseStatus, String> {
+    tokio::task::spawn_blocking(move || vcs::rebase_skip(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn git_rebase_abort(repo_path: String) -> Result<RebaseStatus, String> {
+    tokio::task::spawn_blocking(move || vcs::rebase_abort(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Base, ours and theirs versions of a conflicted file with the conflict
+/// regions of its working-tree text, for the merge editor
+#[tauri::command]
+pub async fn git_get_conflict_versions(repo_path: String, path: String) -> Result<ConflictVersions, String> {
+    tokio::task::spawn_blocking(move || conflicts::conflict_versions(&repo_path, &path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Save the merged content of a conflicted file, staging it with
+/// `mark_resolved`
+#[tauri::command]
+pub async fn git_write_merge_resolution(
+    repo_path: String,
+    path: String,
+    content: String,
+    mark_resolved: Option<bool>,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        conflicts::write_resolution(&repo_path, &path, &content, mark_resolved.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn git_list_submodules(repo_path: String) -> Result<Vec<SubmoduleInfo>, String> {
+    tokio::task::spawn_blocking(move || submodules::list(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Initialize and update submodules (all, or those in `names`), cloning the
+/// missing ones; emits `git-submodule-progress` while fetching
+#[tauri::command]
+pub async fn git_submodule_init_update(
+    app_handle: AppHandle,
+    repo_path: String,
+    names: Option<Vec<String>>,
+    recursive: Option<bool>,
+) -> Result<SubmoduleUpdateResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut emit = |progress| {
+            let _ = app_handle.emit("git-submodule-progress", progress);
+        };
+        submodules::init_update(&repo_path, names.as_deref(), recursive.unwrap_or(true), &mut emit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Add and clone a submodule at `path`, relative to the repository root;
+/// emits `git-submodule-progress` while cloning
+#[tauri::command]
+pub async fn git_add_submodule(
+    app_handle: AppHandle,
+    repo_path: String,
+    url: String,
+    path: String,
+    branch: Option<String>,
+) -> Result<SubmoduleInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut emit = |progress| {
+            let _ = app_handle.emit("git-submodule-progress", progress);
+        };
+        submodules::add(&repo_path, &url, &path, branch.as_deref(), &mut emit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// The main working directory and the linked worktrees of a repository
+#[tauri::command]
+pub async fn git_list_worktrees(repo_path: String) -> Result<Vec<WorktreeInfo>, String> {
+    tokio::task::spawn_blocking(move || worktrees::list(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Check out `branch` in a new worktree at `path`, creating the branch if needed
+#[tauri::command]
+pub async fn git_add_worktree(repo_path: String, branch: String, path: String) -> Result<WorktreeInfo, String> {
+    tokio::task::spawn_blocking(move || worktrees::add(&repo_path, &branch, &path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Delete a worktree; `force` also removes one with uncommitted changes or a lock
+#[tauri::command]
+pub async fn git_remove_worktree(repo_path: String, name: String, force: Option<bool>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || worktrees::remove(&repo_path, &name, force.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
 }
 
 // Helper function to get ahead/behind counts
@@ -0,0 +1,86 @@
+//! HTTP Client
+//!
+//! Repeater-style request runner: send arbitrary HTTP requests and keep
+//! them in named collections stored in <workspace>/.ctr/http_collections.json.
+//! Collections can also be generated from an OpenAPI document, and
+//! imported from or exported to Postman.
+
+use crate::services::http_client::{self, HttpCollection, HttpRequestSpec, HttpResponseData};
+use crate::services::openapi::{self, OpenApiImport};
+use crate::services::postman::{self, PostmanImport};
+
+/// Send an HTTP request
+#[tauri::command]
+pub async fn send_http_request(request: HttpRequestSpec) -> Result<HttpResponseData, String> {
+    http_client::send(&request).await
+}
+
+/// List saved request collections for a workspace
+#[tauri::command]
+pub async fn list_http_collections(workspace_root: String) -> Result<Vec<HttpCollection>, String> {
+    http_client::collections(&workspace_root)
+}
+
+/// Save (or overwrite) a named request in a collection, creating the collection if needed
+#[tauri::command]
+pub async fn save_http_request(
+    workspace_root: String,
+    collection: String,
+    name: String,
+    request: HttpRequestSpec,
+) -> Result<(), String> {
+    http_client::save_request(&workspace_root, collection, name, request)
+}
+
+/// Delete a saved request from a collection
+#[tauri::command]
+pub async fn delete_http_request(
+    workspace_root: String,
+    collection: String,
+    name: String,
+) -> Result<(), String> {
+    http_client::delete_request(&workspace_root, &collection, &name)
+}
+
+/// Delete a whole collection
+#[tauri::command]
+pub async fn delete_http_collection(workspace_root: String, collection: String) -> Result<(), String> {
+    http_client::delete_collection(&workspace_root, &collection)
+}
+
+/// Map the attack surface of an OpenAPI/Swagger document: its endpoints
+/// become a collection of ready-to-send requests (replacing one of the same
+/// name) and risky endpoints are recorded as findings against the file.
+/// `base_url` is used when the document's servers are missing or relative.
+#[tauri::command]
+pub async fn import_openapi_spec(
+    workspace_root: String,
+    path: String,
+    collection: Option<String>,
+    base_url: Option<String>,
+) -> Result<OpenApiImport, String> {
+    openapi::import_file(&workspace_root, &path, collection, base_url.as_deref())
+}
+
+/// Import a Postman v2.1 collection, replacing a collection of the same
+/// name. Variables are resolved from the collection and, when given, a
+/// Postman environment export.
+#[tauri::command]
+pub async fn import_postman_collection(
+    workspace_root: String,
+    src_path: String,
+    environment_path: Option<String>,
+) -> Result<PostmanImport, String> {
+    postman::import_file(&workspace_root, &src_path, environment_path.as_deref())
+}
+
+/// Export a collection as a Postman v2.1 collection file; returns the
+/// number of requests written
+#[tauri::command]
+pub async fn export_postman_collection(
+    workspace_root: String,
+    collection: String,
+    dest_path: String,
+) -> Result<usize, String> {
+    postman::export_file(&workspace_root, &collection, &dest_path)
+}
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use crate::services::findings::{self, Finding, FindingSource};
+use crate::services::intel::{self, cve, IntelConfig, IntelKeyStatus};
+use crate::services::intel::cve::{CveRecord, DependencyScan, ExploitRecord};
+use crate::services::intel::nmap::{self, NmapImport, ReconHost};
+use crate::services::intel::reputation::{FileHashes, ReputationReport};
+use crate::services::security::dependencies::{self, Dependency};
+use crate::services::security::licenses::{self, DependencyLicense};
+
+const DEFAULT_RESULT_LIMIT: usize = 50;
+
+/// Which API keys are configured, without their values
+#[tauri::command]
+pub async fn get_intel_config() -> Result<IntelKeyStatus, String> {
+    Ok(intel::key_status())
+}
+
+/// Save API keys to the keychain; keys left out are unchanged and empty
+/// ones are removed
+#[tauri::command]
+pub async fn set_intel_config(config: IntelConfig) -> Result<(), String> {
+    intel::save_config(&config)
+}
+
+/// Search NVD by keyword and/or CPE
+#[tauri::command]
+pub async fn search_cves(
+    keyword: Option<String>,
+    cpe: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<CveRecord>, String> {
+    cve::search_nvd(
+        keyword.as_deref(),
+        cpe.as_deref(),
+        limit.unwrap_or(DEFAULT_RESULT_LIMIT),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_cve_details(cve_id: String) -> Result<CveRecord, String> {
+    cve::get_cve(&cve_id).await
+}
+
+#[tauri::command]
+pub async fn search_github_advisories(
+    cve_id: Option<String>,
+    ecosystem: Option<String>,
+    affects: Option<String>,
+) -> Result<Vec<CveRecord>, String> {
+    cve::search_github_advisories(cve_id.as_deref(), ecosystem.as_deref(), affects.as_deref()).await
+}
+
+/// Search Exploit-DB by CVE id or keyword
+#[tauri::command]
+pub async fn search_exploit_db(query: String, limit: Option<usize>) -> Result<Vec<ExploitRecord>, String> {
+    cve::search_exploits(&query, limit.unwrap_or(DEFAULT_RESULT_LIMIT)).await
+}
+
+/// List dependencies declared in the workspace manifests
+#[tauri::command]
+pub async fn list_dependencies(workspace_root: String) -> Result<Vec<Dependency>, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.exists() {
+        return Err("Workspace path does not exist".into());
+    }
+    Ok(dependencies::collect_dependencies(&root))
+}
+
+/// Scan workspace dependencies and link each vulnerable one to its advisories/CVEs
+#[tauri::command]
+pub async fn scan_dependencies(workspace_root: String) -> Result<DependencyScan, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.exists() {
+        return Err("Workspace path does not exist".into());
+    }
+    let scan = cve::link_dependencies(dependencies::collect_dependencies(&root)).await?;
+    findings::record_dependency_scan(&workspace_root, &scan);
+    Ok(scan)
+}
+
+/// Resolve the license of each workspace dependency and judge it against
+/// the workspace license policy
+#[tauri::command]
+pub async fn check_dependency_licenses(workspace_root: String) -> Result<Vec<DependencyLicense>, String> {
+    let checked = licenses::check_workspace(&workspace_root).await?;
+    let found = checked.iter().filter_map(Finding::from_license).collect();
+    findings::record_or_warn(&workspace_root, FindingSource::License, None, found);
+    Ok(checked)
+}
+
+/// Import an nmap XML report into the workspace recon data
+#[tauri::command]
+pub async fn import_nmap_scan(workspace_root: String, xml_path: String) -> Result<NmapImport, String> {
+    tokio::task::spawn_blocking(move || intel::import_nmap(&workspace_root, &PathBuf::from(xml_path)))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))?
+}
+
+/// Hosts of the workspace recon data
+#[tauri::command]
+pub async fn get_recon_hosts(workspace_root: String) -> Result<Vec<ReconHost>, String> {
+    Ok(nmap::load_recon(&workspace_root)?.hosts)
+}
+
+/// VirusTotal report for an MD5/SHA-1/SHA-256 hash
+#[tauri::command]
+pub async fn vt_lookup_hash(hash: String) -> Result<ReputationReport, String> {
+    intel::lookup_hash(&hash).await
+}
+
+#[tauri::command]
+pub async fn vt_lookup_url(url: String) -> Result<ReputationReport, String> {
+    intel::lookup_url(&url).await
+}
+
+/// Hash a local file (MD5, SHA-1, SHA-256) for triage
+#[tauri::command]
+pub async fn hash_file(path: String) -> Result<FileHashes, String> {
+    let pb = PathBuf::from(&path);
+    if !pb.is_file() {
+        return Err("File does not exist".into());
+    }
+
+    tokio::task::spawn_blocking(move || intel::hash_file(&pb))
+        .await
+        .map_err(|e| format!("Hashing task failed: {}", e))?
+}
@@ -6,6 +6,10 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use tauri::{AppHandle, Emitter};
 
+use crate::services::audit;
+use crate::services::langdetect::{self, LanguageId};
+use crate::services::processes::{self, ManagedProcess, ProcessKind};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessOutput {
     pub output: String,
@@ -19,17 +23,16 @@ lazy_static::lazy_static! {
         Arc::new(Mutex::new(HashMap::new()));
 }
 
-/// Get the command to run a file based on its extension
+/// Get the command to run a file based on its language
 fn get_run_command(file_path: &str) -> Result<(String, Vec<String>), String> {
-    let extension = std::path::Path::new(file_path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .ok_or("File has no extension")?;
-
-    match extension.to_lowercase().as_str() {
-        "py" => Ok(("python".to_string(), vec![file_path.to_string()])),
-        "js" | "mjs" | "jsx" => Ok(("node".to_string(), vec![file_path.to_string()])),
-        "ts" | "tsx" => {
+    let language = langdetect::detect_file(std::path::Path::new(file_path))
+        .map(|d| d.language)
+        .ok_or_else(|| format!("Could not detect the language of {}", file_path))?;
+
+    match language {
+        LanguageId::Python => Ok(("python".to_string(), vec![file_path.to_string()])),
+        LanguageId::JavaScript => Ok(("node".to_string(), vec![file_path.to_string()])),
+        LanguageId::TypeScript => {
             // Check if ts-node is available, otherwise fall back to error
             let ts_node_check = std::process::Command::new(if cfg!(target_os = "windows") { "where" } else { "which" })
                 .arg("ts-node")
@@ -41,28 +44,28 @@ fn get_run_command(file_path: &str) -> Result<(String, Vec<String>), String> {
                 Err("TypeScript support requires ts-node. Install with: npm install -g ts-node".to_string())
             }
         }
-        "rs" => {
+        LanguageId::Rust => {
             // For Rust, we need to compile first, but for interactive mode, this is tricky
             // For now, return an error suggesting to use the regular code runner
             Err("Rust files should be run using the regular code runner (not interactive mode)".to_string())
         }
-        "c" => {
+        LanguageId::C => {
             // For C, we need to compile first
             Err("C files should be run using the regular code runner (not interactive mode)".to_string())
         }
-        "cpp" | "cc" | "cxx" => {
+        LanguageId::Cpp => {
             // For C++, we need to compile first
             Err("C++ files should be run using the regular code runner (not interactive mode)".to_string())
         }
-        "java" => {
+        LanguageId::Java => {
             // For Java, we need to compile first
             Err("Java files should be run using the regular code runner (not interactive mode)".to_string())
         }
-        "go" => Ok(("go".to_string(), vec!["run".to_string(), file_path.to_string()])),
-        "rb" => Ok(("ruby".to_string(), vec![file_path.to_string()])),
-        "php" => Ok(("php".to_string(), vec![file_path.to_string()])),
-        "sh" | "bash" => Ok(("bash".to_string(), vec![file_path.to_string()])),
-        _ => Err(format!("Unsupported file type: .{}", extension)),
+        LanguageId::Go => Ok(("go".to_string(), vec!["run".to_string(), file_path.to_string()])),
+        LanguageId::Ruby => Ok(("ruby".to_string(), vec![file_path.to_string()])),
+        LanguageId::Php => Ok(("php".to_string(), vec![file_path.to_string()])),
+        LanguageId::Shell => Ok(("bash".to_string(), vec![file_path.to_string()])),
+        other => Err(format!("Unsupported language: {}", other.name())),
     }
 }
 
@@ -97,7 +100,9 @@ pub async fn start_interactive_process(
         .map_err(|e| format!("Failed to start process: {}", e))?;
 
     // Generate unique process ID
-    let process_id = format!("proc_{}", child.id());
+    let pid = child.id();
+    let process_id = format!("proc_{}", pid);
+    audit::record("interactive", Some(&process_id), &file_path, None);
 
     // Get handles for stdout and stderr
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
@@ -106,6 +111,10 @@ pub async fn start_interactive_process(
     // Store the child process
     let child_arc = Arc::new(Mutex::new(child));
     PROCESSES.lock().unwrap().insert(process_id.clone(), child_arc.clone());
+    processes::register(
+        ManagedProcess::new(&process_id, ProcessKind::Interactive, &file_path).with_pid(Some(pid)),
+        None,
+    );
 
     // Spawn thread to read stdout
     let app_handle_stdout = app_handle.clone();
@@ -149,8 +158,10 @@ pub async fn start_interactive_process(
     let app_handle_wait = app_handle.clone();
     let process_id_wait = process_id.clone();
     thread::spawn(move || {
-        // Wait for the process to complete
-        if let Some(child_arc) = PROCESSES.lock().unwrap().get(&process_id_wait) {
+        // Wait for the process to complete; the map lock is released first so
+        // the entry can be removed below
+        let child_arc = PROCESSES.lock().unwrap().get(&process_id_wait).cloned();
+        if let Some(child_arc) = child_arc {
             if let Ok(mut child) = child_arc.lock() {
                 if let Ok(status) = child.wait() {
                     let _ = app_handle_wait.emit(
@@ -164,6 +175,7 @@ pub async fn start_interactive_process(
 
                     // Clean up
                     PROCESSES.lock().unwrap().remove(&process_id_wait);
+                    processes::unregister(&process_id_wait);
                 }
             }
         }
@@ -183,6 +195,8 @@ pub async fn send_process_input(
         .get(&process_id)
         .ok_or("Process not found")?;
 
+    audit::record("interactive", Some(&process_id), input.trim_end(), None);
+
     let mut child = child_arc.lock().unwrap();
     
     if let Some(stdin) = child.stdin.as_mut() {
@@ -205,9 +219,10 @@ pub async fn send_process_input(
 pub async fn stop_interactive_process(
     process_id: String,
 ) -> Result<(), String> {
-    let mut processes = PROCESSES.lock().unwrap();
+    let mut running = PROCESSES.lock().unwrap();
     
-    if let Some(child_arc) = processes.remove(&process_id) {
+    if let Some(child_arc) = running.remove(&process_id) {
+        processes::unregister(&process_id);
         if let Ok(mut child) = child_arc.lock() {
             child
                 .kill()
@@ -0,0 +1,54 @@
+use tauri::AppHandle;
+
+use crate::services::labs::{self, LabInstance, LabSetup};
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::scaffold::{self, ScaffoldResult, SolutionsManifest, VulnerableTemplateInfo};
+
+#[tauri::command]
+pub async fn list_vulnerable_templates() -> Result<Vec<VulnerableTemplateInfo>, String> {
+    Ok(scaffold::list_templates())
+}
+
+#[tauri::command]
+pub async fn scaffold_vulnerable_app(
+    workspace_root: String,
+    template: String,
+    app_name: String,
+) -> Result<ScaffoldResult, String> {
+    scaffold::scaffold(&workspace_root, &template, &app_name)
+}
+
+#[tauri::command]
+pub async fn get_vulnerable_app_solutions(
+    workspace_root: String,
+    app_name: String,
+) -> Result<SolutionsManifest, String> {
+    scaffold::load_solutions(&workspace_root, &app_name)
+}
+
+#[tauri::command]
+pub async fn start_lab_environment(app_handle: AppHandle, name: String, setup: LabSetup) -> Result<LabInstance, String> {
+    let lab = tokio::task::spawn_blocking(move || labs::start(&name, &setup, None))
+        .await
+        .map_err(|e| format!("Lab task failed: {}", e))??;
+    let urls = if lab.urls.is_empty() { "no exposed URLs".to_string() } else { lab.urls.join(", ") };
+    notifications::notify_or_warn(
+        &app_handle,
+        NotificationKind::LabReady,
+        "Lab environment ready",
+        &format!("{} is running: {}", lab.name, urls),
+    );
+    Ok(lab)
+}
+
+#[tauri::command]
+pub async fn stop_lab_environment(lab_id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || labs::stop(&lab_id))
+        .await
+        .map_err(|e| format!("Lab task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn list_lab_environments() -> Result<Vec<LabInstance>, String> {
+    Ok(labs::list())
+}
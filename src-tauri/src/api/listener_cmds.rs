@@ -0,0 +1,40 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::services::listeners::{self, CallbackInfo, ListenerInfo};
+
+/// Open a reverse shell listener. New and closed callbacks are emitted as
+/// `listener-callback` events; each callback's `session_id` works with the
+/// regular terminal commands.
+#[tauri::command]
+pub async fn start_listener(
+    app_handle: AppHandle,
+    port: u16,
+    host: Option<String>,
+) -> Result<ListenerInfo, String> {
+    let host = host.unwrap_or_else(|| "0.0.0.0".to_string());
+    listeners::start_listener(&host, port, move |callback| {
+        let _ = app_handle.emit("listener-callback", callback);
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn stop_listener(listener_id: String) -> Result<(), String> {
+    listeners::stop_listener(&listener_id)
+}
+
+#[tauri::command]
+pub async fn list_listeners() -> Result<Vec<ListenerInfo>, String> {
+    Ok(listeners::list_listeners())
+}
+
+#[tauri::command]
+pub async fn list_callbacks() -> Result<Vec<CallbackInfo>, String> {
+    Ok(listeners::list_callbacks())
+}
+
+/// Spawn a PTY on the target so the callback behaves like a real terminal
+#[tauri::command]
+pub async fn upgrade_callback_shell(session_id: String) -> Result<(), String> {
+    listeners::upgrade_to_pty(&session_id)
+}
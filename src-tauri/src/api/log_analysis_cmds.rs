@@ -0,0 +1,11 @@
+use crate::services::log_analysis::{self, LogAnalysis, LogAnalysisOptions};
+
+/// Analyze web server and auth logs for attack signatures and brute force
+/// bursts, returning the attack timeline and per-source summaries
+#[tauri::command]
+pub async fn analyze_logs(paths: Vec<String>, options: Option<LogAnalysisOptions>) -> Result<LogAnalysis, String> {
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || log_analysis::analyze(&paths, &options))
+        .await
+        .map_err(|e| format!("Log analysis task failed: {}", e))?
+}
@@ -10,3 +10,34 @@ pub mod exploit_cmds;
 pub mod extension_cmds;
 pub mod search_cmds;
 pub mod prover_cmds;
+pub mod audit_cmds;
+pub mod http_client;
+pub mod proxy_cmds;
+pub mod fuzzer;
+pub mod websocket_cmds;
+pub mod recon_cmds;
+pub mod intel_cmds;
+pub mod crypto_cmds;
+pub mod crack_cmds;
+pub mod listener_cmds;
+pub mod payload_cmds;
+pub mod lab_cmds;
+pub mod ctf_cmds;
+pub mod progress_cmds;
+pub mod report_cmds;
+pub mod evidence_cmds;
+pub mod settings_cmds;
+pub mod session_cmds;
+pub mod extension_host_cmds;
+pub mod findings_cmds;
+pub mod diagnostics_cmds;
+pub mod notification_cmds;
+pub mod secrets_cmds;
+pub mod process_cmds;
+pub mod policy_cmds;
+pub mod pcap_cmds;
+pub mod log_analysis_cmds;
+pub mod binary_cmds;
+pub mod spray_cmds;
+pub mod phishing_cmds;
+pub mod scheduler_cmds;
@@ -0,0 +1,15 @@
+use tauri::AppHandle;
+
+use crate::services::notifications::{self, NotificationKind};
+
+/// Show an OS notification, subject to the notification settings; returns
+/// whether it was shown
+#[tauri::command]
+pub async fn send_notification(
+    app_handle: AppHandle,
+    kind: Option<NotificationKind>,
+    title: String,
+    body: String,
+) -> Result<bool, String> {
+    notifications::notify(&app_handle, kind.unwrap_or(NotificationKind::JobFinished), &title, &body)
+}
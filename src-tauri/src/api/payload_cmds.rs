@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use crate::services::payload_library::{self, PayloadQuery, UserPayload};
+
+#[tauri::command]
+pub async fn list_user_payloads() -> Result<Vec<UserPayload>, String> {
+    Ok(payload_library::load_library())
+}
+
+#[tauri::command]
+pub async fn save_user_payload(payload: UserPayload) -> Result<UserPayload, String> {
+    payload_library::upsert(payload)
+}
+
+#[tauri::command]
+pub async fn delete_user_payload(id: String) -> Result<(), String> {
+    payload_library::delete(&id)
+}
+
+#[tauri::command]
+pub async fn search_user_payloads(query: PayloadQuery) -> Result<Vec<UserPayload>, String> {
+    Ok(payload_library::search(&query))
+}
+
+#[tauri::command]
+pub async fn list_payload_tags() -> Result<Vec<String>, String> {
+    Ok(payload_library::tags())
+}
+
+#[tauri::command]
+pub async fn export_user_payloads(dest_path: String, ids: Option<Vec<String>>) -> Result<usize, String> {
+    payload_library::export(&PathBuf::from(dest_path), ids.as_deref())
+}
+
+#[tauri::command]
+pub async fn import_user_payloads(src_path: String) -> Result<usize, String> {
+    payload_library::import(&PathBuf::from(src_path))
+}
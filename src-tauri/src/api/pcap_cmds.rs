@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use crate::services::pcap::{self, CaptureSummary, PacketDetail, PacketPage};
+
+/// Load a pcap or pcapng file and summarize its traffic
+#[tauri::command]
+pub async fn open_capture(path: String) -> Result<CaptureSummary, String> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err("File does not exist".into());
+    }
+    tokio::task::spawn_blocking(move || pcap::open(&path))
+        .await
+        .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_capture_summary(capture_id: String) -> Result<CaptureSummary, String> {
+    pcap::summary(&capture_id)
+}
+
+/// A page of an open capture's packet list, optionally of one protocol
+#[tauri::command]
+pub async fn get_capture_packets(
+    capture_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    protocol: Option<String>,
+) -> Result<PacketPage, String> {
+    tokio::task::spawn_blocking(move || pcap::packets(&capture_id, offset.unwrap_or(0), limit, protocol.as_deref()))
+        .await
+        .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+/// Decoded layers and hex dump of one packet
+#[tauri::command]
+pub async fn get_capture_packet(capture_id: String, index: usize) -> Result<PacketDetail, String> {
+    pcap::packet(&capture_id, index)
+}
+
+#[tauri::command]
+pub async fn close_capture(capture_id: String) -> Result<bool, String> {
+    Ok(pcap::close(&capture_id))
+}
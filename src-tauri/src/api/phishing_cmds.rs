@@ -0,0 +1,67 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::services::phishing::landing::{self, LandingOptions, LandingScaffold};
+use crate::services::phishing::tracker::{self, TrackerInfo, TrackingEvent};
+use crate::services::phishing::{self, Campaign, CampaignRequest, RenderedEmail, SmtpConfig};
+use crate::utils::blocking;
+
+#[tauri::command]
+pub async fn get_smtp_config() -> Result<Option<SmtpConfig>, String> {
+    Ok(phishing::smtp_config())
+}
+
+/// Save the lab SMTP server; `password` goes to the keychain when given
+#[tauri::command]
+pub async fn set_smtp_config(config: SmtpConfig, password: Option<String>) -> Result<(), String> {
+    blocking::run(None, move |_| phishing::set_smtp_config(&config, password.as_deref())).await
+}
+
+/// The campaign's email as its first recipient would get it
+#[tauri::command]
+pub async fn preview_phishing_email(campaign: CampaignRequest) -> Result<RenderedEmail, String> {
+    phishing::preview(&campaign)
+}
+
+/// Send a phishing simulation through the lab SMTP server; the server and
+/// all recipient domains must be on the lab target allowlist
+#[tauri::command]
+pub async fn send_phishing_campaign(campaign: CampaignRequest) -> Result<Campaign, String> {
+    phishing::send(campaign).await
+}
+
+#[tauri::command]
+pub async fn list_phishing_campaigns() -> Result<Vec<Campaign>, String> {
+    Ok(phishing::campaigns())
+}
+
+/// Generate a fake sign-in page and its awareness page into the workspace
+#[tauri::command]
+pub async fn scaffold_phishing_landing(workspace_root: String, options: LandingOptions) -> Result<LandingScaffold, String> {
+    landing::scaffold(&workspace_root, &options)
+}
+
+/// Serve tracking pixels and landing page beacons on host:port; each
+/// recorded open, visit or submission is emitted as `phishing-tracking-event`
+#[tauri::command]
+pub async fn start_phishing_tracker(app_handle: AppHandle, port: u16, host: Option<String>) -> Result<TrackerInfo, String> {
+    let host = host.unwrap_or_else(|| "0.0.0.0".to_string());
+    tracker::start(&host, port, move |event| {
+        let _ = app_handle.emit("phishing-tracking-event", event);
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn stop_phishing_tracker() -> Result<(), String> {
+    tracker::stop()
+}
+
+#[tauri::command]
+pub async fn get_phishing_tracker() -> Result<Option<TrackerInfo>, String> {
+    Ok(tracker::status())
+}
+
+#[tauri::command]
+pub async fn list_phishing_events(campaign_id: Option<String>) -> Result<Vec<TrackingEvent>, String> {
+    Ok(tracker::events(campaign_id.as_deref()))
+}
@@ -0,0 +1,19 @@
+use crate::services::policy::{self, ClassifiedCommand, CommandPolicy};
+
+#[tauri::command]
+pub async fn get_command_policy() -> Result<CommandPolicy, String> {
+    Ok(policy::get())
+}
+
+/// Replace the policy; loosening a configured policy is confirmed in a
+/// dialog before this runs
+#[tauri::command]
+pub async fn set_command_policy(policy: CommandPolicy) -> Result<(), String> {
+    policy::set(policy)
+}
+
+/// The commands the policy classifies, for the settings UI
+#[tauri::command]
+pub async fn list_command_classes() -> Result<Vec<ClassifiedCommand>, String> {
+    Ok(policy::classified_commands())
+}
@@ -0,0 +1,16 @@
+use crate::services::processes::{self, ManagedProcess};
+
+/// Every terminal, interactive run, lab, listener and helper process the
+/// IDE has running
+#[tauri::command]
+pub async fn list_processes() -> Result<Vec<ManagedProcess>, String> {
+    Ok(processes::list())
+}
+
+/// Stop a registered process and its descendants
+#[tauri::command]
+pub async fn stop_process(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || processes::stop(&id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
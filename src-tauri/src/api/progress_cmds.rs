@@ -0,0 +1,32 @@
+use crate::services::progress::{self, ExportFormat, LeaderboardEntry, ProgressEvent, SkillSummary};
+
+#[tauri::command]
+pub async fn record_progress_event(event: ProgressEvent) -> Result<ProgressEvent, String> {
+    progress::record(event)
+}
+
+#[tauri::command]
+pub async fn list_progress_events(user: Option<String>) -> Result<Vec<ProgressEvent>, String> {
+    progress::events(user.as_deref())
+}
+
+#[tauri::command]
+pub async fn get_leaderboard() -> Result<Vec<LeaderboardEntry>, String> {
+    progress::leaderboard()
+}
+
+#[tauri::command]
+pub async fn export_leaderboard(path: String, format: ExportFormat) -> Result<usize, String> {
+    progress::export_leaderboard(std::path::Path::new(&path), format)
+}
+
+/// Per-skill (SQLi, XSS, crypto, ...) competency for one user or everyone
+#[tauri::command]
+pub async fn get_skill_summaries(user: Option<String>) -> Result<Vec<SkillSummary>, String> {
+    progress::skill_summaries(user.as_deref())
+}
+
+#[tauri::command]
+pub async fn reset_progress(user: Option<String>) -> Result<usize, String> {
+    progress::reset(user.as_deref())
+}
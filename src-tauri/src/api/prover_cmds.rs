@@ -3,7 +3,30 @@
 //! Exposes the Exploit Prover analysis engine to the frontend.
 
 use serde::{Deserialize, Serialize};
-use crate::analysis::{AnalysisResult, prover::ExploitProver};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use crate::analysis::{AnalysisResult, ExploitStatus, Language, prover::{detect_chains, ChainResult, ExploitProver, DEFAULT_PAYLOAD_COMMAND}, slicer::TaintConfig};
+use crate::analysis::notebook::{is_notebook, Notebook};
+use crate::analysis::summaries::SummaryStore;
+use crate::services::findings::{self, Finding, FindingSource};
+use crate::services::langdetect::{self, LanguageId};
+use crate::services::project::roots;
+use crate::services::{prover_cache, settings, triage::{self, TriageEntry}};
+use crate::utils::fs_utils::{load_json, save_json, workspace_ctr_dir};
+
+/// How often watched files are checked for changes on disk
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Quiet period after the last edit before a watched file is re-analyzed
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(400);
+
+lazy_static::lazy_static! {
+    /// Watched file path -> channel for unsaved buffer contents; dropping the
+    /// sender stops the watcher
+    static ref PROVER_WATCHERS: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<String>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
 
 /// Request to analyze source code
 #[derive(Debug, Deserialize)]
@@ -14,42 +37,308 @@ pub struct AnalyzeRequest {
     pub target_line: Option<usize>,
     /// The file path (for context)
     pub file_path: Option<String>,
+    /// Workspace whose prover settings apply
+    pub workspace_root: Option<String>,
+}
+
+/// Optional taint sources as configured by the `prover.*` settings
+fn taint_config(workspace_root: Option<&str>) -> TaintConfig {
+    TaintConfig {
+        untrusted_environment: !settings::get_as("prover.trustEnvironment", workspace_root, true),
+        untrusted_config_files: settings::get_as("prover.untrustedConfigFiles", workspace_root, false),
+    }
+}
+
+/// Where a workspace's function summaries are saved between runs
+fn summary_store_path(workspace_root: &str) -> Result<std::path::PathBuf, String> {
+    Ok(workspace_ctr_dir(workspace_root)?.join("taint_summaries.json"))
+}
+
+/// Cross-file slicer for a workspace, starting from its saved summaries
+fn cross_file_slicer(workspace_path: &str) -> Result<crate::analysis::CrossFileSlicer, String> {
+    let store: SummaryStore = load_json(&summary_store_path(workspace_path)?);
+    Ok(crate::analysis::CrossFileSlicer::new(std::path::PathBuf::from(workspace_path))?
+        .with_taint_config(taint_config(Some(workspace_path)))
+        .with_summaries(store))
+}
+
+/// Run the prover, answering unchanged sources analyzed with the same
+/// settings from the cache
+fn analyze_cached(
+    source: &str,
+    language: Language,
+    target_line: Option<usize>,
+    workspace_root: Option<&str>,
+) -> Result<AnalysisResult, String> {
+    let config = taint_config(workspace_root);
+    let command: String = settings::get_as("prover.payloadCommand", workspace_root, DEFAULT_PAYLOAD_COMMAND.to_string());
+    let key = prover_cache::cache_key(source, language, target_line, &config, &command);
+    if let Some(cached) = prover_cache::get(&key) {
+        return Ok(cached);
+    }
+
+    let mut prover = ExploitProver::new()?.with_taint_config(config).with_payload_command(command);
+    let analysis = if let Some(line) = target_line {
+        prover.analyze_at_line_as(language, source, line)
+    } else {
+        prover.analyze_as(language, source)
+    };
+    // A cache write failure should not lose the analysis
+    if let Err(e) = prover_cache::put(&key, &analysis) {
+        log::warn!("{}", e);
+    }
+    Ok(analysis)
+}
+
+/// The prover language of a file, by name, shebang or content; Python
+/// unless recognized
+fn prover_language(file_path: Option<&str>, source: &str) -> Language {
+    let detected = langdetect::detect(std::path::Path::new(file_path.unwrap_or("")), Some(source));
+    match detected.map(|d| d.language) {
+        Some(LanguageId::Go) => Language::Go,
+        Some(LanguageId::Php) => Language::Php,
+        Some(LanguageId::Java) => Language::Java,
+        _ => Language::Python,
+    }
+}
+
+/// The notebook behind `source` when `file_path` names an .ipynb
+fn notebook_for(file_path: Option<&str>, source: &str) -> Result<Option<Notebook>, String> {
+    match file_path {
+        Some(path) if is_notebook(std::path::Path::new(path)) => Notebook::parse(source).map(Some),
+        _ => Ok(None),
+    }
 }
 
-/// Analyze Python source code for exploitable vulnerabilities
+/// Analyze a Python, Go, PHP or Java file, or a notebook; notebook findings carry cell positions
+/// and findings triaged as false positives are downgraded
+fn analyze_document(
+    source: &str,
+    file_path: Option<&str>,
+    target_line: Option<usize>,
+    workspace_root: Option<&str>,
+) -> Result<AnalysisResult, String> {
+    let mut analysis = match notebook_for(file_path, source)? {
+        Some(notebook) => {
+            let mut analysis = analyze_cached(&notebook.source, Language::Python, target_line, workspace_root)?;
+            notebook.map_result(&mut analysis);
+            analysis
+        }
+        None => analyze_cached(source, prover_language(file_path, source), target_line, workspace_root)?,
+    };
+    triage::apply_to_analysis(&mut analysis, file_path.map(std::path::Path::new), workspace_root);
+    Ok(analysis)
+}
+
+/// Analyze Python source code (or notebook JSON, for .ipynb paths) for
+/// exploitable vulnerabilities
 #[tauri::command]
 pub async fn prove_exploitability(request: AnalyzeRequest) -> Result<AnalysisResult, String> {
     // Run the analysis in a blocking task to not block the async runtime
-    let result = tokio::task::spawn_blocking(move || {
-        let mut prover = ExploitProver::new()?;
-        
-        if let Some(line) = request.target_line {
-            Ok(prover.analyze_at_line(&request.source, line))
-        } else {
-            Ok(prover.analyze(&request.source))
+    tokio::task::spawn_blocking(move || {
+        let analysis = analyze_document(
+            &request.source,
+            request.file_path.as_deref(),
+            request.target_line,
+            request.workspace_root.as_deref(),
+        )?;
+        // Analyses of a single line do not cover the rest of the file
+        if let (Some(file), Some(root), None) = (&request.file_path, &request.workspace_root, request.target_line) {
+            let scope = [file.clone()];
+            findings::record_or_warn(root, FindingSource::Prover, Some(&scope), Finding::from_analysis(&analysis, file));
         }
+        Ok(analysis)
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?;
-    
-    result
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Editor marker for one sink of a watched file
+#[derive(Debug, Clone, Serialize)]
+pub struct ProverDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub end_column: usize,
+    /// "error" when the file is exploitable, "warning" when inconclusive, "info" when safe
+    pub severity: String,
+    pub sink_type: String,
+    pub cwe: String,
+    pub message: String,
+}
+
+/// Payload of the `prover-diagnostics` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ProverDiagnosticsEvent {
+    pub file_path: String,
+    pub status: ExploitStatus,
+    pub diagnostics: Vec<ProverDiagnostic>,
+    pub payload: Option<String>,
+}
+
+fn diagnostics_event(file_path: &str, analysis: AnalysisResult) -> ProverDiagnosticsEvent {
+    let (severity, verdict) = match analysis.status {
+        ExploitStatus::Exploitable => ("error", "Exploitable"),
+        ExploitStatus::Inconclusive => ("warning", "Possibly exploitable"),
+        ExploitStatus::Safe | ExploitStatus::NoSinksFound => ("info", "Not exploitable"),
+    };
+    let diagnostics = analysis
+        .sinks
+        .iter()
+        .map(|sink| {
+            let snippet = sink.code_snippet.lines().next().unwrap_or("");
+            let mut message = format!("{}: {}", verdict, sink.sink_type.description());
+            if let (ExploitStatus::Exploitable, Some(payload)) = (&analysis.status, &analysis.payload) {
+                message.push_str(&format!(" (payload: {})", payload));
+            }
+            ProverDiagnostic {
+                line: sink.line,
+                column: sink.column,
+                end_column: sink.column + snippet.chars().count(),
+                severity: severity.to_string(),
+                sink_type: format!("{:?}", sink.sink_type),
+                cwe: sink.sink_type.cwe().to_string(),
+                message,
+            }
+        })
+        .collect();
+
+    ProverDiagnosticsEvent {
+        file_path: file_path.to_string(),
+        status: analysis.status,
+        diagnostics,
+        payload: analysis.payload,
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Re-analyze a file in the background whenever it changes on disk or its
+/// unsaved contents are pushed with `update_watched_source`, emitting
+/// `prover-diagnostics` once edits have settled
+#[tauri::command]
+pub async fn watch_file(app_handle: AppHandle, path: String, workspace_root: Option<String>) -> Result<(), String> {
+    let initial = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    // Replacing the sender closes the previous watcher's channel
+    PROVER_WATCHERS.lock().unwrap().insert(path.clone(), tx);
+
+    tokio::spawn(async move {
+        let mut disk_modified = modified_time(&path);
+        let mut pending = Some(initial);
+        let mut changed_at = Instant::now();
+        let mut analyzed: Option<String> = None;
+
+        loop {
+            tokio::select! {
+                edit = rx.recv() => match edit {
+                    Some(source) => {
+                        pending = Some(source);
+                        changed_at = Instant::now();
+                        continue;
+                    }
+                    None => break,
+                },
+                _ = tokio::time::sleep(WATCH_POLL_INTERVAL) => {}
+            }
+
+            let modified = modified_time(&path);
+            if modified != disk_modified {
+                disk_modified = modified;
+                if let Ok(source) = std::fs::read_to_string(&path) {
+                    pending = Some(source);
+                    changed_at = Instant::now();
+                }
+            }
+            if changed_at.elapsed() < WATCH_DEBOUNCE {
+                continue;
+            }
+            let Some(source) = pending.take() else {
+                continue;
+            };
+            if analyzed.as_ref() == Some(&source) {
+                continue;
+            }
+
+            let root = workspace_root.clone();
+            let input = source.clone();
+            let file_path = path.clone();
+            let analysis = tokio::task::spawn_blocking(move || analyze_document(&input, Some(&file_path), None, root.as_deref()))
+                .await
+                .map_err(|e| format!("Task join error: {}", e))
+                .and_then(|result| result);
+            match analysis {
+                Ok(analysis) => {
+                    let _ = app_handle.emit("prover-diagnostics", diagnostics_event(&path, analysis));
+                }
+                Err(e) => log::warn!("Background analysis of {} failed: {}", path, e),
+            }
+            analyzed = Some(source);
+        }
+    });
+
+    Ok(())
+}
+
+/// Feed the unsaved contents of a watched file so diagnostics follow typing
+#[tauri::command]
+pub async fn update_watched_source(path: String, source: String) -> Result<(), String> {
+    let watchers = PROVER_WATCHERS.lock().unwrap();
+    let tx = watchers.get(&path).ok_or_else(|| format!("{} is not being watched", path))?;
+    tx.send(source).map_err(|_| format!("Watcher for {} has stopped", path))
+}
+
+#[tauri::command]
+pub async fn unwatch_file(path: String) -> Result<(), String> {
+    PROVER_WATCHERS.lock().unwrap().remove(&path);
+    Ok(())
+}
+
+/// Hit and size statistics of the prover result cache
+#[tauri::command]
+pub async fn prover_cache_stats() -> Result<prover_cache::CacheStats, String> {
+    tokio::task::spawn_blocking(prover_cache::stats)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Discard all cached prover results, returning how many were on disk
+#[tauri::command]
+pub async fn clear_prover_cache() -> Result<usize, String> {
+    tokio::task::spawn_blocking(prover_cache::clear)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
 }
 
 /// Quick scan to just detect sinks without full analysis
 #[tauri::command]
-pub async fn quick_scan_sinks(source: String) -> Result<Vec<SinkInfo>, String> {
+pub async fn quick_scan_sinks(source: String, file_path: Option<String>) -> Result<Vec<SinkInfo>, String> {
+    use crate::analysis::go_analyzer::GoAnalyzer;
+    use crate::analysis::java_analyzer::JavaAnalyzer;
+    use crate::analysis::php_analyzer::PhpAnalyzer;
     use crate::analysis::python_parser::PythonParser;
     
     let result = tokio::task::spawn_blocking(move || {
-        let mut parser = PythonParser::new()?;
-        let sinks = parser.find_sinks(&source)?;
+        let notebook = notebook_for(file_path.as_deref(), &source)?;
+        let sinks = match prover_language(file_path.as_deref(), &source) {
+            Language::Go if notebook.is_none() => GoAnalyzer::new()?.find_sinks(&source)?,
+            Language::Php if notebook.is_none() => PhpAnalyzer::new()?.find_sinks(&source)?,
+            Language::Java if notebook.is_none() => JavaAnalyzer::new()?.find_sinks(&source)?,
+            _ => PythonParser::new()?.find_sinks(notebook.as_ref().map_or(&source, |nb| &nb.source))?,
+        };
         
-        Ok(sinks.into_iter().map(|s| SinkInfo {
-            sink_type: format!("{:?}", s.sink_type),
-            line: s.line,
-            column: s.column,
-            code: s.code_snippet,
-            description: s.sink_type.description().to_string(),
+        Ok(sinks.into_iter().map(|s| {
+            let location = notebook.as_ref().and_then(|nb| nb.location(s.line));
+            SinkInfo {
+                sink_type: format!("{:?}", s.sink_type),
+                line: location.map_or(s.line, |l| l.line),
+                cell: location.map(|l| l.cell),
+                column: s.column,
+                code: s.code_snippet,
+                description: s.sink_type.description().to_string(),
+                cwe: s.sink_type.cwe().to_string(),
+            }
         }).collect())
     })
     .await
@@ -63,40 +352,51 @@ pub async fn quick_scan_sinks(source: String) -> Result<Vec<SinkInfo>, String> {
 pub struct SinkInfo {
     pub sink_type: String,
     pub line: usize,
+    /// Notebook cell index for .ipynb files; `line` is then within the cell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell: Option<usize>,
     pub column: usize,
     pub code: String,
     pub description: String,
+    pub cwe: String,
 }
 
-/// Index the workspace for cross-file analysis
+/// Index every root of the workspace for cross-file analysis
 #[tauri::command]
 pub async fn index_workspace(workspace_path: String) -> Result<WorkspaceIndexResult, String> {
-    use crate::analysis::ProjectIndexer;
-    use std::path::PathBuf;
-    
     let result = tokio::task::spawn_blocking(move || {
-        let mut indexer = ProjectIndexer::new(PathBuf::from(&workspace_path))?;
-        let file_count = indexer.index_workspace()?;
-        
-        let symbols: Vec<SymbolInfo> = indexer
-            .get_all_symbols()
-            .iter()
-            .flat_map(|(name, syms)| {
-                syms.iter().map(|s| SymbolInfo {
-                    name: name.clone(),
-                    kind: format!("{:?}", s.kind),
-                    file_path: s.file_path.to_string_lossy().to_string(),
-                    line: s.line,
-                    module_path: s.module_path.clone(),
+        let mut result = WorkspaceIndexResult::default();
+        for root in roots::list(&workspace_path)? {
+            let mut slicer = cross_file_slicer(&root.path)?;
+            let file_count = slicer.index_workspace()?;
+
+            // Only files changed since the last run (and their callers) are re-summarized
+            let summaries_computed = slicer.summarize_workspace();
+            save_json(&summary_store_path(&root.path)?, slicer.summaries())?;
+
+            let symbols = slicer
+                .indexer()
+                .get_all_symbols()
+                .iter()
+                .flat_map(|(name, syms)| {
+                    syms.iter().map(|s| SymbolInfo {
+                        name: name.clone(),
+                        kind: format!("{:?}", s.kind),
+                        file_path: s.file_path.to_string_lossy().to_string(),
+                        line: s.line,
+                        module_path: s.module_path.clone(),
+                        root: root.name.clone(),
+                    })
                 })
-            })
-            .collect();
-        
-        Ok(WorkspaceIndexResult {
-            files_indexed: file_count,
-            symbols_found: symbols.len(),
-            symbols,
-        })
+                .collect::<Vec<_>>();
+
+            result.files_indexed += file_count;
+            result.symbols.extend(symbols);
+            result.files_summarized += slicer.summaries().len();
+            result.summaries_computed += summaries_computed;
+        }
+        result.symbols_found = result.symbols.len();
+        Ok(result)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?;
@@ -105,11 +405,15 @@ pub async fn index_workspace(workspace_path: String) -> Result<WorkspaceIndexRes
 }
 
 /// Result of workspace indexing
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct WorkspaceIndexResult {
     pub files_indexed: usize,
     pub symbols_found: usize,
     pub symbols: Vec<SymbolInfo>,
+    /// Files with function taint summaries, and how many of them were
+    /// recomputed rather than reused
+    pub files_summarized: usize,
+    pub summaries_computed: usize,
 }
 
 /// Info about a symbol in the workspace
@@ -120,19 +424,100 @@ pub struct SymbolInfo {
     pub file_path: String,
     pub line: usize,
     pub module_path: String,
+    /// Name of the workspace root the symbol is in
+    pub root: String,
+}
+
+/// Result of proving every Python file in a workspace and linking the findings
+#[derive(Debug, Serialize)]
+pub struct ExploitChainScan {
+    pub files_analyzed: usize,
+    pub exploitable_files: usize,
+    pub chains: Vec<ChainResult>,
+}
+
+/// Prove every Python file in the workspace, then report findings that
+/// combine into multi-step exploit chains
+#[tauri::command]
+pub async fn detect_exploit_chains(workspace_path: String) -> Result<ExploitChainScan, String> {
+    use crate::analysis::ProjectIndexer;
+    use std::path::PathBuf;
+
+    tokio::task::spawn_blocking(move || {
+        let mut indexer = ProjectIndexer::new(PathBuf::from(&workspace_path))?;
+        indexer.index_workspace()?;
+        let mut files = indexer.indexed_files();
+        files.sort();
+
+        let mut results = Vec::new();
+        for file in files {
+            let source = match std::fs::read_to_string(&file) {
+                Ok(source) => source,
+                Err(e) => {
+                    log::warn!("Failed to read {}: {}", file.display(), e);
+                    continue;
+                }
+            };
+            let mut analysis = analyze_cached(&source, Language::Python, None, Some(&workspace_path))?;
+            triage::apply_to_analysis(&mut analysis, Some(&file), Some(&workspace_path));
+            results.push((file, analysis));
+        }
+
+        Ok(ExploitChainScan {
+            files_analyzed: results.len(),
+            exploitable_files: results.iter().filter(|(_, r)| r.status == ExploitStatus::Exploitable).count(),
+            chains: detect_chains(&results),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Mark a prover or scanner finding as a false positive so later analyses of
+/// the workspace downgrade or hide it
+#[tauri::command]
+pub async fn mark_false_positive(workspace_root: String, finding_fingerprint: String, reason: String) -> Result<TriageEntry, String> {
+    triage::mark(&workspace_root, &finding_fingerprint, &reason)
+}
+
+/// Withdraw a false-positive decision; returns whether one existed
+#[tauri::command]
+pub async fn unmark_false_positive(workspace_root: String, finding_fingerprint: String) -> Result<bool, String> {
+    triage::unmark(&workspace_root, &finding_fingerprint)
+}
+
+#[tauri::command]
+pub async fn list_false_positives(workspace_root: String) -> Result<Vec<TriageEntry>, String> {
+    Ok(triage::load(&workspace_root)?.entries)
+}
+
+/// Write the workspace's triage decisions to a file to share with the team;
+/// returns how many were exported
+#[tauri::command]
+pub async fn export_triage(workspace_root: String, path: String) -> Result<usize, String> {
+    triage::export(&workspace_root, std::path::Path::new(&path))
+}
+
+/// Merge triage decisions exported from another checkout; returns how many
+/// were added or updated
+#[tauri::command]
+pub async fn import_triage(workspace_root: String, path: String) -> Result<usize, String> {
+    triage::import(&workspace_root, std::path::Path::new(&path))
 }
 
 /// Analyze a file with cross-file taint tracking
 #[tauri::command]
 pub async fn analyze_cross_file(file_path: String, workspace_path: String) -> Result<CrossFileResult, String> {
-    use crate::analysis::CrossFileSlicer;
     use std::path::PathBuf;
     
     let result = tokio::task::spawn_blocking(move || {
-        let mut slicer = CrossFileSlicer::new(PathBuf::from(&workspace_path))?;
+        let mut slicer = cross_file_slicer(&workspace_path)?;
         slicer.index_workspace()?;
         
         let analysis = slicer.analyze_file(&PathBuf::from(&file_path))?;
+        if let Err(e) = save_json(&summary_store_path(&workspace_path)?, slicer.summaries()) {
+            log::warn!("Failed to save taint summaries: {}", e);
+        }
         
         Ok(CrossFileResult {
             sinks_found: analysis.sinks.len(),
@@ -151,6 +536,7 @@ pub async fn analyze_cross_file(file_path: String, workspace_path: String) -> Re
                 callee_line: f.callee_line,
                 function_called: f.function_called.clone(),
                 tainted_args: f.tainted_args.clone(),
+                tainted_return: f.tainted_return.clone(),
             }).collect(),
         })
     })
@@ -188,5 +574,7 @@ pub struct CrossFileFlowInfo {
     pub callee_line: usize,
     pub function_called: String,
     pub tainted_args: Vec<String>,
+    /// Caller variable tainted by the call's return value
+    pub tainted_return: Option<String>,
 }
 
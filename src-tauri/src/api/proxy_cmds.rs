@@ -0,0 +1,70 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::services::http_client::HttpRequestSpec;
+use crate::services::proxy::{self, CapturedExchange, MatchReplaceRule, ProxyConfig, ProxyStatus};
+
+const DEFAULT_PROXY_PORT: u16 = 8081;
+
+/// Start the intercepting proxy; captured traffic is emitted as `proxy-traffic` events
+#[tauri::command]
+pub async fn start_proxy(
+    app_handle: AppHandle,
+    port: Option<u16>,
+    intercept_tls: Option<bool>,
+    upstream_proxy: Option<String>,
+) -> Result<ProxyStatus, String> {
+    let config = ProxyConfig {
+        port: port.unwrap_or(DEFAULT_PROXY_PORT),
+        intercept_tls: intercept_tls.unwrap_or(true),
+        upstream_proxy,
+    };
+
+    proxy::start(config, move |exchange| {
+        let _ = app_handle.emit("proxy-traffic", exchange);
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn stop_proxy() -> Result<(), String> {
+    proxy::stop()
+}
+
+#[tauri::command]
+pub async fn get_proxy_status() -> Result<ProxyStatus, String> {
+    Ok(proxy::status())
+}
+
+#[tauri::command]
+pub async fn get_proxy_history() -> Result<Vec<CapturedExchange>, String> {
+    Ok(proxy::history())
+}
+
+#[tauri::command]
+pub async fn clear_proxy_history() -> Result<(), String> {
+    proxy::clear_history();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_proxy_rules() -> Result<Vec<MatchReplaceRule>, String> {
+    Ok(proxy::get_rules())
+}
+
+#[tauri::command]
+pub async fn set_proxy_rules(rules: Vec<MatchReplaceRule>) -> Result<(), String> {
+    proxy::set_rules(rules)
+}
+
+/// Path to the CA certificate to import into the browser trust store
+#[tauri::command]
+pub async fn get_proxy_ca_cert_path() -> Result<String, String> {
+    proxy::ca::CertificateAuthority::load_or_create()?;
+    Ok(proxy::ca::ca_cert_path()?.to_string_lossy().to_string())
+}
+
+/// Convert a captured exchange into a request for the HTTP client
+#[tauri::command]
+pub async fn proxy_send_to_repeater(id: u64) -> Result<HttpRequestSpec, String> {
+    proxy::to_repeater(id)
+}
@@ -0,0 +1,74 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::services::recon::dns::{self, DnsRecord, SubdomainHit, ZoneTransferResult};
+
+const DEFAULT_DNS_CONCURRENCY: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+struct SubdomainFoundEvent {
+    domain: String,
+    hit: SubdomainHit,
+}
+
+#[tauri::command]
+pub async fn dns_lookup(
+    name: String,
+    record_type: String,
+    nameserver: Option<String>,
+) -> Result<Vec<DnsRecord>, String> {
+    dns::lookup(&name, &record_type, nameserver.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn dns_reverse_lookup(ip: String, nameserver: Option<String>) -> Result<Vec<String>, String> {
+    dns::reverse_lookup(&ip, nameserver.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn dns_zone_transfer(
+    domain: String,
+    nameserver: Option<String>,
+) -> Result<Vec<ZoneTransferResult>, String> {
+    dns::zone_transfer(&domain, nameserver.as_deref()).await
+}
+
+/// Enumerate subdomains from a wordlist file or inline word list.
+/// Hits are streamed as `dns-subdomain-found` events and also returned at the end.
+#[tauri::command]
+pub async fn dns_enumerate_subdomains(
+    app_handle: AppHandle,
+    domain: String,
+    wordlist_path: Option<String>,
+    words: Option<Vec<String>>,
+    concurrency: Option<usize>,
+    nameserver: Option<String>,
+) -> Result<Vec<SubdomainHit>, String> {
+    let mut all_words = words.unwrap_or_default();
+    if let Some(path) = wordlist_path {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read wordlist: {}", e))?;
+        all_words.extend(content.lines().map(|l| l.to_string()));
+    }
+    if all_words.is_empty() {
+        return Err("No words to try".to_string());
+    }
+
+    let event_domain = domain.clone();
+    dns::enumerate_subdomains(
+        &domain,
+        all_words,
+        concurrency.unwrap_or(DEFAULT_DNS_CONCURRENCY),
+        nameserver.as_deref(),
+        move |hit| {
+            let _ = app_handle.emit(
+                "dns-subdomain-found",
+                SubdomainFoundEvent {
+                    domain: event_domain.clone(),
+                    hit: hit.clone(),
+                },
+            );
+        },
+    )
+    .await
+}
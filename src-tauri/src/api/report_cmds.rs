@@ -0,0 +1,97 @@
+use crate::services::reporting::{
+    self,
+    export::{self, ReportFormat},
+    FindingInput, FindingUpdate, Report, ReportFinding, ReportMeta, ReportSummary,
+};
+use crate::services::security::licenses;
+
+#[tauri::command]
+pub async fn create_report(title: String) -> Result<Report, String> {
+    reporting::create(&title)
+}
+
+#[tauri::command]
+pub async fn list_reports() -> Result<Vec<ReportSummary>, String> {
+    Ok(reporting::list())
+}
+
+#[tauri::command]
+pub async fn get_report(report_id: String) -> Result<Report, String> {
+    reporting::load(&report_id)
+}
+
+#[tauri::command]
+pub async fn update_report(report_id: String, meta: ReportMeta) -> Result<Report, String> {
+    reporting::update_meta(&report_id, meta)
+}
+
+#[tauri::command]
+pub async fn delete_report(report_id: String) -> Result<(), String> {
+    reporting::delete(&report_id)
+}
+
+/// Attach a scanner issue, prover result, verified payload, proxy capture,
+/// terminal recording or free-form note to a report
+#[tauri::command]
+pub async fn add_report_finding(report_id: String, finding: FindingInput) -> Result<ReportFinding, String> {
+    reporting::add_finding(&report_id, finding)
+}
+
+#[tauri::command]
+pub async fn update_report_finding(
+    report_id: String,
+    finding_id: String,
+    update: FindingUpdate,
+) -> Result<ReportFinding, String> {
+    reporting::update_finding(&report_id, &finding_id, update)
+}
+
+#[tauri::command]
+pub async fn remove_report_finding(report_id: String, finding_id: String) -> Result<(), String> {
+    reporting::remove_finding(&report_id, &finding_id)
+}
+
+#[tauri::command]
+pub async fn reorder_report_findings(report_id: String, order: Vec<String>) -> Result<Report, String> {
+    reporting::reorder_findings(&report_id, &order)
+}
+
+/// Check the licenses of a workspace's dependencies and put the result in
+/// the report's license section
+#[tauri::command]
+pub async fn add_report_licenses(report_id: String, workspace_root: String) -> Result<Report, String> {
+    let licenses = licenses::check_workspace(&workspace_root).await?;
+    reporting::set_licenses(&report_id, licenses)
+}
+
+#[tauri::command]
+pub async fn preview_report_markdown(report_id: String) -> Result<String, String> {
+    Ok(export::to_markdown(&reporting::load(&report_id)?))
+}
+
+#[tauri::command]
+pub async fn export_report(report_id: String, format: ReportFormat, path: String) -> Result<(), String> {
+    let report = reporting::load(&report_id)?;
+    tokio::task::spawn_blocking(move || export::export(&report, format, std::path::Path::new(&path)))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn attach_evidence_to_finding(
+    report_id: String,
+    finding_id: String,
+    evidence_id: String,
+    caption: Option<String>,
+) -> Result<ReportFinding, String> {
+    reporting::attach_evidence(&report_id, &finding_id, &evidence_id, caption.as_deref().unwrap_or(""))
+}
+
+#[tauri::command]
+pub async fn detach_evidence_from_finding(
+    report_id: String,
+    finding_id: String,
+    evidence_id: String,
+) -> Result<(), String> {
+    reporting::detach_evidence(&report_id, &finding_id, &evidence_id)
+}
@@ -0,0 +1,40 @@
+use tauri::AppHandle;
+
+use crate::services::scheduler::{self, RunResult, Schedule, ScheduleInput};
+
+#[tauri::command]
+pub async fn list_schedules() -> Result<Vec<Schedule>, String> {
+    Ok(scheduler::list())
+}
+
+#[tauri::command]
+pub async fn create_schedule(schedule: ScheduleInput) -> Result<Schedule, String> {
+    scheduler::create(schedule)
+}
+
+#[tauri::command]
+pub async fn update_schedule(id: String, schedule: ScheduleInput) -> Result<Schedule, String> {
+    scheduler::update(&id, schedule)
+}
+
+#[tauri::command]
+pub async fn set_schedule_enabled(id: String, enabled: bool) -> Result<Schedule, String> {
+    scheduler::set_enabled(&id, enabled)
+}
+
+#[tauri::command]
+pub async fn delete_schedule(id: String) -> Result<(), String> {
+    scheduler::delete(&id)
+}
+
+/// Run a schedule's task immediately; its result is stored like a timed run
+#[tauri::command]
+pub async fn run_schedule_now(app_handle: AppHandle, id: String) -> Result<RunResult, String> {
+    scheduler::run(&app_handle, &id).await
+}
+
+/// Check a cron expression and preview its next run times
+#[tauri::command]
+pub async fn preview_cron_expression(expression: String, count: Option<usize>) -> Result<Vec<u64>, String> {
+    scheduler::upcoming(&expression, count.unwrap_or(5).min(50))
+}
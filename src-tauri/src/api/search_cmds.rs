@@ -1,7 +1,23 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
 use regex::Regex;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+use crate::services::project::roots;
+use crate::services::project::todos::{self, TodoIndex};
+use crate::services::project::watcher::PollWatcher;
+
+/// How often a TODO watch checks the workspace for changed files
+const TODO_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+lazy_static::lazy_static! {
+    static ref TODO_WATCHERS: Mutex<HashMap<String, oneshot::Sender<()>>> = Mutex::new(HashMap::new());
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SearchMatch {
@@ -16,6 +32,9 @@ pub struct FileResult {
     pub file_path: String,
     pub file_name: String,
     pub matches: Vec<SearchMatch>,
+    /// Workspace root of the file, when every root was searched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,6 +54,9 @@ pub struct SearchOptions {
     pub include_patterns: Vec<String>,
     pub exclude_patterns: Vec<String>,
     pub max_results: usize,
+    /// Search every root of the workspace whose primary root is `path`
+    #[serde(default)]
+    pub all_roots: bool,
 }
 
 fn should_include_file(file_path: &str, include_patterns: &[String], exclude_patterns: &[String]) -> bool {
@@ -180,9 +202,15 @@ pub async fn search_in_files(options: SearchOptions) -> Result<SearchResult, Str
     let mut file_paths = Vec::new();
     let max_files = 5000; // Limit files to search
 
+    let workspace_roots = if options.all_roots && search_path.is_dir() {
+        roots::list(&options.path)?
+    } else {
+        Vec::new()
+    };
+
     if search_path.is_file() {
         file_paths.push(options.path.clone());
-    } else {
+    } else if workspace_roots.is_empty() {
         walk_directory(
             search_path,
             &options.include_patterns,
@@ -190,6 +218,16 @@ pub async fn search_in_files(options: SearchOptions) -> Result<SearchResult, Str
             &mut file_paths,
             max_files,
         );
+    } else {
+        for root in &workspace_roots {
+            walk_directory(
+                Path::new(&root.path),
+                &options.include_patterns,
+                &options.exclude_patterns,
+                &mut file_paths,
+                max_files,
+            );
+        }
     }
 
     let files_searched = file_paths.len();
@@ -217,10 +255,12 @@ pub async fn search_in_files(options: SearchOptions) -> Result<SearchResult, Str
                     .unwrap_or(&file_path)
                     .to_string();
 
+                let root = roots::root_of(&workspace_roots, &file_path).map(|r| r.name.clone());
                 results.push(FileResult {
                     file_path: file_path.clone(),
                     file_name,
                     matches,
+                    root,
                 });
                 total_matches += match_count;
             }
@@ -288,3 +328,90 @@ pub async fn replace_in_files(
 
     Ok(total_replacements)
 }
+
+/// TODO/FIXME/HACK/SECURITY comments of a workspace grouped by file. The
+/// index kept by a running watch is returned unless `refresh` is set.
+#[tauri::command]
+pub async fn get_todo_index(workspace_root: String, refresh: Option<bool>) -> Result<TodoIndex, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err("Workspace path does not exist".to_string());
+    }
+    if !refresh.unwrap_or(false) {
+        if let Some(index) = todos::snapshot(&root) {
+            return Ok(index);
+        }
+    }
+    tokio::task::spawn_blocking(move || todos::full_index(&root))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Index a workspace's TODO comments and keep the index current by
+/// polling for changed files; every change emits `todos-updated`
+#[tauri::command]
+pub async fn start_todo_watch(app_handle: AppHandle, workspace_root: String) -> Result<(), String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err("Workspace path does not exist".to_string());
+    }
+    let (tx, mut rx) = oneshot::channel::<()>();
+    if let Some(previous) = TODO_WATCHERS.lock().unwrap().insert(workspace_root, tx) {
+        let _ = previous.send(());
+    }
+
+    tokio::spawn(async move {
+        let initial_root = root.clone();
+        let initial = tokio::task::spawn_blocking(move || {
+            let watcher = PollWatcher::new(todos::indexed_files(&initial_root));
+            (watcher, todos::full_index(&initial_root))
+        })
+        .await;
+        let Ok((mut watcher, index)) = initial else {
+            log::warn!("TODO watch of {} failed to start", root.display());
+            return;
+        };
+        let _ = app_handle.emit("todos-updated", index);
+
+        loop {
+            tokio::select! {
+                _ = &mut rx => break,
+                _ = tokio::time::sleep(TODO_POLL_INTERVAL) => {}
+            }
+            let poll_root = root.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let changes = watcher.poll(todos::indexed_files(&poll_root));
+                let index = if changes.is_empty() {
+                    None
+                } else {
+                    todos::refresh(&poll_root, &changes.changed, &changes.removed)
+                };
+                (watcher, index)
+            })
+            .await;
+            match result {
+                Ok((returned, index)) => {
+                    watcher = returned;
+                    if let Some(index) = index {
+                        let _ = app_handle.emit("todos-updated", index);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("TODO watch of {} stopped: {}", root.display(), e);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_todo_watch(workspace_root: String) -> Result<(), String> {
+    if let Some(tx) = TODO_WATCHERS.lock().unwrap().remove(&workspace_root) {
+        let _ = tx.send(());
+    }
+    todos::forget(Path::new(&workspace_root));
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::services::secrets::{self, SecretInfo};
+
+/// The secrets integrations use and any others stored, without their values
+#[tauri::command]
+pub async fn list_secrets() -> Result<Vec<SecretInfo>, String> {
+    secrets::list()
+}
+
+/// Whether a secret is stored; values never leave the backend
+#[tauri::command]
+pub async fn has_secret(integration: String, name: String) -> Result<bool, String> {
+    Ok(secrets::get(&integration, &name)?.is_some())
+}
+
+/// Store a secret in the OS keychain; an empty value deletes it
+#[tauri::command]
+pub async fn set_secret(integration: String, name: String, value: String) -> Result<(), String> {
+    secrets::set(&integration, &name, &value)
+}
+
+#[tauri::command]
+pub async fn delete_secret(integration: String, name: String) -> Result<bool, String> {
+    secrets::delete(&integration, &name)
+}
@@ -1,7 +1,34 @@
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{mpsc, oneshot};
 
-use crate::services::security::{self, SecurityIssue};
+use crate::services::findings::{self, Finding, FindingSource};
+use crate::services::juice_shop::{self, JuiceShopStatus};
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::project::roots;
+use crate::services::project::watcher::PollWatcher;
+use crate::services::security::containers::{self, ContainerScan, ContainerScanner};
+use crate::services::security::incremental::{self, FindingsUpdate};
+use crate::services::security::rules::{self, RuleCatalog};
+use crate::services::security::semgrep::{self, RuleSummary};
+use crate::services::security::yara::{self, RuleScope, YaraRuleFile, YaraScanReport};
+use crate::services::security::{self, ScanOptions, ScanReport, SecurityIssue};
+
+pub use crate::services::juice_shop::JuiceShopChallenge;
+
+/// How often a background scan checks the workspace for saved files
+const SCAN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+lazy_static::lazy_static! {
+    static ref JUICE_SHOP_WATCHERS: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>> = Arc::new(Mutex::new(HashMap::new()));
+    /// Workspace root -> channel for saved file paths; dropping the sender
+    /// stops the background scan
+    static ref BACKGROUND_SCANS: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<PathBuf>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
 
 #[derive(Debug, Serialize)]
 pub struct SecurityScanResult {
@@ -9,58 +36,296 @@ pub struct SecurityScanResult {
 }
 
 #[tauri::command]
-pub async fn scan_file_for_issues(path: String) -> Result<SecurityScanResult, String> {
+pub async fn scan_file_for_issues(path: String, workspace_root: Option<String>) -> Result<SecurityScanResult, String> {
     let pb = PathBuf::from(&path);
     if !pb.exists() {
         return Err("File does not exist".into());
     }
 
-    let issues = security::scan_file(&pb);
+    let issues = security::scan_file(&pb, workspace_root.as_deref().map(Path::new));
     Ok(SecurityScanResult { issues })
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
-pub struct JuiceShopChallenge {
-    pub id: u32,
-    pub key: String,
-    pub name: String,
-    pub description: String,
-    pub difficulty: u32,
-    pub category: String,
+#[derive(Debug, Serialize)]
+pub struct SemgrepImportResult {
+    pub rules: Vec<RuleSummary>,
+    /// Rules that could not be converted, with the reason
+    pub skipped: Vec<String>,
 }
 
-#[derive(Debug, serde::Deserialize)]
-struct JuiceShopResponse {
-    status: String,
-    data: Vec<JuiceShopChallenge>,
+/// Preview which rules of a Semgrep file or directory the scanner can use
+#[tauri::command]
+pub async fn import_semgrep_rules(path: String) -> Result<SemgrepImportResult, String> {
+    let set = semgrep::load_rules(Path::new(&path))?;
+    Ok(SemgrepImportResult {
+        rules: set.rules.iter().map(|r| r.summary()).collect(),
+        skipped: set.skipped,
+    })
+}
+
+/// Recompile the scanner's rule packs from disk and list the rules now in effect
+#[tauri::command]
+pub async fn reload_rules(workspace_root: Option<String>) -> Result<RuleCatalog, String> {
+    Ok(rules::reload(workspace_root.as_deref().map(Path::new)))
 }
 
+/// Scan every root of a workspace; `options` narrows the files, rules and
+/// severities reported and caps the number of findings
 #[tauri::command]
-pub async fn run_security_scan(workspace_root: String) -> Result<SecurityScanResult, String> {
+pub async fn run_security_scan(
+    app_handle: AppHandle,
+    workspace_root: String,
+    options: Option<ScanOptions>,
+) -> Result<ScanReport, String> {
     let pb = PathBuf::from(&workspace_root);
     if !pb.exists() {
         return Err("Workspace path does not exist".into());
     }
 
-    let issues = security::scan_workspace(&pb);
-    Ok(SecurityScanResult { issues })
+    let options = options.unwrap_or_default();
+    let workspace_roots = roots::list(&workspace_root)?;
+    let report = if workspace_roots.len() > 1 {
+        security::scan_roots(&workspace_roots, &options)?
+    } else {
+        security::scan_workspace(&pb, &options)?
+    };
+    // Filtered reports leave findings out, which would mark them fixed
+    if options.is_unfiltered() {
+        let found = report.issues.iter().map(Finding::from_issue).collect();
+        findings::record_or_warn(&workspace_root, FindingSource::Scanner, None, found);
+    }
+    notifications::notify_or_warn(
+        &app_handle,
+        NotificationKind::ScanComplete,
+        "Scan complete",
+        &format!(
+            "{} findings in {} files, {} critical",
+            report.total, report.files_scanned, report.by_severity.critical
+        ),
+    );
+    Ok(report)
 }
 
+/// Scan a container image, or the image built from `dockerfile`, with trivy
+/// or grype when installed and the image's package database otherwise
 #[tauri::command]
-pub async fn fetch_juice_shop_challenges(url: String) -> Result<Vec<JuiceShopChallenge>, String> {
-     let client = reqwest::Client::new();
-     let res = client.get(&url)
-        .send()
+pub async fn scan_container_image(
+    workspace_root: String,
+    image: Option<String>,
+    dockerfile: Option<String>,
+    scanner: Option<ContainerScanner>,
+) -> Result<ContainerScan, String> {
+    let dockerfile = dockerfile.map(|path| {
+        let path = PathBuf::from(path);
+        if path.is_relative() { Path::new(&workspace_root).join(path) } else { path }
+    });
+    let scan = containers::scan(image, dockerfile, scanner).await?;
+    let scope = [scan.dockerfile.clone().unwrap_or_else(|| scan.image.clone())];
+    findings::record_or_warn(&workspace_root, FindingSource::Container, Some(&scope), Finding::from_container(&scan, &workspace_root));
+    Ok(scan)
+}
+
+/// YARA rule files of every workspace, and of this one when given
+#[tauri::command]
+pub async fn list_yara_rule_files(workspace_root: Option<String>) -> Result<Vec<YaraRuleFile>, String> {
+    Ok(yara::list_rule_files(workspace_root.as_deref()))
+}
+
+/// Create or replace a YARA rule file; rules that do not compile are refused
+#[tauri::command]
+pub async fn save_yara_rule_file(
+    scope: RuleScope,
+    workspace_root: Option<String>,
+    name: String,
+    source: String,
+) -> Result<YaraRuleFile, String> {
+    yara::save_rule_file(scope, workspace_root.as_deref(), &name, &source)
+}
+
+#[tauri::command]
+pub async fn import_yara_rule_file(scope: RuleScope, workspace_root: Option<String>, path: String) -> Result<YaraRuleFile, String> {
+    yara::import_rule_file(scope, workspace_root.as_deref(), Path::new(&path))
+}
+
+#[tauri::command]
+pub async fn delete_yara_rule_file(scope: RuleScope, workspace_root: Option<String>, name: String) -> Result<(), String> {
+    yara::delete_rule_file(scope, workspace_root.as_deref(), &name)
+}
+
+/// Scan `paths`, or the whole workspace when omitted, with the YARA rules
+#[tauri::command]
+pub async fn run_yara_scan(workspace_root: String, paths: Option<Vec<String>>) -> Result<YaraScanReport, String> {
+    let root = workspace_root.clone();
+    let paths = paths.unwrap_or_default();
+    let whole_workspace = paths.is_empty();
+    let report = tokio::task::spawn_blocking(move || yara::scan(&root, &paths))
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-        
-     let body = res.text().await.map_err(|e| format!("Failed to get text: {}", e))?;
-     
-     // Juice Shop API returns { status: "success", data: [...] }
-     let response: JuiceShopResponse = serde_json::from_str(&body)
-        .map_err(|e| format!("Failed to parse JSON: {} | Body snippet: '{}'", e, &body.chars().take(200).collect::<String>()))?;
-        
-     Ok(response.data)
+        .map_err(|e| format!("YARA scan task failed: {}", e))??;
+    let found = report.matches.iter().map(|m| Finding::from_yara(m, &workspace_root)).collect();
+    let scope = (!whole_workspace).then_some(report.scanned.as_slice());
+    findings::record_or_warn(&workspace_root, FindingSource::Yara, scope, found);
+    Ok(report)
 }
 
+/// Scan a workspace, then keep its findings current in the background:
+/// files reported by `notify_file_saved` are rescanned at once, and other
+/// changes on disk are picked up by polling. Every update emits
+/// `findings-updated` with the workspace's findings.
+#[tauri::command]
+pub async fn start_background_scan(app_handle: AppHandle, workspace_root: String) -> Result<(), String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err("Workspace path does not exist".into());
+    }
+    let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+    // Replacing the sender closes the previous background scan's channel
+    BACKGROUND_SCANS.lock().unwrap().insert(workspace_root, tx);
+
+    tokio::spawn(async move {
+        let initial_root = root.clone();
+        let initial = tokio::task::spawn_blocking(move || {
+            let watcher = PollWatcher::new(security::workspace_files(&initial_root));
+            (watcher, incremental::full_scan(&initial_root))
+        })
+        .await;
+        let Ok((mut watcher, update)) = initial else {
+            log::warn!("Background scan of {} failed to start", root.display());
+            return;
+        };
+        let _ = app_handle.emit("findings-updated", update);
+
+        loop {
+            let saved = tokio::select! {
+                saved = rx.recv() => match saved {
+                    Some(path) => Some(path),
+                    None => break,
+                },
+                _ = tokio::time::sleep(SCAN_POLL_INTERVAL) => None,
+            };
 
+            let scan_root = root.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let update = match saved {
+                    Some(path) => {
+                        let update = incremental::rescan(&scan_root, std::slice::from_ref(&path), &[]);
+                        if update.is_some() {
+                            watcher.mark_seen(&path);
+                        }
+                        update
+                    }
+                    None => {
+                        let changes = watcher.poll(security::workspace_files(&scan_root));
+                        if changes.is_empty() {
+                            None
+                        } else {
+                            incremental::rescan(&scan_root, &changes.changed, &changes.removed)
+                        }
+                    }
+                };
+                (watcher, update)
+            })
+            .await;
+            match result {
+                Ok((returned, update)) => {
+                    watcher = returned;
+                    if let Some(update) = update {
+                        let _ = app_handle.emit("findings-updated", update);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Background scan of {} stopped: {}", root.display(), e);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Rescan a file of a workspace with a background scan as soon as it is saved
+#[tauri::command]
+pub async fn notify_file_saved(workspace_root: String, path: String) -> Result<(), String> {
+    let scans = BACKGROUND_SCANS.lock().unwrap();
+    let tx = scans
+        .get(&workspace_root)
+        .ok_or_else(|| format!("{} has no background scan", workspace_root))?;
+    tx.send(PathBuf::from(path))
+        .map_err(|_| format!("Background scan of {} has stopped", workspace_root))
+}
+
+#[tauri::command]
+pub async fn stop_background_scan(workspace_root: String) -> Result<(), String> {
+    BACKGROUND_SCANS.lock().unwrap().remove(&workspace_root);
+    incremental::forget(Path::new(&workspace_root));
+    Ok(())
+}
+
+/// Findings of a workspace as last updated by its background scan
+#[tauri::command]
+pub async fn get_workspace_findings(workspace_root: String) -> Result<FindingsUpdate, String> {
+    Ok(incremental::snapshot(Path::new(&workspace_root)))
+}
+
+#[tauri::command]
+pub async fn fetch_juice_shop_challenges(url: String) -> Result<Vec<JuiceShopChallenge>, String> {
+    juice_shop::fetch_challenges(&url).await
+}
+
+/// Poll solved status once; emits `juice-shop-challenge-solved` for each newly solved challenge
+#[tauri::command]
+pub async fn poll_juice_shop_progress(app_handle: AppHandle, url: String) -> Result<JuiceShopStatus, String> {
+    let status = juice_shop::poll(&url).await?;
+    emit_newly_solved(&app_handle, &status);
+    Ok(status)
+}
+
+fn emit_newly_solved(app_handle: &AppHandle, status: &JuiceShopStatus) {
+    for key in &status.newly_solved {
+        if let Some(progress) = status.challenges.iter().find(|c| &c.challenge.key == key) {
+            let _ = app_handle.emit("juice-shop-challenge-solved", progress);
+        }
+    }
+}
+
+/// Poll in the background every `interval_secs` until stopped
+#[tauri::command]
+pub async fn start_juice_shop_watch(
+    app_handle: AppHandle,
+    url: String,
+    interval_secs: Option<u64>,
+) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs.unwrap_or(10).max(2));
+    let (tx, mut rx) = oneshot::channel::<()>();
+
+    if let Some(previous) = JUICE_SHOP_WATCHERS.lock().unwrap().insert(url.clone(), tx) {
+        let _ = previous.send(());
+    }
+
+    tokio::spawn(async move {
+        loop {
+            if let Ok(status) = juice_shop::poll(&url).await {
+                emit_newly_solved(&app_handle, &status);
+            }
+            tokio::select! {
+                _ = &mut rx => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_juice_shop_watch(url: String) -> Result<(), String> {
+    if let Some(tx) = JUICE_SHOP_WATCHERS.lock().unwrap().remove(&url) {
+        let _ = tx.send(());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_juice_shop_progress(url: String) -> Result<(), String> {
+    juice_shop::reset_progress(&url)
+}
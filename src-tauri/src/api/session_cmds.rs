@@ -0,0 +1,84 @@
+use crate::services::project::registry::{self, RecentWorkspace};
+use crate::services::project::roots::{self, WorkspaceRoot};
+use crate::services::project::session::{self, WorkspaceSession};
+use crate::services::project::trust::{self, TrustDecision, WorkspaceTrust};
+
+#[tauri::command]
+pub async fn save_workspace_session(workspace_root: String, session: WorkspaceSession) -> Result<(), String> {
+    session::save(&workspace_root, session)
+}
+
+/// Returns None when the workspace has no saved session
+#[tauri::command]
+pub async fn load_workspace_session(workspace_root: String) -> Result<Option<WorkspaceSession>, String> {
+    session::load(&workspace_root)
+}
+
+#[tauri::command]
+pub async fn clear_workspace_session(workspace_root: String) -> Result<(), String> {
+    session::clear(&workspace_root)
+}
+
+/// Record that a workspace folder was opened and refresh its metadata
+#[tauri::command]
+pub async fn register_workspace(workspace_root: String) -> Result<RecentWorkspace, String> {
+    tokio::task::spawn_blocking(move || registry::register(&workspace_root))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Pinned workspaces first, then the most recently opened
+#[tauri::command]
+pub async fn list_recent_workspaces(limit: Option<usize>) -> Result<Vec<RecentWorkspace>, String> {
+    registry::list(limit)
+}
+
+#[tauri::command]
+pub async fn pin_workspace(workspace_root: String, pinned: bool) -> Result<(), String> {
+    registry::set_pinned(&workspace_root, pinned)
+}
+
+#[tauri::command]
+pub async fn remove_recent_workspace(workspace_root: String) -> Result<bool, String> {
+    registry::remove(&workspace_root)
+}
+
+/// Every root of the workspace, the primary one first
+#[tauri::command]
+pub async fn list_workspace_roots(workspace_root: String) -> Result<Vec<WorkspaceRoot>, String> {
+    roots::list(&workspace_root)
+}
+
+#[tauri::command]
+pub async fn add_workspace_root(workspace_root: String, path: String, name: Option<String>) -> Result<WorkspaceRoot, String> {
+    roots::add(&workspace_root, &path, name.as_deref())
+}
+
+/// `root` is the name or path of the root; returns false when there is none
+#[tauri::command]
+pub async fn remove_workspace_root(workspace_root: String, root: String) -> Result<bool, String> {
+    roots::remove(&workspace_root, &root)
+}
+
+/// The trust state of a folder; `unknown` means the user should be asked
+#[tauri::command]
+pub async fn get_workspace_trust(path: String) -> Result<WorkspaceTrust, String> {
+    trust::state(&path)
+}
+
+/// Trust or distrust a folder and everything below it
+#[tauri::command]
+pub async fn set_workspace_trust(path: String, trusted: bool) -> Result<WorkspaceTrust, String> {
+    trust::set(&path, trusted)
+}
+
+#[tauri::command]
+pub async fn list_workspace_trust() -> Result<Vec<TrustDecision>, String> {
+    trust::list()
+}
+
+/// Returns false when no decision was made for exactly this folder
+#[tauri::command]
+pub async fn forget_workspace_trust(path: String) -> Result<bool, String> {
+    trust::forget(&path)
+}
@@ -0,0 +1,119 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+use crate::services::settings::{self, SettingChange, SettingInfo, SettingScope, SettingValue};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+lazy_static::lazy_static! {
+    static ref SETTINGS_WATCHERS: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SettingsChangedEvent {
+    workspace_root: Option<String>,
+    changes: Vec<SettingChange>,
+}
+
+fn emit_changes(app_handle: &AppHandle, workspace_root: Option<String>, changes: Vec<SettingChange>) {
+    if !changes.is_empty() {
+        let _ = app_handle.emit(
+            "settings-changed",
+            SettingsChangedEvent {
+                workspace_root,
+                changes,
+            },
+        );
+    }
+}
+
+#[tauri::command]
+pub async fn get_settings_schema() -> Result<Vec<SettingInfo>, String> {
+    Ok(settings::schema())
+}
+
+#[tauri::command]
+pub async fn get_setting(key: String, workspace_root: Option<String>) -> Result<SettingValue, String> {
+    settings::get(&key, workspace_root.as_deref())
+}
+
+#[tauri::command]
+pub async fn get_all_settings(workspace_root: Option<String>) -> Result<BTreeMap<String, Value>, String> {
+    settings::effective(workspace_root.as_deref())
+}
+
+/// Validate and store a value, emitting `settings-changed` if the effective value moved
+#[tauri::command]
+pub async fn set_setting(
+    app_handle: AppHandle,
+    key: String,
+    value: Value,
+    scope: SettingScope,
+    workspace_root: Option<String>,
+) -> Result<(), String> {
+    let before = settings::effective(workspace_root.as_deref())?;
+    settings::set(&key, value, scope, workspace_root.as_deref())?;
+    let after = settings::effective(workspace_root.as_deref())?;
+    emit_changes(&app_handle, workspace_root, settings::diff(&before, &after));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_setting(
+    app_handle: AppHandle,
+    key: String,
+    scope: SettingScope,
+    workspace_root: Option<String>,
+) -> Result<(), String> {
+    let before = settings::effective(workspace_root.as_deref())?;
+    settings::reset(&key, scope, workspace_root.as_deref())?;
+    let after = settings::effective(workspace_root.as_deref())?;
+    emit_changes(&app_handle, workspace_root, settings::diff(&before, &after));
+    Ok(())
+}
+
+/// Poll the settings files and emit `settings-changed` when they are edited outside the IDE
+#[tauri::command]
+pub async fn watch_settings(app_handle: AppHandle, workspace_root: Option<String>) -> Result<(), String> {
+    let watch_key = workspace_root.clone().unwrap_or_default();
+    let mut snapshot = settings::effective(workspace_root.as_deref())?;
+    let (tx, mut rx) = oneshot::channel::<()>();
+
+    if let Some(previous) = SETTINGS_WATCHERS.lock().unwrap().insert(watch_key, tx) {
+        let _ = previous.send(());
+    }
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut rx => break,
+                _ = tokio::time::sleep(WATCH_INTERVAL) => {}
+            }
+            // A half-written file fails to parse; keep the last good snapshot
+            if let Ok(current) = settings::effective(workspace_root.as_deref()) {
+                let changes = settings::diff(&snapshot, &current);
+                snapshot = current;
+                emit_changes(&app_handle, workspace_root.clone(), changes);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unwatch_settings(workspace_root: Option<String>) -> Result<(), String> {
+    if let Some(tx) = SETTINGS_WATCHERS
+        .lock()
+        .unwrap()
+        .remove(&workspace_root.unwrap_or_default())
+    {
+        let _ = tx.send(());
+    }
+    Ok(())
+}
@@ -6,10 +6,21 @@ use std::thread;
 use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem, MasterPty};
 use uuid::Uuid;
 
+use crate::services::audit;
+use crate::services::listeners;
+use crate::services::processes::{self, ManagedProcess, ProcessKind};
+use crate::services::settings;
+use crate::services::terminal::profiles::{self, ShellProfile};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShellOutput {
+    /// stdout and stderr combined, kept for callers that only display output
     pub output: String,
+    pub stdout: String,
+    pub stderr: String,
     pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub truncated: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,32 +43,57 @@ struct PtySession {
     cwd: String,
     #[allow(dead_code)]
     shell: String,
+    // Partially typed line, used to audit input one command at a time
+    input_line: String,
 }
 
 lazy_static::lazy_static! {
     static ref SESSIONS: Arc<Mutex<HashMap<String, PtySession>>> = Arc::new(Mutex::new(HashMap::new()));
 }
 
-#[tauri::command]
-pub async fn create_terminal_session(cwd: Option<String>, shell: Option<String>) -> Result<TerminalSession, String> {
-    let session_id = Uuid::new_v4().to_string();
-    
-    let pty_system = NativePtySystem::default();
-    
-    // Create PTY with appropriate size
-    let pair = pty_system
-        .openpty(PtySize {
-            rows: 30,
-            cols: 120,
-            pixel_width: 0,
-            pixel_height: 0,
-        })
-        .map_err(|e| format!("Failed to create PTY: {}", e))?;
-        
-    // Determine shell based on parameter or OS default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+}
+
+/// Docker container names and ids match `[A-Za-z0-9][A-Za-z0-9_.-]*`, so a
+/// valid one can never be taken for an option of `docker exec`
+fn is_container_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Resolve the shell executable and arguments for a shell id (or the OS default)
+///
+/// Besides the local shells, `docker:<container>` execs into a running container
+/// and, on Windows, `wsl` / `wsl:<distro>` opens a WSL distro.
+fn resolve_shell(shell: Option<&str>) -> Result<(String, Vec<String>), String> {
+    if let Some(container) = shell.and_then(|s| s.strip_prefix("docker:")) {
+        if !is_container_name(container) {
+            return Err(format!("Invalid container name: {}", container));
+        }
+        // Prefer bash inside the container but fall back to sh for slim images
+        return Ok((
+            "docker".to_string(),
+            vec![
+                "exec".to_string(),
+                "-it".to_string(),
+                container.to_string(),
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "command -v bash >/dev/null && exec bash || exec sh".to_string(),
+            ],
+        ));
+    }
+
     let (shell_path, shell_args): (&str, Vec<&str>) = if cfg!(target_os = "windows") {
-        match shell.as_deref() {
+        match shell {
             Some("cmd") => ("cmd.exe", vec![]),
+            Some("wsl") => ("wsl.exe", vec![]),
+            Some(s) if s.starts_with("wsl:") => ("wsl.exe", vec!["-d", &s[4..]]),
             Some("git-bash") => {
                 // Try common Git Bash locations
                 if std::path::Path::new("C:\\Program Files\\Git\\bin\\bash.exe").exists() {
@@ -76,22 +112,67 @@ pub async fn create_terminal_session(cwd: Option<String>, shell: Option<String>)
     } else {
         ("/bin/bash", vec!["-l"])
     };
+
+    Ok((shell_path.to_string(), shell_args.into_iter().map(String::from).collect()))
+}
+
+#[tauri::command]
+pub async fn create_terminal_session(
+    cwd: Option<String>,
+    shell: Option<String>,
+    profile: Option<String>,
+) -> Result<TerminalSession, String> {
+    let session_id = Uuid::new_v4().to_string();
     
-    let working_dir = cwd.clone().unwrap_or_else(|| {
-        std::env::current_dir()
-            .ok()
-            .and_then(|p| p.to_str().map(String::from))
-            .unwrap_or_else(|| {
-                dirs::home_dir()
-                    .and_then(|p| p.to_str().map(String::from))
-                    .unwrap_or_else(|| String::from("C:\\"))
-            })
-    });
+    // Load the named profile up front so a typo fails before a PTY is allocated
+    let profile = match profile {
+        Some(name) => Some(
+            profiles::get_profile(&name)
+                .ok_or_else(|| format!("Shell profile '{}' not found", name))?,
+        ),
+        None => None,
+    };
+    
+    let pty_system = NativePtySystem::default();
+    
+    // Create PTY with appropriate size
+    let pair = pty_system
+        .openpty(PtySize {
+            rows: 30,
+            cols: 120,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to create PTY: {}", e))?;
+        
+    // Determine shell based on profile, parameter, terminal.defaultShell or OS
+    // default; terminals open at the workspace root, whose settings apply
+    let shell = shell
+        .or_else(|| settings::get_as("terminal.defaultShell", cwd.as_deref(), None::<String>))
+        .filter(|s| !s.is_empty());
+    let (shell_path, shell_args) = match &profile {
+        Some(p) => (p.executable.clone(), p.args.clone()),
+        None => resolve_shell(shell.as_deref())?,
+    };
+    
+    let working_dir = cwd
+        .clone()
+        .or_else(|| profile.as_ref().and_then(|p| p.cwd.clone()))
+        .unwrap_or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .and_then(|p| p.to_str().map(String::from))
+                .unwrap_or_else(|| {
+                    dirs::home_dir()
+                        .and_then(|p| p.to_str().map(String::from))
+                        .unwrap_or_else(|| String::from("C:\\"))
+                })
+        });
     
     // Create command
-    let mut cmd = CommandBuilder::new(shell_path);
+    let mut cmd = CommandBuilder::new(&shell_path);
     for arg in &shell_args {
-        cmd.arg(*arg);
+        cmd.arg(arg);
     }
     
     // Set working directory if it exists
@@ -104,6 +185,13 @@ pub async fn create_terminal_session(cwd: Option<String>, shell: Option<String>)
     cmd.env("TERM", "xterm-256color");
     cmd.env("COLORTERM", "truecolor");
     
+    // Profile env vars are applied last so they can override the defaults
+    if let Some(p) = &profile {
+        for (key, value) in &p.env {
+            cmd.env(key, value);
+        }
+    }
+    
     // Spawn child process
     let child = pair.slave.spawn_command(cmd)
         .map_err(|e| format!("Failed to spawn shell: {}", e))?;
@@ -112,7 +200,7 @@ pub async fn create_terminal_session(cwd: Option<String>, shell: Option<String>)
     let mut reader = pair.master.try_clone_reader()
         .map_err(|e| format!("Failed to clone reader: {}", e))?;
     
-    let writer = pair.master.take_writer()
+    let mut writer = pair.master.take_writer()
         .map_err(|e| format!("Failed to take writer: {}", e))?;
     
     let master = pair.master;
@@ -137,27 +225,52 @@ pub async fn create_terminal_session(cwd: Option<String>, shell: Option<String>)
         }
     });
     
+    // Run profile startup commands (e.g. activating a venv) before handing over the session
+    if let Some(p) = &profile {
+        for line in &p.startup_commands {
+            writer.write_all(format!("{}\r", line).as_bytes())
+                .map_err(|e| format!("Failed to run startup command: {}", e))?;
+        }
+        writer.flush()
+            .map_err(|e| format!("Failed to flush terminal: {}", e))?;
+    }
+    
     let session = PtySession {
         child,
         writer,
         master,
         output_buffer,
         cwd: working_dir.clone(),
-        shell: shell_path.to_string(),
+        shell: shell_path.clone(),
+        input_line: String::new(),
     };
     
-    let mut sessions = SESSIONS.lock().unwrap();
-    sessions.insert(session_id.clone(), session);
+    let pid = session.child.process_id();
+    SESSIONS.lock().unwrap().insert(session_id.clone(), session);
+
+    let id = session_id.clone();
+    processes::register(
+        ManagedProcess::new(&session_id, ProcessKind::Terminal, &shell_path).with_pid(pid),
+        Some(Box::new(move || {
+            if let Some(mut session) = SESSIONS.lock().unwrap().remove(&id) {
+                let _ = session.child.kill();
+            }
+        })),
+    );
     
     Ok(TerminalSession {
         id: session_id,
-        shell: shell_path.to_string(),
+        shell: shell_path,
         cwd: working_dir,
     })
 }
 
 #[tauri::command]
 pub async fn write_to_terminal(session_id: String, data: String) -> Result<(), String> {
+    if listeners::is_session(&session_id) {
+        return listeners::write(&session_id, data.as_bytes());
+    }
+
     let mut sessions = SESSIONS.lock().unwrap();
     
     let session = sessions.get_mut(&session_id)
@@ -169,11 +282,43 @@ pub async fn write_to_terminal(session_id: String, data: String) -> Result<(), S
     session.writer.flush()
         .map_err(|e| format!("Failed to flush terminal: {}", e))?;
     
+    if audit::is_enabled() {
+        track_input_line(&session_id, session, &data);
+    }
+    
     Ok(())
 }
 
+/// Accumulate keystrokes into the session's current line and audit it on Enter
+fn track_input_line(session_id: &str, session: &mut PtySession, data: &str) {
+    // Escape sequences (arrow keys, function keys) arrive as a single write
+    if data.starts_with('\u{1b}') {
+        return;
+    }
+    
+    for ch in data.chars() {
+        match ch {
+            '\r' | '\n' => {
+                let line = std::mem::take(&mut session.input_line);
+                audit::record("terminal", Some(session_id), &line, Some(&session.cwd));
+            }
+            '\u{7f}' | '\u{8}' => {
+                session.input_line.pop();
+            }
+            // Ctrl+C / Ctrl+U discard the line
+            '\u{3}' | '\u{15}' => session.input_line.clear(),
+            c if c.is_control() => {}
+            c => session.input_line.push(c),
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn read_from_terminal(session_id: String, _timeout_ms: Option<u64>) -> Result<String, String> {
+    if listeners::is_session(&session_id) {
+        return listeners::read(&session_id).map(|data| String::from_utf8_lossy(&data).to_string());
+    }
+
     let sessions = SESSIONS.lock().unwrap();
     
     let session = sessions.get(&session_id)
@@ -192,6 +337,11 @@ pub async fn read_from_terminal(session_id: String, _timeout_ms: Option<u64>) ->
 
 #[tauri::command]
 pub async fn close_terminal_session(session_id: String) -> Result<(), String> {
+    if listeners::is_session(&session_id) {
+        listeners::close(&session_id);
+        return Ok(());
+    }
+
     let mut sessions = SESSIONS.lock().unwrap();
     
     if let Some(mut session) = sessions.remove(&session_id) {
@@ -199,12 +349,18 @@ pub async fn close_terminal_session(session_id: String) -> Result<(), String> {
         let _ = session.child.kill();
         let _ = session.child.wait();
     }
+    processes::unregister(&session_id);
     
     Ok(())
 }
 
 #[tauri::command]
 pub async fn resize_terminal(session_id: String, rows: u16, cols: u16) -> Result<(), String> {
+    // Raw sockets have no window size; an upgraded shell can be resized with stty
+    if listeners::is_session(&session_id) {
+        return Ok(());
+    }
+
     let sessions = SESSIONS.lock().unwrap();
     
     let session = sessions.get(&session_id)
@@ -223,12 +379,60 @@ pub async fn resize_terminal(session_id: String, rows: u16, cols: u16) -> Result
 #[tauri::command]
 pub async fn list_terminal_sessions() -> Result<Vec<String>, String> {
     let sessions = SESSIONS.lock().unwrap();
-    Ok(sessions.keys().cloned().collect())
+    let mut ids: Vec<String> = sessions.keys().cloned().collect();
+    ids.extend(
+        listeners::list_callbacks()
+            .into_iter()
+            .filter(|c| c.alive)
+            .map(|c| c.session_id),
+    );
+    Ok(ids)
+}
+
+/// Default cap on captured output per stream for execute_command
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 10 * 1024 * 1024;
+
+/// How long execute_command keeps reading output after the shell exits
+const OUTPUT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Drain a pipe into a shared buffer, keeping at most `max` bytes
+fn capture_stream<R: Read + Send + 'static>(
+    mut reader: R,
+    buffer: Arc<Mutex<Vec<u8>>>,
+    truncated: Arc<Mutex<bool>>,
+    max: usize,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let mut buf = buffer.lock().unwrap();
+                    let room = max.saturating_sub(buf.len());
+                    if n > room {
+                        *truncated.lock().unwrap() = true;
+                    }
+                    // Keep draining past the cap so the child never blocks on a full pipe
+                    buf.extend_from_slice(&chunk[..n.min(room)]);
+                }
+            }
+        }
+    })
 }
 
 #[tauri::command]
-pub async fn execute_command(command: String, cwd: Option<String>) -> Result<ShellOutput, String> {
+pub async fn execute_command(
+    command: String,
+    cwd: Option<String>,
+    timeout_ms: Option<u64>,
+    max_output_bytes: Option<usize>,
+    env: Option<HashMap<String, String>>,
+) -> Result<ShellOutput, String> {
     use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+    
+    audit::record("shell", None, &command, cwd.as_deref());
     
     let shell = if cfg!(target_os = "windows") {
         "cmd"
@@ -245,6 +449,7 @@ pub async fn execute_command(command: String, cwd: Option<String>) -> Result<She
     let mut cmd = Command::new(shell);
     cmd.arg(shell_arg)
         .arg(&command)
+        .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
     
@@ -255,23 +460,83 @@ pub async fn execute_command(command: String, cwd: Option<String>) -> Result<She
         }
     }
     
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to execute command: {}", e))?;
+    if let Some(vars) = env {
+        cmd.envs(vars);
+    }
     
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let combined_output = if stderr.is_empty() {
-        stdout.to_string()
-    } else if stdout.is_empty() {
-        stderr.to_string()
-    } else {
-        format!("{}\n{}", stdout, stderr)
-    };
+    let max_output = max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
     
-    Ok(ShellOutput {
-        output: combined_output,
-        exit_code: output.status.code(),
+    // Waiting (and possibly killing) is blocking work, keep it off the async runtime
+    tokio::task::spawn_blocking(move || {
+        let mut child = cmd.spawn()
+            .map_err(|e| format!("Failed to execute command: {}", e))?;
+        
+        let stdout_buf = Arc::new(Mutex::new(Vec::new()));
+        let stderr_buf = Arc::new(Mutex::new(Vec::new()));
+        let truncated = Arc::new(Mutex::new(false));
+        
+        let readers = vec![
+            capture_stream(child.stdout.take().ok_or("Failed to capture stdout")?, stdout_buf.clone(), truncated.clone(), max_output),
+            capture_stream(child.stderr.take().ok_or("Failed to capture stderr")?, stderr_buf.clone(), truncated.clone(), max_output),
+        ];
+        
+        let deadline = timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms));
+        let mut timed_out = false;
+        
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) => {
+                    if deadline.is_some_and(|d| Instant::now() >= d) {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        timed_out = true;
+                        break None;
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(format!("Failed to wait for command: {}", e)),
+            }
+        };
+        
+        if timed_out {
+            // Grandchildren may still hold the pipes open; take what we have
+            thread::sleep(Duration::from_millis(50));
+        } else {
+            // A background grandchild can inherit the pipes and keep them
+            // open long after the shell exits, so drain for a bounded time
+            // and leave the readers to finish on their own
+            let drain_deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+            while !readers.iter().all(|r| r.is_finished()) && Instant::now() < drain_deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            for reader in readers.into_iter().filter(|r| r.is_finished()) {
+                let _ = reader.join();
+            }
+        }
+        
+        let stdout = String::from_utf8_lossy(&stdout_buf.lock().unwrap()).to_string();
+        let stderr = String::from_utf8_lossy(&stderr_buf.lock().unwrap()).to_string();
+        let combined_output = if stderr.is_empty() {
+            stdout.clone()
+        } else if stdout.is_empty() {
+            stderr.clone()
+        } else {
+            format!("{}\n{}", stdout, stderr)
+        };
+        let truncated = *truncated.lock().unwrap();
+        
+        Ok(ShellOutput {
+            output: combined_output,
+            stdout,
+            stderr,
+            exit_code: status.and_then(|s| s.code()),
+            timed_out,
+            truncated,
+        })
     })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[tauri::command]
@@ -297,3 +562,83 @@ pub async fn change_directory(path: String) -> Result<(), String> {
     std::env::set_current_dir(&path)
         .map_err(|e| format!("Failed to change directory: {}", e))
 }
+
+#[tauri::command]
+pub async fn list_shell_profiles() -> Result<Vec<ShellProfile>, String> {
+    Ok(profiles::load_profiles())
+}
+
+#[tauri::command]
+pub async fn save_shell_profile(profile: ShellProfile) -> Result<(), String> {
+    profiles::upsert_profile(profile)
+}
+
+#[tauri::command]
+pub async fn delete_shell_profile(name: String) -> Result<(), String> {
+    profiles::delete_profile(&name)
+}
+
+/// Decode wsl.exe output, which is UTF-16LE when redirected to a pipe
+fn decode_wsl_output(bytes: &[u8]) -> String {
+    if bytes.contains(&0) {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// List installed WSL distributions (empty on non-Windows hosts)
+#[tauri::command]
+pub async fn list_wsl_distros() -> Result<Vec<String>, String> {
+    if !cfg!(target_os = "windows") {
+        return Ok(Vec::new());
+    }
+
+    let output = std::process::Command::new("wsl.exe")
+        .args(["--list", "--quiet"])
+        .output()
+        .map_err(|e| format!("Failed to run wsl.exe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("wsl.exe failed: {}", decode_wsl_output(&output.stderr).trim()));
+    }
+
+    Ok(decode_wsl_output(&output.stdout)
+        .lines()
+        .map(|l| l.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}').to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+/// List running Docker containers that can be used as terminal targets
+#[tauri::command]
+pub async fn list_docker_containers() -> Result<Vec<ContainerInfo>, String> {
+    let output = std::process::Command::new("docker")
+        .args(["ps", "--format", "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}"])
+        .output()
+        .map_err(|e| format!("Failed to run docker: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("docker ps failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 4 {
+                return None;
+            }
+            Some(ContainerInfo {
+                id: parts[0].to_string(),
+                name: parts[1].to_string(),
+                image: parts[2].to_string(),
+                status: parts[3].to_string(),
+            })
+        })
+        .collect())
+}
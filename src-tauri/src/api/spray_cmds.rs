@@ -0,0 +1,61 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::services::spray::{self, SprayConfig, SprayControl, SprayEvent, SprayResult};
+
+lazy_static::lazy_static! {
+    static ref SPRAY_JOBS: Arc<Mutex<HashMap<String, Arc<SprayControl>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SprayEventPayload {
+    job_id: String,
+    event: SprayEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SprayCompletePayload {
+    job_id: String,
+    result: Option<SprayResult>,
+    error: Option<String>,
+}
+
+/// Start spraying credentials at an allowlisted lab service. Attempts and hits
+/// stream as `spray-event`, and `spray-complete` fires when the job ends.
+#[tauri::command]
+pub async fn start_spray(app_handle: AppHandle, config: SprayConfig) -> Result<String, String> {
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let control = Arc::new(SprayControl::default());
+    SPRAY_JOBS.lock().unwrap().insert(job_id.clone(), control.clone());
+
+    let id = job_id.clone();
+    tokio::spawn(async move {
+        let emit = |event: SprayEvent| {
+            let _ = app_handle.emit("spray-event", SprayEventPayload { job_id: id.clone(), event });
+        };
+        let result = spray::run(&id, config, &control, emit).await;
+        SPRAY_JOBS.lock().unwrap().remove(&id);
+
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(e)),
+        };
+        let _ = app_handle.emit("spray-complete", SprayCompletePayload { job_id: id.clone(), result, error });
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub async fn cancel_spray(job_id: String) -> Result<(), String> {
+    let control = SPRAY_JOBS
+        .lock()
+        .unwrap()
+        .get(&job_id)
+        .cloned()
+        .ok_or_else(|| format!("Spray job {} not found", job_id))?;
+    control.cancel();
+    Ok(())
+}
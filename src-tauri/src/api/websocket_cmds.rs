@@ -0,0 +1,216 @@
+//! WebSocket client
+//!
+//! Opens ws/wss connections, streams received frames to the frontend as
+//! `ws-message` events and keeps saved connection profiles in ~/.ctr/ws_profiles.json.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::services::http_client::HttpHeader;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+lazy_static::lazy_static! {
+    static ref WS_CONNECTIONS: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Message>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsProfile {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<HttpHeader>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WsFrameEvent {
+    connection_id: String,
+    /// "text", "binary", "ping", "pong" or "close"
+    kind: String,
+    /// Frame payload; base64 for binary frames
+    data: String,
+    timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WsClosedEvent {
+    connection_id: String,
+    reason: Option<String>,
+}
+
+fn frame_event(connection_id: &str, message: &Message) -> Option<WsFrameEvent> {
+    let (kind, data) = match message {
+        Message::Text(text) => ("text", text.clone()),
+        Message::Binary(bytes) => ("binary", BASE64.encode(bytes)),
+        Message::Ping(bytes) => ("ping", BASE64.encode(bytes)),
+        Message::Pong(bytes) => ("pong", BASE64.encode(bytes)),
+        Message::Close(frame) => (
+            "close",
+            frame
+                .as_ref()
+                .map(|f| format!("{} {}", u16::from(f.code), f.reason))
+                .unwrap_or_default(),
+        ),
+        Message::Frame(_) => return None,
+    };
+
+    Some(WsFrameEvent {
+        connection_id: connection_id.to_string(),
+        kind: kind.to_string(),
+        data,
+        timestamp: now_millis(),
+    })
+}
+
+/// Open a WebSocket connection and return its id
+#[tauri::command]
+pub async fn ws_connect(
+    app_handle: AppHandle,
+    url: String,
+    headers: Option<Vec<HttpHeader>>,
+) -> Result<String, String> {
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid WebSocket URL: {}", e))?;
+
+    for header in headers.unwrap_or_default() {
+        let name = HeaderName::from_bytes(header.name.as_bytes())
+            .map_err(|e| format!("Invalid header name '{}': {}", header.name, e))?;
+        let value = HeaderValue::from_str(&header.value)
+            .map_err(|e| format!("Invalid header value for '{}': {}", header.name, e))?;
+        request.headers_mut().insert(name, value);
+    }
+
+    let (stream, _response) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| format!("Failed to connect: {}", e))?;
+    let (mut sink, mut source) = stream.split();
+
+    let connection_id = uuid::Uuid::new_v4().to_string();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+    WS_CONNECTIONS
+        .lock()
+        .unwrap()
+        .insert(connection_id.clone(), tx);
+
+    // Writer: forward queued frames to the socket
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let closing = matches!(message, Message::Close(_));
+            if sink.send(message).await.is_err() || closing {
+                break;
+            }
+        }
+    });
+
+    // Reader: stream every received frame to the frontend
+    let id = connection_id.clone();
+    tokio::spawn(async move {
+        let mut reason = None;
+        while let Some(frame) = source.next().await {
+            match frame {
+                Ok(message) => {
+                    if let Some(event) = frame_event(&id, &message) {
+                        let _ = app_handle.emit("ws-message", event);
+                    }
+                }
+                Err(e) => {
+                    reason = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        WS_CONNECTIONS.lock().unwrap().remove(&id);
+        let _ = app_handle.emit(
+            "ws-closed",
+            WsClosedEvent {
+                connection_id: id,
+                reason,
+            },
+        );
+    });
+
+    Ok(connection_id)
+}
+
+/// Send a frame; with `binary` set, `data` is base64-decoded and sent as a binary frame
+#[tauri::command]
+pub async fn ws_send(connection_id: String, data: String, binary: Option<bool>) -> Result<(), String> {
+    let message = if binary.unwrap_or(false) {
+        let bytes = BASE64
+            .decode(&data)
+            .map_err(|e| format!("Invalid base64 data: {}", e))?;
+        Message::Binary(bytes)
+    } else {
+        Message::Text(data)
+    };
+
+    let connections = WS_CONNECTIONS.lock().unwrap();
+    let tx = connections
+        .get(&connection_id)
+        .ok_or_else(|| format!("Connection {} not found", connection_id))?;
+    tx.send(message)
+        .map_err(|_| "Connection is closed".to_string())
+}
+
+#[tauri::command]
+pub async fn ws_disconnect(connection_id: String) -> Result<(), String> {
+    let tx = WS_CONNECTIONS
+        .lock()
+        .unwrap()
+        .remove(&connection_id)
+        .ok_or_else(|| format!("Connection {} not found", connection_id))?;
+    let _ = tx.send(Message::Close(None));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_ws_connections() -> Result<Vec<String>, String> {
+    Ok(WS_CONNECTIONS.lock().unwrap().keys().cloned().collect())
+}
+
+fn profiles_file() -> Result<std::path::PathBuf, String> {
+    Ok(ctr_dir()?.join("ws_profiles.json"))
+}
+
+#[tauri::command]
+pub async fn list_ws_profiles() -> Result<Vec<WsProfile>, String> {
+    Ok(load_json(&profiles_file()?))
+}
+
+#[tauri::command]
+pub async fn save_ws_profile(profile: WsProfile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if !profile.url.starts_with("ws://") && !profile.url.starts_with("wss://") {
+        return Err("WebSocket URL must start with ws:// or wss://".to_string());
+    }
+
+    let path = profiles_file()?;
+    let mut profiles: Vec<WsProfile> = load_json(&path);
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+    save_json(&path, &profiles)
+}
+
+#[tauri::command]
+pub async fn delete_ws_profile(name: String) -> Result<(), String> {
+    let path = profiles_file()?;
+    let mut profiles: Vec<WsProfile> = load_json(&path);
+    profiles.retain(|p| p.name != name);
+    save_json(&path, &profiles)
+}
@@ -1,6 +1,7 @@
 mod api;
 mod services;
 mod analysis;
+mod utils;
 
 use api::{
   editor_cmds,
@@ -15,24 +16,53 @@ use api::{
   extension_cmds,
   search_cmds,
   prover_cmds,
+  audit_cmds,
+  http_client,
+  proxy_cmds,
+  fuzzer,
+  websocket_cmds,
+  recon_cmds,
+  intel_cmds,
+  crypto_cmds,
+  crack_cmds,
+  listener_cmds,
+  payload_cmds,
+  lab_cmds,
+  ctf_cmds,
+  progress_cmds,
+  report_cmds,
+  evidence_cmds,
+  settings_cmds,
+  session_cmds,
+  extension_host_cmds,
+  findings_cmds,
+  diagnostics_cmds,
+  notification_cmds,
+  secrets_cmds,
+  process_cmds,
+  policy_cmds,
+  pcap_cmds,
+  log_analysis_cmds,
+  binary_cmds,
+  spray_cmds,
+  phishing_cmds,
+  scheduler_cmds,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+  services::logging::init();
+  services::processes::reap_orphans();
+
   tauri::Builder::default()
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_shell::init())
+    .plugin(tauri_plugin_notification::init())
     .setup(|app| {
-      if cfg!(debug_assertions) {
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .build(),
-        )?;
-      }
+      services::scheduler::start(app.handle().clone());
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![
+    .invoke_handler(services::policy::guarded(tauri::generate_handler![
       // Editor commands
       editor_cmds::read_file,
       editor_cmds::write_file,
@@ -40,8 +70,18 @@ pub fn run() {
       editor_cmds::delete_file,
       editor_cmds::create_directory,
       editor_cmds::list_directory,
+      editor_cmds::cancel_fs_operation,
       editor_cmds::get_home_directory,
       editor_cmds::rename_file,
+      editor_cmds::set_permissions,
+      editor_cmds::detect_language,
+      editor_cmds::save_draft,
+      editor_cmds::list_drafts,
+      editor_cmds::recover_draft,
+      editor_cmds::discard_draft,
+      editor_cmds::list_file_templates,
+      editor_cmds::create_from_template,
+      editor_cmds::batch_operation,
       // Shell commands - PTY based
       shell_cmds::create_terminal_session,
       shell_cmds::write_to_terminal,
@@ -49,6 +89,13 @@ pub fn run() {
       shell_cmds::close_terminal_session,
       shell_cmds::resize_terminal,
       shell_cmds::list_terminal_sessions,
+      // Shell profiles
+      shell_cmds::list_shell_profiles,
+      shell_cmds::save_shell_profile,
+      shell_cmds::delete_shell_profile,
+      // Terminal targets
+      shell_cmds::list_wsl_distros,
+      shell_cmds::list_docker_containers,
       // Shell commands - Legacy
       shell_cmds::execute_command,
       shell_cmds::get_shell_info,
@@ -66,9 +113,17 @@ pub fn run() {
       interactive_runner::list_interactive_processes,
       // AI commands
       ai_cmds::ai_chat,      ai_cmds::ai_code_completion,
+      ai_cmds::ai_list_model_catalog,
+      ai_cmds::ai_list_local_models,
+      ai_cmds::ai_download_model,
+      ai_cmds::ai_cancel_model_download,
+      ai_cmds::ai_delete_model,
+      ai_cmds::ai_register_model,
+      ai_cmds::ai_triage_findings,
       ai_cmds::ai_code_explain,
       // Git commands
       git_cmds::git_status,
+      git_cmds::git_file_statuses,
       git_cmds::git_commit,
       git_cmds::git_add,
       git_cmds::git_push,
@@ -77,8 +132,24 @@ pub fn run() {
       git_cmds::git_create_branch,
       git_cmds::git_checkout_branch,
       git_cmds::git_log,
+      git_cmds::git_signing_config,
       git_cmds::git_init,
       git_cmds::git_clone,
+      git_cmds::git_cancel_clone,
+      git_cmds::git_rebase_plan,
+      git_cmds::git_rebase_start,
+      git_cmds::git_rebase_status,
+      git_cmds::git_rebase_continue,
+      git_cmds::git_rebase_skip,
+      git_cmds::git_rebase_abort,
+      git_cmds::git_get_conflict_versions,
+      git_cmds::git_write_merge_resolution,
+      git_cmds::git_list_submodules,
+      git_cmds::git_submodule_init_update,
+      git_cmds::git_add_submodule,
+      git_cmds::git_list_worktrees,
+      git_cmds::git_add_worktree,
+      git_cmds::git_remove_worktree,
       // LSP commands
       lsp_cmds::lsp_initialize,
       lsp_cmds::lsp_completion,
@@ -86,11 +157,33 @@ pub fn run() {
       // Security commands
       security_cmds::scan_file_for_issues,
       security_cmds::run_security_scan,
+      security_cmds::scan_container_image,
+      security_cmds::list_yara_rule_files,
+      security_cmds::save_yara_rule_file,
+      security_cmds::import_yara_rule_file,
+      security_cmds::delete_yara_rule_file,
+      security_cmds::run_yara_scan,
       security_cmds::fetch_juice_shop_challenges,
+      security_cmds::poll_juice_shop_progress,
+      security_cmds::import_semgrep_rules,
+      security_cmds::reload_rules,
+      security_cmds::start_background_scan,
+      security_cmds::notify_file_saved,
+      security_cmds::stop_background_scan,
+      security_cmds::get_workspace_findings,
+      security_cmds::start_juice_shop_watch,
+      security_cmds::stop_juice_shop_watch,
+      security_cmds::reset_juice_shop_progress,
       // Exploit commands
       exploit_cmds::get_exploit_payloads,
       exploit_cmds::run_exploit_simulation,
       exploit_cmds::run_exploit_with_custom_payload,
+      exploit_cmds::encode_exploit_payload,
+      exploit_cmds::get_reverse_shell_payloads,
+      exploit_cmds::verify_payload,
+      exploit_cmds::get_payload_verifications,
+      exploit_cmds::get_lab_targets,
+      exploit_cmds::set_lab_targets,
       // Extension commands
       extension_cmds::fetch_marketplace,
       extension_cmds::search_marketplace,
@@ -100,15 +193,255 @@ pub fn run() {
       extension_cmds::enable_extension,
       extension_cmds::disable_extension,
       extension_cmds::uninstall_extension,
+      extension_cmds::list_versions,
+      extension_cmds::pin_extension,
+      extension_cmds::unpin_extension,
+      extension_cmds::check_updates,
+      extension_cmds::verify_extension_integrity,
+      extension_cmds::export_extension_profile,
+      extension_cmds::apply_extension_profile,
+      extension_cmds::list_available_themes,
+      extension_cmds::get_theme_tokens,
+      extension_cmds::list_extension_languages,
+      extension_cmds::get_language_support,
+      extension_cmds::get_grammar_by_scope,
       // Search commands
       search_cmds::search_in_files,
       search_cmds::replace_in_files,
+      search_cmds::get_todo_index,
+      search_cmds::start_todo_watch,
+      search_cmds::stop_todo_watch,
       // Exploit Prover commands
       prover_cmds::prove_exploitability,
       prover_cmds::quick_scan_sinks,
       prover_cmds::index_workspace,
       prover_cmds::analyze_cross_file,
-    ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+      prover_cmds::detect_exploit_chains,
+      prover_cmds::mark_false_positive,
+      prover_cmds::unmark_false_positive,
+      prover_cmds::list_false_positives,
+      prover_cmds::export_triage,
+      prover_cmds::import_triage,
+      prover_cmds::prover_cache_stats,
+      prover_cmds::clear_prover_cache,
+      prover_cmds::watch_file,
+      prover_cmds::update_watched_source,
+      prover_cmds::unwatch_file,
+      // Audit log commands
+      audit_cmds::get_audit_enabled,
+      audit_cmds::set_audit_enabled,
+      audit_cmds::query_audit_log,
+      audit_cmds::export_audit_log,
+      // HTTP client commands
+      http_client::send_http_request,
+      http_client::list_http_collections,
+      http_client::save_http_request,
+      http_client::delete_http_request,
+      http_client::delete_http_collection,
+      http_client::import_openapi_spec,
+      http_client::import_postman_collection,
+      http_client::export_postman_collection,
+      // Intercepting proxy commands
+      proxy_cmds::start_proxy,
+      proxy_cmds::stop_proxy,
+      proxy_cmds::get_proxy_status,
+      proxy_cmds::get_proxy_history,
+      proxy_cmds::clear_proxy_history,
+      proxy_cmds::get_proxy_rules,
+      proxy_cmds::set_proxy_rules,
+      proxy_cmds::get_proxy_ca_cert_path,
+      proxy_cmds::proxy_send_to_repeater,
+      // Fuzzer commands
+      fuzzer::start_fuzz,
+      fuzzer::pause_fuzz,
+      fuzzer::resume_fuzz,
+      fuzzer::cancel_fuzz,
+      // WebSocket client commands
+      websocket_cmds::ws_connect,
+      websocket_cmds::ws_send,
+      websocket_cmds::ws_disconnect,
+      websocket_cmds::list_ws_connections,
+      websocket_cmds::list_ws_profiles,
+      websocket_cmds::save_ws_profile,
+      websocket_cmds::delete_ws_profile,
+      // DNS recon commands
+      recon_cmds::dns_lookup,
+      recon_cmds::dns_reverse_lookup,
+      recon_cmds::dns_zone_transfer,
+      recon_cmds::dns_enumerate_subdomains,
+      // Threat intel commands
+      intel_cmds::get_intel_config,
+      intel_cmds::set_intel_config,
+      intel_cmds::search_cves,
+      intel_cmds::get_cve_details,
+      intel_cmds::search_github_advisories,
+      intel_cmds::search_exploit_db,
+      intel_cmds::list_dependencies,
+      intel_cmds::scan_dependencies,
+      intel_cmds::check_dependency_licenses,
+      intel_cmds::import_nmap_scan,
+      intel_cmds::get_recon_hosts,
+      intel_cmds::vt_lookup_hash,
+      intel_cmds::vt_lookup_url,
+      intel_cmds::hash_file,
+      // Encoder pipeline commands
+      crypto_cmds::crypto_transform,
+      // Hash cracking commands
+      crack_cmds::identify_hash,
+      crack_cmds::detect_crack_tools,
+      crack_cmds::start_crack,
+      crack_cmds::cancel_crack,
+      // Credential spraying commands
+      spray_cmds::start_spray,
+      spray_cmds::cancel_spray,
+      // Phishing simulation commands
+      phishing_cmds::get_smtp_config,
+      phishing_cmds::set_smtp_config,
+      phishing_cmds::preview_phishing_email,
+      phishing_cmds::send_phishing_campaign,
+      phishing_cmds::list_phishing_campaigns,
+      phishing_cmds::scaffold_phishing_landing,
+      phishing_cmds::start_phishing_tracker,
+      phishing_cmds::stop_phishing_tracker,
+      phishing_cmds::get_phishing_tracker,
+      phishing_cmds::list_phishing_events,
+      // Reverse shell listener commands
+      listener_cmds::start_listener,
+      listener_cmds::stop_listener,
+      listener_cmds::list_listeners,
+      listener_cmds::list_callbacks,
+      listener_cmds::upgrade_callback_shell,
+      // Payload library commands
+      payload_cmds::list_user_payloads,
+      payload_cmds::save_user_payload,
+      payload_cmds::delete_user_payload,
+      payload_cmds::search_user_payloads,
+      payload_cmds::list_payload_tags,
+      payload_cmds::export_user_payloads,
+      payload_cmds::import_user_payloads,
+      // Practice lab commands
+      lab_cmds::list_vulnerable_templates,
+      lab_cmds::scaffold_vulnerable_app,
+      lab_cmds::get_vulnerable_app_solutions,
+      lab_cmds::start_lab_environment,
+      lab_cmds::stop_lab_environment,
+      lab_cmds::list_lab_environments,
+      // CTF challenge pack commands
+      ctf_cmds::load_ctf_pack,
+      ctf_cmds::list_ctf_packs,
+      ctf_cmds::remove_ctf_pack,
+      ctf_cmds::start_ctf_challenge,
+      ctf_cmds::submit_ctf_flag,
+      ctf_cmds::reveal_ctf_hint,
+      ctf_cmds::get_ctf_progress,
+      ctf_cmds::list_ctf_completions,
+      ctf_cmds::hash_ctf_flag,
+      // Scoring and progress commands
+      progress_cmds::record_progress_event,
+      progress_cmds::list_progress_events,
+      progress_cmds::get_leaderboard,
+      progress_cmds::export_leaderboard,
+      progress_cmds::get_skill_summaries,
+      progress_cmds::reset_progress,
+      // Report builder commands
+      report_cmds::create_report,
+      report_cmds::list_reports,
+      report_cmds::get_report,
+      report_cmds::update_report,
+      report_cmds::delete_report,
+      report_cmds::add_report_finding,
+      report_cmds::update_report_finding,
+      report_cmds::remove_report_finding,
+      report_cmds::reorder_report_findings,
+      report_cmds::add_report_licenses,
+      report_cmds::preview_report_markdown,
+      report_cmds::export_report,
+      report_cmds::attach_evidence_to_finding,
+      report_cmds::detach_evidence_from_finding,
+      // Evidence capture commands
+      evidence_cmds::capture_window_evidence,
+      evidence_cmds::capture_region_evidence,
+      evidence_cmds::list_evidence,
+      evidence_cmds::verify_evidence,
+      evidence_cmds::delete_evidence,
+      // Settings commands
+      settings_cmds::get_settings_schema,
+      settings_cmds::get_setting,
+      settings_cmds::get_all_settings,
+      settings_cmds::set_setting,
+      settings_cmds::reset_setting,
+      settings_cmds::watch_settings,
+      settings_cmds::unwatch_settings,
+      // Workspace session commands
+      session_cmds::save_workspace_session,
+      session_cmds::load_workspace_session,
+      session_cmds::clear_workspace_session,
+      session_cmds::register_workspace,
+      session_cmds::list_recent_workspaces,
+      session_cmds::pin_workspace,
+      session_cmds::remove_recent_workspace,
+      session_cmds::list_workspace_roots,
+      session_cmds::add_workspace_root,
+      session_cmds::remove_workspace_root,
+      session_cmds::get_workspace_trust,
+      session_cmds::set_workspace_trust,
+      session_cmds::list_workspace_trust,
+      session_cmds::forget_workspace_trust,
+      // Extension host commands
+      extension_host_cmds::start_extension_host,
+      extension_host_cmds::stop_extension_host,
+      extension_host_cmds::get_extension_host_status,
+      extension_host_cmds::activate_hosted_extension,
+      extension_host_cmds::execute_extension_command,
+      // Findings store commands
+      findings_cmds::list_findings,
+      findings_cmds::set_finding_status,
+      findings_cmds::get_findings_summary,
+      // Logging and diagnostics commands
+      diagnostics_cmds::get_log_levels,
+      diagnostics_cmds::set_log_level,
+      diagnostics_cmds::get_recent_logs,
+      diagnostics_cmds::export_diagnostics,
+      // Notification commands
+      notification_cmds::send_notification,
+      // Secret storage commands
+      secrets_cmds::list_secrets,
+      secrets_cmds::has_secret,
+      secrets_cmds::set_secret,
+      secrets_cmds::delete_secret,
+      // Process registry commands
+      process_cmds::list_processes,
+      process_cmds::stop_process,
+      // Command policy commands
+      policy_cmds::get_command_policy,
+      policy_cmds::set_command_policy,
+      policy_cmds::list_command_classes,
+      // Packet capture commands
+      pcap_cmds::open_capture,
+      pcap_cmds::get_capture_summary,
+      pcap_cmds::get_capture_packets,
+      pcap_cmds::get_capture_packet,
+      pcap_cmds::close_capture,
+      // Log analysis commands
+      log_analysis_cmds::analyze_logs,
+      // Binary triage commands
+      binary_cmds::get_binary_info,
+      binary_cmds::get_binary_strings,
+      binary_cmds::get_binary_indicators,
+      // Scheduler commands
+      scheduler_cmds::list_schedules,
+      scheduler_cmds::create_schedule,
+      scheduler_cmds::update_schedule,
+      scheduler_cmds::set_schedule_enabled,
+      scheduler_cmds::delete_schedule,
+      scheduler_cmds::run_schedule_now,
+      scheduler_cmds::preview_cron_expression,
+    ]))
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|_app_handle, event| {
+      if let tauri::RunEvent::Exit = event {
+        services::processes::shutdown_all();
+      }
+    });
 }
@@ -0,0 +1,105 @@
+//! AI provider requests
+//!
+//! Sends a prompt to the backend chosen by the `ai.provider`, `ai.model` and
+//! `ai.endpoint` settings: a self-hosted Ollama server, or the OpenAI or
+//! Anthropic APIs with their key taken from the keychain, or from
+//! OPENAI_API_KEY or ANTHROPIC_API_KEY when none is stored.
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::services::{secrets, settings};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Model used when `ai.model` is empty
+fn default_model(provider: &str) -> &'static str {
+    match provider {
+        "openai" => "gpt-4o-mini",
+        "anthropic" => "claude-3-5-haiku-latest",
+        _ => "qwen2.5-coder",
+    }
+}
+
+fn api_key(secret: &str, variable: &str) -> Result<String, String> {
+    secrets::get_or_none("ai", secret)
+        .or_else(|| std::env::var(variable).ok().filter(|k| !k.trim().is_empty()))
+        .ok_or_else(|| format!("No ai/{} secret is stored and {} is not set", secret, variable))
+}
+
+fn text_at(body: &Value, pointer: &str) -> Option<String> {
+    body.pointer(pointer).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Send `system` and `prompt` to the configured provider and return its reply
+pub async fn complete(system: &str, prompt: &str, max_tokens: u32, workspace_root: Option<&str>) -> Result<String, String> {
+    let provider: String = settings::get_as("ai.provider", workspace_root, "ollama".to_string());
+    let model: String = settings::get_as("ai.model", workspace_root, String::new());
+    let model = if model.trim().is_empty() { default_model(&provider).to_string() } else { model };
+    let endpoint: String = settings::get_as("ai.endpoint", workspace_root, "http://localhost:11434".to_string());
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let (request, pointer) = match provider.as_str() {
+        "ollama" => (
+            client.post(format!("{}/api/chat", endpoint.trim_end_matches('/'))).json(&json!({
+                "model": model,
+                "stream": false,
+                "options": { "num_predict": max_tokens },
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": prompt },
+                ],
+            })),
+            "/message/content",
+        ),
+        "openai" => (
+            client
+                .post("https://api.openai.com/v1/chat/completions")
+                .bearer_auth(api_key("openai_api_key", "OPENAI_API_KEY")?)
+                .json(&json!({
+                    "model": model,
+                    "max_tokens": max_tokens,
+                    "messages": [
+                        { "role": "system", "content": system },
+                        { "role": "user", "content": prompt },
+                    ],
+                })),
+            "/choices/0/message/content",
+        ),
+        "anthropic" => (
+            client
+                .post("https://api.anthropic.com/v1/messages")
+                .header("x-api-key", api_key("anthropic_api_key", "ANTHROPIC_API_KEY")?)
+                .header("anthropic-version", "2023-06-01")
+                .json(&json!({
+                    "model": model,
+                    "max_tokens": max_tokens,
+                    "system": system,
+                    "messages": [{ "role": "user", "content": prompt }],
+                })),
+            "/content/0/text",
+        ),
+        other => return Err(format!("Unknown AI provider: {}", other)),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", provider, e))?;
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {} response: {}", provider, e))?;
+    if !status.is_success() {
+        let message = text_at(&body, "/error/message")
+            .or_else(|| text_at(&body, "/error"))
+            .unwrap_or_else(|| status.to_string());
+        return Err(format!("{} returned an error: {}", provider, message));
+    }
+    text_at(&body, pointer).ok_or_else(|| format!("{} returned no text", provider))
+}
@@ -0,0 +1,534 @@
+//! Local model catalog and downloads
+//!
+//! Recommended GGUF models for running the AI features without internet
+//! access, downloaded into ~/.ctr/models. Downloads resume from the partial
+//! file after a cancel or a dropped connection, and are checked against the
+//! SHA-256 from the catalog or, failing that, the one Hugging Face publishes
+//! for the file. Downloaded models can then be registered with the Ollama
+//! server at `ai.endpoint`. A classroom can extend or replace the built-in
+//! list with ~/.ctr/models/catalog.json.
+
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::services::{secrets, settings};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+/// Minimum time between two progress events of a download
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+lazy_static::lazy_static! {
+    /// Model id -> cancellation flag of downloads still running
+    static ref DOWNLOADS: Mutex<HashMap<String, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogModel {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Parameter count, e.g. "7B"
+    pub parameters: String,
+    /// GGUF quantization, e.g. "Q4_K_M"
+    pub quantization: String,
+    /// Approximate download size
+    pub size_bytes: u64,
+    pub context_length: u32,
+    /// Hugging Face repository and file
+    pub repo: String,
+    pub file: String,
+    /// Expected checksum; looked up on Hugging Face when absent
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// A catalog model with its state on this machine
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogListing {
+    #[serde(flatten)]
+    pub model: CatalogModel,
+    pub downloaded: bool,
+    /// Bytes of an interrupted download that would be resumed
+    pub partial_bytes: u64,
+    pub registered_as: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModel {
+    pub id: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub downloaded_at: u64,
+    /// Name of the model on the Ollama server once registered
+    #[serde(default)]
+    pub registered_as: Option<String>,
+}
+
+/// Payload of the `model-download-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub model_id: String,
+    /// "downloading", "verifying" or "done"
+    pub stage: String,
+    pub downloaded: u64,
+    /// None when the server does not say
+    pub total: Option<u64>,
+    pub bytes_per_sec: u64,
+}
+
+fn builtin_catalog() -> Vec<CatalogModel> {
+    let model = |id: &str,
+                 name: &str,
+                 description: &str,
+                 parameters: &str,
+                 quantization: &str,
+                 size_bytes: u64,
+                 context_length: u32,
+                 repo: &str,
+                 file: &str| CatalogModel {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        parameters: parameters.to_string(),
+        quantization: quantization.to_string(),
+        size_bytes,
+        context_length,
+        repo: repo.to_string(),
+        file: file.to_string(),
+        sha256: None,
+    };
+    vec![
+        model(
+            "qwen2.5-coder-1.5b-q4_k_m",
+            "Qwen2.5 Coder 1.5B Instruct",
+            "Small code model for completion on laptops without a GPU",
+            "1.5B",
+            "Q4_K_M",
+            986_000_000,
+            32_768,
+            "bartowski/Qwen2.5-Coder-1.5B-Instruct-GGUF",
+            "Qwen2.5-Coder-1.5B-Instruct-Q4_K_M.gguf",
+        ),
+        model(
+            "qwen2.5-coder-1.5b-q8_0",
+            "Qwen2.5 Coder 1.5B Instruct",
+            "Higher quality quantization of the small code model",
+            "1.5B",
+            "Q8_0",
+            1_650_000_000,
+            32_768,
+            "bartowski/Qwen2.5-Coder-1.5B-Instruct-GGUF",
+            "Qwen2.5-Coder-1.5B-Instruct-Q8_0.gguf",
+        ),
+        model(
+            "qwen2.5-coder-7b-q4_k_m",
+            "Qwen2.5 Coder 7B Instruct",
+            "Recommended code model for completion and code explanations; needs 8 GB of RAM",
+            "7B",
+            "Q4_K_M",
+            4_680_000_000,
+            32_768,
+            "bartowski/Qwen2.5-Coder-7B-Instruct-GGUF",
+            "Qwen2.5-Coder-7B-Instruct-Q4_K_M.gguf",
+        ),
+        model(
+            "qwen2.5-coder-7b-q8_0",
+            "Qwen2.5 Coder 7B Instruct",
+            "Higher quality quantization of the 7B code model; needs 12 GB of RAM",
+            "7B",
+            "Q8_0",
+            8_100_000_000,
+            32_768,
+            "bartowski/Qwen2.5-Coder-7B-Instruct-GGUF",
+            "Qwen2.5-Coder-7B-Instruct-Q8_0.gguf",
+        ),
+        model(
+            "llama-3.1-8b-q4_k_m",
+            "Llama 3.1 8B Instruct",
+            "General model for chat, triage and report writing",
+            "8B",
+            "Q4_K_M",
+            4_920_000_000,
+            131_072,
+            "bartowski/Meta-Llama-3.1-8B-Instruct-GGUF",
+            "Meta-Llama-3.1-8B-Instruct-Q4_K_M.gguf",
+        ),
+        model(
+            "phi-3.5-mini-q4_k_m",
+            "Phi 3.5 Mini Instruct",
+            "Compact general model for machines with 4 GB of RAM",
+            "3.8B",
+            "Q4_K_M",
+            2_390_000_000,
+            131_072,
+            "bartowski/Phi-3.5-mini-instruct-GGUF",
+            "Phi-3.5-mini-instruct-Q4_K_M.gguf",
+        ),
+    ]
+}
+
+pub fn models_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("models");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create models directory: {}", e))?;
+    Ok(dir)
+}
+
+fn index_path() -> Result<PathBuf, String> {
+    Ok(models_dir()?.join("models.json"))
+}
+
+fn load_local() -> Result<Vec<LocalModel>, String> {
+    Ok(load_json(&index_path()?))
+}
+
+fn save_local(models: &[LocalModel]) -> Result<(), String> {
+    save_json(&index_path()?, models)
+}
+
+/// The built-in catalog with the entries of ~/.ctr/models/catalog.json
+/// added, replacing built-in ones with the same id
+pub fn catalog() -> Result<Vec<CatalogModel>, String> {
+    let custom: Vec<CatalogModel> = load_json(&models_dir()?.join("catalog.json"));
+    let mut models: Vec<CatalogModel> = builtin_catalog()
+        .into_iter()
+        .filter(|m| !custom.iter().any(|c| c.id == m.id))
+        .collect();
+    models.extend(custom);
+    Ok(models)
+}
+
+fn find_model(model_id: &str) -> Result<CatalogModel, String> {
+    catalog()?
+        .into_iter()
+        .find(|m| m.id == model_id)
+        .ok_or_else(|| format!("Unknown model: {}", model_id))
+}
+
+fn partial_path(dir: &Path, model: &CatalogModel) -> PathBuf {
+    dir.join(format!("{}.part", model.file))
+}
+
+pub fn list_catalog() -> Result<Vec<CatalogListing>, String> {
+    let dir = models_dir()?;
+    let local = load_local()?;
+    Ok(catalog()?
+        .into_iter()
+        .map(|model| {
+            let installed = local.iter().find(|l| l.id == model.id && Path::new(&l.path).exists());
+            CatalogListing {
+                downloaded: installed.is_some(),
+                partial_bytes: std::fs::metadata(partial_path(&dir, &model)).map(|m| m.len()).unwrap_or(0),
+                registered_as: installed.and_then(|l| l.registered_as.clone()),
+                model,
+            }
+        })
+        .collect())
+}
+
+/// Downloaded models whose files are still present
+pub fn list_local() -> Result<Vec<LocalModel>, String> {
+    Ok(load_local()?.into_iter().filter(|m| Path::new(&m.path).exists()).collect())
+}
+
+/// Stop a running download, keeping the partial file for resuming; false
+/// when `model_id` is not being downloaded
+pub fn cancel_download(model_id: &str) -> bool {
+    match DOWNLOADS.lock().unwrap().get(model_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn download_url(model: &CatalogModel) -> String {
+    format!("https://huggingface.co/{}/resolve/main/{}", model.repo, model.file)
+}
+
+/// The checksum Hugging Face keeps for an LFS file, sent as X-Linked-Etag on
+/// the redirect to the file's storage
+async fn published_sha256(model: &CatalogModel) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let mut request = client.head(download_url(model));
+    if let Some(token) = secrets::get_or_none("huggingface", "token") {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to look up {}: {}", model.file, e))?;
+    response
+        .headers()
+        .get("x-linked-etag")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim_start_matches("W/").trim_matches('"').to_ascii_lowercase())
+        .filter(|v| v.len() == 64 && v.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("No checksum published for {}; add its sha256 to the catalog", model.file))
+}
+
+/// Download a catalog model into ~/.ctr/models, resuming an interrupted
+/// download, and verify its checksum
+pub async fn download(model_id: &str, progress: &(dyn Fn(DownloadProgress) + Send + Sync)) -> Result<LocalModel, String> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let mut downloads = DOWNLOADS.lock().unwrap();
+        if downloads.contains_key(model_id) {
+            return Err(format!("{} is already being downloaded", model_id));
+        }
+        downloads.insert(model_id.to_string(), cancelled.clone());
+    }
+    let result = download_model(model_id, &cancelled, progress).await;
+    DOWNLOADS.lock().unwrap().remove(model_id);
+    result
+}
+
+async fn download_model(
+    model_id: &str,
+    cancelled: &AtomicBool,
+    progress: &(dyn Fn(DownloadProgress) + Send + Sync),
+) -> Result<LocalModel, String> {
+    let model = find_model(model_id)?;
+    let dir = models_dir()?;
+    let expected = match &model.sha256 {
+        Some(sha) => sha.to_ascii_lowercase(),
+        None => published_sha256(&model).await?,
+    };
+    let part = partial_path(&dir, &model);
+    let mut offset = tokio::fs::metadata(&part).await.map(|m| m.len()).unwrap_or(0);
+
+    // No overall timeout: large models take long, a stalled read still fails
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .read_timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let mut request = client.get(download_url(&model));
+    // Gated models need a Hugging Face token
+    if let Some(token) = secrets::get_or_none("huggingface", "token") {
+        request = request.bearer_auth(token);
+    }
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Download of {} failed: {}", model.file, e))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file is already complete
+    } else if !status.is_success() {
+        return Err(format!("Download of {} failed: HTTP {}", model.file, status));
+    } else {
+        if status != reqwest::StatusCode::PARTIAL_CONTENT {
+            // The server ignored the range; start over
+            offset = 0;
+        }
+        let total = response.content_length().map(|len| len + offset);
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(&part)
+            .await
+            .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+
+        let started = Instant::now();
+        let resumed_from = offset;
+        let mut last_event = Instant::now() - PROGRESS_INTERVAL;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Download of {} interrupted: {}", model.file, e))?
+        {
+            if cancelled.load(Ordering::SeqCst) {
+                let _ = file.flush().await;
+                return Err("Download cancelled".to_string());
+            }
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+            offset += chunk.len() as u64;
+            if last_event.elapsed() >= PROGRESS_INTERVAL {
+                last_event = Instant::now();
+                let elapsed = started.elapsed().as_secs_f64().max(0.001);
+                progress(DownloadProgress {
+                    model_id: model.id.clone(),
+                    stage: "downloading".to_string(),
+                    downloaded: offset,
+                    total,
+                    bytes_per_sec: ((offset - resumed_from) as f64 / elapsed) as u64,
+                });
+            }
+        }
+        file.flush().await.map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+    }
+
+    progress(DownloadProgress {
+        model_id: model.id.clone(),
+        stage: "verifying".to_string(),
+        downloaded: offset,
+        total: Some(offset),
+        bytes_per_sec: 0,
+    });
+    let hash_path = part.clone();
+    let actual = tokio::task::spawn_blocking(move || sha256_file(&hash_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    if actual != expected {
+        let _ = tokio::fs::remove_file(&part).await;
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}; the download was discarded",
+            model.file, expected, actual
+        ));
+    }
+
+    let path = dir.join(&model.file);
+    tokio::fs::rename(&part, &path)
+        .await
+        .map_err(|e| format!("Failed to move {} into place: {}", model.file, e))?;
+    let local = LocalModel {
+        id: model.id.clone(),
+        path: path.to_string_lossy().to_string(),
+        size_bytes: offset,
+        sha256: actual,
+        downloaded_at: now_millis(),
+        registered_as: None,
+    };
+    let mut models = load_local()?;
+    models.retain(|m| m.id != local.id);
+    models.push(local.clone());
+    save_local(&models)?;
+
+    progress(DownloadProgress {
+        model_id: model.id,
+        stage: "done".to_string(),
+        downloaded: offset,
+        total: Some(offset),
+        bytes_per_sec: 0,
+    });
+    Ok(local)
+}
+
+/// Delete a downloaded model and any partial download of it
+pub fn delete(model_id: &str) -> Result<bool, String> {
+    let mut models = load_local()?;
+    let mut removed = false;
+    if let Some(model) = models.iter().find(|m| m.id == model_id) {
+        let _ = std::fs::remove_file(&model.path);
+        removed = true;
+    }
+    if let Ok(entry) = find_model(model_id) {
+        removed |= std::fs::remove_file(partial_path(&models_dir()?, &entry)).is_ok();
+    }
+    models.retain(|m| m.id != model_id);
+    save_local(&models)?;
+    Ok(removed)
+}
+
+/// Create a model named `name` (the model id by default) on the Ollama
+/// server from a downloaded GGUF file, uploading the file first unless the
+/// server already has it
+pub async fn register(model_id: &str, name: Option<&str>) -> Result<String, String> {
+    let mut models = load_local()?;
+    let local = models
+        .iter()
+        .find(|m| m.id == model_id && Path::new(&m.path).exists())
+        .cloned()
+        .ok_or_else(|| format!("{} has not been downloaded", model_id))?;
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or(model_id).to_string();
+    let endpoint: String = settings::get_as("ai.endpoint", None, "http://localhost:11434".to_string());
+    let endpoint = endpoint.trim_end_matches('/');
+    let digest = format!("sha256:{}", local.sha256);
+    let client = reqwest::Client::new();
+
+    let blob_url = format!("{}/api/blobs/{}", endpoint, digest);
+    let exists = client
+        .head(&blob_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama at {}: {}", endpoint, e))?
+        .status()
+        .is_success();
+    if !exists {
+        let file = tokio::fs::File::open(&local.path)
+            .await
+            .map_err(|e| format!("Failed to open {}: {}", local.path, e))?;
+        // Stream the file rather than loading gigabytes into memory
+        let body = stream::unfold(file, |mut file| async move {
+            let mut buffer = vec![0u8; 1024 * 1024];
+            match file.read(&mut buffer).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buffer.truncate(n);
+                    Some((Ok::<_, std::io::Error>(buffer), file))
+                }
+                Err(e) => Some((Err(e), file)),
+            }
+        });
+        let response = client
+            .post(&blob_url)
+            .header(reqwest::header::CONTENT_LENGTH, local.size_bytes)
+            .body(reqwest::Body::wrap_stream(body))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload {} to Ollama: {}", model_id, e))?;
+        if !response.status().is_success() {
+            return Err(format!("Ollama refused the model file: HTTP {}", response.status()));
+        }
+    }
+
+    let file_name = Path::new(&local.path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("{}.gguf", model_id));
+    let response = client
+        .post(format!("{}/api/create", endpoint))
+        .json(&json!({ "model": name, "files": { (file_name): digest }, "stream": false }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create {} on Ollama: {}", name, e))?;
+    if !response.status().is_success() {
+        let detail = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama could not create {}: {}", name, detail.trim()));
+    }
+
+    if let Some(model) = models.iter_mut().find(|m| m.id == model_id) {
+        model.registered_as = Some(name.clone());
+    }
+    save_local(&models)?;
+    Ok(name)
+}
@@ -2,5 +2,4 @@ pub mod engine;
 pub mod manager;
 pub mod rag;
 pub mod stream;
-
-
+pub mod triage;
@@ -0,0 +1,303 @@
+//! Context for AI requests
+//!
+//! Gathers what the model should know beyond the raw buffer: the file's
+//! imports and, for Python, the project symbols they resolve to through the
+//! project indexer; the variables the taint slicer considers user-controlled
+//! at the cursor; and the function signatures around it. The result is
+//! rendered as the prompt for code completion.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::analysis::python_parser::PythonParser;
+use crate::analysis::slicer::BackwardSlicer;
+use crate::analysis::{ProjectIndexer, SymbolKind};
+use crate::services::langdetect::{self, LanguageId};
+
+/// How long a workspace's symbol index is reused before it is rebuilt
+const INDEX_TTL: Duration = Duration::from_secs(300);
+/// Lines of code sent before and after the cursor
+const LINES_BEFORE: usize = 150;
+const LINES_AFTER: usize = 50;
+/// Distance from the cursor within which signatures count as nearby
+const SIGNATURE_RANGE: usize = 80;
+const MAX_IMPORTS: usize = 30;
+const MAX_PROJECT_SYMBOLS: usize = 20;
+const MAX_TAINTED: usize = 20;
+const MAX_SIGNATURES: usize = 15;
+
+pub const CURSOR_MARKER: &str = "<CURSOR>";
+
+lazy_static::lazy_static! {
+    static ref IMPORT_RE: Regex = Regex::new(
+        r"^\s*(?:from\s+[\w.]+\s+import\s.+|import\s.+|#\s*include\s.+|use\s+[\w:]+.*;|(?:const|let|var)\s+.+=\s*require\(.+|require(?:_once)?[\s(].+|package\s+[\w.]+;?)\s*$"
+    ).unwrap();
+    static ref SIGNATURE_RE: Regex = Regex::new(
+        r"^\s*(?:(?:pub(?:\([\w:]+\))?|export|async|static|public|private|protected|final|abstract)\s+)*(?:def|fn|func|function|sub|class|struct|impl|trait|interface)\b.*$"
+    ).unwrap();
+    /// C-style definitions such as `int parse(char *buf, size_t len) {`
+    static ref C_SIGNATURE_RE: Regex = Regex::new(
+        r"^[A-Za-z_][\w\s\*<>,:\[\]]*[\s\*&]([A-Za-z_]\w*)\s*\([^;]*\)\s*(?:const\s*)?(?:throws\s+[\w.,\s]+)?\{?\s*$"
+    ).unwrap();
+    /// Workspace root -> symbol index and when it was built
+    static ref INDEXERS: Mutex<HashMap<String, (Instant, ProjectIndexer)>> = Mutex::new(HashMap::new());
+}
+
+/// A project function or class that an import of the file refers to
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSymbol {
+    pub name: String,
+    pub kind: String,
+    /// Path relative to the workspace root
+    pub file_path: String,
+    pub line: usize,
+    /// The definition line, e.g. `def get_user(user_id):`
+    pub signature: String,
+}
+
+/// A variable holding user-controlled data where the completion is inserted
+#[derive(Debug, Clone, Serialize)]
+pub struct TaintedVariable {
+    pub name: String,
+    /// Line of the definition that reaches the cursor
+    pub line: usize,
+    /// The input expressions its value comes from
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompletionContext {
+    pub language: Option<String>,
+    pub file_path: Option<String>,
+    pub imports: Vec<String>,
+    pub project_symbols: Vec<ProjectSymbol>,
+    pub tainted: Vec<TaintedVariable>,
+    pub signatures: Vec<String>,
+}
+
+fn truncate(line: &str, max: usize) -> String {
+    let line = line.trim();
+    match line.char_indices().nth(max) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+fn imports(code: &str) -> Vec<String> {
+    let mut imports: Vec<String> = Vec::new();
+    for line in code.lines().filter(|l| IMPORT_RE.is_match(l)) {
+        let line = truncate(line, 200);
+        if !imports.contains(&line) {
+            imports.push(line);
+        }
+        if imports.len() == MAX_IMPORTS {
+            break;
+        }
+    }
+    imports
+}
+
+/// Signatures of definitions within `SIGNATURE_RANGE` lines of the cursor,
+/// nearest first
+fn nearby_signatures(code: &str, cursor_line: usize) -> Vec<String> {
+    let mut found: Vec<(usize, String)> = code
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| (i + 1).abs_diff(cursor_line) <= SIGNATURE_RANGE)
+        .filter(|(_, line)| {
+            SIGNATURE_RE.is_match(line)
+                || C_SIGNATURE_RE
+                    .captures(line)
+                    .is_some_and(|c| !matches!(&c[1], "if" | "for" | "while" | "switch" | "return" | "catch"))
+        })
+        .map(|(i, line)| ((i + 1).abs_diff(cursor_line), truncate(line.trim_end_matches(['{', ' ']), 200)))
+        .collect();
+    found.sort_by_key(|(distance, _)| *distance);
+    found.into_iter().map(|(_, s)| s).take(MAX_SIGNATURES).collect()
+}
+
+/// Variables the slicer finds user-controlled at `cursor_line`
+fn tainted_at(code: &str, cursor_line: usize) -> Vec<TaintedVariable> {
+    let Ok(tree) = PythonParser::new().and_then(|mut p| p.parse(code)) else {
+        return Vec::new();
+    };
+    let mut slicer = BackwardSlicer::new();
+    slicer.analyze(code, &tree);
+    let mut tainted: Vec<TaintedVariable> = slicer
+        .definitions_before(cursor_line)
+        .into_iter()
+        .filter(|def| def.name != "request" && slicer.is_tainted(&def.name))
+        .map(|def| TaintedVariable {
+            name: def.name.clone(),
+            line: def.line,
+            sources: slicer.input_sources(&def.name),
+        })
+        .collect();
+    // The closest definitions matter most
+    tainted.reverse();
+    tainted.truncate(MAX_TAINTED);
+    tainted
+}
+
+/// Rebuild a workspace's symbol index now instead of when it goes stale;
+/// returns the number of files indexed
+pub fn refresh_index(workspace_root: &str) -> Result<usize, String> {
+    let mut indexer = ProjectIndexer::new(PathBuf::from(workspace_root))?;
+    let files = indexer.index_workspace()?;
+    INDEXERS.lock().unwrap().insert(workspace_root.to_string(), (Instant::now(), indexer));
+    Ok(files)
+}
+
+/// Project symbols imported by `file_path`, from the workspace's cached index
+fn project_symbols(workspace_root: &str, file_path: &Path) -> Vec<ProjectSymbol> {
+    let mut indexers = INDEXERS.lock().unwrap();
+    let stale = !indexers
+        .get(workspace_root)
+        .is_some_and(|(built, _)| built.elapsed() <= INDEX_TTL);
+    if stale {
+        let Ok(mut indexer) = ProjectIndexer::new(PathBuf::from(workspace_root)) else {
+            return Vec::new();
+        };
+        if let Err(e) = indexer.index_workspace() {
+            log::warn!("Failed to index {} for AI context: {}", workspace_root, e);
+            return Vec::new();
+        }
+        indexers.insert(workspace_root.to_string(), (Instant::now(), indexer));
+    }
+    let Some((_, indexer)) = indexers.get(workspace_root) else {
+        return Vec::new();
+    };
+
+    let mut symbols = Vec::new();
+    for import in indexer.get_file_imports(file_path).into_iter().flatten() {
+        for imported in &import.names {
+            let name = imported.alias.as_ref().unwrap_or(&imported.name);
+            let Some(symbol) = indexer.resolve_symbol(file_path, name) else {
+                continue;
+            };
+            if symbol.file_path == file_path || symbol.kind == SymbolKind::Variable {
+                continue;
+            }
+            let signature = fs::read_to_string(&symbol.file_path)
+                .ok()
+                .and_then(|source| source.lines().nth(symbol.line.saturating_sub(1)).map(|l| truncate(l, 200)))
+                .unwrap_or_default();
+            symbols.push(ProjectSymbol {
+                name: name.clone(),
+                kind: format!("{:?}", symbol.kind).to_lowercase(),
+                file_path: symbol
+                    .file_path
+                    .strip_prefix(workspace_root)
+                    .unwrap_or(&symbol.file_path)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+                line: symbol.line,
+                signature,
+            });
+            if symbols.len() == MAX_PROJECT_SYMBOLS {
+                return symbols;
+            }
+        }
+    }
+    symbols
+}
+
+/// Context for completing `code` at `cursor_line` (1-based). Taint and
+/// project symbols are only available for Python.
+pub fn completion_context(
+    code: &str,
+    language: &str,
+    file_path: Option<&str>,
+    cursor_line: usize,
+    workspace_root: Option<&str>,
+) -> CompletionContext {
+    let language = LanguageId::from_name(language)
+        .or_else(|| file_path.and_then(|p| langdetect::detect(Path::new(p), Some(code))).map(|d| d.language));
+    let python = matches!(language, Some(LanguageId::Python));
+
+    let project_symbols = match (python, workspace_root, file_path) {
+        (true, Some(root), Some(path)) => project_symbols(root, Path::new(path)),
+        _ => Vec::new(),
+    };
+    CompletionContext {
+        language: language.map(|l| l.name().to_string()),
+        file_path: file_path.map(str::to_string),
+        imports: imports(code),
+        project_symbols,
+        tainted: if python { tainted_at(code, cursor_line) } else { Vec::new() },
+        signatures: nearby_signatures(code, cursor_line),
+    }
+}
+
+/// The code around the cursor with `CURSOR_MARKER` at `cursor_line` and
+/// `cursor_column` (0-based, in characters)
+fn code_window(code: &str, cursor_line: usize, cursor_column: usize) -> String {
+    let lines: Vec<&str> = code.lines().collect();
+    let cursor = cursor_line.clamp(1, lines.len().max(1)) - 1;
+    let start = cursor.saturating_sub(LINES_BEFORE);
+    let end = (cursor + LINES_AFTER + 1).min(lines.len());
+
+    let mut window = String::new();
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        if i == cursor {
+            let split = line.char_indices().nth(cursor_column).map(|(b, _)| b).unwrap_or(line.len());
+            window.push_str(&line[..split]);
+            window.push_str(CURSOR_MARKER);
+            window.push_str(&line[split..]);
+        } else {
+            window.push_str(line);
+        }
+        window.push('\n');
+    }
+    if lines.is_empty() {
+        window.push_str(CURSOR_MARKER);
+    }
+    window
+}
+
+/// Prompt asking for the code to insert at the cursor
+pub fn completion_prompt(context: &CompletionContext, code: &str, cursor_line: usize, cursor_column: usize) -> String {
+    let mut prompt = String::new();
+    if let Some(path) = &context.file_path {
+        prompt.push_str(&format!("File: {}\n", path));
+    }
+    if let Some(language) = &context.language {
+        prompt.push_str(&format!("Language: {}\n", language));
+    }
+    if !context.imports.is_empty() {
+        prompt.push_str("\nImports:\n");
+        for import in &context.imports {
+            prompt.push_str(&format!("  {}\n", import));
+        }
+    }
+    if !context.project_symbols.is_empty() {
+        prompt.push_str("\nImported project definitions:\n");
+        for symbol in &context.project_symbols {
+            prompt.push_str(&format!("  {} ({}:{}): {}\n", symbol.name, symbol.file_path, symbol.line, symbol.signature));
+        }
+    }
+    if !context.tainted.is_empty() {
+        prompt.push_str("\nUser-controlled variables at the cursor; do not pass them unsanitized to queries, commands, file paths or templates:\n");
+        for var in &context.tainted {
+            let sources = if var.sources.is_empty() { "user input".to_string() } else { var.sources.join(", ") };
+            prompt.push_str(&format!("  {} (line {}, from {})\n", var.name, var.line, sources));
+        }
+    }
+    if !context.signatures.is_empty() {
+        prompt.push_str("\nNearby definitions:\n");
+        for signature in &context.signatures {
+            prompt.push_str(&format!("  {}\n", signature));
+        }
+    }
+    prompt.push_str(&format!(
+        "\nComplete the code at {}:\n```\n{}```\n",
+        CURSOR_MARKER,
+        code_window(code, cursor_line, cursor_column)
+    ));
+    prompt
+}
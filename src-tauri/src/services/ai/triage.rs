@@ -0,0 +1,224 @@
+//! AI triage of stored findings
+//!
+//! Sends findings from the findings store, with the code around each one, to
+//! the configured AI provider and asks it to group duplicates, point out
+//! likely false positives (test fixtures, dead code, constant input) with
+//! its reasoning, and order the rest by priority. The answer is written back
+//! as annotations on the findings; statuses are left to the user.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::services::ai::engine;
+use crate::services::findings::{self, Finding, FindingQuery, FindingStatus, TriageAnnotation};
+use crate::utils::time::now_millis;
+
+/// Findings sent in one request, most severe first
+const MAX_FINDINGS: usize = 60;
+/// Lines of code shown on each side of a finding's line
+const SNIPPET_CONTEXT: usize = 2;
+
+const SYSTEM_PROMPT: &str = "You triage findings of security scanners for a penetration tester. \
+Group findings that describe the same underlying issue, flag likely false positives with a short reason \
+(test fixtures, example or dead code, constant or trusted input, sanitized values), and order the remaining \
+findings by how urgently they should be reviewed. Reply with JSON only, in the form \
+{\"groups\": [{\"ids\": [\"F1\", \"F4\"], \"reason\": \"...\"}], \
+\"false_positives\": [{\"id\": \"F2\", \"reasoning\": \"...\"}], \
+\"priority\": [\"F3\", \"F1\"]}.";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ModelGroup {
+    ids: Vec<String>,
+    reason: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ModelFalsePositive {
+    id: String,
+    reasoning: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ModelAnswer {
+    groups: Vec<ModelGroup>,
+    false_positives: Vec<ModelFalsePositive>,
+    priority: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FindingGroup {
+    pub id: String,
+    pub fingerprints: Vec<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FalsePositiveSuggestion {
+    pub fingerprint: String,
+    pub reasoning: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TriageReport {
+    pub groups: Vec<FindingGroup>,
+    pub false_positives: Vec<FalsePositiveSuggestion>,
+    /// Fingerprints in suggested review order
+    pub priority: Vec<String>,
+    /// Findings sent for triage and how many were annotated
+    pub triaged: usize,
+    pub annotated: usize,
+}
+
+/// The lines around a finding, numbered, when its file can be read
+fn snippet(workspace_root: &str, finding: &Finding) -> Option<String> {
+    let (file, line) = (finding.file.as_ref()?, finding.line?);
+    let path = Path::new(workspace_root).join(file);
+    let source = std::fs::read_to_string(path).ok()?;
+    let start = line.saturating_sub(SNIPPET_CONTEXT + 1);
+    let lines: Vec<String> = source
+        .lines()
+        .enumerate()
+        .skip(start)
+        .take(SNIPPET_CONTEXT * 2 + 1)
+        .map(|(i, text)| format!("{:>5} {}", i + 1, text))
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// Whether a path looks like tests, fixtures or examples rather than
+/// shipped code; a hint for the model, not a verdict
+fn is_test_path(file: &str) -> bool {
+    let file = file.to_ascii_lowercase().replace('\\', "/");
+    file.split('/').any(|part| {
+        matches!(part, "test" | "tests" | "spec" | "specs" | "fixtures" | "__tests__" | "examples" | "testdata")
+            || part.starts_with("test_")
+            || part.contains("_test.")
+            || part.contains(".test.")
+            || part.contains(".spec.")
+    })
+}
+
+fn prompt(workspace_root: &str, findings: &[Finding]) -> String {
+    let entries: Vec<_> = findings
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            json!({
+                "id": format!("F{}", i + 1),
+                "source": f.source,
+                "rule": f.rule,
+                "title": f.title,
+                "message": f.message,
+                "severity": f.severity,
+                "cwe": f.cwe,
+                "file": f.file,
+                "line": f.line,
+                "test_path": f.file.as_deref().is_some_and(is_test_path),
+                "code": snippet(workspace_root, f),
+            })
+        })
+        .collect();
+    format!(
+        "Triage these {} findings:\n{}",
+        findings.len(),
+        serde_json::to_string_pretty(&entries).unwrap_or_default()
+    )
+}
+
+/// The JSON object in a reply, which models sometimes wrap in prose or fences
+fn parse_answer(reply: &str) -> Result<ModelAnswer, String> {
+    let start = reply.find('{').ok_or("The AI reply contained no JSON")?;
+    let end = reply.rfind('}').ok_or("The AI reply contained no JSON")?;
+    serde_json::from_str(&reply[start..=end]).map_err(|e| format!("Failed to parse the AI reply: {}", e))
+}
+
+/// Triage the open findings with the given fingerprints, or all open
+/// findings when none are given, and annotate them with the suggestions
+pub async fn triage_findings(workspace_root: &str, fingerprints: &[String]) -> Result<TriageReport, String> {
+    let query = FindingQuery { statuses: vec![FindingStatus::Open], ..Default::default() };
+    let mut selected: Vec<Finding> = findings::list(workspace_root, &query)?
+        .into_iter()
+        .filter(|f| fingerprints.is_empty() || fingerprints.contains(&f.fingerprint))
+        .collect();
+    if selected.is_empty() {
+        return Err("No open findings to triage".to_string());
+    }
+    selected.truncate(MAX_FINDINGS);
+
+    let reply = engine::complete(SYSTEM_PROMPT, &prompt(workspace_root, &selected), 4096, Some(workspace_root)).await?;
+    let answer = parse_answer(&reply)?;
+
+    let by_id: HashMap<String, &Finding> = selected
+        .iter()
+        .enumerate()
+        .map(|(i, f)| (format!("F{}", i + 1), f))
+        .collect();
+    let fingerprint_of = |id: &str| by_id.get(id.trim()).map(|f| f.fingerprint.clone());
+
+    let groups: Vec<FindingGroup> = answer
+        .groups
+        .iter()
+        .map(|g| g.ids.iter().filter_map(|id| fingerprint_of(id)).collect::<Vec<_>>())
+        .zip(&answer.groups)
+        .filter(|(members, _)| members.len() > 1)
+        .enumerate()
+        .map(|(i, (fingerprints, group))| FindingGroup {
+            id: format!("G{}", i + 1),
+            fingerprints,
+            reason: group.reason.trim().to_string(),
+        })
+        .collect();
+    let false_positives: Vec<FalsePositiveSuggestion> = answer
+        .false_positives
+        .iter()
+        .filter_map(|fp| {
+            Some(FalsePositiveSuggestion {
+                fingerprint: fingerprint_of(&fp.id)?,
+                reasoning: fp.reasoning.trim().to_string(),
+            })
+        })
+        .collect();
+    let mut priority: Vec<String> = Vec::new();
+    for fingerprint in answer.priority.iter().filter_map(|id| fingerprint_of(id)) {
+        if !priority.contains(&fingerprint) {
+            priority.push(fingerprint);
+        }
+    }
+
+    let now = now_millis();
+    let annotations: Vec<(String, TriageAnnotation)> = selected
+        .iter()
+        .map(|finding| {
+            let fp = &finding.fingerprint;
+            let group = groups.iter().find(|g| g.fingerprints.contains(fp));
+            let false_positive = false_positives.iter().find(|s| &s.fingerprint == fp);
+            let reasoning = match (false_positive, group) {
+                (Some(s), _) => s.reasoning.clone(),
+                (None, Some(g)) => g.reason.clone(),
+                (None, None) => String::new(),
+            };
+            let annotation = TriageAnnotation {
+                group: group.map(|g| g.id.clone()),
+                likely_false_positive: false_positive.is_some(),
+                reasoning,
+                priority: priority.iter().position(|p| p == fp).map(|i| i + 1),
+                suggested_at: now,
+            };
+            (fp.clone(), annotation)
+        })
+        .collect();
+    let annotated = findings::annotate(workspace_root, annotations)?;
+
+    Ok(TriageReport {
+        groups,
+        false_positives,
+        priority,
+        triaged: selected.len(),
+        annotated,
+    })
+}
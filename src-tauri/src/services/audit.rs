@@ -0,0 +1,179 @@
+//! Command Audit Log
+//!
+//! Opt-in, append-only record of commands executed through the IDE
+//! (shell commands, terminal input, code runs), stored as JSON lines
+//! in ~/.ctr/audit/audit.jsonl.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::utils::csv::csv_field;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    /// Where the command came from: "shell", "terminal", "code_runner", "interactive"
+    pub source: String,
+    pub session_id: Option<String>,
+    pub command: String,
+    pub cwd: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub source: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub contains: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AuditConfig {
+    enabled: bool,
+}
+
+lazy_static::lazy_static! {
+    static ref ENABLED: AtomicBool = AtomicBool::new(load_config().enabled);
+    // Serializes appends so concurrent writers never interleave lines
+    static ref WRITE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+fn audit_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("audit");
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create audit directory: {}", e))?;
+    }
+    Ok(dir)
+}
+
+fn log_file() -> Result<PathBuf, String> {
+    Ok(audit_dir()?.join("audit.jsonl"))
+}
+
+fn load_config() -> AuditConfig {
+    match audit_dir() {
+        Ok(dir) => load_json(&dir.join("config.json")),
+        Err(_) => AuditConfig::default(),
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    save_json(&audit_dir()?.join("config.json"), &AuditConfig { enabled })?;
+    ENABLED.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Record a command if auditing is enabled. Failures are logged, never propagated,
+/// so auditing can't break the command being audited.
+pub fn record(source: &str, session_id: Option<&str>, command: &str, cwd: Option<&str>) {
+    if is_enabled() {
+        record_always(source, session_id, command, cwd);
+    }
+}
+
+/// Record a command even when auditing is disabled, for activity that must
+/// always leave a trail
+pub fn record_always(source: &str, session_id: Option<&str>, command: &str, cwd: Option<&str>) {
+    if command.trim().is_empty() {
+        return;
+    }
+
+    let entry = AuditEntry {
+        timestamp: now_millis(),
+        source: source.to_string(),
+        session_id: session_id.map(String::from),
+        command: command.to_string(),
+        cwd: cwd.map(String::from),
+    };
+
+    if let Err(e) = append(&entry) {
+        log::warn!("Failed to write audit entry: {}", e);
+    }
+}
+
+fn append(entry: &AuditEntry) -> Result<(), String> {
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+
+    let _guard = WRITE_LOCK.lock().map_err(|_| "Audit log lock poisoned")?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_file()?)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to append audit entry: {}", e))
+}
+
+/// Read entries matching the query, oldest first
+pub fn query(filter: &AuditQuery) -> Result<Vec<AuditEntry>, String> {
+    let path = log_file()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to open audit log: {}", e))?;
+    let needle = filter.contains.as_ref().map(|s| s.to_lowercase());
+
+    let mut entries: Vec<AuditEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(&line).ok())
+        .filter(|e| filter.source.as_ref().map_or(true, |s| &e.source == s))
+        .filter(|e| filter.since.map_or(true, |t| e.timestamp >= t))
+        .filter(|e| filter.until.map_or(true, |t| e.timestamp <= t))
+        .filter(|e| needle.as_ref().map_or(true, |n| e.command.to_lowercase().contains(n)))
+        .collect();
+
+    // Keep the most recent entries when a limit is given
+    if let Some(limit) = filter.limit {
+        if entries.len() > limit {
+            entries.drain(..entries.len() - limit);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Export matching entries to a file as "jsonl" or "csv"; returns the number exported
+pub fn export(filter: &AuditQuery, dest: &str, format: &str) -> Result<usize, String> {
+    let entries = query(filter)?;
+
+    let content = match format.to_lowercase().as_str() {
+        "jsonl" | "json" => entries
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "csv" => {
+            let mut out = String::from("timestamp,source,session_id,command,cwd\n");
+            for e in &entries {
+                out.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    e.timestamp,
+                    csv_field(&e.source),
+                    csv_field(e.session_id.as_deref().unwrap_or("")),
+                    csv_field(&e.command),
+                    csv_field(e.cwd.as_deref().unwrap_or("")),
+                ));
+            }
+            out
+        }
+        _ => return Err(format!("Unsupported export format: {}", format)),
+    };
+
+    fs::write(dest, content).map_err(|e| format!("Failed to write export: {}", e))?;
+    Ok(entries.len())
+}
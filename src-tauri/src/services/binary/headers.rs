@@ -0,0 +1,179 @@
+//! ELF and PE header parsing: architecture, entry point, sections and the
+//! functions a binary imports and exports
+
+use goblin::elf::section_header::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_NOBITS};
+use goblin::elf::Elf;
+use goblin::pe::section_table::{IMAGE_SCN_MEM_EXECUTE, IMAGE_SCN_MEM_READ, IMAGE_SCN_MEM_WRITE};
+use goblin::pe::PE;
+use goblin::Object;
+use serde::Serialize;
+
+use super::entropy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryFormat {
+    Elf,
+    Pe,
+    #[serde(rename = "mach-o")]
+    MachO,
+    Archive,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Section {
+    pub name: String,
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    /// Memory protection, like "r-x"
+    pub permissions: String,
+    /// Shannon entropy of the section's file data, 0 to 8 bits per byte
+    pub entropy: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedFunction {
+    /// Library the function comes from; ELF symbols do not record one
+    pub library: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryHeaders {
+    pub architecture: String,
+    pub bits: u8,
+    pub little_endian: bool,
+    /// Executable, shared object, DLL, ...
+    pub file_type: String,
+    pub entry_point: u64,
+    /// ELF program interpreter
+    pub interpreter: Option<String>,
+    /// PE link time, as a Unix timestamp in seconds
+    pub timestamp: Option<u64>,
+    /// PDB path left in the PE debug directory
+    pub pdb_path: Option<String>,
+    /// Shared libraries or DLLs the binary links against
+    pub libraries: Vec<String>,
+    pub sections: Vec<Section>,
+    pub imports: Vec<ImportedFunction>,
+    pub exports: Vec<String>,
+    /// Whether the ELF symbol table was stripped
+    pub stripped: bool,
+}
+
+fn permissions(read: bool, write: bool, execute: bool) -> String {
+    [(read, 'r'), (write, 'w'), (execute, 'x')].iter().map(|&(set, c)| if set { c } else { '-' }).collect()
+}
+
+fn section_entropy(data: &[u8], offset: u64, size: u64) -> f64 {
+    let start = (offset as usize).min(data.len());
+    let end = start.saturating_add(size as usize).min(data.len());
+    entropy(&data[start..end])
+}
+
+fn elf_headers(elf: &Elf, data: &[u8]) -> BinaryHeaders {
+    let sections = elf
+        .section_headers
+        .iter()
+        .filter(|sh| sh.sh_size > 0)
+        .map(|sh| {
+            let flags = sh.sh_flags as u32;
+            let on_disk = sh.sh_type != SHT_NOBITS;
+            Section {
+                name: elf.shdr_strtab.get_at(sh.sh_name).unwrap_or_default().to_string(),
+                address: sh.sh_addr,
+                offset: sh.sh_offset,
+                size: sh.sh_size,
+                permissions: permissions(flags & SHF_ALLOC != 0, flags & SHF_WRITE != 0, flags & SHF_EXECINSTR != 0),
+                entropy: if on_disk { section_entropy(data, sh.sh_offset, sh.sh_size) } else { 0.0 },
+            }
+        })
+        .collect();
+
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+    for sym in elf.dynsyms.iter() {
+        let Some(name) = elf.dynstrtab.get_at(sym.st_name).filter(|n| !n.is_empty()) else {
+            continue;
+        };
+        if sym.is_import() {
+            imports.push(ImportedFunction { library: None, name: name.to_string() });
+        } else if sym.is_function() && sym.st_value != 0 {
+            exports.push(name.to_string());
+        }
+    }
+
+    BinaryHeaders {
+        architecture: goblin::elf::header::machine_to_str(elf.header.e_machine).to_string(),
+        bits: if elf.is_64 { 64 } else { 32 },
+        little_endian: elf.little_endian,
+        file_type: goblin::elf::header::et_to_str(elf.header.e_type).to_string(),
+        entry_point: elf.entry,
+        interpreter: elf.interpreter.map(String::from),
+        timestamp: None,
+        pdb_path: None,
+        libraries: elf.libraries.iter().map(|l| l.to_string()).collect(),
+        sections,
+        imports,
+        exports,
+        stripped: elf.syms.is_empty(),
+    }
+}
+
+fn pe_headers(pe: &PE, data: &[u8]) -> BinaryHeaders {
+    let sections = pe
+        .sections
+        .iter()
+        .map(|s| {
+            let c = s.characteristics;
+            Section {
+                name: s.name().map(String::from).unwrap_or_else(|_| String::from_utf8_lossy(&s.name).trim_end_matches('\0').to_string()),
+                address: s.virtual_address as u64,
+                offset: s.pointer_to_raw_data as u64,
+                size: s.virtual_size.max(s.size_of_raw_data) as u64,
+                permissions: permissions(c & IMAGE_SCN_MEM_READ != 0, c & IMAGE_SCN_MEM_WRITE != 0, c & IMAGE_SCN_MEM_EXECUTE != 0),
+                entropy: section_entropy(data, s.pointer_to_raw_data as u64, s.size_of_raw_data as u64),
+            }
+        })
+        .collect();
+    let imports = pe
+        .imports
+        .iter()
+        .map(|i| ImportedFunction { library: Some(i.dll.to_string()), name: i.name.to_string() })
+        .collect();
+    let pdb_path = pe
+        .debug_data
+        .as_ref()
+        .and_then(|d| d.codeview_pdb70_debug_info.as_ref())
+        .map(|info| String::from_utf8_lossy(info.filename).trim_end_matches('\0').to_string());
+
+    BinaryHeaders {
+        architecture: goblin::pe::header::machine_to_str(pe.header.coff_header.machine).to_string(),
+        bits: if pe.is_64 { 64 } else { 32 },
+        little_endian: true,
+        file_type: if pe.is_lib { "DLL" } else { "EXE" }.to_string(),
+        entry_point: pe.image_base as u64 + pe.entry as u64,
+        interpreter: None,
+        timestamp: Some(pe.header.coff_header.time_date_stamp as u64),
+        pdb_path,
+        libraries: pe.libraries.iter().map(|l| l.to_string()).collect(),
+        sections,
+        imports,
+        exports: pe.exports.iter().filter_map(|e| e.name.map(String::from)).collect(),
+        stripped: false,
+    }
+}
+
+/// The file's format, and its headers when it is an ELF or PE binary
+pub fn parse(data: &[u8]) -> Result<(BinaryFormat, Option<BinaryHeaders>), String> {
+    match Object::parse(data) {
+        Ok(Object::Elf(elf)) => Ok((BinaryFormat::Elf, Some(elf_headers(&elf, data)))),
+        Ok(Object::PE(pe)) => Ok((BinaryFormat::Pe, Some(pe_headers(&pe, data)))),
+        Ok(Object::Mach(_)) => Ok((BinaryFormat::MachO, None)),
+        Ok(Object::Archive(_)) => Ok((BinaryFormat::Archive, None)),
+        Ok(_) => Ok((BinaryFormat::Unknown, None)),
+        Err(e) => Err(format!("Malformed binary: {}", e)),
+    }
+}
@@ -0,0 +1,179 @@
+//! Static triage of binaries
+//!
+//! Reads executables and other files dropped during labs without running
+//! them: hashes and entropy, ELF/PE headers with sections, imports and
+//! exports, printable strings (ASCII and UTF-16) with their offsets, and the
+//! URLs and IP addresses embedded in those strings.
+
+pub mod headers;
+pub mod strings;
+
+use md5::Md5;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use headers::{BinaryFormat, BinaryHeaders};
+use strings::{ExtractedString, Indicator, StringEncoding};
+
+/// Files are read whole, so larger ones are refused
+const MAX_BINARY_BYTES: u64 = 256 * 1024 * 1024;
+
+const DEFAULT_PAGE_SIZE: usize = 500;
+const MAX_PAGE_SIZE: usize = 10_000;
+
+/// Entropy above which data is likely packed or encrypted
+const HIGH_ENTROPY: f64 = 7.2;
+
+/// Section names left by common packers and protectors
+const PACKER_SECTIONS: &[&str] =
+    &["UPX0", "UPX1", "UPX2", ".aspack", ".adata", ".packed", ".petite", ".themida", ".vmp0", ".vmp1", ".MPRESS1", ".MPRESS2", ".nsp0", ".nsp1"];
+
+/// Imports that often point at process injection, persistence or download
+/// and execute behavior
+const SUSPICIOUS_IMPORTS: &[&str] = &[
+    "VirtualAllocEx", "WriteProcessMemory", "CreateRemoteThread", "NtUnmapViewOfSection", "SetWindowsHookEx",
+    "QueueUserAPC", "URLDownloadToFile", "WinExec", "ShellExecute", "IsDebuggerPresent", "CryptEncrypt",
+    "RegSetValueEx", "ptrace", "execve", "system", "mprotect",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryInfo {
+    pub path: String,
+    pub size: u64,
+    pub md5: String,
+    pub sha256: String,
+    /// Shannon entropy of the whole file, 0 to 8 bits per byte
+    pub entropy: f64,
+    pub format: BinaryFormat,
+    pub headers: Option<BinaryHeaders>,
+    /// Why the headers could not be read, for malformed binaries
+    pub header_error: Option<String>,
+    /// Things worth a closer look: packing, writable code, suspicious imports
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StringPage {
+    pub total: usize,
+    pub offset: usize,
+    pub strings: Vec<ExtractedString>,
+}
+
+/// Shannon entropy in bits per byte
+pub fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[byte as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    let size = std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?.len();
+    if size > MAX_BINARY_BYTES {
+        return Err(format!("File is larger than {} MB", MAX_BINARY_BYTES / (1024 * 1024)));
+    }
+    std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn triage_notes(headers: &BinaryHeaders, file_entropy: f64) -> Vec<String> {
+    let mut notes = Vec::new();
+    if file_entropy > HIGH_ENTROPY {
+        notes.push(format!("High overall entropy ({:.2}); the file may be packed or encrypted", file_entropy));
+    }
+    for section in &headers.sections {
+        if PACKER_SECTIONS.iter().any(|p| section.name.eq_ignore_ascii_case(p)) {
+            notes.push(format!("Section {} is left by a known packer", section.name));
+        }
+        if section.permissions.contains('w') && section.permissions.contains('x') {
+            notes.push(format!("Section {} is both writable and executable", section.name));
+        }
+        if section.permissions.contains('x') && section.entropy > HIGH_ENTROPY {
+            notes.push(format!("Code section {} has high entropy ({:.2})", section.name, section.entropy));
+        }
+    }
+    if headers.imports.is_empty() && headers.interpreter.is_some() {
+        notes.push("Dynamically linked but imports nothing; imports may be resolved at run time".into());
+    }
+    let mut suspicious: Vec<&str> = headers
+        .imports
+        .iter()
+        .filter(|i| SUSPICIOUS_IMPORTS.iter().any(|s| i.name.trim_end_matches(['A', 'W']) == *s))
+        .map(|i| i.name.as_str())
+        .collect();
+    suspicious.sort_unstable();
+    suspicious.dedup();
+    if !suspicious.is_empty() {
+        notes.push(format!("Suspicious imports: {}", suspicious.join(", ")));
+    }
+    if let Some(pdb) = &headers.pdb_path {
+        notes.push(format!("Debug path left in the binary: {}", pdb));
+    }
+    notes
+}
+
+/// Hashes, format, headers and triage notes of a file
+pub fn info(path: &Path) -> Result<BinaryInfo, String> {
+    let data = read(path)?;
+    let file_entropy = entropy(&data);
+    let (format, headers, header_error) = match headers::parse(&data) {
+        Ok((format, headers)) => (format, headers, None),
+        Err(e) => (BinaryFormat::Unknown, None, Some(e)),
+    };
+    let mut notes = headers.as_ref().map(|h| triage_notes(h, file_entropy)).unwrap_or_default();
+    if headers.is_none() && file_entropy > HIGH_ENTROPY {
+        notes.push(format!("High overall entropy ({:.2}); the file may be compressed or encrypted", file_entropy));
+    }
+    Ok(BinaryInfo {
+        path: path.to_string_lossy().to_string(),
+        size: data.len() as u64,
+        md5: hex::encode(Md5::digest(&data)),
+        sha256: hex::encode(Sha256::digest(&data)),
+        entropy: file_entropy,
+        format,
+        headers,
+        header_error,
+        notes,
+    })
+}
+
+/// A page of a file's strings, optionally only those containing `filter`
+/// (case-insensitive)
+pub fn strings(
+    path: &Path,
+    min_length: Option<usize>,
+    encoding: Option<StringEncoding>,
+    filter: Option<&str>,
+    offset: usize,
+    limit: Option<usize>,
+) -> Result<StringPage, String> {
+    let data = read(path)?;
+    let mut found = strings::extract(&data, min_length.unwrap_or(strings::DEFAULT_MIN_LENGTH), encoding);
+    if let Some(filter) = filter.map(str::to_lowercase).filter(|f| !f.is_empty()) {
+        found.retain(|s| s.value.to_lowercase().contains(&filter));
+    }
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    Ok(StringPage {
+        total: found.len(),
+        offset,
+        strings: found.into_iter().skip(offset).take(limit).collect(),
+    })
+}
+
+/// URLs and IP addresses embedded in a file's strings
+pub fn indicators(path: &Path) -> Result<Vec<Indicator>, String> {
+    let data = read(path)?;
+    Ok(strings::indicators(&strings::extract(&data, strings::DEFAULT_MIN_LENGTH, None)))
+}
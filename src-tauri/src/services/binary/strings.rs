@@ -0,0 +1,157 @@
+//! Printable string extraction and the network indicators found in them
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+pub const DEFAULT_MIN_LENGTH: usize = 4;
+
+/// Longest string kept; longer runs are cut here
+const MAX_STRING_LENGTH: usize = 4096;
+
+lazy_static::lazy_static! {
+    static ref URL: Regex = Regex::new(r#"(?i)\b(?:https?|ftp|wss?)://[^\s"'<>`{}|\\^]+"#).unwrap();
+    static ref IPV4: Regex = Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StringEncoding {
+    Ascii,
+    /// UTF-16 little endian, as used by Windows binaries
+    Utf16le,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractedString {
+    /// File offset of the first byte
+    pub offset: usize,
+    pub encoding: StringEncoding,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorKind {
+    Url,
+    Ip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Indicator {
+    pub kind: IndicatorKind,
+    pub value: String,
+    /// File offset of the string of the first occurrence
+    pub offset: usize,
+    pub occurrences: usize,
+}
+
+fn printable(byte: u8) -> bool {
+    byte == b'\t' || (0x20..0x7f).contains(&byte)
+}
+
+fn ascii_strings(data: &[u8], min_length: usize, out: &mut Vec<ExtractedString>) {
+    let mut start = 0;
+    let mut length = 0;
+    for (i, &byte) in data.iter().enumerate() {
+        if printable(byte) {
+            if length == 0 {
+                start = i;
+            }
+            length += 1;
+            continue;
+        }
+        if length >= min_length {
+            let value = String::from_utf8_lossy(&data[start..start + length.min(MAX_STRING_LENGTH)]).into_owned();
+            out.push(ExtractedString { offset: start, encoding: StringEncoding::Ascii, value });
+        }
+        length = 0;
+    }
+    if length >= min_length {
+        let value = String::from_utf8_lossy(&data[start..start + length.min(MAX_STRING_LENGTH)]).into_owned();
+        out.push(ExtractedString { offset: start, encoding: StringEncoding::Ascii, value });
+    }
+}
+
+/// Printable ASCII characters each followed by a zero byte, at either
+/// alignment
+fn utf16_strings(data: &[u8], min_length: usize, out: &mut Vec<ExtractedString>) {
+    for alignment in 0..2 {
+        let mut start = alignment;
+        let mut value = String::new();
+        let mut i = alignment;
+        while i + 1 < data.len() {
+            if printable(data[i]) && data[i + 1] == 0 {
+                if value.is_empty() {
+                    start = i;
+                }
+                if value.len() < MAX_STRING_LENGTH {
+                    value.push(data[i] as char);
+                }
+            } else {
+                if value.len() >= min_length {
+                    out.push(ExtractedString { offset: start, encoding: StringEncoding::Utf16le, value: value.clone() });
+                }
+                value.clear();
+            }
+            i += 2;
+        }
+        if value.len() >= min_length {
+            out.push(ExtractedString { offset: start, encoding: StringEncoding::Utf16le, value });
+        }
+    }
+}
+
+/// Strings of at least `min_length` characters in file order; `encoding`
+/// limits the search to one encoding
+pub fn extract(data: &[u8], min_length: usize, encoding: Option<StringEncoding>) -> Vec<ExtractedString> {
+    let min_length = min_length.max(1);
+    let mut strings = Vec::new();
+    if encoding != Some(StringEncoding::Utf16le) {
+        ascii_strings(data, min_length, &mut strings);
+    }
+    if encoding != Some(StringEncoding::Ascii) {
+        utf16_strings(data, min_length, &mut strings);
+    }
+    strings.sort_by_key(|s| s.offset);
+    strings
+}
+
+/// URLs and IPv4 addresses in the strings, each once with a count. IPs that
+/// are part of a URL are not reported again, nor are ones in 0.0.0.0/8,
+/// which are mostly version numbers
+pub fn indicators(strings: &[ExtractedString]) -> Vec<Indicator> {
+    let mut found: Vec<Indicator> = Vec::new();
+    let mut index: HashMap<(IndicatorKind, String), usize> = HashMap::new();
+    let mut add = |kind: IndicatorKind, value: &str, offset: usize| match index.get(&(kind, value.to_string())) {
+        Some(&i) => found[i].occurrences += 1,
+        None => {
+            index.insert((kind, value.to_string()), found.len());
+            found.push(Indicator { kind, value: value.to_string(), offset, occurrences: 1 });
+        }
+    };
+
+    for string in strings {
+        let mut urls = Vec::new();
+        for m in URL.find_iter(&string.value) {
+            let url = m.as_str().trim_end_matches(['.', ',', ';', ')', ']']);
+            add(IndicatorKind::Url, url, string.offset);
+            urls.push(m.range());
+        }
+        for m in IPV4.find_iter(&string.value) {
+            if urls.iter().any(|r| r.contains(&m.start())) {
+                continue;
+            }
+            let address = m.as_str().split(':').next().unwrap_or_default();
+            let Ok(ip) = address.parse::<Ipv4Addr>() else {
+                continue;
+            };
+            if ip.octets()[0] == 0 {
+                continue;
+            }
+            add(IndicatorKind::Ip, m.as_str(), string.offset);
+        }
+    }
+    found
+}
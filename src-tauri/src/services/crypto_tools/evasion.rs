@@ -0,0 +1,187 @@
+//! WAF-evasion variants of exploit payloads.
+//!
+//! Each technique produces one rewritten payload; callers pick which
+//! techniques to apply and get every variant back for comparison.
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::exploit_sandbox::AttackType;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvasionOptions {
+    #[serde(default)]
+    pub url_encode: bool,
+    #[serde(default)]
+    pub double_url_encode: bool,
+    /// IIS-style %uXXXX escapes
+    #[serde(default)]
+    pub unicode_escape: bool,
+    /// Overlong two-byte UTF-8 sequences (%C0%AF for '/')
+    #[serde(default)]
+    pub overlong_utf8: bool,
+    #[serde(default)]
+    pub case_mutation: bool,
+    /// Replace spaces with inline comments (SQL injection payloads only)
+    #[serde(default)]
+    pub sql_comments: bool,
+    /// Body encoded with Transfer-Encoding: chunked in tiny chunks
+    #[serde(default)]
+    pub chunking: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvasionVariant {
+    pub technique: String,
+    pub payload: String,
+}
+
+fn encode_special(payload: &str, encode: impl Fn(u8) -> String) -> String {
+    payload
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() {
+                (b as char).to_string()
+            } else {
+                encode(b)
+            }
+        })
+        .collect()
+}
+
+pub fn url_encode(payload: &str) -> String {
+    encode_special(payload, |b| format!("%{:02X}", b))
+}
+
+pub fn double_url_encode(payload: &str) -> String {
+    encode_special(payload, |b| format!("%25{:02X}", b))
+}
+
+pub fn unicode_escape(payload: &str) -> String {
+    payload
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_string()
+            } else {
+                format!("%u{:04X}", c as u32)
+            }
+        })
+        .collect()
+}
+
+pub fn overlong_utf8(payload: &str) -> String {
+    encode_special(payload, |b| {
+        if b < 0x80 {
+            format!("%{:02X}%{:02X}", 0xC0 | (b >> 6), 0x80 | (b & 0x3F))
+        } else {
+            format!("%{:02X}", b)
+        }
+    })
+}
+
+/// Alternate letter case: "select" -> "SeLeCt"
+pub fn mutate_case(payload: &str) -> String {
+    let mut upper = true;
+    payload
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                let out = if upper { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() };
+                upper = !upper;
+                out
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+pub fn sql_comments(payload: &str) -> String {
+    payload.replace(' ', "/**/")
+}
+
+/// Chunked transfer-encoding body with chunks of at most 2 bytes; a
+/// multi-byte character is never split, so it may fill a longer chunk
+pub fn chunk_body(payload: &str) -> String {
+    let mut body = String::new();
+    let mut push = |chunk: &str| body.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+    let mut start = 0;
+    for (i, c) in payload.char_indices() {
+        if i > start && i + c.len_utf8() - start > 2 {
+            push(&payload[start..i]);
+            start = i;
+        }
+    }
+    if start < payload.len() {
+        push(&payload[start..]);
+    }
+    body.push_str("0\r\n\r\n");
+    body
+}
+
+/// Build every enabled variant of `payload`
+pub fn variants(payload: &str, attack_type: &AttackType, options: &EvasionOptions) -> Vec<EvasionVariant> {
+    let mut out = Vec::new();
+    let mut push = |technique: &str, payload: String| {
+        out.push(EvasionVariant {
+            technique: technique.to_string(),
+            payload,
+        })
+    };
+
+    if options.url_encode {
+        push("url_encode", url_encode(payload));
+    }
+    if options.double_url_encode {
+        push("double_url_encode", double_url_encode(payload));
+    }
+    if options.unicode_escape {
+        push("unicode_escape", unicode_escape(payload));
+    }
+    if options.overlong_utf8 {
+        push("overlong_utf8", overlong_utf8(payload));
+    }
+    if options.case_mutation {
+        push("case_mutation", mutate_case(payload));
+    }
+    if options.sql_comments && matches!(attack_type, AttackType::SqlInjection) {
+        push("sql_comments", sql_comments(payload));
+        push("sql_comments_case_mutation", mutate_case(&sql_comments(payload)));
+    }
+    if options.chunking {
+        push("chunked_body", chunk_body(payload));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_body() {
+        assert_eq!(chunk_body("abc"), "2\r\nab\r\n1\r\nc\r\n0\r\n\r\n");
+        assert_eq!(chunk_body(""), "0\r\n\r\n");
+    }
+
+    #[test]
+    fn test_chunk_body_keeps_characters_whole() {
+        // Chunk sizes count bytes: "é" is 2 bytes and "€" is 3
+        assert_eq!(chunk_body("aé€b"), "1\r\na\r\n2\r\né\r\n3\r\n€\r\n1\r\nb\r\n0\r\n\r\n");
+        let payload = "' OR 'ü'='ü' -- ☃";
+        let body = chunk_body(payload);
+        let mut rejoined = String::new();
+        let mut lines = body.split("\r\n");
+        while let Some(size) = lines.next() {
+            let size = usize::from_str_radix(size, 16).unwrap();
+            if size == 0 {
+                break;
+            }
+            let chunk = lines.next().unwrap();
+            assert_eq!(chunk.len(), size);
+            rejoined.push_str(chunk);
+        }
+        assert_eq!(rejoined, payload);
+    }
+}
@@ -0,0 +1,500 @@
+//! Hash identification and dictionary cracking.
+//!
+//! Fast unsalted hashes (MD5, SHA-1, SHA-256, NTLM) are cracked natively with
+//! rayon; everything else is delegated to hashcat or John the Ripper when they
+//! are installed.
+
+use md4::Md4;
+use md5::Md5;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+const NATIVE_CHUNK_SIZE: usize = 100_000;
+/// Lines of cracker stderr kept for error messages
+const STDERR_TAIL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashCandidate {
+    pub name: String,
+    pub hashcat_mode: Option<u32>,
+    pub john_format: Option<String>,
+    /// Whether `crack_native` can handle this type
+    pub native: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NativeAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Ntlm,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CrackEvent {
+    Progress { tried: u64, total: Option<u64>, message: Option<String> },
+    Found { hash: String, plaintext: String },
+}
+
+/// Cancellation and child-process handle shared with the command layer
+#[derive(Default)]
+pub struct CrackControl {
+    pub cancelled: AtomicBool,
+    pub child: Mutex<Option<Child>>,
+}
+
+impl CrackControl {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            let _ = child.kill();
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+fn candidate(name: &str, hashcat_mode: Option<u32>, john_format: Option<&str>, native: bool) -> HashCandidate {
+    HashCandidate {
+        name: name.to_string(),
+        hashcat_mode,
+        john_format: john_format.map(|s| s.to_string()),
+        native,
+    }
+}
+
+/// Guess the hash type from its format, most likely first
+pub fn identify(sample: &str) -> Vec<HashCandidate> {
+    let hash = sample.trim();
+    let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+
+    let prefixed = [
+        ("$2a$", "bcrypt", 3200, "bcrypt"),
+        ("$2b$", "bcrypt", 3200, "bcrypt"),
+        ("$2y$", "bcrypt", 3200, "bcrypt"),
+        ("$1$", "md5crypt", 500, "md5crypt"),
+        ("$apr1$", "Apache APR1 MD5", 1600, "md5crypt"),
+        ("$5$", "sha256crypt", 7400, "sha256crypt"),
+        ("$6$", "sha512crypt", 1800, "sha512crypt"),
+        ("$y$", "yescrypt", 0, "crypt"),
+        ("$argon2", "Argon2", 0, "argon2"),
+        ("$P$", "phpass (WordPress)", 400, "phpass"),
+        ("$H$", "phpass (phpBB)", 400, "phpass"),
+        ("pbkdf2_sha256$", "Django PBKDF2-SHA256", 10000, "django"),
+    ];
+    for (prefix, name, mode, john) in prefixed {
+        if hash.starts_with(prefix) {
+            let mode = if mode == 0 { None } else { Some(mode) };
+            return vec![candidate(name, mode, Some(john), false)];
+        }
+    }
+
+    if let Some(rest) = hash.strip_prefix('*') {
+        if rest.len() == 40 && is_hex(rest) {
+            return vec![candidate("MySQL 4.1+", Some(300), Some("mysql-sha1"), false)];
+        }
+    }
+
+    if !is_hex(hash) {
+        return Vec::new();
+    }
+
+    match hash.len() {
+        16 => vec![candidate("MySQL 3.23", Some(200), Some("mysql"), false)],
+        32 => vec![
+            candidate("MD5", Some(0), Some("raw-md5"), true),
+            candidate("NTLM", Some(1000), Some("nt"), true),
+            candidate("MD4", Some(900), Some("raw-md4"), false),
+            candidate("LM", Some(3000), Some("lm"), false),
+        ],
+        40 => vec![
+            candidate("SHA-1", Some(100), Some("raw-sha1"), true),
+            candidate("RIPEMD-160", Some(6000), Some("ripemd-160"), false),
+        ],
+        56 => vec![candidate("SHA-224", Some(1300), Some("raw-sha224"), false)],
+        64 => vec![
+            candidate("SHA-256", Some(1400), Some("raw-sha256"), true),
+            candidate("SHA3-256", Some(17400), Some("raw-sha3"), false),
+        ],
+        96 => vec![candidate("SHA-384", Some(10800), Some("raw-sha384"), false)],
+        128 => vec![
+            candidate("SHA-512", Some(1700), Some("raw-sha512"), false),
+            candidate("Whirlpool", Some(6100), Some("whirlpool"), false),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+fn native_hash(algorithm: NativeAlgorithm, word: &str) -> String {
+    match algorithm {
+        NativeAlgorithm::Md5 => hex::encode(Md5::digest(word.as_bytes())),
+        NativeAlgorithm::Sha1 => hex::encode(Sha1::digest(word.as_bytes())),
+        NativeAlgorithm::Sha256 => hex::encode(Sha256::digest(word.as_bytes())),
+        NativeAlgorithm::Ntlm => {
+            let utf16: Vec<u8> = word.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+            hex::encode(Md4::digest(&utf16))
+        }
+    }
+}
+
+/// Dictionary attack using all cores. Returns the number of hashes cracked.
+pub fn crack_native<F>(
+    hashes: &[String],
+    algorithm: NativeAlgorithm,
+    wordlist: &Path,
+    control: &CrackControl,
+    on_event: F,
+) -> Result<usize, String>
+where
+    F: Fn(CrackEvent) + Sync,
+{
+    let content = std::fs::read(wordlist).map_err(|e| format!("Failed to read wordlist: {}", e))?;
+    let text = String::from_utf8_lossy(&content);
+    let words: Vec<&str> = text.lines().map(|l| l.trim_end_matches('\r')).collect();
+    let total = words.len() as u64;
+
+    let mut remaining: HashSet<String> = hashes.iter().map(|h| h.trim().to_lowercase()).collect();
+    let mut cracked = 0;
+    let mut tried = 0u64;
+
+    for chunk in words.chunks(NATIVE_CHUNK_SIZE) {
+        if control.is_cancelled() || remaining.is_empty() {
+            break;
+        }
+
+        let found: Vec<(String, &str)> = chunk
+            .par_iter()
+            .filter_map(|word| {
+                let digest = native_hash(algorithm, word);
+                remaining.contains(&digest).then_some((digest, *word))
+            })
+            .collect();
+
+        for (hash, plaintext) in found {
+            if remaining.remove(&hash) {
+                cracked += 1;
+                on_event(CrackEvent::Found {
+                    hash,
+                    plaintext: plaintext.to_string(),
+                });
+            }
+        }
+
+        tried += chunk.len() as u64;
+        on_event(CrackEvent::Progress {
+            tried,
+            total: Some(total),
+            message: None,
+        });
+    }
+
+    Ok(cracked)
+}
+
+pub fn tool_available(tool: &str) -> bool {
+    Command::new(tool)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// Run the child to completion, passing each stdout line to `on_line`.
+/// Exit codes outside `ok_codes` fail with the tail of the child's stderr,
+/// unless the run was cancelled.
+fn run_streaming<F>(mut command: Command, control: &CrackControl, ok_codes: &[i32], mut on_line: F) -> Result<(), String>
+where
+    F: FnMut(&str),
+{
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start cracker: {}", e))?;
+    let stdout = child.stdout.take().ok_or("Failed to capture cracker output")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture cracker errors")?;
+    *control.child.lock().unwrap() = Some(child);
+
+    let stderr_reader = std::thread::spawn(move || {
+        let mut tail = Vec::new();
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            tail.push(line);
+            if tail.len() > STDERR_TAIL_LINES {
+                tail.remove(0);
+            }
+        }
+        tail.join("\n")
+    });
+
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        on_line(&line);
+    }
+
+    let status = match control.child.lock().unwrap().take() {
+        Some(mut child) => Some(child.wait().map_err(|e| format!("Failed to wait for cracker: {}", e))?),
+        None => None,
+    };
+    let errors = stderr_reader.join().unwrap_or_default();
+
+    if control.is_cancelled() {
+        return Ok(());
+    }
+    match status.and_then(|s| s.code()) {
+        Some(code) if ok_codes.contains(&code) => Ok(()),
+        code => {
+            let code = code.map(|c| c.to_string()).unwrap_or_else(|| "a signal".to_string());
+            if errors.trim().is_empty() {
+                Err(format!("Cracker exited with {}", code))
+            } else {
+                Err(format!("Cracker exited with {}: {}", code, errors.trim()))
+            }
+        }
+    }
+}
+
+/// Match a hashcat outfile line ("hash:plain", format 1,2) against the input
+/// hashes. Salted hashes and plaintexts may both contain ':', so the line is
+/// split after a known hash rather than at a separator.
+fn parse_hashcat_line<'a>(line: &'a str, hashes: &'a [String]) -> Option<(&'a str, String)> {
+    let hash = hashes
+        .iter()
+        .map(|h| h.trim())
+        .filter(|h| {
+            line.len() > h.len()
+                && line.as_bytes()[h.len()] == b':'
+                && line.get(..h.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(h))
+        })
+        .max_by_key(|h| h.len())?;
+    let plain = &line[hash.len() + 1..];
+    // Plaintexts that aren't printable ASCII come back as $HEX[...]
+    let plaintext = match plain.strip_prefix("$HEX[").and_then(|p| p.strip_suffix(']')) {
+        Some(encoded) => hex::decode(encoded)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_else(|_| plain.to_string()),
+        None => plain.to_string(),
+    };
+    Some((hash, plaintext))
+}
+
+/// Report outfile lines that haven't been seen yet; returns the number found
+fn read_hashcat_outfile<F>(outfile: &Path, hashes: &[String], seen: &mut HashSet<String>, on_event: &F) -> usize
+where
+    F: Fn(CrackEvent),
+{
+    let Ok(content) = std::fs::read_to_string(outfile) else {
+        return 0;
+    };
+    let mut found = 0;
+    for line in content.lines() {
+        let Some((hash, plaintext)) = parse_hashcat_line(line, hashes) else {
+            continue;
+        };
+        if seen.insert(hash.to_lowercase()) {
+            found += 1;
+            on_event(CrackEvent::Found {
+                hash: hash.to_string(),
+                plaintext,
+            });
+        }
+    }
+    found
+}
+
+/// Orchestrate hashcat in straight (dictionary) mode
+pub fn crack_hashcat<F>(
+    hashes: &[String],
+    hash_file: &Path,
+    mode: u32,
+    wordlist: &Path,
+    control: &CrackControl,
+    on_event: F,
+) -> Result<usize, String>
+where
+    F: Fn(CrackEvent),
+{
+    std::fs::write(hash_file, hashes.join("\n"))
+        .map_err(|e| format!("Failed to write hash file: {}", e))?;
+
+    let outfile = hash_file.with_extension("cracked");
+    let _ = std::fs::remove_file(&outfile);
+
+    let mut command = Command::new("hashcat");
+    command
+        .arg("-m")
+        .arg(mode.to_string())
+        .args(["-a", "0", "--quiet", "--potfile-disable", "--status", "--status-json", "--status-timer", "2"])
+        .arg("--outfile")
+        .arg(&outfile)
+        .arg("--outfile-format=1,2")
+        .arg(hash_file)
+        .arg(wordlist);
+
+    let mut seen = HashSet::new();
+    let mut cracked = 0;
+    // 0: all cracked, 1: wordlist exhausted
+    let result = run_streaming(command, control, &[0, 1], |line| {
+        // Status lines: {"progress":[done,total], ...}
+        let Ok(status) = serde_json::from_str::<serde_json::Value>(line) else {
+            return;
+        };
+        cracked += read_hashcat_outfile(&outfile, hashes, &mut seen, &on_event);
+        let progress = &status["progress"];
+        on_event(CrackEvent::Progress {
+            tried: progress[0].as_u64().unwrap_or(0),
+            total: progress[1].as_u64(),
+            message: status["status"].as_u64().map(|s| format!("hashcat status {}", s)),
+        });
+    });
+    cracked += read_hashcat_outfile(&outfile, hashes, &mut seen, &on_event);
+    let _ = std::fs::remove_file(&outfile);
+    result?;
+
+    Ok(cracked)
+}
+
+/// Orchestrate John the Ripper in wordlist mode, then collect results with --show
+pub fn crack_john<F>(
+    hashes: &[String],
+    hash_file: &Path,
+    format: Option<&str>,
+    wordlist: &Path,
+    control: &CrackControl,
+    on_event: F,
+) -> Result<usize, String>
+where
+    F: Fn(CrackEvent),
+{
+    // Tag each hash with its index as the user field so --show output maps back
+    let tagged: Vec<String> = hashes
+        .iter()
+        .enumerate()
+        .map(|(i, h)| format!("h{}:{}", i, h.trim()))
+        .collect();
+    std::fs::write(hash_file, tagged.join("\n"))
+        .map_err(|e| format!("Failed to write hash file: {}", e))?;
+
+    let format_arg = format.map(|f| format!("--format={}", f));
+
+    let mut command = Command::new("john");
+    command.arg(format!("--wordlist={}", wordlist.display()));
+    if let Some(arg) = &format_arg {
+        command.arg(arg);
+    }
+    command.arg(hash_file);
+
+    run_streaming(command, control, &[0], |line| {
+        on_event(CrackEvent::Progress {
+            tried: 0,
+            total: None,
+            message: Some(line.to_string()),
+        });
+    })?;
+
+    if control.is_cancelled() {
+        return Ok(0);
+    }
+
+    let mut show = Command::new("john");
+    show.arg("--show");
+    if let Some(arg) = &format_arg {
+        show.arg(arg);
+    }
+    let output = show
+        .arg(hash_file)
+        .output()
+        .map_err(|e| format!("Failed to run john --show: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "john --show failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // Lines look like "h<index>:<plaintext>"; the trailing summary line has no colon
+    let mut cracked = 0;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((tag, plaintext)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(hash) = tag
+            .strip_prefix('h')
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| hashes.get(i))
+        else {
+            continue;
+        };
+        cracked += 1;
+        on_event(CrackEvent::Found {
+            hash: hash.trim().to_string(),
+            plaintext: plaintext.to_string(),
+        });
+    }
+
+    Ok(cracked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_hash_vectors() {
+        let vectors = [
+            (NativeAlgorithm::Md5, "", "d41d8cd98f00b204e9800998ecf8427e"),
+            (NativeAlgorithm::Md5, "abc", "900150983cd24fb0d6963f7d28e17f72"),
+            (NativeAlgorithm::Sha1, "", "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+            (NativeAlgorithm::Sha1, "abc", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+            (
+                NativeAlgorithm::Sha256,
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                NativeAlgorithm::Sha256,
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (NativeAlgorithm::Ntlm, "", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            (NativeAlgorithm::Ntlm, "password", "8846f7eaee8fb117ad06bdd830b7586c"),
+        ];
+        for (algorithm, word, expected) in vectors {
+            assert_eq!(native_hash(algorithm, word), expected, "{:?}({:?})", algorithm, word);
+        }
+    }
+
+    #[test]
+    fn test_identify() {
+        let names = |hash: &str| identify(hash).into_iter().map(|c| c.name).collect::<Vec<_>>();
+        assert_eq!(names("900150983cd24fb0d6963f7d28e17f72")[..2], ["MD5", "NTLM"]);
+        assert_eq!(names("a9993e364706816aba3e25717850c26c9cd0d89d")[0], "SHA-1");
+        assert_eq!(names("$2b$10$abcdefghijklmnopqrstuv"), ["bcrypt"]);
+        assert!(identify("not a hash").is_empty());
+    }
+
+    #[test]
+    fn test_parse_hashcat_line() {
+        let hashes = vec!["abc123:salt".to_string(), "abc123".to_string()];
+        assert_eq!(
+            parse_hashcat_line("abc123:salt:pass:word", &hashes),
+            Some(("abc123:salt", "pass:word".to_string()))
+        );
+        assert_eq!(
+            parse_hashcat_line("ABC123:$HEX[70617373]", &hashes),
+            Some(("abc123", "pass".to_string()))
+        );
+        assert_eq!(parse_hashcat_line("other:pass", &hashes), None);
+    }
+}
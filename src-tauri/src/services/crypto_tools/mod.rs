@@ -0,0 +1,5 @@
+pub mod evasion;
+pub mod hashes;
+pub mod pipeline;
+
+pub use pipeline::{transform, transform_str, Operation};
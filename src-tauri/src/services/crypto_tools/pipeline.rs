@@ -0,0 +1,337 @@
+//! Chained encode/decode pipeline (CyberChef-style recipes).
+//!
+//! Data flows between operations as raw bytes, so binary steps like gzip or
+//! AES can sit between text encodings without lossy conversions.
+
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use flate2::read::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::io::Read;
+
+type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+type Aes192CbcEnc = cbc::Encryptor<aes::Aes192>;
+type Aes192CbcDec = cbc::Decryptor<aes::Aes192>;
+type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
+type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
+
+/// How a key/IV string is interpreted
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyFormat {
+    #[default]
+    Utf8,
+    Hex,
+    Base64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    HexEncode,
+    HexDecode,
+    HtmlEntityEncode,
+    HtmlEntityDecode,
+    Gzip,
+    Gunzip,
+    Rot13,
+    Xor {
+        key: String,
+        #[serde(default)]
+        key_format: KeyFormat,
+    },
+    /// AES-CBC with PKCS#7 padding; key length picks AES-128/192/256
+    AesEncrypt {
+        key: String,
+        iv: String,
+        #[serde(default)]
+        key_format: KeyFormat,
+    },
+    AesDecrypt {
+        key: String,
+        iv: String,
+        #[serde(default)]
+        key_format: KeyFormat,
+    },
+    /// Replaces the data with its hex digest
+    Hash { algorithm: HashAlgorithm },
+}
+
+fn parse_key(value: &str, format: KeyFormat) -> Result<Vec<u8>, String> {
+    match format {
+        KeyFormat::Utf8 => Ok(value.as_bytes().to_vec()),
+        KeyFormat::Hex => hex::decode(value.trim()).map_err(|e| format!("Invalid hex key: {}", e)),
+        KeyFormat::Base64 => BASE64
+            .decode(value.trim())
+            .map_err(|e| format!("Invalid base64 key: {}", e)),
+    }
+}
+
+fn html_encode(data: &[u8]) -> Vec<u8> {
+    let text = String::from_utf8_lossy(data);
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out.into_bytes()
+}
+
+fn html_decode(data: &[u8]) -> Vec<u8> {
+    let text = String::from_utf8_lossy(data);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text.as_ref();
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|h| u32::from_str_radix(h, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.into_bytes()
+}
+
+fn rot13(data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .map(|&b| match b {
+            b'a'..=b'z' => (b - b'a' + 13) % 26 + b'a',
+            b'A'..=b'Z' => (b - b'A' + 13) % 26 + b'A',
+            _ => b,
+        })
+        .collect()
+}
+
+fn aes_encrypt(data: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    let err = |e: aes::cipher::InvalidLength| format!("Invalid AES key/IV length: {}", e);
+    Ok(match key.len() {
+        16 => Aes128CbcEnc::new_from_slices(key, iv).map_err(err)?.encrypt_padded_vec_mut::<Pkcs7>(data),
+        24 => Aes192CbcEnc::new_from_slices(key, iv).map_err(err)?.encrypt_padded_vec_mut::<Pkcs7>(data),
+        32 => Aes256CbcEnc::new_from_slices(key, iv).map_err(err)?.encrypt_padded_vec_mut::<Pkcs7>(data),
+        n => return Err(format!("AES key must be 16, 24 or 32 bytes (got {})", n)),
+    })
+}
+
+fn aes_decrypt(data: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    let err = |e: aes::cipher::InvalidLength| format!("Invalid AES key/IV length: {}", e);
+    let result = match key.len() {
+        16 => Aes128CbcDec::new_from_slices(key, iv).map_err(err)?.decrypt_padded_vec_mut::<Pkcs7>(data),
+        24 => Aes192CbcDec::new_from_slices(key, iv).map_err(err)?.decrypt_padded_vec_mut::<Pkcs7>(data),
+        32 => Aes256CbcDec::new_from_slices(key, iv).map_err(err)?.decrypt_padded_vec_mut::<Pkcs7>(data),
+        n => return Err(format!("AES key must be 16, 24 or 32 bytes (got {})", n)),
+    };
+    result.map_err(|_| "AES decryption failed: bad key, IV or padding".to_string())
+}
+
+pub fn hash_bytes(algorithm: HashAlgorithm, data: &[u8]) -> String {
+    match algorithm {
+        HashAlgorithm::Md5 => hex::encode(Md5::digest(data)),
+        HashAlgorithm::Sha1 => hex::encode(Sha1::digest(data)),
+        HashAlgorithm::Sha256 => hex::encode(Sha256::digest(data)),
+        HashAlgorithm::Sha512 => hex::encode(Sha512::digest(data)),
+    }
+}
+
+fn apply(op: &Operation, data: Vec<u8>) -> Result<Vec<u8>, String> {
+    match op {
+        Operation::Base64Encode => Ok(BASE64.encode(&data).into_bytes()),
+        Operation::Base64Decode => {
+            let text: String = String::from_utf8_lossy(&data)
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+            BASE64
+                .decode(text)
+                .map_err(|e| format!("Invalid base64: {}", e))
+        }
+        Operation::UrlEncode => Ok(urlencoding::encode_binary(&data).into_owned().into_bytes()),
+        Operation::UrlDecode => Ok(urlencoding::decode_binary(&data).into_owned()),
+        Operation::HexEncode => Ok(hex::encode(&data).into_bytes()),
+        Operation::HexDecode => {
+            let text: String = String::from_utf8_lossy(&data)
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .collect();
+            hex::decode(text).map_err(|e| format!("Invalid hex: {}", e))
+        }
+        Operation::HtmlEntityEncode => Ok(html_encode(&data)),
+        Operation::HtmlEntityDecode => Ok(html_decode(&data)),
+        Operation::Gzip => {
+            let mut out = Vec::new();
+            GzEncoder::new(data.as_slice(), Compression::default())
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Gzip failed: {}", e))?;
+            Ok(out)
+        }
+        Operation::Gunzip => {
+            let mut out = Vec::new();
+            GzDecoder::new(data.as_slice())
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Gunzip failed: {}", e))?;
+            Ok(out)
+        }
+        Operation::Rot13 => Ok(rot13(&data)),
+        Operation::Xor { key, key_format } => {
+            let key = parse_key(key, *key_format)?;
+            if key.is_empty() {
+                return Err("XOR key cannot be empty".to_string());
+            }
+            Ok(data
+                .iter()
+                .zip(key.iter().cycle())
+                .map(|(b, k)| b ^ k)
+                .collect())
+        }
+        Operation::AesEncrypt { key, iv, key_format } => aes_encrypt(
+            &data,
+            &parse_key(key, *key_format)?,
+            &parse_key(iv, *key_format)?,
+        ),
+        Operation::AesDecrypt { key, iv, key_format } => aes_decrypt(
+            &data,
+            &parse_key(key, *key_format)?,
+            &parse_key(iv, *key_format)?,
+        ),
+        Operation::Hash { algorithm } => Ok(hash_bytes(*algorithm, &data).into_bytes()),
+    }
+}
+
+/// Run `input` through every operation of `recipe` in order
+pub fn transform(input: &[u8], recipe: &[Operation]) -> Result<Vec<u8>, String> {
+    recipe
+        .iter()
+        .enumerate()
+        .try_fold(input.to_vec(), |data, (i, op)| {
+            apply(op, data).map_err(|e| format!("Step {} failed: {}", i + 1, e))
+        })
+}
+
+/// Convenience wrapper for text payloads; binary output is returned lossily
+pub fn transform_str(input: &str, recipe: &[Operation]) -> Result<String, String> {
+    transform(input.as_bytes(), recipe).map(|out| String::from_utf8_lossy(&out).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(data: &[u8], encode: Operation, decode: Operation) {
+        let encoded = transform(data, &[encode]).unwrap();
+        assert_eq!(transform(&encoded, &[decode]).unwrap(), data);
+    }
+
+    #[test]
+    fn test_text_encodings_round_trip() {
+        let data = "<a href=\"x?q=1&r='2'\">é €</a>".as_bytes();
+        round_trip(data, Operation::Base64Encode, Operation::Base64Decode);
+        round_trip(data, Operation::HexEncode, Operation::HexDecode);
+        round_trip(data, Operation::UrlEncode, Operation::UrlDecode);
+        round_trip(data, Operation::HtmlEntityEncode, Operation::HtmlEntityDecode);
+        round_trip(data, Operation::Rot13, Operation::Rot13);
+    }
+
+    #[test]
+    fn test_binary_steps_round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        round_trip(&data, Operation::Gzip, Operation::Gunzip);
+        let xor = || Operation::Xor {
+            key: "6b6579".to_string(),
+            key_format: KeyFormat::Hex,
+        };
+        round_trip(&data, xor(), xor());
+        round_trip(
+            &data,
+            Operation::AesEncrypt {
+                key: "0123456789abcdef".to_string(),
+                iv: "fedcba9876543210".to_string(),
+                key_format: KeyFormat::Utf8,
+            },
+            Operation::AesDecrypt {
+                key: "0123456789abcdef".to_string(),
+                iv: "fedcba9876543210".to_string(),
+                key_format: KeyFormat::Utf8,
+            },
+        );
+    }
+
+    #[test]
+    fn test_chained_recipe() {
+        let recipe = [Operation::Gzip, Operation::Base64Encode, Operation::UrlEncode];
+        let encoded = transform_str("payload", &recipe).unwrap();
+        let decode = [Operation::UrlDecode, Operation::Base64Decode, Operation::Gunzip];
+        assert_eq!(transform_str(&encoded, &decode).unwrap(), "payload");
+
+        assert_eq!(transform_str("hi", &[Operation::HexEncode, Operation::Base64Encode]).unwrap(), "Njg2OQ==");
+    }
+
+    #[test]
+    fn test_html_decode_entities() {
+        let decoded = transform_str("&lt;&#x41;&#66;&bogus;&amp", &[Operation::HtmlEntityDecode]).unwrap();
+        assert_eq!(decoded, "<AB&bogus;&amp");
+    }
+
+    #[test]
+    fn test_failing_step_is_numbered() {
+        let err = transform_str("abc", &[Operation::Base64Encode, Operation::HexEncode, Operation::Gunzip]).unwrap_err();
+        assert!(err.starts_with("Step 3 failed: Gunzip failed"), "{}", err);
+        let err = transform_str("abc", &[Operation::AesDecrypt {
+            key: "short".to_string(),
+            iv: "short".to_string(),
+            key_format: KeyFormat::Utf8,
+        }])
+        .unwrap_err();
+        assert!(err.contains("AES key must be 16, 24 or 32 bytes (got 5)"), "{}", err);
+    }
+}
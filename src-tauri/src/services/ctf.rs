@@ -0,0 +1,367 @@
+//! CTF challenge packs
+//!
+//! A pack is a directory containing `pack.json` plus whatever its targets need
+//! (compose files, Dockerfiles). Flags are stored only as SHA-256 hex digests.
+//! Loaded pack locations live in ~/.ctr/ctf/packs.json and per-user completion
+//! in ~/.ctr/ctf/completions.json.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::services::labs::LabSetup;
+use crate::services::progress::{self, ProgressEvent, ProgressKind};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+const MANIFEST_FILE: &str = "pack.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeHint {
+    pub text: String,
+    /// Points deducted when the hint is revealed
+    #[serde(default)]
+    pub cost: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeManifest {
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    pub description: String,
+    #[serde(default = "default_points")]
+    pub points: u32,
+    /// Lowercase hex SHA-256 of the exact flag string
+    pub flag_hash: String,
+    #[serde(default)]
+    pub target: Option<LabSetup>,
+    #[serde(default)]
+    pub hints: Vec<ChallengeHint>,
+}
+
+fn default_points() -> u32 {
+    100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengePack {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    pub challenges: Vec<ChallengeManifest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PackEntry {
+    id: String,
+    path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Completion {
+    pub user: String,
+    pub pack_id: String,
+    pub challenge_id: String,
+    pub attempts: u32,
+    #[serde(default)]
+    pub hints_revealed: Vec<usize>,
+    pub started_at: u64,
+    pub solved_at: Option<u64>,
+    /// Challenge points minus revealed hint costs, set when solved
+    pub score: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagResult {
+    pub correct: bool,
+    pub already_solved: bool,
+    pub score: Option<u32>,
+    pub attempts: u32,
+}
+
+/// Challenge as shown to players: the flag hash and unrevealed hints are withheld
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeStatus {
+    pub pack_id: String,
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    pub description: String,
+    pub points: u32,
+    pub has_target: bool,
+    pub hint_count: usize,
+    pub revealed_hints: Vec<ChallengeHint>,
+    pub attempts: u32,
+    pub solved_at: Option<u64>,
+    pub score: Option<u32>,
+}
+
+fn ctf_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("ctf");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create ctf directory: {}", e))?;
+    Ok(dir)
+}
+
+fn packs_file() -> Result<PathBuf, String> {
+    Ok(ctf_dir()?.join("packs.json"))
+}
+
+fn completions_file() -> Result<PathBuf, String> {
+    Ok(ctf_dir()?.join("completions.json"))
+}
+
+fn load_entries() -> Vec<PackEntry> {
+    match packs_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn load_completions() -> Vec<Completion> {
+    match completions_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_completions(completions: &[Completion]) -> Result<(), String> {
+    save_json(&completions_file()?, completions)
+}
+
+pub fn hash_flag(flag: &str) -> String {
+    hex::encode(Sha256::digest(flag.trim().as_bytes()))
+}
+
+fn read_pack(dir: &Path) -> Result<ChallengePack, String> {
+    let manifest = dir.join(MANIFEST_FILE);
+    let content = std::fs::read_to_string(&manifest)
+        .map_err(|e| format!("Failed to read {}: {}", manifest.display(), e))?;
+    let pack: ChallengePack = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid challenge pack manifest: {}", e))?;
+    validate(&pack)?;
+    Ok(pack)
+}
+
+fn validate(pack: &ChallengePack) -> Result<(), String> {
+    if pack.id.trim().is_empty() {
+        return Err("Pack id is required".to_string());
+    }
+    let mut seen = std::collections::HashSet::new();
+    for challenge in &pack.challenges {
+        if !seen.insert(challenge.id.as_str()) {
+            return Err(format!("Duplicate challenge id: {}", challenge.id));
+        }
+        let hash_ok = challenge.flag_hash.len() == 64
+            && challenge.flag_hash.chars().all(|c| c.is_ascii_hexdigit());
+        if !hash_ok {
+            return Err(format!(
+                "Challenge {} has an invalid flag_hash (expected SHA-256 hex)",
+                challenge.id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate and register the pack at `path` (its directory or its pack.json)
+pub fn load_pack(path: &str) -> Result<ChallengePack, String> {
+    let path = Path::new(path);
+    let dir = if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve pack path: {}", e))?;
+    let pack = read_pack(&dir)?;
+
+    let mut entries = load_entries();
+    entries.retain(|e| e.id != pack.id);
+    entries.push(PackEntry {
+        id: pack.id.clone(),
+        path: dir.to_string_lossy().to_string(),
+    });
+    save_json(&packs_file()?, &entries)?;
+    Ok(pack)
+}
+
+/// Packs that are registered and still readable
+pub fn list_packs() -> Vec<ChallengePack> {
+    load_entries()
+        .iter()
+        .filter_map(|e| read_pack(Path::new(&e.path)).ok())
+        .collect()
+}
+
+pub fn remove_pack(pack_id: &str) -> Result<(), String> {
+    let mut entries = load_entries();
+    entries.retain(|e| e.id != pack_id);
+    save_json(&packs_file()?, &entries)
+}
+
+fn find_pack(pack_id: &str) -> Result<(ChallengePack, PathBuf), String> {
+    let entry = load_entries()
+        .into_iter()
+        .find(|e| e.id == pack_id)
+        .ok_or_else(|| format!("Challenge pack {} is not loaded", pack_id))?;
+    let dir = PathBuf::from(&entry.path);
+    Ok((read_pack(&dir)?, dir))
+}
+
+/// Look up a challenge and the directory of the pack that defines it
+pub fn find_challenge(pack_id: &str, challenge_id: &str) -> Result<(ChallengeManifest, PathBuf), String> {
+    let (pack, dir) = find_pack(pack_id)?;
+    let challenge = pack
+        .challenges
+        .into_iter()
+        .find(|c| c.id == challenge_id)
+        .ok_or_else(|| format!("Challenge {} not found in pack {}", challenge_id, pack_id))?;
+    Ok((challenge, dir))
+}
+
+fn completion_mut<'a>(
+    completions: &'a mut Vec<Completion>,
+    user: &str,
+    pack_id: &str,
+    challenge_id: &str,
+) -> &'a mut Completion {
+    let index = completions
+        .iter()
+        .position(|c| c.user == user && c.pack_id == pack_id && c.challenge_id == challenge_id);
+    match index {
+        Some(i) => &mut completions[i],
+        None => {
+            completions.push(Completion {
+                user: user.to_string(),
+                pack_id: pack_id.to_string(),
+                challenge_id: challenge_id.to_string(),
+                attempts: 0,
+                hints_revealed: Vec::new(),
+                started_at: now_millis(),
+                solved_at: None,
+                score: None,
+            });
+            completions.last_mut().unwrap()
+        }
+    }
+}
+
+pub fn submit_flag(user: &str, pack_id: &str, challenge_id: &str, flag: &str) -> Result<FlagResult, String> {
+    let (challenge, _) = find_challenge(pack_id, challenge_id)?;
+    let mut completions = load_completions();
+    let completion = completion_mut(&mut completions, user, pack_id, challenge_id);
+
+    if completion.solved_at.is_some() {
+        return Ok(FlagResult {
+            correct: true,
+            already_solved: true,
+            score: completion.score,
+            attempts: completion.attempts,
+        });
+    }
+
+    completion.attempts += 1;
+    let correct = hash_flag(flag) == challenge.flag_hash.to_lowercase();
+    if correct {
+        let penalty: u32 = completion
+            .hints_revealed
+            .iter()
+            .filter_map(|&i| challenge.hints.get(i))
+            .map(|h| h.cost)
+            .sum();
+        let solved_at = now_millis();
+        completion.solved_at = Some(solved_at);
+        completion.score = Some(challenge.points.saturating_sub(penalty));
+
+        progress::record_quietly(ProgressEvent {
+            id: 0,
+            user: user.to_string(),
+            kind: ProgressKind::ChallengeCompleted,
+            skill: progress::skill_for(&challenge.category),
+            reference: format!("{}/{}", pack_id, challenge_id),
+            detail: challenge.title.clone(),
+            points: completion.score.unwrap_or(0),
+            duration_ms: Some(solved_at.saturating_sub(completion.started_at)),
+            created_at: 0,
+        });
+    }
+
+    let result = FlagResult {
+        correct,
+        already_solved: false,
+        score: completion.score,
+        attempts: completion.attempts,
+    };
+    save_completions(&completions)?;
+    Ok(result)
+}
+
+pub fn reveal_hint(user: &str, pack_id: &str, challenge_id: &str, index: usize) -> Result<ChallengeHint, String> {
+    let (challenge, _) = find_challenge(pack_id, challenge_id)?;
+    let hint = challenge
+        .hints
+        .get(index)
+        .cloned()
+        .ok_or_else(|| format!("Hint {} does not exist", index))?;
+
+    let mut completions = load_completions();
+    let completion = completion_mut(&mut completions, user, pack_id, challenge_id);
+    if !completion.hints_revealed.contains(&index) {
+        completion.hints_revealed.push(index);
+    }
+    save_completions(&completions)?;
+    Ok(hint)
+}
+
+/// Every challenge of the loaded packs (or just `pack_id`) with `user`'s progress
+pub fn progress(user: &str, pack_id: Option<&str>) -> Vec<ChallengeStatus> {
+    let completions = load_completions();
+
+    list_packs()
+        .into_iter()
+        .filter(|p| pack_id.map_or(true, |id| p.id == id))
+        .flat_map(|pack| {
+            let completions = &completions;
+            pack.challenges.into_iter().map(move |c| {
+                let completion = completions
+                    .iter()
+                    .find(|d| d.user == user && d.pack_id == pack.id && d.challenge_id == c.id);
+                let revealed_hints = completion
+                    .map(|d| {
+                        d.hints_revealed
+                            .iter()
+                            .filter_map(|&i| c.hints.get(i).cloned())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ChallengeStatus {
+                    pack_id: pack.id.clone(),
+                    id: c.id,
+                    title: c.title,
+                    category: c.category,
+                    description: c.description,
+                    points: c.points,
+                    has_target: c.target.is_some(),
+                    hint_count: c.hints.len(),
+                    revealed_hints,
+                    attempts: completion.map(|d| d.attempts).unwrap_or(0),
+                    solved_at: completion.and_then(|d| d.solved_at),
+                    score: completion.and_then(|d| d.score),
+                }
+            })
+        })
+        .collect()
+}
+
+/// All recorded completions, for instructor views
+pub fn completions() -> Vec<Completion> {
+    load_completions()
+}
@@ -0,0 +1,240 @@
+//! Screenshot evidence capture
+//!
+//! Captures the screen (or a region of it) with the platform's screenshot tool
+//! into ~/.ctr/evidence/screenshots/, records the SHA-256 of every file in
+//! ~/.ctr/evidence/index.json and tracks which report findings use it.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingLink {
+    pub report_id: String,
+    pub finding_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceItem {
+    pub id: String,
+    pub path: String,
+    pub sha256: String,
+    pub captured_at: u64,
+    #[serde(default)]
+    pub label: String,
+    /// None for a full-screen capture
+    pub region: Option<CaptureRegion>,
+    #[serde(default)]
+    pub links: Vec<FindingLink>,
+}
+
+fn evidence_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("evidence");
+    std::fs::create_dir_all(dir.join("screenshots"))
+        .map_err(|e| format!("Failed to create evidence directory: {}", e))?;
+    Ok(dir)
+}
+
+fn index_file() -> Result<PathBuf, String> {
+    Ok(evidence_dir()?.join("index.json"))
+}
+
+pub fn list() -> Vec<EvidenceItem> {
+    match index_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_index(items: &[EvidenceItem]) -> Result<(), String> {
+    save_json(&index_file()?, items)
+}
+
+pub fn get(id: &str) -> Result<EvidenceItem, String> {
+    list()
+        .into_iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| format!("Evidence {} not found", id))
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn capture_to(path: &Path, region: Option<CaptureRegion>) -> Result<(), String> {
+    let mut command = Command::new("screencapture");
+    command.args(["-x", "-t", "png"]);
+    if let Some(r) = region {
+        command.arg(format!("-R{},{},{},{}", r.x, r.y, r.width, r.height));
+    }
+    run(command.arg(path))
+}
+
+#[cfg(target_os = "windows")]
+fn capture_to(path: &Path, region: Option<CaptureRegion>) -> Result<(), String> {
+    let (x, y, w, h) = match region {
+        Some(r) => (r.x.to_string(), r.y.to_string(), r.width.to_string(), r.height.to_string()),
+        None => (
+            "$b.Left".to_string(),
+            "$b.Top".to_string(),
+            "$b.Width".to_string(),
+            "$b.Height".to_string(),
+        ),
+    };
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms,System.Drawing; \
+         $b = [System.Windows.Forms.SystemInformation]::VirtualScreen; \
+         $bmp = New-Object System.Drawing.Bitmap {w}, {h}; \
+         $g = [System.Drawing.Graphics]::FromImage($bmp); \
+         $g.CopyFromScreen({x}, {y}, 0, 0, $bmp.Size); \
+         $bmp.Save('{path}', [System.Drawing.Imaging.ImageFormat]::Png)",
+        w = w,
+        h = h,
+        x = x,
+        y = y,
+        path = path.to_string_lossy().replace('\'', "''")
+    );
+    run(Command::new("powershell").args(["-NoProfile", "-Command", &script]))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn capture_to(path: &Path, region: Option<CaptureRegion>) -> Result<(), String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    // grim on Wayland, ImageMagick's import on X11
+    let mut command = if wayland {
+        let mut command = Command::new("grim");
+        if let Some(r) = region {
+            command.args(["-g", &format!("{},{} {}x{}", r.x, r.y, r.width, r.height)]);
+        }
+        command
+    } else {
+        let mut command = Command::new("import");
+        command.args(["-window", "root"]);
+        if let Some(r) = region {
+            command.args(["-crop", &format!("{}x{}+{}+{}", r.width, r.height, r.x, r.y)]);
+        }
+        command
+    };
+    command.arg(path);
+
+    run(&mut command).or_else(|e| {
+        // gnome-screenshot can't crop, so it is only a fallback for full captures
+        if region.is_some() {
+            return Err(e);
+        }
+        run(Command::new("gnome-screenshot").arg("-f").arg(path)).map_err(|_| e)
+    })
+}
+
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read evidence file: {}", e))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+/// Capture the screen (or `region`) to a timestamped PNG and record its hash
+pub fn capture(region: Option<CaptureRegion>, label: &str) -> Result<EvidenceItem, String> {
+    if region.is_some_and(|r| r.width == 0 || r.height == 0) {
+        return Err("Capture region must have a non-zero size".to_string());
+    }
+
+    let captured_at = now_millis();
+    let id = uuid::Uuid::new_v4().to_string();
+    let path = evidence_dir()?
+        .join("screenshots")
+        .join(format!("{}-{}.png", captured_at, &id[..8]));
+
+    capture_to(&path, region)?;
+    if !path.exists() {
+        return Err("Screenshot tool did not produce a file".to_string());
+    }
+
+    let item = EvidenceItem {
+        id,
+        path: path.to_string_lossy().to_string(),
+        sha256: hash_file(&path)?,
+        captured_at,
+        label: label.to_string(),
+        region,
+        links: Vec::new(),
+    };
+
+    let mut items = list();
+    items.push(item.clone());
+    save_index(&items)?;
+    Ok(item)
+}
+
+/// Re-hash the file and compare against the recorded digest
+pub fn verify(id: &str) -> Result<bool, String> {
+    let item = get(id)?;
+    Ok(hash_file(Path::new(&item.path))? == item.sha256)
+}
+
+pub fn link(id: &str, report_id: &str, finding_id: &str) -> Result<EvidenceItem, String> {
+    let mut items = list();
+    let item = items
+        .iter_mut()
+        .find(|item| item.id == id)
+        .ok_or_else(|| format!("Evidence {} not found", id))?;
+    let exists = item
+        .links
+        .iter()
+        .any(|l| l.report_id == report_id && l.finding_id == finding_id);
+    if !exists {
+        item.links.push(FindingLink {
+            report_id: report_id.to_string(),
+            finding_id: finding_id.to_string(),
+        });
+    }
+    let item = item.clone();
+    save_index(&items)?;
+    Ok(item)
+}
+
+pub fn unlink(id: &str, report_id: &str, finding_id: &str) -> Result<(), String> {
+    let mut items = list();
+    if let Some(item) = items.iter_mut().find(|item| item.id == id) {
+        item.links
+            .retain(|l| !(l.report_id == report_id && l.finding_id == finding_id));
+    }
+    save_index(&items)
+}
+
+/// Remove the file and its index entry
+pub fn delete(id: &str) -> Result<(), String> {
+    let mut items = list();
+    let Some(index) = items.iter().position(|item| item.id == id) else {
+        return Err(format!("Evidence {} not found", id));
+    };
+    let item = items.remove(index);
+    if !item.links.is_empty() {
+        return Err("Evidence is attached to report findings; detach it first".to_string());
+    }
+    let _ = std::fs::remove_file(&item.path);
+    save_index(&items)
+}
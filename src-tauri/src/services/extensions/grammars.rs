@@ -0,0 +1,232 @@
+//! Languages and TextMate grammars contributed by extensions
+//!
+//! `contributes.languages` entries are merged by language id across
+//! extensions (one may declare a language and another ship its grammar).
+//! Grammars are handed to the frontend as raw file content together with their
+//! scope mappings, since the TextMate tokenizer there reads both JSON and
+//! property list grammars.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::{enabled_manifests, parse_jsonc, InstalledManifest};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LanguageContribution {
+    pub id: String,
+    /// Extensions declaring the language, in load order
+    pub extension_ids: Vec<String>,
+    pub aliases: Vec<String>,
+    /// File extensions including the dot, e.g. ".sol"
+    pub extensions: Vec<String>,
+    pub filenames: Vec<String>,
+    pub first_line: Option<String>,
+    pub mimetypes: Vec<String>,
+    /// Root scope of the grammar for this language, if any extension ships one
+    pub scope_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrammarFormat {
+    Json,
+    Plist,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Grammar {
+    pub scope_name: String,
+    pub language: Option<String>,
+    pub extension_id: String,
+    pub format: GrammarFormat,
+    pub content: String,
+    /// Scope name to language id for embedded code blocks
+    pub embedded_languages: BTreeMap<String, String>,
+    /// Scope selector to token type (string, comment, other)
+    pub token_types: BTreeMap<String, String>,
+    /// Scopes of injection grammars targeting this grammar
+    pub injections: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageSupport {
+    pub language: LanguageContribution,
+    pub grammar: Option<Grammar>,
+    /// Parsed language-configuration.json (comments, brackets, auto-closing pairs)
+    pub configuration: Option<Value>,
+}
+
+struct GrammarEntry {
+    extension_id: String,
+    scope_name: String,
+    language: Option<String>,
+    path: String,
+    embedded_languages: BTreeMap<String, String>,
+    token_types: BTreeMap<String, String>,
+    inject_to: Vec<String>,
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn string_map(value: Option<&Value>) -> BTreeMap<String, String> {
+    value
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn push_unique(target: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !target.contains(&item) {
+            target.push(item);
+        }
+    }
+}
+
+fn grammar_entries(manifests: &[InstalledManifest]) -> Vec<GrammarEntry> {
+    manifests
+        .iter()
+        .flat_map(|ext| {
+            ext.contributions("grammars").iter().filter_map(|g| {
+                Some(GrammarEntry {
+                    extension_id: ext.id.clone(),
+                    scope_name: g.get("scopeName")?.as_str()?.to_string(),
+                    language: g.get("language").and_then(Value::as_str).map(String::from),
+                    path: g.get("path")?.as_str()?.to_string(),
+                    embedded_languages: string_map(g.get("embeddedLanguages")),
+                    token_types: string_map(g.get("tokenTypes")),
+                    inject_to: strings(g.get("injectTo")),
+                })
+            })
+        })
+        .collect()
+}
+
+fn merged_languages(manifests: &[InstalledManifest], grammars: &[GrammarEntry]) -> BTreeMap<String, LanguageContribution> {
+    let mut languages: BTreeMap<String, LanguageContribution> = BTreeMap::new();
+
+    for ext in manifests {
+        for entry in ext.contributions("languages") {
+            let Some(id) = entry.get("id").and_then(Value::as_str) else {
+                continue;
+            };
+            let language = languages.entry(id.to_string()).or_insert_with(|| LanguageContribution {
+                id: id.to_string(),
+                ..Default::default()
+            });
+            push_unique(&mut language.extension_ids, vec![ext.id.clone()]);
+            push_unique(&mut language.aliases, strings(entry.get("aliases")));
+            push_unique(&mut language.extensions, strings(entry.get("extensions")));
+            push_unique(&mut language.filenames, strings(entry.get("filenames")));
+            push_unique(&mut language.mimetypes, strings(entry.get("mimetypes")));
+            if language.first_line.is_none() {
+                language.first_line = entry.get("firstLine").and_then(Value::as_str).map(String::from);
+            }
+        }
+    }
+
+    for grammar in grammars {
+        if let Some(language) = grammar.language.as_ref().and_then(|id| languages.get_mut(id)) {
+            language.scope_name.get_or_insert_with(|| grammar.scope_name.clone());
+        }
+    }
+    languages
+}
+
+/// Every language contributed by enabled extensions
+pub fn list_languages() -> Vec<LanguageContribution> {
+    let manifests = enabled_manifests();
+    let grammars = grammar_entries(&manifests);
+    merged_languages(&manifests, &grammars).into_values().collect()
+}
+
+fn load_grammar(manifests: &[InstalledManifest], grammars: &[GrammarEntry], entry: &GrammarEntry) -> Result<Grammar, String> {
+    let ext = manifests
+        .iter()
+        .find(|m| m.id == entry.extension_id)
+        .ok_or_else(|| format!("Extension {} not found", entry.extension_id))?;
+    let path = ext.resolve(&entry.path)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read grammar {}: {}", entry.path, e))?;
+    let format = if entry.path.to_lowercase().ends_with(".json") {
+        GrammarFormat::Json
+    } else {
+        GrammarFormat::Plist
+    };
+
+    Ok(Grammar {
+        scope_name: entry.scope_name.clone(),
+        language: entry.language.clone(),
+        extension_id: entry.extension_id.clone(),
+        format,
+        content,
+        embedded_languages: entry.embedded_languages.clone(),
+        token_types: entry.token_types.clone(),
+        injections: grammars
+            .iter()
+            .filter(|g| g.inject_to.contains(&entry.scope_name))
+            .map(|g| g.scope_name.clone())
+            .collect(),
+    })
+}
+
+/// Grammar by scope name, used for embedded languages, includes and injections
+pub fn grammar_for_scope(scope_name: &str) -> Result<Grammar, String> {
+    let manifests = enabled_manifests();
+    let grammars = grammar_entries(&manifests);
+    let entry = grammars
+        .iter()
+        .find(|g| g.scope_name == scope_name)
+        .ok_or_else(|| format!("No grammar provides scope {}", scope_name))?;
+    load_grammar(&manifests, &grammars, entry)
+}
+
+/// Everything needed to highlight and edit a language: its declaration, root
+/// grammar and language configuration
+pub fn language_support(language_id: &str) -> Result<LanguageSupport, String> {
+    let manifests = enabled_manifests();
+    let grammars = grammar_entries(&manifests);
+    let language = merged_languages(&manifests, &grammars)
+        .remove(language_id)
+        .ok_or_else(|| format!("No extension contributes language {}", language_id))?;
+
+    let grammar = match grammars
+        .iter()
+        .find(|g| g.language.as_deref() == Some(language_id))
+    {
+        Some(entry) => Some(load_grammar(&manifests, &grammars, entry)?),
+        None => None,
+    };
+
+    // First declaring extension that ships a configuration file
+    let configuration = manifests
+        .iter()
+        .filter(|ext| language.extension_ids.contains(&ext.id))
+        .flat_map(|ext| {
+            ext.contributions("languages")
+                .iter()
+                .filter(|l| l.get("id").and_then(Value::as_str) == Some(language_id))
+                .filter_map(|l| l.get("configuration").and_then(Value::as_str))
+                .map(move |path| (ext, path))
+        })
+        .find_map(|(ext, path)| {
+            let path = ext.resolve(path).ok()?;
+            parse_jsonc(&std::fs::read_to_string(path).ok()?).ok()
+        });
+
+    Ok(LanguageSupport {
+        language,
+        grammar,
+        configuration,
+    })
+}
@@ -0,0 +1,614 @@
+// Extension host: runs extension activation scripts under Node and talks to the
+// IDE backend with newline-delimited JSON over stdio. Each extension runs in its
+// own `vm` context with no `process`, `require` or Node built-ins: it gets a
+// small `vscode` module, `console`, timers, and a loader for files inside its
+// own directory. The context's only link to this script is a bridge that passes
+// JSON strings, and workspace file access goes through the backend.
+//
+// A vm context separates extensions from each other and from this script's
+// globals, but it is not a security boundary: code that escapes it runs with
+// this process's rights. Those rights are limited only where the Node version
+// supports the permission model, which the backend then uses to confine the
+// process to reading the extension directories. The backend also disallows code
+// generation from strings, but that covers this script's own realm only; inside
+// the contexts `eval` and `new Function` still work, and the module loader below
+// relies on them.
+'use strict';
+
+const fs = require('fs');
+const { builtinModules } = require('module');
+const path = require('path');
+const readline = require('readline');
+const util = require('util');
+const vm = require('vm');
+
+const LOG_LEVELS = new Set(['log', 'info', 'warn', 'error', 'debug']);
+
+// setTimeout fires at once for delays past a signed 32-bit millisecond count
+const MAX_TIMER_DELAY = 2 ** 31 - 1;
+
+const write = process.stdout.write.bind(process.stdout);
+const send = (message) => write(JSON.stringify(message) + '\n');
+
+// stdout carries the protocol, so the host's own console output becomes log messages
+for (const level of LOG_LEVELS) {
+  console[level] = (...args) => send({ type: 'log', level, message: util.format(...args) });
+}
+
+let workspaceRoot = null;
+let configuration = {};
+const extensions = new Map(); // id -> { id, root, main, dispatch, timers }
+const commands = new Map(); // command -> extension id
+const pending = new Map(); // backend request id -> { ext, id }
+const calls = new Map(); // call id -> { ext, resolve, reject }
+let nextRequestId = 1;
+let nextCallId = 1;
+
+// Values from a context are only ever read through these, never touched directly
+function describe(error) {
+  try {
+    return String(error && error.stack ? error.stack : error);
+  } catch {
+    return 'Unknown error';
+  }
+}
+
+const errorMessage = (e) => (e instanceof Error ? e.message : describe(e));
+
+function inside(root, filename) {
+  return filename === root || filename.startsWith(root + path.sep);
+}
+
+function isFile(filename) {
+  try {
+    return fs.statSync(filename).isFile();
+  } catch {
+    return false;
+  }
+}
+
+function resolveFile(base) {
+  for (const candidate of [base, base + '.js', base + '.json']) {
+    if (isFile(candidate)) return candidate;
+  }
+  let main = 'index.js';
+  try {
+    main = JSON.parse(fs.readFileSync(path.join(base, 'package.json'), 'utf8')).main || main;
+  } catch {
+    // No package.json: fall back to index.js
+  }
+  const entry = path.join(base, main);
+  return [entry, entry + '.js', path.join(base, 'index.js'), path.join(base, 'index.json')].find(isFile) || null;
+}
+
+// Extensions can load their own files and bundled node_modules, nothing else
+function resolveModule(ext, from, specifier) {
+  const name = specifier.replace(/^node:/, '').split('/')[0];
+  if (specifier.startsWith('node:') || builtinModules.includes(name)) {
+    throw new Error(`Module '${specifier}' is not available to extensions`);
+  }
+
+  const bases = [];
+  if (specifier.startsWith('.') || path.isAbsolute(specifier)) {
+    bases.push(path.resolve(from, specifier));
+  } else {
+    for (let dir = path.resolve(from); inside(ext.root, dir); dir = path.dirname(dir)) {
+      bases.push(path.join(dir, 'node_modules', specifier));
+      if (dir === ext.root) break;
+    }
+  }
+
+  for (const base of bases.filter((b) => inside(ext.root, b))) {
+    const filename = resolveFile(base);
+    if (!filename) continue;
+    const real = fs.realpathSync(filename);
+    if (!inside(ext.root, real)) throw new Error(`Module '${specifier}' is outside the extension`);
+    return { filename: real, dirname: path.dirname(real), source: fs.readFileSync(real, 'utf8') };
+  }
+  throw new Error(`Cannot find module '${specifier}'`);
+}
+
+function deliver(ext, message) {
+  const { dispatch } = ext;
+  try {
+    dispatch(JSON.stringify(message));
+  } catch (e) {
+    console.error(`Extension ${ext.id} failed to handle a message: ${describe(e)}`);
+  }
+}
+
+function callExtension(ext, message) {
+  const call = nextCallId++;
+  return new Promise((resolve, reject) => {
+    calls.set(call, { ext, resolve, reject });
+    deliver(ext, { ...message, call });
+  });
+}
+
+async function runCommand(command, args) {
+  const ext = extensions.get(commands.get(command));
+  if (!ext) throw new Error(`Command '${command}' is not registered`);
+  return callExtension(ext, { type: 'execute', command, args: args || [] });
+}
+
+function clearTimer(ext, id) {
+  clearTimeout(ext.timers.get(id));
+  ext.timers.delete(id);
+}
+
+// Requests from inside a context; params and results are plain JSON
+function hostCall(ext, method, params) {
+  switch (method) {
+    case 'environment':
+      return {
+        id: ext.id,
+        root: ext.root,
+        main: ext.main,
+        workspaceRoot,
+        workspaceName: workspaceRoot ? path.basename(workspaceRoot) : null,
+        configuration,
+      };
+    case 'resolve':
+      return resolveModule(ext, String(params.from), String(params.specifier));
+    case 'joinPath':
+      return path.join(...params.map(String));
+    case 'log':
+      send({
+        type: 'log',
+        extension: ext.id,
+        level: LOG_LEVELS.has(params.level) ? params.level : 'info',
+        channel: typeof params.channel === 'string' ? params.channel : undefined,
+        message: String(params.message),
+      });
+      return null;
+    case 'ui':
+      send({ ...params, type: 'ui', extension: ext.id });
+      return null;
+    case 'register_command':
+      commands.set(String(params.command), ext.id);
+      send({ type: 'register_command', extension: ext.id, command: String(params.command) });
+      return null;
+    case 'unregister_command':
+      if (commands.get(params.command) === ext.id) {
+        commands.delete(params.command);
+        send({ type: 'unregister_command', extension: ext.id, command: params.command });
+      }
+      return null;
+    case 'commands':
+      return [...commands.keys()];
+    case 'request': {
+      const id = nextRequestId++;
+      pending.set(id, { ext, id: params.id });
+      send({ type: 'request', id, method: String(params.method), params: params.params });
+      return null;
+    }
+    case 'execute':
+      runCommand(String(params.command), Array.isArray(params.args) ? params.args : []).then(
+        (result) => deliver(ext, { type: 'response', id: params.id, result }),
+        (e) => deliver(ext, { type: 'response', id: params.id, error: errorMessage(e) }),
+      );
+      return null;
+    case 'setTimer': {
+      clearTimer(ext, params.id);
+      const delay = Math.min(Math.max(Number(params.ms) || 0, 0), MAX_TIMER_DELAY);
+      ext.timers.set(params.id, setTimeout(() => {
+        ext.timers.delete(params.id);
+        deliver(ext, { type: 'timer', id: params.id });
+      }, delay));
+      return null;
+    }
+    case 'clearTimer':
+      clearTimer(ext, params.id);
+      return null;
+    case 'done': {
+      const waiter = calls.get(params.call);
+      if (!waiter || waiter.ext !== ext) return null;
+      calls.delete(params.call);
+      if ('error' in params) waiter.reject(new Error(String(params.error)));
+      else waiter.resolve(params.result === undefined ? null : params.result);
+      return null;
+    }
+    default:
+      throw new Error(`Unknown host call '${method}'`);
+  }
+}
+
+// Runs inside each extension's context, compiled from its source text, so it
+// must not refer to anything else in this file. `hostCall` takes a method name
+// and JSON params and returns JSON; it is removed from the global object before
+// extension code runs and never handed out. Returns the context's dispatch
+// function, which takes messages from the host as JSON.
+function sandboxMain(hostCall) {
+  'use strict';
+  delete globalThis.hostCall;
+  const { parse, stringify } = JSON;
+
+  function call(method, params) {
+    let reply;
+    try {
+      reply = hostCall(method, stringify(params === undefined ? null : params));
+    } catch {
+      throw new Error(`Extension host call '${method}' failed`);
+    }
+    const { result, error } = parse(reply);
+    if (error !== undefined) throw new Error(error);
+    return result;
+  }
+
+  const env = call('environment');
+
+  const format = (args) =>
+    args
+      .map((arg) => {
+        if (typeof arg === 'string') return arg;
+        if (arg instanceof Error) return arg.stack || arg.message;
+        try {
+          return stringify(arg) ?? String(arg);
+        } catch {
+          return String(arg);
+        }
+      })
+      .join(' ');
+  const console = {};
+  for (const level of ['log', 'info', 'warn', 'error', 'debug']) {
+    console[level] = (...args) => call('log', { level, message: format(args) });
+  }
+
+  const timers = new Map();
+  let nextTimer = 1;
+  const schedule = (fn, ms, args, repeat) => {
+    const id = nextTimer++;
+    timers.set(id, { fn, ms, args, repeat });
+    call('setTimer', { id, ms });
+    return id;
+  };
+  const cancel = (id) => {
+    if (timers.delete(id)) call('clearTimer', { id });
+  };
+  function fire(id) {
+    const timer = timers.get(id);
+    if (!timer) return;
+    if (timer.repeat) call('setTimer', { id, ms: timer.ms });
+    else timers.delete(id);
+    try {
+      timer.fn(...timer.args);
+    } catch (e) {
+      console.error('Uncaught exception in extension:', e);
+    }
+  }
+
+  Object.assign(globalThis, {
+    console,
+    setTimeout: (fn, ms, ...args) => schedule(fn, ms, args, false),
+    setInterval: (fn, ms, ...args) => schedule(fn, ms, args, true),
+    setImmediate: (fn, ...args) => schedule(fn, 0, args, false),
+    clearTimeout: cancel,
+    clearInterval: cancel,
+    clearImmediate: cancel,
+    queueMicrotask: (fn) => {
+      Promise.resolve().then(fn);
+    },
+  });
+
+  const encodeUtf8 = (text) => Uint8Array.from(unescape(encodeURIComponent(text)), (c) => c.charCodeAt(0));
+  function decodeUtf8(bytes) {
+    let binary = '';
+    for (const byte of bytes) binary += String.fromCharCode(byte);
+    return decodeURIComponent(escape(binary));
+  }
+
+  // Backend requests and other extensions' commands answer with a response message
+  const waiting = new Map();
+  let nextWait = 1;
+  const ask = (method, params) =>
+    new Promise((resolve, reject) => {
+      const id = nextWait++;
+      waiting.set(id, { resolve, reject });
+      try {
+        call(method, { ...params, id });
+      } catch (e) {
+        waiting.delete(id);
+        reject(e);
+      }
+    });
+
+  const disposable = (fn) => ({ dispose: fn || (() => {}) });
+
+  function uriFile(fsPath) {
+    return {
+      scheme: 'file',
+      fsPath,
+      path: fsPath,
+      toString: () => 'file://' + fsPath,
+    };
+  }
+
+  class EventEmitter {
+    constructor() {
+      this.listeners = new Set();
+      this.event = (listener) => {
+        this.listeners.add(listener);
+        return disposable(() => this.listeners.delete(listener));
+      };
+    }
+    fire(event) {
+      for (const listener of this.listeners) listener(event);
+    }
+    dispose() {
+      this.listeners.clear();
+    }
+  }
+
+  function memento() {
+    const values = new Map();
+    return {
+      keys: () => [...values.keys()],
+      get: (key, fallback) => (values.has(key) ? values.get(key) : fallback),
+      update: (key, value) => {
+        values.set(key, value);
+        return Promise.resolve();
+      },
+    };
+  }
+
+  const handlers = new Map();
+  const ui = (kind, payload) => call('ui', { kind, ...payload });
+  const message = (severity) => (text, ...items) => {
+    ui('message', { severity, message: String(text), items: items.filter((i) => typeof i === 'string') });
+    return Promise.resolve(undefined);
+  };
+  const fields = (item) => ({
+    text: item.text, tooltip: item.tooltip, command: item.command, alignment: item.alignment, priority: item.priority,
+  });
+  let nextItem = 1;
+
+  const vscode = {
+    version: '1.80.0',
+    Disposable: Object.assign(function Disposable(fn) { return disposable(fn); }, {
+      from: (...items) => disposable(() => items.forEach((d) => d.dispose())),
+    }),
+    EventEmitter,
+    Uri: {
+      file: uriFile,
+      joinPath: (base, ...parts) => uriFile(call('joinPath', [base.fsPath, ...parts])),
+    },
+    StatusBarAlignment: { Left: 1, Right: 2 },
+    commands: {
+      registerCommand(command, handler, thisArg) {
+        handlers.set(command, handler.bind(thisArg));
+        call('register_command', { command });
+        return disposable(() => {
+          handlers.delete(command);
+          call('unregister_command', { command });
+        });
+      },
+      executeCommand: (command, ...args) =>
+        handlers.has(command)
+          ? Promise.resolve().then(() => handlers.get(command)(...args))
+          : ask('execute', { command, args }),
+      getCommands: () => Promise.resolve(call('commands')),
+    },
+    window: {
+      showInformationMessage: message('info'),
+      showWarningMessage: message('warning'),
+      showErrorMessage: message('error'),
+      createStatusBarItem(alignment, priority) {
+        const itemId = `${env.id}:${nextItem++}`;
+        const item = {
+          alignment, priority, text: '', tooltip: '', command: undefined,
+          show: () => ui('status_bar', { id: itemId, visible: true, ...fields(item) }),
+          hide: () => ui('status_bar', { id: itemId, visible: false }),
+          dispose: () => ui('status_bar', { id: itemId, visible: false, disposed: true }),
+        };
+        return item;
+      },
+      createOutputChannel(name) {
+        const log = (text) => call('log', { level: 'info', channel: name, message: String(text) });
+        return { name, append: log, appendLine: log, clear() {}, show() {}, hide() {}, dispose() {} };
+      },
+    },
+    workspace: {
+      get workspaceFolders() {
+        return env.workspaceRoot ? [{ uri: uriFile(env.workspaceRoot), name: env.workspaceName, index: 0 }] : undefined;
+      },
+      get rootPath() {
+        return env.workspaceRoot || undefined;
+      },
+      getConfiguration(section) {
+        const key = (k) => (section ? `${section}.${k}` : k);
+        return {
+          get: (k, fallback) => (key(k) in env.configuration ? env.configuration[key(k)] : fallback),
+          has: (k) => key(k) in env.configuration,
+          update: () => Promise.reject(new Error('Settings are read-only for extensions')),
+        };
+      },
+      fs: {
+        readFile: async (uri) =>
+          encodeUtf8(await ask('request', { method: 'fs.readFile', params: { path: uri.fsPath } })),
+        writeFile: (uri, content) =>
+          ask('request', {
+            method: 'fs.writeFile',
+            params: { path: uri.fsPath, content: typeof content === 'string' ? content : decodeUtf8(content) },
+          }),
+        readDirectory: (uri) => ask('request', { method: 'fs.readDirectory', params: { path: uri.fsPath } }),
+      },
+    },
+    env: { appName: 'Cyber Threat Range IDE', language: 'en' },
+  };
+
+  // CommonJS loader over the extension's own files
+  const modules = new Map();
+  function load(from, specifier) {
+    if (specifier === 'vscode') return vscode;
+    const { filename, dirname, source } = call('resolve', { from, specifier });
+    const cached = modules.get(filename);
+    if (cached) return cached.exports;
+
+    const module = { id: filename, filename, exports: {}, loaded: false };
+    modules.set(filename, module);
+    if (filename.endsWith('.json')) {
+      module.exports = parse(source);
+    } else {
+      const body = source.replace(/^#!.*/, '') + '\n//# sourceURL=' + filename;
+      const wrapper = new Function('exports', 'require', 'module', '__filename', '__dirname', body);
+      const require = (child) => load(dirname, child);
+      wrapper.call(module.exports, module.exports, require, module, filename, dirname);
+    }
+    module.loaded = true;
+    return module.exports;
+  }
+
+  const context = {
+    subscriptions: [],
+    extensionPath: env.root,
+    extensionUri: uriFile(env.root),
+    globalState: memento(),
+    workspaceState: memento(),
+    asAbsolutePath: (relative) => call('joinPath', [env.root, relative]),
+  };
+  let main = null;
+
+  // Values must survive JSON; anything else is dropped
+  function serializable(value) {
+    try {
+      return value === undefined ? null : parse(stringify(value));
+    } catch {
+      return null;
+    }
+  }
+
+  async function perform(request) {
+    switch (request.type) {
+      case 'activate':
+        main = load(env.root, env.main);
+        return typeof main.activate === 'function' ? main.activate(context) : null;
+      case 'execute': {
+        const handler = handlers.get(request.command);
+        if (!handler) throw new Error(`Command '${request.command}' is not registered`);
+        return handler(...request.args);
+      }
+      case 'deactivate':
+        if (main && typeof main.deactivate === 'function') await main.deactivate();
+        context.subscriptions.forEach((d) => d && d.dispose && d.dispose());
+        return null;
+      default:
+        throw new Error(`Unknown request '${request.type}'`);
+    }
+  }
+
+  return function dispatch(json) {
+    const incoming = parse(json);
+    switch (incoming.type) {
+      case 'response': {
+        const waiter = waiting.get(incoming.id);
+        if (!waiter) return;
+        waiting.delete(incoming.id);
+        if ('error' in incoming) waiter.reject(new Error(incoming.error));
+        else waiter.resolve(incoming.result);
+        return;
+      }
+      case 'timer':
+        fire(incoming.id);
+        return;
+      default:
+        perform(incoming).then(
+          (result) => call('done', { call: incoming.call, result: serializable(result) }),
+          (e) => call('done', { call: incoming.call, error: e && e.message ? e.message : String(e) }),
+        );
+    }
+  };
+}
+
+function createSandbox(id, root, main) {
+  const ext = { id, root: fs.realpathSync(root), main, dispatch: null, timers: new Map() };
+  // A null prototype keeps the host's Object out of reach through `globalThis`
+  const global = Object.create(null);
+  global.hostCall = (method, params) => {
+    try {
+      if (typeof method !== 'string' || typeof params !== 'string') throw new Error('Host calls take strings');
+      const result = hostCall(ext, method, JSON.parse(params));
+      return JSON.stringify({ result: result === undefined ? null : result });
+    } catch (e) {
+      return JSON.stringify({ error: errorMessage(e) });
+    }
+  };
+  const context = vm.createContext(global, { name: `extension:${id}` });
+  ext.dispatch = vm.runInContext(`(${sandboxMain})(globalThis.hostCall)`, context, {
+    filename: 'extension-host-sandbox.js',
+  });
+  return ext;
+}
+
+async function activate(id, root, main) {
+  if (extensions.has(id)) return null;
+  const ext = createSandbox(id, root, main);
+  extensions.set(id, ext);
+  try {
+    return await callExtension(ext, { type: 'activate' });
+  } catch (e) {
+    extensions.delete(id);
+    ext.timers.forEach(clearTimeout);
+    for (const [command, owner] of commands) {
+      if (owner === id) commands.delete(command);
+    }
+    throw e;
+  }
+}
+
+async function deactivateAll() {
+  await Promise.all(
+    [...extensions.values()].map((ext) =>
+      callExtension(ext, { type: 'deactivate' }).catch((e) =>
+        console.error(`Failed to deactivate ${ext.id}: ${e.message}`),
+      ),
+    ),
+  );
+}
+
+async function reply(id, work) {
+  try {
+    const result = await work();
+    send({ type: 'response', id, result: result === undefined ? null : result });
+  } catch (e) {
+    send({ type: 'response', id, error: errorMessage(e) });
+  }
+}
+
+readline.createInterface({ input: process.stdin }).on('line', (line) => {
+  let message;
+  try {
+    message = JSON.parse(line);
+  } catch {
+    return;
+  }
+  switch (message.type) {
+    case 'init':
+      workspaceRoot = message.workspaceRoot || null;
+      configuration = message.configuration || {};
+      send({ type: 'ready' });
+      break;
+    case 'activate':
+      reply(message.id, () => activate(message.extension, message.root, message.main));
+      break;
+    case 'execute':
+      reply(message.id, () => runCommand(message.command, message.args));
+      break;
+    case 'response': {
+      const waiter = pending.get(message.id);
+      if (!waiter) break;
+      pending.delete(message.id);
+      const response = { type: 'response', id: waiter.id };
+      if ('error' in message) response.error = message.error;
+      else response.result = message.result;
+      deliver(waiter.ext, response);
+      break;
+    }
+    case 'shutdown':
+      deactivateAll().finally(() => process.exit(0));
+      break;
+  }
+});
+
+// Rejections and exceptions from extension code land here too
+process.on('unhandledRejection', (e) => console.error(`Unhandled rejection in extension: ${describe(e)}`));
+process.on('uncaughtException', (e) => console.error(`Uncaught exception in extension: ${describe(e)}`));
@@ -0,0 +1,611 @@
+//! JavaScript extension host
+//!
+//! Runs the `main` scripts of enabled extensions in a Node sidecar process
+//! (`host.js`) that exposes a restricted `vscode` API. The two sides exchange
+//! newline-delimited JSON over stdio: the backend activates extensions and
+//! executes commands, the host registers commands, raises UI contributions and
+//! asks the backend for workspace file access. Each extension runs in its own
+//! `vm` context, which keeps extensions apart but is no security boundary; the
+//! process itself is confined only where Node supports the permission model,
+//! limited to reading the extension directories.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, oneshot};
+
+use super::{enabled_manifests, extensions_dir, InstalledManifest};
+use crate::api::editor_cmds;
+use crate::services::processes::{self, ManagedProcess, ProcessKind};
+use crate::services::project::trust;
+use crate::services::settings;
+use crate::utils::fs_utils::ctr_dir;
+
+const HOST_SCRIPT: &str = include_str!("host.js");
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContributedCommand {
+    pub command: String,
+    pub title: String,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HostedExtension {
+    pub id: String,
+    pub main: String,
+    pub activation_events: Vec<String>,
+    pub activated: bool,
+    pub error: Option<String>,
+    /// Commands registered at runtime
+    pub commands: Vec<String>,
+    /// Commands declared in `contributes.commands`, for menus and the palette
+    pub contributed_commands: Vec<ContributedCommand>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HostStatus {
+    pub running: bool,
+    pub node_version: Option<String>,
+    /// Whether Node's permission model is confining the host
+    pub sandboxed: bool,
+    pub workspace_root: Option<String>,
+    pub extensions: Vec<HostedExtension>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostEvent {
+    /// A UI request from an extension (message, status bar item)
+    Ui { extension: String, payload: Value },
+    Log {
+        extension: Option<String>,
+        level: String,
+        message: String,
+    },
+    Exited { code: Option<i32> },
+}
+
+type EventCallback = Arc<dyn Fn(HostEvent) + Send + Sync>;
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+type Handles = (mpsc::UnboundedSender<String>, Pending, Arc<Mutex<HostState>>);
+
+struct HostState {
+    node_version: String,
+    sandboxed: bool,
+    workspace_root: Option<String>,
+    extensions: Vec<HostedExtension>,
+    /// Extension roots keyed by id, for activation
+    roots: HashMap<String, (PathBuf, PathBuf)>,
+}
+
+struct RunningHost {
+    instance: u64,
+    outgoing: mpsc::UnboundedSender<String>,
+    pending: Pending,
+    state: Arc<Mutex<HostState>>,
+    shutdown: oneshot::Sender<()>,
+}
+
+lazy_static::lazy_static! {
+    static ref HOST: Mutex<Option<RunningHost>> = Mutex::new(None);
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Id of the host in the process registry; there is one host at a time
+const HOST_PROCESS_ID: &str = "extension-host";
+
+pub fn status() -> HostStatus {
+    let host = HOST.lock().unwrap();
+    match host.as_ref() {
+        Some(host) => {
+            let state = host.state.lock().unwrap();
+            HostStatus {
+                running: true,
+                node_version: Some(state.node_version.clone()),
+                sandboxed: state.sandboxed,
+                workspace_root: state.workspace_root.clone(),
+                extensions: state.extensions.clone(),
+            }
+        }
+        None => HostStatus {
+            running: false,
+            node_version: None,
+            sandboxed: false,
+            workspace_root: None,
+            extensions: Vec::new(),
+        },
+    }
+}
+
+/// Permission model flag for a Node version string like "v22.13.1"
+fn permission_flag(version: &str) -> Option<&'static str> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+    match (major, minor) {
+        (m, _) if m >= 23 => Some("--permission"),
+        (22, m) if m >= 13 => Some("--permission"),
+        (20..=22, _) => Some("--experimental-permission"),
+        _ => None,
+    }
+}
+
+/// The script named by `main`, which may omit its .js suffix or name a directory
+fn resolve_main(ext: &InstalledManifest, main: &str) -> Option<PathBuf> {
+    [main.to_string(), format!("{}.js", main), format!("{}/index.js", main)]
+        .iter()
+        .filter_map(|candidate| ext.resolve(candidate).ok())
+        .find(|path| path.is_file())
+}
+
+fn hosted_extensions() -> (Vec<HostedExtension>, HashMap<String, (PathBuf, PathBuf)>) {
+    let mut hosted = Vec::new();
+    let mut roots = HashMap::new();
+
+    for ext in enabled_manifests() {
+        let Some(main) = ext.manifest.get("main").and_then(Value::as_str) else {
+            continue;
+        };
+        let Some(main_path) = resolve_main(&ext, main) else {
+            continue;
+        };
+        let activation_events = ext
+            .manifest
+            .get("activationEvents")
+            .and_then(Value::as_array)
+            .map(|events| events.iter().filter_map(Value::as_str).map(String::from).collect())
+            .unwrap_or_default();
+        let contributed_commands = ext
+            .contributions("commands")
+            .iter()
+            .filter_map(|c| {
+                Some(ContributedCommand {
+                    command: c.get("command")?.as_str()?.to_string(),
+                    title: c.get("title").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    category: c.get("category").and_then(Value::as_str).map(String::from),
+                })
+            })
+            .collect();
+
+        let root = ext.root.canonicalize().unwrap_or_else(|_| ext.root.clone());
+        roots.insert(ext.id.clone(), (root, main_path.clone()));
+        hosted.push(HostedExtension {
+            id: ext.id,
+            main: main_path.to_string_lossy().to_string(),
+            activation_events,
+            activated: false,
+            error: None,
+            commands: Vec::new(),
+            contributed_commands,
+        });
+    }
+    (hosted, roots)
+}
+
+/// Extensions to activate at startup; the rest wait for one of their commands
+fn activates_eagerly(ext: &HostedExtension, has_workspace: bool) -> bool {
+    ext.activation_events.iter().any(|event| {
+        event == "*"
+            || event == "onStartupFinished"
+            || (has_workspace && event.starts_with("workspaceContains:"))
+    })
+}
+
+fn send(outgoing: &mpsc::UnboundedSender<String>, message: Value) -> Result<(), String> {
+    outgoing
+        .send(message.to_string())
+        .map_err(|_| "Extension host is not running".to_string())
+}
+
+fn handles() -> Result<Handles, String> {
+    let host = HOST.lock().unwrap();
+    let host = host.as_ref().ok_or("Extension host is not running")?;
+    Ok((host.outgoing.clone(), host.pending.clone(), host.state.clone()))
+}
+
+/// Send a message carrying a fresh request id and wait for the host's response
+async fn call(message: Value) -> Result<Value, String> {
+    let (outgoing, pending, _) = handles()?;
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let (tx, rx) = oneshot::channel();
+    pending.lock().unwrap().insert(id, tx);
+
+    let mut message = message;
+    message["id"] = json!(id);
+    send(&outgoing, message)?;
+
+    match tokio::time::timeout(REQUEST_TIMEOUT, rx).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("Extension host exited".to_string()),
+        Err(_) => {
+            pending.lock().unwrap().remove(&id);
+            Err("Extension host did not respond in time".to_string())
+        }
+    }
+}
+
+async fn activate(id: &str) -> Result<(), String> {
+    let (_, _, state) = handles()?;
+    let (root, main) = {
+        let state = state.lock().unwrap();
+        if let Some(workspace_root) = &state.workspace_root {
+            trust::require_trusted(workspace_root, "Running extensions")?;
+        }
+        state
+            .roots
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Extension {} has no script to run", id))?
+    };
+
+    let result = call(json!({
+        "type": "activate",
+        "extension": id,
+        "root": root,
+        "main": main,
+    }))
+    .await;
+
+    let mut state = state.lock().unwrap();
+    if let Some(ext) = state.extensions.iter_mut().find(|e| e.id == id) {
+        ext.activated = result.is_ok();
+        ext.error = result.as_ref().err().cloned();
+    }
+    result.map(|_| ())
+}
+
+/// Workspace path an extension asked for, refusing anything outside the workspace
+fn workspace_path(workspace_root: Option<&str>, path: &str) -> Result<PathBuf, String> {
+    let root = workspace_root.ok_or("No workspace is open")?;
+    let root = Path::new(root)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve workspace: {}", e))?;
+    let path = Path::new(path);
+    let resolved = match path.canonicalize() {
+        Ok(p) => p,
+        // New files: resolve the parent instead
+        Err(_) => {
+            let parent = path.parent().ok_or("Invalid path")?;
+            let name = path.file_name().ok_or("Invalid path")?;
+            parent
+                .canonicalize()
+                .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?
+                .join(name)
+        }
+    };
+    if !resolved.starts_with(&root) {
+        return Err(format!("{} is outside the workspace", path.display()));
+    }
+    Ok(resolved)
+}
+
+/// File system requests from extensions, served through the editor commands
+async fn serve_request(workspace_root: Option<String>, method: &str, params: &Value) -> Result<Value, String> {
+    let path = params.get("path").and_then(Value::as_str).ok_or("Missing path")?;
+    let path = workspace_path(workspace_root.as_deref(), path)?
+        .to_string_lossy()
+        .to_string();
+
+    match method {
+        "fs.readFile" => editor_cmds::read_file(path, None).await.map(Value::from),
+        "fs.writeFile" => {
+            let content = params.get("content").and_then(Value::as_str).unwrap_or_default();
+            editor_cmds::write_file(path, content.to_string()).await.map(|_| Value::Null)
+        }
+        "fs.readDirectory" => {
+            // [name, type] pairs using the vscode FileType values (1 file, 2 directory)
+            let nodes = editor_cmds::list_directory(path, None).await?;
+            Ok(Value::Array(
+                nodes
+                    .iter()
+                    .map(|n| json!([n.name, if n.node_type == "directory" { 2 } else { 1 }]))
+                    .collect(),
+            ))
+        }
+        _ => Err(format!("Unsupported host request: {}", method)),
+    }
+}
+
+fn handle_message(line: &str, outgoing: &mpsc::UnboundedSender<String>, pending: &Pending, state: &Arc<Mutex<HostState>>, on_event: &EventCallback) {
+    let Ok(message) = serde_json::from_str::<Value>(line) else {
+        on_event(HostEvent::Log {
+            extension: None,
+            level: "info".to_string(),
+            message: line.to_string(),
+        });
+        return;
+    };
+    let str_field = |key: &str| message.get(key).and_then(Value::as_str).map(String::from);
+
+    match message.get("type").and_then(Value::as_str).unwrap_or_default() {
+        "response" => {
+            let Some(id) = message.get("id").and_then(Value::as_u64) else {
+                return;
+            };
+            if let Some(tx) = pending.lock().unwrap().remove(&id) {
+                let result = match str_field("error") {
+                    Some(error) => Err(error),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let _ = tx.send(result);
+            }
+        }
+        kind @ ("register_command" | "unregister_command") => {
+            let (Some(extension), Some(command)) = (str_field("extension"), str_field("command")) else {
+                return;
+            };
+            let mut state = state.lock().unwrap();
+            if let Some(ext) = state.extensions.iter_mut().find(|e| e.id == extension) {
+                ext.commands.retain(|c| c != &command);
+                if kind == "register_command" {
+                    ext.commands.push(command);
+                }
+            }
+        }
+        "request" => {
+            let Some(id) = message.get("id").and_then(Value::as_u64) else {
+                return;
+            };
+            let method = str_field("method").unwrap_or_default();
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            let workspace_root = state.lock().unwrap().workspace_root.clone();
+            let outgoing = outgoing.clone();
+            tokio::spawn(async move {
+                let reply = match serve_request(workspace_root, &method, &params).await {
+                    Ok(result) => json!({ "type": "response", "id": id, "result": result }),
+                    Err(error) => json!({ "type": "response", "id": id, "error": error }),
+                };
+                let _ = send(&outgoing, reply);
+            });
+        }
+        "ui" => {
+            let mut payload = message.clone();
+            if let Some(object) = payload.as_object_mut() {
+                object.remove("type");
+                object.remove("extension");
+            }
+            on_event(HostEvent::Ui {
+                extension: str_field("extension").unwrap_or_default(),
+                payload,
+            });
+        }
+        "log" => on_event(HostEvent::Log {
+            extension: str_field("extension"),
+            level: str_field("level").unwrap_or_else(|| "info".to_string()),
+            message: str_field("message").unwrap_or_default(),
+        }),
+        _ => {}
+    }
+}
+
+/// Start the host and activate extensions whose activation events fire at
+/// startup; in an untrusted workspace extensions stay inactive
+pub async fn start<F>(workspace_root: Option<String>, on_event: F) -> Result<HostStatus, String>
+where
+    F: Fn(HostEvent) + Send + Sync + 'static,
+{
+    if HOST.lock().unwrap().is_some() {
+        return Err("Extension host is already running".to_string());
+    }
+
+    let version = Command::new("node")
+        .arg("--version")
+        .output()
+        .await
+        .map_err(|_| "Node.js is required to run extensions but was not found in PATH".to_string())?;
+    let node_version = String::from_utf8_lossy(&version.stdout).trim().to_string();
+
+    let host_dir = ctr_dir()?.join("extension-host");
+    std::fs::create_dir_all(&host_dir).map_err(|e| format!("Failed to create extension host directory: {}", e))?;
+    let script = host_dir.join("host.js");
+    std::fs::write(&script, HOST_SCRIPT).map_err(|e| format!("Failed to write extension host script: {}", e))?;
+
+    let (extensions, roots) = hosted_extensions();
+
+    let mut command = Command::new("node");
+    let flag = permission_flag(&node_version);
+    if let Some(flag) = flag {
+        command
+            .arg(flag)
+            .arg(format!("--allow-fs-read={}", host_dir.to_string_lossy()))
+            .arg(format!("--allow-fs-read={}", extensions_dir()?.to_string_lossy()));
+    }
+    // Applies to the host script's realm only, not to the extensions' vm contexts
+    let mut child = command
+        .arg("--disallow-code-generation-from-strings")
+        .arg("--no-warnings")
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start extension host: {}", e))?;
+
+    processes::register(
+        ManagedProcess::new(HOST_PROCESS_ID, ProcessKind::ExtensionHost, "Extension host").with_pid(child.id()),
+        Some(Box::new(|| {
+            let _ = stop();
+        })),
+    );
+
+    let mut stdin = child.stdin.take().ok_or("Failed to open extension host stdin")?;
+    let stdout = child.stdout.take().ok_or("Failed to capture extension host stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture extension host stderr")?;
+
+    let on_event: EventCallback = Arc::new(on_event);
+    let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
+    let state = Arc::new(Mutex::new(HostState {
+        node_version,
+        sandboxed: flag.is_some(),
+        workspace_root: workspace_root.clone(),
+        extensions,
+        roots,
+    }));
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    let instance = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+
+    // Writer
+    tokio::spawn(async move {
+        while let Some(line) = outgoing_rx.recv().await {
+            if stdin.write_all(format!("{}\n", line).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // Protocol reader
+    {
+        let outgoing = outgoing.clone();
+        let pending = pending.clone();
+        let state = state.clone();
+        let on_event = on_event.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                handle_message(&line, &outgoing, &pending, &state, &on_event);
+            }
+        });
+    }
+
+    // Stderr carries crashes and output that bypassed the console shim
+    {
+        let on_event = on_event.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                on_event(HostEvent::Log {
+                    extension: None,
+                    level: "error".to_string(),
+                    message: line,
+                });
+            }
+        });
+    }
+
+    // Lifetime: exit on shutdown or when the process dies
+    {
+        let pending = pending.clone();
+        let on_event = on_event.clone();
+        tokio::spawn(async move {
+            let code = tokio::select! {
+                _ = &mut shutdown_rx => {
+                    // Give extensions a moment to deactivate before killing the process
+                    match tokio::time::timeout(Duration::from_secs(3), child.wait()).await {
+                        Ok(status) => status.ok().and_then(|s| s.code()),
+                        Err(_) => {
+                            let _ = child.kill().await;
+                            None
+                        }
+                    }
+                }
+                status = child.wait() => status.ok().and_then(|s| s.code()),
+            };
+
+            let mut host = HOST.lock().unwrap();
+            if host.as_ref().is_some_and(|h| h.instance == instance) {
+                *host = None;
+                processes::unregister(HOST_PROCESS_ID);
+            }
+            drop(host);
+            // Dropping the senders fails any outstanding calls
+            pending.lock().unwrap().clear();
+            on_event(HostEvent::Exited { code });
+        });
+    }
+
+    let configuration = settings::effective(workspace_root.as_deref()).unwrap_or_default();
+    send(
+        &outgoing,
+        json!({
+            "type": "init",
+            "workspaceRoot": workspace_root,
+            "configuration": configuration,
+        }),
+    )?;
+
+    *HOST.lock().unwrap() = Some(RunningHost {
+        instance,
+        outgoing,
+        pending,
+        state: state.clone(),
+        shutdown: shutdown_tx,
+    });
+
+    // Nothing activates by itself in an untrusted workspace
+    let trusted = workspace_root.as_deref().map_or(true, trust::is_trusted);
+    let eager: Vec<String> = {
+        let state = state.lock().unwrap();
+        state
+            .extensions
+            .iter()
+            .filter(|ext| trusted && activates_eagerly(ext, workspace_root.is_some()))
+            .map(|ext| ext.id.clone())
+            .collect()
+    };
+    for id in eager {
+        // Failures are recorded on the extension; one bad extension must not stop the rest
+        let _ = activate(&id).await;
+    }
+
+    Ok(status())
+}
+
+/// Ask the host to deactivate extensions and exit
+pub fn stop() -> Result<(), String> {
+    let host = HOST.lock().unwrap().take().ok_or("Extension host is not running")?;
+    processes::unregister(HOST_PROCESS_ID);
+    let _ = send(&host.outgoing, json!({ "type": "shutdown" }));
+    let _ = host.shutdown.send(());
+    Ok(())
+}
+
+/// Run an extension command, activating the contributing extension first if needed
+pub async fn execute_command(command: &str, args: Vec<Value>) -> Result<Value, String> {
+    let (_, _, state) = handles()?;
+    let inactive_owner = {
+        let state = state.lock().unwrap();
+        let registered = state.extensions.iter().any(|e| e.commands.iter().any(|c| c == command));
+        if registered {
+            None
+        } else {
+            let activation_event = format!("onCommand:{}", command);
+            state
+                .extensions
+                .iter()
+                .find(|e| {
+                    !e.activated
+                        && (e.contributed_commands.iter().any(|c| c.command == command)
+                            || e.activation_events.contains(&activation_event))
+                })
+                .map(|e| e.id.clone())
+        }
+    };
+    if let Some(id) = inactive_owner {
+        activate(&id).await?;
+    }
+
+    call(json!({
+        "type": "execute",
+        "command": command,
+        "args": args,
+    }))
+    .await
+}
+
+/// Activate an extension on demand
+pub async fn activate_extension(id: &str) -> Result<HostStatus, String> {
+    activate(id).await?;
+    Ok(status())
+}
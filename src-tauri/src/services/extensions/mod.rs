@@ -0,0 +1,162 @@
+//! Installed extension contributions
+//!
+//! Reads the VS Code style `package.json` of extensions unpacked under
+//! ~/.ctr/extensions/<id>/ and resolves the files they contribute. Extension
+//! JSON is frequently JSONC (comments, trailing commas), so it is parsed
+//! leniently.
+
+pub mod grammars;
+pub mod host;
+pub mod themes;
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs_utils::{ctr_dir, load_json};
+
+pub struct InstalledManifest {
+    pub id: String,
+    /// Directory containing package.json; contribution paths are relative to it
+    pub root: PathBuf,
+    pub manifest: Value,
+}
+
+pub fn extensions_dir() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("extensions"))
+}
+
+fn disabled_extensions() -> Vec<String> {
+    match ctr_dir() {
+        Ok(dir) => load_json(&dir.join("extension_state.json")),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Directory holding an extension's package.json; .vsix archives nest it under extension/
+fn manifest_root(dir: &Path) -> Option<PathBuf> {
+    [dir.join("extension"), dir.to_path_buf()]
+        .into_iter()
+        .find(|root| root.join("package.json").is_file())
+}
+
+pub fn load_manifest(id: &str) -> Result<InstalledManifest, String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return Err(format!("Invalid extension id: {}", id));
+    }
+    let dir = extensions_dir()?.join(id);
+    let root = manifest_root(&dir).ok_or_else(|| format!("Extension {} has no package.json", id))?;
+    let content = std::fs::read_to_string(root.join("package.json"))
+        .map_err(|e| format!("Failed to read manifest for {}: {}", id, e))?;
+    Ok(InstalledManifest {
+        id: id.to_string(),
+        root,
+        manifest: parse_jsonc(&content)?,
+    })
+}
+
+/// Manifests of every installed, enabled extension; unreadable ones are skipped
+pub fn enabled_manifests() -> Vec<InstalledManifest> {
+    let Ok(dir) = extensions_dir() else {
+        return Vec::new();
+    };
+    let disabled = disabled_extensions();
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut manifests: Vec<InstalledManifest> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .filter(|id| !disabled.contains(id))
+        .filter_map(|id| load_manifest(&id).ok())
+        .collect();
+    manifests.sort_by(|a, b| a.id.cmp(&b.id));
+    manifests
+}
+
+impl InstalledManifest {
+    /// Entries of `contributes.<point>`, or nothing if the extension has none
+    pub fn contributions(&self, point: &str) -> &[Value] {
+        self.manifest
+            .get("contributes")
+            .and_then(|c| c.get(point))
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Resolve a contributed path, refusing anything that escapes the extension
+    pub fn resolve(&self, relative: &str) -> Result<PathBuf, String> {
+        resolve_within(&self.root, &self.root, relative)
+    }
+}
+
+/// Resolve `relative` against `base`, requiring the result to stay inside `root`
+pub fn resolve_within(root: &Path, base: &Path, relative: &str) -> Result<PathBuf, String> {
+    let path = base
+        .join(relative)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", relative, e))?;
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve extension directory: {}", e))?;
+    if !path.starts_with(&root) {
+        return Err(format!("{} points outside the extension", relative));
+    }
+    Ok(path)
+}
+
+/// Parse JSON that may contain comments and trailing commas
+pub fn parse_jsonc(content: &str) -> Result<Value, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    let mut in_string = false;
+
+    while let Some(c) = rest.chars().next() {
+        if in_string {
+            let len = match c {
+                '\\' => 1 + rest[1..].chars().next().map_or(0, char::len_utf8),
+                '"' => {
+                    in_string = false;
+                    1
+                }
+                _ => c.len_utf8(),
+            };
+            out.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+        if rest.starts_with("//") || rest.starts_with("/*") {
+            rest = strip_leading_comments(rest);
+            out.push('\n');
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            // Drop the comma if the next significant character closes a container
+            ',' if strip_leading_comments(&rest[1..]).starts_with(['}', ']']) => {}
+            _ => out.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    serde_json::from_str(&out).map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+fn strip_leading_comments(mut s: &str) -> &str {
+    loop {
+        s = s.trim_start();
+        if let Some(rest) = s.strip_prefix("//") {
+            s = rest.split_once('\n').map(|(_, r)| r).unwrap_or("");
+        } else if let Some(rest) = s.strip_prefix("/*") {
+            s = rest.split_once("*/").map(|(_, r)| r).unwrap_or("");
+        } else {
+            return s;
+        }
+    }
+}
@@ -0,0 +1,456 @@
+//! Color and file icon themes contributed by extensions
+//!
+//! Color themes are normalized to one shape whatever their source: VS Code
+//! JSON themes (following `include` chains) or TextMate `.tmTheme` property
+//! lists. Icon themes have their icon and font paths made absolute so the
+//! frontend can load them directly.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{enabled_manifests, load_manifest, parse_jsonc, resolve_within, InstalledManifest};
+
+/// Limit on nested `include`s, which also guards against include cycles
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeKind {
+    Color,
+    Icon,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeInfo {
+    /// `<extension id>/<theme id>`
+    pub id: String,
+    pub extension_id: String,
+    pub label: String,
+    pub kind: ThemeKind,
+    /// light, dark, hc-dark or hc-light; color themes only
+    pub base: Option<String>,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenColor {
+    pub name: Option<String>,
+    pub scope: Vec<String>,
+    pub foreground: Option<String>,
+    pub background: Option<String>,
+    pub font_style: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ColorTheme {
+    pub id: String,
+    pub label: String,
+    pub base: String,
+    /// Workbench colors such as `editor.background`
+    pub colors: BTreeMap<String, String>,
+    pub token_colors: Vec<TokenColor>,
+    pub semantic_highlighting: bool,
+    pub semantic_token_colors: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IconDefinition {
+    /// Absolute path to an image icon
+    pub icon_path: Option<String>,
+    pub font_character: Option<String>,
+    pub font_color: Option<String>,
+    pub font_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IconFont {
+    pub id: String,
+    /// Absolute font file paths
+    pub src: Vec<String>,
+    pub weight: Option<String>,
+    pub style: Option<String>,
+    pub size: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IconTheme {
+    pub id: String,
+    pub label: String,
+    pub definitions: BTreeMap<String, IconDefinition>,
+    pub fonts: Vec<IconFont>,
+    /// The remaining associations (file, folder, fileExtensions, fileNames,
+    /// languageIds, light, highContrast, ...) as published
+    pub associations: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ThemeData {
+    Color(ColorTheme),
+    Icon(IconTheme),
+}
+
+fn base_from_ui_theme(ui_theme: &str) -> &'static str {
+    match ui_theme {
+        "vs" => "light",
+        "hc-black" => "hc-dark",
+        "hc-light" => "hc-light",
+        _ => "dark",
+    }
+}
+
+fn contributed(ext: &InstalledManifest) -> Vec<ThemeInfo> {
+    let points = [("themes", ThemeKind::Color), ("iconThemes", ThemeKind::Icon)];
+    let mut themes = Vec::new();
+
+    for (point, kind) in points {
+        for entry in ext.contributions(point) {
+            let Some(path) = entry.get("path").and_then(Value::as_str) else {
+                continue;
+            };
+            let label = entry
+                .get("label")
+                .and_then(Value::as_str)
+                .unwrap_or(path)
+                .to_string();
+            let theme_id = entry.get("id").and_then(Value::as_str).unwrap_or(&label);
+            themes.push(ThemeInfo {
+                id: format!("{}/{}", ext.id, theme_id),
+                extension_id: ext.id.clone(),
+                label: label.clone(),
+                kind,
+                base: (kind == ThemeKind::Color).then(|| {
+                    let ui_theme = entry.get("uiTheme").and_then(Value::as_str).unwrap_or("vs-dark");
+                    base_from_ui_theme(ui_theme).to_string()
+                }),
+                path: path.to_string(),
+            });
+        }
+    }
+    themes
+}
+
+/// Every color and icon theme contributed by enabled extensions
+pub fn list() -> Vec<ThemeInfo> {
+    enabled_manifests().iter().flat_map(contributed).collect()
+}
+
+/// Load and normalize a theme by the id returned from `list`
+pub fn load(id: &str) -> Result<ThemeData, String> {
+    let (extension_id, _) = id
+        .split_once('/')
+        .ok_or_else(|| format!("Invalid theme id: {}", id))?;
+    let ext = load_manifest(extension_id)?;
+    let info = contributed(&ext)
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Theme {} not found", id))?;
+    let path = ext.resolve(&info.path)?;
+
+    match info.kind {
+        ThemeKind::Color => {
+            let mut theme = ColorTheme {
+                id: info.id,
+                label: info.label,
+                base: info.base.unwrap_or_else(|| "dark".to_string()),
+                colors: BTreeMap::new(),
+                token_colors: Vec::new(),
+                semantic_highlighting: false,
+                semantic_token_colors: BTreeMap::new(),
+            };
+            load_color_theme(&ext.root, &path, &mut theme, 0)?;
+            Ok(ThemeData::Color(theme))
+        }
+        ThemeKind::Icon => load_icon_theme(&ext.root, &path, info.id, info.label).map(ThemeData::Icon),
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn is_tm_theme(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("tmTheme"))
+}
+
+/// Merge a theme file into `theme`; included themes are applied first so the
+/// including file wins
+fn load_color_theme(root: &Path, path: &Path, theme: &mut ColorTheme, depth: usize) -> Result<(), String> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err("Theme includes are nested too deeply".to_string());
+    }
+    let dir = path.parent().unwrap_or(root);
+
+    if is_tm_theme(path) {
+        let plist = parse_plist(&read(path)?)?;
+        apply_tm_settings(theme, plist.get("settings").unwrap_or(&Value::Null));
+        return Ok(());
+    }
+
+    let doc = parse_jsonc(&read(path)?)?;
+    if let Some(include) = doc.get("include").and_then(Value::as_str) {
+        let included = resolve_within(root, dir, include)?;
+        load_color_theme(root, &included, theme, depth + 1)?;
+    }
+
+    if let Some(colors) = doc.get("colors").and_then(Value::as_object) {
+        for (key, value) in colors {
+            if let Some(color) = value.as_str() {
+                theme.colors.insert(key.clone(), color.to_string());
+            }
+        }
+    }
+
+    match doc.get("tokenColors") {
+        // A path to a .tmTheme or JSON file holding the rules
+        Some(Value::String(file)) => {
+            let file = resolve_within(root, dir, file)?;
+            if is_tm_theme(&file) {
+                load_color_theme(root, &file, theme, depth + 1)?;
+            } else {
+                let rules = parse_jsonc(&read(&file)?)?;
+                let rules = rules.get("tokenColors").or(rules.get("settings")).unwrap_or(&rules);
+                apply_tm_settings(theme, rules);
+            }
+        }
+        Some(rules) => apply_tm_settings(theme, rules),
+        None => {}
+    }
+
+    if let Some(enabled) = doc.get("semanticHighlighting").and_then(Value::as_bool) {
+        theme.semantic_highlighting = enabled;
+    }
+    if let Some(colors) = doc.get("semanticTokenColors").and_then(Value::as_object) {
+        theme
+            .semantic_token_colors
+            .extend(colors.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    Ok(())
+}
+
+fn string_field(settings: &Value, key: &str) -> Option<String> {
+    settings.get(key).and_then(Value::as_str).map(String::from)
+}
+
+/// Apply TextMate style rules. A rule without a scope carries the global
+/// editor colors, which map onto workbench color keys.
+fn apply_tm_settings(theme: &mut ColorTheme, rules: &Value) {
+    let Some(rules) = rules.as_array() else {
+        return;
+    };
+
+    for rule in rules {
+        let Some(settings) = rule.get("settings") else {
+            continue;
+        };
+        let scope: Vec<String> = match rule.get("scope") {
+            Some(Value::String(s)) => s
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+            Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(String::from).collect(),
+            _ => Vec::new(),
+        };
+
+        if scope.is_empty() {
+            let globals = [
+                ("background", "editor.background"),
+                ("foreground", "editor.foreground"),
+                ("caret", "editorCursor.foreground"),
+                ("selection", "editor.selectionBackground"),
+                ("lineHighlight", "editor.lineHighlightBackground"),
+                ("invisibles", "editorWhitespace.foreground"),
+            ];
+            for (key, color_key) in globals {
+                if let Some(color) = string_field(settings, key) {
+                    theme.colors.entry(color_key.to_string()).or_insert(color);
+                }
+            }
+            continue;
+        }
+
+        theme.token_colors.push(TokenColor {
+            name: string_field(rule, "name"),
+            scope,
+            foreground: string_field(settings, "foreground"),
+            background: string_field(settings, "background"),
+            font_style: string_field(settings, "fontStyle"),
+        });
+    }
+}
+
+fn load_icon_theme(root: &Path, path: &Path, id: String, label: String) -> Result<IconTheme, String> {
+    let mut doc = parse_jsonc(&read(path)?)?;
+    let dir = path.parent().unwrap_or(root);
+    let absolute = |relative: &str| {
+        resolve_within(root, dir, relative)
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())
+    };
+
+    let mut definitions = BTreeMap::new();
+    if let Some(Value::Object(defs)) = doc.as_object_mut().and_then(|d| d.remove("iconDefinitions")) {
+        for (name, def) in defs {
+            definitions.insert(
+                name,
+                IconDefinition {
+                    icon_path: def.get("iconPath").and_then(Value::as_str).and_then(absolute),
+                    font_character: string_field(&def, "fontCharacter"),
+                    font_color: string_field(&def, "fontColor"),
+                    font_id: string_field(&def, "fontId"),
+                },
+            );
+        }
+    }
+
+    let mut fonts = Vec::new();
+    if let Some(Value::Array(entries)) = doc.as_object_mut().and_then(|d| d.remove("fonts")) {
+        for font in entries {
+            let src = font
+                .get("src")
+                .and_then(Value::as_array)
+                .map(|src| {
+                    src.iter()
+                        .filter_map(|s| s.get("path").and_then(Value::as_str))
+                        .filter_map(absolute)
+                        .collect()
+                })
+                .unwrap_or_default();
+            fonts.push(IconFont {
+                id: string_field(&font, "id").unwrap_or_default(),
+                src,
+                weight: string_field(&font, "weight"),
+                style: string_field(&font, "style"),
+                size: string_field(&font, "size"),
+            });
+        }
+    }
+
+    Ok(IconTheme {
+        id,
+        label,
+        definitions,
+        fonts,
+        associations: doc,
+    })
+}
+
+/// Minimal XML property list reader covering what .tmTheme files use
+fn parse_plist(content: &str) -> Result<Value, String> {
+    let token_re = Regex::new(r"(?s)<!--.*?-->|<\?.*?\?>|<!DOCTYPE[^>]*>|<(/?)([A-Za-z]+)[^>]*?(/?)>|([^<]+)")
+        .map_err(|e| e.to_string())?;
+    let mut tokens = Vec::new();
+    for caps in token_re.captures_iter(content) {
+        if let Some(name) = caps.get(2) {
+            let closing = !caps[1].is_empty();
+            let empty = !caps[3].is_empty();
+            tokens.push(PlistToken::Tag(name.as_str().to_string(), closing, empty));
+        } else if let Some(text) = caps.get(4) {
+            tokens.push(PlistToken::Text(text.as_str().to_string()));
+        }
+    }
+
+    let mut pos = 0;
+    // Skip the outer <plist> wrapper
+    while let Some(PlistToken::Tag(name, _, _)) = tokens.get(pos) {
+        if name != "plist" {
+            break;
+        }
+        pos += 1;
+    }
+    plist_value(&tokens, &mut pos)
+}
+
+enum PlistToken {
+    /// name, closing, self-closing
+    Tag(String, bool, bool),
+    Text(String),
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Skip whitespace text and return the next tag
+fn next_tag<'a>(tokens: &'a [PlistToken], pos: &mut usize) -> Option<(&'a str, bool, bool)> {
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        if let PlistToken::Tag(name, closing, empty) = token {
+            return Some((name, *closing, *empty));
+        }
+    }
+    None
+}
+
+fn plist_text(tokens: &[PlistToken], pos: &mut usize) -> String {
+    let mut text = String::new();
+    while let Some(PlistToken::Text(t)) = tokens.get(*pos) {
+        text.push_str(t);
+        *pos += 1;
+    }
+    // Consume the closing tag
+    next_tag(tokens, pos);
+    xml_unescape(&text)
+}
+
+fn plist_value(tokens: &[PlistToken], pos: &mut usize) -> Result<Value, String> {
+    let (name, closing, empty) = next_tag(tokens, pos).ok_or("Unexpected end of property list")?;
+    if closing {
+        return Err(format!("Unexpected </{}> in property list", name));
+    }
+
+    match name {
+        "dict" => {
+            let mut map = Map::new();
+            if empty {
+                return Ok(Value::Object(map));
+            }
+            loop {
+                match next_tag(tokens, pos) {
+                    Some(("dict", true, _)) => break,
+                    Some(("key", false, _)) => {
+                        let key = plist_text(tokens, pos);
+                        map.insert(key, plist_value(tokens, pos)?);
+                    }
+                    other => return Err(format!("Malformed dict in property list: {:?}", other)),
+                }
+            }
+            Ok(Value::Object(map))
+        }
+        "array" => {
+            let mut items = Vec::new();
+            if empty {
+                return Ok(Value::Array(items));
+            }
+            loop {
+                let mark = *pos;
+                if let Some(("array", true, _)) = next_tag(tokens, pos) {
+                    break;
+                }
+                *pos = mark;
+                items.push(plist_value(tokens, pos)?);
+            }
+            Ok(Value::Array(items))
+        }
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ if empty => Ok(Value::String(String::new())),
+        "integer" | "real" => {
+            let text = plist_text(tokens, pos);
+            Ok(text.trim().parse::<f64>().map(Value::from).unwrap_or(Value::Null))
+        }
+        // string, date, data
+        _ => Ok(Value::String(plist_text(tokens, pos))),
+    }
+}
@@ -0,0 +1,543 @@
+//! Findings Store
+//!
+//! Every finding of a workspace, whichever engine reported it, kept in
+//! `.ctr/findings.json` and identified by the same fingerprint as triage.
+//! Each scan is reconciled with what is stored: new findings open, findings
+//! the scan no longer reports within its scope are marked fixed, and fixed
+//! findings that come back are reopened. Status changes, automatic or made
+//! by a user, are kept as history.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::analysis::AnalysisResult;
+use crate::services::intel::cve::{DependencyFinding, DependencyScan};
+use crate::services::openapi::ApiRisk;
+use crate::services::progress::{self, ProgressEvent, ProgressKind};
+use crate::services::project::roots;
+use crate::services::security::containers::ContainerScan;
+use crate::services::security::licenses::{DependencyLicense, PolicyVerdict};
+use crate::services::security::yara::YaraMatch;
+use crate::services::security::{SecurityIssue, Severity};
+use crate::services::triage;
+use crate::utils::fs_utils::{load_json, save_json, workspace_ctr_dir};
+use crate::utils::time::now_millis;
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles of the store files
+    static ref STORE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSource {
+    /// Pattern, Semgrep and structural rules
+    Scanner,
+    Prover,
+    /// Vulnerable dependencies linked to advisories
+    Dependency,
+    /// Infrastructure-as-code checks
+    Iac,
+    /// Dependency licenses failing the license policy
+    License,
+    /// Vulnerable packages of container images
+    Container,
+    /// YARA rule matches
+    Yara,
+    /// Risky endpoints of an imported OpenAPI document
+    OpenApi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingStatus {
+    Open,
+    /// Dismissed as a false positive
+    Triaged,
+    /// No longer reported by the scan that found it
+    Fixed,
+    /// A real issue whose risk was accepted
+    Accepted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub status: FindingStatus,
+    /// Unix timestamp in milliseconds
+    pub at: u64,
+    /// The user who changed it; None for changes made by a scan
+    pub author: Option<String>,
+    pub note: Option<String>,
+}
+
+/// A suggestion from AI triage, kept next to the finding for a user to act
+/// on; it never changes the finding's status by itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageAnnotation {
+    /// Findings judged to be the same underlying issue share a group
+    pub group: Option<String>,
+    pub likely_false_positive: bool,
+    pub reasoning: String,
+    /// Suggested review order, 1 first
+    pub priority: Option<usize>,
+    /// Unix timestamp in milliseconds
+    pub suggested_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub fingerprint: String,
+    pub source: FindingSource,
+    /// Rule id, sink type or advisory id
+    pub rule: String,
+    pub title: String,
+    pub message: String,
+    pub severity: Severity,
+    pub cwe: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    /// Name of the workspace root the file is in
+    #[serde(default)]
+    pub root: Option<String>,
+    pub status: FindingStatus,
+    /// Unix timestamps in milliseconds
+    pub first_seen: u64,
+    pub last_seen: u64,
+    #[serde(default)]
+    pub history: Vec<StatusChange>,
+    #[serde(default)]
+    pub annotation: Option<TriageAnnotation>,
+}
+
+impl Finding {
+    fn new(source: FindingSource, fingerprint: String, rule: String, title: String, message: String, severity: Severity) -> Self {
+        Self {
+            fingerprint,
+            source,
+            rule,
+            title,
+            message,
+            severity,
+            cwe: None,
+            file: None,
+            line: None,
+            root: None,
+            status: FindingStatus::Open,
+            first_seen: 0,
+            last_seen: 0,
+            history: Vec::new(),
+            annotation: None,
+        }
+    }
+
+    pub fn from_issue(issue: &SecurityIssue) -> Self {
+        let fingerprint = issue
+            .fingerprint
+            .clone()
+            .unwrap_or_else(|| triage::fingerprint(&issue.kind, Some(Path::new(&issue.file)), None, &issue.message));
+        let mut finding = Self::new(
+            FindingSource::Scanner,
+            fingerprint,
+            issue.rule.clone(),
+            issue.kind.clone(),
+            issue.message.clone(),
+            issue.severity.clone(),
+        );
+        finding.cwe = issue.cwe.clone();
+        finding.file = Some(issue.file.clone());
+        finding.line = Some(issue.line);
+        if issue.triage.is_some() {
+            finding.status = FindingStatus::Triaged;
+        }
+        finding
+    }
+
+    /// One finding per sink of an analysis; sinks proven exploitable take
+    /// the severity of their CVSS score, the others are Medium
+    pub fn from_analysis(analysis: &AnalysisResult, file: &str) -> Vec<Self> {
+        analysis
+            .sinks
+            .iter()
+            .map(|sink| {
+                let kind = format!("{:?}", sink.sink_type);
+                let fingerprint = sink
+                    .fingerprint
+                    .clone()
+                    .unwrap_or_else(|| triage::fingerprint(&kind, Some(Path::new(file)), None, &sink.code_snippet));
+                let severity = sink
+                    .cvss
+                    .as_ref()
+                    .and_then(|cvss| Severity::parse(&cvss.severity))
+                    .unwrap_or(Severity::Medium);
+                let mut finding = Self::new(
+                    FindingSource::Prover,
+                    fingerprint,
+                    kind,
+                    sink.sink_type.description().to_string(),
+                    sink.code_snippet.lines().next().unwrap_or("").trim().to_string(),
+                    severity,
+                );
+                finding.cwe = Some(sink.sink_type.cwe().to_string());
+                finding.file = Some(file.to_string());
+                finding.line = Some(sink.line);
+                if sink.triage.is_some() {
+                    finding.status = FindingStatus::Triaged;
+                }
+                finding
+            })
+            .collect()
+    }
+
+    /// One finding per advisory of a vulnerable dependency
+    pub fn from_dependency(dependency: &DependencyFinding) -> Vec<Self> {
+        let dep = &dependency.dependency;
+        let package = match &dep.version {
+            Some(version) => format!("{}@{}", dep.name, version),
+            None => dep.name.clone(),
+        };
+        dependency
+            .advisories
+            .iter()
+            .map(|advisory| {
+                let severity = advisory.severity.as_deref().and_then(Severity::parse).unwrap_or(Severity::Medium);
+                let mut finding = Self::new(
+                    FindingSource::Dependency,
+                    triage::fingerprint(&advisory.id, Some(Path::new(&dep.manifest)), None, &package),
+                    advisory.id.clone(),
+                    format!("Vulnerable dependency {}", package),
+                    advisory.description.lines().next().unwrap_or("").to_string(),
+                    severity,
+                );
+                finding.cwe = advisory.cwes.first().cloned();
+                finding.file = Some(dep.manifest.clone());
+                finding
+            })
+            .collect()
+    }
+
+    /// A dependency whose license the policy denies or flags for review;
+    /// None for allowed licenses
+    pub fn from_license(license: &DependencyLicense) -> Option<Self> {
+        let dep = &license.dependency;
+        let package = match &dep.version {
+            Some(version) => format!("{}@{}", dep.name, version),
+            None => dep.name.clone(),
+        };
+        let declared = license.license.as_deref().unwrap_or("no license");
+        let (rule, outcome, severity) = match license.verdict {
+            PolicyVerdict::Allowed => return None,
+            PolicyVerdict::Denied => ("license-denied", "denied", Severity::High),
+            PolicyVerdict::Review => ("license-review", "flagged for review", Severity::Low),
+        };
+        let mut finding = Self::new(
+            FindingSource::License,
+            triage::fingerprint(rule, Some(Path::new(&dep.manifest)), None, &package),
+            rule.to_string(),
+            format!("License of {}: {}", package, declared),
+            format!(
+                "{} ({}) is {} by the workspace license policy.",
+                declared,
+                license.category.name().replace('_', " "),
+                outcome
+            ),
+            severity,
+        );
+        finding.file = Some(dep.manifest.clone());
+        Some(finding)
+    }
+
+    /// One finding per vulnerable package of a container image, filed
+    /// under the Dockerfile it was built from or else the image reference
+    pub fn from_container(scan: &ContainerScan, workspace_root: &str) -> Vec<Self> {
+        let file = scan.dockerfile.clone().unwrap_or_else(|| scan.image.clone());
+        scan.vulnerabilities
+            .iter()
+            .map(|vuln| {
+                let package = format!("{}@{}", vuln.package, vuln.installed_version);
+                let fix = match &vuln.fixed_version {
+                    Some(fixed) => format!(" Fixed in {}.", fixed),
+                    None => String::new(),
+                };
+                let mut finding = Self::new(
+                    FindingSource::Container,
+                    triage::fingerprint(&vuln.id, Some(Path::new(&file)), Some(Path::new(workspace_root)), &package),
+                    vuln.id.clone(),
+                    format!("Vulnerable package {} in {}", package, scan.image),
+                    format!("{}{}", vuln.title.lines().next().unwrap_or(""), fix),
+                    vuln.severity.clone(),
+                );
+                finding.cwe = vuln.cwe.clone();
+                finding.file = Some(file.clone());
+                finding
+            })
+            .collect()
+    }
+
+    /// A YARA rule matching a file; the rule's `description` metadata is
+    /// the message when present
+    pub fn from_yara(found: &YaraMatch, workspace_root: &str) -> Self {
+        let description = found.metadata.get("description").and_then(|d| d.as_str()).map(String::from);
+        let patterns: Vec<&str> = found.matches.iter().map(|m| m.pattern.as_str()).collect();
+        let mut finding = Self::new(
+            FindingSource::Yara,
+            triage::fingerprint(&found.rule, Some(Path::new(&found.file)), Some(Path::new(workspace_root)), &found.namespace),
+            format!("yara:{}", found.rule),
+            format!("YARA rule {} matched", found.rule),
+            description.unwrap_or_else(|| format!("Matched {} in {}", patterns.join(", "), found.namespace)),
+            found.severity.clone(),
+        );
+        finding.file = Some(found.file.clone());
+        finding
+    }
+
+    /// A risky endpoint of the OpenAPI document at `spec_file`
+    pub fn from_api_risk(risk: &ApiRisk, spec_file: &str, workspace_root: &str) -> Self {
+        let endpoint = format!("{} {}", risk.method, risk.path);
+        let mut finding = Self::new(
+            FindingSource::OpenApi,
+            triage::fingerprint(risk.kind.rule(), Some(Path::new(spec_file)), Some(Path::new(workspace_root)), &endpoint),
+            risk.kind.rule().to_string(),
+            risk.title.clone(),
+            risk.message.clone(),
+            risk.severity.clone(),
+        );
+        finding.cwe = Some(risk.cwe.to_string());
+        finding.file = Some(spec_file.to_string());
+        finding
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FindingStore {
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+}
+
+/// What recording a scan changed in the store
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecordSummary {
+    pub added: usize,
+    pub reopened: usize,
+    pub fixed: usize,
+}
+
+/// Filters for listing findings; the defaults list everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FindingQuery {
+    pub sources: Vec<FindingSource>,
+    pub statuses: Vec<FindingStatus>,
+    pub min_severity: Option<Severity>,
+    /// Only findings in this file
+    pub file: Option<String>,
+    /// Only findings in the workspace root of this name
+    pub root: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FindingsSummary {
+    pub total: usize,
+    pub by_status: BTreeMap<String, usize>,
+    /// Severities of the findings still open
+    pub open_by_severity: BTreeMap<String, usize>,
+    pub by_source: BTreeMap<String, usize>,
+    /// Findings per workspace root
+    pub by_root: BTreeMap<String, usize>,
+}
+
+fn store_path(workspace_root: &str) -> Result<std::path::PathBuf, String> {
+    Ok(workspace_ctr_dir(workspace_root)?.join("findings.json"))
+}
+
+pub fn load(workspace_root: &str) -> Result<FindingStore, String> {
+    Ok(load_json(&store_path(workspace_root)?))
+}
+
+fn change_status(finding: &mut Finding, status: FindingStatus, at: u64, author: Option<String>, note: Option<String>) {
+    finding.status = status;
+    finding.history.push(StatusChange { status, at, author, note });
+}
+
+/// Reconcile the findings of one scan with the store. `scope` lists the
+/// files the scan covered; stored findings of the same source in those
+/// files, or anywhere when None, that were not reported again are marked
+/// fixed. Duplicate fingerprints keep the first occurrence.
+pub fn record(
+    workspace_root: &str,
+    source: FindingSource,
+    scope: Option<&[String]>,
+    findings: Vec<Finding>,
+) -> Result<RecordSummary, String> {
+    let guard = STORE_LOCK.lock().unwrap();
+    let mut store = load(workspace_root)?;
+    let now = now_millis();
+    let mut summary = RecordSummary::default();
+    let mut reported = HashSet::new();
+    let mut discovered = Vec::new();
+    let workspace_roots = roots::list(workspace_root).unwrap_or_default();
+
+    for mut incoming in findings {
+        if !reported.insert(incoming.fingerprint.clone()) {
+            continue;
+        }
+        incoming.root = incoming
+            .file
+            .as_deref()
+            .and_then(|file| roots::root_of(&workspace_roots, file))
+            .map(|root| root.name.clone());
+        match store.findings.iter_mut().find(|f| f.fingerprint == incoming.fingerprint) {
+            Some(existing) => {
+                existing.last_seen = now;
+                existing.line = incoming.line;
+                existing.file = incoming.file;
+                existing.root = incoming.root;
+                existing.message = incoming.message;
+                existing.severity = incoming.severity;
+                if existing.status == FindingStatus::Fixed {
+                    change_status(existing, FindingStatus::Open, now, None, Some("Reported again".to_string()));
+                    summary.reopened += 1;
+                }
+                // False-positive decisions made through triage carry over
+                if incoming.status == FindingStatus::Triaged && existing.status == FindingStatus::Open {
+                    change_status(existing, FindingStatus::Triaged, now, None, None);
+                }
+            }
+            None => {
+                incoming.first_seen = now;
+                incoming.last_seen = now;
+                let status = incoming.status;
+                change_status(&mut incoming, status, now, None, None);
+                if status == FindingStatus::Open {
+                    discovered.push(vulnerability_event(&incoming));
+                }
+                store.findings.push(incoming);
+                summary.added += 1;
+            }
+        }
+    }
+
+    for finding in &mut store.findings {
+        let in_scope = match scope {
+            None => true,
+            Some(files) => finding.file.as_ref().is_some_and(|f| files.contains(f)),
+        };
+        if finding.source == source && in_scope && finding.status != FindingStatus::Fixed && !reported.contains(&finding.fingerprint) {
+            change_status(finding, FindingStatus::Fixed, now, None, None);
+            summary.fixed += 1;
+        }
+    }
+
+    save_json(&store_path(workspace_root)?, &store)?;
+    drop(guard);
+    for event in discovered {
+        progress::record_quietly(event);
+    }
+    Ok(summary)
+}
+
+/// Progress event for a finding stored for the first time
+fn vulnerability_event(finding: &Finding) -> ProgressEvent {
+    let reference = match (&finding.file, finding.line) {
+        (Some(file), Some(line)) => format!("{}:{}", file, line),
+        (Some(file), None) => file.clone(),
+        _ => finding.title.clone(),
+    };
+    ProgressEvent {
+        id: 0,
+        user: progress::default_user(),
+        kind: ProgressKind::VulnerabilityFound,
+        skill: String::new(),
+        reference,
+        detail: format!("{} {} {}", finding.rule, finding.cwe.as_deref().unwrap_or(""), finding.title),
+        points: 0,
+        duration_ms: None,
+        created_at: 0,
+    }
+}
+
+/// Record a scan, logging rather than failing when the store is unavailable
+pub fn record_or_warn(workspace_root: &str, source: FindingSource, scope: Option<&[String]>, findings: Vec<Finding>) {
+    if let Err(e) = record(workspace_root, source, scope, findings) {
+        log::warn!("Failed to record findings: {}", e);
+    }
+}
+
+/// Record a dependency scan. When some lookups failed, nothing is marked
+/// fixed, since a failed lookup says nothing about the advisories it had
+pub fn record_dependency_scan(workspace_root: &str, scan: &DependencyScan) {
+    let found = scan.findings.iter().flat_map(Finding::from_dependency).collect();
+    let scope: Option<&[String]> = if scan.errors.is_empty() { None } else { Some(&[]) };
+    record_or_warn(workspace_root, FindingSource::Dependency, scope, found);
+}
+
+/// Set the status of a finding by hand, noting who changed it and why
+pub fn set_status(workspace_root: &str, fingerprint: &str, status: FindingStatus, note: Option<String>) -> Result<Finding, String> {
+    let _guard = STORE_LOCK.lock().unwrap();
+    let mut store = load(workspace_root)?;
+    let finding = store
+        .findings
+        .iter_mut()
+        .find(|f| f.fingerprint == fingerprint)
+        .ok_or_else(|| format!("No finding with fingerprint {}", fingerprint))?;
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    change_status(finding, status, now_millis(), Some(triage::current_author()), note);
+    let finding = finding.clone();
+    save_json(&store_path(workspace_root)?, &store)?;
+    Ok(finding)
+}
+
+/// Attach triage suggestions to findings by fingerprint, replacing earlier
+/// ones; returns how many findings were annotated
+pub fn annotate(workspace_root: &str, annotations: Vec<(String, TriageAnnotation)>) -> Result<usize, String> {
+    let _guard = STORE_LOCK.lock().unwrap();
+    let mut store = load(workspace_root)?;
+    let mut annotated = 0;
+    for (fingerprint, annotation) in annotations {
+        if let Some(finding) = store.findings.iter_mut().find(|f| f.fingerprint == fingerprint) {
+            finding.annotation = Some(annotation);
+            annotated += 1;
+        }
+    }
+    save_json(&store_path(workspace_root)?, &store)?;
+    Ok(annotated)
+}
+
+fn matches(finding: &Finding, query: &FindingQuery) -> bool {
+    (query.sources.is_empty() || query.sources.contains(&finding.source))
+        && (query.statuses.is_empty() || query.statuses.contains(&finding.status))
+        && query.min_severity.as_ref().map_or(true, |min| &finding.severity >= min)
+        && query.file.as_ref().map_or(true, |file| finding.file.as_ref() == Some(file))
+        && (query.root.is_none() || finding.root == query.root)
+}
+
+/// Stored findings matching a query, most severe first, then newest
+pub fn list(workspace_root: &str, query: &FindingQuery) -> Result<Vec<Finding>, String> {
+    let mut findings: Vec<Finding> = load(workspace_root)?.findings.into_iter().filter(|f| matches(f, query)).collect();
+    findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| b.last_seen.cmp(&a.last_seen)));
+    Ok(findings)
+}
+
+fn label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_lowercase()))
+        .unwrap_or_default()
+}
+
+pub fn summary(workspace_root: &str) -> Result<FindingsSummary, String> {
+    let store = load(workspace_root)?;
+    let mut summary = FindingsSummary { total: store.findings.len(), ..Default::default() };
+    for finding in &store.findings {
+        *summary.by_status.entry(label(&finding.status)).or_insert(0) += 1;
+        *summary.by_source.entry(label(&finding.source)).or_insert(0) += 1;
+        if let Some(root) = &finding.root {
+            *summary.by_root.entry(root.clone()).or_insert(0) += 1;
+        }
+        if finding.status == FindingStatus::Open {
+            *summary.open_by_severity.entry(label(&finding.severity)).or_insert(0) += 1;
+        }
+    }
+    Ok(summary)
+}
@@ -0,0 +1,263 @@
+//! HTTP client
+//!
+//! Sends arbitrary HTTP requests for the repeater, fuzzer, proxy and payload
+//! verifier, and keeps named request collections in
+//! <workspace>/.ctr/http_collections.json.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::utils::fs_utils::{load_json, save_json, workspace_ctr_dir};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HttpAuth {
+    Basic { username: String, password: Option<String> },
+    Bearer { token: String },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRequestSpec {
+    pub method: String,
+    pub url: String,
+    /// Ordered list so duplicate headers survive a round trip
+    #[serde(default)]
+    pub headers: Vec<HttpHeader>,
+    pub body: Option<String>,
+    /// Set when `body` holds base64-encoded binary data
+    #[serde(default)]
+    pub body_base64: bool,
+    pub auth: Option<HttpAuth>,
+    /// Upstream proxy, e.g. http://127.0.0.1:8080
+    pub proxy: Option<String>,
+    #[serde(default = "default_true")]
+    pub verify_tls: bool,
+    #[serde(default = "default_true")]
+    pub follow_redirects: bool,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponseData {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<HttpHeader>,
+    /// Text body, or base64 when `body_base64` is set
+    pub body: String,
+    pub body_base64: bool,
+    pub size_bytes: usize,
+    pub time_ms: u128,
+    pub final_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRequest {
+    pub name: String,
+    pub request: HttpRequestSpec,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpCollection {
+    pub name: String,
+    pub requests: Vec<SavedRequest>,
+}
+
+/// Imported OpenAPI documents and Postman collections larger than this are refused
+const MAX_IMPORT_BYTES: u64 = 20 * 1024 * 1024;
+
+fn is_text_content(content_type: &str) -> bool {
+    let ct = content_type.to_lowercase();
+    ct.starts_with("text/")
+        || ct.contains("json")
+        || ct.contains("xml")
+        || ct.contains("javascript")
+        || ct.contains("x-www-form-urlencoded")
+}
+
+fn build_client(spec: &HttpRequestSpec) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder()
+        .danger_accept_invalid_certs(!spec.verify_tls)
+        .redirect(if spec.follow_redirects {
+            reqwest::redirect::Policy::limited(10)
+        } else {
+            reqwest::redirect::Policy::none()
+        });
+
+    if let Some(ms) = spec.timeout_ms {
+        builder = builder.timeout(Duration::from_millis(ms));
+    }
+
+    if let Some(proxy) = spec.proxy.as_deref().filter(|p| !p.is_empty()) {
+        let proxy = reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy: {}", e))?;
+        builder = builder.proxy(proxy);
+    }
+
+    builder.build().map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Send a request and capture the full response. Shared by the fuzzer and proxy repeater.
+pub async fn send(spec: &HttpRequestSpec) -> Result<HttpResponseData, String> {
+    let client = build_client(spec)?;
+
+    let method = reqwest::Method::from_bytes(spec.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", spec.method))?;
+
+    let mut request = client.request(method, &spec.url);
+
+    for header in &spec.headers {
+        request = request.header(header.name.as_str(), header.value.as_str());
+    }
+
+    match &spec.auth {
+        Some(HttpAuth::Basic { username, password }) => {
+            request = request.basic_auth(username, password.as_ref());
+        }
+        Some(HttpAuth::Bearer { token }) => {
+            request = request.bearer_auth(token);
+        }
+        None => {}
+    }
+
+    if let Some(body) = &spec.body {
+        request = if spec.body_base64 {
+            let bytes = BASE64
+                .decode(body)
+                .map_err(|e| format!("Invalid base64 body: {}", e))?;
+            request.body(bytes)
+        } else {
+            request.body(body.clone())
+        };
+    }
+
+    let start = Instant::now();
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let final_url = response.url().to_string();
+    let headers: Vec<HttpHeader> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| HttpHeader {
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).to_string(),
+        })
+        .collect();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+    let time_ms = start.elapsed().as_millis();
+
+    // Binary bodies go back as base64 so the frontend never sees mangled bytes
+    let (body, body_base64) = match std::str::from_utf8(&bytes) {
+        Ok(text) if content_type.is_empty() || is_text_content(&content_type) => {
+            (text.to_string(), false)
+        }
+        _ => (BASE64.encode(&bytes), true),
+    };
+
+    Ok(HttpResponseData {
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or("").to_string(),
+        headers,
+        body,
+        body_base64,
+        size_bytes: bytes.len(),
+        time_ms,
+        final_url,
+    })
+}
+
+fn collections_file(workspace_root: &str) -> Result<PathBuf, String> {
+    Ok(workspace_ctr_dir(workspace_root)?.join("http_collections.json"))
+}
+
+pub fn collections(workspace_root: &str) -> Result<Vec<HttpCollection>, String> {
+    Ok(load_json(&collections_file(workspace_root)?))
+}
+
+fn save_collections(workspace_root: &str, collections: &[HttpCollection]) -> Result<(), String> {
+    save_json(&collections_file(workspace_root)?, collections)
+}
+
+/// Save (or overwrite) a named request in a collection, creating the collection if needed
+pub fn save_request(workspace_root: &str, collection: String, name: String, request: HttpRequestSpec) -> Result<(), String> {
+    if collection.trim().is_empty() || name.trim().is_empty() {
+        return Err("Collection and request names cannot be empty".to_string());
+    }
+
+    let mut all = collections(workspace_root)?;
+    let index = match all.iter().position(|c| c.name == collection) {
+        Some(i) => i,
+        None => {
+            all.push(HttpCollection {
+                name: collection,
+                requests: Vec::new(),
+            });
+            all.len() - 1
+        }
+    };
+
+    let requests = &mut all[index].requests;
+    match requests.iter_mut().find(|r| r.name == name) {
+        Some(existing) => existing.request = request,
+        None => requests.push(SavedRequest { name, request }),
+    }
+
+    save_collections(workspace_root, &all)
+}
+
+pub fn delete_request(workspace_root: &str, collection: &str, name: &str) -> Result<(), String> {
+    let mut all = collections(workspace_root)?;
+    let target = all
+        .iter_mut()
+        .find(|c| c.name == collection)
+        .ok_or_else(|| format!("Collection '{}' not found", collection))?;
+    target.requests.retain(|r| r.name != name);
+    save_collections(workspace_root, &all)
+}
+
+pub fn delete_collection(workspace_root: &str, collection: &str) -> Result<(), String> {
+    let mut all = collections(workspace_root)?;
+    all.retain(|c| c.name != collection);
+    save_collections(workspace_root, &all)
+}
+
+/// Store an imported collection, replacing one of the same name
+pub fn replace_collection(workspace_root: &str, collection: HttpCollection) -> Result<(), String> {
+    let mut all = collections(workspace_root)?;
+    all.retain(|c| c.name != collection.name);
+    all.push(collection);
+    save_collections(workspace_root, &all)
+}
+
+/// Read a document to import, refusing oversized files
+pub fn read_import(path: &str) -> Result<String, String> {
+    let size = std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path, e))?.len();
+    if size > MAX_IMPORT_BYTES {
+        return Err(format!("{} is too large to import", path));
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
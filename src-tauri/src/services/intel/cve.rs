@@ -0,0 +1,402 @@
+//! CVE and exploit lookups: NVD, GitHub security advisories and the Exploit-DB index.
+//!
+//! Results from every source are normalized into `CveRecord` so the frontend
+//! renders them the same way.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use super::{http_client, intel_dir, load_config};
+use crate::services::security::dependencies::Dependency;
+
+const NVD_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+const GITHUB_ADVISORIES_URL: &str = "https://api.github.com/advisories";
+const EXPLOITDB_CSV_URL: &str =
+    "https://gitlab.com/exploit-database/exploitdb/-/raw/main/files_exploits.csv";
+/// Re-download the Exploit-DB index once it is older than this
+const EXPLOITDB_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CveRecord {
+    /// CVE id, or the GHSA id when an advisory has no CVE assigned
+    pub id: String,
+    pub description: String,
+    pub cvss_score: Option<f64>,
+    pub severity: Option<String>,
+    pub cvss_vector: Option<String>,
+    pub published: Option<String>,
+    pub cwes: Vec<String>,
+    pub references: Vec<String>,
+    /// "nvd" or "github"
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExploitRecord {
+    pub id: String,
+    pub title: String,
+    pub platform: String,
+    pub exploit_type: String,
+    pub published: String,
+    pub codes: Vec<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyFinding {
+    pub dependency: Dependency,
+    pub advisories: Vec<CveRecord>,
+}
+
+/// A dependency whose advisory lookup failed
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyLookupError {
+    pub dependency: Dependency,
+    pub error: String,
+}
+
+/// Result of checking dependencies; lookups that failed don't stop the rest
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyScan {
+    /// Only the vulnerable dependencies
+    pub findings: Vec<DependencyFinding>,
+    pub errors: Vec<DependencyLookupError>,
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+fn parse_nvd_cve(cve: &Value) -> Option<CveRecord> {
+    let id = str_field(cve, "id")?;
+
+    let description = cve
+        .get("descriptions")
+        .and_then(|d| d.as_array())
+        .and_then(|d| {
+            d.iter()
+                .find(|x| x.get("lang").and_then(|l| l.as_str()) == Some("en"))
+                .or_else(|| d.first())
+        })
+        .and_then(|d| str_field(d, "value"))
+        .unwrap_or_default();
+
+    // Prefer the newest CVSS version present
+    let metrics = cve.get("metrics");
+    let metric = ["cvssMetricV40", "cvssMetricV31", "cvssMetricV30", "cvssMetricV2"]
+        .iter()
+        .find_map(|key| metrics.and_then(|m| m.get(key)).and_then(|m| m.get(0)));
+    let cvss_data = metric.and_then(|m| m.get("cvssData"));
+    let cvss_score = cvss_data
+        .and_then(|d| d.get("baseScore"))
+        .and_then(|s| s.as_f64());
+    let severity = cvss_data
+        .and_then(|d| str_field(d, "baseSeverity"))
+        .or_else(|| metric.and_then(|m| str_field(m, "baseSeverity")));
+    let cvss_vector = cvss_data.and_then(|d| str_field(d, "vectorString"));
+
+    let cwes = cve
+        .get("weaknesses")
+        .and_then(|w| w.as_array())
+        .map(|w| {
+            w.iter()
+                .filter_map(|x| x.get("description").and_then(|d| d.as_array()))
+                .flatten()
+                .filter_map(|d| str_field(d, "value"))
+                .filter(|v| v.starts_with("CWE-"))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let references = cve
+        .get("references")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|x| str_field(x, "url")).collect())
+        .unwrap_or_default();
+
+    Some(CveRecord {
+        id,
+        description,
+        cvss_score,
+        severity,
+        cvss_vector,
+        published: str_field(cve, "published"),
+        cwes,
+        references,
+        source: "nvd".to_string(),
+    })
+}
+
+async fn query_nvd(params: &[(&str, String)]) -> Result<Vec<CveRecord>, String> {
+    let mut request = http_client()?.get(NVD_URL).query(params);
+    if let Some(key) = load_config().nvd_api_key.filter(|k| !k.is_empty()) {
+        request = request.header("apiKey", key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("NVD request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("NVD returned HTTP {}", response.status()));
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse NVD response: {}", e))?;
+
+    Ok(body
+        .get("vulnerabilities")
+        .and_then(|v| v.as_array())
+        .map(|v| {
+            v.iter()
+                .filter_map(|x| x.get("cve"))
+                .filter_map(parse_nvd_cve)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Search NVD by keyword and/or CPE name (e.g. cpe:2.3:a:apache:log4j:2.14.1:*:*:*:*:*:*:*)
+pub async fn search_nvd(keyword: Option<&str>, cpe: Option<&str>, limit: usize) -> Result<Vec<CveRecord>, String> {
+    let mut params = vec![("resultsPerPage", limit.clamp(1, 2000).to_string())];
+    if let Some(keyword) = keyword.filter(|k| !k.is_empty()) {
+        params.push(("keywordSearch", keyword.to_string()));
+    }
+    if let Some(cpe) = cpe.filter(|c| !c.is_empty()) {
+        params.push(("cpeName", cpe.to_string()));
+    }
+    if params.len() == 1 {
+        return Err("Provide a keyword or CPE to search".to_string());
+    }
+    query_nvd(&params).await
+}
+
+pub async fn get_cve(cve_id: &str) -> Result<CveRecord, String> {
+    query_nvd(&[("cveId", cve_id.to_uppercase())])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("{} not found in NVD", cve_id))
+}
+
+fn parse_github_advisory(advisory: &Value) -> Option<CveRecord> {
+    let id = str_field(advisory, "cve_id").or_else(|| str_field(advisory, "ghsa_id"))?;
+    let cvss = advisory.get("cvss");
+
+    let mut references: Vec<String> = advisory
+        .get("references")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+    if let Some(url) = str_field(advisory, "html_url") {
+        references.insert(0, url);
+    }
+
+    Some(CveRecord {
+        id,
+        description: str_field(advisory, "summary")
+            .or_else(|| str_field(advisory, "description"))
+            .unwrap_or_default(),
+        cvss_score: cvss
+            .and_then(|c| c.get("score"))
+            .and_then(|s| s.as_f64()),
+        severity: str_field(advisory, "severity").map(|s| s.to_uppercase()),
+        cvss_vector: cvss.and_then(|c| str_field(c, "vector_string")),
+        published: str_field(advisory, "published_at"),
+        cwes: advisory
+            .get("cwes")
+            .and_then(|c| c.as_array())
+            .map(|c| c.iter().filter_map(|x| str_field(x, "cwe_id")).collect())
+            .unwrap_or_default(),
+        references,
+        source: "github".to_string(),
+    })
+}
+
+/// Query the GitHub global advisory database. `affects` takes `package` or `package@version`.
+pub async fn search_github_advisories(
+    cve_id: Option<&str>,
+    ecosystem: Option<&str>,
+    affects: Option<&str>,
+) -> Result<Vec<CveRecord>, String> {
+    let mut params = vec![("per_page", "100".to_string())];
+    if let Some(cve) = cve_id.filter(|c| !c.is_empty()) {
+        params.push(("cve_id", cve.to_uppercase()));
+    }
+    if let Some(ecosystem) = ecosystem.filter(|e| !e.is_empty()) {
+        params.push(("ecosystem", ecosystem.to_string()));
+    }
+    if let Some(affects) = affects.filter(|a| !a.is_empty()) {
+        params.push(("affects", affects.to_string()));
+    }
+
+    let mut request = http_client()?
+        .get(GITHUB_ADVISORIES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .query(&params);
+    if let Some(token) = load_config().github_token.filter(|t| !t.is_empty()) {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("GitHub advisory request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub returned HTTP {}", response.status()));
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
+
+    Ok(body
+        .as_array()
+        .map(|a| a.iter().filter_map(parse_github_advisory).collect())
+        .unwrap_or_default())
+}
+
+/// Local copy of the Exploit-DB index, refreshed weekly
+async fn exploitdb_index() -> Result<String, String> {
+    let path = intel_dir()?.join("files_exploits.csv");
+
+    let fresh = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .is_some_and(|age| age < EXPLOITDB_MAX_AGE);
+
+    if !fresh {
+        let download = async {
+            let response = http_client()?
+                .get(EXPLOITDB_CSV_URL)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to download Exploit-DB index: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("Exploit-DB index returned HTTP {}", response.status()));
+            }
+            response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read Exploit-DB index: {}", e))
+        };
+
+        match download.await {
+            Ok(csv) => {
+                fs::write(&path, &csv)
+                    .map_err(|e| format!("Failed to cache Exploit-DB index: {}", e))?;
+                return Ok(csv);
+            }
+            // Fall back to a stale copy when offline
+            Err(e) if !path.exists() => return Err(e),
+            Err(_) => {}
+        }
+    }
+
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read Exploit-DB index: {}", e))
+}
+
+/// Split one CSV line, honouring double-quoted fields
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Search Exploit-DB by CVE id or free text
+pub async fn search_exploits(query: &str, limit: usize) -> Result<Vec<ExploitRecord>, String> {
+    let csv = exploitdb_index().await?;
+    let mut lines = csv.lines();
+    let header = split_csv_line(lines.next().unwrap_or(""));
+    let column = |name: &str| header.iter().position(|h| h == name);
+
+    let (id_col, desc_col) = match (column("id"), column("description")) {
+        (Some(id), Some(desc)) => (id, desc),
+        _ => return Err("Unexpected Exploit-DB index format".to_string()),
+    };
+    let date_col = column("date_published");
+    let type_col = column("type");
+    let platform_col = column("platform");
+    let codes_col = column("codes");
+
+    let needle = query.to_lowercase();
+    let get = |fields: &[String], col: Option<usize>| {
+        col.and_then(|c| fields.get(c)).cloned().unwrap_or_default()
+    };
+
+    Ok(lines
+        .map(split_csv_line)
+        .filter(|fields| {
+            fields
+                .get(desc_col)
+                .is_some_and(|d| d.to_lowercase().contains(&needle))
+                || get(fields, codes_col).to_lowercase().contains(&needle)
+        })
+        .take(limit)
+        .map(|fields| {
+            let id = get(&fields, Some(id_col));
+            ExploitRecord {
+                url: format!("https://www.exploit-db.com/exploits/{}", id),
+                id,
+                title: get(&fields, Some(desc_col)),
+                platform: get(&fields, platform_col),
+                exploit_type: get(&fields, type_col),
+                published: get(&fields, date_col),
+                codes: get(&fields, codes_col)
+                    .split(';')
+                    .filter(|c| !c.is_empty())
+                    .map(|c| c.to_string())
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+/// Look up advisories for each dependency. Fails only when every lookup
+/// failed, e.g. with the network down; otherwise per-dependency errors are
+/// collected alongside the findings.
+pub async fn link_dependencies(dependencies: Vec<Dependency>) -> Result<DependencyScan, String> {
+    let mut scan = DependencyScan::default();
+    let total = dependencies.len();
+
+    for dependency in dependencies {
+        let affects = match &dependency.version {
+            Some(version) => format!("{}@{}", dependency.name, version),
+            None => dependency.name.clone(),
+        };
+        match search_github_advisories(None, Some(&dependency.ecosystem), Some(&affects)).await {
+            Ok(advisories) if advisories.is_empty() => {}
+            Ok(advisories) => scan.findings.push(DependencyFinding {
+                dependency,
+                advisories,
+            }),
+            Err(error) => scan.errors.push(DependencyLookupError { dependency, error }),
+        }
+    }
+
+    if total > 0 && scan.errors.len() == total {
+        return Err(format!("Advisory lookups failed: {}", scan.errors[0].error));
+    }
+    Ok(scan)
+}
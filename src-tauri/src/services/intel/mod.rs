@@ -0,0 +1,122 @@
+//! Threat intelligence lookups (CVE databases, exploit indexes, reputation services).
+//!
+//! API keys are optional and kept in the OS keychain under the `intel`
+//! integration. Keys found in ~/.ctr/intel/config.json, where earlier
+//! versions stored them, are moved to the keychain when the config is read.
+
+pub mod cve;
+pub mod nmap;
+pub mod reputation;
+
+pub use nmap::import_nmap;
+pub use reputation::{hash_file, lookup_hash, lookup_url};
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::services::secrets;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntelConfig {
+    /// Raises the NVD rate limit from 5 to 50 requests per 30 seconds
+    pub nvd_api_key: Option<String>,
+    /// Raises the GitHub API rate limit for advisory lookups
+    pub github_token: Option<String>,
+    /// Required for VirusTotal hash/URL reputation lookups
+    pub virustotal_api_key: Option<String>,
+}
+
+pub fn intel_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("intel");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create intel directory: {}", e))?;
+    Ok(dir)
+}
+
+impl IntelConfig {
+    /// Each key with its secret name
+    fn keys_mut(&mut self) -> [(&'static str, &mut Option<String>); 3] {
+        [
+            ("nvd_api_key", &mut self.nvd_api_key),
+            ("github_token", &mut self.github_token),
+            ("virustotal_api_key", &mut self.virustotal_api_key),
+        ]
+    }
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(intel_dir()?.join("config.json"))
+}
+
+pub fn load_config() -> IntelConfig {
+    let Ok(path) = config_path() else {
+        return IntelConfig::default();
+    };
+    let mut stored: IntelConfig = load_json(&path);
+    let mut migrated = false;
+    for (name, value) in stored.keys_mut() {
+        let Some(key) = value.clone().filter(|k| !k.is_empty()) else {
+            continue;
+        };
+        match secrets::set("intel", name, &key) {
+            Ok(()) => {
+                *value = None;
+                migrated = true;
+            }
+            Err(e) => log::warn!("Failed to move {} to the keychain: {}", name, e),
+        }
+    }
+    if migrated {
+        if let Err(e) = save_json(&path, &stored) {
+            log::warn!("{}", e);
+        }
+    }
+
+    // Keys that could not be moved are still used from the file
+    let mut config = IntelConfig::default();
+    for ((name, value), (_, fallback)) in config.keys_mut().into_iter().zip(stored.keys_mut()) {
+        *value = secrets::get_or_none("intel", name).or(fallback.take());
+    }
+    config
+}
+
+/// Which keys are configured; the keys themselves never leave the backend
+#[derive(Debug, Clone, Serialize)]
+pub struct IntelKeyStatus {
+    pub nvd_api_key: bool,
+    pub github_token: bool,
+    pub virustotal_api_key: bool,
+}
+
+pub fn key_status() -> IntelKeyStatus {
+    let config = load_config();
+    let present = |key: &Option<String>| key.as_deref().is_some_and(|k| !k.is_empty());
+    IntelKeyStatus {
+        nvd_api_key: present(&config.nvd_api_key),
+        github_token: present(&config.github_token),
+        virustotal_api_key: present(&config.virustotal_api_key),
+    }
+}
+
+/// Store the given keys in the keychain; a key left out keeps its stored
+/// value and an empty one is deleted
+pub fn save_config(config: &IntelConfig) -> Result<(), String> {
+    let path = config_path()?;
+    let mut config = config.clone();
+    let mut stored: IntelConfig = load_json(&path);
+    for ((name, value), (_, fallback)) in config.keys_mut().into_iter().zip(stored.keys_mut()) {
+        if let Some(key) = value.take() {
+            secrets::set("intel", name, &key)?;
+            *fallback = None;
+        }
+    }
+    save_json(&path, &stored)
+}
+
+pub(crate) fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("CyberThreatRange-IDE")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
@@ -0,0 +1,319 @@
+//! nmap XML import
+//!
+//! Parses `nmap -oX` output into hosts, ports, services and script results,
+//! merges them into the workspace recon data in `.ctr/recon.json`, and
+//! suggests exploit payload categories for each open service.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::services::exploit_sandbox::AttackType;
+use crate::utils::fs_utils::{load_json, save_json, workspace_ctr_dir};
+use crate::utils::time::now_millis;
+
+/// Services that front a web application
+const WEB_SERVICES: &[&str] = &["http", "https", "http-alt", "http-proxy", "https-alt", "http-mgmt", "webcache"];
+
+/// Database services that take SQL
+const SQL_SERVICES: &[&str] = &["mysql", "postgresql", "ms-sql-s", "oracle", "oracle-tns", "sybase", "ibm-db2", "drda"];
+
+/// Services that speak serialized Java objects
+const DESERIALIZATION_SERVICES: &[&str] = &["java-rmi", "rmiregistry", "ajp13", "jdwp", "iiop", "t3", "java-object"];
+
+/// Web server products known for Java deserialization endpoints
+const DESERIALIZATION_PRODUCTS: &[&str] = &["weblogic", "jboss", "wildfly", "websphere", "jenkins", "tomcat"];
+
+/// File transfer services where paths are attacker controlled
+const FILE_SERVICES: &[&str] = &["ftp", "tftp", "nfs", "rsync"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScriptResult {
+    pub id: String,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconService {
+    pub name: String,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub extra_info: Option<String>,
+    /// "ssl" when nmap found the service behind TLS
+    pub tunnel: Option<String>,
+    #[serde(default)]
+    pub cpes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconPort {
+    pub protocol: String,
+    pub port: u16,
+    /// open, closed, filtered, ...
+    pub state: String,
+    pub service: Option<ReconService>,
+    #[serde(default)]
+    pub scripts: Vec<ScriptResult>,
+    /// Payload categories worth trying against the service
+    #[serde(default)]
+    pub suggested_payloads: Vec<AttackType>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconHost {
+    pub address: String,
+    pub mac: Option<String>,
+    #[serde(default)]
+    pub hostnames: Vec<String>,
+    /// up or down
+    pub status: String,
+    /// Best OS match
+    pub os: Option<String>,
+    #[serde(default)]
+    pub ports: Vec<ReconPort>,
+    /// Host scripts, which are not tied to a port
+    #[serde(default)]
+    pub scripts: Vec<ScriptResult>,
+    /// Unix timestamp in milliseconds of the last import that reported it
+    #[serde(default)]
+    pub last_seen: u64,
+}
+
+/// Hosts of a workspace, merged across imports
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReconData {
+    #[serde(default)]
+    pub hosts: Vec<ReconHost>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NmapImport {
+    /// The nmap command line the scan ran with
+    pub args: Option<String>,
+    pub hosts: Vec<ReconHost>,
+    /// Hosts not in the recon data before
+    pub new_hosts: usize,
+}
+
+fn store_path(workspace_root: &str) -> Result<PathBuf, String> {
+    Ok(workspace_ctr_dir(workspace_root)?.join("recon.json"))
+}
+
+pub fn load_recon(workspace_root: &str) -> Result<ReconData, String> {
+    Ok(load_json(&store_path(workspace_root)?))
+}
+
+/// Parse an nmap XML file and merge its hosts into the workspace recon data
+pub fn import_nmap(workspace_root: &str, xml_path: &Path) -> Result<NmapImport, String> {
+    let xml = std::fs::read_to_string(xml_path).map_err(|e| format!("Failed to read {}: {}", xml_path.display(), e))?;
+    let (args, mut hosts) = parse_nmap_xml(&xml)?;
+    let now = now_millis();
+    for host in &mut hosts {
+        host.last_seen = now;
+    }
+
+    let mut data = load_recon(workspace_root)?;
+    let mut new_hosts = 0;
+    for host in &hosts {
+        match data.hosts.iter_mut().find(|h| h.address == host.address) {
+            Some(existing) => merge_host(existing, host.clone()),
+            None => {
+                data.hosts.push(host.clone());
+                new_hosts += 1;
+            }
+        }
+    }
+    save_json(&store_path(workspace_root)?, &data)?;
+    log::info!("Imported {} hosts from {}", hosts.len(), xml_path.display());
+    Ok(NmapImport { args, hosts, new_hosts })
+}
+
+/// Ports and scripts the new scan reports replace the stored ones; the rest
+/// are kept, since a later scan may have covered fewer ports
+fn merge_host(existing: &mut ReconHost, host: ReconHost) {
+    for port in host.ports {
+        match existing.ports.iter_mut().find(|p| p.port == port.port && p.protocol == port.protocol) {
+            Some(stored) => *stored = port,
+            None => existing.ports.push(port),
+        }
+    }
+    existing.ports.sort_by(|a, b| (&a.protocol, a.port).cmp(&(&b.protocol, b.port)));
+    for script in host.scripts {
+        existing.scripts.retain(|s| s.id != script.id);
+        existing.scripts.push(script);
+    }
+    for name in host.hostnames {
+        if !existing.hostnames.contains(&name) {
+            existing.hostnames.push(name);
+        }
+    }
+    existing.mac = host.mac.or(existing.mac.take());
+    existing.os = host.os.or(existing.os.take());
+    existing.status = host.status;
+    existing.last_seen = host.last_seen;
+}
+
+fn attr(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+        .filter(|v| !v.is_empty())
+}
+
+/// The scan's command line and its hosts
+pub fn parse_nmap_xml(xml: &str) -> Result<(Option<String>, Vec<ReconHost>), String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut args = None;
+    let mut hosts = Vec::new();
+    let mut host: Option<ReconHost> = None;
+    let mut port: Option<ReconPort> = None;
+    let mut os_accuracy = 0u32;
+    let mut in_cpe = false;
+    let mut seen_nmaprun = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid nmap XML at byte {}: {}", reader.buffer_position(), e))?;
+        match event {
+            Event::Start(element) | Event::Empty(element) => {
+                match element.name().as_ref() {
+                    b"nmaprun" => {
+                        seen_nmaprun = true;
+                        args = attr(&element, "args");
+                    }
+                    b"host" => {
+                        host = Some(ReconHost::default());
+                        os_accuracy = 0;
+                    }
+                    b"status" => {
+                        if let Some(host) = host.as_mut() {
+                            host.status = attr(&element, "state").unwrap_or_default();
+                        }
+                    }
+                    b"address" => {
+                        if let (Some(host), Some(addr)) = (host.as_mut(), attr(&element, "addr")) {
+                            if attr(&element, "addrtype").as_deref() == Some("mac") {
+                                host.mac = Some(addr);
+                            } else if host.address.is_empty() {
+                                host.address = addr;
+                            }
+                        }
+                    }
+                    b"hostname" => {
+                        if let (Some(host), Some(name)) = (host.as_mut(), attr(&element, "name")) {
+                            if !host.hostnames.contains(&name) {
+                                host.hostnames.push(name);
+                            }
+                        }
+                    }
+                    b"osmatch" => {
+                        let accuracy = attr(&element, "accuracy").and_then(|a| a.parse().ok()).unwrap_or(0);
+                        if let Some(host) = host.as_mut().filter(|_| accuracy > os_accuracy) {
+                            host.os = attr(&element, "name");
+                            os_accuracy = accuracy;
+                        }
+                    }
+                    b"port" => {
+                        port = Some(ReconPort {
+                            protocol: attr(&element, "protocol").unwrap_or_else(|| "tcp".into()),
+                            port: attr(&element, "portid").and_then(|p| p.parse().ok()).unwrap_or(0),
+                            state: String::new(),
+                            service: None,
+                            scripts: Vec::new(),
+                            suggested_payloads: Vec::new(),
+                        });
+                    }
+                    b"state" => {
+                        if let Some(port) = port.as_mut() {
+                            port.state = attr(&element, "state").unwrap_or_default();
+                        }
+                    }
+                    b"service" => {
+                        if let Some(port) = port.as_mut() {
+                            port.service = Some(ReconService {
+                                name: attr(&element, "name").unwrap_or_default(),
+                                product: attr(&element, "product"),
+                                version: attr(&element, "version"),
+                                extra_info: attr(&element, "extrainfo"),
+                                tunnel: attr(&element, "tunnel"),
+                                cpes: Vec::new(),
+                            });
+                        }
+                    }
+                    b"cpe" => in_cpe = port.is_some(),
+                    b"script" => {
+                        let script = ScriptResult {
+                            id: attr(&element, "id").unwrap_or_default(),
+                            output: attr(&element, "output").unwrap_or_default(),
+                        };
+                        if let Some(port) = port.as_mut() {
+                            port.scripts.push(script);
+                        } else if let Some(host) = host.as_mut() {
+                            host.scripts.push(script);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(text) if in_cpe => {
+                if let Some(service) = port.as_mut().and_then(|p| p.service.as_mut()) {
+                    service.cpes.push(text.unescape().map_err(|e| e.to_string())?.into_owned());
+                }
+            }
+            Event::End(element) => match element.name().as_ref() {
+                b"cpe" => in_cpe = false,
+                b"port" => {
+                    if let (Some(host), Some(mut port)) = (host.as_mut(), port.take()) {
+                        if port.state == "open" {
+                            port.suggested_payloads = suggest_payloads(port.service.as_ref());
+                        }
+                        host.ports.push(port);
+                    }
+                }
+                b"host" => {
+                    if let Some(host) = host.take().filter(|h| !h.address.is_empty()) {
+                        hosts.push(host);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_nmaprun {
+        return Err("Not an nmap XML report (no <nmaprun> element)".into());
+    }
+    Ok((args, hosts))
+}
+
+/// Payload categories worth trying against a service
+pub fn suggest_payloads(service: Option<&ReconService>) -> Vec<AttackType> {
+    let Some(service) = service else {
+        return Vec::new();
+    };
+    let name = service.name.to_ascii_lowercase();
+    let product = service.product.as_deref().unwrap_or_default().to_ascii_lowercase();
+    let mut suggested = Vec::new();
+    if WEB_SERVICES.contains(&name.as_str()) {
+        suggested.extend([AttackType::SqlInjection, AttackType::XSS, AttackType::CommandInjection, AttackType::PathTraversal]);
+        if DESERIALIZATION_PRODUCTS.iter().any(|p| product.contains(p)) {
+            suggested.push(AttackType::Deserialization);
+        }
+    } else if SQL_SERVICES.contains(&name.as_str()) {
+        suggested.push(AttackType::SqlInjection);
+    } else if DESERIALIZATION_SERVICES.contains(&name.as_str()) {
+        suggested.push(AttackType::Deserialization);
+    } else if FILE_SERVICES.contains(&name.as_str()) {
+        suggested.push(AttackType::PathTraversal);
+    }
+    suggested
+}
@@ -0,0 +1,197 @@
+//! File/URL reputation via VirusTotal, plus local file hashing for triage.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use md5::Md5;
+use serde::Serialize;
+use serde_json::Value;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::{http_client, load_config};
+
+const VT_API_URL: &str = "https://www.virustotal.com/api/v3";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileHashes {
+    pub path: String,
+    pub size: u64,
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VendorVerdict {
+    pub engine: String,
+    /// VirusTotal category: malicious, suspicious, harmless, undetected, ...
+    pub category: String,
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReputationReport {
+    pub resource: String,
+    /// False when VirusTotal has never seen the hash/URL
+    pub found: bool,
+    pub malicious: u64,
+    pub suspicious: u64,
+    pub harmless: u64,
+    pub undetected: u64,
+    /// Engines that returned a verdict
+    pub total: u64,
+    pub reputation: Option<i64>,
+    pub name: Option<String>,
+    pub type_description: Option<String>,
+    /// Only engines that flagged the resource, to keep the payload small
+    pub verdicts: Vec<VendorVerdict>,
+    pub permalink: String,
+}
+
+/// Compute MD5, SHA-1 and SHA-256 of a file in a single pass
+pub fn hash_file(path: &Path) -> Result<FileHashes, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut md5 = Md5::new();
+    let mut sha1 = Sha1::new();
+    let mut sha256 = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+
+    loop {
+        let n = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        md5.update(&buffer[..n]);
+        sha1.update(&buffer[..n]);
+        sha256.update(&buffer[..n]);
+        size += n as u64;
+    }
+
+    Ok(FileHashes {
+        path: path.to_string_lossy().to_string(),
+        size,
+        md5: hex::encode(md5.finalize()),
+        sha1: hex::encode(sha1.finalize()),
+        sha256: hex::encode(sha256.finalize()),
+    })
+}
+
+fn api_key() -> Result<String, String> {
+    load_config()
+        .virustotal_api_key
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| "VirusTotal API key is not configured".to_string())
+}
+
+fn not_found(resource: &str, permalink: String) -> ReputationReport {
+    ReputationReport {
+        resource: resource.to_string(),
+        found: false,
+        malicious: 0,
+        suspicious: 0,
+        harmless: 0,
+        undetected: 0,
+        total: 0,
+        reputation: None,
+        name: None,
+        type_description: None,
+        verdicts: Vec::new(),
+        permalink,
+    }
+}
+
+fn parse_report(resource: &str, body: &Value, permalink: String) -> ReputationReport {
+    let attributes = &body["data"]["attributes"];
+    let stats = &attributes["last_analysis_stats"];
+    let stat = |key: &str| stats[key].as_u64().unwrap_or(0);
+
+    let mut verdicts: Vec<VendorVerdict> = attributes["last_analysis_results"]
+        .as_object()
+        .map(|results| {
+            results
+                .iter()
+                .map(|(engine, r)| VendorVerdict {
+                    engine: engine.clone(),
+                    category: r["category"].as_str().unwrap_or("").to_string(),
+                    result: r["result"].as_str().map(|s| s.to_string()),
+                })
+                .filter(|v| v.category == "malicious" || v.category == "suspicious")
+                .collect()
+        })
+        .unwrap_or_default();
+    verdicts.sort_by(|a, b| a.engine.cmp(&b.engine));
+
+    let malicious = stat("malicious");
+    let suspicious = stat("suspicious");
+    let harmless = stat("harmless");
+    let undetected = stat("undetected");
+
+    ReputationReport {
+        resource: resource.to_string(),
+        found: true,
+        malicious,
+        suspicious,
+        harmless,
+        undetected,
+        total: malicious + suspicious + harmless + undetected,
+        reputation: attributes["reputation"].as_i64(),
+        name: attributes["meaningful_name"]
+            .as_str()
+            .or_else(|| attributes["title"].as_str())
+            .map(|s| s.to_string()),
+        type_description: attributes["type_description"].as_str().map(|s| s.to_string()),
+        verdicts,
+        permalink,
+    }
+}
+
+async fn vt_get(path: &str) -> Result<Option<Value>, String> {
+    let response = http_client()?
+        .get(format!("{}/{}", VT_API_URL, path))
+        .header("x-apikey", api_key()?)
+        .send()
+        .await
+        .map_err(|e| format!("VirusTotal request failed: {}", e))?;
+
+    match response.status().as_u16() {
+        404 => Ok(None),
+        401 | 403 => Err("VirusTotal rejected the API key".to_string()),
+        429 => Err("VirusTotal rate limit exceeded".to_string()),
+        code if !(200..300).contains(&code) => Err(format!("VirusTotal returned HTTP {}", code)),
+        _ => response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| format!("Failed to parse VirusTotal response: {}", e)),
+    }
+}
+
+/// Look up a file hash (MD5, SHA-1 or SHA-256)
+pub async fn lookup_hash(hash: &str) -> Result<ReputationReport, String> {
+    let hash = hash.trim().to_lowercase();
+    if !matches!(hash.len(), 32 | 40 | 64) || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Expected an MD5, SHA-1 or SHA-256 hex digest".to_string());
+    }
+
+    let permalink = format!("https://www.virustotal.com/gui/file/{}", hash);
+    Ok(match vt_get(&format!("files/{}", hash)).await? {
+        Some(body) => parse_report(&hash, &body, permalink),
+        None => not_found(&hash, permalink),
+    })
+}
+
+/// Look up a URL's last analysis
+pub async fn lookup_url(url: &str) -> Result<ReputationReport, String> {
+    let id = URL_SAFE_NO_PAD.encode(url.trim());
+    let permalink = format!("https://www.virustotal.com/gui/url/{}", id);
+
+    Ok(match vt_get(&format!("urls/{}", id)).await? {
+        Some(body) => parse_report(url, &body, permalink),
+        None => not_found(url, permalink),
+    })
+}
@@ -0,0 +1,162 @@
+//! OWASP Juice Shop integration
+//!
+//! Polls a Juice Shop instance's challenge API, remembers when each challenge
+//! was first seen solved (~/.ctr/juice_shop_progress.json, keyed by instance)
+//! and maps challenge categories to the built-in exploit payloads.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::services::exploit_sandbox::{get_exploit_templates, AttackType};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JuiceShopChallenge {
+    pub id: u32,
+    pub key: String,
+    pub name: String,
+    pub description: String,
+    pub difficulty: u32,
+    pub category: String,
+    #[serde(default)]
+    pub solved: bool,
+    #[serde(default)]
+    pub hint: Option<String>,
+    #[serde(default, rename = "hintUrl")]
+    pub hint_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JuiceShopResponse {
+    #[allow(dead_code)]
+    status: String,
+    data: Vec<JuiceShopChallenge>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeProgress {
+    pub challenge: JuiceShopChallenge,
+    /// When the challenge was first observed solved
+    pub solved_at: Option<u64>,
+    /// Indices into the built-in exploit payload list
+    pub payload_indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JuiceShopStatus {
+    pub challenges: Vec<ChallengeProgress>,
+    pub solved: usize,
+    pub total: usize,
+    /// Keys of challenges that flipped to solved since the previous poll
+    pub newly_solved: Vec<String>,
+}
+
+/// instance url -> challenge key -> solved timestamp
+type ProgressStore = HashMap<String, HashMap<String, u64>>;
+
+fn progress_file() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("juice_shop_progress.json"))
+}
+
+/// Fetch all challenges from the Juice Shop challenges endpoint (…/api/Challenges)
+pub async fn fetch_challenges(url: &str) -> Result<Vec<JuiceShopChallenge>, String> {
+    let client = reqwest::Client::new();
+    let res = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let body = res.text().await.map_err(|e| format!("Failed to get text: {}", e))?;
+
+    // Juice Shop API returns { status: "success", data: [...] }
+    let response: JuiceShopResponse = serde_json::from_str(&body).map_err(|e| {
+        format!(
+            "Failed to parse JSON: {} | Body snippet: '{}'",
+            e,
+            &body.chars().take(200).collect::<String>()
+        )
+    })?;
+
+    Ok(response.data)
+}
+
+fn attack_types_for(category: &str) -> Vec<AttackType> {
+    let category = category.to_lowercase();
+    if category.contains("injection") {
+        vec![AttackType::SqlInjection, AttackType::CommandInjection]
+    } else if category.contains("xss") {
+        vec![AttackType::XSS]
+    } else if category.contains("deserialization") {
+        vec![AttackType::Deserialization]
+    } else if category.contains("sensitive data") || category.contains("access control") {
+        vec![AttackType::PathTraversal]
+    } else if category.contains("authentication") {
+        vec![AttackType::SqlInjection]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Built-in payload indices relevant to a challenge category
+pub fn payloads_for_category(category: &str) -> Vec<usize> {
+    let wanted: Vec<String> = attack_types_for(category)
+        .iter()
+        .map(|t| format!("{:?}", t))
+        .collect();
+
+    get_exploit_templates()
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| wanted.contains(&format!("{:?}", p.attack_type)))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Fetch challenge state, update local progress and report what changed
+pub async fn poll(url: &str) -> Result<JuiceShopStatus, String> {
+    let challenges = fetch_challenges(url).await?;
+
+    let path = progress_file()?;
+    let mut store: ProgressStore = load_json(&path);
+    let instance = store.entry(url.to_string()).or_default();
+
+    let now = now_millis();
+    let mut newly_solved = Vec::new();
+    for challenge in challenges.iter().filter(|c| c.solved) {
+        if !instance.contains_key(&challenge.key) {
+            instance.insert(challenge.key.clone(), now);
+            newly_solved.push(challenge.key.clone());
+        }
+    }
+
+    let progress: Vec<ChallengeProgress> = challenges
+        .into_iter()
+        .map(|challenge| ChallengeProgress {
+            solved_at: instance.get(&challenge.key).copied(),
+            payload_indices: payloads_for_category(&challenge.category),
+            challenge,
+        })
+        .collect();
+
+    if !newly_solved.is_empty() {
+        save_json(&path, &store)?;
+    }
+
+    Ok(JuiceShopStatus {
+        solved: progress.iter().filter(|p| p.challenge.solved).count(),
+        total: progress.len(),
+        challenges: progress,
+        newly_solved,
+    })
+}
+
+/// Forget locally tracked progress for an instance (e.g. after a Juice Shop reset)
+pub fn reset_progress(url: &str) -> Result<(), String> {
+    let path = progress_file()?;
+    let mut store: ProgressStore = load_json(&path);
+    store.remove(url);
+    save_json(&path, &store)
+}
@@ -0,0 +1,244 @@
+//! Lab environments
+//!
+//! Starts and stops local Docker targets for practice content. A lab is either
+//! a single container or a compose project; running labs are tracked in memory
+//! and single containers carry a `ctr.lab=<id>` label.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use crate::services::processes::{self, ManagedProcess, ProcessKind};
+use crate::utils::time::now_millis;
+
+const LAB_LABEL: &str = "ctr.lab";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortMapping {
+    pub host: u16,
+    pub container: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LabSetup {
+    /// A single container started from `image`
+    Docker {
+        image: String,
+        #[serde(default)]
+        ports: Vec<PortMapping>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+    },
+    /// A compose file, relative to the content that defines it
+    Compose {
+        file: String,
+        /// Exposed URLs to show once the project is up
+        #[serde(default)]
+        urls: Vec<String>,
+    },
+    /// Already running somewhere else; nothing to start
+    External { url: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LabInstance {
+    pub id: String,
+    pub name: String,
+    pub setup: LabSetup,
+    pub urls: Vec<String>,
+    pub started_at: u64,
+}
+
+lazy_static::lazy_static! {
+    static ref RUNNING_LABS: Arc<Mutex<HashMap<String, LabInstance>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+fn docker(args: &[&str], cwd: Option<&Path>) -> Result<String, String> {
+    let mut command = Command::new("docker");
+    command.args(args);
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run docker: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "docker {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn project_name(id: &str) -> String {
+    format!("ctr-lab-{}", &id[..8])
+}
+
+fn resolve(base_dir: Option<&Path>, file: &str) -> PathBuf {
+    match base_dir {
+        Some(dir) => dir.join(file),
+        None => PathBuf::from(file),
+    }
+}
+
+/// Start a lab. `base_dir` resolves relative compose files.
+pub fn start(name: &str, setup: &LabSetup, base_dir: Option<&Path>) -> Result<LabInstance, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let project = project_name(&id);
+    let label = format!("{}={}", LAB_LABEL, id);
+
+    // Compose paths are stored resolved so the lab can be stopped without the base dir
+    let mut setup = setup.clone();
+    if let LabSetup::Compose { file, .. } = &mut setup {
+        *file = resolve(base_dir, file).to_string_lossy().to_string();
+    }
+
+    let urls = match &setup {
+        LabSetup::Docker { image, ports, env } => {
+            let mut args: Vec<String> = vec![
+                "run".into(),
+                "-d".into(),
+                "--rm".into(),
+                "--name".into(),
+                project.clone(),
+                "--label".into(),
+                label,
+            ];
+            for port in ports {
+                args.push("-p".into());
+                args.push(format!("127.0.0.1:{}:{}", port.host, port.container));
+            }
+            for (key, value) in env {
+                args.push("-e".into());
+                args.push(format!("{}={}", key, value));
+            }
+            args.push(image.clone());
+
+            let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            docker(&args, None)?;
+            ports
+                .iter()
+                .map(|p| format!("http://127.0.0.1:{}", p.host))
+                .collect()
+        }
+        LabSetup::Compose { file, urls } => {
+            let path = Path::new(file);
+            if !path.exists() {
+                return Err(format!("Compose file not found: {}", file));
+            }
+            docker(
+                &["compose", "-f", file, "-p", &project, "up", "-d"],
+                path.parent(),
+            )?;
+            urls.clone()
+        }
+        LabSetup::External { url } => vec![url.clone()],
+    };
+
+    let instance = LabInstance {
+        id: id.clone(),
+        name: name.to_string(),
+        setup,
+        urls,
+        started_at: now_millis(),
+    };
+    RUNNING_LABS.lock().unwrap().insert(id.clone(), instance.clone());
+    if let Some(command) = stop_command(&instance) {
+        let lab_id = id.clone();
+        processes::register(
+            ManagedProcess::new(&id, ProcessKind::Lab, name).with_cleanup(command),
+            Some(Box::new(move || {
+                if let Err(e) = stop(&lab_id) {
+                    log::warn!("Failed to stop lab {}: {}", lab_id, e);
+                }
+            })),
+        );
+    }
+    Ok(instance)
+}
+
+/// The docker command that tears a lab down; None for external labs
+fn stop_command(instance: &LabInstance) -> Option<Vec<String>> {
+    let project = project_name(&instance.id);
+    match &instance.setup {
+        LabSetup::Docker { .. } => Some(vec!["docker".into(), "stop".into(), project]),
+        LabSetup::Compose { file, .. } => Some(vec![
+            "docker".into(),
+            "compose".into(),
+            "-f".into(),
+            file.clone(),
+            "-p".into(),
+            project,
+            "down".into(),
+            "-v".into(),
+        ]),
+        LabSetup::External { .. } => None,
+    }
+}
+
+pub fn stop(id: &str) -> Result<(), String> {
+    let instance = RUNNING_LABS
+        .lock()
+        .unwrap()
+        .remove(id)
+        .ok_or_else(|| format!("Lab {} not found", id))?;
+    processes::unregister(id);
+    let Some(command) = stop_command(&instance) else {
+        return Ok(());
+    };
+    let args: Vec<&str> = command[1..].iter().map(String::as_str).collect();
+    let cwd = match &instance.setup {
+        LabSetup::Compose { file, .. } => Path::new(file).parent(),
+        _ => None,
+    };
+    docker(&args, cwd).map(|_| ())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LabHealth {
+    pub id: String,
+    pub name: String,
+    pub healthy: bool,
+    /// Container states, or why the check failed
+    pub detail: String,
+}
+
+/// Container state of each running lab; external labs are not checked.
+/// A lab is unhealthy when a container stopped or its health check fails.
+pub fn health() -> Vec<LabHealth> {
+    list()
+        .into_iter()
+        .filter_map(|instance| {
+            let filter = match &instance.setup {
+                LabSetup::Docker { .. } => format!("label={}={}", LAB_LABEL, instance.id),
+                LabSetup::Compose { .. } => format!("label=com.docker.compose.project={}", project_name(&instance.id)),
+                LabSetup::External { .. } => return None,
+            };
+            let (healthy, detail) = match docker(&["ps", "-a", "--filter", &filter, "--format", "{{.Names}}\t{{.State}}\t{{.Status}}"], None) {
+                Ok(output) if output.is_empty() => (false, "No containers".to_string()),
+                Ok(output) => {
+                    let healthy = output.lines().all(|line| {
+                        let mut fields = line.split('\t');
+                        let state = fields.nth(1).unwrap_or_default();
+                        state == "running" && !fields.next().unwrap_or_default().contains("(unhealthy)")
+                    });
+                    let detail = output.lines().map(|line| line.replace('\t', " ")).collect::<Vec<_>>().join("; ");
+                    (healthy, detail)
+                }
+                Err(e) => (false, e),
+            };
+            Some(LabHealth { id: instance.id, name: instance.name, healthy, detail })
+        })
+        .collect()
+}
+
+pub fn list() -> Vec<LabInstance> {
+    let mut labs: Vec<LabInstance> = RUNNING_LABS.lock().unwrap().values().cloned().collect();
+    labs.sort_by_key(|l| l.started_at);
+    labs
+}
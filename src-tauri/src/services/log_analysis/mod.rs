@@ -0,0 +1,253 @@
+//! Log analysis for blue-team exercises
+//!
+//! Ingests web server access logs and syslog auth logs, flags requests that
+//! match attack signatures, finds brute force bursts of failed logins (and
+//! successful logins that follow them), and aggregates everything by
+//! source IP. Detections come back in time order as the attack timeline.
+
+pub mod parse;
+pub mod signatures;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::services::security::Severity;
+use parse::{EventKind, LogEvent};
+use signatures::DetectionKind;
+
+/// HTTP status of a rejected login
+const HTTP_UNAUTHORIZED: u16 = 401;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogAnalysisOptions {
+    /// Failed logins from one source that make a brute force burst
+    pub brute_force_attempts: usize,
+    /// Largest gap in seconds between failures of the same burst
+    pub brute_force_window_secs: u64,
+}
+
+impl Default for LogAnalysisOptions {
+    fn default() -> Self {
+        Self { brute_force_attempts: 10, brute_force_window_secs: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Detection {
+    pub kind: DetectionKind,
+    pub title: String,
+    pub severity: Severity,
+    pub source_ip: Option<String>,
+    /// Unix timestamp in milliseconds; for bursts, of the first attempt
+    pub timestamp: Option<u64>,
+    pub file: String,
+    pub line: usize,
+    /// The matched text, or a description of the burst
+    pub evidence: String,
+    /// Response status of a matched request; 2xx suggests it got through
+    pub status: Option<u16>,
+    /// Attempts in a burst; 1 for single requests
+    pub count: usize,
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SourceSummary {
+    pub ip: String,
+    pub requests: usize,
+    pub auth_failures: usize,
+    pub auth_successes: usize,
+    pub detections: usize,
+    pub kinds: Vec<DetectionKind>,
+    pub users: Vec<String>,
+    pub first_seen: Option<u64>,
+    pub last_seen: Option<u64>,
+    /// Severity of the worst detection
+    pub max_severity: Option<Severity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogAnalysis {
+    pub files: Vec<String>,
+    pub lines: usize,
+    /// Lines recognized as requests or login attempts
+    pub events: usize,
+    /// Detections in time order; undated ones last, in file order
+    pub timeline: Vec<Detection>,
+    /// Sources with detections first, then by activity
+    pub sources: Vec<SourceSummary>,
+}
+
+fn detection(kind: DetectionKind, event: &LogEvent, evidence: String, count: usize, users: Vec<String>) -> Detection {
+    Detection {
+        kind,
+        title: kind.title().to_string(),
+        severity: kind.severity(),
+        source_ip: event.source_ip.clone(),
+        timestamp: event.timestamp,
+        file: event.file.clone(),
+        line: event.line,
+        evidence,
+        status: event.status,
+        count,
+        users,
+    }
+}
+
+fn is_auth_failure(event: &LogEvent) -> bool {
+    event.kind == EventKind::AuthFailure || event.status == Some(HTTP_UNAUTHORIZED)
+}
+
+fn read_events(path: &Path, lines: &mut usize) -> Result<Vec<LogEvent>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let name = path.to_string_lossy().to_string();
+    let mut reader = BufReader::new(file);
+    let mut events = Vec::new();
+    let mut buffer = Vec::new();
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        let read = reader
+            .read_until(b'\n', &mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        number += 1;
+        let text = String::from_utf8_lossy(&buffer);
+        events.extend(parse::parse_line(&name, number, text.trim_end()));
+    }
+    *lines += number;
+    Ok(events)
+}
+
+/// Bursts of failed logins per source, and successful logins during or
+/// right after a burst
+fn brute_force(events: &[LogEvent], options: &LogAnalysisOptions) -> Vec<Detection> {
+    let window = options.brute_force_window_secs * 1000;
+    let mut by_source: HashMap<&str, Vec<&LogEvent>> = HashMap::new();
+    for event in events.iter().filter(|e| e.timestamp.is_some()) {
+        if let Some(ip) = event.source_ip.as_deref() {
+            by_source.entry(ip).or_default().push(event);
+        }
+    }
+
+    let mut detections = Vec::new();
+    for source_events in by_source.values_mut() {
+        source_events.sort_by_key(|e| e.timestamp);
+        let failures: Vec<&LogEvent> = source_events.iter().copied().filter(|e| is_auth_failure(e)).collect();
+        let mut start = 0;
+        while start < failures.len() {
+            let mut end = start;
+            while end + 1 < failures.len() && failures[end + 1].timestamp.unwrap_or(0) - failures[end].timestamp.unwrap_or(0) <= window {
+                end += 1;
+            }
+            let burst = &failures[start..=end];
+            start = end + 1;
+            if burst.len() < options.brute_force_attempts.max(1) {
+                continue;
+            }
+            let (first, last) = (burst[0], burst[burst.len() - 1]);
+            let users: Vec<String> = burst.iter().filter_map(|e| e.user.clone()).collect::<BTreeSet<_>>().into_iter().collect();
+            let secs = (last.timestamp.unwrap_or(0) - first.timestamp.unwrap_or(0)) / 1000;
+            let evidence = format!("{} failed logins in {}s", burst.len(), secs);
+            detections.push(detection(DetectionKind::BruteForce, first, evidence, burst.len(), users));
+
+            let until = last.timestamp.unwrap_or(0) + window;
+            let compromised = source_events.iter().find(|e| {
+                e.kind == EventKind::AuthSuccess && e.timestamp >= first.timestamp && e.timestamp.is_some_and(|t| t <= until)
+            });
+            if let Some(login) = compromised {
+                let user = login.user.clone().unwrap_or_default();
+                let evidence = format!("Logged in as {} after {} failed attempts", user, burst.len());
+                detections.push(detection(DetectionKind::CompromisedLogin, login, evidence, 1, vec![user]));
+            }
+        }
+    }
+    detections
+}
+
+fn summarize_sources(events: &[LogEvent], timeline: &[Detection]) -> Vec<SourceSummary> {
+    let mut sources: HashMap<String, SourceSummary> = HashMap::new();
+    for event in events {
+        let Some(ip) = event.source_ip.as_deref() else {
+            continue;
+        };
+        let source = sources.entry(ip.to_string()).or_insert_with(|| SourceSummary { ip: ip.to_string(), ..Default::default() });
+        match event.kind {
+            EventKind::Request => source.requests += 1,
+            EventKind::AuthFailure => source.auth_failures += 1,
+            EventKind::AuthSuccess => source.auth_successes += 1,
+        }
+        if let Some(user) = event.user.as_ref().filter(|u| !source.users.contains(u)) {
+            source.users.push(user.clone());
+        }
+        if let Some(at) = event.timestamp {
+            source.first_seen = Some(source.first_seen.map_or(at, |t| t.min(at)));
+            source.last_seen = Some(source.last_seen.map_or(at, |t| t.max(at)));
+        }
+    }
+    for found in timeline {
+        let Some(ip) = found.source_ip.as_deref() else {
+            continue;
+        };
+        let source = sources.entry(ip.to_string()).or_insert_with(|| SourceSummary { ip: ip.to_string(), ..Default::default() });
+        source.detections += 1;
+        if !source.kinds.contains(&found.kind) {
+            source.kinds.push(found.kind);
+        }
+        if source.max_severity.as_ref().map_or(true, |max| found.severity > *max) {
+            source.max_severity = Some(found.severity.clone());
+        }
+    }
+
+    let mut sources: Vec<SourceSummary> = sources.into_values().collect();
+    sources.sort_by(|a, b| {
+        (b.max_severity.clone(), b.detections, b.requests + b.auth_failures)
+            .cmp(&(a.max_severity.clone(), a.detections, a.requests + a.auth_failures))
+            .then_with(|| a.ip.cmp(&b.ip))
+    });
+    sources
+}
+
+/// Analyze log files together, so a source's activity across web and auth
+/// logs lands in one summary
+pub fn analyze(paths: &[String], options: &LogAnalysisOptions) -> Result<LogAnalysis, String> {
+    if paths.is_empty() {
+        return Err("No log files given".into());
+    }
+    let mut lines = 0;
+    let mut events = Vec::new();
+    for path in paths {
+        events.extend(read_events(Path::new(path), &mut lines)?);
+    }
+
+    let mut timeline: Vec<Detection> = Vec::new();
+    // A scanner is reported once per source and tool, counting its requests
+    let mut scanners: HashMap<(Option<String>, String), usize> = HashMap::new();
+    for event in events.iter().filter(|e| e.kind == EventKind::Request) {
+        let hits = signatures::match_request(event.path.as_deref().unwrap_or_default(), event.user_agent.as_deref());
+        for (kind, evidence) in hits {
+            if kind == DetectionKind::Scanner {
+                if let Some(&index) = scanners.get(&(event.source_ip.clone(), evidence.clone())) {
+                    timeline[index].count += 1;
+                    continue;
+                }
+                scanners.insert((event.source_ip.clone(), evidence.clone()), timeline.len());
+            }
+            timeline.push(detection(kind, event, evidence, 1, event.user.clone().into_iter().collect()));
+        }
+    }
+    timeline.extend(brute_force(&events, options));
+    timeline.sort_by(|a, b| {
+        (a.timestamp.is_none(), a.timestamp, &a.file, a.line).cmp(&(b.timestamp.is_none(), b.timestamp, &b.file, b.line))
+    });
+
+    let sources = summarize_sources(&events, &timeline);
+    log::info!("Analyzed {} log lines: {} detections from {} sources", lines, timeline.len(), sources.len());
+    Ok(LogAnalysis { files: paths.to_vec(), lines, events: events.len(), timeline, sources })
+}
@@ -0,0 +1,157 @@
+//! Log line parsing: web server access logs (common and combined formats)
+//! and syslog auth logs (sshd and PAM)
+
+use chrono::{Datelike, NaiveDate, Utc};
+use regex::Regex;
+use serde::Serialize;
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+lazy_static::lazy_static! {
+    /// host ident user [time] "request" status size ["referer" "agent"]
+    static ref ACCESS_LINE: Regex = Regex::new(
+        r#"^(\S+) \S+ (\S+) \[([^\]]+)\] "([^"]*)" (\d{3}) (\S+)(?: "([^"]*)" "([^"]*)")?"#
+    ).unwrap();
+    /// 10/Oct/2000:13:55:36 -0700
+    static ref ACCESS_TIME: Regex = Regex::new(
+        r"^(\d{1,2})/(\w{3})/(\d{4}):(\d{2}):(\d{2}):(\d{2})(?: ([+-])(\d{2})(\d{2}))?"
+    ).unwrap();
+    /// "Oct 11 22:14:15 host program[pid]: message", or with an RFC 3339 time
+    static ref SYSLOG_LINE: Regex = Regex::new(
+        r"^(\w{3} [ \d]\d \d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?) (\S+) ([^:\[]+)(?:\[\d+\])?: (.*)$"
+    ).unwrap();
+    static ref RFC3339_TIME: Regex = Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})(?:\.\d+)?(Z|([+-])(\d{2}):?(\d{2}))?$"
+    ).unwrap();
+    static ref SSH_FAILED: Regex = Regex::new(
+        r"Failed (?:password|publickey|keyboard-interactive\S*) for (?:invalid user )?(\S+) from (\S+)"
+    ).unwrap();
+    static ref SSH_ACCEPTED: Regex = Regex::new(r"Accepted \S+ for (\S+) from (\S+)").unwrap();
+    /// Failures of sshd are skipped here, since sshd logs its own line for them
+    static ref PAM_FAILURE: Regex = Regex::new(r"authentication failure;.*?rhost=(\S+)(?:\s+user=(\S+))?").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Request,
+    AuthFailure,
+    AuthSuccess,
+}
+
+/// One parsed log line
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEvent {
+    pub file: String,
+    pub line: usize,
+    /// Unix timestamp in milliseconds
+    pub timestamp: Option<u64>,
+    pub source_ip: Option<String>,
+    pub kind: EventKind,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub status: Option<u16>,
+    pub user: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+fn millis(year: i64, month: i64, day: i64, hour: i64, minute: i64, second: i64, offset_minutes: i64) -> Option<u64> {
+    let date = NaiveDate::from_ymd_opt(year.try_into().ok()?, month.try_into().ok()?, day.try_into().ok()?)?;
+    let at = date.and_hms_opt(hour.try_into().ok()?, minute.try_into().ok()?, second.try_into().ok()?)?;
+    let utc = at.and_utc().timestamp_millis() - offset_minutes * 60_000;
+    u64::try_from(utc).ok()
+}
+
+fn month_number(name: &str) -> Option<i64> {
+    MONTHS.iter().position(|m| m.eq_ignore_ascii_case(name)).map(|i| i as i64 + 1)
+}
+
+fn access_time(value: &str) -> Option<u64> {
+    let caps = ACCESS_TIME.captures(value)?;
+    let num = |i: usize| caps.get(i).and_then(|m| m.as_str().parse::<i64>().ok());
+    let offset = match caps.get(7) {
+        Some(sign) => {
+            let minutes = num(8)? * 60 + num(9)?;
+            if sign.as_str() == "-" { -minutes } else { minutes }
+        }
+        None => 0,
+    };
+    millis(num(3)?, month_number(&caps[2])?, num(1)?, num(4)?, num(5)?, num(6)?, offset)
+}
+
+/// Classic syslog times carry no year or zone; they are read as UTC in the
+/// current year, or the previous one when that would put them in the future
+fn syslog_time(value: &str) -> Option<u64> {
+    if let Some(caps) = RFC3339_TIME.captures(value) {
+        let num = |i: usize| caps.get(i).and_then(|m| m.as_str().parse::<i64>().ok());
+        let offset = match caps.get(8) {
+            Some(sign) => {
+                let minutes = num(9)? * 60 + num(10)?;
+                if sign.as_str() == "-" { -minutes } else { minutes }
+            }
+            None => 0,
+        };
+        return millis(num(1)?, num(2)?, num(3)?, num(4)?, num(5)?, num(6)?, offset);
+    }
+    let mut parts = value.split_whitespace();
+    let month = month_number(parts.next()?)?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    let mut clock = parts.next()?.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    let now = Utc::now();
+    let year = i64::from(now.year());
+    let at = millis(year, month, day, hour, minute, second, 0)?;
+    if at as i64 > now.timestamp_millis() + MILLIS_PER_DAY {
+        millis(year - 1, month, day, hour, minute, second, 0)
+    } else {
+        Some(at)
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty() && value != "-").then(|| value.to_string())
+}
+
+pub fn parse_line(file: &str, line: usize, text: &str) -> Option<LogEvent> {
+    if let Some(caps) = ACCESS_LINE.captures(text) {
+        let mut request = caps[4].split_whitespace();
+        return Some(LogEvent {
+            file: file.to_string(),
+            line,
+            timestamp: access_time(&caps[3]),
+            source_ip: Some(caps[1].to_string()),
+            kind: EventKind::Request,
+            method: request.next().map(String::from),
+            path: request.next().map(String::from),
+            status: caps[5].parse().ok(),
+            user: non_empty(&caps[2]),
+            user_agent: caps.get(8).and_then(|m| non_empty(m.as_str())),
+        });
+    }
+
+    let caps = SYSLOG_LINE.captures(text)?;
+    let message = &caps[4];
+    let (kind, user, source_ip) = if let Some(m) = SSH_FAILED.captures(message) {
+        (EventKind::AuthFailure, Some(m[1].to_string()), Some(m[2].to_string()))
+    } else if let Some(m) = SSH_ACCEPTED.captures(message) {
+        (EventKind::AuthSuccess, Some(m[1].to_string()), Some(m[2].to_string()))
+    } else if let Some(m) = PAM_FAILURE.captures(message).filter(|_| !message.contains("(sshd:auth)")) {
+        (EventKind::AuthFailure, m.get(2).map(|u| u.as_str().to_string()), Some(m[1].to_string()))
+    } else {
+        return None;
+    };
+    Some(LogEvent {
+        file: file.to_string(),
+        line,
+        timestamp: syslog_time(&caps[1]),
+        source_ip,
+        kind,
+        method: None,
+        path: None,
+        status: None,
+        user,
+        user_agent: None,
+    })
+}
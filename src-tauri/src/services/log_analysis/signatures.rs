@@ -0,0 +1,100 @@
+//! Attack signatures matched against requested URLs and user agents
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::services::security::Severity;
+
+/// Tools that announce themselves in the User-Agent header
+const SCANNER_AGENTS: &[&str] = &[
+    "sqlmap", "nikto", "nmap", "masscan", "gobuster", "dirbuster", "dirb", "wfuzz", "ffuf", "feroxbuster", "nuclei",
+    "acunetix", "netsparker", "wpscan", "zgrab", "hydra", "whatweb", "openvas", "arachni", "commix",
+];
+
+/// Percent-decoding rounds, so double-encoded payloads are seen too
+const DECODE_ROUNDS: usize = 2;
+
+lazy_static::lazy_static! {
+    static ref SQL_INJECTION: Regex = Regex::new(
+        r"(?i)(\bunion\b.{0,40}\bselect\b|'\s*(or|and)\s+['\d]|\bor\s+1\s*=\s*1\b|\b(sleep|benchmark|pg_sleep)\s*\(|waitfor\s+delay|information_schema|;\s*(drop|insert|update|delete)\s|'\s*--|\bextractvalue\s*\(|\bload_file\s*\()"
+    ).unwrap();
+    static ref PATH_TRAVERSAL: Regex = Regex::new(
+        r"(?i)(\.\./|\.\.\\|/etc/(passwd|shadow|hosts)|/proc/self/|c:\\windows|win\.ini|boot\.ini|\x00)"
+    ).unwrap();
+    static ref XSS: Regex = Regex::new(
+        r"(?i)(<script|javascript:|\bon(error|load|mouseover|focus)\s*=|<svg|<iframe|<img[^>]+src|document\.cookie|alert\s*\()"
+    ).unwrap();
+    static ref COMMAND_INJECTION: Regex = Regex::new(
+        r"(?i)(;|\||&&|\$\(|`)\s*(cat|id|whoami|uname|wget|curl|nc|ncat|bash|sh|ping|powershell|cmd)\b"
+    ).unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionKind {
+    SqlInjection,
+    PathTraversal,
+    Xss,
+    CommandInjection,
+    Scanner,
+    BruteForce,
+    /// A successful login from a source that was brute forcing
+    CompromisedLogin,
+}
+
+impl DetectionKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::SqlInjection => "SQL injection attempt",
+            Self::PathTraversal => "Path traversal attempt",
+            Self::Xss => "Cross-site scripting attempt",
+            Self::CommandInjection => "Command injection attempt",
+            Self::Scanner => "Automated scanner",
+            Self::BruteForce => "Brute force",
+            Self::CompromisedLogin => "Login after brute force",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::CompromisedLogin => Severity::Critical,
+            Self::SqlInjection | Self::CommandInjection | Self::PathTraversal | Self::BruteForce => Severity::High,
+            Self::Xss => Severity::Medium,
+            Self::Scanner => Severity::Low,
+        }
+    }
+}
+
+fn decode(path: &str) -> String {
+    let mut decoded = path.replace('+', " ");
+    for _ in 0..DECODE_ROUNDS {
+        match urlencoding::decode_binary(decoded.as_bytes()) {
+            bytes if bytes.as_ref() != decoded.as_bytes() => decoded = String::from_utf8_lossy(&bytes).into_owned(),
+            _ => break,
+        }
+    }
+    decoded
+}
+
+/// Signatures a request matches, each with the text that matched
+pub fn match_request(path: &str, user_agent: Option<&str>) -> Vec<(DetectionKind, String)> {
+    let decoded = decode(path);
+    let mut hits = Vec::new();
+    for (kind, pattern) in [
+        (DetectionKind::SqlInjection, &*SQL_INJECTION),
+        (DetectionKind::PathTraversal, &*PATH_TRAVERSAL),
+        (DetectionKind::Xss, &*XSS),
+        (DetectionKind::CommandInjection, &*COMMAND_INJECTION),
+    ] {
+        if let Some(m) = pattern.find(&decoded) {
+            hits.push((kind, m.as_str().to_string()));
+        }
+    }
+    if let Some(agent) = user_agent {
+        let lower = agent.to_ascii_lowercase();
+        if let Some(tool) = SCANNER_AGENTS.iter().find(|tool| lower.contains(*tool)) {
+            hits.push((DetectionKind::Scanner, tool.to_string()));
+        }
+    }
+    hits
+}
@@ -0,0 +1,426 @@
+//! Logging
+//!
+//! A `tracing` subscriber that every subsystem logs through: events from
+//! `tracing` and from the `log` macros used across the backend are written
+//! as JSON lines to ~/.ctr/logs/ctr.log, which is rotated by size, and kept
+//! in memory for the in-app output panel. The level is read from the
+//! `log.level` setting at startup and can be changed at runtime, globally or
+//! for one target such as `app_lib::services::proxy`. Diagnostics bundles
+//! package the logs with version information and the user settings.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Metadata, Subscriber};
+use tracing_log::{AsLog, NormalizeEvent};
+
+use crate::services::settings;
+use crate::utils::fs_utils::ctr_dir;
+use crate::utils::time::now_millis;
+
+const LOG_FILE: &str = "ctr.log";
+/// The log file is rotated once it grows past this size
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one, ctr.1.log being the newest
+const ROTATED_FILES: usize = 4;
+/// Entries kept in memory for the output panel
+const RECENT_CAPACITY: usize = 2000;
+
+/// Setting keys whose values are left out of diagnostics bundles
+const REDACTED_KEY_PARTS: &[&str] = &["key", "token", "secret", "password", "passphrase"];
+
+lazy_static::lazy_static! {
+    static ref LEVELS: RwLock<LevelConfig> = RwLock::new(LevelConfig::default());
+    static ref RECENT: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY));
+    static ref WRITER: Mutex<Option<RotatingFile>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Sequence number, so the panel can ask for entries after the last it has
+    pub id: u64,
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub level: String,
+    /// Module path of the subsystem that logged it
+    pub target: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLevels {
+    pub level: String,
+    /// Per-target levels that override the global one
+    pub targets: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    /// Entries with an id greater than this
+    pub after: Option<u64>,
+    /// Drop entries less severe than this level
+    pub min_level: Option<String>,
+    /// Only entries whose target contains this
+    pub target: Option<String>,
+    pub limit: Option<usize>,
+}
+
+struct LevelConfig {
+    level: LevelFilter,
+    targets: BTreeMap<String, LevelFilter>,
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        Self { level: LevelFilter::INFO, targets: BTreeMap::new() }
+    }
+}
+
+impl LevelConfig {
+    /// The level of the longest target prefix with its own level
+    fn for_target(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+
+    /// The most verbose level in effect anywhere
+    fn max(&self) -> LevelFilter {
+        self.targets.values().copied().fold(self.level, LevelFilter::max)
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "off" => Ok(LevelFilter::OFF),
+        "error" => Ok(LevelFilter::ERROR),
+        "warn" | "warning" => Ok(LevelFilter::WARN),
+        "info" => Ok(LevelFilter::INFO),
+        "debug" => Ok(LevelFilter::DEBUG),
+        "trace" => Ok(LevelFilter::TRACE),
+        other => Err(format!("Unknown log level: {}", other)),
+    }
+}
+
+fn level_name(level: LevelFilter) -> String {
+    level.to_string().to_ascii_lowercase()
+}
+
+fn logs_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("logs");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    Ok(dir)
+}
+
+/// The log file, renamed to ctr.1.log, ctr.2.log, ... as it fills up
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(dir.join(LOG_FILE))?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir, file, size })
+    }
+
+    fn rotated(dir: &Path, n: usize) -> PathBuf {
+        dir.join(format!("ctr.{}.log", n))
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let _ = fs::remove_file(Self::rotated(&self.dir, ROTATED_FILES));
+        for n in (1..ROTATED_FILES).rev() {
+            let from = Self::rotated(&self.dir, n);
+            if from.exists() {
+                fs::rename(&from, Self::rotated(&self.dir, n + 1))?;
+            }
+        }
+        fs::rename(self.dir.join(LOG_FILE), Self::rotated(&self.dir, 1))?;
+        *self = Self::open(self.dir.clone())?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size + line.len() as u64 + 1 > MAX_LOG_BYTES && self.size > 0 {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+/// Collects an event's message and fields
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: BTreeMap<String, Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        // The `log` bridge adds the original target and location as fields
+        if !field.name().starts_with("log.") {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, Value::from(value));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.insert(field, Value::from(format!("{:?}", value)));
+        }
+    }
+}
+
+/// Records events; spans are given ids but otherwise not tracked
+struct CtrSubscriber {
+    next_span: AtomicU64,
+    next_entry: AtomicU64,
+}
+
+impl Subscriber for CtrSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // Levels change at runtime, so every event asks `enabled`
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        LEVELS.read().unwrap().for_target(metadata.target()) >= *metadata.level()
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LEVELS.read().unwrap().max())
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let entry = LogEntry {
+            id: self.next_entry.fetch_add(1, Ordering::Relaxed),
+            timestamp: now_millis(),
+            level: metadata.level().as_str().to_ascii_lowercase(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+
+        if cfg!(debug_assertions) {
+            eprintln!("[{}] {} {}", entry.level.to_ascii_uppercase(), entry.target, entry.message);
+        }
+        if let Some(writer) = WRITER.lock().unwrap().as_mut() {
+            if let Ok(line) = serde_json::to_string(&entry) {
+                // Nowhere left to report a failing log file
+                let _ = writer.write_line(&line);
+            }
+        }
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// Install the subscriber and route the `log` macros through it. Called
+/// once at startup; the log file is optional, entries are still kept in
+/// memory when it cannot be opened.
+pub fn init() {
+    let level = settings::get_as("log.level", None, "info".to_string());
+    LEVELS.write().unwrap().level = parse_level(&level).unwrap_or(LevelFilter::INFO);
+
+    match logs_dir().and_then(|dir| RotatingFile::open(dir).map_err(|e| format!("Failed to open log file: {}", e))) {
+        Ok(file) => *WRITER.lock().unwrap() = Some(file),
+        Err(e) => eprintln!("{}", e),
+    }
+
+    let subscriber = CtrSubscriber { next_span: AtomicU64::new(1), next_entry: AtomicU64::new(1) };
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        return;
+    }
+    if tracing_log::LogTracer::init().is_ok() {
+        log::set_max_level(LEVELS.read().unwrap().max().as_log());
+    }
+}
+
+pub fn levels() -> LogLevels {
+    let config = LEVELS.read().unwrap();
+    LogLevels {
+        level: level_name(config.level),
+        targets: config.targets.iter().map(|(t, l)| (t.clone(), level_name(*l))).collect(),
+    }
+}
+
+/// Set the global level, or the level of one target and the modules under
+/// it; `reset` as the level of a target drops its override
+pub fn set_level(level: &str, target: Option<&str>) -> Result<LogLevels, String> {
+    {
+        let mut config = LEVELS.write().unwrap();
+        match target.map(str::trim).filter(|t| !t.is_empty()) {
+            Some(target) if level.trim().eq_ignore_ascii_case("reset") => {
+                config.targets.remove(target);
+            }
+            Some(target) => {
+                config.targets.insert(target.to_string(), parse_level(level)?);
+            }
+            None => config.level = parse_level(level)?,
+        }
+        log::set_max_level(config.max().as_log());
+    }
+    tracing::callsite::rebuild_interest_cache();
+    Ok(levels())
+}
+
+/// Entries kept in memory matching a query, oldest first; `limit` keeps
+/// the newest
+pub fn recent(query: &LogQuery) -> Result<Vec<LogEntry>, String> {
+    let min_level = query.min_level.as_deref().map(parse_level).transpose()?;
+    let recent = RECENT.lock().unwrap();
+    let mut entries: Vec<LogEntry> = recent
+        .iter()
+        .filter(|e| query.after.map_or(true, |after| e.id > after))
+        .filter(|e| min_level.map_or(true, |min| parse_level(&e.level).is_ok_and(|level| level <= min)))
+        .filter(|e| query.target.as_deref().map_or(true, |target| e.target.contains(target)))
+        .cloned()
+        .collect();
+    if let Some(limit) = query.limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+    Ok(entries)
+}
+
+fn redact(settings: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+    settings
+        .into_iter()
+        .map(|(key, value)| {
+            let lower = key.to_ascii_lowercase();
+            if REDACTED_KEY_PARTS.iter().any(|part| lower.contains(part)) {
+                (key, Value::from("<redacted>"))
+            } else {
+                (key, value)
+            }
+        })
+        .collect()
+}
+
+/// Zip the log files, version information and the user settings, with
+/// secrets redacted, into `dest_path` or ~/.ctr/diagnostics
+pub fn export_diagnostics(dest_path: Option<&str>) -> Result<PathBuf, String> {
+    use zip::write::SimpleFileOptions;
+
+    let dest = match dest_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let dir = ctr_dir()?.join("diagnostics");
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create diagnostics directory: {}", e))?;
+            dir.join(format!("ctr-diagnostics-{}.zip", now_millis()))
+        }
+    };
+    let file = File::create(&dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    let zip_err = |e: zip::result::ZipError| format!("Failed to write diagnostics: {}", e);
+
+    let versions = serde_json::json!({
+        "app": env!("CARGO_PKG_VERSION"),
+        "tauri": tauri::VERSION,
+        "libgit2": format!("{:?}", git2::Version::get().libgit2_version()),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "log_levels": levels(),
+        "created_at": now_millis(),
+    });
+    zip.start_file("versions.json", options).map_err(zip_err)?;
+    zip.write_all(serde_json::to_string_pretty(&versions).unwrap_or_default().as_bytes())
+        .map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+
+    let user_settings = redact(settings::effective(None)?);
+    zip.start_file("settings.json", options).map_err(zip_err)?;
+    zip.write_all(serde_json::to_string_pretty(&user_settings).unwrap_or_default().as_bytes())
+        .map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+
+    // Flush so the current file is complete in the bundle
+    if let Some(writer) = WRITER.lock().unwrap().as_mut() {
+        let _ = writer.file.flush();
+    }
+    let dir = logs_dir()?;
+    let mut logs: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read log directory: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    logs.sort();
+    for path in logs {
+        let Ok(contents) = fs::read(&path) else {
+            continue;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        zip.start_file(format!("logs/{}", name), options).map_err(zip_err)?;
+        zip.write_all(&contents).map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+    }
+
+    zip.finish().map_err(zip_err)?;
+    Ok(dest)
+}
+
@@ -4,3 +4,38 @@ pub mod project;
 pub mod terminal;
 pub mod security;
 pub mod exploit_sandbox;
+pub mod audit;
+pub mod proxy;
+pub mod recon;
+pub mod intel;
+pub mod crypto_tools;
+pub mod listeners;
+pub mod payload_library;
+pub mod payload_verifier;
+pub mod juice_shop;
+pub mod scaffold;
+pub mod labs;
+pub mod ctf;
+pub mod progress;
+pub mod reporting;
+pub mod evidence;
+pub mod settings;
+pub mod extensions;
+pub mod prover_cache;
+pub mod triage;
+pub mod findings;
+pub mod langdetect;
+pub mod logging;
+pub mod notifications;
+pub mod secrets;
+pub mod processes;
+pub mod policy;
+pub mod pcap;
+pub mod log_analysis;
+pub mod binary;
+pub mod spray;
+pub mod phishing;
+pub mod scheduler;
+pub mod openapi;
+pub mod postman;
+pub mod http_client;
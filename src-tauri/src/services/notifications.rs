@@ -0,0 +1,76 @@
+//! OS notifications
+//!
+//! Tells the user when a long-running job finishes while they are looking
+//! elsewhere: a workspace scan, a verified exploit, a lab that came up.
+//! Notifications go through the Tauri notification plugin and respect the
+//! `notifications.*` settings, which turn them off entirely, per kind, or
+//! while the IDE window has focus.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::services::settings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    ScanComplete,
+    ExploitVerified,
+    LabReady,
+    /// Anything else the frontend wants to announce
+    JobFinished,
+}
+
+impl NotificationKind {
+    fn key(&self) -> &'static str {
+        match self {
+            NotificationKind::ScanComplete => "scan_complete",
+            NotificationKind::ExploitVerified => "exploit_verified",
+            NotificationKind::LabReady => "lab_ready",
+            NotificationKind::JobFinished => "job_finished",
+        }
+    }
+}
+
+fn window_focused(app_handle: &AppHandle) -> bool {
+    app_handle
+        .webview_windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false))
+}
+
+/// Whether a notification of this kind should be shown now
+fn wanted(app_handle: &AppHandle, kind: NotificationKind) -> bool {
+    if !settings::get_as("notifications.enabled", None, true) {
+        return false;
+    }
+    let disabled: Vec<String> = settings::get_as("notifications.disabledKinds", None, Vec::new());
+    if disabled.iter().any(|k| k == kind.key()) {
+        return false;
+    }
+    !(settings::get_as("notifications.onlyWhenUnfocused", None, true) && window_focused(app_handle))
+}
+
+/// Show a notification unless the preferences rule it out; returns whether
+/// it was shown
+pub fn notify(app_handle: &AppHandle, kind: NotificationKind, title: &str, body: &str) -> Result<bool, String> {
+    if !wanted(app_handle, kind) {
+        return Ok(false);
+    }
+    app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+    Ok(true)
+}
+
+/// `notify` for background jobs, where a failing notification is only logged
+pub fn notify_or_warn(app_handle: &AppHandle, kind: NotificationKind, title: &str, body: &str) {
+    if let Err(e) = notify(app_handle, kind, title, body) {
+        log::warn!("{}", e);
+    }
+}
@@ -0,0 +1,652 @@
+//! OpenAPI / Swagger import
+//!
+//! Reads OpenAPI 3.x and Swagger 2.0 documents (JSON or YAML) into a flat
+//! list of endpoints with their parameters and auth requirements, builds a
+//! ready-to-send HTTP client request for each, and flags endpoints worth a
+//! closer look. Only local `$ref`s are followed.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::services::findings::{self, Finding, FindingSource};
+use crate::services::http_client::{self, HttpAuth, HttpCollection, HttpHeader, HttpRequestSpec, SavedRequest};
+use crate::services::security::Severity;
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+const STATE_CHANGING: [&str; 4] = ["POST", "PUT", "PATCH", "DELETE"];
+/// Nested `$ref`s and schemas deeper than this are not expanded
+const MAX_DEPTH: usize = 8;
+const MULTIPART_BOUNDARY: &str = "----ctr-form-boundary";
+/// Stands in for credentials the user still has to fill in
+pub const CREDENTIAL_PLACEHOLDER: &str = "REPLACE_ME";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthScheme {
+    pub name: String,
+    /// `apiKey`, `http`, `basic`, `oauth2` or `openIdConnect`
+    pub kind: String,
+    /// HTTP auth scheme, e.g. `bearer`
+    pub scheme: Option<String>,
+    /// Where an API key goes: `header`, `query` or `cookie`
+    pub location: Option<String>,
+    /// Header, query or cookie name of an API key
+    pub parameter: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiParameter {
+    pub name: String,
+    /// `path`, `query`, `header`, `cookie` or `form`
+    pub location: String,
+    pub required: bool,
+    pub schema_type: Option<String>,
+    /// Example, default or a value made up from the type
+    pub example: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Endpoint {
+    pub method: String,
+    pub path: String,
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+    pub parameters: Vec<ApiParameter>,
+    pub content_type: Option<String>,
+    /// Sample body for `content_type`
+    pub body: Option<String>,
+    /// Alternative sets of auth scheme names, any one of which is enough;
+    /// empty when the endpoint needs no auth, and an empty set makes auth
+    /// optional
+    pub security: Vec<Vec<String>>,
+    pub deprecated: bool,
+    pub file_upload: bool,
+}
+
+impl Endpoint {
+    pub fn requires_auth(&self) -> bool {
+        !self.security.is_empty() && self.security.iter().all(|set| !set.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiSpec {
+    pub title: String,
+    pub version: String,
+    /// `openapi` or `swagger` version of the document
+    pub spec_version: String,
+    /// Base URLs, first one preferred; may be relative
+    pub servers: Vec<String>,
+    pub auth_schemes: Vec<AuthScheme>,
+    pub endpoints: Vec<Endpoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskKind {
+    /// POST/PUT/PATCH/DELETE that can be called without credentials
+    UnauthenticatedStateChange,
+    FileUpload,
+}
+
+impl RiskKind {
+    pub fn rule(&self) -> &'static str {
+        match self {
+            RiskKind::UnauthenticatedStateChange => "openapi:unauthenticated-state-change",
+            RiskKind::FileUpload => "openapi:file-upload",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiRisk {
+    pub kind: RiskKind,
+    pub method: String,
+    pub path: String,
+    pub title: String,
+    pub message: String,
+    pub severity: Severity,
+    pub cwe: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenApiImport {
+    pub collection: String,
+    pub spec: ApiSpec,
+    pub risks: Vec<ApiRisk>,
+}
+
+/// Follow local `$ref`s; external ones are left unresolved
+fn resolve<'a>(root: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..MAX_DEPTH {
+        let Some(pointer) = value.get("$ref").and_then(Value::as_str).and_then(|r| r.strip_prefix('#')) else {
+            break;
+        };
+        match root.pointer(pointer) {
+            Some(target) => value = target,
+            None => break,
+        }
+    }
+    value
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(String::from)
+}
+
+/// A value that fits `schema`, preferring the examples the document gives
+fn sample(root: &Value, schema: &Value, depth: usize) -> Value {
+    let schema = resolve(root, schema);
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+    for key in ["example", "default"] {
+        if let Some(value) = schema.get(key) {
+            return value.clone();
+        }
+    }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|e| e.first()) {
+        return first.clone();
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            if let Value::Object(fields) = sample(root, part, depth + 1) {
+                merged.extend(fields);
+            }
+        }
+        return Value::Object(merged);
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(first) = schema.get(key).and_then(Value::as_array).and_then(|v| v.first()) {
+            return sample(root, first, depth + 1);
+        }
+    }
+    let kind = schema.get("type").and_then(|t| match t {
+        // OpenAPI 3.1 allows a list of types
+        Value::Array(types) => types.iter().filter_map(Value::as_str).find(|t| *t != "null"),
+        other => other.as_str(),
+    });
+    let kind = kind.unwrap_or(if schema.get("properties").is_some() { "object" } else { "string" });
+    match kind {
+        "object" => {
+            let mut object = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    object.insert(name.clone(), sample(root, property, depth + 1));
+                }
+            }
+            Value::Object(object)
+        }
+        "array" => match schema.get("items") {
+            Some(items) => json!([sample(root, items, depth + 1)]),
+            None => json!([]),
+        },
+        "integer" | "number" => json!(1),
+        "boolean" => json!(true),
+        _ => match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => json!("2024-01-01T00:00:00Z"),
+            Some("date") => json!("2024-01-01"),
+            Some("email") => json!("user@example.com"),
+            Some("uuid") => json!("00000000-0000-0000-0000-000000000000"),
+            Some("uri" | "url") => json!("https://example.com"),
+            Some("ipv4") => json!("127.0.0.1"),
+            Some("binary" | "byte") => json!(""),
+            _ => json!("string"),
+        },
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether a field holds a file, or a list of files
+fn is_binary(root: &Value, schema: &Value) -> bool {
+    let file = |schema: &Value| {
+        schema.get("type").and_then(Value::as_str) == Some("file")
+            || matches!(schema.get("format").and_then(Value::as_str), Some("binary" | "base64"))
+    };
+    let schema = resolve(root, schema);
+    file(schema) || schema.get("items").is_some_and(|items| file(resolve(root, items)))
+}
+
+fn is_upload_content_type(content_type: &str) -> bool {
+    content_type == "application/octet-stream"
+        || ["image/", "audio/", "video/"].iter().any(|prefix| content_type.starts_with(prefix))
+}
+
+/// Form fields: (name, sample value, whether it holds a file)
+fn form_body(content_type: &str, fields: &[(String, String, bool)]) -> String {
+    if content_type.starts_with("multipart/") {
+        let mut body = String::new();
+        for (name, value, file) in fields {
+            body.push_str(&format!("--{}\r\n", MULTIPART_BOUNDARY));
+            if *file {
+                body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"test.txt\"\r\nContent-Type: text/plain\r\n\r\ntest\r\n",
+                    name
+                ));
+            } else {
+                body.push_str(&format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", name, value));
+            }
+        }
+        body.push_str(&format!("--{}--\r\n", MULTIPART_BOUNDARY));
+        body
+    } else {
+        fields
+            .iter()
+            .map(|(name, value, _)| format!("{}={}", urlencoding::encode(name), urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Preferred request content type: JSON, then forms, then whatever is first
+fn pick_content_type<'a>(types: &[&'a str]) -> Option<&'a str> {
+    let find = |matches: fn(&str) -> bool| types.iter().copied().find(|t| matches(t));
+    find(|t| t == "application/json")
+        .or_else(|| find(|t| t.ends_with("+json")))
+        .or_else(|| find(|t| t == "application/x-www-form-urlencoded"))
+        .or_else(|| find(|t| t.starts_with("multipart/form-data")))
+        .or_else(|| types.first().copied())
+}
+
+fn security(root: &Value, operation: &Value) -> Vec<Vec<String>> {
+    let requirements = operation.get("security").or_else(|| root.get("security"));
+    requirements
+        .and_then(Value::as_array)
+        .map(|sets| {
+            sets.iter()
+                .map(|set| set.as_object().map(|s| s.keys().cloned().collect()).unwrap_or_default())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn auth_schemes(definitions: Option<&Value>) -> Vec<AuthScheme> {
+    let Some(definitions) = definitions.and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    definitions
+        .iter()
+        .map(|(name, scheme)| AuthScheme {
+            name: name.clone(),
+            kind: text(scheme, "type").unwrap_or_default(),
+            scheme: text(scheme, "scheme").map(|s| s.to_lowercase()),
+            location: text(scheme, "in"),
+            parameter: text(scheme, "name"),
+            description: text(scheme, "description"),
+        })
+        .collect()
+}
+
+/// Path-level parameters overridden by operation-level ones of the same name
+fn merged_parameters<'a>(root: &'a Value, path_item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+    let mut merged: Vec<&Value> = Vec::new();
+    for source in [path_item, operation] {
+        for parameter in source.get("parameters").and_then(Value::as_array).into_iter().flatten() {
+            let parameter = resolve(root, parameter);
+            let key = (text(parameter, "name"), text(parameter, "in"));
+            merged.retain(|p| (text(p, "name"), text(p, "in")) != key);
+            merged.push(parameter);
+        }
+    }
+    merged
+}
+
+fn parameter(root: &Value, value: &Value, swagger: bool) -> ApiParameter {
+    let location = text(value, "in").unwrap_or_default();
+    // Swagger 2 puts the type on the parameter, OpenAPI 3 on its schema
+    let schema = if swagger { value } else { value.get("schema").unwrap_or(&Value::Null) };
+    let example = value
+        .get("example")
+        .cloned()
+        .or_else(|| {
+            value
+                .get("examples")
+                .and_then(Value::as_object)
+                .and_then(|examples| examples.values().next())
+                .map(|example| resolve(root, example).get("value").cloned().unwrap_or(Value::Null))
+        })
+        .unwrap_or_else(|| sample(root, schema, 0));
+    ApiParameter {
+        name: text(value, "name").unwrap_or_default(),
+        location: if location == "formData" { "form".to_string() } else { location.clone() },
+        required: value.get("required").and_then(Value::as_bool).unwrap_or(location == "path"),
+        schema_type: text(resolve(root, schema), "type"),
+        example: value_text(&example),
+    }
+}
+
+/// Content type, sample body and whether it uploads files, from an
+/// OpenAPI 3 `requestBody`
+fn request_body(root: &Value, body: &Value) -> (Option<String>, Option<String>, bool) {
+    let Some(content) = resolve(root, body).get("content").and_then(Value::as_object) else {
+        return (None, None, false);
+    };
+    let mut file_upload = false;
+    for (content_type, media) in content {
+        let schema = resolve(root, media.get("schema").unwrap_or(&Value::Null));
+        let binary_field = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .is_some_and(|properties| properties.values().any(|p| is_binary(root, p)));
+        if is_upload_content_type(content_type) || (content_type.starts_with("multipart/") && binary_field) {
+            file_upload = true;
+        }
+    }
+    let Some(content_type) = pick_content_type(&content.keys().map(String::as_str).collect::<Vec<_>>()) else {
+        return (None, None, file_upload);
+    };
+    let media = &content[content_type];
+    let schema = media.get("schema").unwrap_or(&Value::Null);
+    let example = media.get("example").cloned().unwrap_or_else(|| sample(root, schema, 0));
+    let body = if content_type.contains("json") {
+        serde_json::to_string_pretty(&example).ok()
+    } else if content_type.starts_with("multipart/") || content_type == "application/x-www-form-urlencoded" {
+        let properties = resolve(root, schema).get("properties").and_then(Value::as_object);
+        let fields: Vec<(String, String, bool)> = properties
+            .into_iter()
+            .flatten()
+            .map(|(name, property)| {
+                (name.clone(), value_text(example.get(name).unwrap_or(&Value::Null)), is_binary(root, property))
+            })
+            .collect();
+        Some(form_body(content_type, &fields))
+    } else if is_upload_content_type(content_type) {
+        Some(String::new())
+    } else {
+        Some(value_text(&example))
+    };
+    (Some(content_type.to_string()), body, file_upload)
+}
+
+fn operation_endpoint(root: &Value, swagger: bool, path: &str, method: &str, path_item: &Value, operation: &Value) -> Endpoint {
+    let raw_parameters = merged_parameters(root, path_item, operation);
+    let mut parameters = Vec::new();
+    let (mut content_type, mut body, mut file_upload) = (None, None, false);
+
+    if swagger {
+        let consumes: Vec<&str> = operation
+            .get("consumes")
+            .or_else(|| root.get("consumes"))
+            .and_then(Value::as_array)
+            .map(|types| types.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let mut form_fields = Vec::new();
+        for raw in &raw_parameters {
+            match raw.get("in").and_then(Value::as_str) {
+                Some("body") => {
+                    let example = sample(root, raw.get("schema").unwrap_or(&Value::Null), 0);
+                    content_type = Some(pick_content_type(&consumes).unwrap_or("application/json").to_string());
+                    body = serde_json::to_string_pretty(&example).ok();
+                }
+                Some("formData") => {
+                    let field = parameter(root, raw, true);
+                    let file = is_binary(root, raw);
+                    file_upload |= file;
+                    form_fields.push((field.name.clone(), field.example.clone(), file));
+                    parameters.push(field);
+                }
+                _ => parameters.push(parameter(root, raw, true)),
+            }
+        }
+        if !form_fields.is_empty() {
+            let multipart = file_upload || consumes.iter().any(|t| t.starts_with("multipart/"));
+            let kind = if multipart { "multipart/form-data" } else { "application/x-www-form-urlencoded" };
+            body = Some(form_body(kind, &form_fields));
+            content_type = Some(kind.to_string());
+        }
+        file_upload |= consumes.iter().any(|t| is_upload_content_type(t));
+    } else {
+        parameters = raw_parameters.iter().map(|raw| parameter(root, raw, false)).collect();
+        if let Some(body_spec) = operation.get("requestBody") {
+            (content_type, body, file_upload) = request_body(root, body_spec);
+        }
+    }
+
+    Endpoint {
+        method: method.to_uppercase(),
+        path: path.to_string(),
+        operation_id: text(operation, "operationId"),
+        summary: text(operation, "summary"),
+        tags: operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).map(String::from).collect())
+            .unwrap_or_default(),
+        parameters,
+        content_type,
+        body,
+        security: security(root, operation),
+        deprecated: operation.get("deprecated").and_then(Value::as_bool).unwrap_or(false),
+        file_upload,
+    }
+}
+
+fn servers(root: &Value, swagger: bool) -> Vec<String> {
+    if swagger {
+        let base_path = text(root, "basePath").unwrap_or_default();
+        let Some(host) = text(root, "host") else {
+            return if base_path.is_empty() { Vec::new() } else { vec![base_path] };
+        };
+        let schemes: Vec<&str> = root
+            .get("schemes")
+            .and_then(Value::as_array)
+            .map(|s| s.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let schemes = if schemes.is_empty() { vec!["https"] } else { schemes };
+        return schemes.iter().map(|scheme| format!("{}://{}{}", scheme, host, base_path)).collect();
+    }
+    root.get("servers")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|server| {
+            let mut url = text(server, "url")?;
+            for (name, variable) in server.get("variables").and_then(Value::as_object).into_iter().flatten() {
+                let default = variable.get("default").map(value_text).unwrap_or_default();
+                url = url.replace(&format!("{{{}}}", name), &default);
+            }
+            Some(url)
+        })
+        .collect()
+}
+
+/// Parse an OpenAPI 3.x or Swagger 2.0 document
+pub fn parse(source: &str) -> Result<ApiSpec, String> {
+    let root: Value = if source.trim_start().starts_with('{') {
+        serde_json::from_str(source).map_err(|e| format!("Invalid JSON: {}", e))?
+    } else {
+        // Through serde_yaml's own value, since YAML keys such as response
+        // codes are often numbers
+        let yaml: serde_yaml::Value = serde_yaml::from_str(source).map_err(|e| format!("Invalid YAML: {}", e))?;
+        serde_json::to_value(yaml).map_err(|e| format!("Unsupported YAML: {}", e))?
+    };
+    let (swagger, spec_version) = match (text(&root, "openapi"), text(&root, "swagger")) {
+        (Some(version), _) => (false, version),
+        (None, Some(version)) => (true, version),
+        (None, None) => return Err("Not an OpenAPI or Swagger document".to_string()),
+    };
+    let info = root.get("info").unwrap_or(&Value::Null);
+
+    let mut endpoints = Vec::new();
+    for (path, path_item) in root.get("paths").and_then(Value::as_object).into_iter().flatten() {
+        let path_item = resolve(&root, path_item);
+        for method in METHODS {
+            if let Some(operation) = path_item.get(method) {
+                endpoints.push(operation_endpoint(&root, swagger, path, method, path_item, operation));
+            }
+        }
+    }
+
+    let definitions = if swagger {
+        root.get("securityDefinitions")
+    } else {
+        root.pointer("/components/securitySchemes")
+    };
+    Ok(ApiSpec {
+        title: text(info, "title").unwrap_or_else(|| "Imported API".to_string()),
+        version: info.get("version").map(value_text).unwrap_or_default(),
+        spec_version,
+        servers: servers(&root, swagger),
+        auth_schemes: auth_schemes(definitions),
+        endpoints,
+    })
+}
+
+/// Endpoints worth a closer look: state changes without authentication and
+/// file uploads
+pub fn risks(spec: &ApiSpec) -> Vec<ApiRisk> {
+    let mut risks = Vec::new();
+    for endpoint in &spec.endpoints {
+        let target = format!("{} {}", endpoint.method, endpoint.path);
+        if STATE_CHANGING.contains(&endpoint.method.as_str()) && !endpoint.requires_auth() {
+            let how = if endpoint.security.is_empty() { "requires no authentication" } else { "makes authentication optional" };
+            risks.push(ApiRisk {
+                kind: RiskKind::UnauthenticatedStateChange,
+                method: endpoint.method.clone(),
+                path: endpoint.path.clone(),
+                title: format!("Unauthenticated {}", target),
+                message: format!("{} changes state but {}", target, how),
+                severity: Severity::High,
+                cwe: "CWE-306",
+            });
+        }
+        if endpoint.file_upload {
+            let open = !endpoint.requires_auth();
+            risks.push(ApiRisk {
+                kind: RiskKind::FileUpload,
+                method: endpoint.method.clone(),
+                path: endpoint.path.clone(),
+                title: format!("File upload at {}", target),
+                message: format!(
+                    "{} accepts file uploads{}; check type, size and storage location handling",
+                    target,
+                    if open { " without authentication" } else { "" }
+                ),
+                severity: if open { Severity::High } else { Severity::Medium },
+                cwe: "CWE-434",
+            });
+        }
+    }
+    risks
+}
+
+fn append_query(url: &mut String, name: &str, value: &str) {
+    url.push(if url.contains('?') { '&' } else { '?' });
+    url.push_str(&format!("{}={}", urlencoding::encode(name), urlencoding::encode(value)));
+}
+
+/// A ready-to-send request for every endpoint, named "METHOD /path".
+/// Relative servers are resolved against `base_url`; credentials are
+/// left as placeholders.
+pub fn requests(spec: &ApiSpec, base_url: Option<&str>) -> Vec<SavedRequest> {
+    let server = spec.servers.first().map(String::as_str).unwrap_or_default();
+    let base = match base_url.map(|b| b.trim_end_matches('/')) {
+        Some(base) if !server.contains("://") => format!("{}{}", base, server),
+        _ => server.to_string(),
+    };
+    let base = base.trim_end_matches('/');
+
+    spec.endpoints
+        .iter()
+        .map(|endpoint| {
+            let mut path = endpoint.path.clone();
+            let mut headers = Vec::new();
+            let mut cookies = Vec::new();
+            let mut query = Vec::new();
+            for parameter in &endpoint.parameters {
+                match parameter.location.as_str() {
+                    "path" => path = path.replace(&format!("{{{}}}", parameter.name), &urlencoding::encode(&parameter.example)),
+                    "query" if parameter.required => query.push((parameter.name.clone(), parameter.example.clone())),
+                    "header" => headers.push(HttpHeader { name: parameter.name.clone(), value: parameter.example.clone() }),
+                    "cookie" => cookies.push(format!("{}={}", parameter.name, parameter.example)),
+                    _ => {}
+                }
+            }
+
+            let mut auth = None;
+            let schemes = endpoint.security.iter().find(|set| !set.is_empty()).into_iter().flatten();
+            for scheme in schemes.filter_map(|name| spec.auth_schemes.iter().find(|s| &s.name == name)) {
+                let name = scheme.parameter.clone().unwrap_or_default();
+                match (scheme.kind.as_str(), scheme.scheme.as_deref(), scheme.location.as_deref()) {
+                    ("apiKey", _, Some("query")) => query.push((name, CREDENTIAL_PLACEHOLDER.to_string())),
+                    ("apiKey", _, Some("cookie")) => cookies.push(format!("{}={}", name, CREDENTIAL_PLACEHOLDER)),
+                    ("apiKey", _, _) => headers.push(HttpHeader { name, value: CREDENTIAL_PLACEHOLDER.to_string() }),
+                    ("basic", _, _) | ("http", Some("basic"), _) => {
+                        auth = Some(HttpAuth::Basic {
+                            username: CREDENTIAL_PLACEHOLDER.to_string(),
+                            password: Some(CREDENTIAL_PLACEHOLDER.to_string()),
+                        })
+                    }
+                    _ => auth = Some(HttpAuth::Bearer { token: CREDENTIAL_PLACEHOLDER.to_string() }),
+                }
+            }
+
+            let mut url = format!("{}{}", base, path);
+            for (name, value) in &query {
+                append_query(&mut url, name, value);
+            }
+            if !cookies.is_empty() {
+                headers.push(HttpHeader { name: "Cookie".to_string(), value: cookies.join("; ") });
+            }
+            if let Some(content_type) = &endpoint.content_type {
+                let value = if content_type.starts_with("multipart/") {
+                    format!("{}; boundary={}", content_type, MULTIPART_BOUNDARY)
+                } else {
+                    content_type.clone()
+                };
+                headers.push(HttpHeader { name: "Content-Type".to_string(), value });
+            }
+
+            SavedRequest {
+                name: format!("{} {}", endpoint.method, endpoint.path),
+                request: HttpRequestSpec {
+                    method: endpoint.method.clone(),
+                    url,
+                    headers,
+                    body: endpoint.body.clone(),
+                    body_base64: false,
+                    auth,
+                    proxy: None,
+                    verify_tls: true,
+                    follow_redirects: true,
+                    timeout_ms: None,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Import the document at `path` into a workspace: its requests replace the
+/// collection named `collection` (the document title by default) and its
+/// risks are recorded as findings against the file
+pub fn import_file(workspace_root: &str, path: &str, collection: Option<String>, base_url: Option<&str>) -> Result<OpenApiImport, String> {
+    let spec = parse(&http_client::read_import(path)?)?;
+    let risks = risks(&spec);
+
+    let name = collection
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| spec.title.clone());
+    http_client::replace_collection(
+        workspace_root,
+        HttpCollection {
+            name: name.clone(),
+            requests: requests(&spec, base_url),
+        },
+    )?;
+
+    let scope = [path.to_string()];
+    let found = risks.iter().map(|risk| Finding::from_api_risk(risk, path, workspace_root)).collect();
+    findings::record_or_warn(workspace_root, FindingSource::OpenApi, Some(&scope), found);
+
+    Ok(OpenApiImport { collection: name, spec, risks })
+}
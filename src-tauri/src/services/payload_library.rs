@@ -0,0 +1,160 @@
+//! Payload Library
+//!
+//! User-curated payloads (name, category, text, notes, tags) persisted in
+//! ~/.ctr/payloads/library.json, alongside the built-in exploit templates.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPayload {
+    /// Assigned on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub payload: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub created_at: u64,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PayloadQuery {
+    /// Case-insensitive match against name, payload and notes
+    pub text: Option<String>,
+    pub category: Option<String>,
+    /// Payload must carry every listed tag
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn library_file() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("payloads");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create payloads directory: {}", e))?;
+    Ok(dir.join("library.json"))
+}
+
+pub fn load_library() -> Vec<UserPayload> {
+    match library_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_library(payloads: &[UserPayload]) -> Result<(), String> {
+    save_json(&library_file()?, payloads)
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Create a payload or update the one with the same id; returns the stored payload
+pub fn upsert(mut payload: UserPayload) -> Result<UserPayload, String> {
+    if payload.name.trim().is_empty() {
+        return Err("Payload name cannot be empty".to_string());
+    }
+    if payload.payload.is_empty() {
+        return Err("Payload text cannot be empty".to_string());
+    }
+
+    let now = now_millis();
+    payload.tags = normalize_tags(&payload.tags);
+    payload.updated_at = now;
+
+    let mut library = load_library();
+    match library.iter_mut().find(|p| !payload.id.is_empty() && p.id == payload.id) {
+        Some(existing) => {
+            payload.created_at = existing.created_at;
+            *existing = payload.clone();
+        }
+        None => {
+            if payload.id.is_empty() {
+                payload.id = uuid::Uuid::new_v4().to_string();
+            }
+            payload.created_at = now;
+            library.push(payload.clone());
+        }
+    }
+
+    save_library(&library)?;
+    Ok(payload)
+}
+
+pub fn delete(id: &str) -> Result<(), String> {
+    let mut library = load_library();
+    let before = library.len();
+    library.retain(|p| p.id != id);
+
+    if library.len() == before {
+        return Err(format!("Payload {} not found", id));
+    }
+    save_library(&library)
+}
+
+pub fn search(query: &PayloadQuery) -> Vec<UserPayload> {
+    let text = query.text.as_ref().map(|t| t.to_lowercase());
+    let category = query.category.as_ref().map(|c| c.to_lowercase());
+    let tags = normalize_tags(&query.tags);
+
+    load_library()
+        .into_iter()
+        .filter(|p| {
+            text.as_ref().map_or(true, |t| {
+                p.name.to_lowercase().contains(t)
+                    || p.payload.to_lowercase().contains(t)
+                    || p.notes.to_lowercase().contains(t)
+            })
+        })
+        .filter(|p| category.as_ref().map_or(true, |c| p.category.to_lowercase() == *c))
+        .filter(|p| tags.iter().all(|t| p.tags.contains(t)))
+        .collect()
+}
+
+/// All tags in use, for autocomplete
+pub fn tags() -> Vec<String> {
+    let all: Vec<String> = load_library().into_iter().flat_map(|p| p.tags).collect();
+    normalize_tags(&all)
+}
+
+/// Export the given payloads (or the whole library) to a JSON file
+pub fn export(dest: &Path, ids: Option<&[String]>) -> Result<usize, String> {
+    let payloads: Vec<UserPayload> = load_library()
+        .into_iter()
+        .filter(|p| ids.map_or(true, |ids| ids.contains(&p.id)))
+        .collect();
+    save_json(dest, &payloads)?;
+    Ok(payloads.len())
+}
+
+/// Import payloads from a JSON file. Entries whose id already exists are
+/// replaced; entries without an id get a fresh one.
+pub fn import(src: &Path) -> Result<usize, String> {
+    let content = std::fs::read_to_string(src)
+        .map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+    let incoming: Vec<UserPayload> = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid payload file: {}", e))?;
+
+    let count = incoming.len();
+    for payload in incoming {
+        upsert(payload)?;
+    }
+    Ok(count)
+}
@@ -0,0 +1,293 @@
+//! Payload Verification
+//!
+//! Sends a payload to a lab target and looks for evidence that it worked:
+//! error signatures, time delays, response differences against a benign
+//! baseline and unescaped reflection. Only hosts on the user's lab allowlist
+//! (~/.ctr/lab_targets.json) can be targeted, and every run is recorded in
+//! ~/.ctr/evidence/verifications.json.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::services::http_client::{self, HttpHeader, HttpRequestSpec, HttpResponseData};
+use crate::services::progress::{self, ProgressEvent, ProgressKind};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+/// Extra delay over the baseline that counts as a time-based hit
+const TIME_DELAY_THRESHOLD_MS: u128 = 4000;
+/// Relative body-length change that counts as a response difference
+const LENGTH_DIFF_RATIO: f64 = 0.1;
+const EXCERPT_RADIUS: usize = 120;
+
+const ERROR_SIGNATURES: &[(&str, &str)] = &[
+    ("sql_error", r"(?i)you have an error in your sql syntax"),
+    ("sql_error", r"(?i)warning: mysql_|mysqli?_fetch"),
+    ("sql_error", r"(?i)unclosed quotation mark after the character string"),
+    ("sql_error", r"ORA-\d{5}"),
+    ("sql_error", r"(?i)sqlite3?::|SQLITE_ERROR|near \S+: syntax error"),
+    ("sql_error", r"(?i)pg::syntaxerror|postgresql.*error|syntax error at or near"),
+    ("command_output", r"uid=\d+\([^)]+\) gid=\d+"),
+    ("file_disclosure", r"root:x:0:0:"),
+    ("file_disclosure", r"(?i)\[(fonts|extensions)\]\s*\r?\n"),
+    ("template_eval", r"\b49\b.*\{\{7\*7\}\}|\{\{7\*7\}\}.*\b49\b"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationSignal {
+    /// sql_error, command_output, file_disclosure, time_delay, response_diff, reflected, ...
+    pub kind: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseSummary {
+    pub status: u16,
+    pub length: usize,
+    pub time_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationResult {
+    pub id: String,
+    pub timestamp: u64,
+    pub target_url: String,
+    pub parameter: String,
+    pub method: String,
+    pub payload: String,
+    pub verified: bool,
+    pub signals: Vec<VerificationSignal>,
+    pub baseline: Option<ResponseSummary>,
+    pub response: ResponseSummary,
+    /// Response text around the strongest signal
+    pub evidence_excerpt: Option<String>,
+}
+
+fn targets_file() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("lab_targets.json"))
+}
+
+fn evidence_file() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("evidence");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create evidence directory: {}", e))?;
+    Ok(dir.join("verifications.json"))
+}
+
+/// Hosts (optionally host:port) that payloads may be sent to
+pub fn lab_targets() -> Vec<String> {
+    match targets_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn set_lab_targets(targets: Vec<String>) -> Result<(), String> {
+    let mut targets: Vec<String> = targets
+        .into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    targets.sort();
+    targets.dedup();
+    save_json(&targets_file()?, &targets)
+}
+
+/// Whether `host` (on `port`, when known) is on the lab target allowlist
+pub fn is_lab_target(host: &str, port: Option<u16>) -> bool {
+    let host = host.to_lowercase();
+    let host_port = port
+        .map(|p| format!("{}:{}", host, p))
+        .unwrap_or_else(|| host.clone());
+
+    lab_targets()
+        .iter()
+        .any(|t| *t == host || *t == host_port)
+}
+
+fn is_allowed(url: &reqwest::Url) -> bool {
+    match url.host_str() {
+        Some(host) => is_lab_target(host, url.port_or_known_default()),
+        None => false,
+    }
+}
+
+pub fn history() -> Vec<VerificationResult> {
+    match evidence_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn record(result: &VerificationResult) -> Result<(), String> {
+    let path = evidence_file()?;
+    let mut results: Vec<VerificationResult> = load_json(&path);
+    results.push(result.clone());
+    save_json(&path, &results)
+}
+
+fn build_request(url: &reqwest::Url, parameter: &str, value: &str, method: &str) -> HttpRequestSpec {
+    let method = method.to_uppercase();
+    let mut target = url.clone();
+    let mut headers = Vec::new();
+    let mut body = None;
+
+    if method == "GET" || method == "DELETE" {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, _)| k != parameter)
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        target
+            .query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair(parameter, value);
+    } else {
+        headers.push(HttpHeader {
+            name: "Content-Type".to_string(),
+            value: "application/x-www-form-urlencoded".to_string(),
+        });
+        body = Some(format!("{}={}", urlencoding::encode(parameter), urlencoding::encode(value)));
+    }
+
+    HttpRequestSpec {
+        method,
+        url: target.to_string(),
+        headers,
+        body,
+        body_base64: false,
+        auth: None,
+        proxy: None,
+        verify_tls: false,
+        // A redirect could leave the allowlisted target, so it is judged as is
+        follow_redirects: false,
+        timeout_ms: Some(30_000),
+    }
+}
+
+fn summary(response: &HttpResponseData) -> ResponseSummary {
+    ResponseSummary {
+        status: response.status,
+        length: response.size_bytes,
+        time_ms: response.time_ms,
+    }
+}
+
+fn excerpt(body: &str, start: usize, end: usize) -> String {
+    let mut from = start.saturating_sub(EXCERPT_RADIUS);
+    let mut to = (end + EXCERPT_RADIUS).min(body.len());
+    while !body.is_char_boundary(from) {
+        from -= 1;
+    }
+    while !body.is_char_boundary(to) {
+        to += 1;
+    }
+    body[from..to].to_string()
+}
+
+/// Send `payload` in `parameter` to an allowlisted lab target and look for success signals
+pub async fn verify(target_url: &str, parameter: &str, payload: &str, method: &str) -> Result<VerificationResult, String> {
+    let url = reqwest::Url::parse(target_url).map_err(|e| format!("Invalid target URL: {}", e))?;
+    if !is_allowed(&url) {
+        return Err(format!(
+            "{} is not in the lab target allowlist",
+            url.host_str().unwrap_or(target_url)
+        ));
+    }
+
+    let baseline = http_client::send(&build_request(&url, parameter, "1", method))
+        .await
+        .ok();
+    let response = http_client::send(&build_request(&url, parameter, payload, method)).await?;
+
+    let body = if response.body_base64 { "" } else { response.body.as_str() };
+    let baseline_body = baseline
+        .as_ref()
+        .filter(|b| !b.body_base64)
+        .map(|b| b.body.as_str())
+        .unwrap_or("");
+
+    let mut signals = Vec::new();
+    let mut evidence_excerpt = None;
+
+    for (kind, pattern) in ERROR_SIGNATURES {
+        let re = Regex::new(pattern).unwrap();
+        // Signatures already present in the baseline are not caused by the payload
+        if let Some(m) = re.find(body).filter(|_| !re.is_match(baseline_body)) {
+            signals.push(VerificationSignal {
+                kind: kind.to_string(),
+                detail: m.as_str().trim().to_string(),
+            });
+            evidence_excerpt.get_or_insert_with(|| excerpt(body, m.start(), m.end()));
+        }
+    }
+
+    if let Some(base) = &baseline {
+        if response.time_ms > base.time_ms + TIME_DELAY_THRESHOLD_MS {
+            signals.push(VerificationSignal {
+                kind: "time_delay".to_string(),
+                detail: format!("{} ms vs {} ms baseline", response.time_ms, base.time_ms),
+            });
+        }
+
+        let length_ratio = (response.size_bytes as f64 - base.size_bytes as f64).abs()
+            / (base.size_bytes.max(1) as f64);
+        if response.status != base.status || length_ratio > LENGTH_DIFF_RATIO {
+            signals.push(VerificationSignal {
+                kind: "response_diff".to_string(),
+                detail: format!(
+                    "status {} -> {}, length {} -> {}",
+                    base.status, response.status, base.size_bytes, response.size_bytes
+                ),
+            });
+        }
+    }
+
+    // Only meaningful for payloads with markup/special characters
+    let has_special = payload.chars().any(|c| "<>\"'".contains(c));
+    if has_special {
+        if let Some(pos) = body.find(payload) {
+            signals.push(VerificationSignal {
+                kind: "reflected".to_string(),
+                detail: "Payload reflected without encoding".to_string(),
+            });
+            evidence_excerpt.get_or_insert_with(|| excerpt(body, pos, pos + payload.len()));
+        }
+    }
+
+    // A bare response difference is too weak on its own to call the payload verified
+    let verified = signals.iter().any(|s| s.kind != "response_diff");
+
+    let result = VerificationResult {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: now_millis(),
+        target_url: target_url.to_string(),
+        parameter: parameter.to_string(),
+        method: method.to_uppercase(),
+        payload: payload.to_string(),
+        verified,
+        signals,
+        baseline: baseline.as_ref().map(summary),
+        response: summary(&response),
+        evidence_excerpt,
+    };
+
+    record(&result)?;
+    if verified {
+        let kinds: Vec<&str> = result.signals.iter().map(|s| s.kind.as_str()).collect();
+        progress::record_quietly(ProgressEvent {
+            id: 0,
+            user: progress::default_user(),
+            kind: ProgressKind::ExploitVerified,
+            skill: String::new(),
+            reference: format!("{} [{}]", target_url, parameter),
+            detail: kinds.join(", "),
+            points: 0,
+            duration_ms: None,
+            created_at: 0,
+        });
+    }
+    Ok(result)
+}
@@ -0,0 +1,299 @@
+//! Link, network and transport layer decoding of captured frames
+
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const LINKTYPE_NULL: i32 = 0;
+const LINKTYPE_ETHERNET: i32 = 1;
+const LINKTYPE_RAW: i32 = 101;
+const LINKTYPE_LOOP: i32 = 108;
+const LINKTYPE_LINUX_SLL: i32 = 113;
+const LINKTYPE_IPV4: i32 = 228;
+const LINKTYPE_IPV6: i32 = 229;
+const LINKTYPE_LINUX_SLL2: i32 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_ARP: u16 = 0x0806;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+
+/// IPv6 extension headers skipped on the way to the transport header
+const IPV6_EXTENSION_HEADERS: &[u8] = &[0, 43, 60];
+
+/// Application protocols by well-known port
+const PORT_PROTOCOLS: &[(u16, &str)] = &[
+    (20, "FTP-DATA"), (21, "FTP"), (22, "SSH"), (23, "Telnet"), (25, "SMTP"), (53, "DNS"), (67, "DHCP"),
+    (68, "DHCP"), (80, "HTTP"), (88, "Kerberos"), (110, "POP3"), (123, "NTP"), (137, "NetBIOS"),
+    (139, "NetBIOS"), (143, "IMAP"), (161, "SNMP"), (162, "SNMP"), (389, "LDAP"), (443, "TLS"), (445, "SMB"),
+    (465, "SMTP"), (587, "SMTP"), (636, "LDAPS"), (993, "IMAPS"), (995, "POP3S"), (1433, "MSSQL"),
+    (3306, "MySQL"), (3389, "RDP"), (5353, "mDNS"), (5432, "PostgreSQL"), (6379, "Redis"), (8000, "HTTP"),
+    (8008, "HTTP"), (8080, "HTTP"), (8443, "TLS"),
+];
+
+const HTTP_METHODS: &[&str] = &["GET ", "POST ", "PUT ", "DELETE ", "HEAD ", "OPTIONS ", "PATCH ", "CONNECT ", "TRACE "];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerField {
+    pub name: String,
+    pub value: String,
+}
+
+/// One decoded header, as shown in the packet detail tree
+#[derive(Debug, Clone, Serialize)]
+pub struct Layer {
+    pub name: String,
+    pub fields: Vec<LayerField>,
+}
+
+impl Layer {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), fields: Vec::new() }
+    }
+
+    fn field(mut self, name: &str, value: impl ToString) -> Self {
+        self.fields.push(LayerField { name: name.to_string(), value: value.to_string() });
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Decoded<'a> {
+    pub layers: Vec<Layer>,
+    pub src: Option<IpAddr>,
+    pub dst: Option<IpAddr>,
+    /// TCP, UDP, ICMP, ICMPv6 or ARP
+    pub transport: Option<&'static str>,
+    pub src_port: Option<u16>,
+    pub dst_port: Option<u16>,
+    pub tcp_flags: Option<u8>,
+    /// Transport payload
+    pub payload: &'a [u8],
+}
+
+impl Decoded<'_> {
+    /// The application protocol when one is recognized, else the transport
+    pub fn protocol(&self) -> &'static str {
+        if self.transport == Some("TCP") && looks_like_http(self.payload) {
+            return "HTTP";
+        }
+        if matches!(self.transport, Some("TCP") | Some("UDP")) {
+            let by_port = |port: Option<u16>| port.and_then(|p| PORT_PROTOCOLS.iter().find(|(known, _)| *known == p));
+            // The lower port is more likely the service's
+            let (low, high) = match (self.src_port, self.dst_port) {
+                (Some(s), Some(d)) if s < d => (Some(s), Some(d)),
+                (s, d) => (d, s),
+            };
+            if let Some((_, name)) = by_port(low).or_else(|| by_port(high)) {
+                return name;
+            }
+        }
+        self.transport.unwrap_or(match (self.src, self.layers.is_empty()) {
+            (Some(IpAddr::V4(_)), _) => "IPv4",
+            (Some(IpAddr::V6(_)), _) => "IPv6",
+            (None, false) => "Ethernet",
+            (None, true) => "Unknown",
+        })
+    }
+}
+
+pub fn looks_like_http(payload: &[u8]) -> bool {
+    payload.starts_with(b"HTTP/1.") || HTTP_METHODS.iter().any(|m| payload.starts_with(m.as_bytes()))
+}
+
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    data.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn mac(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":")
+}
+
+pub fn decode(linktype: i32, data: &[u8]) -> Decoded<'_> {
+    let mut decoded = Decoded::default();
+    match linktype {
+        LINKTYPE_ETHERNET if data.len() >= 14 => {
+            decoded.layers.push(
+                Layer::new("Ethernet")
+                    .field("Destination", mac(&data[0..6]))
+                    .field("Source", mac(&data[6..12]))
+                    .field("Type", format!("0x{:04x}", u16_at(data, 12).unwrap_or(0))),
+            );
+            ethertype(u16_at(data, 12).unwrap_or(0), &data[14..], &mut decoded);
+        }
+        LINKTYPE_LINUX_SLL if data.len() >= 16 => {
+            let protocol = u16_at(data, 14).unwrap_or(0);
+            decoded.layers.push(Layer::new("Linux cooked capture").field("Protocol", format!("0x{:04x}", protocol)));
+            ethertype(protocol, &data[16..], &mut decoded);
+        }
+        LINKTYPE_LINUX_SLL2 if data.len() >= 20 => {
+            let protocol = u16_at(data, 0).unwrap_or(0);
+            decoded.layers.push(Layer::new("Linux cooked capture v2").field("Protocol", format!("0x{:04x}", protocol)));
+            ethertype(protocol, &data[20..], &mut decoded);
+        }
+        // The address family is in host byte order, so go by the IP version instead
+        LINKTYPE_NULL | LINKTYPE_LOOP if data.len() >= 4 => {
+            decoded.layers.push(Layer::new("Loopback"));
+            ip(&data[4..], &mut decoded);
+        }
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => ip(data, &mut decoded),
+        _ => {}
+    }
+    decoded
+}
+
+fn ethertype<'a>(kind: u16, data: &'a [u8], decoded: &mut Decoded<'a>) {
+    match kind {
+        ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => ip(data, decoded),
+        ETHERTYPE_VLAN if data.len() >= 4 => {
+            decoded.layers.push(Layer::new("802.1Q").field("VLAN", u16_at(data, 0).unwrap_or(0) & 0x0fff));
+            ethertype(u16_at(data, 2).unwrap_or(0), &data[4..], decoded);
+        }
+        ETHERTYPE_ARP if data.len() >= 28 => {
+            let operation = match u16_at(data, 6) {
+                Some(1) => "request",
+                Some(2) => "reply",
+                _ => "other",
+            };
+            let sender = Ipv4Addr::new(data[14], data[15], data[16], data[17]);
+            let target = Ipv4Addr::new(data[24], data[25], data[26], data[27]);
+            decoded.layers.push(
+                Layer::new("ARP")
+                    .field("Operation", operation)
+                    .field("Sender MAC", mac(&data[8..14]))
+                    .field("Sender IP", sender)
+                    .field("Target MAC", mac(&data[18..24]))
+                    .field("Target IP", target),
+            );
+            decoded.src = Some(IpAddr::V4(sender));
+            decoded.dst = Some(IpAddr::V4(target));
+            decoded.transport = Some("ARP");
+        }
+        _ => {}
+    }
+}
+
+fn ip<'a>(data: &'a [u8], decoded: &mut Decoded<'a>) {
+    match data.first().map(|b| b >> 4) {
+        Some(4) => ipv4(data, decoded),
+        Some(6) => ipv6(data, decoded),
+        _ => {}
+    }
+}
+
+fn ipv4<'a>(data: &'a [u8], decoded: &mut Decoded<'a>) {
+    let header_len = usize::from(data.first().copied().unwrap_or(0) & 0x0f) * 4;
+    if data.len() < 20 || header_len < 20 || data.len() < header_len {
+        return;
+    }
+    let total = usize::from(u16_at(data, 2).unwrap_or(0)).clamp(header_len, data.len());
+    let fragment_offset = u16_at(data, 6).unwrap_or(0) & 0x1fff;
+    let protocol = data[9];
+    let src = Ipv4Addr::new(data[12], data[13], data[14], data[15]);
+    let dst = Ipv4Addr::new(data[16], data[17], data[18], data[19]);
+    decoded.layers.push(
+        Layer::new("IPv4")
+            .field("Source", src)
+            .field("Destination", dst)
+            .field("TTL", data[8])
+            .field("Protocol", protocol)
+            .field("Identification", format!("0x{:04x}", u16_at(data, 4).unwrap_or(0)))
+            .field("Total length", total),
+    );
+    decoded.src = Some(IpAddr::V4(src));
+    decoded.dst = Some(IpAddr::V4(dst));
+    // Later fragments carry no transport header
+    if fragment_offset == 0 {
+        transport(protocol, &data[header_len..total], decoded);
+    }
+}
+
+fn ipv6<'a>(data: &'a [u8], decoded: &mut Decoded<'a>) {
+    if data.len() < 40 {
+        return;
+    }
+    let end = (40 + usize::from(u16_at(data, 4).unwrap_or(0))).min(data.len());
+    let address = |at: usize| {
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(&data[at..at + 16]);
+        Ipv6Addr::from(octets)
+    };
+    let (src, dst) = (address(8), address(24));
+    decoded.layers.push(
+        Layer::new("IPv6")
+            .field("Source", src)
+            .field("Destination", dst)
+            .field("Hop limit", data[7])
+            .field("Next header", data[6]),
+    );
+    decoded.src = Some(IpAddr::V6(src));
+    decoded.dst = Some(IpAddr::V6(dst));
+
+    let (mut next, mut offset) = (data[6], 40);
+    while IPV6_EXTENSION_HEADERS.contains(&next) && offset + 2 <= end {
+        next = data[offset];
+        offset += (usize::from(data[offset + 1]) + 1) * 8;
+    }
+    if offset <= end {
+        transport(next, &data[offset..end], decoded);
+    }
+}
+
+fn transport<'a>(protocol: u8, data: &'a [u8], decoded: &mut Decoded<'a>) {
+    match protocol {
+        6 if data.len() >= 20 => {
+            let header_len = (usize::from(data[12] >> 4) * 4).clamp(20, data.len());
+            let (src_port, dst_port) = (u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0));
+            let flags = data[13];
+            decoded.layers.push(
+                Layer::new("TCP")
+                    .field("Source port", src_port)
+                    .field("Destination port", dst_port)
+                    .field("Sequence", u32_at(data, 4).unwrap_or(0))
+                    .field("Acknowledgment", u32_at(data, 8).unwrap_or(0))
+                    .field("Flags", tcp_flags(flags))
+                    .field("Window", u16_at(data, 14).unwrap_or(0)),
+            );
+            decoded.transport = Some("TCP");
+            decoded.src_port = Some(src_port);
+            decoded.dst_port = Some(dst_port);
+            decoded.tcp_flags = Some(flags);
+            decoded.payload = &data[header_len..];
+        }
+        17 if data.len() >= 8 => {
+            let (src_port, dst_port) = (u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0));
+            decoded.layers.push(
+                Layer::new("UDP")
+                    .field("Source port", src_port)
+                    .field("Destination port", dst_port)
+                    .field("Length", u16_at(data, 4).unwrap_or(0)),
+            );
+            decoded.transport = Some("UDP");
+            decoded.src_port = Some(src_port);
+            decoded.dst_port = Some(dst_port);
+            decoded.payload = &data[8..];
+        }
+        1 | 58 if data.len() >= 4 => {
+            let name = if protocol == 1 { "ICMP" } else { "ICMPv6" };
+            decoded.layers.push(Layer::new(name).field("Type", data[0]).field("Code", data[1]));
+            decoded.transport = Some(name);
+            decoded.payload = &data[4..];
+        }
+        _ => {}
+    }
+}
+
+/// Set TCP flags as "SYN, ACK"
+pub fn tcp_flags(flags: u8) -> String {
+    const NAMES: [&str; 8] = ["FIN", "SYN", "RST", "PSH", "ACK", "URG", "ECE", "CWR"];
+    NAMES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,394 @@
+//! Packet capture analysis
+//!
+//! Loads pcap and pcapng files, summarizes their conversations and
+//! protocols, and pulls out HTTP requests, DNS queries and credentials sent
+//! in cleartext. Opened captures stay in memory under an id so the capture
+//! viewer can page through packets and decode them one at a time.
+
+pub mod decode;
+pub mod protocols;
+
+use pcap_parser::pcapng::Block;
+use pcap_parser::{create_reader, PcapBlockOwned, PcapError};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use decode::{decode, tcp_flags, Decoded, Layer};
+use protocols::{CleartextCredential, DnsQuery, HttpRequest};
+
+/// Captures are held in memory, so larger files are refused
+const MAX_CAPTURE_BYTES: u64 = 512 * 1024 * 1024;
+
+const READ_BUFFER_BYTES: usize = 1 << 16;
+const DEFAULT_PAGE_SIZE: usize = 200;
+const MAX_PAGE_SIZE: usize = 5000;
+const MICROS_PER_SECOND: u64 = 1_000_000;
+
+lazy_static::lazy_static! {
+    /// Capture id -> opened capture
+    static ref CAPTURES: Mutex<HashMap<String, Arc<Capture>>> = Mutex::new(HashMap::new());
+}
+
+struct Packet {
+    /// Microseconds since the Unix epoch
+    timestamp: u64,
+    /// Length on the wire; the captured data may be shorter
+    length: u32,
+    linktype: i32,
+    data: Vec<u8>,
+}
+
+struct Capture {
+    packets: Vec<Packet>,
+    summary: CaptureSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Conversation {
+    /// Transport protocol
+    pub protocol: String,
+    /// The endpoint that sent the first packet
+    pub a: String,
+    pub b: String,
+    pub packets: usize,
+    pub bytes_a_to_b: u64,
+    pub bytes_b_to_a: u64,
+    /// Seconds from the start of the capture
+    pub start: f64,
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtocolStats {
+    pub protocol: String,
+    pub packets: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSummary {
+    pub id: String,
+    pub path: String,
+    pub packets: usize,
+    pub bytes: u64,
+    /// Unix timestamp in milliseconds of the first packet
+    pub started_at: Option<u64>,
+    /// Seconds from the first packet to the last
+    pub duration: f64,
+    /// Busiest first
+    pub protocols: Vec<ProtocolStats>,
+    /// Busiest first
+    pub conversations: Vec<Conversation>,
+    pub http_requests: Vec<HttpRequest>,
+    pub dns_queries: Vec<DnsQuery>,
+    pub credentials: Vec<CleartextCredential>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PacketSummary {
+    pub index: usize,
+    /// Seconds from the start of the capture
+    pub time: f64,
+    pub length: u32,
+    pub source: String,
+    pub destination: String,
+    pub protocol: String,
+    pub info: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PacketPage {
+    /// Packets matching the filter
+    pub total: usize,
+    pub offset: usize,
+    pub packets: Vec<PacketSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PacketDetail {
+    pub summary: PacketSummary,
+    pub layers: Vec<Layer>,
+    /// Offset, hex and ASCII columns, 16 bytes per line
+    pub hex: String,
+}
+
+fn endpoint(address: Option<IpAddr>, port: Option<u16>) -> String {
+    match (address, port) {
+        (Some(IpAddr::V6(ip)), Some(port)) => format!("[{}]:{}", ip, port),
+        (Some(ip), Some(port)) => format!("{}:{}", ip, port),
+        (Some(ip), None) => ip.to_string(),
+        (None, _) => String::new(),
+    }
+}
+
+fn seconds(micros: u64) -> f64 {
+    micros as f64 / MICROS_PER_SECOND as f64
+}
+
+fn read_packets(path: &Path) -> Result<Vec<Packet>, String> {
+    let size = std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?.len();
+    if size > MAX_CAPTURE_BYTES {
+        return Err(format!("{} is larger than {} MB", path.display(), MAX_CAPTURE_BYTES / (1024 * 1024)));
+    }
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut reader = create_reader(READ_BUFFER_BYTES, file)
+        .map_err(|e| format!("{} is not a pcap or pcapng file: {:?}", path.display(), e))?;
+
+    let mut packets = Vec::new();
+    let mut legacy_linktype = 1;
+    let mut legacy_nanoseconds = false;
+    // pcapng interfaces as (linktype, timestamp units per second, offset)
+    let mut interfaces: Vec<(i32, u64, i64)> = Vec::new();
+    loop {
+        match reader.next() {
+            Ok((consumed, block)) => {
+                let packet = match block {
+                    PcapBlockOwned::LegacyHeader(header) => {
+                        legacy_linktype = header.network.0;
+                        legacy_nanoseconds = header.is_nanosecond_precision();
+                        None
+                    }
+                    PcapBlockOwned::Legacy(record) => {
+                        let fraction = if legacy_nanoseconds { u64::from(record.ts_usec) / 1000 } else { u64::from(record.ts_usec) };
+                        Some(Packet {
+                            timestamp: u64::from(record.ts_sec) * MICROS_PER_SECOND + fraction,
+                            length: record.origlen,
+                            linktype: legacy_linktype,
+                            data: record.data.to_vec(),
+                        })
+                    }
+                    PcapBlockOwned::NG(Block::SectionHeader(_)) => {
+                        interfaces.clear();
+                        None
+                    }
+                    PcapBlockOwned::NG(Block::InterfaceDescription(interface)) => {
+                        let resolution = interface.ts_resolution().unwrap_or(MICROS_PER_SECOND);
+                        interfaces.push((interface.linktype.0, resolution, interface.ts_offset()));
+                        None
+                    }
+                    PcapBlockOwned::NG(Block::EnhancedPacket(epb)) => {
+                        let (linktype, resolution, offset) =
+                            interfaces.get(epb.if_id as usize).copied().unwrap_or((1, MICROS_PER_SECOND, 0));
+                        let (secs, fraction) = epb.decode_ts(offset.max(0) as u64, resolution);
+                        let captured = (epb.caplen as usize).min(epb.data.len());
+                        Some(Packet {
+                            timestamp: u64::from(secs) * MICROS_PER_SECOND + u64::from(fraction) * MICROS_PER_SECOND / resolution,
+                            length: epb.origlen,
+                            linktype,
+                            data: epb.data[..captured].to_vec(),
+                        })
+                    }
+                    // Simple packets carry no timestamp and belong to the first interface
+                    PcapBlockOwned::NG(Block::SimplePacket(spb)) => {
+                        let captured = (spb.origlen as usize).min(spb.data.len());
+                        Some(Packet {
+                            timestamp: 0,
+                            length: spb.origlen,
+                            linktype: interfaces.first().map_or(1, |i| i.0),
+                            data: spb.data[..captured].to_vec(),
+                        })
+                    }
+                    _ => None,
+                };
+                packets.extend(packet);
+                reader.consume(consumed);
+            }
+            Err(PcapError::Eof) => break,
+            Err(PcapError::Incomplete(_)) => {
+                reader.refill().map_err(|e| format!("Failed to read {}: {:?}", path.display(), e))?;
+            }
+            Err(e) => return Err(format!("Failed to read {}: {:?}", path.display(), e)),
+        }
+    }
+    Ok(packets)
+}
+
+/// One line of the packet list
+fn summarize(index: usize, packet: &Packet, decoded: &Decoded, start: u64) -> PacketSummary {
+    let protocol = decoded.protocol();
+    let ports = match (decoded.src_port, decoded.dst_port) {
+        (Some(s), Some(d)) => format!("{} → {}", s, d),
+        _ => String::new(),
+    };
+    let info = match protocol {
+        "HTTP" if decode::looks_like_http(decoded.payload) => {
+            let payload = String::from_utf8_lossy(decoded.payload);
+            payload.lines().next().unwrap_or_default().to_string()
+        }
+        "DNS" if decoded.transport == Some("UDP") => {
+            let names: Vec<String> = protocols::dns_queries(index, "", "", decoded.payload)
+                .into_iter()
+                .map(|q| format!("{} {}", q.record_type, q.name))
+                .collect();
+            if names.is_empty() { "Response".to_string() } else { format!("Query {}", names.join(", ")) }
+        }
+        _ => match decoded.tcp_flags {
+            Some(flags) => format!("{} [{}] Len={}", ports, tcp_flags(flags), decoded.payload.len()),
+            None if !ports.is_empty() => format!("{} Len={}", ports, decoded.payload.len()),
+            None => decoded.layers.last().map(|l| l.name.clone()).unwrap_or_default(),
+        },
+    };
+    PacketSummary {
+        index,
+        time: seconds(packet.timestamp.saturating_sub(start)),
+        length: packet.length,
+        source: decoded.src.map(|ip| ip.to_string()).unwrap_or_default(),
+        destination: decoded.dst.map(|ip| ip.to_string()).unwrap_or_default(),
+        protocol: protocol.to_string(),
+        info,
+    }
+}
+
+fn build_summary(id: &str, path: &Path, packets: &[Packet]) -> CaptureSummary {
+    let start = packets.iter().map(|p| p.timestamp).min().unwrap_or(0);
+    let end = packets.iter().map(|p| p.timestamp).max().unwrap_or(0);
+    let mut protocols: HashMap<&'static str, ProtocolStats> = HashMap::new();
+    let mut conversations: HashMap<(String, String, String), Conversation> = HashMap::new();
+    let mut http_requests = Vec::new();
+    let mut dns_queries = Vec::new();
+    let mut credentials = Vec::new();
+
+    for (index, packet) in packets.iter().enumerate() {
+        let decoded = decode(packet.linktype, &packet.data);
+        let protocol = decoded.protocol();
+        let stats = protocols
+            .entry(protocol)
+            .or_insert_with(|| ProtocolStats { protocol: protocol.to_string(), packets: 0, bytes: 0 });
+        stats.packets += 1;
+        stats.bytes += u64::from(packet.length);
+
+        let (Some(transport), Some(_)) = (decoded.transport, decoded.src) else {
+            continue;
+        };
+        let src = endpoint(decoded.src, decoded.src_port);
+        let dst = endpoint(decoded.dst, decoded.dst_port);
+        let key = if src <= dst {
+            (transport.to_string(), src.clone(), dst.clone())
+        } else {
+            (transport.to_string(), dst.clone(), src.clone())
+        };
+        let time = seconds(packet.timestamp.saturating_sub(start));
+        let conversation = conversations.entry(key).or_insert_with(|| Conversation {
+            protocol: transport.to_string(),
+            a: src.clone(),
+            b: dst.clone(),
+            packets: 0,
+            bytes_a_to_b: 0,
+            bytes_b_to_a: 0,
+            start: time,
+            duration: 0.0,
+        });
+        conversation.packets += 1;
+        if conversation.a == src {
+            conversation.bytes_a_to_b += u64::from(packet.length);
+        } else {
+            conversation.bytes_b_to_a += u64::from(packet.length);
+        }
+        conversation.duration = time - conversation.start;
+
+        match protocol {
+            "HTTP" => http_requests.extend(protocols::http_request(index, &src, &dst, decoded.payload)),
+            "DNS" | "mDNS" => dns_queries.extend(protocols::dns_queries(index, &src, &dst, decoded.payload)),
+            _ => {}
+        }
+        if let Some(port) = decoded.dst_port.filter(|_| transport == "TCP") {
+            credentials.extend(protocols::credentials(index, &src, &dst, port, decoded.payload));
+        }
+    }
+
+    let mut protocols: Vec<ProtocolStats> = protocols.into_values().collect();
+    protocols.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+    let mut conversations: Vec<Conversation> = conversations.into_values().collect();
+    conversations.sort_by_key(|c| std::cmp::Reverse(c.bytes_a_to_b + c.bytes_b_to_a));
+    CaptureSummary {
+        id: id.to_string(),
+        path: path.to_string_lossy().to_string(),
+        packets: packets.len(),
+        bytes: packets.iter().map(|p| u64::from(p.length)).sum(),
+        started_at: (!packets.is_empty()).then_some(start / 1000),
+        duration: seconds(end - start),
+        protocols,
+        conversations,
+        http_requests,
+        dns_queries,
+        credentials,
+    }
+}
+
+/// Load a capture file and summarize it; the summary's id names the
+/// capture for the paging and detail calls
+pub fn open(path: &Path) -> Result<CaptureSummary, String> {
+    let packets = read_packets(path)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let summary = build_summary(&id, path, &packets);
+    log::info!("Opened capture {} with {} packets", path.display(), packets.len());
+    CAPTURES.lock().unwrap().insert(id, Arc::new(Capture { packets, summary: summary.clone() }));
+    Ok(summary)
+}
+
+fn get(capture_id: &str) -> Result<Arc<Capture>, String> {
+    CAPTURES
+        .lock()
+        .unwrap()
+        .get(capture_id)
+        .cloned()
+        .ok_or_else(|| format!("Capture {} is not open", capture_id))
+}
+
+pub fn summary(capture_id: &str) -> Result<CaptureSummary, String> {
+    Ok(get(capture_id)?.summary.clone())
+}
+
+/// A page of the packet list, optionally only packets of one protocol
+pub fn packets(capture_id: &str, offset: usize, limit: Option<usize>, protocol: Option<&str>) -> Result<PacketPage, String> {
+    let capture = get(capture_id)?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let start = capture.packets.iter().map(|p| p.timestamp).min().unwrap_or(0);
+    let summaries = capture
+        .packets
+        .iter()
+        .enumerate()
+        .map(|(index, packet)| (index, packet, decode(packet.linktype, &packet.data)))
+        .filter(|(_, _, decoded)| protocol.map_or(true, |p| decoded.protocol().eq_ignore_ascii_case(p)));
+    let mut total = 0;
+    let mut page = Vec::new();
+    for (index, packet, decoded) in summaries {
+        if total >= offset && page.len() < limit {
+            page.push(summarize(index, packet, &decoded, start));
+        }
+        total += 1;
+    }
+    Ok(PacketPage { total, offset, packets: page })
+}
+
+/// Every decoded layer of one packet, with a hex dump of its bytes
+pub fn packet(capture_id: &str, index: usize) -> Result<PacketDetail, String> {
+    let capture = get(capture_id)?;
+    let packet = capture.packets.get(index).ok_or_else(|| format!("No packet {}", index))?;
+    let start = capture.packets.iter().map(|p| p.timestamp).min().unwrap_or(0);
+    let decoded = decode(packet.linktype, &packet.data);
+    Ok(PacketDetail {
+        summary: summarize(index, packet, &decoded, start),
+        layers: decoded.layers.clone(),
+        hex: hex_dump(&packet.data),
+    })
+}
+
+pub fn close(capture_id: &str) -> bool {
+    CAPTURES.lock().unwrap().remove(capture_id).is_some()
+}
+
+fn hex_dump(data: &[u8]) -> String {
+    data.chunks(16)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+            format!("{:08x}  {:<47}  {}", line * 16, hex.join(" "), ascii)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,208 @@
+//! Application data pulled from single packets: HTTP requests, DNS queries
+//! and credentials sent in cleartext. Streams are not reassembled, so a
+//! request split across segments is only seen up to its first segment.
+
+use base64::Engine;
+use serde::Serialize;
+
+use super::decode::looks_like_http;
+
+/// Form fields that carry a password
+const PASSWORD_FIELDS: &[&str] = &["password", "passwd", "pass", "pwd", "secret"];
+
+/// Form fields that carry a user name
+const USER_FIELDS: &[&str] = &["username", "user", "login", "email", "uname"];
+
+/// Longest DNS name followed before giving up on a malformed packet
+const MAX_DNS_LABELS: usize = 64;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpRequest {
+    pub packet: usize,
+    pub client: String,
+    pub server: String,
+    pub method: String,
+    pub host: Option<String>,
+    pub path: String,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsQuery {
+    pub packet: usize,
+    pub client: String,
+    pub server: String,
+    pub name: String,
+    /// A, AAAA, TXT, ...
+    pub record_type: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleartextCredential {
+    pub packet: usize,
+    /// HTTP Basic, HTTP form, FTP, POP3, IMAP or SMTP
+    pub protocol: String,
+    pub client: String,
+    pub server: String,
+    pub username: Option<String>,
+    pub secret: Option<String>,
+}
+
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// The request line and headers of an HTTP request, and its body
+fn split_request(payload: &[u8]) -> Option<(String, String)> {
+    if !looks_like_http(payload) || payload.starts_with(b"HTTP/") {
+        return None;
+    }
+    let text = String::from_utf8_lossy(payload);
+    let (head, body) = text.split_once("\r\n\r\n").unwrap_or((&text, ""));
+    Some((head.to_string(), body.to_string()))
+}
+
+pub fn http_request(packet: usize, client: &str, server: &str, payload: &[u8]) -> Option<HttpRequest> {
+    let (head, _) = split_request(payload)?;
+    let mut request_line = head.lines().next()?.split_whitespace();
+    Some(HttpRequest {
+        packet,
+        client: client.to_string(),
+        server: server.to_string(),
+        method: request_line.next()?.to_string(),
+        path: request_line.next().unwrap_or("/").to_string(),
+        host: header(&head, "Host").map(String::from),
+        user_agent: header(&head, "User-Agent").map(String::from),
+    })
+}
+
+fn decode_base64(value: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(value.trim()).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Credentials a client sent in one packet to `server_port`
+pub fn credentials(packet: usize, client: &str, server: &str, server_port: u16, payload: &[u8]) -> Vec<CleartextCredential> {
+    let credential = |protocol: &str, username: Option<String>, secret: Option<String>| CleartextCredential {
+        packet,
+        protocol: protocol.to_string(),
+        client: client.to_string(),
+        server: server.to_string(),
+        username,
+        secret,
+    };
+    let mut found = Vec::new();
+
+    if let Some((head, body)) = split_request(payload) {
+        let basic = header(&head, "Authorization")
+            .and_then(|value| value.strip_prefix("Basic "))
+            .and_then(decode_base64);
+        if let Some(decoded) = basic {
+            let (user, password) = decoded.split_once(':').unwrap_or((&decoded, ""));
+            found.push(credential("HTTP Basic", Some(user.to_string()), Some(password.to_string())));
+        }
+        let form = header(&head, "Content-Type").is_some_and(|t| t.starts_with("application/x-www-form-urlencoded"));
+        if form {
+            let fields: Vec<(String, String)> = body
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (k.to_ascii_lowercase(), urlencoding::decode(&v.replace('+', " ")).map(|v| v.into_owned()).unwrap_or_default()))
+                .collect();
+            let field = |names: &[&str]| fields.iter().find(|(k, _)| names.contains(&k.as_str())).map(|(_, v)| v.clone());
+            if let Some(password) = field(PASSWORD_FIELDS) {
+                found.push(credential("HTTP form", field(USER_FIELDS), Some(password)));
+            }
+        }
+        return found;
+    }
+
+    let text = String::from_utf8_lossy(payload);
+    for line in text.lines() {
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim().to_string();
+        match (server_port, command.to_ascii_uppercase().as_str()) {
+            (21, "USER") => found.push(credential("FTP", Some(argument), None)),
+            (21, "PASS") => found.push(credential("FTP", None, Some(argument))),
+            (110, "USER") => found.push(credential("POP3", Some(argument), None)),
+            (110, "PASS") => found.push(credential("POP3", None, Some(argument))),
+            (25 | 587, "AUTH") => {
+                // AUTH PLAIN carries "\0user\0password" inline
+                let plain = argument.strip_prefix("PLAIN ").and_then(decode_base64);
+                if let Some(plain) = plain {
+                    let mut parts = plain.split('\0').skip(1);
+                    found.push(credential("SMTP", parts.next().map(String::from), parts.next().map(String::from)));
+                }
+            }
+            // IMAP commands are tagged: "a1 LOGIN user password"
+            (143, _) => {
+                let mut words = argument.split_whitespace();
+                if words.next().is_some_and(|w| w.eq_ignore_ascii_case("LOGIN")) {
+                    let unquote = |w: &str| w.trim_matches('"').to_string();
+                    found.push(credential("IMAP", words.next().map(unquote), words.next().map(unquote)));
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+fn dns_record_type(code: u16) -> String {
+    match code {
+        1 => "A".into(),
+        2 => "NS".into(),
+        5 => "CNAME".into(),
+        6 => "SOA".into(),
+        12 => "PTR".into(),
+        15 => "MX".into(),
+        16 => "TXT".into(),
+        28 => "AAAA".into(),
+        33 => "SRV".into(),
+        65 => "HTTPS".into(),
+        255 => "ANY".into(),
+        other => format!("TYPE{}", other),
+    }
+}
+
+/// The questions of a DNS query; responses are skipped
+pub fn dns_queries(packet: usize, client: &str, server: &str, payload: &[u8]) -> Vec<DnsQuery> {
+    if payload.len() < 12 || payload[2] & 0x80 != 0 {
+        return Vec::new();
+    }
+    let questions = u16::from_be_bytes([payload[4], payload[5]]);
+    let mut offset = 12;
+    let mut queries = Vec::new();
+    for _ in 0..questions {
+        let mut labels = Vec::new();
+        loop {
+            let Some(&len) = payload.get(offset) else {
+                return queries;
+            };
+            offset += 1;
+            // Questions of a query are not compressed
+            if len == 0 || len & 0xc0 != 0 || labels.len() >= MAX_DNS_LABELS {
+                break;
+            }
+            let Some(label) = payload.get(offset..offset + usize::from(len)) else {
+                return queries;
+            };
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            offset += usize::from(len);
+        }
+        let Some(kind) = payload.get(offset..offset + 2) else {
+            return queries;
+        };
+        offset += 4;
+        queries.push(DnsQuery {
+            packet,
+            client: client.to_string(),
+            server: server.to_string(),
+            name: labels.join("."),
+            record_type: dns_record_type(u16::from_be_bytes([kind[0], kind[1]])),
+        });
+    }
+    queries
+}
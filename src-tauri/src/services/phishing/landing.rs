@@ -0,0 +1,139 @@
+//! Landing page scaffolds for phishing simulations
+//!
+//! Generates a static fake sign-in page and the awareness page it leads to.
+//! The sign-in form never sends what is typed: submitting only reports the
+//! recipient's token (and whether a password was entered) to the tracking
+//! listener, then shows the awareness page.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::html_escape;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LandingOptions {
+    /// Folder created under `<workspace>/phishing/`
+    pub name: String,
+    /// Organization the page pretends to be
+    pub brand: String,
+    /// Listener that receives `submit` beacons, as "host:port"
+    #[serde(default)]
+    pub tracking: Option<String>,
+    /// Shown on the awareness page
+    #[serde(default)]
+    pub awareness_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LandingScaffold {
+    pub dir: String,
+    pub files: Vec<String>,
+    /// Serves the page for the lab
+    pub run_command: String,
+}
+
+const DEFAULT_AWARENESS_MESSAGE: &str = "This was a simulated phishing exercise. Nothing you typed was sent or stored. \
+Before signing in, check the address bar, be wary of urgent requests, and report suspicious emails.";
+
+const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>{{brand}} - Sign in</title>
+  <style>
+    body { font-family: system-ui, sans-serif; background: #f3f4f6; display: flex; align-items: center; justify-content: center; height: 100vh; margin: 0; }
+    form { background: #fff; padding: 2rem; border-radius: 8px; box-shadow: 0 2px 10px rgba(0,0,0,.1); width: 320px; }
+    h1 { font-size: 1.3rem; margin-top: 0; }
+    input { width: 100%; padding: .6rem; margin: .4rem 0 1rem; box-sizing: border-box; }
+    button { width: 100%; padding: .7rem; background: #2563eb; color: #fff; border: 0; border-radius: 4px; }
+  </style>
+</head>
+<body>
+  <!-- Phishing awareness exercise: the form below never transmits its fields -->
+  <form id="login">
+    <h1>{{brand}}</h1>
+    <label>Email<input type="email" autocomplete="off"></label>
+    <label>Password<input type="password" id="password" autocomplete="off"></label>
+    <button type="submit">Sign in</button>
+  </form>
+  <script>
+    const tracking = "{{tracking}}";
+    const rid = new URLSearchParams(location.search).get("rid") || "unknown";
+    if (tracking) new Image().src = "http://" + tracking + "/t/landing/" + encodeURIComponent(rid) + ".gif?event=visit";
+    document.getElementById("login").addEventListener("submit", (e) => {
+      e.preventDefault();
+      const typed = document.getElementById("password").value.length > 0;
+      const done = () => location.href = "awareness.html?rid=" + encodeURIComponent(rid);
+      if (!tracking) return done();
+      const beacon = new Image();
+      beacon.onload = beacon.onerror = done;
+      beacon.src = "http://" + tracking + "/t/landing/" + encodeURIComponent(rid) + ".gif?event=submit&password_entered=" + typed;
+      setTimeout(done, 1500);
+    });
+  </script>
+</body>
+</html>
+"#;
+
+const AWARENESS_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Phishing exercise</title>
+  <style>
+    body { font-family: system-ui, sans-serif; max-width: 640px; margin: 4rem auto; line-height: 1.5; padding: 0 1rem; }
+    .banner { background: #fef3c7; border-left: 4px solid #d97706; padding: 1rem 1.5rem; }
+  </style>
+</head>
+<body>
+  <div class="banner">
+    <h1>This was a phishing test</h1>
+    <p>{{message}}</p>
+  </div>
+</body>
+</html>
+"#;
+
+/// Write the landing page into `<workspace_root>/phishing/<name>`
+pub fn scaffold(workspace_root: &str, options: &LandingOptions) -> Result<LandingScaffold, String> {
+    let valid_name = !options.name.is_empty()
+        && options
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Err("Page name may only contain letters, digits, '-' and '_'".to_string());
+    }
+    let tracking = options.tracking.as_deref().unwrap_or_default().trim();
+    let valid_tracking = tracking
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '-' | '[' | ']'));
+    if !valid_tracking {
+        return Err("Tracking listener must be given as host:port".to_string());
+    }
+
+    let dir = Path::new(workspace_root).join("phishing").join(&options.name);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let message = options.awareness_message.as_deref().filter(|m| !m.trim().is_empty()).unwrap_or(DEFAULT_AWARENESS_MESSAGE);
+    let pages = [
+        ("index.html", INDEX_HTML.replace("{{brand}}", &html_escape(&options.brand)).replace("{{tracking}}", tracking)),
+        ("awareness.html", AWARENESS_HTML.replace("{{message}}", &html_escape(message))),
+    ];
+    let mut files = Vec::new();
+    for (name, content) in pages {
+        let path = dir.join(name);
+        std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        files.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(LandingScaffold {
+        dir: dir.to_string_lossy().to_string(),
+        files,
+        run_command: format!("python3 -m http.server 8080 --directory {}", dir.display()),
+    })
+}
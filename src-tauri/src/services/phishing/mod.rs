@@ -0,0 +1,349 @@
+//! Phishing simulation for awareness labs
+//!
+//! Renders templated emails for a list of recipients and sends them through
+//! a lab SMTP server. Each recipient gets a token, which goes into a
+//! tracking pixel URL and into the landing page link; the tracking endpoint
+//! in `tracker` answers both, so opens and clicks can be tied back to them. The SMTP server and
+//! every recipient's domain must be on the lab target allowlist. The SMTP
+//! settings live in ~/.ctr/phishing/smtp.json, its password in the keychain,
+//! and sent campaigns in ~/.ctr/phishing/campaigns.json.
+
+pub mod landing;
+pub mod tracker;
+
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::services::audit;
+use crate::services::{listeners, payload_verifier, secrets};
+use crate::utils::blocking;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Recipients per campaign; this is for labs, not mailing lists
+const MAX_RECIPIENTS: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Plaintext, as lab mail catchers usually expect
+    #[default]
+    None,
+    StartTls,
+    /// TLS from the first byte (SMTPS)
+    Tls,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// Login name; the password is kept in the keychain as
+    /// `phishing/smtp_password`
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Sender, like "IT Support <it@lab.local>"
+    pub from: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailTemplate {
+    pub subject: String,
+    pub html_body: String,
+    /// Plain-text alternative; without one only the HTML part is sent
+    #[serde(default)]
+    pub text_body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipient {
+    pub email: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Extra template variables for this recipient
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+/// Where tracking pixel requests go: the tracking endpoint on this host/port
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackingOptions {
+    /// Defaults to this machine's address
+    #[serde(default)]
+    pub host: Option<String>,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CampaignRequest {
+    pub name: String,
+    pub template: EmailTemplate,
+    pub recipients: Vec<Recipient>,
+    #[serde(default)]
+    pub tracking: Option<TrackingOptions>,
+    /// Landing page; each recipient's link gets their token as `rid`
+    #[serde(default)]
+    pub landing_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedEmail {
+    pub to: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: Option<String>,
+    pub token: String,
+    pub tracking_url: Option<String>,
+    pub landing_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentEmail {
+    pub email: String,
+    pub token: String,
+    pub tracking_url: Option<String>,
+    pub landing_url: Option<String>,
+    pub sent: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Campaign {
+    pub id: String,
+    pub name: String,
+    pub subject: String,
+    pub smtp_host: String,
+    /// Unix timestamp in milliseconds
+    pub sent_at: u64,
+    pub emails: Vec<SentEmail>,
+}
+
+fn phishing_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("phishing");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create phishing directory: {}", e))?;
+    Ok(dir)
+}
+
+pub fn smtp_config() -> Option<SmtpConfig> {
+    load_json(&phishing_dir().ok()?.join("smtp.json"))
+}
+
+/// Save the SMTP settings; the server must be an allowlisted lab target
+pub fn set_smtp_config(config: &SmtpConfig, password: Option<&str>) -> Result<(), String> {
+    if !payload_verifier::is_lab_target(&config.host, Some(config.port)) {
+        return Err(format!("{} is not in the lab target allowlist", config.host));
+    }
+    config.from.parse::<Mailbox>().map_err(|e| format!("Invalid sender address: {}", e))?;
+    if let Some(password) = password {
+        secrets::set("phishing", "smtp_password", password)?;
+    }
+    save_json(&phishing_dir()?.join("smtp.json"), config)
+}
+
+pub fn campaigns() -> Vec<Campaign> {
+    match phishing_dir() {
+        Ok(dir) => load_json(&dir.join("campaigns.json")),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_campaign(campaign: &Campaign) -> Result<(), String> {
+    let path = phishing_dir()?.join("campaigns.json");
+    let mut all: Vec<Campaign> = load_json(&path);
+    all.push(campaign.clone());
+    save_json(&path, &all)
+}
+
+/// Parse the recipient once, so the address checked against the allowlist
+/// is the one the message is sent to
+fn check_recipient(recipient: &Recipient) -> Result<Mailbox, String> {
+    let mailbox: Mailbox = recipient
+        .email
+        .parse()
+        .map_err(|e| format!("Invalid recipient address {}: {}", recipient.email, e))?;
+    let domain = mailbox.email.domain();
+    if !payload_verifier::is_lab_target(domain, None) {
+        return Err(format!("Recipient domain {} is not in the lab target allowlist", domain));
+    }
+    Ok(mailbox)
+}
+
+fn html_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+
+/// Replace `{{name}}` placeholders; unknown ones are left as they are
+fn fill(text: &str, variables: &HashMap<String, String>, escape: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = after[..end].trim();
+        match variables.get(key) {
+            // The pixel is markup by design
+            Some(value) if escape && key != "tracking_pixel" => out.push_str(&html_escape(value)),
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn tracking_url(tracking: &TrackingOptions, campaign_id: &str, token: &str) -> String {
+    let host = tracking.host.clone().filter(|h| !h.is_empty()).unwrap_or_else(listeners::local_ip);
+    format!("http://{}:{}/t/{}/{}.gif", host, tracking.port, campaign_id, token)
+}
+
+fn landing_link(landing_url: &str, token: &str) -> Result<String, String> {
+    let mut url = reqwest::Url::parse(landing_url).map_err(|e| format!("Invalid landing page URL: {}", e))?;
+    url.query_pairs_mut().append_pair("rid", token);
+    Ok(url.to_string())
+}
+
+/// Fill in the template for one recipient. Variables: `email`, `name`,
+/// `first_name`, `campaign`, `token`, `tracking_url`, `tracking_pixel`,
+/// `landing_url`, plus the recipient's own.
+pub fn render(request: &CampaignRequest, campaign_id: &str, recipient: &Recipient, token: &str) -> Result<RenderedEmail, String> {
+    let tracking = request.tracking.as_ref().map(|t| tracking_url(t, campaign_id, token));
+    let landing = request.landing_url.as_deref().filter(|u| !u.is_empty()).map(|u| landing_link(u, token)).transpose()?;
+
+    let mut variables = recipient.variables.clone();
+    let name = recipient.name.clone().unwrap_or_else(|| recipient.email.split('@').next().unwrap_or_default().to_string());
+    variables.insert("first_name".into(), name.split_whitespace().next().unwrap_or_default().to_string());
+    variables.insert("name".into(), name);
+    variables.insert("email".into(), recipient.email.clone());
+    variables.insert("campaign".into(), request.name.clone());
+    variables.insert("token".into(), token.to_string());
+    variables.insert("tracking_url".into(), tracking.clone().unwrap_or_default());
+    variables.insert(
+        "tracking_pixel".into(),
+        tracking.as_ref().map(|u| format!(r#"<img src="{}" width="1" height="1" alt="" style="display:none">"#, u)).unwrap_or_default(),
+    );
+    variables.insert("landing_url".into(), landing.clone().unwrap_or_default());
+
+    let mut html_body = fill(&request.template.html_body, &variables, true);
+    // Without a placeholder the pixel still goes at the end of the body
+    if let Some(pixel) = variables.get("tracking_pixel").filter(|p| !p.is_empty() && !request.template.html_body.contains("tracking_pixel")) {
+        html_body.push_str(pixel);
+    }
+    Ok(RenderedEmail {
+        to: recipient.email.clone(),
+        subject: fill(&request.template.subject, &variables, false),
+        html_body,
+        text_body: request.template.text_body.as_deref().filter(|t| !t.is_empty()).map(|t| fill(t, &variables, false)),
+        token: token.to_string(),
+        tracking_url: tracking,
+        landing_url: landing,
+    })
+}
+
+fn transport(config: &SmtpConfig, password: Option<String>) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let tls = || {
+        TlsParameters::builder(config.host.clone())
+            .dangerous_accept_invalid_certs(true)
+            .build()
+            .map_err(|e| format!("Failed to set up TLS: {}", e))
+    };
+    let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+        .port(config.port)
+        .timeout(Some(SMTP_TIMEOUT))
+        .tls(match config.security {
+            SmtpSecurity::None => Tls::None,
+            SmtpSecurity::StartTls => Tls::Required(tls()?),
+            SmtpSecurity::Tls => Tls::Wrapper(tls()?),
+        });
+    if let Some(username) = config.username.as_ref().filter(|u| !u.is_empty()) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.unwrap_or_default()));
+    }
+    Ok(builder.build())
+}
+
+fn message(from: &Mailbox, to: Mailbox, email: &RenderedEmail) -> Result<Message, String> {
+    let html = SinglePart::builder().header(ContentType::TEXT_HTML).body(email.html_body.clone());
+    let builder = Message::builder().from(from.clone()).to(to).subject(&email.subject);
+    let built = match &email.text_body {
+        Some(text) => builder.multipart(MultiPart::alternative().singlepart(SinglePart::plain(text.clone())).singlepart(html)),
+        None => builder.singlepart(html),
+    };
+    built.map_err(|e| format!("Failed to build message: {}", e))
+}
+
+/// Render the campaign for its first recipient without sending anything
+pub fn preview(request: &CampaignRequest) -> Result<RenderedEmail, String> {
+    let recipient = request.recipients.first().ok_or("No recipients")?;
+    render(request, "preview", recipient, "preview")
+}
+
+/// Send the campaign through the configured lab SMTP server. Recipients
+/// outside the allowlist fail the whole campaign before anything is sent.
+pub async fn send(request: CampaignRequest) -> Result<Campaign, String> {
+    let config = smtp_config().ok_or("No SMTP server configured")?;
+    if !payload_verifier::is_lab_target(&config.host, Some(config.port)) {
+        return Err(format!("{} is not in the lab target allowlist", config.host));
+    }
+    if request.recipients.is_empty() {
+        return Err("No recipients".into());
+    }
+    if request.recipients.len() > MAX_RECIPIENTS {
+        return Err(format!("At most {} recipients per campaign", MAX_RECIPIENTS));
+    }
+    let mailboxes = request.recipients.iter().map(check_recipient).collect::<Result<Vec<_>, _>>()?;
+
+    let from: Mailbox = config.from.parse().map_err(|e| format!("Invalid sender address: {}", e))?;
+    let password = blocking::run(None, |_| secrets::get("phishing", "smtp_password")).await?;
+    let transport = transport(&config, password)?;
+    let campaign_id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+    audit::record(
+        "phishing",
+        Some(&campaign_id),
+        &format!("send campaign {} via {}:{} to {} recipients", request.name, config.host, config.port, request.recipients.len()),
+        None,
+    );
+
+    let mut emails = Vec::new();
+    for (recipient, mailbox) in request.recipients.iter().zip(mailboxes) {
+        let token = uuid::Uuid::new_v4().simple().to_string()[..16].to_string();
+        let rendered = render(&request, &campaign_id, recipient, &token)?;
+        let result = match message(&from, mailbox, &rendered) {
+            Ok(message) => transport.send(message).await.map(|_| ()).map_err(|e| format!("SMTP error: {}", e)),
+            Err(e) => Err(e),
+        };
+        audit::record("phishing", Some(&campaign_id), &format!("to {}: {}", recipient.email, result.as_ref().err().map_or("sent", |e| e.as_str())), None);
+        emails.push(SentEmail {
+            email: recipient.email.clone(),
+            token,
+            tracking_url: rendered.tracking_url,
+            landing_url: rendered.landing_url,
+            sent: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    let campaign = Campaign {
+        id: campaign_id,
+        name: request.name,
+        subject: request.template.subject,
+        smtp_host: config.host,
+        sent_at: now_millis(),
+        emails,
+    };
+    save_campaign(&campaign)?;
+    Ok(campaign)
+}
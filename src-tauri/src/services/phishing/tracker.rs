@@ -0,0 +1,294 @@
+//! Tracking endpoint for phishing campaigns
+//!
+//! A small HTTP listener that answers `/t/<campaign>/<token>.gif` with a
+//! transparent pixel and records the hit against the recipient the token was
+//! sent to. Landing pages report to `/t/landing/<token>.gif?event=visit` and
+//! `?event=submit`. Hits with tokens no sent campaign knows are not recorded.
+//! Events are kept in ~/.ctr/phishing/events.json.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use super::{campaigns, phishing_dir};
+use crate::services::processes::{self, ManagedProcess, ProcessKind};
+use crate::utils::fs_utils::{load_json, save_json};
+use crate::utils::time::now_millis;
+
+const PROCESS_ID: &str = "phishing-tracker";
+
+/// Requests are a GET line and a few headers; anything larger is dropped
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Oldest events are dropped past this many
+const MAX_EVENTS: usize = 10_000;
+
+/// 1x1 transparent GIF
+const PIXEL: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0x21,
+    0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44,
+    0x01, 0x00, 0x3b,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackingKind {
+    /// The tracking pixel in the email was loaded
+    Open,
+    /// The landing page was opened
+    Visit,
+    /// The landing page form was submitted
+    Submit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackingEvent {
+    pub campaign_id: String,
+    pub email: String,
+    pub kind: TrackingKind,
+    /// Only for submissions: whether anything was typed as a password
+    pub password_entered: Option<bool>,
+    pub remote_addr: String,
+    pub user_agent: Option<String>,
+    /// Unix timestamp in milliseconds
+    pub at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackerInfo {
+    pub host: String,
+    pub port: u16,
+    pub started_at: u64,
+}
+
+struct RunningTracker {
+    info: TrackerInfo,
+    shutdown: oneshot::Sender<()>,
+}
+
+lazy_static::lazy_static! {
+    static ref TRACKER: Mutex<Option<RunningTracker>> = Mutex::new(None);
+    static ref EVENTS_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// A hit on a tracking URL, before its token is resolved
+struct Hit {
+    /// Campaign id from the path, or "landing"
+    campaign: String,
+    token: String,
+    kind: TrackingKind,
+    password_entered: Option<bool>,
+}
+
+/// Parse a request target like `/t/<campaign>/<token>.gif?event=submit`
+fn parse_target(target: &str) -> Option<Hit> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (campaign, file) = path.strip_prefix("/t/")?.split_once('/')?;
+    let token = file.strip_suffix(".gif")?;
+    let valid = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid(campaign) || !valid(token) {
+        return None;
+    }
+
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+    let kind = match (campaign, param("event")) {
+        ("landing", Some("submit")) => TrackingKind::Submit,
+        ("landing", _) => TrackingKind::Visit,
+        _ => TrackingKind::Open,
+    };
+    let password_entered = (kind == TrackingKind::Submit).then(|| param("password_entered") == Some("true"));
+    Some(Hit {
+        campaign: campaign.to_string(),
+        token: token.to_string(),
+        kind,
+        password_entered,
+    })
+}
+
+/// Campaign id and recipient address the hit's token was sent to
+fn resolve(hit: &Hit) -> Option<(String, String)> {
+    campaigns()
+        .into_iter()
+        .filter(|c| hit.campaign == "landing" || c.id == hit.campaign)
+        .find_map(|c| {
+            c.emails
+                .iter()
+                .find(|e| e.token == hit.token)
+                .map(|e| (c.id.clone(), e.email.clone()))
+        })
+}
+
+fn record(event: &TrackingEvent) -> Result<(), String> {
+    let _guard = EVENTS_LOCK.lock().unwrap();
+    let path = phishing_dir()?.join("events.json");
+    let mut all: Vec<TrackingEvent> = load_json(&path);
+    all.push(event.clone());
+    if all.len() > MAX_EVENTS {
+        all.drain(..all.len() - MAX_EVENTS);
+    }
+    save_json(&path, &all)
+}
+
+/// Recorded events, oldest first, optionally for one campaign
+pub fn events(campaign_id: Option<&str>) -> Vec<TrackingEvent> {
+    let Ok(dir) = phishing_dir() else {
+        return Vec::new();
+    };
+    let _guard = EVENTS_LOCK.lock().unwrap();
+    let all: Vec<TrackingEvent> = load_json(&dir.join("events.json"));
+    all.into_iter()
+        .filter(|e| campaign_id.map_or(true, |id| e.campaign_id == id))
+        .collect()
+}
+
+/// Read the request head; None when it is too large, too slow or not GET
+async fn read_request(stream: &mut TcpStream) -> Option<(String, Option<String>)> {
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut chunk)).await.ok()?.ok()?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            return None;
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    if request_line.next()? != "GET" {
+        return None;
+    }
+    let target = request_line.next()?.to_string();
+    let user_agent = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("user-agent"))
+        .map(|(_, value)| value.trim().to_string());
+    Some((target, user_agent))
+}
+
+async fn handle<F>(mut stream: TcpStream, remote_addr: String, on_event: Arc<F>)
+where
+    F: Fn(&TrackingEvent) + Send + Sync + 'static,
+{
+    let Some((target, user_agent)) = read_request(&mut stream).await else {
+        return;
+    };
+    let hit = parse_target(&target);
+
+    if let Some((hit, (campaign_id, email))) = hit.as_ref().and_then(|h| Some((h, resolve(h)?))) {
+        let event = TrackingEvent {
+            campaign_id,
+            email,
+            kind: hit.kind,
+            password_entered: hit.password_entered,
+            remote_addr,
+            user_agent,
+            at: now_millis(),
+        };
+        match record(&event) {
+            Ok(()) => on_event(&event),
+            Err(e) => log::warn!("Failed to record tracking event: {}", e),
+        }
+    }
+
+    // Well-formed tracking URLs always get the pixel, known token or not
+    let response = match hit {
+        Some(_) => {
+            let mut response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: image/gif\r\nContent-Length: {}\r\nCache-Control: no-store\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n",
+                PIXEL.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(PIXEL);
+            response
+        }
+        None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+    };
+    let _ = stream.write_all(&response).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Start the tracking endpoint on host:port; `on_event` is called for each
+/// recorded open, visit or submission. Only one runs at a time.
+pub async fn start<F>(host: &str, port: u16, on_event: F) -> Result<TrackerInfo, String>
+where
+    F: Fn(&TrackingEvent) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind((host, port))
+        .await
+        .map_err(|e| format!("Failed to listen on {}:{}: {}", host, port, e))?;
+    let port = listener.local_addr().map(|a| a.port()).unwrap_or(port);
+    let info = TrackerInfo {
+        host: host.to_string(),
+        port,
+        started_at: now_millis(),
+    };
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    {
+        let mut tracker = TRACKER.lock().unwrap();
+        if let Some(running) = tracker.as_ref() {
+            return Err(format!(
+                "Tracking endpoint already running on {}:{}",
+                running.info.host, running.info.port
+            ));
+        }
+        *tracker = Some(RunningTracker {
+            info: info.clone(),
+            shutdown: shutdown_tx,
+        });
+    }
+
+    let on_event = Arc::new(on_event);
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, addr)) = accepted {
+                        tokio::spawn(handle(stream, addr.to_string(), on_event.clone()));
+                    }
+                }
+            }
+        }
+    });
+
+    processes::register(
+        ManagedProcess::new(
+            PROCESS_ID,
+            ProcessKind::Listener,
+            &format!("Phishing tracking on {}:{}", info.host, info.port),
+        ),
+        Some(Box::new(|| {
+            let _ = stop();
+        })),
+    );
+    Ok(info)
+}
+
+pub fn stop() -> Result<(), String> {
+    let running = TRACKER
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("Tracking endpoint is not running")?;
+    processes::unregister(PROCESS_ID);
+    let _ = running.shutdown.send(());
+    Ok(())
+}
+
+pub fn status() -> Option<TrackerInfo> {
+    TRACKER.lock().unwrap().as_ref().map(|t| t.info.clone())
+}
@@ -0,0 +1,534 @@
+//! Command permission policy
+//!
+//! Defense in depth for the IPC bridge: every command the frontend invokes
+//! is classified as reading or writing files, executing programs or using
+//! the network, and the user's policy decides per class (or per command)
+//! whether it runs, is asked about in a native dialog first, or is denied.
+//! A shell command whose program is a network tool (nmap, curl, nc, ...)
+//! counts as network use as well as execution. The policy is kept in
+//! ~/.ctr/policy.json and allows everything until configured. Decisions
+//! other than a silent allow go to the application log and, when it is
+//! enabled, to the command audit log with the source "policy".
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::Runtime;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::services::audit;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+
+/// Longest command line shown in a prompt or recorded in the audit log
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// The command that changes the policy; loosening a configured policy is
+/// always confirmed
+const POLICY_COMMAND: &str = "set_command_policy";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandClass {
+    ReadFs,
+    WriteFs,
+    Exec,
+    Network,
+    /// Changes what the IDE trusts, allows, keeps secret or reports
+    SecuritySettings,
+}
+
+/// Ordered from least to most restrictive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    #[default]
+    Allow,
+    Ask,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandPolicy {
+    /// Action per class; unlisted classes are allowed
+    #[serde(default)]
+    pub classes: BTreeMap<CommandClass, PolicyAction>,
+    /// Per-command overrides, taking precedence over the classes
+    #[serde(default)]
+    pub commands: BTreeMap<String, PolicyAction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClassifiedCommand {
+    pub command: String,
+    pub classes: Vec<CommandClass>,
+}
+
+use CommandClass::{Exec, Network, ReadFs, SecuritySettings, WriteFs};
+
+/// Commands with effects the policy governs. Every registered command is
+/// either here or in `UNRESTRICTED`, which a test checks against lib.rs.
+const CLASSES: &[(&str, &[CommandClass])] = &[
+    // Files
+    ("read_file", &[ReadFs]),
+    ("list_directory", &[ReadFs]),
+    ("search_in_files", &[ReadFs]),
+    ("hash_file", &[ReadFs]),
+    ("load_ctf_pack", &[ReadFs]),
+    ("import_semgrep_rules", &[ReadFs]),
+    ("import_triage", &[ReadFs]),
+    ("import_user_payloads", &[ReadFs]),
+    ("import_nmap_scan", &[ReadFs]),
+    ("open_capture", &[ReadFs]),
+    ("analyze_logs", &[ReadFs]),
+    ("get_binary_info", &[ReadFs]),
+    ("get_binary_strings", &[ReadFs]),
+    ("get_binary_indicators", &[ReadFs]),
+    ("import_yara_rule_file", &[ReadFs]),
+    ("run_yara_scan", &[ReadFs]),
+    ("import_openapi_spec", &[ReadFs]),
+    ("import_postman_collection", &[ReadFs]),
+    ("detect_language", &[ReadFs]),
+    ("git_status", &[ReadFs]),
+    ("git_file_statuses", &[ReadFs]),
+    ("git_list_branches", &[ReadFs]),
+    ("git_signing_config", &[ReadFs]),
+    ("git_rebase_plan", &[ReadFs]),
+    ("git_rebase_status", &[ReadFs]),
+    ("git_get_conflict_versions", &[ReadFs]),
+    ("git_list_submodules", &[ReadFs]),
+    ("git_list_worktrees", &[ReadFs]),
+    ("scan_file_for_issues", &[ReadFs]),
+    ("run_security_scan", &[ReadFs]),
+    ("start_background_scan", &[ReadFs]),
+    ("list_yara_rule_files", &[ReadFs]),
+    ("reload_rules", &[ReadFs]),
+    ("get_todo_index", &[ReadFs]),
+    ("start_todo_watch", &[ReadFs]),
+    ("prove_exploitability", &[ReadFs]),
+    ("index_workspace", &[ReadFs]),
+    ("analyze_cross_file", &[ReadFs]),
+    ("detect_exploit_chains", &[ReadFs]),
+    ("watch_file", &[ReadFs]),
+    ("list_dependencies", &[ReadFs]),
+    ("write_file", &[WriteFs]),
+    ("create_file", &[WriteFs]),
+    ("delete_file", &[WriteFs]),
+    ("create_directory", &[WriteFs]),
+    ("rename_file", &[WriteFs]),
+    ("set_permissions", &[WriteFs]),
+    ("create_from_template", &[WriteFs]),
+    ("batch_operation", &[WriteFs]),
+    ("replace_in_files", &[ReadFs, WriteFs]),
+    ("scaffold_vulnerable_app", &[WriteFs]),
+    ("scaffold_phishing_landing", &[WriteFs]),
+    ("git_write_merge_resolution", &[WriteFs]),
+    ("git_init", &[WriteFs]),
+    ("git_add_worktree", &[WriteFs]),
+    ("git_remove_worktree", &[WriteFs]),
+    ("export_audit_log", &[WriteFs]),
+    ("export_triage", &[WriteFs]),
+    ("export_report", &[WriteFs]),
+    ("export_leaderboard", &[WriteFs]),
+    ("export_user_payloads", &[WriteFs]),
+    ("export_extension_profile", &[WriteFs]),
+    ("export_diagnostics", &[WriteFs]),
+    ("export_postman_collection", &[WriteFs]),
+    ("save_yara_rule_file", &[WriteFs]),
+    ("delete_yara_rule_file", &[WriteFs]),
+    ("uninstall_extension", &[WriteFs]),
+    ("git_add", &[WriteFs]),
+    ("git_create_branch", &[WriteFs]),
+    ("git_checkout_branch", &[WriteFs]),
+    // Programs
+    ("execute_command", &[Exec]),
+    ("create_terminal_session", &[Exec]),
+    ("write_to_terminal", &[Exec]),
+    ("run_code_file", &[Exec]),
+    ("run_code_snippet", &[Exec]),
+    ("start_interactive_process", &[Exec]),
+    ("send_process_input", &[Exec]),
+    ("start_lab_environment", &[Exec]),
+    ("stop_lab_environment", &[Exec]),
+    // CTF challenge targets are lab environments
+    ("start_ctf_challenge", &[Exec]),
+    ("start_extension_host", &[Exec]),
+    ("activate_hosted_extension", &[Exec]),
+    ("execute_extension_command", &[Exec]),
+    ("start_crack", &[Exec]),
+    ("detect_crack_tools", &[Exec]),
+    ("check_language_available", &[Exec]),
+    ("list_wsl_distros", &[Exec]),
+    ("list_docker_containers", &[Exec]),
+    // Signing runs gpg or ssh-keygen; verifying signatures runs them too
+    ("git_commit", &[WriteFs, Exec]),
+    ("git_log", &[ReadFs, Exec]),
+    ("git_rebase_start", &[WriteFs, Exec]),
+    ("git_rebase_continue", &[WriteFs, Exec]),
+    ("git_rebase_skip", &[WriteFs, Exec]),
+    ("git_rebase_abort", &[WriteFs, Exec]),
+    ("verify_payload", &[Exec, Network]),
+    ("scan_container_image", &[ReadFs, Exec, Network]),
+    ("run_schedule_now", &[ReadFs, Exec, Network]),
+    // Network
+    ("send_http_request", &[Network]),
+    ("proxy_send_to_repeater", &[Network]),
+    ("start_proxy", &[Network]),
+    ("start_fuzz", &[Network]),
+    ("start_spray", &[ReadFs, Network]),
+    ("send_phishing_campaign", &[Network]),
+    ("ws_connect", &[Network]),
+    ("ws_send", &[Network]),
+    ("dns_lookup", &[Network]),
+    ("dns_reverse_lookup", &[Network]),
+    ("dns_zone_transfer", &[Network]),
+    ("dns_enumerate_subdomains", &[Network]),
+    ("start_listener", &[Network]),
+    ("start_phishing_tracker", &[Network]),
+    ("upgrade_callback_shell", &[Network]),
+    ("run_exploit_simulation", &[Network]),
+    ("run_exploit_with_custom_payload", &[Network]),
+    ("search_cves", &[Network]),
+    ("get_cve_details", &[Network]),
+    ("search_github_advisories", &[Network]),
+    ("search_exploit_db", &[Network]),
+    ("scan_dependencies", &[ReadFs, Network]),
+    ("check_dependency_licenses", &[ReadFs, Network]),
+    ("add_report_licenses", &[ReadFs, Network]),
+    ("vt_lookup_hash", &[Network]),
+    ("vt_lookup_url", &[Network]),
+    ("fetch_marketplace", &[Network]),
+    ("search_marketplace", &[Network]),
+    ("get_extension_details", &[Network]),
+    ("check_updates", &[Network]),
+    ("install_from_marketplace", &[WriteFs, Network]),
+    ("apply_extension_profile", &[ReadFs, WriteFs, Network]),
+    ("list_versions", &[Network]),
+    ("fetch_juice_shop_challenges", &[Network]),
+    ("poll_juice_shop_progress", &[Network]),
+    ("start_juice_shop_watch", &[Network]),
+    ("ai_code_completion", &[Network]),
+    ("ai_triage_findings", &[Network]),
+    ("ai_download_model", &[WriteFs, Network]),
+    ("ai_register_model", &[ReadFs, Network]),
+    ("git_clone", &[WriteFs, Network]),
+    ("git_push", &[Network]),
+    ("git_pull", &[WriteFs, Network]),
+    ("git_add_submodule", &[WriteFs, Network]),
+    ("git_submodule_init_update", &[WriteFs, Network]),
+    // Security settings
+    ("set_command_policy", &[SecuritySettings]),
+    ("set_lab_targets", &[SecuritySettings]),
+    ("set_audit_enabled", &[SecuritySettings]),
+    ("set_workspace_trust", &[SecuritySettings]),
+    ("forget_workspace_trust", &[SecuritySettings]),
+    ("set_secret", &[SecuritySettings]),
+    ("delete_secret", &[SecuritySettings]),
+    ("set_smtp_config", &[SecuritySettings]),
+    ("set_intel_config", &[SecuritySettings]),
+    ("set_proxy_rules", &[SecuritySettings]),
+    ("set_setting", &[SecuritySettings]),
+    ("reset_setting", &[SecuritySettings]),
+    ("add_workspace_root", &[SecuritySettings]),
+    ("enable_extension", &[SecuritySettings]),
+    ("disable_extension", &[SecuritySettings]),
+    ("pin_extension", &[SecuritySettings]),
+    ("unpin_extension", &[SecuritySettings]),
+    ("mark_false_positive", &[SecuritySettings]),
+    ("unmark_false_positive", &[SecuritySettings]),
+    ("set_finding_status", &[SecuritySettings]),
+    // Scheduled tasks run scans and programs unattended
+    ("create_schedule", &[SecuritySettings]),
+    ("update_schedule", &[SecuritySettings]),
+    ("set_schedule_enabled", &[SecuritySettings]),
+    ("delete_schedule", &[SecuritySettings]),
+];
+
+/// Commands the policy never restricts: queries, pure computation,
+/// in-memory state such as jobs, sessions and watchers, and the app's own
+/// records (drafts, profiles, reports, progress) under ~/.ctr or a
+/// workspace's .ctr directory
+#[cfg(test)]
+const UNRESTRICTED: &[&str] = &[
+    // Queries and pure computation
+    "get_home_directory", "list_drafts", "list_file_templates", "list_terminal_sessions", "list_shell_profiles",
+    "get_shell_info", "get_current_directory", "get_supported_languages", "list_interactive_processes",
+    "ai_chat", "ai_list_model_catalog", "ai_list_local_models", "ai_code_explain", "lsp_initialize",
+    "lsp_completion", "lsp_hover", "get_workspace_findings", "get_exploit_payloads", "encode_exploit_payload",
+    "get_reverse_shell_payloads", "get_payload_verifications", "get_lab_targets", "list_installed_extensions",
+    "verify_extension_integrity", "list_available_themes", "get_theme_tokens", "list_extension_languages",
+    "get_language_support", "get_grammar_by_scope", "quick_scan_sinks", "list_false_positives",
+    "prover_cache_stats", "get_audit_enabled", "query_audit_log", "list_http_collections", "get_proxy_status",
+    "get_proxy_history", "get_proxy_rules", "get_proxy_ca_cert_path", "list_ws_connections", "list_ws_profiles",
+    "get_intel_config", "get_recon_hosts", "crypto_transform", "identify_hash", "get_smtp_config",
+    "preview_phishing_email", "list_phishing_campaigns", "get_phishing_tracker", "list_phishing_events",
+    "list_listeners", "list_callbacks", "list_user_payloads", "search_user_payloads", "list_payload_tags",
+    "list_vulnerable_templates", "get_vulnerable_app_solutions", "list_lab_environments", "list_ctf_packs",
+    "get_ctf_progress", "list_ctf_completions", "hash_ctf_flag", "list_progress_events", "get_leaderboard",
+    "get_skill_summaries", "list_reports", "get_report", "preview_report_markdown", "list_evidence",
+    "verify_evidence", "get_settings_schema", "get_setting", "get_all_settings", "load_workspace_session",
+    "list_recent_workspaces", "list_workspace_roots", "get_workspace_trust", "list_workspace_trust",
+    "get_extension_host_status", "list_findings", "get_findings_summary", "get_log_levels", "get_recent_logs",
+    "list_secrets", "has_secret", "list_processes", "get_command_policy", "list_command_classes",
+    "get_capture_summary", "get_capture_packets", "get_capture_packet", "list_schedules",
+    "preview_cron_expression",
+    // Jobs, sessions and watchers
+    "cancel_fs_operation", "read_from_terminal", "close_terminal_session", "resize_terminal",
+    "change_directory", "stop_interactive_process", "ai_cancel_model_download", "git_cancel_clone",
+    "notify_file_saved", "stop_background_scan", "stop_juice_shop_watch", "stop_todo_watch",
+    "update_watched_source", "unwatch_file", "stop_proxy", "clear_proxy_history", "pause_fuzz", "resume_fuzz",
+    "cancel_fuzz", "ws_disconnect", "cancel_crack", "cancel_spray", "stop_phishing_tracker", "stop_listener",
+    "watch_settings", "unwatch_settings", "stop_extension_host", "set_log_level", "send_notification",
+    "stop_process", "close_capture",
+    // The app's own records
+    "save_draft", "recover_draft", "discard_draft", "save_shell_profile", "delete_shell_profile",
+    "ai_delete_model", "reset_juice_shop_progress", "clear_prover_cache", "save_http_request",
+    "delete_http_request", "delete_http_collection", "save_ws_profile", "delete_ws_profile",
+    "save_user_payload", "delete_user_payload", "remove_ctf_pack", "submit_ctf_flag", "reveal_ctf_hint",
+    "record_progress_event", "reset_progress", "create_report", "update_report", "delete_report",
+    "add_report_finding", "update_report_finding", "remove_report_finding", "reorder_report_findings",
+    "attach_evidence_to_finding", "detach_evidence_from_finding", "capture_window_evidence",
+    "capture_region_evidence", "delete_evidence", "save_workspace_session", "clear_workspace_session",
+    "register_workspace", "pin_workspace", "remove_recent_workspace", "remove_workspace_root",
+];
+
+/// Programs that make a shell command network use
+const NETWORK_TOOLS: &[&str] = &[
+    "nmap", "masscan", "rustscan", "curl", "wget", "nc", "ncat", "netcat", "socat", "ssh", "scp", "sftp",
+    "ftp", "telnet", "ping", "traceroute", "dig", "nslookup", "whois", "hydra", "medusa", "sqlmap", "nikto",
+    "gobuster", "ffuf", "dirb", "wfuzz", "feroxbuster", "nuclei", "msfconsole", "smbclient", "crackmapexec",
+    "netexec", "nxc", "enum4linux", "responder", "tcpdump", "tshark", "evil-winrm", "impacket-psexec",
+];
+
+/// Words that run the program after them
+const COMMAND_PREFIXES: &[&str] = &["sudo", "env", "nohup", "time", "exec", "proxychains", "proxychains4"];
+
+lazy_static::lazy_static! {
+    static ref POLICY: RwLock<CommandPolicy> = RwLock::new(load());
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("policy.json"))
+}
+
+fn load() -> CommandPolicy {
+    store_path().map(|path| load_json(&path)).unwrap_or_default()
+}
+
+pub fn get() -> CommandPolicy {
+    POLICY.read().unwrap().clone()
+}
+
+pub fn set(policy: CommandPolicy) -> Result<(), String> {
+    save_json(&store_path()?, &policy)?;
+    log::info!("Command policy updated");
+    *POLICY.write().unwrap() = policy;
+    Ok(())
+}
+
+/// Every command the policy knows a class for
+pub fn classified_commands() -> Vec<ClassifiedCommand> {
+    CLASSES
+        .iter()
+        .map(|(command, classes)| ClassifiedCommand { command: command.to_string(), classes: classes.to_vec() })
+        .collect()
+}
+
+/// Whether a shell command line runs a network tool in any of its pipeline
+/// or list segments
+fn runs_network_tool(command_line: &str) -> bool {
+    command_line
+        .split(['|', ';', '&', '\n', '(', ')', '`'])
+        .filter_map(|segment| {
+            segment
+                .split_whitespace()
+                .find(|word| !COMMAND_PREFIXES.contains(word) && !word.starts_with('-') && !word.contains('='))
+        })
+        .map(|program| program.rsplit(['/', '\\']).next().unwrap_or(program).trim_end_matches(".exe"))
+        .any(|program| NETWORK_TOOLS.contains(&program))
+}
+
+fn string_arg<'a>(payload: &'a InvokeBody, name: &str) -> Option<&'a str> {
+    match payload {
+        InvokeBody::Json(Value::Object(args)) => args.get(name).and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// The classes of one invocation
+fn classify(command: &str, payload: &InvokeBody) -> BTreeSet<CommandClass> {
+    let mut classes: BTreeSet<CommandClass> = CLASSES
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, classes)| classes.iter().copied().collect())
+        .unwrap_or_default();
+    if command == "execute_command" && string_arg(payload, "command").is_some_and(runs_network_tool) {
+        classes.insert(Network);
+    }
+    classes
+}
+
+/// Whether `proposed` allows something the current policy does not
+fn loosens(current: &CommandPolicy, payload: &InvokeBody) -> bool {
+    let InvokeBody::Json(args) = payload else {
+        return true;
+    };
+    let Some(proposed) = args.get("policy").and_then(|p| serde_json::from_value::<CommandPolicy>(p.clone()).ok()) else {
+        return false;
+    };
+    let class_loosened = current
+        .classes
+        .iter()
+        .any(|(class, action)| proposed.classes.get(class).copied().unwrap_or_default() < *action);
+    let command_loosened = current
+        .commands
+        .iter()
+        .any(|(command, action)| proposed.commands.get(command).map_or(true, |a| a < action));
+    class_loosened || command_loosened
+}
+
+fn decide(command: &str, classes: &BTreeSet<CommandClass>, payload: &InvokeBody) -> PolicyAction {
+    let policy = POLICY.read().unwrap();
+    if command == POLICY_COMMAND {
+        return if loosens(&policy, payload) { PolicyAction::Ask } else { PolicyAction::Allow };
+    }
+    if let Some(action) = policy.commands.get(command) {
+        return *action;
+    }
+    classes
+        .iter()
+        .map(|class| policy.classes.get(class).copied().unwrap_or_default())
+        .max()
+        .unwrap_or_default()
+}
+
+fn class_names(classes: &BTreeSet<CommandClass>) -> String {
+    let names: Vec<&str> = classes
+        .iter()
+        .map(|class| match class {
+            ReadFs => "read files",
+            WriteFs => "write files",
+            Exec => "execute programs",
+            Network => "use the network",
+            SecuritySettings => "change security settings",
+        })
+        .collect();
+    names.join(", ")
+}
+
+/// What the command will act on, for the prompt and the audit log
+fn detail(payload: &InvokeBody) -> Option<String> {
+    ["command", "filePath", "path", "url", "target", "host"]
+        .iter()
+        .find_map(|name| string_arg(payload, name))
+        .map(|value| value.chars().take(MAX_DETAIL_CHARS).collect())
+}
+
+fn record(command: &str, detail: Option<&str>, outcome: &str) {
+    let line = match detail {
+        Some(detail) => format!("{} {}: {}", command, detail, outcome),
+        None => format!("{}: {}", command, outcome),
+    };
+    log::info!("Policy: {}", line);
+    audit::record("policy", None, &line, None);
+}
+
+/// Wrap the app's invoke handler so every command passes the policy first
+pub fn guarded<R, F>(handler: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    move |invoke: Invoke<R>| {
+        let command = invoke.message.command().to_string();
+        let classes = classify(&command, invoke.message.payload());
+        match decide(&command, &classes, invoke.message.payload()) {
+            PolicyAction::Allow => handler(invoke),
+            PolicyAction::Deny => {
+                record(&command, detail(invoke.message.payload()).as_deref(), "denied");
+                invoke.resolver.reject(format!("{} was denied by the command policy", command));
+                true
+            }
+            PolicyAction::Ask => {
+                let detail = detail(invoke.message.payload());
+                let mut message = if command == POLICY_COMMAND {
+                    "Loosen the command policy?".to_string()
+                } else {
+                    format!("Allow {} to {}?", command, class_names(&classes))
+                };
+                if let Some(detail) = &detail {
+                    message.push_str(&format!("\n\n{}", detail));
+                }
+                let handler = handler.clone();
+                invoke
+                    .message
+                    .webview_ref()
+                    .dialog()
+                    .message(message)
+                    .title("Command permission")
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom("Allow".to_string(), "Deny".to_string()))
+                    .show(move |allowed| {
+                        if allowed {
+                            record(&command, detail.as_deref(), "allowed after prompt");
+                            handler(invoke);
+                        } else {
+                            record(&command, detail.as_deref(), "denied after prompt");
+                            invoke.resolver.reject(format!("{} was denied by the user", command));
+                        }
+                    });
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Command names registered with `generate_handler!` in lib.rs
+    fn registered_commands() -> Vec<String> {
+        let source = include_str!("../lib.rs");
+        let start = source.find("generate_handler![").expect("lib.rs registers commands") + "generate_handler![".len();
+        let end = start + source[start..].find(']').expect("generate_handler! is closed");
+        source[start..end]
+            .lines()
+            .map(|line| line.split("//").next().unwrap_or(""))
+            .flat_map(|line| line.split(','))
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.rsplit("::").next().unwrap_or(entry).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_command_is_classified_or_unrestricted() {
+        let commands = registered_commands();
+        assert!(commands.len() > 100, "parsed only {} commands from lib.rs", commands.len());
+        for command in &commands {
+            let classified = CLASSES.iter().any(|(name, _)| name == command);
+            let unrestricted = UNRESTRICTED.contains(&command.as_str());
+            assert!(classified || unrestricted, "{} is neither classified nor listed as unrestricted", command);
+            assert!(!(classified && unrestricted), "{} is both classified and unrestricted", command);
+        }
+    }
+
+    #[test]
+    fn lists_name_only_registered_commands() {
+        let commands = registered_commands();
+        for name in CLASSES.iter().map(|(name, _)| *name).chain(UNRESTRICTED.iter().copied()) {
+            assert!(commands.iter().any(|c| c == name), "{} is not a registered command", name);
+        }
+    }
+
+    #[test]
+    fn shell_commands_running_network_tools_are_network_use() {
+        assert!(runs_network_tool("sudo nmap -sV 10.0.0.1"));
+        assert!(runs_network_tool("cat hosts | /usr/bin/curl -d @- http://lab"));
+        assert!(!runs_network_tool("ls -la && grep curl notes.txt"));
+    }
+}
@@ -0,0 +1,445 @@
+//! Postman collections
+//!
+//! Converts Postman v2.1 collections to HTTP client collections and back.
+//! On import, folders are flattened into "Folder / Request" names, auth is
+//! inherited from the enclosing folders and collection, and `{{variables}}`
+//! are filled in from the collection's variables and an optional
+//! environment. Whatever has no equivalent (file bodies, unsupported auth
+//! types, unknown variables) is reported as a warning rather than failing
+//! the import.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::services::http_client::{self, HttpAuth, HttpCollection, HttpHeader, HttpRequestSpec, SavedRequest};
+
+const SCHEMA_V21: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+const MULTIPART_BOUNDARY: &str = "----ctr-form-boundary";
+/// Variables referring to other variables are expanded this many times
+const MAX_VARIABLE_PASSES: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostmanImport {
+    pub collection: String,
+    pub requests: usize,
+    pub warnings: Vec<String>,
+}
+
+struct Importer {
+    variables: HashMap<String, String>,
+    warnings: Vec<String>,
+    unresolved: BTreeSet<String>,
+    requests: Vec<SavedRequest>,
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    })
+}
+
+fn enabled(entry: &Value) -> bool {
+    !entry.get("disabled").and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// `[{key, value}]` lists as used for variables, headers and auth fields
+fn key_values(list: Option<&Value>) -> Vec<(String, String)> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|entry| enabled(entry))
+        .filter_map(|entry| Some((text(entry, "key")?, text(entry, "value").unwrap_or_default())))
+        .collect()
+}
+
+/// Variables of a Postman environment export, for `import`
+pub fn environment(source: &str) -> Result<HashMap<String, String>, String> {
+    let root: Value = serde_json::from_str(source).map_err(|e| format!("Invalid environment: {}", e))?;
+    let values = root.get("values").ok_or("Not a Postman environment")?;
+    Ok(values
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.get("enabled").and_then(Value::as_bool).unwrap_or(true))
+        .filter_map(|entry| Some((text(entry, "key")?, text(entry, "value").unwrap_or_default())))
+        .collect())
+}
+
+fn encode_form(fields: &[(String, String)]) -> String {
+    fields
+        .iter()
+        .map(|(name, value)| format!("{}={}", urlencoding::encode(name), urlencoding::encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+impl Importer {
+    /// Fill in `{{name}}` references; dynamic ones such as `{{$guid}}` and
+    /// unknown ones are left as they are
+    fn substitute(&mut self, input: &str) -> String {
+        let mut output = input.to_string();
+        for _ in 0..MAX_VARIABLE_PASSES {
+            let mut changed = false;
+            let mut rest = output.as_str();
+            let mut next = String::new();
+            while let Some(start) = rest.find("{{") {
+                let Some(end) = rest[start..].find("}}") else { break };
+                let name = &rest[start + 2..start + end];
+                next.push_str(&rest[..start]);
+                match self.variables.get(name.trim()) {
+                    Some(value) => {
+                        next.push_str(value);
+                        changed = true;
+                    }
+                    None => next.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &rest[start + end + 2..];
+            }
+            next.push_str(rest);
+            output = next;
+            if !changed {
+                break;
+            }
+        }
+        let mut rest = output.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else { break };
+            let name = rest[start + 2..start + end].trim();
+            if !name.starts_with('$') {
+                self.unresolved.insert(name.to_string());
+            }
+            rest = &rest[start + end + 2..];
+        }
+        output
+    }
+
+    fn url(&mut self, url: Option<&Value>) -> String {
+        let Some(url) = url else { return String::new() };
+        if let Some(raw) = url.as_str() {
+            return self.substitute(raw);
+        }
+        let mut raw = match text(url, "raw") {
+            Some(raw) => raw,
+            None => {
+                let join = |key: &str, separator: &str| {
+                    url.get(key)
+                        .and_then(Value::as_array)
+                        .map(|parts| parts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(separator))
+                        .unwrap_or_else(|| text(url, key).unwrap_or_default())
+                };
+                let mut raw = join("host", ".");
+                if let Some(protocol) = text(url, "protocol") {
+                    raw = format!("{}://{}", protocol, raw);
+                }
+                if let Some(port) = text(url, "port") {
+                    raw = format!("{}:{}", raw, port);
+                }
+                let path = join("path", "/");
+                if !path.is_empty() {
+                    raw = format!("{}/{}", raw, path.trim_start_matches('/'));
+                }
+                let query = key_values(url.get("query"));
+                if !query.is_empty() {
+                    let pairs: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                    raw = format!("{}?{}", raw, pairs.join("&"));
+                }
+                raw
+            }
+        };
+        // Path variables such as /users/:id
+        for (name, value) in key_values(url.get("variable")) {
+            raw = raw.replace(&format!("/:{}", name), &format!("/{}", value));
+        }
+        self.substitute(&raw)
+    }
+
+    /// Body and the content type it implies
+    fn body(&mut self, name: &str, body: Option<&Value>) -> (Option<String>, Option<String>) {
+        let Some(body) = body.filter(|b| enabled(b)) else {
+            return (None, None);
+        };
+        match body.get("mode").and_then(Value::as_str) {
+            Some("raw") => {
+                let language = body.pointer("/options/raw/language").and_then(Value::as_str);
+                let content_type = match language {
+                    Some("json") => Some("application/json"),
+                    Some("xml") => Some("application/xml"),
+                    Some("html") => Some("text/html"),
+                    Some("javascript") => Some("application/javascript"),
+                    _ => None,
+                };
+                let raw = text(body, "raw").unwrap_or_default();
+                (Some(self.substitute(&raw)), content_type.map(String::from))
+            }
+            Some("urlencoded") => {
+                let fields: Vec<(String, String)> = key_values(body.get("urlencoded"))
+                    .into_iter()
+                    .map(|(k, v)| (self.substitute(&k), self.substitute(&v)))
+                    .collect();
+                (Some(encode_form(&fields)), Some("application/x-www-form-urlencoded".to_string()))
+            }
+            Some("formdata") => {
+                let mut form = String::new();
+                for field in body.get("formdata").and_then(Value::as_array).into_iter().flatten().filter(|f| enabled(f)) {
+                    let key = self.substitute(&text(field, "key").unwrap_or_default());
+                    form.push_str(&format!("--{}\r\n", MULTIPART_BOUNDARY));
+                    if field.get("type").and_then(Value::as_str) == Some("file") {
+                        self.warnings.push(format!("{}: file field '{}' was left empty", name, key));
+                        form.push_str(&format!(
+                            "Content-Disposition: form-data; name=\"{}\"; filename=\"file\"\r\nContent-Type: application/octet-stream\r\n\r\n\r\n",
+                            key
+                        ));
+                    } else {
+                        let value = self.substitute(&text(field, "value").unwrap_or_default());
+                        form.push_str(&format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", key, value));
+                    }
+                }
+                form.push_str(&format!("--{}--\r\n", MULTIPART_BOUNDARY));
+                (Some(form), Some(format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY)))
+            }
+            Some("graphql") => {
+                let graphql = body.get("graphql").unwrap_or(&Value::Null);
+                let variables = text(graphql, "variables")
+                    .and_then(|v| serde_json::from_str::<Value>(&v).ok())
+                    .unwrap_or_else(|| json!({}));
+                let payload = json!({ "query": text(graphql, "query").unwrap_or_default(), "variables": variables });
+                (Some(self.substitute(&payload.to_string())), Some("application/json".to_string()))
+            }
+            Some("file") => {
+                self.warnings.push(format!("{}: file body was not imported", name));
+                (None, None)
+            }
+            _ => (None, None),
+        }
+    }
+
+    /// The effective auth of a request; API keys become a header or query
+    /// parameter instead
+    fn auth(&mut self, name: &str, auth: &Value, headers: &mut Vec<HttpHeader>, url: &mut String) -> Option<HttpAuth> {
+        let kind = auth.get("type").and_then(Value::as_str).unwrap_or("noauth");
+        let fields: HashMap<String, String> = key_values(auth.get(kind)).into_iter().collect();
+        let mut field = |key: &str| self.substitute(fields.get(key).map(String::as_str).unwrap_or_default());
+        match kind {
+            "noauth" => None,
+            "basic" => Some(HttpAuth::Basic { username: field("username"), password: Some(field("password")) }),
+            "bearer" => Some(HttpAuth::Bearer { token: field("token") }),
+            "apikey" => {
+                let (key, value) = (field("key"), field("value"));
+                if fields.get("in").map(String::as_str) == Some("query") {
+                    url.push(if url.contains('?') { '&' } else { '?' });
+                    url.push_str(&format!("{}={}", urlencoding::encode(&key), urlencoding::encode(&value)));
+                } else {
+                    headers.push(HttpHeader { name: key, value });
+                }
+                None
+            }
+            other => {
+                self.warnings.push(format!("{}: {} auth is not supported and was dropped", name, other));
+                None
+            }
+        }
+    }
+
+    fn request(&mut self, name: String, request: &Value, inherited_auth: Option<&Value>) {
+        // A bare string is shorthand for a GET of that URL
+        if let Some(url) = request.as_str() {
+            let url = self.substitute(url);
+            self.requests.push(SavedRequest { name, request: spec("GET", url, Vec::new(), None, None) });
+            return;
+        }
+        let mut url = self.url(request.get("url"));
+        let mut headers: Vec<HttpHeader> = key_values(request.get("header"))
+            .into_iter()
+            .map(|(k, v)| HttpHeader { name: self.substitute(&k), value: self.substitute(&v) })
+            .collect();
+        let (body, content_type) = self.body(&name, request.get("body"));
+        if let Some(content_type) = content_type {
+            if !headers.iter().any(|h| h.name.eq_ignore_ascii_case("content-type")) {
+                headers.push(HttpHeader { name: "Content-Type".to_string(), value: content_type });
+            }
+        }
+        let auth = match request.get("auth").or(inherited_auth) {
+            Some(auth) => self.auth(&name, auth, &mut headers, &mut url),
+            None => None,
+        };
+        let method = text(request, "method").unwrap_or_else(|| "GET".to_string()).to_uppercase();
+        self.requests.push(SavedRequest { name, request: spec(&method, url, headers, body, auth) });
+    }
+
+    fn items(&mut self, items: Option<&Value>, prefix: &str, inherited_auth: Option<&Value>) {
+        for item in items.and_then(Value::as_array).into_iter().flatten() {
+            let item_name = text(item, "name").unwrap_or_else(|| "Untitled".to_string());
+            let name = if prefix.is_empty() { item_name } else { format!("{} / {}", prefix, item_name) };
+            if let Some(request) = item.get("request") {
+                self.request(name, request, inherited_auth);
+            } else if item.get("item").is_some() {
+                // Folders may have variables of their own in older exports
+                for (key, value) in key_values(item.get("variable")) {
+                    self.variables.entry(key).or_insert(value);
+                }
+                let auth = item.get("auth").or(inherited_auth);
+                self.items(item.get("item"), &name, auth);
+            }
+        }
+    }
+}
+
+fn spec(method: &str, url: String, headers: Vec<HttpHeader>, body: Option<String>, auth: Option<HttpAuth>) -> HttpRequestSpec {
+    HttpRequestSpec {
+        method: method.to_string(),
+        url,
+        headers,
+        body,
+        body_base64: false,
+        auth,
+        proxy: None,
+        verify_tls: true,
+        follow_redirects: true,
+        timeout_ms: None,
+    }
+}
+
+/// Convert a Postman v2.1 (or 2.0) collection; `environment` values take
+/// precedence over the collection's own variables
+pub fn import(source: &str, environment: HashMap<String, String>) -> Result<(HttpCollection, Vec<String>), String> {
+    let root: Value = serde_json::from_str(source).map_err(|e| format!("Invalid collection: {}", e))?;
+    if root.get("requests").is_some() {
+        return Err("Postman v1 collections are not supported; export the collection as v2.1".to_string());
+    }
+    let info = root.get("info").ok_or("Not a Postman collection")?;
+    let schema = text(info, "schema").unwrap_or_default();
+    if !schema.contains("/v2.") {
+        return Err("Only Postman v2.0 and v2.1 collections can be imported".to_string());
+    }
+
+    let mut variables: HashMap<String, String> = key_values(root.get("variable")).into_iter().collect();
+    variables.extend(environment);
+    let mut importer = Importer { variables, warnings: Vec::new(), unresolved: BTreeSet::new(), requests: Vec::new() };
+    importer.items(root.get("item"), "", root.get("auth"));
+
+    let mut warnings = importer.warnings;
+    if !importer.unresolved.is_empty() {
+        let names: Vec<String> = importer.unresolved.into_iter().collect();
+        warnings.push(format!("Variables without a value: {}", names.join(", ")));
+    }
+    let collection = HttpCollection {
+        name: text(info, "name").unwrap_or_else(|| "Postman collection".to_string()),
+        requests: importer.requests,
+    };
+    Ok((collection, warnings))
+}
+
+fn export_request(saved: &SavedRequest) -> Value {
+    let request = &saved.request;
+    let headers: Vec<Value> = request
+        .headers
+        .iter()
+        .map(|h| json!({ "key": h.name, "value": h.value, "type": "text" }))
+        .collect();
+    let mut exported = json!({
+        "method": request.method,
+        "header": headers,
+        "url": { "raw": request.url },
+    });
+    if let Some(body) = &request.body {
+        exported["body"] = if request.body_base64 {
+            // Postman keeps binary bodies as files; the data has nowhere to go
+            json!({ "mode": "file", "file": {} })
+        } else {
+            let json = request
+                .headers
+                .iter()
+                .any(|h| h.name.eq_ignore_ascii_case("content-type") && h.value.contains("json"));
+            let mut raw = json!({ "mode": "raw", "raw": body });
+            if json {
+                raw["options"] = json!({ "raw": { "language": "json" } });
+            }
+            raw
+        };
+    }
+    let field = |key: &str, value: &str| json!({ "key": key, "value": value, "type": "string" });
+    match &request.auth {
+        Some(HttpAuth::Basic { username, password }) => {
+            exported["auth"] = json!({
+                "type": "basic",
+                "basic": [field("username", username), field("password", password.as_deref().unwrap_or_default())],
+            });
+        }
+        Some(HttpAuth::Bearer { token }) => {
+            exported["auth"] = json!({ "type": "bearer", "bearer": [field("token", token)] });
+        }
+        None => {}
+    }
+    exported
+}
+
+/// Add an item under the folders named by `folders`, creating them as needed
+fn insert_item(items: &mut Vec<Value>, folders: &[&str], item: Value) {
+    let Some((folder, rest)) = folders.split_first() else {
+        items.push(item);
+        return;
+    };
+    let existing = items
+        .iter()
+        .position(|i| i.get("item").is_some() && i.get("name").and_then(Value::as_str) == Some(folder));
+    let index = existing.unwrap_or_else(|| {
+        items.push(json!({ "name": folder, "item": [] }));
+        items.len() - 1
+    });
+    if let Some(children) = items[index].get_mut("item").and_then(Value::as_array_mut) {
+        insert_item(children, rest, item);
+    }
+}
+
+/// A Postman v2.1 collection of the saved requests; "Folder / Request"
+/// names become folders again
+pub fn export(collection: &HttpCollection) -> Value {
+    let mut items = Vec::new();
+    for saved in &collection.requests {
+        let mut parts: Vec<&str> = saved.name.split(" / ").collect();
+        let name = parts.pop().unwrap_or_default();
+        insert_item(&mut items, &parts, json!({ "name": name, "request": export_request(saved) }));
+    }
+    json!({
+        "info": {
+            "_postman_id": uuid::Uuid::new_v4().to_string(),
+            "name": collection.name,
+            "schema": SCHEMA_V21,
+        },
+        "item": items,
+    })
+}
+
+/// Import a collection file into a workspace, replacing a collection of the
+/// same name; variables come from the collection and, when given, a Postman
+/// environment file
+pub fn import_file(workspace_root: &str, src_path: &str, environment_path: Option<&str>) -> Result<PostmanImport, String> {
+    let environment = match environment_path {
+        Some(path) => environment(&http_client::read_import(path)?)?,
+        None => HashMap::new(),
+    };
+    let (collection, warnings) = import(&http_client::read_import(src_path)?, environment)?;
+    let result = PostmanImport {
+        collection: collection.name.clone(),
+        requests: collection.requests.len(),
+        warnings,
+    };
+    http_client::replace_collection(workspace_root, collection)?;
+    Ok(result)
+}
+
+/// Write a workspace collection to `dest_path`; returns the number of
+/// requests written
+pub fn export_file(workspace_root: &str, collection: &str, dest_path: &str) -> Result<usize, String> {
+    let collections = http_client::collections(workspace_root)?;
+    let target = collections
+        .iter()
+        .find(|c| c.name == collection)
+        .ok_or_else(|| format!("Collection '{}' not found", collection))?;
+    let json = serde_json::to_string_pretty(&export(target)).map_err(|e| format!("Failed to serialize collection: {}", e))?;
+    std::fs::write(dest_path, json).map_err(|e| format!("Failed to write {}: {}", dest_path, e))?;
+    Ok(target.requests.len())
+}
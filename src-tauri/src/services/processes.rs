@@ -0,0 +1,280 @@
+//! Process registry
+//!
+//! One list of everything the IDE keeps running: PTY sessions, interactive
+//! runs, the extension host, lab containers and listeners. Subsystems
+//! register what they start and unregister it when it ends. On exit every
+//! entry is stopped, child processes together with their descendants.
+//! Entries that outlive a crash (processes and lab containers) are recorded
+//! in ~/.ctr/processes.json and reaped at the next startup.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+#[cfg(unix)]
+use std::time::Duration;
+
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+/// How long a process tree gets to exit after SIGTERM before it is killed
+#[cfg(unix)]
+const TERMINATE_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    Terminal,
+    Interactive,
+    ExtensionHost,
+    Lab,
+    Listener,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedProcess {
+    /// The id the owning subsystem knows it by
+    pub id: String,
+    pub kind: ProcessKind,
+    pub label: String,
+    pub pid: Option<u32>,
+    /// Executable name of `pid`, checked before reaping so a reused pid is
+    /// not killed
+    #[serde(default)]
+    pub process_name: Option<String>,
+    /// Command that removes what the entry left behind, e.g. stopping a
+    /// lab's containers
+    #[serde(default)]
+    pub cleanup: Option<Vec<String>>,
+    /// Unix timestamp in milliseconds
+    pub started_at: u64,
+}
+
+type StopHook = Box<dyn FnOnce() + Send>;
+
+struct Registered {
+    process: ManagedProcess,
+    stop: Option<StopHook>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProcessFile {
+    #[serde(default)]
+    processes: Vec<ManagedProcess>,
+}
+
+lazy_static::lazy_static! {
+    static ref REGISTRY: Mutex<HashMap<String, Registered>> = Mutex::new(HashMap::new());
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("processes.json"))
+}
+
+/// Record the entries that would be left behind by a crash
+fn persist(registry: &HashMap<String, Registered>) {
+    let processes = registry
+        .values()
+        .map(|r| r.process.clone())
+        .filter(|p| p.pid.is_some() || p.cleanup.is_some())
+        .collect();
+    if let Err(e) = store_path().and_then(|path| save_json(&path, &ProcessFile { processes })) {
+        log::warn!("Failed to record running processes: {}", e);
+    }
+}
+
+impl ManagedProcess {
+    pub fn new(id: &str, kind: ProcessKind, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            kind,
+            label: label.to_string(),
+            pid: None,
+            process_name: None,
+            cleanup: None,
+            started_at: now_millis(),
+        }
+    }
+
+    pub fn with_pid(mut self, pid: Option<u32>) -> Self {
+        self.process_name = pid.and_then(process_name);
+        self.pid = pid;
+        self
+    }
+
+    pub fn with_cleanup(mut self, command: Vec<String>) -> Self {
+        self.cleanup = Some(command);
+        self
+    }
+}
+
+/// Add an entry; `stop` is how the owning subsystem shuts it down on exit,
+/// before its process tree is killed
+pub fn register(process: ManagedProcess, stop: Option<StopHook>) {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.insert(process.id.clone(), Registered { process, stop });
+    persist(&registry);
+}
+
+/// Remove an entry once it has ended; unknown ids are ignored
+pub fn unregister(id: &str) {
+    let mut registry = REGISTRY.lock().unwrap();
+    if registry.remove(id).is_some() {
+        persist(&registry);
+    }
+}
+
+/// Everything running, oldest first
+pub fn list() -> Vec<ManagedProcess> {
+    let mut processes: Vec<ManagedProcess> = REGISTRY.lock().unwrap().values().map(|r| r.process.clone()).collect();
+    processes.sort_by_key(|p| p.started_at);
+    processes
+}
+
+/// Executable name of a running process
+fn process_name(pid: u32) -> Option<String> {
+    let output = if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+            .output()
+            .ok()?
+    } else {
+        Command::new("ps").args(["-p", &pid.to_string(), "-o", "comm="]).output().ok()?
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    let name = if cfg!(windows) {
+        // "name.exe","1234",...; tasklist prints an INFO line when nothing matches
+        text.split(',').next()?.trim().trim_matches('"').to_string()
+    } else {
+        text.trim().rsplit('/').next()?.to_string()
+    };
+    (output.status.success() && !name.is_empty() && !name.starts_with("INFO:")).then_some(name)
+}
+
+/// `pid` and all of its descendants, parents first
+#[cfg(unix)]
+fn process_tree(pid: u32) -> Vec<u32> {
+    let Ok(output) = Command::new("ps").args(["-A", "-o", "pid=,ppid="]).output() else {
+        return vec![pid];
+    };
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut parts = line.split_whitespace().filter_map(|p| p.parse::<u32>().ok());
+        if let (Some(child), Some(parent)) = (parts.next(), parts.next()) {
+            children.entry(parent).or_default().push(child);
+        }
+    }
+    let mut tree = vec![pid];
+    let mut seen = std::collections::HashSet::from([pid]);
+    let mut i = 0;
+    while i < tree.len() {
+        for child in children.get(&tree[i]).into_iter().flatten() {
+            if seen.insert(*child) {
+                tree.push(*child);
+            }
+        }
+        i += 1;
+    }
+    tree
+}
+
+/// Terminate a process and its descendants, killing whatever is still
+/// running after the grace period
+#[cfg(unix)]
+pub fn kill_tree(pid: u32) {
+    let tree: Vec<String> = process_tree(pid).iter().map(u32::to_string).collect();
+    let _ = Command::new("kill").arg("-TERM").args(&tree).output();
+    let deadline = std::time::Instant::now() + TERMINATE_GRACE;
+    while std::time::Instant::now() < deadline {
+        let alive = Command::new("kill").arg("-0").arg(&tree[0]).output();
+        if !alive.is_ok_and(|o| o.status.success()) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let _ = Command::new("kill").arg("-KILL").args(&tree).output();
+}
+
+#[cfg(windows)]
+pub fn kill_tree(pid: u32) {
+    let _ = Command::new("taskkill").args(["/PID", &pid.to_string(), "/T", "/F"]).output();
+}
+
+fn run_cleanup(process: &ManagedProcess) {
+    let Some((program, args)) = process.cleanup.as_ref().and_then(|c| c.split_first()) else {
+        return;
+    };
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => log::warn!(
+            "Cleanup of {} failed: {}",
+            process.label,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => log::warn!("Cleanup of {} failed: {}", process.label, e),
+    }
+}
+
+fn stop_entry(entry: Registered) {
+    // The cleanup command is for entries whose owner is gone
+    match entry.stop {
+        Some(stop) => stop(),
+        None => run_cleanup(&entry.process),
+    }
+    if let Some(pid) = entry.process.pid {
+        kill_tree(pid);
+    }
+}
+
+/// Stop one entry, whatever its kind
+pub fn stop(id: &str) -> Result<(), String> {
+    let entry = {
+        let mut registry = REGISTRY.lock().unwrap();
+        let entry = registry.remove(id).ok_or_else(|| format!("Process {} not found", id))?;
+        persist(&registry);
+        entry
+    };
+    stop_entry(entry);
+    Ok(())
+}
+
+/// Stop everything; called when the app exits
+pub fn shutdown_all() {
+    // Hooks may unregister their own entries, so the lock is released first
+    let entries: Vec<Registered> = REGISTRY.lock().unwrap().drain().map(|(_, entry)| entry).collect();
+    for entry in entries {
+        stop_entry(entry);
+    }
+    let _ = store_path().map(|path| save_json(&path, &ProcessFile::default()));
+}
+
+/// Kill the processes and clean up the labs recorded by a session that did
+/// not shut down. Called once at startup, before anything new is
+/// registered; the reaping itself runs in the background.
+pub fn reap_orphans() {
+    let Ok(path) = store_path() else {
+        return;
+    };
+    let file: ProcessFile = load_json(&path);
+    if file.processes.is_empty() {
+        return;
+    }
+    let _ = save_json(&path, &ProcessFile::default());
+
+    std::thread::spawn(move || {
+        for process in file.processes {
+            // A pid that now belongs to another program is left alone
+            let alive = process
+                .pid
+                .filter(|pid| process_name(*pid).is_some_and(|name| process.process_name.as_ref() == Some(&name)));
+            if let Some(pid) = alive {
+                kill_tree(pid);
+            }
+            run_cleanup(&process);
+            if alive.is_some() || process.cleanup.is_some() {
+                log::info!("Reaped {} left running by a previous session", process.label);
+            }
+        }
+    });
+}
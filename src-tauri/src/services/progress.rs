@@ -0,0 +1,344 @@
+//! Scoring and progress tracking
+//!
+//! Records training activity (completed challenges, vulnerabilities found,
+//! exploits verified) in a local SQLite database at ~/.ctr/progress.db and
+//! derives leaderboards and per-skill competency summaries from it.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::utils::csv::csv_field;
+use crate::utils::fs_utils::ctr_dir;
+use crate::utils::time::now_millis;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS progress_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    skill TEXT NOT NULL,
+    reference TEXT NOT NULL,
+    detail TEXT NOT NULL DEFAULT '',
+    points INTEGER NOT NULL DEFAULT 0,
+    duration_ms INTEGER,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_progress_user ON progress_events(user);
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressKind {
+    ChallengeCompleted,
+    VulnerabilityFound,
+    ExploitVerified,
+}
+
+impl ProgressKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ProgressKind::ChallengeCompleted => "challenge_completed",
+            ProgressKind::VulnerabilityFound => "vulnerability_found",
+            ProgressKind::ExploitVerified => "exploit_verified",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "challenge_completed" => Some(ProgressKind::ChallengeCompleted),
+            "vulnerability_found" => Some(ProgressKind::VulnerabilityFound),
+            "exploit_verified" => Some(ProgressKind::ExploitVerified),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    #[serde(default)]
+    pub id: i64,
+    pub user: String,
+    pub kind: ProgressKind,
+    /// Skill bucket; derived from `reference` and `detail` when empty
+    #[serde(default)]
+    pub skill: String,
+    /// What was completed/found, e.g. "pack/challenge" or "file.py:42"
+    pub reference: String,
+    #[serde(default)]
+    pub detail: String,
+    #[serde(default)]
+    pub points: u32,
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub user: String,
+    pub points: u64,
+    pub challenges: u32,
+    pub vulnerabilities: u32,
+    pub exploits: u32,
+    pub total_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillSummary {
+    pub user: String,
+    pub skill: String,
+    pub challenges: u32,
+    pub vulnerabilities: u32,
+    pub exploits: u32,
+    pub points: u64,
+    pub avg_time_ms: Option<u64>,
+    /// novice, intermediate or proficient
+    pub competency: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Keyword buckets, checked in order against categories, CWEs and attack types
+const SKILLS: &[(&str, &[&str])] = &[
+    ("sqli", &["sql", "cwe-89", "injection (sql)"]),
+    ("xss", &["xss", "cross-site scripting", "cwe-79", "reflected"]),
+    ("crypto", &["crypto", "hash", "cipher", "cwe-327", "cwe-328", "cwe-916"]),
+    ("command_injection", &["command", "rce", "cwe-78"]),
+    ("deserialization", &["deserializ", "pickle", "cwe-502"]),
+    ("path_traversal", &["traversal", "lfi", "cwe-22", "file_disclosure"]),
+    ("ssrf", &["ssrf", "cwe-918"]),
+    ("auth", &["auth", "login", "session", "jwt", "cwe-287"]),
+];
+
+/// Map free text (category, CWE, attack type) to a skill bucket
+pub fn skill_for(text: &str) -> String {
+    let text = text.to_lowercase();
+    SKILLS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| text.contains(k)))
+        .map(|(skill, _)| skill.to_string())
+        .unwrap_or_else(|| "other".to_string())
+}
+
+/// The OS account name, used when activity isn't tied to a named player
+pub fn default_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "local".to_string())
+}
+
+fn open() -> Result<Connection, String> {
+    let path = ctr_dir()?.join("progress.db");
+    let conn = Connection::open(&path).map_err(|e| format!("Failed to open progress database: {}", e))?;
+    conn.execute_batch(SCHEMA)
+        .map_err(|e| format!("Failed to initialize progress database: {}", e))?;
+    Ok(conn)
+}
+
+pub fn record(mut event: ProgressEvent) -> Result<ProgressEvent, String> {
+    if event.skill.trim().is_empty() {
+        event.skill = skill_for(&format!("{} {}", event.reference, event.detail));
+    }
+    event.created_at = now_millis();
+
+    let conn = open()?;
+    conn.execute(
+        "INSERT INTO progress_events (user, kind, skill, reference, detail, points, duration_ms, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            event.user,
+            event.kind.as_str(),
+            event.skill,
+            event.reference,
+            event.detail,
+            event.points,
+            event.duration_ms.map(|d| d as i64),
+            event.created_at as i64,
+        ],
+    )
+    .map_err(|e| format!("Failed to record progress: {}", e))?;
+    event.id = conn.last_insert_rowid();
+    Ok(event)
+}
+
+/// Record without failing the caller; progress is a side channel
+pub fn record_quietly(event: ProgressEvent) {
+    if let Err(e) = record(event) {
+        log::warn!("{}", e);
+    }
+}
+
+pub fn events(user: Option<&str>) -> Result<Vec<ProgressEvent>, String> {
+    let conn = open()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, user, kind, skill, reference, detail, points, duration_ms, created_at
+             FROM progress_events WHERE ?1 IS NULL OR user = ?1 ORDER BY created_at",
+        )
+        .map_err(|e| format!("Failed to query progress: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![user], |row| {
+            let kind: String = row.get(2)?;
+            // Rows with a kind this build doesn't know are skipped
+            let Some(kind) = ProgressKind::parse(&kind) else {
+                return Ok(None);
+            };
+            let duration: Option<i64> = row.get(7)?;
+            let created: i64 = row.get(8)?;
+            Ok(Some(ProgressEvent {
+                id: row.get(0)?,
+                user: row.get(1)?,
+                kind,
+                skill: row.get(3)?,
+                reference: row.get(4)?,
+                detail: row.get(5)?,
+                points: row.get(6)?,
+                duration_ms: duration.map(|d| d as u64),
+                created_at: created as u64,
+            }))
+        })
+        .map_err(|e| format!("Failed to query progress: {}", e))?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        if let Some(event) = row.map_err(|e| format!("Failed to read progress row: {}", e))? {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+pub fn leaderboard() -> Result<Vec<LeaderboardEntry>, String> {
+    let mut by_user: HashMap<String, LeaderboardEntry> = HashMap::new();
+    for event in events(None)? {
+        let entry = by_user.entry(event.user.clone()).or_insert_with(|| LeaderboardEntry {
+            rank: 0,
+            user: event.user.clone(),
+            points: 0,
+            challenges: 0,
+            vulnerabilities: 0,
+            exploits: 0,
+            total_time_ms: 0,
+        });
+        entry.points += event.points as u64;
+        entry.total_time_ms += event.duration_ms.unwrap_or(0);
+        match event.kind {
+            ProgressKind::ChallengeCompleted => entry.challenges += 1,
+            ProgressKind::VulnerabilityFound => entry.vulnerabilities += 1,
+            ProgressKind::ExploitVerified => entry.exploits += 1,
+        }
+    }
+
+    // Higher score first; ties go to whoever spent less time
+    let mut entries: Vec<LeaderboardEntry> = by_user.into_values().collect();
+    entries.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(a.total_time_ms.cmp(&b.total_time_ms))
+            .then(a.user.cmp(&b.user))
+    });
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.rank = i + 1;
+    }
+    Ok(entries)
+}
+
+fn competency(summary: &SkillSummary) -> &'static str {
+    let weight = summary.challenges * 2 + summary.exploits * 2 + summary.vulnerabilities;
+    match weight {
+        0..=2 => "novice",
+        3..=7 => "intermediate",
+        _ => "proficient",
+    }
+}
+
+/// Per user and skill totals, for instructor review
+pub fn skill_summaries(user: Option<&str>) -> Result<Vec<SkillSummary>, String> {
+    let mut groups: HashMap<(String, String), (SkillSummary, u64, u32)> = HashMap::new();
+    for event in events(user)? {
+        let key = (event.user.clone(), event.skill.clone());
+        let (summary, time_total, timed) = groups.entry(key).or_insert_with(|| {
+            (
+                SkillSummary {
+                    user: event.user.clone(),
+                    skill: event.skill.clone(),
+                    challenges: 0,
+                    vulnerabilities: 0,
+                    exploits: 0,
+                    points: 0,
+                    avg_time_ms: None,
+                    competency: String::new(),
+                },
+                0,
+                0,
+            )
+        });
+        summary.points += event.points as u64;
+        match event.kind {
+            ProgressKind::ChallengeCompleted => summary.challenges += 1,
+            ProgressKind::VulnerabilityFound => summary.vulnerabilities += 1,
+            ProgressKind::ExploitVerified => summary.exploits += 1,
+        }
+        if let Some(duration) = event.duration_ms {
+            *time_total += duration;
+            *timed += 1;
+        }
+    }
+
+    let mut summaries: Vec<SkillSummary> = groups
+        .into_values()
+        .map(|(mut summary, time_total, timed)| {
+            if timed > 0 {
+                summary.avg_time_ms = Some(time_total / timed as u64);
+            }
+            summary.competency = competency(&summary).to_string();
+            summary
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.user.cmp(&b.user).then(a.skill.cmp(&b.skill)));
+    Ok(summaries)
+}
+
+pub fn export_leaderboard(path: &Path, format: ExportFormat) -> Result<usize, String> {
+    let entries = leaderboard()?;
+    let content = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("Failed to serialize leaderboard: {}", e))?,
+        ExportFormat::Csv => {
+            let mut out = String::from("rank,user,points,challenges,vulnerabilities,exploits,total_time_ms\n");
+            for e in &entries {
+                out.push_str(&format!(
+                    "{},{},{},{},{},{},{}\n",
+                    e.rank,
+                    csv_field(&e.user),
+                    e.points,
+                    e.challenges,
+                    e.vulnerabilities,
+                    e.exploits,
+                    e.total_time_ms
+                ));
+            }
+            out
+        }
+    };
+    std::fs::write(path, content).map_err(|e| format!("Failed to write leaderboard: {}", e))?;
+    Ok(entries.len())
+}
+
+pub fn reset(user: Option<&str>) -> Result<usize, String> {
+    open()?
+        .execute(
+            "DELETE FROM progress_events WHERE ?1 IS NULL OR user = ?1",
+            params![user],
+        )
+        .map_err(|e| format!("Failed to reset progress: {}", e))
+}
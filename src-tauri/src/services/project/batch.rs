@@ -0,0 +1,271 @@
+//! Batch file operations
+//!
+//! Runs a list of create/copy/move/delete operations as one unit. Deleted
+//! and overwritten paths are first renamed aside next to the original, so
+//! when an operation fails everything done before it can be undone in
+//! reverse order. The set-aside copies are removed once the whole batch
+//! succeeded.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FileOperation {
+    /// Write a file, creating its parent directories
+    Create {
+        path: String,
+        #[serde(default)]
+        content: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    CreateDir { path: String },
+    /// Copy a file or, recursively, a directory
+    Copy {
+        from: String,
+        to: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    Move {
+        from: String,
+        to: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    Delete { path: String },
+}
+
+impl FileOperation {
+    fn describe(&self) -> String {
+        match self {
+            FileOperation::Create { path, .. } => format!("create {}", path),
+            FileOperation::CreateDir { path } => format!("create directory {}", path),
+            FileOperation::Copy { from, to, .. } => format!("copy {} to {}", from, to),
+            FileOperation::Move { from, to, .. } => format!("move {} to {}", from, to),
+            FileOperation::Delete { path } => format!("delete {}", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    /// Operations finished so far
+    pub completed: usize,
+    pub total: usize,
+    /// The operation just finished, or being undone while rolling back
+    pub operation: String,
+    pub rolling_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult {
+    pub batch_id: String,
+    pub completed: usize,
+}
+
+/// How to undo one step of a batch
+enum Undo {
+    /// Remove a path the batch created
+    Remove(PathBuf),
+    /// Put back a path that was set aside
+    Restore { aside: PathBuf, original: PathBuf },
+    /// Move a path back to where it was
+    MoveBack { from: PathBuf, to: PathBuf },
+}
+
+struct Batch<'a> {
+    id: &'a str,
+    undo: Vec<Undo>,
+    /// Set-aside paths to delete once the batch succeeded
+    aside: Vec<PathBuf>,
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Rename, falling back to copy and remove across filesystems
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_recursive(from, to)?;
+    remove_path(from)
+}
+
+fn create_parent(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent directory: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Batch<'_> {
+    /// Rename `path` to a sibling so it can be restored on rollback
+    fn set_aside(&mut self, path: &Path) -> Result<(), String> {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let aside = path.with_file_name(format!(".{}.ctr-batch-{}-{}", name, self.id, self.undo.len()));
+        fs::rename(path, &aside).map_err(|e| format!("Failed to set {} aside: {}", path.display(), e))?;
+        self.undo.push(Undo::Restore { aside: aside.clone(), original: path.to_path_buf() });
+        self.aside.push(aside);
+        Ok(())
+    }
+
+    /// Make room for `target`: fail if it exists, unless `overwrite`
+    fn prepare_target(&mut self, target: &Path, overwrite: bool) -> Result<(), String> {
+        if !target.exists() {
+            return create_parent(target);
+        }
+        if !overwrite {
+            return Err(format!("{} already exists", target.display()));
+        }
+        self.set_aside(target)
+    }
+
+    fn apply(&mut self, operation: &FileOperation) -> Result<(), String> {
+        match operation {
+            FileOperation::Create { path, content, overwrite } => {
+                let path = PathBuf::from(path);
+                self.prepare_target(&path, *overwrite)?;
+                fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+                self.undo.push(Undo::Remove(path));
+            }
+            FileOperation::CreateDir { path } => {
+                let path = PathBuf::from(path);
+                if path.is_dir() {
+                    return Ok(());
+                }
+                // Only the topmost directory created needs removing on rollback
+                let mut top = path.clone();
+                while let Some(parent) = top.parent() {
+                    if parent.as_os_str().is_empty() || parent.exists() {
+                        break;
+                    }
+                    top = parent.to_path_buf();
+                }
+                fs::create_dir_all(&path).map_err(|e| format!("Failed to create directory: {}", e))?;
+                self.undo.push(Undo::Remove(top));
+            }
+            FileOperation::Copy { from, to, overwrite } => {
+                let (from, to) = (PathBuf::from(from), PathBuf::from(to));
+                if !from.exists() {
+                    return Err(format!("{} does not exist", from.display()));
+                }
+                self.prepare_target(&to, *overwrite)?;
+                // Registered first so a partial copy is cleaned up as well
+                self.undo.push(Undo::Remove(to.clone()));
+                copy_recursive(&from, &to).map_err(|e| format!("Failed to copy: {}", e))?;
+            }
+            FileOperation::Move { from, to, overwrite } => {
+                let (from, to) = (PathBuf::from(from), PathBuf::from(to));
+                if !from.exists() {
+                    return Err(format!("{} does not exist", from.display()));
+                }
+                self.prepare_target(&to, *overwrite)?;
+                move_path(&from, &to).map_err(|e| format!("Failed to move: {}", e))?;
+                self.undo.push(Undo::MoveBack { from: to, to: from });
+            }
+            FileOperation::Delete { path } => {
+                let path = PathBuf::from(path);
+                if !path.exists() {
+                    return Err(format!("{} does not exist", path.display()));
+                }
+                self.set_aside(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Undo every step in reverse order; returns the steps that could not be undone
+    fn rollback(&mut self, mut progress: impl FnMut(&str)) -> Vec<String> {
+        let mut failures = Vec::new();
+        while let Some(step) = self.undo.pop() {
+            let (description, result) = match step {
+                Undo::Remove(path) => {
+                    let result = if path.exists() { remove_path(&path) } else { Ok(()) };
+                    (format!("remove {}", path.display()), result)
+                }
+                Undo::Restore { aside, original } => {
+                    (format!("restore {}", original.display()), fs::rename(&aside, &original))
+                }
+                Undo::MoveBack { from, to } => {
+                    (format!("move {} back to {}", from.display(), to.display()), move_path(&from, &to))
+                }
+            };
+            progress(&description);
+            if let Err(e) = result {
+                failures.push(format!("{}: {}", description, e));
+            }
+        }
+        failures
+    }
+}
+
+/// Run `operations` in order. When one fails, the earlier ones are rolled
+/// back and the error names the failed operation and anything that could
+/// not be undone.
+pub fn run(
+    batch_id: &str,
+    operations: &[FileOperation],
+    mut progress: impl FnMut(BatchProgress),
+) -> Result<BatchResult, String> {
+    let total = operations.len();
+    let mut batch = Batch { id: batch_id, undo: Vec::new(), aside: Vec::new() };
+
+    for (index, operation) in operations.iter().enumerate() {
+        if let Err(error) = batch.apply(operation) {
+            let failures = batch.rollback(|description| {
+                progress(BatchProgress {
+                    batch_id: batch_id.to_string(),
+                    completed: index,
+                    total,
+                    operation: description.to_string(),
+                    rolling_back: true,
+                })
+            });
+            let mut message = format!("Operation {} ({}) failed: {}", index + 1, operation.describe(), error);
+            if failures.is_empty() {
+                message.push_str("; earlier operations were rolled back");
+            } else {
+                message.push_str(&format!("; rollback incomplete: {}", failures.join(", ")));
+            }
+            return Err(message);
+        }
+        progress(BatchProgress {
+            batch_id: batch_id.to_string(),
+            completed: index + 1,
+            total,
+            operation: operation.describe(),
+            rolling_back: false,
+        });
+    }
+
+    for aside in &batch.aside {
+        if let Err(e) = remove_path(aside) {
+            log::warn!("Failed to remove {}: {}", aside.display(), e);
+        }
+    }
+    Ok(BatchResult { batch_id: batch_id.to_string(), completed: total })
+}
@@ -0,0 +1,245 @@
+//! Repository cloning
+//!
+//! Clones through git2 with optional shallow depth, a single branch and
+//! submodules, reporting transfer and checkout progress so that cloning a
+//! large vulnerable-app repository does not look frozen. A clone started
+//! with an id can be cancelled; the partial clone is removed.
+
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{Direction, ErrorCode, FetchOptions, Remote, RemoteCallbacks};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::credentials;
+use super::submodules::{self, SubmoduleProgress};
+
+lazy_static::lazy_static! {
+    /// Clone id -> cancellation flag of clones still running
+    static ref CLONES: Mutex<HashMap<String, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// Number of commits to fetch; None for the full history
+    pub depth: Option<u32>,
+    /// Branch to check out instead of the remote's default
+    pub branch: Option<String>,
+    /// Fetch only the checked out branch
+    pub single_branch: bool,
+    pub recurse_submodules: bool,
+}
+
+/// Payload of the `git-clone-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct CloneProgress {
+    pub clone_id: String,
+    /// "receiving", "resolving", "checkout", "submodules" or "done"
+    pub stage: String,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+    pub indexed_deltas: usize,
+    pub total_deltas: usize,
+    /// Files checked out so far and their total
+    pub checkout_current: usize,
+    pub checkout_total: usize,
+    /// Name of the submodule being cloned in the "submodules" stage
+    pub submodule: Option<String>,
+}
+
+impl CloneProgress {
+    fn new(clone_id: &str, stage: &str) -> Self {
+        CloneProgress {
+            clone_id: clone_id.to_string(),
+            stage: stage.to_string(),
+            received_objects: 0,
+            indexed_objects: 0,
+            total_objects: 0,
+            received_bytes: 0,
+            indexed_deltas: 0,
+            total_deltas: 0,
+            checkout_current: 0,
+            checkout_total: 0,
+            submodule: None,
+        }
+    }
+}
+
+/// Cancel a running clone; false when no clone runs under `clone_id`
+pub fn cancel(clone_id: &str) -> bool {
+    match CLONES.lock().unwrap().get(clone_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The branch HEAD of the remote points to, for a single-branch clone
+/// without an explicit branch
+fn default_branch(url: &str) -> Result<String, String> {
+    let mut remote = Remote::create_detached(url).map_err(|e| format!("Invalid remote {}: {}", url, e))?;
+    let mut callbacks = RemoteCallbacks::new();
+    credentials::attach(&mut callbacks);
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(callbacks), None)
+        .map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+    let head = connection
+        .default_branch()
+        .map_err(|e| format!("Failed to find the default branch of {}: {}", url, e))?;
+    let head = head.as_str().ok_or("Default branch name is not valid UTF-8")?;
+    Ok(head.trim_start_matches("refs/heads/").to_string())
+}
+
+/// Clone `url` into `dest_path`, which must not exist or be empty; `cancel`
+/// with `clone_id` stops it. Cancelling while submodules are cloned takes
+/// effect once they are done.
+pub fn clone(
+    url: &str,
+    dest_path: &str,
+    options: &CloneOptions,
+    clone_id: &str,
+    progress: &mut dyn FnMut(CloneProgress),
+) -> Result<(), String> {
+    let dest = Path::new(dest_path);
+    if dest.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("{} already exists and is not empty", dest_path));
+    }
+    let created = !dest.exists();
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    CLONES.lock().unwrap().insert(clone_id.to_string(), cancelled.clone());
+    let result = run(url, dest, options, clone_id, &cancelled, progress);
+    CLONES.lock().unwrap().remove(clone_id);
+
+    if result.is_err() {
+        // Leave no half-cloned repository behind
+        if created {
+            let _ = fs::remove_dir_all(dest);
+        } else if let Ok(entries) = dest.read_dir() {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let _ = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+            }
+        }
+    }
+    result
+}
+
+fn run(
+    url: &str,
+    dest: &Path,
+    options: &CloneOptions,
+    clone_id: &str,
+    cancelled: &AtomicBool,
+    progress: &mut dyn FnMut(CloneProgress),
+) -> Result<(), String> {
+    let branch = match (&options.branch, options.single_branch) {
+        (Some(branch), _) => Some(branch.clone()),
+        (None, true) => Some(default_branch(url)?),
+        (None, false) => None,
+    };
+    // Progress is shared by the transfer and checkout callbacks
+    let progress = RefCell::new(progress);
+
+    let cloned = {
+        let mut last_percent = None;
+        let mut callbacks = RemoteCallbacks::new();
+        credentials::attach(&mut callbacks);
+        callbacks.transfer_progress(|stats| {
+            if cancelled.load(Ordering::SeqCst) {
+                return false;
+            }
+            let (stage, done, total) = if stats.received_objects() < stats.total_objects() {
+                ("receiving", stats.received_objects(), stats.total_objects())
+            } else {
+                ("resolving", stats.indexed_deltas(), stats.total_deltas())
+            };
+            // At most one event per percent of each stage
+            let percent = (stage, done * 100 / total.max(1));
+            if last_percent != Some(percent) {
+                last_percent = Some(percent);
+                let mut event = CloneProgress::new(clone_id, stage);
+                event.received_objects = stats.received_objects();
+                event.indexed_objects = stats.indexed_objects();
+                event.total_objects = stats.total_objects();
+                event.received_bytes = stats.received_bytes();
+                event.indexed_deltas = stats.indexed_deltas();
+                event.total_deltas = stats.total_deltas();
+                (progress.borrow_mut())(event);
+            }
+            true
+        });
+
+        let mut fetch = FetchOptions::new();
+        fetch.remote_callbacks(callbacks);
+        if let Some(depth) = options.depth.filter(|d| *d > 0) {
+            fetch.depth(depth.min(i32::MAX as u32) as i32);
+        }
+
+        let mut last_checkout = None;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.progress(|_, current, total| {
+            let percent = current * 100 / total.max(1);
+            if last_checkout != Some(percent) {
+                last_checkout = Some(percent);
+                let mut event = CloneProgress::new(clone_id, "checkout");
+                event.checkout_current = current;
+                event.checkout_total = total;
+                (progress.borrow_mut())(event);
+            }
+        });
+
+        let mut builder = RepoBuilder::new();
+        builder.fetch_options(fetch).with_checkout(checkout);
+        if let Some(branch) = &branch {
+            builder.branch(branch);
+        }
+        if options.single_branch {
+            let branch = branch.clone().unwrap_or_default();
+            builder.remote_create(move |repo, name, url| {
+                let refspec = format!("+refs/heads/{0}:refs/remotes/{1}/{0}", branch, name);
+                repo.remote_with_fetch(name, url, &refspec)
+            });
+        }
+        builder.clone(url, dest)
+    };
+    let progress = progress.into_inner();
+
+    if let Err(e) = cloned {
+        if cancelled.load(Ordering::SeqCst) || e.code() == ErrorCode::User {
+            return Err("Clone cancelled".to_string());
+        }
+        return Err(format!("Failed to clone repository: {}", e));
+    }
+
+    if options.recurse_submodules {
+        let dest_path = dest.to_string_lossy().to_string();
+        let mut forward = |sub: SubmoduleProgress| {
+            let mut event = CloneProgress::new(clone_id, "submodules");
+            event.received_objects = sub.received_objects;
+            event.indexed_objects = sub.indexed_objects;
+            event.total_objects = sub.total_objects;
+            event.received_bytes = sub.received_bytes;
+            event.submodule = Some(sub.name);
+            progress(event);
+        };
+        let result = submodules::init_update(&dest_path, None, true, &mut forward)?;
+        if cancelled.load(Ordering::SeqCst) {
+            return Err("Clone cancelled".to_string());
+        }
+        if let Some((name, error)) = result.failed.first() {
+            return Err(format!("Failed to clone submodule {}: {}", name, error));
+        }
+    }
+
+    progress(CloneProgress::new(clone_id, "done"));
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! Merge conflicts
+//!
+//! Data for a three-way merge editor: the base, ours and theirs versions of
+//! a conflicted file from the index, and the conflict regions of its
+//! working-tree text, parsed from the markers git wrote (with the base part
+//! when merge.conflictStyle is diff3 or zdiff3). Resolutions are written
+//! back and optionally staged, which clears the conflict.
+
+use git2::{IndexEntry, Repository};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Length of git's default conflict markers
+const MARKER_SIZE: usize = 7;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictHunk {
+    /// 1-based lines of the opening and closing markers
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Labels after the markers, usually HEAD and the merged branch
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: String,
+    /// Only with the diff3 conflict style
+    pub base: Option<String>,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictVersions {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Common ancestor; None when the file was added on both sides
+    pub base: Option<String>,
+    /// None when the file was deleted on that side
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    /// Working-tree text with the conflict markers
+    pub working: Option<String>,
+    pub hunks: Vec<ConflictHunk>,
+    /// Binary files have no text versions
+    pub binary: bool,
+}
+
+fn open(repo_path: &str) -> Result<Repository, String> {
+    Repository::open(repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+/// `path` relative to the working directory, with forward slashes
+fn relative_path(repo: &Repository, path: &str) -> Result<(PathBuf, String), String> {
+    let workdir = repo.workdir().ok_or("Repository has no working directory")?;
+    let path = Path::new(path);
+    let relative = path.strip_prefix(workdir).unwrap_or(path);
+    if relative.is_absolute() {
+        return Err(format!("{} is outside the repository", path.display()));
+    }
+    Ok((workdir.join(relative), relative.to_string_lossy().replace('\\', "/")))
+}
+
+/// The marker `kind` repeated, followed by nothing or a space and a label
+fn marker(line: &str, kind: char) -> Option<&str> {
+    let rest = line.strip_prefix(&kind.to_string().repeat(MARKER_SIZE))?;
+    let rest = rest.trim_end_matches(['\r', '\n']);
+    match rest.strip_prefix(' ') {
+        Some(label) => Some(label),
+        None if rest.is_empty() => Some(""),
+        None => None,
+    }
+}
+
+/// Conflict regions of a file's text; unterminated regions are ignored
+pub fn parse_conflicts(text: &str) -> Vec<ConflictHunk> {
+    enum Part {
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut hunks = Vec::new();
+    let mut current: Option<(ConflictHunk, Part)> = None;
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let number = i + 1;
+        if let Some(label) = marker(line, '<') {
+            current = Some((
+                ConflictHunk {
+                    start_line: number,
+                    end_line: number,
+                    ours_label: label.to_string(),
+                    theirs_label: String::new(),
+                    ours: String::new(),
+                    base: None,
+                    theirs: String::new(),
+                },
+                Part::Ours,
+            ));
+            continue;
+        }
+        let Some((hunk, part)) = current.as_mut() else {
+            continue;
+        };
+        match part {
+            Part::Ours if marker(line, '|').is_some() => {
+                hunk.base = Some(String::new());
+                *part = Part::Base;
+            }
+            Part::Ours | Part::Base if marker(line, '=').is_some_and(|label| label.is_empty()) => {
+                *part = Part::Theirs;
+            }
+            Part::Theirs if marker(line, '>').is_some() => {
+                hunk.theirs_label = marker(line, '>').unwrap_or("").to_string();
+                hunk.end_line = number;
+                if let Some((hunk, _)) = current.take() {
+                    hunks.push(hunk);
+                }
+            }
+            Part::Ours => hunk.ours.push_str(line),
+            Part::Base => hunk.base.get_or_insert_with(String::new).push_str(line),
+            Part::Theirs => hunk.theirs.push_str(line),
+        }
+    }
+    hunks
+}
+
+/// Contents of an index stage; Err(()) for binary blobs
+fn blob_text(repo: &Repository, entry: Option<&IndexEntry>) -> Result<Option<String>, ()> {
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+    let Ok(blob) = repo.find_blob(entry.id) else {
+        return Ok(None);
+    };
+    if blob.is_binary() {
+        return Err(());
+    }
+    Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+}
+
+pub fn conflict_versions(repo_path: &str, path: &str) -> Result<ConflictVersions, String> {
+    let repo = open(repo_path)?;
+    let (full_path, relative) = relative_path(&repo, path)?;
+    let index = repo.index().map_err(|e| format!("Failed to get index: {}", e))?;
+    let conflict = index
+        .conflicts()
+        .map_err(|e| format!("Failed to read conflicts: {}", e))?
+        .flatten()
+        .find(|c| {
+            [&c.our, &c.their, &c.ancestor]
+                .iter()
+                .any(|entry| entry.as_ref().is_some_and(|e| e.path == relative.as_bytes()))
+        })
+        .ok_or_else(|| format!("{} has no merge conflict", relative))?;
+
+    let versions = (
+        blob_text(&repo, conflict.ancestor.as_ref()),
+        blob_text(&repo, conflict.our.as_ref()),
+        blob_text(&repo, conflict.their.as_ref()),
+    );
+    let (Ok(base), Ok(ours), Ok(theirs)) = versions else {
+        return Ok(ConflictVersions {
+            path: relative,
+            base: None,
+            ours: None,
+            theirs: None,
+            working: None,
+            hunks: Vec::new(),
+            binary: true,
+        });
+    };
+
+    let working = fs::read(&full_path)
+        .ok()
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+    let hunks = working.as_deref().map(parse_conflicts).unwrap_or_default();
+    Ok(ConflictVersions { path: relative, base, ours, theirs, working, hunks, binary: false })
+}
+
+/// Write the merged `content` of a conflicted file. With `mark_resolved`
+/// the file is staged, which clears the conflict; content that still has
+/// conflict markers is refused then.
+pub fn write_resolution(repo_path: &str, path: &str, content: &str, mark_resolved: bool) -> Result<(), String> {
+    let repo = open(repo_path)?;
+    let (full_path, relative) = relative_path(&repo, path)?;
+    if mark_resolved && !parse_conflicts(content).is_empty() {
+        return Err(format!("{} still has conflict markers", relative));
+    }
+    fs::write(&full_path, content).map_err(|e| format!("Failed to write {}: {}", relative, e))?;
+
+    if mark_resolved {
+        let mut index = repo.index().map_err(|e| format!("Failed to get index: {}", e))?;
+        index
+            .add_path(Path::new(&relative))
+            .and_then(|_| index.write())
+            .map_err(|e| format!("Failed to stage {}: {}", relative, e))?;
+    }
+    Ok(())
+}
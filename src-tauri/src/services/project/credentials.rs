@@ -0,0 +1,76 @@
+//! Git credentials
+//!
+//! Credentials for fetches made through git2, which, unlike the git CLI,
+//! does not consult the user's setup by itself. HTTPS remotes use a token
+//! stored as the `git/<host>` secret, then git's credential helpers; SSH
+//! remotes use the SSH agent, then the default key with the `ssh/passphrase`
+//! secret.
+
+use git2::{Config, Cred, CredentialType, RemoteCallbacks};
+use std::path::PathBuf;
+
+use crate::services::secrets;
+
+/// Attempts after which a remote that keeps rejecting credentials fails
+const MAX_ATTEMPTS: usize = 3;
+
+/// Host of an `https://`, `ssh://` or scp-style `user@host:path` URL
+fn host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', ':']).next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    (!host.is_empty()).then_some(host)
+}
+
+fn default_ssh_key() -> Option<PathBuf> {
+    let ssh = dirs::home_dir()?.join(".ssh");
+    ["id_ed25519", "id_ecdsa", "id_rsa"]
+        .iter()
+        .map(|name| ssh.join(name))
+        .find(|path| path.exists())
+}
+
+fn credentials(url: &str, username: Option<&str>, allowed: CredentialType, attempt: usize) -> Result<Cred, git2::Error> {
+    if attempt > MAX_ATTEMPTS {
+        return Err(git2::Error::from_str(&format!("Authentication to {} failed", url)));
+    }
+    let user = username.unwrap_or("git");
+
+    if allowed.contains(CredentialType::SSH_KEY) {
+        if attempt == 1 {
+            return Cred::ssh_key_from_agent(user);
+        }
+        if let Some(key) = default_ssh_key() {
+            let passphrase = secrets::get_or_none("ssh", "passphrase");
+            return Cred::ssh_key(user, None, &key, passphrase.as_deref());
+        }
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        let token = if attempt == 1 { host(url).and_then(|host| secrets::get_or_none("git", host)) } else { None };
+        if let Some(token) = token {
+            return Cred::userpass_plaintext(username.unwrap_or("x-access-token"), &token);
+        }
+        if let Ok(config) = Config::open_default() {
+            if let Ok(cred) = Cred::credential_helper(&config, url, username) {
+                return Ok(cred);
+            }
+        }
+    }
+    if allowed.contains(CredentialType::DEFAULT) {
+        return Cred::default();
+    }
+    Err(git2::Error::from_str(&format!("No credentials available for {}", url)))
+}
+
+/// Add the credentials callback to the callbacks of a fetch
+pub fn attach(callbacks: &mut RemoteCallbacks<'_>) {
+    let mut attempt = 0;
+    callbacks.credentials(move |url, username, allowed| {
+        // SSH asks for the user name first when the URL has none
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username.unwrap_or("git"));
+        }
+        attempt += 1;
+        credentials(url, username, allowed, attempt)
+    });
+}
@@ -0,0 +1,113 @@
+//! Unsaved buffer drafts
+//!
+//! The editor periodically hands over the contents of modified buffers,
+//! which are kept in ~/.ctr/drafts, one JSON file per path, until the file
+//! is saved or the draft discarded. After a crash the drafts left behind
+//! are offered for recovery.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::utils::fs_utils::{ctr_dir, save_json};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub path: String,
+    pub content: String,
+    /// Unix timestamp in milliseconds
+    pub saved_at: u64,
+    /// Modification time of the file on disk when the draft was taken, to
+    /// tell whether it was changed by something else since
+    #[serde(default)]
+    pub disk_modified: Option<u64>,
+}
+
+/// A draft as listed for recovery, without its content
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftInfo {
+    pub path: String,
+    pub saved_at: u64,
+    pub size: usize,
+    /// Whether the file on disk changed after the draft was taken
+    pub disk_changed: bool,
+    /// Whether the file no longer exists
+    pub file_missing: bool,
+}
+
+fn drafts_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("drafts");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create drafts directory: {}", e))?;
+    Ok(dir)
+}
+
+fn draft_path(path: &str) -> Result<PathBuf, String> {
+    let digest = Sha256::digest(path.as_bytes());
+    Ok(drafts_dir()?.join(format!("{}.json", hex::encode(&digest[..12]))))
+}
+
+fn disk_modified(path: &str) -> Option<u64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64)
+}
+
+fn info(draft: &Draft) -> DraftInfo {
+    let file_missing = !Path::new(&draft.path).exists();
+    DraftInfo {
+        path: draft.path.clone(),
+        saved_at: draft.saved_at,
+        size: draft.content.len(),
+        disk_changed: !file_missing && disk_modified(&draft.path) != draft.disk_modified,
+        file_missing,
+    }
+}
+
+fn read(file: &Path) -> Option<Draft> {
+    serde_json::from_str(&fs::read_to_string(file).ok()?).ok()
+}
+
+/// Keep the unsaved contents of a file. A buffer that matches the file on
+/// disk needs no draft, so any previous one is dropped instead.
+pub fn save(path: &str, content: String) -> Result<(), String> {
+    if fs::read_to_string(path).is_ok_and(|on_disk| on_disk == content) {
+        discard(path)?;
+        return Ok(());
+    }
+    let draft = Draft {
+        path: path.to_string(),
+        content,
+        saved_at: now_millis(),
+        disk_modified: disk_modified(path),
+    };
+    save_json(&draft_path(path)?, &draft)
+}
+
+/// Every draft left behind, most recent first
+pub fn list() -> Result<Vec<DraftInfo>, String> {
+    let entries = fs::read_dir(drafts_dir()?).map_err(|e| format!("Failed to read drafts directory: {}", e))?;
+    let mut drafts: Vec<DraftInfo> = entries
+        .flatten()
+        .filter_map(|entry| read(&entry.path()))
+        .map(|draft| info(&draft))
+        .collect();
+    drafts.sort_by_key(|d| std::cmp::Reverse(d.saved_at));
+    Ok(drafts)
+}
+
+/// The draft of a file, with its content
+pub fn recover(path: &str) -> Result<Draft, String> {
+    read(&draft_path(path)?).ok_or_else(|| format!("No draft for {}", path))
+}
+
+/// Drop the draft of a file; returns whether there was one
+pub fn discard(path: &str) -> Result<bool, String> {
+    let file = draft_path(path)?;
+    if !file.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&file).map_err(|e| format!("Failed to discard draft: {}", e))?;
+    Ok(true)
+}
@@ -1,5 +1,18 @@
 pub mod vcs;
 pub mod walker;
 pub mod watcher;
-
-
+pub mod session;
+pub mod drafts;
+pub mod registry;
+pub mod templates;
+pub mod todos;
+pub mod batch;
+pub mod permissions;
+pub mod submodules;
+pub mod signing;
+pub mod clone;
+pub mod conflicts;
+pub mod worktrees;
+pub mod roots;
+pub mod credentials;
+pub mod trust;
@@ -0,0 +1,124 @@
+//! File permissions
+//!
+//! chmod-style permission changes for the editor, e.g. making an exploit
+//! script executable. Modes are given in octal ("755") or symbolically
+//! ("+x", "u+x,go-w", "a=r"). Outside Unix only the read-only flag exists,
+//! so only changes to write permission have an effect there.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PermissionInfo {
+    /// Unix permission bits, None outside Unix
+    pub mode: Option<u32>,
+    /// The bits as `rwxr-xr-x`
+    pub symbolic: Option<String>,
+    pub readonly: bool,
+    pub executable: bool,
+}
+
+pub fn describe(metadata: &fs::Metadata) -> PermissionInfo {
+    let readonly = metadata.permissions().readonly();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = metadata.permissions().mode() & 0o7777;
+        PermissionInfo {
+            mode: Some(mode),
+            symbolic: Some(symbolic(mode)),
+            readonly,
+            executable: metadata.is_file() && mode & 0o111 != 0,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        PermissionInfo { mode: None, symbolic: None, readonly, executable: false }
+    }
+}
+
+fn symbolic(mode: u32) -> String {
+    (0..9)
+        .map(|i| {
+            let bit = 0o400 >> i;
+            if mode & bit == 0 {
+                '-'
+            } else {
+                ['r', 'w', 'x'][i % 3]
+            }
+        })
+        .collect()
+}
+
+/// Apply `spec` to `current`, the existing permission bits
+pub fn apply_mode(current: u32, spec: &str) -> Result<u32, String> {
+    let spec = spec.trim();
+    if !spec.is_empty() && spec.chars().all(|c| c.is_digit(8)) {
+        return u32::from_str_radix(spec, 8)
+            .ok()
+            .filter(|m| *m <= 0o7777)
+            .ok_or_else(|| format!("Invalid mode: {}", spec));
+    }
+
+    let mut mode = current;
+    for clause in spec.split(',') {
+        let op_at = clause
+            .find(['+', '-', '='])
+            .ok_or_else(|| format!("Invalid mode: {}", spec))?;
+        let (who, rest) = clause.split_at(op_at);
+        let (op, perms) = rest.split_at(1);
+
+        let mut who_mask = 0;
+        for c in who.chars() {
+            who_mask |= match c {
+                'u' => 0o700,
+                'g' => 0o070,
+                'o' => 0o007,
+                'a' => 0o777,
+                _ => return Err(format!("Invalid mode: {}", spec)),
+            };
+        }
+        if who_mask == 0 {
+            who_mask = 0o777;
+        }
+
+        let mut perm_bits = 0;
+        for c in perms.chars() {
+            perm_bits |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                _ => return Err(format!("Invalid mode: {}", spec)),
+            };
+        }
+        let bits = who_mask & perm_bits;
+        mode = match op {
+            "+" => mode | bits,
+            "-" => mode & !bits,
+            _ => (mode & !who_mask) | bits,
+        };
+    }
+    Ok(mode)
+}
+
+/// Change the permissions of `path` as `chmod` would
+pub fn set(path: &Path, spec: &str) -> Result<PermissionInfo, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    let mut permissions = metadata.permissions();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(apply_mode(permissions.mode() & 0o7777, spec)?);
+    }
+    #[cfg(not(unix))]
+    {
+        let current = if permissions.readonly() { 0o555 } else { 0o777 };
+        permissions.set_readonly(apply_mode(current, spec)? & 0o222 == 0);
+    }
+
+    fs::set_permissions(path, permissions).map_err(|e| format!("Failed to set permissions: {}", e))?;
+    let metadata = fs::metadata(path).map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    Ok(describe(&metadata))
+}
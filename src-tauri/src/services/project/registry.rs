@@ -0,0 +1,227 @@
+//! Recent workspaces
+//!
+//! Every workspace folder the user opens is registered in
+//! ~/.ctr/workspaces.json with when it was last opened, whether it is pinned
+//! and a little metadata gathered on open (frameworks in use, lab setups),
+//! so the welcome screen can list them without touching each folder.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::langdetect::{self, LanguageId};
+use crate::services::security::dependencies;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+/// Unpinned workspaces kept in the list; older ones are forgotten
+const MAX_RECENT: usize = 30;
+/// How deep lab setups are looked for below the workspace root
+const LAB_SEARCH_DEPTH: usize = 2;
+
+/// Dependencies that identify a framework, by ecosystem
+const FRAMEWORK_DEPENDENCIES: &[(&str, &str, &str)] = &[
+    ("pip", "flask", "Flask"),
+    ("pip", "django", "Django"),
+    ("pip", "fastapi", "FastAPI"),
+    ("npm", "express", "Express"),
+    ("npm", "next", "Next.js"),
+    ("npm", "react", "React"),
+    ("npm", "vue", "Vue"),
+    ("npm", "@angular/core", "Angular"),
+    ("rust", "actix-web", "Actix Web"),
+    ("rust", "axum", "Axum"),
+    ("rust", "rocket", "Rocket"),
+];
+
+/// Manifests of other ecosystems and the text that identifies a framework
+const FRAMEWORK_MARKERS: &[(&str, &str, &str)] = &[
+    ("pom.xml", "spring-boot", "Spring Boot"),
+    ("build.gradle", "spring-boot", "Spring Boot"),
+    ("go.mod", "github.com/gin-gonic/gin", "Gin"),
+    ("composer.json", "laravel/framework", "Laravel"),
+    ("Gemfile", "rails", "Rails"),
+];
+
+const COMPOSE_FILES: &[&str] = &["docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceMetadata {
+    #[serde(default)]
+    pub frameworks: Vec<String>,
+    /// Compose files and Dockerfiles, relative to the workspace
+    #[serde(default)]
+    pub lab_configs: Vec<String>,
+    #[serde(default)]
+    pub is_git_repo: bool,
+    /// Unix timestamp in milliseconds
+    #[serde(default)]
+    pub detected_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentWorkspace {
+    pub path: String,
+    pub name: String,
+    /// Unix timestamp in milliseconds
+    pub last_opened: u64,
+    #[serde(default)]
+    pub open_count: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub metadata: WorkspaceMetadata,
+    /// Whether the folder still exists; worked out when listing
+    #[serde(skip_deserializing, default)]
+    pub exists: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    workspaces: Vec<RecentWorkspace>,
+}
+
+fn registry_path() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("workspaces.json"))
+}
+
+fn load() -> Result<Registry, String> {
+    Ok(load_json(&registry_path()?))
+}
+
+fn save(registry: &Registry) -> Result<(), String> {
+    save_json(&registry_path()?, registry)
+}
+
+fn lab_configs(dir: &Path, root: &Path, depth: usize, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if path.is_dir() {
+            if depth > 0 && !name.starts_with('.') && name != "node_modules" {
+                lab_configs(&path, root, depth - 1, out);
+            }
+        } else if COMPOSE_FILES.contains(&name) || langdetect::from_file_name(name) == Some(LanguageId::Dockerfile) {
+            out.push(path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string());
+        }
+    }
+}
+
+/// Work out the frameworks and lab setups of a workspace
+pub fn detect_metadata(root: &Path) -> WorkspaceMetadata {
+    let mut frameworks: Vec<String> = Vec::new();
+    let mut add = |framework: &str| {
+        if !frameworks.iter().any(|f| f == framework) {
+            frameworks.push(framework.to_string());
+        }
+    };
+    for dependency in dependencies::collect_dependencies(root) {
+        let name = dependency.name.to_ascii_lowercase();
+        if let Some((_, _, framework)) = FRAMEWORK_DEPENDENCIES
+            .iter()
+            .find(|(ecosystem, dep, _)| *ecosystem == dependency.ecosystem && *dep == name)
+        {
+            add(framework);
+        }
+    }
+    for (manifest, marker, framework) in FRAMEWORK_MARKERS {
+        if fs::read_to_string(root.join(manifest)).is_ok_and(|content| content.contains(marker)) {
+            add(framework);
+        }
+    }
+
+    let mut configs = Vec::new();
+    lab_configs(root, root, LAB_SEARCH_DEPTH, &mut configs);
+    WorkspaceMetadata {
+        frameworks,
+        lab_configs: configs,
+        is_git_repo: root.join(".git").exists(),
+        detected_at: now_millis(),
+    }
+}
+
+/// Record that a workspace was opened, refreshing its metadata
+pub fn register(path: &str) -> Result<RecentWorkspace, String> {
+    let root = Path::new(path);
+    if !root.is_dir() {
+        return Err("Workspace path does not exist".to_string());
+    }
+    let metadata = detect_metadata(root);
+
+    let mut registry = load()?;
+    let index = match registry.workspaces.iter().position(|w| w.path == path) {
+        Some(index) => index,
+        None => {
+            registry.workspaces.push(RecentWorkspace {
+                path: path.to_string(),
+                name: root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string()),
+                last_opened: 0,
+                open_count: 0,
+                pinned: false,
+                metadata: WorkspaceMetadata::default(),
+                exists: true,
+            });
+            registry.workspaces.len() - 1
+        }
+    };
+    let entry = &mut registry.workspaces[index];
+    entry.last_opened = now_millis();
+    entry.open_count += 1;
+    entry.metadata = metadata;
+    entry.exists = true;
+    let entry = entry.clone();
+
+    // Forget the oldest unpinned workspaces beyond the limit
+    registry.workspaces.sort_by_key(|w| std::cmp::Reverse(w.last_opened));
+    let mut unpinned = 0;
+    registry.workspaces.retain(|w| {
+        if w.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_RECENT
+    });
+    save(&registry)?;
+    Ok(entry)
+}
+
+/// Pinned workspaces first, then the most recently opened
+pub fn list(limit: Option<usize>) -> Result<Vec<RecentWorkspace>, String> {
+    let mut workspaces = load()?.workspaces;
+    for workspace in &mut workspaces {
+        workspace.exists = Path::new(&workspace.path).is_dir();
+    }
+    workspaces.sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| b.last_opened.cmp(&a.last_opened)));
+    if let Some(limit) = limit {
+        workspaces.truncate(limit);
+    }
+    Ok(workspaces)
+}
+
+pub fn set_pinned(path: &str, pinned: bool) -> Result<(), String> {
+    let mut registry = load()?;
+    let workspace = registry
+        .workspaces
+        .iter_mut()
+        .find(|w| w.path == path)
+        .ok_or_else(|| format!("{} is not a recent workspace", path))?;
+    workspace.pinned = pinned;
+    save(&registry)
+}
+
+/// Drop a workspace from the list; returns whether it was there
+pub fn remove(path: &str) -> Result<bool, String> {
+    let mut registry = load()?;
+    let before = registry.workspaces.len();
+    registry.workspaces.retain(|w| w.path != path);
+    if registry.workspaces.len() == before {
+        return Ok(false);
+    }
+    save(&registry)?;
+    Ok(true)
+}
@@ -0,0 +1,116 @@
+//! Multi-root workspaces
+//!
+//! A workspace is opened by its primary root, the path commands receive as
+//! `workspace_root`, and can hold further roots: the other services of a
+//! monorepo lab, a second target, a checked-out exploit kit. Extra roots are
+//! kept in the primary root's `.ctr/roots.json`. Indexing, scans, search and
+//! the findings store (which stays with the primary root) cover every root
+//! and say which root each result belongs to.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::utils::fs_utils::{load_json, save_json, workspace_ctr_dir};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRoot {
+    /// Unique within the workspace; the folder name unless given
+    pub name: String,
+    pub path: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RootsFile {
+    #[serde(default)]
+    roots: Vec<WorkspaceRoot>,
+}
+
+fn store_path(workspace_root: &str) -> Result<std::path::PathBuf, String> {
+    Ok(workspace_ctr_dir(workspace_root)?.join("roots.json"))
+}
+
+fn normalize(path: &str) -> String {
+    let trimmed = path.trim().trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() { path.trim().to_string() } else { trimmed.to_string() }
+}
+
+fn folder_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Whether one of the paths contains the other
+fn overlaps(a: &str, b: &str) -> bool {
+    Path::new(a).starts_with(b) || Path::new(b).starts_with(a)
+}
+
+/// Every root of the workspace, the primary one first
+pub fn list(workspace_root: &str) -> Result<Vec<WorkspaceRoot>, String> {
+    let primary = normalize(workspace_root);
+    let stored: RootsFile = load_json(&store_path(workspace_root)?);
+    let mut roots = vec![WorkspaceRoot { name: folder_name(&primary), path: primary, primary: true }];
+    roots.extend(stored.roots.into_iter().map(|r| WorkspaceRoot { primary: false, ..r }));
+    Ok(roots)
+}
+
+/// Add a folder as a root of the workspace. Roots may not contain each
+/// other, as their files would be scanned twice.
+pub fn add(workspace_root: &str, path: &str, name: Option<&str>) -> Result<WorkspaceRoot, String> {
+    let path = normalize(path);
+    if !Path::new(&path).is_dir() {
+        return Err(format!("{} is not a directory", path));
+    }
+    let roots = list(workspace_root)?;
+    if let Some(existing) = roots.iter().find(|r| overlaps(&r.path, &path)) {
+        return Err(format!("{} overlaps the workspace root {}", path, existing.name));
+    }
+
+    let base = name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| folder_name(&path));
+    let mut name = base.clone();
+    let mut n = 2;
+    while roots.iter().any(|r| r.name == name) {
+        name = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let root = WorkspaceRoot { name, path, primary: false };
+    let mut extra: Vec<WorkspaceRoot> = roots.into_iter().filter(|r| !r.primary).collect();
+    extra.push(root.clone());
+    save_json(&store_path(workspace_root)?, &RootsFile { roots: extra })?;
+    Ok(root)
+}
+
+/// Remove a root by name or path; the primary root cannot be removed
+pub fn remove(workspace_root: &str, root: &str) -> Result<bool, String> {
+    let root = normalize(root);
+    let roots = list(workspace_root)?;
+    if roots.iter().any(|r| r.primary && (r.name == root || r.path == root)) {
+        return Err("The primary root cannot be removed".to_string());
+    }
+    let before = roots.len();
+    let extra: Vec<WorkspaceRoot> = roots
+        .into_iter()
+        .filter(|r| !r.primary && r.name != root && r.path != root)
+        .collect();
+    if extra.len() + 1 == before {
+        return Ok(false);
+    }
+    save_json(&store_path(workspace_root)?, &RootsFile { roots: extra })?;
+    Ok(true)
+}
+
+/// The root a file belongs to; relative paths are relative to the primary root
+pub fn root_of<'a>(roots: &'a [WorkspaceRoot], file: &str) -> Option<&'a WorkspaceRoot> {
+    if Path::new(file).is_relative() {
+        return roots.iter().find(|r| r.primary);
+    }
+    roots.iter().find(|r| Path::new(file).starts_with(&r.path))
+}
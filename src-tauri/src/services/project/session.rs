@@ -0,0 +1,142 @@
+//! Workspace session state
+//!
+//! Remembers what the user had open in a workspace (editors with cursor
+//! positions, terminals and their directories, layout hints, the last scan)
+//! in <workspace>/.ctr/session.json so reopening the project restores it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs_utils::{save_json, workspace_ctr_dir};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenFileState {
+    /// Relative to the workspace root when inside it
+    pub path: String,
+    #[serde(default)]
+    pub line: u32,
+    #[serde(default)]
+    pub column: u32,
+    #[serde(default)]
+    pub scroll_top: f64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalState {
+    #[serde(default)]
+    pub title: String,
+    pub cwd: String,
+    #[serde(default)]
+    pub shell: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Where the results of the last scan live, so they can be reloaded lazily
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanPointer {
+    /// e.g. "security_scan", "prover", "dependencies"
+    pub kind: String,
+    pub location: String,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub issue_count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSession {
+    #[serde(default)]
+    pub open_files: Vec<OpenFileState>,
+    #[serde(default)]
+    pub active_file: Option<String>,
+    #[serde(default)]
+    pub terminals: Vec<TerminalState>,
+    /// Free-form UI layout (panel sizes, visible views); owned by the frontend
+    #[serde(default)]
+    pub layout: Value,
+    #[serde(default)]
+    pub last_scan: Option<ScanPointer>,
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+fn session_path(workspace_root: &str) -> PathBuf {
+    Path::new(workspace_root).join(".ctr").join("session.json")
+}
+
+/// Store paths relative to the workspace so the project can be moved
+fn relativize(workspace_root: &Path, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(workspace_root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn absolutize(workspace_root: &Path, path: &str) -> String {
+    let p = Path::new(path);
+    if p.is_absolute() {
+        path.to_string()
+    } else {
+        workspace_root.join(p).to_string_lossy().to_string()
+    }
+}
+
+pub fn save(workspace_root: &str, mut session: WorkspaceSession) -> Result<(), String> {
+    let root = Path::new(workspace_root);
+    for file in session.open_files.iter_mut() {
+        file.path = relativize(root, &file.path);
+    }
+    session.active_file = session.active_file.map(|p| relativize(root, &p));
+    for terminal in session.terminals.iter_mut() {
+        terminal.cwd = relativize(root, &terminal.cwd);
+    }
+    session.saved_at = now_millis();
+
+    workspace_ctr_dir(workspace_root)?;
+    save_json(&session_path(workspace_root), &session)
+}
+
+/// The saved session with absolute paths; files and directories that no longer
+/// exist are dropped
+pub fn load(workspace_root: &str) -> Result<Option<WorkspaceSession>, String> {
+    let path = session_path(workspace_root);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let mut session: WorkspaceSession = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse session state: {}", e))?;
+
+    let root = Path::new(workspace_root);
+    session.open_files = session
+        .open_files
+        .into_iter()
+        .map(|mut f| {
+            f.path = absolutize(root, &f.path);
+            f
+        })
+        .filter(|f| Path::new(&f.path).is_file())
+        .collect();
+    session.active_file = session
+        .active_file
+        .map(|p| absolutize(root, &p))
+        .filter(|p| session.open_files.iter().any(|f| &f.path == p));
+    for terminal in session.terminals.iter_mut() {
+        terminal.cwd = absolutize(root, &terminal.cwd);
+        if !Path::new(&terminal.cwd).is_dir() {
+            terminal.cwd = workspace_root.to_string();
+        }
+    }
+    Ok(Some(session))
+}
+
+pub fn clear(workspace_root: &str) -> Result<(), String> {
+    let path = session_path(workspace_root);
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to clear session state: {}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+//! Commit signing
+//!
+//! Signs commits with the key configured for git (user.signingkey,
+//! gpg.format, gpg.program, gpg.ssh.program) by running gpg or
+//! `ssh-keygen -Y sign` on the commit buffer, the way git does, and writes
+//! the signed commit through git2. Verification of existing commits is left
+//! to the system git, which knows the user's keyrings and allowed signers.
+
+use git2::{Commit, Oid, Repository, Signature, Tree};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SigningConfig {
+    /// "openpgp", "ssh" or "x509"
+    pub format: String,
+    /// user.signingkey; for ssh a key file or a `key::` literal
+    pub key: Option<String>,
+    /// The program run to sign
+    pub program: String,
+    /// commit.gpgsign: whether commits are signed unless asked otherwise
+    pub sign_by_default: bool,
+    /// Whether the signing program could be run
+    pub program_available: bool,
+}
+
+/// Signature state of a commit, from git's `%G?`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSignature {
+    /// "good", "bad", "unknown_validity", "expired_signature",
+    /// "expired_key", "revoked_key", "missing_key" or, when not verified,
+    /// "unverified"
+    pub status: String,
+    pub signer: Option<String>,
+    pub key: Option<String>,
+}
+
+/// Whether `program` can be started; its exit status does not matter, as
+/// ssh-keygen has no --version
+fn program_available(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+pub fn signing_config(repo: &Repository) -> Result<SigningConfig, String> {
+    let config = repo.config().map_err(|e| format!("Failed to read git config: {}", e))?;
+    let format = config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string());
+    let program = config
+        .get_string(&format!("gpg.{}.program", format))
+        .or_else(|_| config.get_string("gpg.program"))
+        .unwrap_or_else(|_| {
+            match format.as_str() {
+                "ssh" => "ssh-keygen",
+                "x509" => "gpgsm",
+                _ => "gpg",
+            }
+            .to_string()
+        });
+    Ok(SigningConfig {
+        key: config.get_string("user.signingkey").ok().filter(|k| !k.trim().is_empty()),
+        sign_by_default: config.get_bool("commit.gpgsign").unwrap_or(false),
+        program_available: program_available(&program),
+        format,
+        program,
+    })
+}
+
+fn run_signer(program: &str, args: &[&str], input: &[u8]) -> Result<Vec<u8>, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    child
+        .stdin
+        .take()
+        .ok_or("Failed to open signer input")?
+        .write_all(input)
+        .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "{} failed to sign: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Key file for ssh-keygen; a `key::` literal is written to a temporary file
+fn ssh_key_file(key: &str) -> Result<(PathBuf, bool), String> {
+    match key.strip_prefix("key::") {
+        Some(literal) => {
+            let path = std::env::temp_dir().join(format!("ctr-signing-{}.pub", uuid::Uuid::new_v4()));
+            std::fs::write(&path, literal.trim()).map_err(|e| format!("Failed to write signing key: {}", e))?;
+            Ok((path, true))
+        }
+        None => {
+            let path = match key.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().ok_or("Could not find home directory")?.join(rest),
+                None => PathBuf::from(key),
+            };
+            Ok((path, false))
+        }
+    }
+}
+
+/// Detached signature of `buffer` in the configured format
+fn sign_buffer(config: &SigningConfig, committer: &Signature, buffer: &str) -> Result<String, String> {
+    let signature = match config.format.as_str() {
+        "ssh" => {
+            let key = config.key.as_deref().ok_or("No SSH signing key configured (user.signingkey)")?;
+            let (key_file, temporary) = ssh_key_file(key)?;
+            let key_path = key_file.to_string_lossy().to_string();
+            let result = run_signer(&config.program, &["-Y", "sign", "-n", "git", "-f", &key_path], buffer.as_bytes());
+            if temporary {
+                let _ = std::fs::remove_file(&key_file);
+            }
+            result?
+        }
+        _ => {
+            // Like git, sign as the committer when no key is configured
+            let identity = config.key.clone().unwrap_or_else(|| {
+                format!("{} <{}>", committer.name().unwrap_or(""), committer.email().unwrap_or(""))
+            });
+            run_signer(&config.program, &["--status-fd=2", "-bsau", &identity], buffer.as_bytes())?
+        }
+    };
+    String::from_utf8(signature).map_err(|_| "Signature is not valid UTF-8".to_string())
+}
+
+/// Create a signed commit of `tree` on top of `parents` and move HEAD (or
+/// the branch it points to) to it
+pub fn commit_signed(
+    repo: &Repository,
+    signature: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) -> Result<Oid, String> {
+    let config = signing_config(repo)?;
+    if config.format == "x509" {
+        return Err("x509 commit signing is not supported".to_string());
+    }
+    let buffer = repo
+        .commit_create_buffer(signature, signature, message, tree, parents)
+        .map_err(|e| format!("Failed to create commit: {}", e))?;
+    let buffer = buffer.as_str().ok_or("Commit is not valid UTF-8")?.to_string();
+    let signed = sign_buffer(&config, signature, &buffer)?;
+    let oid = repo
+        .commit_signed(&buffer, &signed, None)
+        .map_err(|e| format!("Failed to create signed commit: {}", e))?;
+
+    let head = repo.find_reference("HEAD").map_err(|e| format!("Failed to get HEAD: {}", e))?;
+    let summary = message.lines().next().unwrap_or("");
+    match head.symbolic_target() {
+        Some(branch) => {
+            repo.reference(branch, oid, true, &format!("commit: {}", summary))
+                .map_err(|e| format!("Failed to update {}: {}", branch, e))?;
+        }
+        None => repo.set_head_detached(oid).map_err(|e| format!("Failed to update HEAD: {}", e))?,
+    }
+    Ok(oid)
+}
+
+/// Whether a commit carries a signature, without checking it
+pub fn is_signed(repo: &Repository, oid: Oid) -> bool {
+    repo.extract_signature(&oid, None).is_ok()
+}
+
+fn status_name(code: &str) -> &'static str {
+    match code {
+        "G" => "good",
+        "B" => "bad",
+        "U" => "unknown_validity",
+        "X" => "expired_signature",
+        "Y" => "expired_key",
+        "R" => "revoked_key",
+        "E" => "missing_key",
+        _ => "none",
+    }
+}
+
+/// Verify the signatures of the `limit` commits from HEAD with the system
+/// git; commit hash -> signature, for signed commits only
+pub fn verify_log(repo_path: &str, limit: usize) -> Result<HashMap<String, CommitSignature>, String> {
+    let output = Command::new("git")
+        .args(["log", "-n", &limit.to_string(), "--format=%H%x00%G?%x00%GS%x00%GK"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git log: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let non_empty = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let hash = fields.next()?.to_string();
+            let status = status_name(fields.next()?);
+            if status == "none" {
+                return None;
+            }
+            let signer = fields.next().and_then(non_empty);
+            let key = fields.next().and_then(non_empty);
+            Some((hash, CommitSignature { status: status.to_string(), signer, key }))
+        })
+        .collect())
+}
@@ -0,0 +1,245 @@
+//! Git submodules
+//!
+//! Lists the submodules of a repository with their state, initializes and
+//! updates them (cloning the missing ones) and adds new ones, reporting
+//! fetch progress so large tool or target clones do not look stuck.
+
+use git2::{FetchOptions, RemoteCallbacks, Repository, SubmoduleIgnore, SubmoduleStatus, SubmoduleUpdateOptions};
+use serde::Serialize;
+use std::path::Path;
+
+use super::credentials;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    /// Path relative to the repository root
+    pub path: String,
+    pub url: Option<String>,
+    pub branch: Option<String>,
+    /// Commit recorded by the parent repository's HEAD
+    pub head_id: Option<String>,
+    /// Commit checked out in the submodule's working directory
+    pub workdir_id: Option<String>,
+    /// "uninitialized", "not_cloned", "modified" (different commit checked
+    /// out), "dirty" (local changes) or "clean"
+    pub state: String,
+}
+
+/// Payload of the `git-submodule-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmoduleProgress {
+    pub name: String,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+    /// Set on the last event of a submodule
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmoduleUpdateResult {
+    pub updated: Vec<String>,
+    /// Submodule name and error, for those that failed
+    pub failed: Vec<(String, String)>,
+}
+
+fn open(repo_path: &str) -> Result<Repository, String> {
+    Repository::open(repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn state(status: SubmoduleStatus) -> &'static str {
+    if status.contains(SubmoduleStatus::WD_UNINITIALIZED) && !status.contains(SubmoduleStatus::IN_CONFIG) {
+        "uninitialized"
+    } else if status.contains(SubmoduleStatus::WD_UNINITIALIZED) {
+        "not_cloned"
+    } else if status.contains(SubmoduleStatus::WD_MODIFIED) {
+        "modified"
+    } else if status.intersects(
+        SubmoduleStatus::WD_INDEX_MODIFIED | SubmoduleStatus::WD_WD_MODIFIED | SubmoduleStatus::WD_UNTRACKED,
+    ) {
+        "dirty"
+    } else {
+        "clean"
+    }
+}
+
+pub fn list(repo_path: &str) -> Result<Vec<SubmoduleInfo>, String> {
+    let repo = open(repo_path)?;
+    let submodules = repo.submodules().map_err(|e| format!("Failed to list submodules: {}", e))?;
+    Ok(submodules
+        .iter()
+        .map(|submodule| {
+            let name = submodule.name().unwrap_or("").to_string();
+            let status = repo
+                .submodule_status(&name, SubmoduleIgnore::Unspecified)
+                .unwrap_or(SubmoduleStatus::WD_UNINITIALIZED);
+            SubmoduleInfo {
+                path: submodule.path().to_string_lossy().replace('\\', "/"),
+                url: submodule.url().map(str::to_string),
+                branch: submodule.branch().map(str::to_string),
+                head_id: submodule.head_id().map(|id| id.to_string()),
+                workdir_id: submodule.workdir_id().map(|id| id.to_string()),
+                state: state(status).to_string(),
+                name,
+            }
+        })
+        .collect())
+}
+
+/// Update options that report fetch progress for `name`, at most once per
+/// percent of objects received
+fn update_options<'a>(name: &'a str, progress: &'a mut dyn FnMut(SubmoduleProgress)) -> SubmoduleUpdateOptions<'a> {
+    let mut last_percent = None;
+    let mut callbacks = RemoteCallbacks::new();
+    credentials::attach(&mut callbacks);
+    callbacks.transfer_progress(move |stats| {
+        let total = stats.total_objects();
+        let percent = (stats.received_objects() + stats.indexed_objects()) * 50 / total.max(1);
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            progress(SubmoduleProgress {
+                name: name.to_string(),
+                received_objects: stats.received_objects(),
+                indexed_objects: stats.indexed_objects(),
+                total_objects: total,
+                received_bytes: stats.received_bytes(),
+                done: false,
+                error: None,
+            });
+        }
+        true
+    });
+    let mut fetch = FetchOptions::new();
+    fetch.remote_callbacks(callbacks);
+    let mut options = SubmoduleUpdateOptions::new();
+    options.fetch(fetch);
+    options
+}
+
+fn finished(name: &str, error: Option<String>) -> SubmoduleProgress {
+    SubmoduleProgress {
+        name: name.to_string(),
+        received_objects: 0,
+        indexed_objects: 0,
+        total_objects: 0,
+        received_bytes: 0,
+        done: true,
+        error,
+    }
+}
+
+fn update_repo(
+    repo: &Repository,
+    prefix: &str,
+    names: Option<&[String]>,
+    recursive: bool,
+    progress: &mut dyn FnMut(SubmoduleProgress),
+    result: &mut SubmoduleUpdateResult,
+) -> Result<(), String> {
+    let submodules = repo.submodules().map_err(|e| format!("Failed to list submodules: {}", e))?;
+    for mut submodule in submodules {
+        let name = submodule.name().unwrap_or("").to_string();
+        if names.is_some_and(|names| !names.contains(&name)) {
+            continue;
+        }
+        let full_name = format!("{}{}", prefix, name);
+
+        let outcome = {
+            let mut options = update_options(&full_name, progress);
+            submodule.update(true, Some(&mut options))
+        };
+        match outcome {
+            Ok(()) => {
+                progress(finished(&full_name, None));
+                result.updated.push(full_name.clone());
+                if recursive {
+                    let nested = submodule
+                        .open()
+                        .map_err(|e| format!("Failed to open submodule {}: {}", full_name, e))
+                        .and_then(|sub_repo| {
+                            update_repo(&sub_repo, &format!("{}/", full_name), None, true, progress, result)
+                        });
+                    if let Err(e) = nested {
+                        result.failed.push((full_name, e));
+                    }
+                }
+            }
+            Err(e) => {
+                let error = e.message().to_string();
+                progress(finished(&full_name, Some(error.clone())));
+                result.failed.push((full_name, error));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Initialize and update submodules, cloning those not cloned yet. `names`
+/// limits the update to some submodules; nested submodules of the updated
+/// ones are updated too with `recursive`. A failing submodule does not stop
+/// the others.
+pub fn init_update(
+    repo_path: &str,
+    names: Option<&[String]>,
+    recursive: bool,
+    progress: &mut dyn FnMut(SubmoduleProgress),
+) -> Result<SubmoduleUpdateResult, String> {
+    let repo = open(repo_path)?;
+    let mut result = SubmoduleUpdateResult { updated: Vec::new(), failed: Vec::new() };
+    update_repo(&repo, "", names, recursive, progress, &mut result)?;
+    Ok(result)
+}
+
+/// Add `url` as a submodule at `path` (relative to the repository root) and
+/// clone it; the new .gitmodules entry and gitlink are staged
+pub fn add(
+    repo_path: &str,
+    url: &str,
+    path: &str,
+    branch: Option<&str>,
+    progress: &mut dyn FnMut(SubmoduleProgress),
+) -> Result<SubmoduleInfo, String> {
+    let mut repo = open(repo_path)?;
+    let relative = Path::new(path);
+    if relative.is_absolute() {
+        return Err("Submodule path must be relative to the repository".to_string());
+    }
+    if Path::new(repo_path).join(relative).exists() {
+        return Err(format!("{} already exists", path));
+    }
+
+    {
+        let mut submodule = repo
+            .submodule(url, relative, true)
+            .map_err(|e| format!("Failed to add submodule: {}", e))?;
+        let cloned = {
+            let mut options = update_options(path, progress);
+            submodule.clone(Some(&mut options))
+        };
+        if let Err(e) = cloned {
+            progress(finished(path, Some(e.message().to_string())));
+            return Err(format!("Failed to clone submodule: {}", e));
+        }
+        submodule
+            .add_finalize()
+            .map_err(|e| format!("Failed to finalize submodule: {}", e))?;
+    }
+    if let Some(branch) = branch {
+        repo.submodule_set_branch(path, branch)
+            .map_err(|e| format!("Failed to set submodule branch: {}", e))?;
+        let mut index = repo.index().map_err(|e| format!("Failed to get index: {}", e))?;
+        index
+            .add_path(Path::new(".gitmodules"))
+            .and_then(|_| index.write())
+            .map_err(|e| format!("Failed to stage .gitmodules: {}", e))?;
+    }
+    progress(finished(path, None));
+
+    list(repo_path)?
+        .into_iter()
+        .find(|s| s.path == path.replace('\\', "/"))
+        .ok_or_else(|| format!("Submodule {} not found after adding it", path))
+}
@@ -0,0 +1,390 @@
+//! File templates
+//!
+//! New files can be created from a template: a built-in skeleton (exploit
+//! script, PoC server, pwntools script, Nmap wrapper) or a user template
+//! dropped into ~/.ctr/templates. Templates contain `{{name}}` placeholders
+//! that are filled from the supplied variables, the template's defaults and
+//! a few values derived from the destination path.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::langdetect::{self, LanguageId};
+use crate::utils::fs_utils::ctr_dir;
+
+lazy_static::lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap();
+}
+
+/// Largest user template read from ~/.ctr/templates
+const MAX_USER_TEMPLATE_BYTES: u64 = 512 * 1024;
+
+struct BuiltinTemplate {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    language: LanguageId,
+    extension: &'static str,
+    /// Default values of the template's variables
+    defaults: &'static [(&'static str, &'static str)],
+    content: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub language: Option<LanguageId>,
+    pub extension: String,
+    /// "builtin" or "user"
+    pub source: String,
+    /// Placeholders used by the template, in order of first use
+    pub variables: Vec<String>,
+    pub defaults: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedFile {
+    pub path: String,
+    pub template_id: String,
+    /// Variables that were substituted, with the values used
+    pub variables: HashMap<String, String>,
+}
+
+const PYTHON_EXPLOIT: &str = r#"#!/usr/bin/env python3
+"""{{title}}
+
+Target: {{target}}
+"""
+import argparse
+import sys
+
+import requests
+
+
+def exploit(target, timeout):
+    session = requests.Session()
+    response = session.get(target, timeout=timeout)
+    print(f"[*] {response.status_code} {target}")
+    # TODO: send the payload and check whether it worked
+    return False
+
+
+def main():
+    parser = argparse.ArgumentParser(description="{{title}}")
+    parser.add_argument("--target", default="{{target}}")
+    parser.add_argument("--timeout", type=float, default=10.0)
+    args = parser.parse_args()
+
+    if exploit(args.target, args.timeout):
+        print("[+] exploited")
+        return 0
+    print("[-] not vulnerable")
+    return 1
+
+
+if __name__ == "__main__":
+    sys.exit(main())
+"#;
+
+const FLASK_POC_SERVER: &str = r#"#!/usr/bin/env python3
+"""{{title}}
+
+Serves PoC pages and logs every request, e.g. exfiltrated cookies or
+callbacks from an SSRF/XXE payload.
+"""
+from flask import Flask, request
+
+app = Flask(__name__)
+
+
+@app.route("/", defaults={"path": ""}, methods=["GET", "POST"])
+@app.route("/<path:path>", methods=["GET", "POST"])
+def catch_all(path):
+    print(f"[*] {request.remote_addr} {request.method} /{path} {dict(request.args)}")
+    if request.data:
+        print(f"    body: {request.get_data(as_text=True)}")
+    return "ok"
+
+
+if __name__ == "__main__":
+    app.run(host="{{host}}", port={{port}})
+"#;
+
+const PWNTOOLS_SCRIPT: &str = r#"#!/usr/bin/env python3
+"""{{title}}"""
+from pwn import *
+
+BINARY = "{{binary}}"
+HOST, PORT = "{{host}}", {{port}}
+
+context.binary = elf = ELF(BINARY, checksec=False)
+context.log_level = "info"
+
+
+def start():
+    if args.REMOTE:
+        return remote(HOST, PORT)
+    if args.GDB:
+        return gdb.debug(BINARY, gdbscript="break main\ncontinue")
+    return process(BINARY)
+
+
+io = start()
+
+offset = 0
+payload = flat({offset: [0xdeadbeef]})
+io.sendline(payload)
+
+io.interactive()
+"#;
+
+const NMAP_WRAPPER: &str = r#"#!/usr/bin/env python3
+"""{{title}}
+
+Runs nmap against the target and prints the open ports from its XML output.
+"""
+import subprocess
+import sys
+import xml.etree.ElementTree as ET
+
+TARGET = "{{target}}"
+NMAP_ARGS = "{{nmap_args}}".split()
+
+
+def scan(target):
+    result = subprocess.run(
+        ["nmap", *NMAP_ARGS, "-oX", "-", target],
+        capture_output=True,
+        text=True,
+        check=True,
+    )
+    return ET.fromstring(result.stdout)
+
+
+def main():
+    target = sys.argv[1] if len(sys.argv) > 1 else TARGET
+    root = scan(target)
+    for host in root.iter("host"):
+        address = host.find("address").get("addr")
+        for port in host.iter("port"):
+            if port.find("state").get("state") != "open":
+                continue
+            service = port.find("service")
+            name = service.get("name", "") if service is not None else ""
+            print(f"{address}\t{port.get('portid')}/{port.get('protocol')}\t{name}")
+
+
+if __name__ == "__main__":
+    main()
+"#;
+
+const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        id: "python-exploit",
+        name: "Python exploit skeleton",
+        description: "argparse-driven exploit script using requests",
+        language: LanguageId::Python,
+        extension: "py",
+        defaults: &[("title", "Exploit"), ("target", "http://127.0.0.1:8080")],
+        content: PYTHON_EXPLOIT,
+    },
+    BuiltinTemplate {
+        id: "flask-poc-server",
+        name: "Flask PoC server",
+        description: "Catch-all Flask server that logs incoming requests",
+        language: LanguageId::Python,
+        extension: "py",
+        defaults: &[("title", "PoC server"), ("host", "0.0.0.0"), ("port", "8000")],
+        content: FLASK_POC_SERVER,
+    },
+    BuiltinTemplate {
+        id: "pwntools-script",
+        name: "pwntools script",
+        description: "Local, GDB and remote exploitation of a binary with pwntools",
+        language: LanguageId::Python,
+        extension: "py",
+        defaults: &[("title", "pwn"), ("binary", "./vuln"), ("host", "127.0.0.1"), ("port", "1337")],
+        content: PWNTOOLS_SCRIPT,
+    },
+    BuiltinTemplate {
+        id: "nmap-wrapper",
+        name: "Nmap scan wrapper",
+        description: "Runs nmap and lists open ports from its XML output",
+        language: LanguageId::Python,
+        extension: "py",
+        defaults: &[("title", "Nmap scan"), ("target", "127.0.0.1"), ("nmap_args", "-sV -T4")],
+        content: NMAP_WRAPPER,
+    },
+];
+
+/// A template resolved from either source, ready to render
+struct Template {
+    info: FileTemplateInfo,
+    content: String,
+}
+
+fn user_templates_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("templates");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create templates directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Placeholder names in order of first use
+fn placeholders(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for capture in PLACEHOLDER.captures_iter(content) {
+        let name = &capture[1];
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+fn builtin(template: &BuiltinTemplate) -> Template {
+    Template {
+        info: FileTemplateInfo {
+            id: template.id.to_string(),
+            name: template.name.to_string(),
+            description: template.description.to_string(),
+            language: Some(template.language),
+            extension: template.extension.to_string(),
+            source: "builtin".to_string(),
+            variables: placeholders(template.content),
+            defaults: template
+                .defaults
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        },
+        content: template.content.to_string(),
+    }
+}
+
+/// User templates are plain files; the id is the file stem
+fn user_templates() -> Result<Vec<Template>, String> {
+    let entries = fs::read_dir(user_templates_dir()?)
+        .map_err(|e| format!("Failed to read templates directory: {}", e))?;
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let too_large = !entry.metadata().is_ok_and(|m| m.len() <= MAX_USER_TEMPLATE_BYTES);
+        if !path.is_file() || too_large {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if stem.starts_with('.') {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_string();
+        let language = langdetect::detect(&path, Some(&content)).map(|d| d.language);
+        templates.push(Template {
+            info: FileTemplateInfo {
+                id: stem.to_string(),
+                name: stem.to_string(),
+                description: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                language,
+                extension,
+                source: "user".to_string(),
+                variables: placeholders(&content),
+                defaults: HashMap::new(),
+            },
+            content,
+        });
+    }
+    templates.sort_by(|a, b| a.info.id.cmp(&b.info.id));
+    Ok(templates)
+}
+
+/// Built-in templates followed by user templates; a user template with the
+/// id of a built-in one replaces it
+fn all_templates() -> Result<Vec<Template>, String> {
+    let user = user_templates()?;
+    let mut templates: Vec<Template> = BUILTIN_TEMPLATES
+        .iter()
+        .filter(|b| !user.iter().any(|u| u.info.id == b.id))
+        .map(builtin)
+        .collect();
+    templates.extend(user);
+    Ok(templates)
+}
+
+pub fn list() -> Result<Vec<FileTemplateInfo>, String> {
+    Ok(all_templates()?.into_iter().map(|t| t.info).collect())
+}
+
+/// Values derived from the destination, available to every template
+fn path_variables(dest: &Path) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    if let Some(name) = dest.file_name().and_then(|n| n.to_str()) {
+        values.insert("file_name".to_string(), name.to_string());
+    }
+    if let Some(stem) = dest.file_stem().and_then(|s| s.to_str()) {
+        values.insert("file_stem".to_string(), stem.to_string());
+    }
+    values
+}
+
+/// Render `template_id` into `dest_path`, which must not exist yet.
+/// Explicit variables win over the template's defaults, which win over the
+/// values derived from the path; any placeholder left without a value is an
+/// error.
+pub fn create_from_template(
+    template_id: &str,
+    dest_path: &str,
+    variables: HashMap<String, String>,
+) -> Result<CreatedFile, String> {
+    let template = all_templates()?
+        .into_iter()
+        .find(|t| t.info.id == template_id)
+        .ok_or_else(|| format!("Unknown template: {}", template_id))?;
+
+    let dest = Path::new(dest_path);
+    if dest.exists() {
+        return Err(format!("{} already exists", dest_path));
+    }
+
+    let mut values = path_variables(dest);
+    values.extend(template.info.defaults.clone());
+    values.extend(variables);
+
+    let missing: Vec<&str> = template
+        .info
+        .variables
+        .iter()
+        .filter(|name| !values.contains_key(*name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing template variables: {}", missing.join(", ")));
+    }
+
+    let content = PLACEHOLDER.replace_all(&template.content, |caps: &regex::Captures| values[&caps[1]].clone());
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent directory: {}", e))?;
+    }
+    fs::write(dest, content.as_bytes()).map_err(|e| format!("Failed to write {}: {}", dest_path, e))?;
+
+    let used = template
+        .info
+        .variables
+        .iter()
+        .map(|name| (name.clone(), values[name].clone()))
+        .collect();
+    Ok(CreatedFile {
+        path: dest_path.to_string(),
+        template_id: template.info.id,
+        variables: used,
+    })
+}
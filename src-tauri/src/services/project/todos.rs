@@ -0,0 +1,215 @@
+//! TODO comment index
+//!
+//! Collects TODO, FIXME, HACK and SECURITY comments across a workspace,
+//! walking it the way the rest of the project does but also honouring the
+//! workspace's .gitignore. Comments that mention security topics are flagged
+//! the same way as the scanner's security-todo rule. The index of each
+//! workspace is kept in memory so a watcher only re-reads changed files.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::walker;
+use crate::services::langdetect;
+
+/// Files larger than this are not searched for comments
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Bytes checked for a NUL to recognise binary files
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+lazy_static::lazy_static! {
+    /// A marker after a comment leader, with an optional `(owner)` and colon
+    static ref MARKER: Regex = Regex::new(
+        r"(?://|#|/\*|<!--|--|^\s*\*)\s*@?(TODO|FIXME|HACK|SECURITY)\b(?:\(([^)]*)\))?:?\s*(.*)"
+    ).unwrap();
+    static ref SECURITY_TOPIC: Regex = Regex::new(
+        r"(?i)security|auth|password|secret|vulnerab|unsafe|inject|xss|csrf|sanitiz|escap|crypt"
+    ).unwrap();
+    /// Workspace root -> file path -> comments of the file
+    static ref INDEXES: Mutex<HashMap<String, HashMap<String, Vec<TodoItem>>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TodoKind {
+    Todo,
+    Fixme,
+    Hack,
+    Security,
+}
+
+impl TodoKind {
+    fn from_marker(marker: &str) -> Option<Self> {
+        match marker {
+            "TODO" => Some(TodoKind::Todo),
+            "FIXME" => Some(TodoKind::Fixme),
+            "HACK" => Some(TodoKind::Hack),
+            "SECURITY" => Some(TodoKind::Security),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoItem {
+    pub line: usize,
+    /// Byte offset of the marker in the line
+    pub column: usize,
+    pub kind: TodoKind,
+    /// Name in `TODO(name):`, if given
+    pub owner: Option<String>,
+    /// The comment text after the marker
+    pub text: String,
+    /// SECURITY markers and comments mentioning security topics
+    pub security_related: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoFile {
+    pub file: String,
+    pub items: Vec<TodoItem>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TodoCounts {
+    pub todo: usize,
+    pub fixme: usize,
+    pub hack: usize,
+    pub security: usize,
+    /// Comments of any kind flagged as security related
+    pub security_related: usize,
+}
+
+/// Payload of `get_todo_index` and the `todos-updated` event
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoIndex {
+    pub workspace_root: String,
+    /// Files with at least one comment, sorted by path
+    pub files: Vec<TodoFile>,
+    pub counts: TodoCounts,
+}
+
+fn is_text(path: &Path) -> bool {
+    if !fs::metadata(path).is_ok_and(|m| m.len() <= MAX_FILE_BYTES) {
+        return false;
+    }
+    let mut head = vec![0; BINARY_SNIFF_BYTES];
+    let read = fs::File::open(path).and_then(|mut f| f.read(&mut head)).unwrap_or(0);
+    !head[..read].contains(&0)
+}
+
+/// Source files of a workspace that may hold comments
+pub fn indexed_files(root: &Path) -> Vec<PathBuf> {
+    let skip_dirs: Vec<String> = walker::DEFAULT_SKIP_DIRS.iter().map(|d| d.to_string()).collect();
+    let mut files = walker::files(root, &skip_dirs, true);
+    files.retain(|p| langdetect::detect_file(p).is_some());
+    files
+}
+
+/// The marked comments of one source text
+pub fn find_todos(content: &str) -> Vec<TodoItem> {
+    let mut items = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let Some(caps) = MARKER.captures(line) else {
+            continue;
+        };
+        let Some(kind) = TodoKind::from_marker(&caps[1]) else {
+            continue;
+        };
+        let text = caps[3].trim_end().trim_end_matches("*/").trim_end_matches("-->").trim().to_string();
+        items.push(TodoItem {
+            line: idx + 1,
+            column: caps.get(1).map_or(0, |m| m.start()),
+            kind,
+            owner: caps.get(2).map(|m| m.as_str().trim().to_string()).filter(|o| !o.is_empty()),
+            security_related: kind == TodoKind::Security || SECURITY_TOPIC.is_match(&text),
+            text,
+        });
+    }
+    items
+}
+
+fn index_file(path: &Path) -> Vec<TodoItem> {
+    if !is_text(path) {
+        return Vec::new();
+    }
+    fs::read_to_string(path).map(|c| find_todos(&c)).unwrap_or_default()
+}
+
+fn workspace_key(root: &Path) -> String {
+    root.to_string_lossy().to_string()
+}
+
+fn snapshot_of(root: &Path, files: &HashMap<String, Vec<TodoItem>>) -> TodoIndex {
+    let sorted: BTreeMap<&String, &Vec<TodoItem>> = files.iter().filter(|(_, items)| !items.is_empty()).collect();
+    let mut counts = TodoCounts::default();
+    for item in sorted.values().flat_map(|items| items.iter()) {
+        match item.kind {
+            TodoKind::Todo => counts.todo += 1,
+            TodoKind::Fixme => counts.fixme += 1,
+            TodoKind::Hack => counts.hack += 1,
+            TodoKind::Security => counts.security += 1,
+        }
+        if item.security_related {
+            counts.security_related += 1;
+        }
+    }
+    TodoIndex {
+        workspace_root: workspace_key(root),
+        files: sorted
+            .into_iter()
+            .map(|(file, items)| TodoFile { file: file.clone(), items: items.clone() })
+            .collect(),
+        counts,
+    }
+}
+
+/// Index every file of a workspace, replacing its previous index
+pub fn full_index(root: &Path) -> TodoIndex {
+    let files: HashMap<String, Vec<TodoItem>> = indexed_files(root)
+        .into_iter()
+        .map(|path| {
+            let items = index_file(&path);
+            (path.to_string_lossy().to_string(), items)
+        })
+        .collect();
+    let index = snapshot_of(root, &files);
+    INDEXES.lock().unwrap().insert(workspace_key(root), files);
+    index
+}
+
+/// Re-read changed files and drop removed ones; None when no comment was
+/// added, changed or removed
+pub fn refresh(root: &Path, changed: &[PathBuf], removed: &[PathBuf]) -> Option<TodoIndex> {
+    let updated: Vec<(String, Vec<TodoItem>)> = changed
+        .iter()
+        .map(|path| (path.to_string_lossy().to_string(), index_file(path)))
+        .collect();
+
+    let mut indexes = INDEXES.lock().unwrap();
+    let files = indexes.entry(workspace_key(root)).or_default();
+    let mut dirty = false;
+    for path in removed {
+        dirty |= files.remove(&*path.to_string_lossy()).is_some_and(|items| !items.is_empty());
+    }
+    for (path, items) in updated {
+        let had_items = files.get(&path).is_some_and(|old| !old.is_empty());
+        dirty |= had_items || !items.is_empty();
+        files.insert(path, items);
+    }
+    dirty.then(|| snapshot_of(root, files))
+}
+
+/// The in-memory index of a workspace, if it was built
+pub fn snapshot(root: &Path) -> Option<TodoIndex> {
+    INDEXES.lock().unwrap().get(&workspace_key(root)).map(|files| snapshot_of(root, files))
+}
+
+pub fn forget(root: &Path) {
+    INDEXES.lock().unwrap().remove(&workspace_key(root));
+}
@@ -0,0 +1,143 @@
+//! Workspace trust
+//!
+//! Folders opened from a downloaded CTF archive or a target's repository may
+//! carry code that runs by itself: extensions activating on the workspace,
+//! or file contents the AI features send to a provider. Until the user
+//! trusts a folder those paths are disabled; scanning, search and editing
+//! stay available. Decisions are stored in ~/.ctr/trust.json rather than in
+//! the workspace, so a folder cannot declare itself trusted, and a decision
+//! about a folder applies to everything below it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustState {
+    Trusted,
+    Untrusted,
+    /// No decision yet; the frontend asks, and the folder is treated as
+    /// untrusted meanwhile
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustDecision {
+    pub path: String,
+    pub trusted: bool,
+    /// Unix timestamp in milliseconds
+    pub decided_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceTrust {
+    pub path: String,
+    pub state: TrustState,
+    /// The folder the decision was made for, the path itself or a parent
+    pub decided_for: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrustFile {
+    #[serde(default)]
+    decisions: BTreeMap<String, TrustDecision>,
+}
+
+lazy_static::lazy_static! {
+    /// Serializes updates of the trust file
+    static ref TRUST_LOCK: Mutex<()> = Mutex::new(());
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("trust.json"))
+}
+
+/// Absolute form of a folder, so decisions hold however the path was spelled
+fn normalize(path: &str) -> String {
+    let path = path.trim();
+    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let text = resolved.to_string_lossy().to_string();
+    let trimmed = text.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() { text } else { trimmed.to_string() }
+}
+
+/// The decision covering `path`: its own, or that of the closest parent
+fn decision_for<'a>(file: &'a TrustFile, path: &str) -> Option<&'a TrustDecision> {
+    Path::new(path)
+        .ancestors()
+        .find_map(|dir| file.decisions.get(dir.to_string_lossy().as_ref()))
+}
+
+pub fn state(path: &str) -> Result<WorkspaceTrust, String> {
+    let path = normalize(path);
+    let file: TrustFile = load_json(&store_path()?);
+    let decision = decision_for(&file, &path);
+    Ok(WorkspaceTrust {
+        state: match decision {
+            Some(d) if d.trusted => TrustState::Trusted,
+            Some(_) => TrustState::Untrusted,
+            None => TrustState::Unknown,
+        },
+        decided_for: decision.map(|d| d.path.clone()),
+        path,
+    })
+}
+
+/// Record the user's decision for a folder and everything below it
+pub fn set(path: &str, trusted: bool) -> Result<WorkspaceTrust, String> {
+    let normalized = normalize(path);
+    if !Path::new(&normalized).is_dir() {
+        return Err(format!("{} is not a directory", path));
+    }
+    {
+        let _guard = TRUST_LOCK.lock().unwrap();
+        let store = store_path()?;
+        let mut file: TrustFile = load_json(&store);
+        file.decisions.insert(
+            normalized.clone(),
+            TrustDecision { path: normalized.clone(), trusted, decided_at: now_millis() },
+        );
+        save_json(&store, &file)?;
+    }
+    log::info!("Workspace {} marked {}", normalized, if trusted { "trusted" } else { "untrusted" });
+    state(&normalized)
+}
+
+/// Drop the decision made for exactly this folder; returns false when there
+/// was none
+pub fn forget(path: &str) -> Result<bool, String> {
+    let path = normalize(path);
+    let _guard = TRUST_LOCK.lock().unwrap();
+    let store = store_path()?;
+    let mut file: TrustFile = load_json(&store);
+    if file.decisions.remove(&path).is_none() {
+        return Ok(false);
+    }
+    save_json(&store, &file)?;
+    Ok(true)
+}
+
+/// Every decision, most recent first
+pub fn list() -> Result<Vec<TrustDecision>, String> {
+    let file: TrustFile = load_json(&store_path()?);
+    let mut decisions: Vec<TrustDecision> = file.decisions.into_values().collect();
+    decisions.sort_by_key(|d| std::cmp::Reverse(d.decided_at));
+    Ok(decisions)
+}
+
+pub fn is_trusted(path: &str) -> bool {
+    state(path).is_ok_and(|t| t.state == TrustState::Trusted)
+}
+
+/// Fail with a message naming `action` unless the workspace is trusted
+pub fn require_trusted(path: &str, action: &str) -> Result<(), String> {
+    if is_trusted(path) {
+        return Ok(());
+    }
+    Err(format!("{} is disabled until the workspace {} is trusted", action, path))
+}
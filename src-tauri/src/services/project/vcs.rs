@@ -0,0 +1,306 @@
+//! Git operations run through the system git binary
+//!
+//! Used where git2 has no equivalent, such as interactive rebase. The user's
+//! git configuration (credentials, hooks, signing) applies as usual. Rebase
+//! plans are fed to `git rebase -i` by pointing GIT_SEQUENCE_EDITOR at a
+//! prepared todo list, and GIT_EDITOR is disabled so git never waits for an
+//! editor; new commit messages are written to files and applied with
+//! `git commit --amend -F` after the commit is picked.
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Scratch directory for rebase plan and message files, inside .git
+const WORK_DIR: &str = "ctr-rebase";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RebaseAction {
+    Pick,
+    /// Keep the commit with a new message
+    Reword,
+    /// Meld into the previous commit, combining the messages
+    Squash,
+    /// Meld into the previous commit, keeping its message
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+}
+
+/// One commit of a rebase plan, in the order it is to be applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseStep {
+    pub hash: String,
+    pub action: RebaseAction,
+    /// New message, required for reword and optional for squash
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// A commit that a rebase onto a base would replay
+#[derive(Debug, Clone, Serialize)]
+pub struct RebaseCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub summary: String,
+    pub author: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RebaseStatus {
+    pub in_progress: bool,
+    /// Steps of the todo list applied so far and their total
+    pub done: usize,
+    pub total: usize,
+    /// The branch being rebased
+    pub head_name: Option<String>,
+    /// Files with unresolved conflicts
+    pub conflicts: Vec<String>,
+    /// What git printed for the last command
+    pub output: String,
+}
+
+fn open(repo_path: &str) -> Result<Repository, String> {
+    Repository::open(repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn git(repo_path: &str, args: &[&str], sequence_editor: Option<&str>) -> Result<(bool, String), String> {
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .current_dir(repo_path)
+        .env("GIT_EDITOR", "true")
+        .env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(editor) = sequence_editor {
+        command.env("GIT_SEQUENCE_EDITOR", editor);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run git {}: {}", args.first().unwrap_or(&""), e))?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok((output.status.success(), text.trim().to_string()))
+}
+
+/// Quote a path for the shell git runs editors and exec lines with
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\\', "/").replace('\'', "'\\''"))
+}
+
+fn rebase_state_dir(repo: &Repository) -> Option<PathBuf> {
+    ["rebase-merge", "rebase-apply"]
+        .iter()
+        .map(|d| repo.path().join(d))
+        .find(|d| d.is_dir())
+}
+
+fn work_dir(repo: &Repository) -> Result<PathBuf, String> {
+    let dir = repo.path().join(WORK_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create rebase work directory: {}", e))?;
+    Ok(dir)
+}
+
+fn read_count(dir: &Path, names: &[&str]) -> usize {
+    names
+        .iter()
+        .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn rebase_status(repo_path: &str) -> Result<RebaseStatus, String> {
+    let repo = open(repo_path)?;
+    let Some(dir) = rebase_state_dir(&repo) else {
+        // Plan and message files are only needed while a rebase runs
+        let _ = fs::remove_dir_all(repo.path().join(WORK_DIR));
+        return Ok(RebaseStatus::default());
+    };
+
+    let conflicts = repo
+        .index()
+        .ok()
+        .and_then(|index| {
+            let conflicts = index.conflicts().ok()?;
+            Some(
+                conflicts
+                    .flatten()
+                    .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+                    .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                    .collect(),
+            )
+        })
+        .unwrap_or_default();
+    let head_name = fs::read_to_string(dir.join("head-name"))
+        .ok()
+        .map(|s| s.trim().trim_start_matches("refs/heads/").to_string());
+
+    Ok(RebaseStatus {
+        in_progress: true,
+        done: read_count(&dir, &["msgnum", "next"]),
+        total: read_count(&dir, &["end", "last"]),
+        head_name,
+        conflicts,
+        output: String::new(),
+    })
+}
+
+/// Commits between `onto` and HEAD, oldest first, as a rebase would replay them
+pub fn rebase_commits(repo_path: &str, onto: &str) -> Result<Vec<RebaseCommit>, String> {
+    let repo = open(repo_path)?;
+    let base = repo
+        .revparse_single(onto)
+        .and_then(|o| o.peel_to_commit())
+        .map_err(|e| format!("Failed to find {}: {}", onto, e))?;
+
+    let mut revwalk = repo.revwalk().map_err(|e| format!("Failed to create revwalk: {}", e))?;
+    revwalk.push_head().map_err(|e| format!("Failed to push HEAD: {}", e))?;
+    revwalk.hide(base.id()).map_err(|e| format!("Failed to hide {}: {}", onto, e))?;
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)
+        .map_err(|e| format!("Failed to sort commits: {}", e))?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid.map_err(|e| format!("Failed to get OID: {}", e))?;
+        let commit = repo.find_commit(oid).map_err(|e| format!("Failed to find commit: {}", e))?;
+        // Merge commits are not replayed by a plain interactive rebase
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let hash = oid.to_string();
+        commits.push(RebaseCommit {
+            short_hash: hash[..7].to_string(),
+            hash,
+            summary: commit.summary().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            timestamp: commit.time().seconds(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Write the todo list for `plan`, with message files for rewritten commits
+fn write_todo(repo: &Repository, plan: &[RebaseStep]) -> Result<PathBuf, String> {
+    let dir = work_dir(repo)?;
+    let mut todo = String::new();
+    for (i, step) in plan.iter().enumerate() {
+        let commit = repo
+            .revparse_single(&step.hash)
+            .and_then(|o| o.peel_to_commit())
+            .map_err(|e| format!("Failed to find commit {}: {}", step.hash, e))?;
+        let first_kept = plan[..i].iter().all(|s| s.action == RebaseAction::Drop);
+        if first_kept && matches!(step.action, RebaseAction::Squash | RebaseAction::Fixup) {
+            return Err(format!(
+                "Cannot {} {}: there is no earlier commit to meld into",
+                step.action.as_str(),
+                step.hash
+            ));
+        }
+        let message = step.message.as_deref().filter(|m| !m.trim().is_empty());
+        if step.action == RebaseAction::Reword && message.is_none() {
+            return Err(format!("Reword of {} needs a message", step.hash));
+        }
+
+        // A new message is applied by the exec line below
+        let action = match step.action {
+            RebaseAction::Reword => "pick",
+            other => other.as_str(),
+        };
+        todo.push_str(&format!("{} {} {}\n", action, commit.id(), commit.summary().unwrap_or("")));
+
+        if let (Some(message), true) = (message, matches!(step.action, RebaseAction::Reword | RebaseAction::Squash)) {
+            let message_file = dir.join(format!("message-{}", i));
+            fs::write(&message_file, message).map_err(|e| format!("Failed to write commit message: {}", e))?;
+            todo.push_str(&format!("exec git commit --amend --allow-empty -F {}\n", shell_quote(&message_file)));
+        }
+    }
+
+    let todo_file = dir.join("todo");
+    fs::write(&todo_file, todo).map_err(|e| format!("Failed to write rebase plan: {}", e))?;
+    Ok(todo_file)
+}
+
+/// Start an interactive rebase onto `onto`. Without a plan every commit is
+/// picked as is; a plan lists the commits in their new order with an action
+/// each, and commits left out of it are dropped. Stops on conflicts, which
+/// the returned status lists.
+pub fn rebase_start(repo_path: &str, onto: &str, plan: Option<&[RebaseStep]>) -> Result<RebaseStatus, String> {
+    let repo = open(repo_path)?;
+    if rebase_state_dir(&repo).is_some() {
+        return Err("A rebase is already in progress".to_string());
+    }
+    // Only a resolved commit id reaches git, never an option-like argument
+    let onto_id = repo
+        .revparse_single(onto)
+        .and_then(|o| o.peel_to_commit())
+        .map_err(|e| format!("Failed to find {}: {}", onto, e))?
+        .id()
+        .to_string();
+
+    let plan = match plan {
+        Some(plan) => plan.to_vec(),
+        None => rebase_commits(repo_path, &onto_id)?
+            .into_iter()
+            .map(|c| RebaseStep { hash: c.hash, action: RebaseAction::Pick, message: None })
+            .collect(),
+    };
+    if plan.is_empty() {
+        return Err(format!("No commits to rebase onto {}", onto));
+    }
+    let todo_file = write_todo(&repo, &plan)?;
+    let editor = format!("cp {}", shell_quote(&todo_file));
+
+    let (success, output) = git(repo_path, &["rebase", "-i", &onto_id], Some(&editor))?;
+    finish(repo_path, success, output)
+}
+
+/// Status after a rebase command; a failure that left the rebase stopped,
+/// typically on a conflict, is reported in the status rather than as an error
+fn finish(repo_path: &str, success: bool, output: String) -> Result<RebaseStatus, String> {
+    let mut status = rebase_status(repo_path)?;
+    if !success && !status.in_progress {
+        return Err(format!("Rebase failed: {}", output));
+    }
+    status.output = output;
+    Ok(status)
+}
+
+fn rebase_command(repo_path: &str, flag: &str) -> Result<RebaseStatus, String> {
+    let repo = open(repo_path)?;
+    if rebase_state_dir(&repo).is_none() {
+        return Err("No rebase in progress".to_string());
+    }
+    let (success, output) = git(repo_path, &["rebase", flag], None)?;
+    finish(repo_path, success, output)
+}
+
+/// Continue after resolving conflicts; resolved files must be staged
+pub fn rebase_continue(repo_path: &str) -> Result<RebaseStatus, String> {
+    rebase_command(repo_path, "--continue")
+}
+
+pub fn rebase_skip(repo_path: &str) -> Result<RebaseStatus, String> {
+    rebase_command(repo_path, "--skip")
+}
+
+pub fn rebase_abort(repo_path: &str) -> Result<RebaseStatus, String> {
+    rebase_command(repo_path, "--abort")
+}
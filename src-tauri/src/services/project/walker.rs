@@ -0,0 +1,92 @@
+//! Workspace file walker
+//!
+//! Lists the files of a workspace, leaving out directories such as
+//! node_modules and, when asked, whatever the workspace's top-level
+//! .gitignore excludes. Only the common .gitignore forms are understood:
+//! name and path globs, directory-only patterns with a trailing slash and
+//! comments; negations are ignored.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories no workspace walk descends into by default
+pub const DEFAULT_SKIP_DIRS: &[&str] = &["node_modules", ".git", "target", "build", "dist", "__pycache__", ".venv", "venv"];
+
+struct IgnorePattern {
+    pattern: glob::Pattern,
+    /// Matched against the path relative to the root rather than a name
+    anchored: bool,
+    dir_only: bool,
+}
+
+/// Patterns of a workspace's top-level .gitignore
+#[derive(Default)]
+pub struct IgnoreRules {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnoreRules {
+    pub fn load(root: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(root.join(".gitignore")) else {
+            return Self::default();
+        };
+        let patterns = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+            .filter_map(|line| {
+                let dir_only = line.ends_with('/');
+                let line = line.trim_end_matches('/');
+                let anchored = line.contains('/');
+                let pattern = glob::Pattern::new(line.trim_start_matches('/')).ok()?;
+                Some(IgnorePattern { pattern, anchored, dir_only })
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    /// Whether `relative`, a path below the root, is ignored
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        let name = relative.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        self.patterns.iter().any(|p| {
+            (is_dir || !p.dir_only)
+                && if p.anchored {
+                    p.pattern.matches(&relative_str)
+                } else {
+                    p.pattern.matches(name)
+                }
+        })
+    }
+}
+
+/// Every file below `root`, skipping directories named in `skip_dirs` and,
+/// with `gitignore`, paths ignored by the root .gitignore
+pub fn files(root: &Path, skip_dirs: &[String], gitignore: bool) -> Vec<PathBuf> {
+    fn collect(dir: &Path, root: &Path, skip_dirs: &[String], ignore: &IgnoreRules, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = path.is_dir();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            if ignore.is_ignored(relative, is_dir) {
+                continue;
+            }
+            if is_dir {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                if !skip_dirs.iter().any(|d| d == name) {
+                    collect(&path, root, skip_dirs, ignore, out);
+                }
+            } else {
+                out.push(path);
+            }
+        }
+    }
+
+    let ignore = if gitignore { IgnoreRules::load(root) } else { IgnoreRules::default() };
+    let mut out = Vec::new();
+    collect(root, root, skip_dirs, &ignore, &mut out);
+    out
+}
@@ -0,0 +1,72 @@
+//! Workspace file watcher
+//!
+//! Detects saved, created and deleted files by comparing modification times
+//! between polls, the same way the prover watches single files. Callers
+//! decide which files to watch and how often to poll.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What changed since the previous poll
+#[derive(Debug, Clone, Default)]
+pub struct FileChanges {
+    /// Files created or written
+    pub changed: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+impl FileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[derive(Debug, Default)]
+pub struct PollWatcher {
+    known: HashMap<PathBuf, SystemTime>,
+}
+
+impl PollWatcher {
+    /// Start from the current state of `files`, so only later changes are reported
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        let mut watcher = Self::default();
+        watcher.poll(files);
+        watcher
+    }
+
+    /// Compare `files`, the set being watched now, with the previous poll
+    pub fn poll(&mut self, files: Vec<PathBuf>) -> FileChanges {
+        let mut changes = FileChanges::default();
+        let mut current = HashMap::with_capacity(files.len());
+        for file in files {
+            let Some(modified) = modified_time(&file) else {
+                continue;
+            };
+            if self.known.get(&file) != Some(&modified) {
+                changes.changed.push(file.clone());
+            }
+            current.insert(file, modified);
+        }
+        changes.removed = self.known.keys().filter(|f| !current.contains_key(*f)).cloned().collect();
+        self.known = current;
+        changes
+    }
+
+    /// Record a file as handled at its current modification time, so the
+    /// next poll does not report it again
+    pub fn mark_seen(&mut self, path: &Path) {
+        match modified_time(path) {
+            Some(modified) => {
+                self.known.insert(path.to_path_buf(), modified);
+            }
+            None => {
+                self.known.remove(path);
+            }
+        }
+    }
+}
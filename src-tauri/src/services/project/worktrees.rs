@@ -0,0 +1,190 @@
+//! Git worktrees
+//!
+//! Extra working directories sharing one repository, so that two branches of
+//! a challenge (say "vulnerable" and "patched") can be checked out side by
+//! side and compared.
+
+use git2::{BranchType, Repository, StatusOptions, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeInfo {
+    /// Worktree name; "main" for the repository's own working directory
+    pub name: String,
+    pub path: String,
+    /// Checked out branch; None when HEAD is detached
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub is_main: bool,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    /// False when the directory was deleted without removing the worktree
+    pub valid: bool,
+}
+
+fn open(repo_path: &str) -> Result<Repository, String> {
+    Repository::open(repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+/// The main repository, also when `repo_path` is one of its worktrees
+fn open_main(repo_path: &str) -> Result<Repository, String> {
+    let repo = open(repo_path)?;
+    if !repo.is_worktree() {
+        return Ok(repo);
+    }
+    // A linked worktree's git directory names the shared one in `commondir`
+    let common = fs::read_to_string(repo.path().join("commondir"))
+        .map_err(|e| format!("Failed to find the main repository: {}", e))?;
+    Repository::open(repo.path().join(common.trim()))
+        .map_err(|e| format!("Failed to open main repository: {}", e))
+}
+
+fn head_info(repo: &Repository) -> (Option<String>, Option<String>) {
+    match repo.head() {
+        Ok(head) => (
+            head.is_branch().then(|| head.shorthand().unwrap_or("").to_string()),
+            head.target().map(|oid| oid.to_string()),
+        ),
+        Err(_) => (None, None),
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().trim_end_matches(['/', '\\']).to_string()
+}
+
+pub fn list(repo_path: &str) -> Result<Vec<WorktreeInfo>, String> {
+    let repo = open_main(repo_path)?;
+    let mut worktrees = Vec::new();
+    if let Some(workdir) = repo.workdir() {
+        let (branch, head) = head_info(&repo);
+        worktrees.push(WorktreeInfo {
+            name: "main".to_string(),
+            path: path_string(workdir),
+            branch,
+            head,
+            is_main: true,
+            locked: false,
+            lock_reason: None,
+            valid: true,
+        });
+    }
+
+    let names = repo.worktrees().map_err(|e| format!("Failed to list worktrees: {}", e))?;
+    for name in names.iter().flatten() {
+        let worktree = repo
+            .find_worktree(name)
+            .map_err(|e| format!("Failed to open worktree {}: {}", name, e))?;
+        let valid = worktree.validate().is_ok();
+        let (branch, head) = if valid {
+            Repository::open_from_worktree(&worktree)
+                .map(|wt_repo| head_info(&wt_repo))
+                .unwrap_or((None, None))
+        } else {
+            (None, None)
+        };
+        let (locked, lock_reason) = match worktree.is_locked() {
+            Ok(WorktreeLockStatus::Locked(reason)) => (true, reason.filter(|r| !r.is_empty())),
+            _ => (false, None),
+        };
+        worktrees.push(WorktreeInfo {
+            name: name.to_string(),
+            path: path_string(worktree.path()),
+            branch,
+            head,
+            is_main: false,
+            locked,
+            lock_reason,
+            valid,
+        });
+    }
+    Ok(worktrees)
+}
+
+/// Check out `branch` in a new worktree at `path`. A branch that does not
+/// exist locally is created from the remote branch of that name or, failing
+/// that, from HEAD. A branch can only be checked out in one worktree.
+pub fn add(repo_path: &str, branch: &str, path: &str) -> Result<WorktreeInfo, String> {
+    let repo = open_main(repo_path)?;
+    let dest = PathBuf::from(path);
+    if dest.exists() {
+        return Err(format!("{} already exists", path));
+    }
+    let name: String = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("Worktree path has no directory name")?
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    if repo.find_worktree(&name).is_ok() {
+        return Err(format!("A worktree named {} already exists", name));
+    }
+
+    let local = match repo.find_branch(branch, BranchType::Local) {
+        Ok(local) => local,
+        Err(_) => {
+            let start = match repo.find_branch(&format!("origin/{}", branch), BranchType::Remote) {
+                Ok(remote) => remote.get().peel_to_commit(),
+                Err(_) => repo.head().and_then(|h| h.peel_to_commit()),
+            }
+            .map_err(|e| format!("Failed to find a commit to start {} from: {}", branch, e))?;
+            repo.branch(branch, &start, false)
+                .map_err(|e| format!("Failed to create branch {}: {}", branch, e))?
+        }
+    };
+    if list(repo_path)?.iter().any(|w| w.branch.as_deref() == Some(branch)) {
+        return Err(format!("{} is already checked out in another worktree", branch));
+    }
+
+    let reference = local.into_reference();
+    let mut options = WorktreeAddOptions::new();
+    options.reference(Some(&reference));
+    let worktree = repo
+        .worktree(&name, &dest, Some(&options))
+        .map_err(|e| format!("Failed to add worktree: {}", e))?;
+    list(repo_path)?
+        .into_iter()
+        .find(|w| w.name == worktree.name().unwrap_or(&name))
+        .ok_or_else(|| format!("Worktree {} not found after adding it", name))
+}
+
+/// Delete a worktree's directory and administrative files. Worktrees with
+/// uncommitted changes or a lock are only removed with `force`; the branch
+/// is kept.
+pub fn remove(repo_path: &str, name: &str, force: bool) -> Result<(), String> {
+    let repo = open_main(repo_path)?;
+    let worktree = repo
+        .find_worktree(name)
+        .map_err(|e| format!("Worktree {} not found: {}", name, e))?;
+    if !force {
+        if let Ok(WorktreeLockStatus::Locked(_)) = worktree.is_locked() {
+            return Err(format!("Worktree {} is locked", name));
+        }
+        if worktree.validate().is_ok() {
+            let wt_repo = Repository::open_from_worktree(&worktree)
+                .map_err(|e| format!("Failed to open worktree {}: {}", name, e))?;
+            let mut opts = StatusOptions::new();
+            opts.include_untracked(true).exclude_submodules(true);
+            let dirty = wt_repo
+                .statuses(Some(&mut opts))
+                .map(|statuses| !statuses.is_empty())
+                .map_err(|e| format!("Failed to get statuses of {}: {}", name, e))?;
+            if dirty {
+                return Err(format!("Worktree {} has uncommitted changes", name));
+            }
+        }
+    }
+
+    if worktree.path().exists() {
+        fs::remove_dir_all(worktree.path())
+            .map_err(|e| format!("Failed to delete {}: {}", worktree.path().display(), e))?;
+    }
+    let mut prune = WorktreePruneOptions::new();
+    prune.locked(force).working_tree(true);
+    worktree
+        .prune(Some(&mut prune))
+        .map_err(|e| format!("Failed to remove worktree {}: {}", name, e))
+}
@@ -0,0 +1,187 @@
+//! Exploit prover result cache
+//!
+//! Results are keyed by a SHA-256 of the source, the analysis options and
+//! `analysis::ANALYSIS_VERSION`, so an edit, a settings change or a new rule
+//! set all miss. Recent results are kept in memory; every result is also
+//! written to ~/.ctr/prover_cache/<key>.json so they survive restarts, up to
+//! `DISK_CAPACITY` files with the least recently used removed first.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::analysis::{slicer::TaintConfig, AnalysisResult, Language, ANALYSIS_VERSION};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+
+/// Results kept in memory before the oldest is dropped
+const MEMORY_CAPACITY: usize = 256;
+/// Result files kept on disk before the least recently used are removed
+const DISK_CAPACITY: usize = 2000;
+
+#[derive(Default)]
+struct MemoryCache {
+    entries: HashMap<String, AnalysisResult>,
+    /// Keys in insertion order, oldest first
+    order: VecDeque<String>,
+    memory_hits: u64,
+    disk_hits: u64,
+    misses: u64,
+}
+
+lazy_static::lazy_static! {
+    static ref CACHE: Mutex<MemoryCache> = Mutex::new(MemoryCache::default());
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub memory_entries: usize,
+    pub disk_entries: usize,
+    pub memory_hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    /// Share of lookups answered from either cache, 0.0 to 1.0
+    pub hit_rate: f64,
+}
+
+fn cache_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("prover_cache");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create prover cache directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Cache key for analyzing `source` with the given options
+pub fn cache_key(
+    source: &str,
+    language: Language,
+    target_line: Option<usize>,
+    config: &TaintConfig,
+    payload_command: &str,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!(
+        "v{}|lang={:?}|line={:?}|env={}|config_files={}|command={:?}\n",
+        ANALYSIS_VERSION, language, target_line, config.untrusted_environment, config.untrusted_config_files, payload_command
+    ));
+    hasher.update(source.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Cached result for `key`, checking memory first and then disk
+pub fn get(key: &str) -> Option<AnalysisResult> {
+    let mut cache = CACHE.lock().unwrap();
+    if let Some(result) = cache.entries.get(key).cloned() {
+        cache.memory_hits += 1;
+        return Some(result);
+    }
+
+    let path = cache_dir().ok().map(|dir| dir.join(format!("{}.json", key)));
+    let stored: Option<AnalysisResult> = path.as_deref().and_then(load_json);
+    match stored {
+        Some(result) => {
+            cache.disk_hits += 1;
+            if let Some(path) = &path {
+                touch(path);
+            }
+            remember(&mut cache, key, result.clone());
+            Some(result)
+        }
+        None => {
+            cache.misses += 1;
+            None
+        }
+    }
+}
+
+/// Store a completed analysis; failed analyses are not cached so they are retried
+pub fn put(key: &str, result: &AnalysisResult) -> Result<(), String> {
+    if !result.success {
+        return Ok(());
+    }
+    remember(&mut CACHE.lock().unwrap(), key, result.clone());
+    save_json(&cache_dir()?.join(format!("{}.json", key)), result)?;
+    prune_disk();
+    Ok(())
+}
+
+/// Mark a result file as used, so pruning keeps it longer
+fn touch(path: &Path) {
+    let touched = std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(e) = touched {
+        log::warn!("Failed to update {}: {}", path.display(), e);
+    }
+}
+
+/// Remove the least recently used result files beyond `DISK_CAPACITY`
+fn prune_disk() {
+    let mut files: Vec<(SystemTime, PathBuf)> = disk_entries()
+        .into_iter()
+        .map(|path| {
+            let used = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (used, path)
+        })
+        .collect();
+    if files.len() <= DISK_CAPACITY {
+        return;
+    }
+    files.sort();
+    for (_, path) in &files[..files.len() - DISK_CAPACITY] {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn remember(cache: &mut MemoryCache, key: &str, result: AnalysisResult) {
+    if cache.entries.insert(key.to_string(), result).is_none() {
+        cache.order.push_back(key.to_string());
+    }
+    while cache.order.len() > MEMORY_CAPACITY {
+        if let Some(oldest) = cache.order.pop_front() {
+            cache.entries.remove(&oldest);
+        }
+    }
+}
+
+fn disk_entries() -> Vec<PathBuf> {
+    let Ok(entries) = cache_dir().and_then(|dir| std::fs::read_dir(dir).map_err(|e| e.to_string())) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect()
+}
+
+pub fn stats() -> CacheStats {
+    let cache = CACHE.lock().unwrap();
+    let hits = cache.memory_hits + cache.disk_hits;
+    let lookups = hits + cache.misses;
+    CacheStats {
+        memory_entries: cache.entries.len(),
+        disk_entries: disk_entries().len(),
+        memory_hits: cache.memory_hits,
+        disk_hits: cache.disk_hits,
+        misses: cache.misses,
+        hit_rate: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+    }
+}
+
+/// Drop every cached result and reset the statistics; returns how many files were removed
+pub fn clear() -> Result<usize, String> {
+    *CACHE.lock().unwrap() = MemoryCache::default();
+    let mut removed = 0;
+    for path in disk_entries() {
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,154 @@
+//! Local certificate authority used to mint per-host certificates for TLS interception.
+//!
+//! The CA key and certificate live in ~/.ctr/proxy/. The certificate file is what the
+//! user imports into their browser; only the key is needed to keep issuing leaves.
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType, IsCa, KeyPair,
+    KeyUsagePurpose,
+};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use crate::utils::fs_utils::ctr_dir;
+
+const CA_COMMON_NAME: &str = "CTR Intercepting Proxy CA";
+
+pub struct CertificateAuthority {
+    cert: Certificate,
+    key: KeyPair,
+    cache: Mutex<HashMap<String, Arc<ServerConfig>>>,
+}
+
+fn proxy_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("proxy");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create proxy directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Path of the CA certificate users should trust
+pub fn ca_cert_path() -> Result<PathBuf, String> {
+    Ok(proxy_dir()?.join("ca.pem"))
+}
+
+fn ca_params() -> CertificateParams {
+    let mut params = CertificateParams::default();
+    let mut dn = DistinguishedName::new();
+    dn.push(DnType::CommonName, CA_COMMON_NAME);
+    dn.push(DnType::OrganizationName, "Cyber Threat Range IDE");
+    params.distinguished_name = dn;
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params
+}
+
+/// Write the CA key readable by the owner only
+fn write_private_key(path: &Path, pem: &str) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(|e| format!("Failed to write CA key: {}", e))?;
+    file.write_all(pem.as_bytes())
+        .map_err(|e| format!("Failed to write CA key: {}", e))?;
+    // The mode only applies to new files
+    restrict_to_owner(path);
+    Ok(())
+}
+
+fn restrict_to_owner(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(0o600)) {
+            log::warn!("Failed to restrict {}: {}", path.display(), e);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+impl CertificateAuthority {
+    /// Load the CA from disk, generating a fresh one on first use
+    pub fn load_or_create() -> Result<Self, String> {
+        let dir = proxy_dir()?;
+        let key_path = dir.join("ca.key.pem");
+        let cert_path = dir.join("ca.pem");
+
+        let key = if key_path.exists() {
+            // Keys written before they were created owner-only get tightened here
+            restrict_to_owner(&key_path);
+            let pem = fs::read_to_string(&key_path)
+                .map_err(|e| format!("Failed to read CA key: {}", e))?;
+            KeyPair::from_pem(&pem).map_err(|e| format!("Failed to parse CA key: {}", e))?
+        } else {
+            KeyPair::generate().map_err(|e| format!("Failed to generate CA key: {}", e))?
+        };
+
+        // The issuer only needs a matching subject and key, so rebuilding the CA
+        // certificate from the stored key keeps previously trusted certs valid.
+        let cert = ca_params()
+            .self_signed(&key)
+            .map_err(|e| format!("Failed to build CA certificate: {}", e))?;
+
+        if !key_path.exists() || !cert_path.exists() {
+            write_private_key(&key_path, &key.serialize_pem())?;
+            fs::write(&cert_path, cert.pem())
+                .map_err(|e| format!("Failed to write CA certificate: {}", e))?;
+        }
+
+        Ok(Self {
+            cert,
+            key,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// TLS acceptor presenting a certificate for `host`, signed by this CA
+    pub fn acceptor_for(&self, host: &str) -> Result<TlsAcceptor, String> {
+        if let Some(config) = self.cache.lock().unwrap().get(host) {
+            return Ok(TlsAcceptor::from(config.clone()));
+        }
+
+        let mut params = CertificateParams::new(vec![host.to_string()])
+            .map_err(|e| format!("Invalid host for certificate: {}", e))?;
+        params.distinguished_name.push(DnType::CommonName, host);
+
+        let leaf_key = KeyPair::generate().map_err(|e| format!("Failed to generate key: {}", e))?;
+        let leaf = params
+            .signed_by(&leaf_key, &self.cert, &self.key)
+            .map_err(|e| format!("Failed to sign certificate for {}: {}", host, e))?;
+
+        let chain = vec![leaf.der().clone(), CertificateDer::from(self.cert.der().to_vec())];
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(leaf_key.serialize_der()));
+
+        let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Failed to configure TLS: {}", e))?
+            .with_no_client_auth()
+            .with_single_cert(chain, key)
+            .map_err(|e| format!("Failed to configure TLS: {}", e))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        let config = Arc::new(config);
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(host.to_string(), config.clone());
+        Ok(TlsAcceptor::from(config))
+    }
+}
@@ -0,0 +1,695 @@
+//! Intercepting HTTP(S) proxy
+//!
+//! Plain HTTP requests are forwarded as-is; CONNECT tunnels are terminated with a
+//! certificate from the local CA so HTTPS traffic can be captured too. Every
+//! request/response pair is recorded in an in-memory history and handed to a
+//! callback (the API layer turns that into a frontend event).
+
+pub mod ca;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::services::http_client::{HttpHeader, HttpRequestSpec};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use ca::CertificateAuthority;
+
+const MAX_HISTORY: usize = 2000;
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// Request bodies larger than this are refused rather than buffered
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// Headers that apply to a single hop and must not be forwarded
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "proxy-connection",
+    "keep-alive",
+    "transfer-encoding",
+    "te",
+    "trailer",
+    "upgrade",
+    "proxy-authorization",
+    "host",
+    "content-length",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    pub port: u16,
+    /// Terminate CONNECT tunnels with the local CA instead of passing them through
+    pub intercept_tls: bool,
+    /// Optional upstream proxy for chaining
+    pub upstream_proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub intercept_tls: bool,
+    pub ca_cert_path: Option<String>,
+    pub captured: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleTarget {
+    RequestUrl,
+    RequestHeader,
+    RequestBody,
+    ResponseHeader,
+    ResponseBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchReplaceRule {
+    pub enabled: bool,
+    pub target: RuleTarget,
+    pub pattern: String,
+    pub replacement: String,
+    /// Treat `pattern` as a regex rather than a literal string
+    #[serde(default)]
+    pub is_regex: bool,
+}
+
+/// A rule with its regex compiled once, when the rules are loaded or saved
+struct CompiledRule {
+    rule: MatchReplaceRule,
+    /// None for literal rules, and for stored regex rules that no longer compile
+    regex: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn new(rule: MatchReplaceRule) -> Result<Self, String> {
+        let regex = if rule.is_regex {
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid rule pattern '{}': {}", rule.pattern, e))?;
+            Some(regex)
+        } else {
+            None
+        };
+        Ok(Self { rule, regex })
+    }
+
+    fn applies(&self, target: RuleTarget) -> bool {
+        self.rule.enabled && self.rule.target == target
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedExchange {
+    pub id: u64,
+    pub timestamp: u64,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<HttpHeader>,
+    pub request_body: String,
+    pub request_body_base64: bool,
+    pub status: Option<u16>,
+    pub response_headers: Vec<HttpHeader>,
+    pub response_body: String,
+    pub response_body_base64: bool,
+    pub time_ms: u128,
+    pub error: Option<String>,
+}
+
+struct RunningProxy {
+    config: ProxyConfig,
+    shutdown: oneshot::Sender<()>,
+}
+
+lazy_static::lazy_static! {
+    static ref RUNNING: Mutex<Option<RunningProxy>> = Mutex::new(None);
+    static ref HISTORY: Mutex<VecDeque<CapturedExchange>> = Mutex::new(VecDeque::new());
+    static ref RULES: Mutex<Arc<Vec<CompiledRule>>> = Mutex::new(Arc::new(load_rules()));
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+type ExchangeCallback = Arc<dyn Fn(&CapturedExchange) + Send + Sync>;
+
+struct ProxyContext {
+    ca: Option<CertificateAuthority>,
+    client: reqwest::Client,
+    on_exchange: ExchangeCallback,
+}
+
+fn rules_file() -> std::path::PathBuf {
+    ctr_dir()
+        .map(|d| d.join("proxy").join("rules.json"))
+        .unwrap_or_default()
+}
+
+fn load_rules() -> Vec<CompiledRule> {
+    let rules: Vec<MatchReplaceRule> = load_json(&rules_file());
+    rules
+        .into_iter()
+        .map(|rule| {
+            CompiledRule::new(rule.clone()).unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                CompiledRule { rule, regex: None }
+            })
+        })
+        .collect()
+}
+
+pub fn get_rules() -> Vec<MatchReplaceRule> {
+    RULES.lock().unwrap().iter().map(|r| r.rule.clone()).collect()
+}
+
+pub fn set_rules(rules: Vec<MatchReplaceRule>) -> Result<(), String> {
+    let compiled = rules
+        .iter()
+        .cloned()
+        .map(CompiledRule::new)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let path = rules_file();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create proxy directory: {}", e))?;
+    }
+    save_json(&path, &rules)?;
+    *RULES.lock().unwrap() = Arc::new(compiled);
+    Ok(())
+}
+
+pub fn history() -> Vec<CapturedExchange> {
+    HISTORY.lock().unwrap().iter().cloned().collect()
+}
+
+pub fn clear_history() {
+    HISTORY.lock().unwrap().clear();
+}
+
+pub fn get_exchange(id: u64) -> Option<CapturedExchange> {
+    HISTORY.lock().unwrap().iter().find(|e| e.id == id).cloned()
+}
+
+pub fn status() -> ProxyStatus {
+    let running = RUNNING.lock().unwrap();
+    let captured = HISTORY.lock().unwrap().len();
+    match running.as_ref() {
+        Some(proxy) => ProxyStatus {
+            running: true,
+            port: Some(proxy.config.port),
+            intercept_tls: proxy.config.intercept_tls,
+            ca_cert_path: ca::ca_cert_path()
+                .ok()
+                .map(|p| p.to_string_lossy().to_string()),
+            captured,
+        },
+        None => ProxyStatus {
+            running: false,
+            port: None,
+            intercept_tls: false,
+            ca_cert_path: None,
+            captured,
+        },
+    }
+}
+
+/// Start the proxy on 127.0.0.1:<port>
+pub async fn start<F>(config: ProxyConfig, on_exchange: F) -> Result<ProxyStatus, String>
+where
+    F: Fn(&CapturedExchange) + Send + Sync + 'static,
+{
+    if RUNNING.lock().unwrap().is_some() {
+        return Err("Proxy is already running".to_string());
+    }
+
+    let ca = if config.intercept_tls {
+        Some(CertificateAuthority::load_or_create()?)
+    } else {
+        None
+    };
+
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .danger_accept_invalid_certs(true);
+    builder = match config.upstream_proxy.as_deref().filter(|p| !p.is_empty()) {
+        Some(upstream) => builder
+            .proxy(reqwest::Proxy::all(upstream).map_err(|e| format!("Invalid upstream proxy: {}", e))?),
+        None => builder.no_proxy(),
+    };
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let listener = TcpListener::bind(("127.0.0.1", config.port))
+        .await
+        .map_err(|e| format!("Failed to bind proxy port {}: {}", config.port, e))?;
+
+    let ctx = Arc::new(ProxyContext {
+        ca,
+        client,
+        on_exchange: Arc::new(on_exchange),
+    });
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        let ctx = ctx.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_client(stream, ctx).await {
+                                log::debug!("Proxy connection closed: {}", e);
+                            }
+                        });
+                    }
+                }
+            }
+        }
+    });
+
+    *RUNNING.lock().unwrap() = Some(RunningProxy {
+        config,
+        shutdown: shutdown_tx,
+    });
+
+    Ok(status())
+}
+
+pub fn stop() -> Result<(), String> {
+    let running = RUNNING
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("Proxy is not running")?;
+    let _ = running.shutdown.send(());
+    Ok(())
+}
+
+/// Turn a captured request into a spec the HTTP client (repeater) can send
+pub fn to_repeater(id: u64) -> Result<HttpRequestSpec, String> {
+    let exchange = get_exchange(id).ok_or_else(|| format!("Captured request {} not found", id))?;
+
+    Ok(HttpRequestSpec {
+        method: exchange.method,
+        url: exchange.url,
+        headers: exchange.request_headers,
+        body: if exchange.request_body.is_empty() {
+            None
+        } else {
+            Some(exchange.request_body)
+        },
+        body_base64: exchange.request_body_base64,
+        auth: None,
+        proxy: None,
+        verify_tls: false,
+        follow_redirects: false,
+        timeout_ms: None,
+    })
+}
+
+struct RequestHead {
+    method: String,
+    target: String,
+    headers: Vec<HttpHeader>,
+}
+
+impl RequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str())
+    }
+}
+
+async fn read_head<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<RequestHead>, String> {
+    let mut lines = Vec::new();
+    let mut total = 0;
+
+    loop {
+        let mut line = String::new();
+        let n = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| format!("Failed to read request: {}", e))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        total += n;
+        if total > MAX_HEAD_BYTES {
+            return Err("Request head too large".to_string());
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']).to_string();
+        if line.is_empty() {
+            if lines.is_empty() {
+                continue;
+            }
+            break;
+        }
+        lines.push(line);
+    }
+
+    let mut parts = lines[0].split_whitespace();
+    let method = parts.next().ok_or("Malformed request line")?.to_string();
+    let target = parts.next().ok_or("Malformed request line")?.to_string();
+
+    let headers = lines[1..]
+        .iter()
+        .filter_map(|l| l.split_once(':'))
+        .map(|(name, value)| HttpHeader {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+        })
+        .collect();
+
+    Ok(Some(RequestHead {
+        method,
+        target,
+        headers,
+    }))
+}
+
+async fn read_body<R: AsyncBufRead + Unpin>(reader: &mut R, head: &RequestHead) -> Result<Vec<u8>, String> {
+    let chunked = head
+        .header("transfer-encoding")
+        .map(|v| v.to_lowercase().contains("chunked"))
+        .unwrap_or(false);
+
+    if chunked {
+        let mut body = Vec::new();
+        loop {
+            let mut size_line = String::new();
+            reader
+                .read_line(&mut size_line)
+                .await
+                .map_err(|e| format!("Failed to read chunk: {}", e))?;
+            let size_str = size_line.trim().split(';').next().unwrap_or("0");
+            let size = usize::from_str_radix(size_str, 16)
+                .map_err(|_| "Malformed chunk size".to_string())?;
+            if size == 0 {
+                // Consume optional trailers up to the blank line
+                loop {
+                    let mut trailer = String::new();
+                    let n = reader.read_line(&mut trailer).await.map_err(|e| e.to_string())?;
+                    if n == 0 || trailer.trim().is_empty() {
+                        break;
+                    }
+                }
+                break;
+            }
+            if body.len().checked_add(size).map_or(true, |total| total > MAX_BODY_BYTES) {
+                return Err(format!("Request body exceeds {} bytes", MAX_BODY_BYTES));
+            }
+            // The chunk data is followed by CRLF
+            let mut chunk = vec![0u8; size + 2];
+            reader
+                .read_exact(&mut chunk)
+                .await
+                .map_err(|e| format!("Failed to read chunk: {}", e))?;
+            body.extend_from_slice(&chunk[..size]);
+        }
+        return Ok(body);
+    }
+
+    let length: usize = head
+        .header("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err(format!("Request body exceeds {} bytes", MAX_BODY_BYTES));
+    }
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| format!("Failed to read request body: {}", e))?;
+    Ok(body)
+}
+
+async fn handle_client(stream: TcpStream, ctx: Arc<ProxyContext>) -> Result<(), String> {
+    let mut reader = BufReader::new(stream);
+    let head = match read_head(&mut reader).await? {
+        Some(head) => head,
+        None => return Ok(()),
+    };
+
+    if !head.method.eq_ignore_ascii_case("CONNECT") {
+        return serve_http(reader, Some(head), None, ctx).await;
+    }
+
+    let authority = head.target.clone();
+    let host = authority
+        .rsplit_once(':')
+        .map(|(h, _)| h)
+        .unwrap_or(&authority)
+        .trim_matches(['[', ']'])
+        .to_string();
+
+    let mut stream = reader.into_inner();
+    stream
+        .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match &ctx.ca {
+        Some(ca) => {
+            let acceptor = ca.acceptor_for(&host)?;
+            let tls = acceptor
+                .accept(stream)
+                .await
+                .map_err(|e| format!("TLS handshake with client failed: {}", e))?;
+            let base = if authority.ends_with(":443") {
+                format!("https://{}", host)
+            } else {
+                format!("https://{}", authority)
+            };
+            serve_http(BufReader::new(tls), None, Some(base), ctx.clone()).await
+        }
+        None => {
+            // Pass-through tunnel when TLS interception is disabled
+            let mut upstream = TcpStream::connect(&authority)
+                .await
+                .map_err(|e| format!("Failed to connect to {}: {}", authority, e))?;
+            tokio::io::copy_bidirectional(&mut stream, &mut upstream)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Serve HTTP/1.1 requests on a (possibly TLS-wrapped) client connection
+async fn serve_http<S>(
+    mut reader: BufReader<S>,
+    mut first: Option<RequestHead>,
+    base_url: Option<String>,
+    ctx: Arc<ProxyContext>,
+) -> Result<(), String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let head = match first.take() {
+            Some(head) => head,
+            None => match read_head(&mut reader).await? {
+                Some(head) => head,
+                None => return Ok(()),
+            },
+        };
+        let body = read_body(&mut reader, &head).await?;
+
+        let close = head
+            .header("connection")
+            .or_else(|| head.header("proxy-connection"))
+            .map(|v| v.eq_ignore_ascii_case("close"))
+            .unwrap_or(false);
+
+        let url = match &base_url {
+            Some(base) => format!("{}{}", base, head.target),
+            None if head.target.starts_with("http://") || head.target.starts_with("https://") => {
+                head.target.clone()
+            }
+            None => match head.header("host") {
+                Some(host) => format!("http://{}{}", host, head.target),
+                None => return Err("Request without Host header".to_string()),
+            },
+        };
+
+        let response = forward(&ctx, head, url, body).await;
+        reader
+            .get_mut()
+            .write_all(&response)
+            .await
+            .map_err(|e| format!("Failed to write response: {}", e))?;
+
+        if close {
+            return Ok(());
+        }
+    }
+}
+
+fn apply_text_rules(rules: &[CompiledRule], target: RuleTarget, text: &str) -> String {
+    let mut result = text.to_string();
+    for compiled in rules.iter().filter(|r| r.applies(target)) {
+        let rule = &compiled.rule;
+        result = match &compiled.regex {
+            Some(re) => re.replace_all(&result, rule.replacement.as_str()).to_string(),
+            None if rule.is_regex => result,
+            None => result.replace(&rule.pattern, &rule.replacement),
+        };
+    }
+    result
+}
+
+fn apply_header_rules(rules: &[CompiledRule], target: RuleTarget, headers: Vec<HttpHeader>) -> Vec<HttpHeader> {
+    if !rules.iter().any(|r| r.applies(target)) {
+        return headers;
+    }
+
+    // Rules see headers as "Name: value" lines; an emptied line drops the header
+    headers
+        .into_iter()
+        .filter_map(|h| {
+            let line = apply_text_rules(rules, target, &format!("{}: {}", h.name, h.value));
+            line.split_once(':').map(|(name, value)| HttpHeader {
+                name: name.trim().to_string(),
+                value: value.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+fn apply_body_rules(rules: &[CompiledRule], target: RuleTarget, body: Vec<u8>) -> Vec<u8> {
+    if !rules.iter().any(|r| r.applies(target)) {
+        return body;
+    }
+    match String::from_utf8(body) {
+        Ok(text) => apply_text_rules(rules, target, &text).into_bytes(),
+        Err(e) => e.into_bytes(),
+    }
+}
+
+fn encode_body(bytes: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (BASE64.encode(bytes), true),
+    }
+}
+
+async fn forward(ctx: &ProxyContext, head: RequestHead, url: String, body: Vec<u8>) -> Vec<u8> {
+    let rules = RULES.lock().unwrap().clone();
+    let url = apply_text_rules(&rules, RuleTarget::RequestUrl, &url);
+    let request_headers = apply_header_rules(&rules, RuleTarget::RequestHeader, head.headers);
+    let body = apply_body_rules(&rules, RuleTarget::RequestBody, body);
+
+    let (request_body, request_body_base64) = encode_body(&body);
+    let mut exchange = CapturedExchange {
+        id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+        timestamp: crate::utils::time::now_millis(),
+        method: head.method.clone(),
+        url: url.clone(),
+        request_headers: request_headers.clone(),
+        request_body,
+        request_body_base64,
+        status: None,
+        response_headers: Vec::new(),
+        response_body: String::new(),
+        response_body_base64: false,
+        time_ms: 0,
+        error: None,
+    };
+
+    let start = Instant::now();
+    let result = async {
+        let method = reqwest::Method::from_bytes(head.method.as_bytes())
+            .map_err(|_| format!("Invalid HTTP method: {}", head.method))?;
+        let mut request = ctx.client.request(method, &url);
+        for header in request_headers
+            .iter()
+            .filter(|h| !HOP_BY_HOP.contains(&h.name.to_lowercase().as_str()))
+        {
+            request = request.header(header.name.as_str(), header.value.as_str());
+        }
+        if !body.is_empty() {
+            request = request.body(body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Upstream request failed: {}", e))?;
+        let status = response.status();
+        let headers: Vec<HttpHeader> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| HttpHeader {
+                name: name.to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).to_string(),
+            })
+            .collect();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read upstream response: {}", e))?;
+        Ok::<_, String>((status, headers, bytes.to_vec()))
+    }
+    .await;
+    exchange.time_ms = start.elapsed().as_millis();
+
+    let raw = match result {
+        Ok((status, headers, bytes)) => {
+            let headers = apply_header_rules(&rules, RuleTarget::ResponseHeader, headers);
+            let bytes = apply_body_rules(&rules, RuleTarget::ResponseBody, bytes);
+
+            let mut raw = format!(
+                "HTTP/1.1 {} {}\r\n",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("")
+            );
+            for header in headers
+                .iter()
+                .filter(|h| !HOP_BY_HOP.contains(&h.name.to_lowercase().as_str()))
+            {
+                raw.push_str(&format!("{}: {}\r\n", header.name, header.value));
+            }
+            raw.push_str(&format!("Content-Length: {}\r\n\r\n", bytes.len()));
+
+            let (response_body, response_body_base64) = encode_body(&bytes);
+            exchange.status = Some(status.as_u16());
+            exchange.response_headers = headers;
+            exchange.response_body = response_body;
+            exchange.response_body_base64 = response_body_base64;
+
+            let mut raw = raw.into_bytes();
+            raw.extend_from_slice(&bytes);
+            raw
+        }
+        Err(e) => {
+            let message = format!("CTR proxy error: {}", e);
+            exchange.status = Some(502);
+            exchange.error = Some(e);
+            format!(
+                "HTTP/1.1 502 Bad Gateway\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                message.len(),
+                message
+            )
+            .into_bytes()
+        }
+    };
+
+    (ctx.on_exchange)(&exchange);
+    let mut history = HISTORY.lock().unwrap();
+    if history.len() >= MAX_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(exchange);
+
+    raw
+}
@@ -0,0 +1,295 @@
+//! DNS reconnaissance: record lookups, reverse DNS, zone transfers and
+//! wordlist-based subdomain enumeration.
+
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::proto::op::{Message, MessageType, OpCode, Query};
+use hickory_resolver::proto::rr::{Name, Record, RecordType};
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+
+const AXFR_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: String,
+    pub ttl: u32,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubdomainHit {
+    pub subdomain: String,
+    pub addresses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneTransferResult {
+    pub nameserver: String,
+    pub success: bool,
+    /// False when the transfer ended before its closing SOA record; the
+    /// records received until then are still returned
+    pub complete: bool,
+    pub records: Vec<DnsRecord>,
+    pub error: Option<String>,
+}
+
+impl From<&Record> for DnsRecord {
+    fn from(record: &Record) -> Self {
+        DnsRecord {
+            name: record.name().to_string(),
+            record_type: record.record_type().to_string(),
+            ttl: record.ttl(),
+            data: record.data().map(|d| d.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Build a resolver, optionally pinned to a specific nameserver
+pub fn resolver(nameserver: Option<&str>) -> Result<TokioAsyncResolver, String> {
+    match nameserver.filter(|s| !s.is_empty()) {
+        Some(server) => {
+            let ip = IpAddr::from_str(server)
+                .map_err(|_| format!("Nameserver must be an IP address: {}", server))?;
+            let group = NameServerConfigGroup::from_ips_clear(&[ip], 53, true);
+            Ok(TokioAsyncResolver::tokio(
+                ResolverConfig::from_parts(None, vec![], group),
+                ResolverOpts::default(),
+            ))
+        }
+        // Fall back to public resolvers when the system config can't be read
+        None => Ok(TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|_| {
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+        })),
+    }
+}
+
+/// Look up records of any type (A, AAAA, MX, TXT, NS, SOA, CNAME, SRV, CAA, ...)
+pub async fn lookup(name: &str, record_type: &str, nameserver: Option<&str>) -> Result<Vec<DnsRecord>, String> {
+    let record_type = RecordType::from_str(&record_type.to_uppercase())
+        .map_err(|_| format!("Unknown record type: {}", record_type))?;
+    let resolver = resolver(nameserver)?;
+
+    let lookup = resolver
+        .lookup(name, record_type)
+        .await
+        .map_err(|e| format!("Lookup failed: {}", e))?;
+
+    Ok(lookup.records().iter().map(DnsRecord::from).collect())
+}
+
+pub async fn reverse_lookup(ip: &str, nameserver: Option<&str>) -> Result<Vec<String>, String> {
+    let ip = IpAddr::from_str(ip).map_err(|_| format!("Invalid IP address: {}", ip))?;
+    let resolver = resolver(nameserver)?;
+
+    let lookup = resolver
+        .reverse_lookup(ip)
+        .await
+        .map_err(|e| format!("Reverse lookup failed: {}", e))?;
+
+    Ok(lookup.iter().map(|name| name.to_string()).collect())
+}
+
+/// Attempt an AXFR against every authoritative nameserver of `domain`
+/// (or just `nameserver` when given)
+pub async fn zone_transfer(domain: &str, nameserver: Option<&str>) -> Result<Vec<ZoneTransferResult>, String> {
+    let targets: Vec<String> = match nameserver.filter(|s| !s.is_empty()) {
+        Some(ns) => vec![ns.to_string()],
+        None => lookup(domain, "NS", None)
+            .await?
+            .into_iter()
+            .map(|r| r.data)
+            .collect(),
+    };
+
+    let mut results = Vec::new();
+    for target in targets {
+        let result = match tokio::time::timeout(AXFR_TIMEOUT, axfr(domain, &target)).await {
+            Ok(Ok((records, true))) => ZoneTransferResult {
+                nameserver: target,
+                success: !records.is_empty(),
+                complete: true,
+                records,
+                error: None,
+            },
+            Ok(Ok((records, false))) => ZoneTransferResult {
+                nameserver: target,
+                success: false,
+                complete: false,
+                error: (!records.is_empty()).then(|| {
+                    format!("Transfer ended before the closing SOA record after {} records", records.len())
+                }),
+                records,
+            },
+            Ok(Err(e)) => ZoneTransferResult {
+                nameserver: target,
+                success: false,
+                complete: false,
+                records: Vec::new(),
+                error: Some(e),
+            },
+            Err(_) => ZoneTransferResult {
+                nameserver: target,
+                success: false,
+                complete: false,
+                records: Vec::new(),
+                error: Some("Zone transfer timed out".to_string()),
+            },
+        };
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+async fn resolve_server(server: &str) -> Result<SocketAddr, String> {
+    if let Ok(ip) = IpAddr::from_str(server) {
+        return Ok(SocketAddr::new(ip, 53));
+    }
+    let ips = resolver(None)?
+        .lookup_ip(server)
+        .await
+        .map_err(|e| format!("Failed to resolve nameserver {}: {}", server, e))?;
+    ips.iter()
+        .next()
+        .map(|ip| SocketAddr::new(ip, 53))
+        .ok_or_else(|| format!("Nameserver {} has no address", server))
+}
+
+/// The transferred records, and whether the transfer was complete
+async fn axfr(domain: &str, server: &str) -> Result<(Vec<DnsRecord>, bool), String> {
+    let zone = Name::from_str(domain).map_err(|e| format!("Invalid domain: {}", e))?;
+    let addr = resolve_server(server).await?;
+
+    let mut query = Message::new();
+    query
+        .set_id(rand_id())
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(false)
+        .add_query(Query::query(zone, RecordType::AXFR));
+    let bytes = query
+        .to_vec()
+        .map_err(|e| format!("Failed to encode query: {}", e))?;
+
+    let mut stream = TcpStream::connect(addr)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", addr, e))?;
+    // DNS over TCP: two-byte length prefix
+    stream
+        .write_all(&(bytes.len() as u16).to_be_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    stream.write_all(&bytes).await.map_err(|e| e.to_string())?;
+
+    // A complete transfer is bracketed by two SOA records
+    let mut records = Vec::new();
+    let mut soa_seen = 0;
+    while soa_seen < 2 {
+        let mut len = [0u8; 2];
+        if stream.read_exact(&mut len).await.is_err() {
+            break;
+        }
+        let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+        stream
+            .read_exact(&mut buf)
+            .await
+            .map_err(|e| format!("Truncated response: {}", e))?;
+
+        let response = Message::from_vec(&buf).map_err(|e| format!("Malformed response: {}", e))?;
+        if response.response_code() != hickory_resolver::proto::op::ResponseCode::NoError {
+            return Err(format!("Transfer refused: {}", response.response_code()));
+        }
+        if response.answers().is_empty() {
+            break;
+        }
+        for record in response.answers() {
+            if record.record_type() == RecordType::SOA {
+                soa_seen += 1;
+            }
+            records.push(DnsRecord::from(record));
+        }
+    }
+
+    Ok((records, soa_seen >= 2))
+}
+
+fn rand_id() -> u16 {
+    (uuid::Uuid::new_v4().as_u128() & 0xffff) as u16
+}
+
+async fn resolve_addresses(resolver: &TokioAsyncResolver, name: &str) -> Option<BTreeSet<String>> {
+    resolver
+        .lookup_ip(name)
+        .await
+        .ok()
+        .map(|ips| ips.iter().map(|ip| ip.to_string()).collect())
+        .filter(|set: &BTreeSet<String>| !set.is_empty())
+}
+
+/// Brute-force subdomains of `domain` from `words`, calling `on_hit` for each
+/// name that resolves. Wildcard DNS answers are filtered out.
+pub async fn enumerate_subdomains<F>(
+    domain: &str,
+    words: Vec<String>,
+    concurrency: usize,
+    nameserver: Option<&str>,
+    on_hit: F,
+) -> Result<Vec<SubdomainHit>, String>
+where
+    F: Fn(&SubdomainHit) + Send + Sync + 'static,
+{
+    let resolver = Arc::new(resolver(nameserver)?);
+    let wildcard = resolve_addresses(&resolver, &format!("{}.{}", uuid::Uuid::new_v4().simple(), domain)).await;
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let on_hit = Arc::new(on_hit);
+    let wildcard = Arc::new(wildcard);
+    let mut handles = Vec::new();
+
+    for word in words {
+        let word = word.trim().to_string();
+        if word.is_empty() || word.starts_with('#') {
+            continue;
+        }
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| e.to_string())?;
+        let resolver = resolver.clone();
+        let on_hit = on_hit.clone();
+        let wildcard = wildcard.clone();
+        let subdomain = format!("{}.{}", word, domain);
+
+        handles.push(tokio::spawn(async move {
+            let _permit = permit;
+            let addresses = resolve_addresses(&resolver, &subdomain).await?;
+            if wildcard.as_ref().as_ref() == Some(&addresses) {
+                return None;
+            }
+            let hit = SubdomainHit {
+                subdomain,
+                addresses: addresses.into_iter().collect(),
+            };
+            on_hit(&hit);
+            Some(hit)
+        }));
+    }
+
+    let mut hits = Vec::new();
+    for handle in handles {
+        if let Ok(Some(hit)) = handle.await {
+            hits.push(hit);
+        }
+    }
+    Ok(hits)
+}
@@ -0,0 +1,496 @@
+//! Report export: Markdown is the canonical rendering, HTML is produced from
+//! it with a print stylesheet, and PDF is laid out directly with the built-in
+//! PDF fonts.
+
+use chrono::DateTime;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use pulldown_cmark::{html, Event, Options, Parser};
+use serde::Deserialize;
+use std::io::BufWriter;
+use std::path::Path;
+
+use super::Report;
+use crate::services::security::licenses::{DependencyLicense, PolicyVerdict};
+
+pub const DEFAULT_METHODOLOGY: &str = "Testing followed the OWASP Web Security Testing Guide: \
+reconnaissance, mapping, automated and manual vulnerability discovery, exploitation to \
+confirm impact, and reporting.";
+
+const SEVERITIES: &[&str] = &["critical", "high", "medium", "low", "info"];
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// YYYY-MM-DD (UTC) for a millisecond timestamp
+fn date(millis: u64) -> String {
+    i64::try_from(millis)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|at| at.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Longest run of backticks in `text` plus one, so fences never close early
+fn fence_for(text: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat((longest + 1).max(3))
+}
+
+/// file:// URL for a local path so exported reports can show screenshots
+fn file_url(path: &str) -> String {
+    let path = path.replace('\\', "/").replace(' ', "%20");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+pub fn to_markdown(report: &Report) -> String {
+    let mut md = String::new();
+    md.push_str(&format!("# {}\n\n", report.title));
+    if !report.client.is_empty() {
+        md.push_str(&format!("**Client:** {}  \n", report.client));
+    }
+    if !report.author.is_empty() {
+        md.push_str(&format!("**Author:** {}  \n", report.author));
+    }
+    md.push_str(&format!("**Date:** {}\n\n", date(report.updated_at)));
+
+    md.push_str("## Executive Summary\n\n");
+    if report.executive_summary.trim().is_empty() {
+        md.push_str(&format!(
+            "The assessment identified {} finding(s).\n\n",
+            report.findings.len()
+        ));
+    } else {
+        md.push_str(&format!("{}\n\n", report.executive_summary.trim()));
+    }
+
+    if !report.scope.is_empty() {
+        md.push_str("## Scope\n\n");
+        for item in &report.scope {
+            md.push_str(&format!("- {}\n", item));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Methodology\n\n");
+    md.push_str(&format!("{}\n\n", report.methodology.trim()));
+
+    md.push_str("## Summary of Findings\n\n");
+    md.push_str("| Severity | Count |\n|---|---|\n");
+    for severity in SEVERITIES {
+        let count = report.findings.iter().filter(|f| f.severity == *severity).count();
+        md.push_str(&format!("| {} | {} |\n", capitalize(severity), count));
+    }
+    md.push('\n');
+
+    if !report.findings.is_empty() {
+        md.push_str("| # | Finding | Severity |\n|---|---|---|\n");
+        for (i, finding) in report.findings.iter().enumerate() {
+            md.push_str(&format!(
+                "| {} | {} | {} |\n",
+                i + 1,
+                finding.title.replace('|', "\\|"),
+                capitalize(&finding.severity)
+            ));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Detailed Findings\n\n");
+    for (i, finding) in report.findings.iter().enumerate() {
+        md.push_str(&format!("### {}. {}\n\n", i + 1, finding.title));
+        md.push_str(&format!("**Severity:** {}  \n", capitalize(&finding.severity)));
+        if let Some(cwe) = &finding.cwe {
+            md.push_str(&format!("**CWE:** {}  \n", cwe));
+        }
+        if let Some(location) = finding.location.as_ref().filter(|l| !l.is_empty()) {
+            md.push_str(&format!("**Location:** `{}`  \n", location));
+        }
+        md.push('\n');
+
+        if !finding.description.trim().is_empty() {
+            md.push_str(&format!("{}\n\n", finding.description.trim()));
+        }
+        if !finding.evidence_text.trim().is_empty() {
+            let fence = fence_for(&finding.evidence_text);
+            md.push_str(&format!(
+                "#### Evidence\n\n{}\n{}\n{}\n\n",
+                fence,
+                finding.evidence_text.trim_end(),
+                fence
+            ));
+        }
+        if !finding.attachments.is_empty() {
+            md.push_str("#### Screenshots\n\n");
+            for attachment in &finding.attachments {
+                md.push_str(&format!(
+                    "![{}](<{}>)\n\n*SHA-256: `{}`*\n\n",
+                    attachment.caption.replace(['[', ']'], ""),
+                    file_url(&attachment.path),
+                    attachment.sha256
+                ));
+            }
+        }
+        if !finding.annotation.trim().is_empty() {
+            md.push_str(&format!("#### Tester Notes\n\n{}\n\n", finding.annotation.trim()));
+        }
+        if !finding.remediation.trim().is_empty() {
+            md.push_str(&format!("#### Remediation\n\n{}\n\n", finding.remediation.trim()));
+        }
+    }
+
+    if !report.licenses.is_empty() {
+        md.push_str("## License Compliance\n\n");
+        md.push_str(&format!("{}\n\n", license_summary(report)));
+        md.push_str("| Package | Version | License | Category | Policy | Manifest |\n|---|---|---|---|---|---|\n");
+        for license in sorted_licenses(report) {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} | `{}` |\n",
+                license.dependency.name,
+                license.dependency.version.as_deref().unwrap_or("-"),
+                license.license.as_deref().unwrap_or("Unknown").replace('|', "\\|"),
+                capitalize(&license.category.name().replace('_', " ")),
+                verdict_label(license.verdict),
+                license.dependency.manifest
+            ));
+        }
+        md.push('\n');
+    }
+
+    md
+}
+
+const HTML_STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; max-width: 900px; margin: 2rem auto; color: #1f2328; line-height: 1.5; }
+h1 { border-bottom: 3px solid #b60205; padding-bottom: .3rem; }
+h2 { border-bottom: 1px solid #d0d7de; padding-bottom: .2rem; margin-top: 2rem; }
+h3 { page-break-before: auto; margin-top: 1.8rem; }
+table { border-collapse: collapse; margin: 1rem 0; }
+th, td { border: 1px solid #d0d7de; padding: .3rem .8rem; text-align: left; }
+pre { background: #f6f8fa; padding: .8rem; overflow-x: auto; white-space: pre-wrap; word-break: break-all; font-size: .85rem; }
+code { font-family: Consolas, 'Courier New', monospace; }
+@media print { body { margin: 0; max-width: none; } pre { page-break-inside: avoid; } }
+";
+
+/// Denied licenses first, then those to review, then by package name
+fn sorted_licenses(report: &Report) -> Vec<&DependencyLicense> {
+    let rank = |verdict: PolicyVerdict| match verdict {
+        PolicyVerdict::Denied => 0,
+        PolicyVerdict::Review => 1,
+        PolicyVerdict::Allowed => 2,
+    };
+    let mut licenses: Vec<&DependencyLicense> = report.licenses.iter().collect();
+    licenses.sort_by(|a, b| {
+        rank(a.verdict)
+            .cmp(&rank(b.verdict))
+            .then_with(|| a.dependency.name.cmp(&b.dependency.name))
+    });
+    licenses
+}
+
+fn verdict_label(verdict: PolicyVerdict) -> &'static str {
+    match verdict {
+        PolicyVerdict::Allowed => "Allowed",
+        PolicyVerdict::Review => "Review",
+        PolicyVerdict::Denied => "Denied",
+    }
+}
+
+/// One line on how many dependency licenses the policy denies or flags
+fn license_summary(report: &Report) -> String {
+    let count = |verdict| report.licenses.iter().filter(|l| l.verdict == verdict).count();
+    format!(
+        "{} dependencies checked against the license policy: {} denied, {} to review.",
+        report.licenses.len(),
+        count(PolicyVerdict::Denied),
+        count(PolicyVerdict::Review)
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn to_html(report: &Report) -> String {
+    let markdown = to_markdown(report);
+    let mut body = String::new();
+    // Findings routinely quote attack payloads, so raw HTML is rendered as text
+    let events = Parser::new_ext(&markdown, Options::ENABLE_TABLES).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        other => other,
+    });
+    html::push_html(&mut body, events);
+    format!(
+        "<!doctype html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(&report.title),
+        HTML_STYLE,
+        body
+    )
+}
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+
+/// Sequential text layout over A4 pages
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    mono: IndirectFontRef,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let font = |f| doc.add_builtin_font(f).map_err(|e| format!("Failed to load PDF font: {}", e));
+        let regular = font(BuiltinFont::Helvetica)?;
+        let bold = font(BuiltinFont::HelveticaBold)?;
+        let mono = font(BuiltinFont::Courier)?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            mono,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn space(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+
+    /// Wrap `text` to the page width and write it; `mono` selects Courier
+    fn text(&mut self, text: &str, size: f32, bold: bool, mono: bool) {
+        // Helvetica averages ~0.5em per glyph, Courier is exactly 0.6em
+        let char_width_mm = size * if mono { 0.6 } else { 0.5 } * 0.3528;
+        let max_chars = (((PAGE_WIDTH - 2.0 * MARGIN) / char_width_mm) as usize).max(10);
+        let line_height = size * 0.3528 * 1.4;
+
+        for paragraph in text.lines() {
+            // The built-in fonts only cover Latin-1
+            let paragraph: String = paragraph
+                .chars()
+                .map(|c| match c {
+                    '\t' => ' ',
+                    c if (c as u32) < 256 => c,
+                    _ => '?',
+                })
+                .collect();
+            for line in wrap(&paragraph, max_chars) {
+                if self.y - line_height < MARGIN {
+                    self.new_page();
+                }
+                self.y -= line_height;
+                let font = if mono {
+                    &self.mono
+                } else if bold {
+                    &self.bold
+                } else {
+                    &self.regular
+                };
+                self.layer.use_text(line, size, Mm(MARGIN), Mm(self.y), font);
+            }
+        }
+    }
+}
+
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    if text.is_empty() {
+        return vec![String::new()];
+    }
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split(' ') {
+        let mut word = word.to_string();
+        // Hard-break words longer than a line
+        while word.chars().count() > max_chars {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let split: String = word.chars().take(max_chars).collect();
+            word = word.chars().skip(max_chars).collect();
+            lines.push(split);
+        }
+        if current.chars().count() + word.chars().count() + 1 > max_chars && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    lines.push(current);
+    lines
+}
+
+pub fn to_pdf(report: &Report) -> Result<Vec<u8>, String> {
+    let mut pdf = PdfWriter::new(&report.title)?;
+
+    pdf.text(&report.title, 22.0, true, false);
+    pdf.space(4.0);
+    if !report.client.is_empty() {
+        pdf.text(&format!("Client: {}", report.client), 11.0, false, false);
+    }
+    if !report.author.is_empty() {
+        pdf.text(&format!("Author: {}", report.author), 11.0, false, false);
+    }
+    pdf.text(&format!("Date: {}", date(report.updated_at)), 11.0, false, false);
+    pdf.space(6.0);
+
+    pdf.text("Executive Summary", 16.0, true, false);
+    pdf.space(2.0);
+    let summary = if report.executive_summary.trim().is_empty() {
+        format!("The assessment identified {} finding(s).", report.findings.len())
+    } else {
+        report.executive_summary.trim().to_string()
+    };
+    pdf.text(&summary, 11.0, false, false);
+    pdf.space(5.0);
+
+    if !report.scope.is_empty() {
+        pdf.text("Scope", 16.0, true, false);
+        pdf.space(2.0);
+        for item in &report.scope {
+            pdf.text(&format!("- {}", item), 11.0, false, false);
+        }
+        pdf.space(5.0);
+    }
+
+    pdf.text("Methodology", 16.0, true, false);
+    pdf.space(2.0);
+    pdf.text(report.methodology.trim(), 11.0, false, false);
+    pdf.space(5.0);
+
+    pdf.text("Summary of Findings", 16.0, true, false);
+    pdf.space(2.0);
+    for severity in SEVERITIES {
+        let count = report.findings.iter().filter(|f| f.severity == *severity).count();
+        pdf.text(&format!("{:<10} {}", capitalize(severity), count), 11.0, false, true);
+    }
+    pdf.space(5.0);
+
+    pdf.text("Detailed Findings", 16.0, true, false);
+    for (i, finding) in report.findings.iter().enumerate() {
+        pdf.space(5.0);
+        pdf.text(&format!("{}. {}", i + 1, finding.title), 13.0, true, false);
+        pdf.space(1.0);
+        let mut meta = format!("Severity: {}", capitalize(&finding.severity));
+        if let Some(cwe) = &finding.cwe {
+            meta.push_str(&format!("    CWE: {}", cwe));
+        }
+        pdf.text(&meta, 10.0, false, false);
+        if let Some(location) = finding.location.as_ref().filter(|l| !l.is_empty()) {
+            pdf.text(&format!("Location: {}", location), 10.0, false, false);
+        }
+        pdf.space(2.0);
+
+        if !finding.description.trim().is_empty() {
+            pdf.text(finding.description.trim(), 11.0, false, false);
+            pdf.space(2.0);
+        }
+        if !finding.evidence_text.trim().is_empty() {
+            pdf.text("Evidence", 11.0, true, false);
+            pdf.text(finding.evidence_text.trim_end(), 8.0, false, true);
+            pdf.space(2.0);
+        }
+        if !finding.attachments.is_empty() {
+            pdf.text("Screenshots", 11.0, true, false);
+            for attachment in &finding.attachments {
+                pdf.text(&format!("{} ({})", attachment.caption, attachment.path), 10.0, false, false);
+                pdf.text(&format!("SHA-256: {}", attachment.sha256), 8.0, false, true);
+            }
+            pdf.space(2.0);
+        }
+        if !finding.annotation.trim().is_empty() {
+            pdf.text("Tester Notes", 11.0, true, false);
+            pdf.text(finding.annotation.trim(), 11.0, false, false);
+            pdf.space(2.0);
+        }
+        if !finding.remediation.trim().is_empty() {
+            pdf.text("Remediation", 11.0, true, false);
+            pdf.text(finding.remediation.trim(), 11.0, false, false);
+        }
+    }
+
+    if !report.licenses.is_empty() {
+        pdf.space(5.0);
+        pdf.text("License Compliance", 16.0, true, false);
+        pdf.space(2.0);
+        pdf.text(&license_summary(report), 11.0, false, false);
+        pdf.space(2.0);
+        for license in sorted_licenses(report) {
+            let package = match &license.dependency.version {
+                Some(version) => format!("{}@{}", license.dependency.name, version),
+                None => license.dependency.name.clone(),
+            };
+            pdf.text(
+                &format!(
+                    "{:<8} {:<32} {}",
+                    verdict_label(license.verdict),
+                    package,
+                    license.license.as_deref().unwrap_or("Unknown")
+                ),
+                9.0,
+                false,
+                true,
+            );
+        }
+    }
+
+    let mut buffer = BufWriter::new(Vec::new());
+    pdf.doc
+        .save(&mut buffer)
+        .map_err(|e| format!("Failed to render PDF: {}", e))?;
+    buffer
+        .into_inner()
+        .map_err(|e| format!("Failed to render PDF: {}", e))
+}
+
+/// Render `report` in `format` and write it to `path`
+pub fn export(report: &Report, format: ReportFormat, path: &Path) -> Result<(), String> {
+    let bytes = match format {
+        ReportFormat::Markdown => to_markdown(report).into_bytes(),
+        ReportFormat::Html => to_html(report).into_bytes(),
+        ReportFormat::Pdf => to_pdf(report)?,
+    };
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write report: {}", e))
+}
@@ -0,0 +1,521 @@
+//! Pentest report builder
+//!
+//! Reports collect findings backed by evidence from the rest of the IDE
+//! (scanner issues, prover results, verified payloads, proxy captures,
+//! terminal recordings). Each report is one JSON document under
+//! ~/.ctr/reports/ and is exported through the `export` module.
+
+pub mod export;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::services::evidence;
+use crate::services::security::licenses::DependencyLicense;
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+
+/// Longest evidence body copied into a report, in characters
+const MAX_EVIDENCE_CHARS: usize = 8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceKind {
+    ScannerIssue,
+    ProverResult,
+    VerifiedPayload,
+    HttpCapture,
+    TerminalRecording,
+    Note,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportFinding {
+    pub id: String,
+    pub kind: EvidenceKind,
+    pub title: String,
+    /// critical, high, medium, low or info
+    pub severity: String,
+    #[serde(default)]
+    pub description: String,
+    /// Tester commentary shown under the evidence
+    #[serde(default)]
+    pub annotation: String,
+    #[serde(default)]
+    pub remediation: String,
+    #[serde(default)]
+    pub cwe: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// The original object the finding was created from
+    #[serde(default)]
+    pub evidence: Value,
+    /// Pre-rendered plain text of `evidence`, used by the exporters
+    #[serde(default)]
+    pub evidence_text: String,
+    /// Captured evidence files (screenshots) backing the finding
+    #[serde(default)]
+    pub attachments: Vec<EvidenceAttachment>,
+    pub added_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceAttachment {
+    pub evidence_id: String,
+    pub path: String,
+    pub sha256: String,
+    #[serde(default)]
+    pub caption: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub client: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub scope: Vec<String>,
+    #[serde(default)]
+    pub executive_summary: String,
+    #[serde(default)]
+    pub methodology: String,
+    #[serde(default)]
+    pub findings: Vec<ReportFinding>,
+    /// Dependency licenses of the assessed code, shown in their own section
+    #[serde(default)]
+    pub licenses: Vec<DependencyLicense>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSummary {
+    pub id: String,
+    pub title: String,
+    pub client: String,
+    pub finding_count: usize,
+    pub updated_at: u64,
+}
+
+/// Editable report fields; `None` leaves the current value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReportMeta {
+    pub title: Option<String>,
+    pub client: Option<String>,
+    pub author: Option<String>,
+    pub scope: Option<Vec<String>>,
+    pub executive_summary: Option<String>,
+    pub methodology: Option<String>,
+}
+
+/// A finding to attach; empty fields are filled in from the evidence
+#[derive(Debug, Clone, Deserialize)]
+pub struct FindingInput {
+    pub kind: EvidenceKind,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub severity: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub annotation: String,
+    #[serde(default)]
+    pub remediation: String,
+    #[serde(default)]
+    pub evidence: Value,
+}
+
+/// Editable finding fields; `None` leaves the current value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FindingUpdate {
+    pub title: Option<String>,
+    pub severity: Option<String>,
+    pub description: Option<String>,
+    pub annotation: Option<String>,
+    pub remediation: Option<String>,
+}
+
+fn reports_dir() -> Result<PathBuf, String> {
+    let dir = ctr_dir()?.join("reports");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create reports directory: {}", e))?;
+    Ok(dir)
+}
+
+fn report_file(id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid report id: {}", id));
+    }
+    Ok(reports_dir()?.join(format!("{}.json", id)))
+}
+
+pub fn load(id: &str) -> Result<Report, String> {
+    let path = report_file(id)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read report {}: {}", id, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse report {}: {}", id, e))
+}
+
+fn save(report: &mut Report) -> Result<(), String> {
+    report.updated_at = now_millis();
+    save_json(&report_file(&report.id)?, report)
+}
+
+pub fn create(title: &str) -> Result<Report, String> {
+    let now = now_millis();
+    let mut report = Report {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        client: String::new(),
+        author: String::new(),
+        scope: Vec::new(),
+        executive_summary: String::new(),
+        methodology: export::DEFAULT_METHODOLOGY.to_string(),
+        findings: Vec::new(),
+        licenses: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+    save(&mut report)?;
+    Ok(report)
+}
+
+pub fn list() -> Vec<ReportSummary> {
+    let Ok(dir) = reports_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<ReportSummary> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| {
+            let report: Option<Report> = load_json(&e.path());
+            report
+        })
+        .map(|r| ReportSummary {
+            id: r.id,
+            title: r.title,
+            client: r.client,
+            finding_count: r.findings.len(),
+            updated_at: r.updated_at,
+        })
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.updated_at));
+    reports
+}
+
+pub fn delete(id: &str) -> Result<(), String> {
+    std::fs::remove_file(report_file(id)?).map_err(|e| format!("Failed to delete report: {}", e))
+}
+
+pub fn update_meta(id: &str, meta: ReportMeta) -> Result<Report, String> {
+    let mut report = load(id)?;
+    if let Some(title) = meta.title {
+        report.title = title;
+    }
+    if let Some(client) = meta.client {
+        report.client = client;
+    }
+    if let Some(author) = meta.author {
+        report.author = author;
+    }
+    if let Some(scope) = meta.scope {
+        report.scope = scope;
+    }
+    if let Some(summary) = meta.executive_summary {
+        report.executive_summary = summary;
+    }
+    if let Some(methodology) = meta.methodology {
+        report.methodology = methodology;
+    }
+    save(&mut report)?;
+    Ok(report)
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+fn truncate(text: String) -> String {
+    match text.char_indices().nth(MAX_EVIDENCE_CHARS) {
+        Some((end, _)) => format!("{}\n... [truncated]", &text[..end]),
+        None => text,
+    }
+}
+
+fn headers_text(value: &Value) -> String {
+    value
+        .as_array()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|h| format!("{}: {}\n", str_field(h, "name"), str_field(h, "value")))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Default (title, severity, description, cwe, location) for a piece of evidence
+fn derive_defaults(kind: EvidenceKind, evidence: &Value) -> (String, String, String, Option<String>, Option<String>) {
+    let cwe = evidence.get("cwe").and_then(Value::as_str).map(|s| s.to_string());
+    match kind {
+        EvidenceKind::ScannerIssue => (
+            str_field(evidence, "kind").to_string(),
+            str_field(evidence, "severity").to_lowercase(),
+            str_field(evidence, "message").to_string(),
+            cwe,
+            Some(format!(
+                "{}:{}",
+                str_field(evidence, "file"),
+                evidence.get("line").and_then(Value::as_u64).unwrap_or(0)
+            )),
+        ),
+        EvidenceKind::ProverResult => {
+            let sink = evidence
+                .get("sinks")
+                .and_then(|s| s.get(0))
+                .and_then(|s| s.get("sink_type"))
+                .and_then(Value::as_str)
+                .unwrap_or("Vulnerability");
+            let exploitable = str_field(evidence, "status") == "Exploitable";
+            (
+                format!("Proven {}", sink),
+                if exploitable { "high" } else { "medium" }.to_string(),
+                str_field(evidence, "explanation").to_string(),
+                cwe,
+                None,
+            )
+        }
+        EvidenceKind::VerifiedPayload => (
+            format!("Verified payload in parameter '{}'", str_field(evidence, "parameter")),
+            "high".to_string(),
+            format!(
+                "The payload was confirmed against {}.",
+                str_field(evidence, "target_url")
+            ),
+            cwe,
+            Some(str_field(evidence, "target_url").to_string()),
+        ),
+        EvidenceKind::HttpCapture => (
+            format!("{} {}", str_field(evidence, "method"), str_field(evidence, "url")),
+            "info".to_string(),
+            String::new(),
+            cwe,
+            Some(str_field(evidence, "url").to_string()),
+        ),
+        EvidenceKind::TerminalRecording => ("Terminal session".to_string(), "info".to_string(), String::new(), cwe, None),
+        EvidenceKind::Note => ("Note".to_string(), "info".to_string(), String::new(), cwe, None),
+    }
+}
+
+/// Plain-text rendering of the evidence object for exports
+fn render_evidence(kind: EvidenceKind, evidence: &Value) -> String {
+    let text = match kind {
+        EvidenceKind::ScannerIssue => {
+            let mut text = format!(
+                "{}:{}\n{}",
+                str_field(evidence, "file"),
+                evidence.get("line").and_then(Value::as_u64).unwrap_or(0),
+                str_field(evidence, "message")
+            );
+            if let Some(hint) = evidence.get("fix_hint").and_then(Value::as_str) {
+                text.push_str(&format!("\nFix: {}", hint));
+            }
+            text
+        }
+        EvidenceKind::ProverResult => {
+            let mut text = format!("Status: {}\n", str_field(evidence, "status"));
+            for node in evidence.get("attack_path").and_then(Value::as_array).into_iter().flatten() {
+                text.push_str(&format!(
+                    "  line {}: {}  # {}\n",
+                    node.get("line").and_then(Value::as_u64).unwrap_or(0),
+                    str_field(node, "code"),
+                    str_field(node, "description")
+                ));
+            }
+            if let Some(payload) = evidence.get("payload").and_then(Value::as_str) {
+                text.push_str(&format!("Payload: {}\n", payload));
+            }
+            text
+        }
+        EvidenceKind::VerifiedPayload => {
+            let signals: Vec<String> = evidence
+                .get("signals")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|s| format!("{} ({})", str_field(s, "kind"), str_field(s, "detail")))
+                .collect();
+            format!(
+                "{} {}\nParameter: {}\nPayload: {}\nSignals: {}\n\n{}",
+                str_field(evidence, "method"),
+                str_field(evidence, "target_url"),
+                str_field(evidence, "parameter"),
+                str_field(evidence, "payload"),
+                signals.join(", "),
+                str_field(evidence, "evidence_excerpt")
+            )
+        }
+        EvidenceKind::HttpCapture => {
+            let status = evidence
+                .get("status")
+                .and_then(Value::as_u64)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            format!(
+                "{} {}\n{}\n{}\n\nHTTP {}\n{}\n{}",
+                str_field(evidence, "method"),
+                str_field(evidence, "url"),
+                headers_text(&evidence["request_headers"]),
+                str_field(evidence, "request_body"),
+                status,
+                headers_text(&evidence["response_headers"]),
+                str_field(evidence, "response_body")
+            )
+        }
+        EvidenceKind::TerminalRecording => match evidence {
+            Value::String(s) => s.clone(),
+            // A list of audit log entries
+            Value::Array(entries) => entries
+                .iter()
+                .map(|e| format!("$ {}\n", str_field(e, "command")))
+                .collect(),
+            other => other.to_string(),
+        },
+        EvidenceKind::Note => match evidence {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        },
+    };
+    truncate(text)
+}
+
+pub fn add_finding(report_id: &str, input: FindingInput) -> Result<ReportFinding, String> {
+    let mut report = load(report_id)?;
+    let (title, severity, description, cwe, location) = derive_defaults(input.kind, &input.evidence);
+    let pick = |given: String, derived: String| if given.trim().is_empty() { derived } else { given };
+
+    let finding = ReportFinding {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: input.kind,
+        title: pick(input.title, title),
+        severity: pick(input.severity.to_lowercase(), severity),
+        description: pick(input.description, description),
+        annotation: input.annotation,
+        remediation: input.remediation,
+        cwe,
+        location,
+        evidence_text: render_evidence(input.kind, &input.evidence),
+        evidence: input.evidence,
+        attachments: Vec::new(),
+        added_at: now_millis(),
+    };
+    report.findings.push(finding.clone());
+    save(&mut report)?;
+    Ok(finding)
+}
+
+pub fn update_finding(report_id: &str, finding_id: &str, update: FindingUpdate) -> Result<ReportFinding, String> {
+    let mut report = load(report_id)?;
+    let finding = report
+        .findings
+        .iter_mut()
+        .find(|f| f.id == finding_id)
+        .ok_or_else(|| format!("Finding {} not found", finding_id))?;
+
+    if let Some(title) = update.title {
+        finding.title = title;
+    }
+    if let Some(severity) = update.severity {
+        finding.severity = severity.to_lowercase();
+    }
+    if let Some(description) = update.description {
+        finding.description = description;
+    }
+    if let Some(annotation) = update.annotation {
+        finding.annotation = annotation;
+    }
+    if let Some(remediation) = update.remediation {
+        finding.remediation = remediation;
+    }
+    let finding = finding.clone();
+    save(&mut report)?;
+    Ok(finding)
+}
+
+/// Replace the license section of a report
+pub fn set_licenses(report_id: &str, licenses: Vec<DependencyLicense>) -> Result<Report, String> {
+    let mut report = load(report_id)?;
+    report.licenses = licenses;
+    save(&mut report)?;
+    Ok(report)
+}
+
+pub fn remove_finding(report_id: &str, finding_id: &str) -> Result<(), String> {
+    let mut report = load(report_id)?;
+    let before = report.findings.len();
+    report.findings.retain(|f| f.id != finding_id);
+    if report.findings.len() == before {
+        return Err(format!("Finding {} not found", finding_id));
+    }
+    save(&mut report)
+}
+
+/// Attach captured evidence to a finding and record the link on the evidence side
+pub fn attach_evidence(
+    report_id: &str,
+    finding_id: &str,
+    evidence_id: &str,
+    caption: &str,
+) -> Result<ReportFinding, String> {
+    let item = evidence::get(evidence_id)?;
+    let mut report = load(report_id)?;
+    let finding = report
+        .findings
+        .iter_mut()
+        .find(|f| f.id == finding_id)
+        .ok_or_else(|| format!("Finding {} not found", finding_id))?;
+
+    if !finding.attachments.iter().any(|a| a.evidence_id == evidence_id) {
+        finding.attachments.push(EvidenceAttachment {
+            evidence_id: item.id.clone(),
+            path: item.path.clone(),
+            sha256: item.sha256.clone(),
+            caption: if caption.is_empty() { item.label.clone() } else { caption.to_string() },
+        });
+    }
+    let finding = finding.clone();
+    save(&mut report)?;
+    evidence::link(evidence_id, report_id, finding_id)?;
+    Ok(finding)
+}
+
+pub fn detach_evidence(report_id: &str, finding_id: &str, evidence_id: &str) -> Result<(), String> {
+    let mut report = load(report_id)?;
+    let finding = report
+        .findings
+        .iter_mut()
+        .find(|f| f.id == finding_id)
+        .ok_or_else(|| format!("Finding {} not found", finding_id))?;
+    finding.attachments.retain(|a| a.evidence_id != evidence_id);
+    save(&mut report)?;
+    evidence::unlink(evidence_id, report_id, finding_id)
+}
+
+/// Reorder findings to match `order`; ids not listed keep their relative order at the end
+pub fn reorder_findings(report_id: &str, order: &[String]) -> Result<Report, String> {
+    let mut report = load(report_id)?;
+    let position = |id: &str| order.iter().position(|o| o == id).unwrap_or(usize::MAX);
+    report.findings.sort_by_key(|f| position(&f.id));
+    save(&mut report)?;
+    Ok(report)
+}
@@ -0,0 +1,498 @@
+//! Vulnerable practice app scaffolding
+//!
+//! Writes small, intentionally vulnerable applications into the workspace for
+//! training. Each bug is seeded so the security scanner and the prover pick it
+//! up. An instructor solutions manifest is written separately to
+//! `~/.ctr/solutions/<workspace-id>/<app>.json`, outside the workspace, so
+//! the answers aren't shared along with it.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs_utils::{ctr_dir, save_json};
+use crate::utils::time::now_millis;
+
+struct SeededBug {
+    /// Path inside the generated app
+    file: &'static str,
+    /// Unique snippet on the vulnerable line, used to compute its line number
+    marker: &'static str,
+    vulnerability: &'static str,
+    cwe: &'static str,
+    exploit: &'static str,
+    fix: &'static str,
+}
+
+struct Template {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    language: &'static str,
+    run_command: &'static str,
+    files: &'static [(&'static str, &'static str)],
+    bugs: &'static [SeededBug],
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VulnerableTemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub language: String,
+    pub vulnerabilities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolutionEntry {
+    pub file: String,
+    pub line: usize,
+    pub vulnerability: String,
+    pub cwe: String,
+    pub exploit: String,
+    pub fix: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolutionsManifest {
+    pub app: String,
+    pub template: String,
+    pub created_at: u64,
+    pub solutions: Vec<SolutionEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScaffoldResult {
+    pub app_dir: String,
+    pub files: Vec<String>,
+    pub run_command: String,
+    pub solutions_path: String,
+}
+
+const FLASK_APP: &str = r#"import sqlite3
+
+from flask import Flask, request, jsonify
+
+app = Flask(__name__)
+DB_PATH = "shop.db"
+
+
+def get_db():
+    conn = sqlite3.connect(DB_PATH)
+    conn.row_factory = sqlite3.Row
+    return conn
+
+
+def init_db():
+    conn = get_db()
+    conn.executescript(
+        """
+        DROP TABLE IF EXISTS users;
+        DROP TABLE IF EXISTS products;
+        CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT, password TEXT, is_admin INTEGER);
+        CREATE TABLE products (id INTEGER PRIMARY KEY, name TEXT, price REAL);
+        INSERT INTO users (username, password, is_admin) VALUES ('admin', 'S3cr3t!', 1), ('alice', 'alice123', 0);
+        INSERT INTO products (name, price) VALUES ('Keyboard', 49.99), ('Mouse', 19.99), ('Monitor', 199.0);
+        """
+    )
+    conn.commit()
+    conn.close()
+
+
+@app.route("/products")
+def products():
+    name = request.args.get("name", "")
+    cursor = get_db().cursor()
+    query = "SELECT id, name, price FROM products WHERE name LIKE '%" + name + "%'"
+    cursor.execute(query)
+    return jsonify([dict(row) for row in cursor.fetchall()])
+
+
+@app.route("/login", methods=["POST"])
+def login():
+    username = request.form.get("username", "")
+    password = request.form.get("password", "")
+    cursor = get_db().cursor()
+    cursor.execute(f"SELECT * FROM users WHERE username = '{username}' AND password = '{password}'")
+    user = cursor.fetchone()
+    if user is None:
+        return jsonify({"error": "invalid credentials"}), 401
+    return jsonify({"welcome": user["username"], "admin": bool(user["is_admin"])})
+
+
+if __name__ == "__main__":
+    init_db()
+    app.run(host="127.0.0.1", port=5000, debug=True)
+"#;
+
+const FLASK_REQUIREMENTS: &str = "flask==3.0.3\n";
+
+const FLASK_README: &str = r#"# Vulnerable Shop (Flask)
+
+Intentionally vulnerable training application. Do not deploy it anywhere
+reachable from an untrusted network.
+
+    pip install -r requirements.txt
+    python app.py
+
+Endpoints:
+
+- `GET /products?name=...` – product search
+- `POST /login` – form fields `username` and `password`
+"#;
+
+const NODE_SERVER: &str = r#"const express = require('express');
+const path = require('path');
+
+const app = express();
+const comments = [];
+
+app.use(express.urlencoded({ extended: false }));
+app.use(express.static(path.join(__dirname, 'public')));
+
+app.get('/search', (req, res) => {
+  const q = req.query.q || '';
+  res.send(`<h1>Results for ${q}</h1><p>No products matched.</p>`);
+});
+
+app.post('/comments', (req, res) => {
+  comments.push(req.body.comment || '');
+  res.redirect('/comments');
+});
+
+app.get('/comments', (req, res) => {
+  const items = comments.map((c) => `<li>${c}</li>`).join('');
+  res.send(`<ul>${items}</ul><form method="post"><input name="comment"><button>Post</button></form>`);
+});
+
+app.listen(3000, '127.0.0.1', () => {
+  console.log('Vulnerable board listening on http://127.0.0.1:3000');
+});
+"#;
+
+const NODE_CLIENT: &str = r#"// Greets the visitor using the name from the URL fragment, e.g. /#name=Alice
+window.addEventListener('DOMContentLoaded', () => {
+  const params = new URLSearchParams(window.location.hash.slice(1));
+  const name = params.get('name') || 'guest';
+  document.getElementById('greeting').innerHTML = 'Welcome back, ' + name + '!';
+});
+"#;
+
+const NODE_INDEX: &str = r#"<!doctype html>
+<html>
+  <head><title>Vulnerable Board</title></head>
+  <body>
+    <div id="greeting"></div>
+    <form action="/search"><input name="q"><button>Search</button></form>
+    <a href="/comments">Comments</a>
+    <script src="app.js"></script>
+  </body>
+</html>
+"#;
+
+const NODE_PACKAGE: &str = r#"{
+  "name": "vulnerable-board",
+  "version": "1.0.0",
+  "private": true,
+  "main": "server.js",
+  "scripts": {
+    "start": "node server.js"
+  },
+  "dependencies": {
+    "express": "^4.19.2"
+  }
+}
+"#;
+
+const NODE_README: &str = r#"# Vulnerable Board (Node.js)
+
+Intentionally vulnerable training application. Do not deploy it anywhere
+reachable from an untrusted network.
+
+    npm install
+    npm start
+
+Endpoints:
+
+- `GET /search?q=...` – search page
+- `GET /comments`, `POST /comments` – comment board
+- `/#name=...` – client-side greeting
+"#;
+
+const PICKLE_SERVICE: &str = r#"import base64
+import pickle
+
+from flask import Flask, request, jsonify
+
+app = Flask(__name__)
+
+
+class Session:
+    def __init__(self, user, cart=None):
+        self.user = user
+        self.cart = cart or []
+
+
+@app.route("/session", methods=["POST"])
+def create_session():
+    user = request.form.get("user", "guest")
+    token = base64.b64encode(pickle.dumps(Session(user))).decode()
+    return jsonify({"token": token})
+
+
+@app.route("/cart")
+def cart():
+    token = request.args.get("token", "")
+    session = pickle.loads(base64.b64decode(token))
+    return jsonify({"user": session.user, "cart": session.cart})
+
+
+@app.route("/import", methods=["POST"])
+def import_cart():
+    data = request.get_data()
+    items = pickle.loads(data)
+    return jsonify({"imported": len(items)})
+
+
+if __name__ == "__main__":
+    app.run(host="127.0.0.1", port=5001)
+"#;
+
+const PICKLE_README: &str = r#"# Session Service (Python pickle)
+
+Intentionally vulnerable training application. Do not deploy it anywhere
+reachable from an untrusted network.
+
+    pip install -r requirements.txt
+    python service.py
+
+Endpoints:
+
+- `POST /session` – form field `user`, returns a session token
+- `GET /cart?token=...` – loads the cart for a session token
+- `POST /import` – imports a serialized cart
+"#;
+
+const TEMPLATES: &[Template] = &[
+    Template {
+        id: "flask_sqli",
+        name: "Vulnerable Shop (Flask SQL injection)",
+        description: "Flask + SQLite shop with injectable product search and login",
+        language: "python",
+        run_command: "pip install -r requirements.txt && python app.py",
+        files: &[
+            ("app.py", FLASK_APP),
+            ("requirements.txt", FLASK_REQUIREMENTS),
+            ("README.md", FLASK_README),
+        ],
+        bugs: &[
+            SeededBug {
+                file: "app.py",
+                marker: "cursor.execute(query)",
+                vulnerability: "UNION-based SQL injection in product search",
+                cwe: "CWE-89",
+                exploit: "GET /products?name=' UNION SELECT id, username, password FROM users--",
+                fix: "cursor.execute(\"SELECT id, name, price FROM products WHERE name LIKE ?\", (f\"%{name}%\",))",
+            },
+            SeededBug {
+                file: "app.py",
+                marker: "cursor.execute(f\"SELECT * FROM users",
+                vulnerability: "Authentication bypass via SQL injection in login",
+                cwe: "CWE-89",
+                exploit: "POST /login with username=admin'-- and any password",
+                fix: "Use a parameterized query and compare password hashes instead of plaintext",
+            },
+        ],
+    },
+    Template {
+        id: "node_xss",
+        name: "Vulnerable Board (Node.js XSS)",
+        description: "Express app with reflected, stored and DOM-based XSS",
+        language: "javascript",
+        run_command: "npm install && npm start",
+        files: &[
+            ("server.js", NODE_SERVER),
+            ("public/app.js", NODE_CLIENT),
+            ("public/index.html", NODE_INDEX),
+            ("package.json", NODE_PACKAGE),
+            ("README.md", NODE_README),
+        ],
+        bugs: &[
+            SeededBug {
+                file: "server.js",
+                marker: "res.send(`<h1>Results for ${q}",
+                vulnerability: "Reflected XSS in search results",
+                cwe: "CWE-79",
+                exploit: "GET /search?q=<script>alert(document.domain)</script>",
+                fix: "HTML-escape q before interpolating it, or render through a template engine with autoescaping",
+            },
+            SeededBug {
+                file: "server.js",
+                marker: "`<li>${c}</li>`",
+                vulnerability: "Stored XSS in comment board",
+                cwe: "CWE-79",
+                exploit: "POST /comments with comment=<img src=x onerror=alert(1)>",
+                fix: "Escape comments on output",
+            },
+            SeededBug {
+                file: "public/app.js",
+                marker: ".innerHTML =",
+                vulnerability: "DOM-based XSS from the URL fragment",
+                cwe: "CWE-79",
+                exploit: "/#name=<img src=x onerror=alert(1)>",
+                fix: "Assign to textContent instead of innerHTML",
+            },
+        ],
+    },
+    Template {
+        id: "pickle_service",
+        name: "Session Service (pickle deserialization)",
+        description: "Flask service that trusts pickled session tokens",
+        language: "python",
+        run_command: "pip install -r requirements.txt && python service.py",
+        files: &[
+            ("service.py", PICKLE_SERVICE),
+            ("requirements.txt", FLASK_REQUIREMENTS),
+            ("README.md", PICKLE_README),
+        ],
+        bugs: &[
+            SeededBug {
+                file: "service.py",
+                marker: "pickle.loads(base64.b64decode(token))",
+                vulnerability: "Remote code execution via pickled session token",
+                cwe: "CWE-502",
+                exploit: "Craft an object whose __reduce__ returns (os.system, ('id',)), pickle and base64 it, then GET /cart?token=<value>",
+                fix: "Use signed JSON tokens (e.g. itsdangerous) instead of pickle",
+            },
+            SeededBug {
+                file: "service.py",
+                marker: "pickle.loads(data)",
+                vulnerability: "Remote code execution via cart import",
+                cwe: "CWE-502",
+                exploit: "POST /import with a malicious pickle as the raw request body",
+                fix: "Accept JSON and validate the item list",
+            },
+        ],
+    },
+];
+
+fn find_template(id: &str) -> Result<&'static Template, String> {
+    TEMPLATES
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Unknown template: {}", id))
+}
+
+fn line_of(content: &str, marker: &str) -> usize {
+    content
+        .lines()
+        .position(|line| line.contains(marker))
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+pub fn list_templates() -> Vec<VulnerableTemplateInfo> {
+    TEMPLATES
+        .iter()
+        .map(|t| VulnerableTemplateInfo {
+            id: t.id.to_string(),
+            name: t.name.to_string(),
+            description: t.description.to_string(),
+            language: t.language.to_string(),
+            vulnerabilities: t.bugs.iter().map(|b| b.vulnerability.to_string()).collect(),
+        })
+        .collect()
+}
+
+fn validate_app_name(app_name: &str) -> Result<(), String> {
+    let valid = !app_name.is_empty()
+        && app_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err("App name may only contain letters, digits, '-' and '_'".to_string())
+    }
+}
+
+/// Solutions of each workspace live under a hash of its canonical path
+fn solutions_path(workspace_root: &str, app_name: &str) -> Result<PathBuf, String> {
+    validate_app_name(app_name)?;
+    let root = Path::new(workspace_root)
+        .canonicalize()
+        .map_err(|_| "Workspace path does not exist".to_string())?;
+    let workspace_id = hex::encode(&Sha256::digest(root.to_string_lossy().as_bytes())[..8]);
+    let dir = ctr_dir()?.join("solutions").join(workspace_id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create solutions directory: {}", e))?;
+    Ok(dir.join(format!("{}.json", app_name)))
+}
+
+/// Generate `template` into `<workspace_root>/<app_name>` and write its solutions manifest
+pub fn scaffold(workspace_root: &str, template_id: &str, app_name: &str) -> Result<ScaffoldResult, String> {
+    let template = find_template(template_id)?;
+
+    validate_app_name(app_name)?;
+
+    let app_dir = Path::new(workspace_root).join(app_name);
+    if app_dir.exists() {
+        return Err(format!("{} already exists", app_dir.display()));
+    }
+
+    let mut files = Vec::new();
+    for (rel, content) in template.files {
+        let path = app_dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", rel, e))?;
+        files.push(path.to_string_lossy().to_string());
+    }
+
+    let solutions = template
+        .bugs
+        .iter()
+        .map(|bug| {
+            let content = template
+                .files
+                .iter()
+                .find(|(rel, _)| *rel == bug.file)
+                .map(|(_, content)| *content)
+                .unwrap_or("");
+            SolutionEntry {
+                file: format!("{}/{}", app_name, bug.file),
+                line: line_of(content, bug.marker),
+                vulnerability: bug.vulnerability.to_string(),
+                cwe: bug.cwe.to_string(),
+                exploit: bug.exploit.to_string(),
+                fix: bug.fix.to_string(),
+            }
+        })
+        .collect();
+
+    let manifest = SolutionsManifest {
+        app: app_name.to_string(),
+        template: template.id.to_string(),
+        created_at: now_millis(),
+        solutions,
+    };
+    let manifest_path = solutions_path(workspace_root, app_name)?;
+    save_json(&manifest_path, &manifest)?;
+
+    Ok(ScaffoldResult {
+        app_dir: app_dir.to_string_lossy().to_string(),
+        files,
+        run_command: template.run_command.to_string(),
+        solutions_path: manifest_path.to_string_lossy().to_string(),
+    })
+}
+
+/// Read the instructor solutions for a previously generated app
+pub fn load_solutions(workspace_root: &str, app_name: &str) -> Result<SolutionsManifest, String> {
+    let path = solutions_path(workspace_root, app_name)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read solutions for {}: {}", app_name, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse solutions: {}", e))
+}
@@ -0,0 +1,205 @@
+//! Cron expressions: the five standard fields (minute hour day-of-month
+//! month day-of-week) with lists, ranges, steps and month/day names, plus
+//! the @hourly, @daily, @weekly, @monthly and @yearly shorthands. Times are
+//! local.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead to look for the next matching time; impossible dates like
+/// February 30th never match
+const SEARCH_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether day-of-month and day-of-week were given; when both are, a day
+    /// matching either one matches
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Ok(number) = value.parse::<u32>() {
+        return Ok(number);
+    }
+    names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+        .map(|i| i as u32 + min)
+        .ok_or_else(|| format!("Invalid value: {}", value))
+}
+
+/// Bitset of the values a field allows
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("Invalid step: {}", step))?;
+                if step == 0 {
+                    return Err("Step must be at least 1".into());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, names)?, parse_value(b, min, names)?)
+        } else {
+            let start = parse_value(range, min, names)?;
+            // "5/15" runs from 5 to the end of the range
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("{} is outside {}-{}", range, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ if expression.starts_with('@') => return Err(format!("Unknown schedule: {}", expression)),
+            _ => expression,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err("Expected five fields: minute hour day-of-month month day-of-week".into());
+        };
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAY_NAMES)?;
+        // Both 0 and 7 are Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])?,
+            days: parse_field(day, 1, 31, &[])?,
+            months: parse_field(month, 1, 12, &MONTH_NAMES)?,
+            weekdays,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// The first matching minute after `after`
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..SEARCH_DAYS {
+            if self.months & (1 << date.month()) != 0 && self.day_matches(date) {
+                let first_minute = if date == start.date() { start.hour() * 60 + start.minute() } else { 0 };
+                for minute_of_day in first_minute..24 * 60 {
+                    let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+                    if self.hours & (1 << hour) == 0 || self.minutes & (1 << minute) == 0 {
+                        continue;
+                    }
+                    let naive = NaiveDateTime::new(date, NaiveTime::from_hms_opt(hour, minute, 0)?);
+                    // Times skipped by a daylight saving change do not exist
+                    if let Some(time) = Local.from_local_datetime(&naive).earliest() {
+                        return Some(time);
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The next `count` run times after Saturday 2026-10-17 10:07:30, local time
+    fn runs(expression: &str, count: usize) -> Vec<String> {
+        let cron = CronExpression::parse(expression).unwrap();
+        let mut time = Local.with_ymd_and_hms(2026, 10, 17, 10, 7, 30).unwrap();
+        let mut out = Vec::new();
+        for _ in 0..count {
+            let Some(next) = cron.next_after(time) else {
+                break;
+            };
+            out.push(next.format("%a %m-%d %H:%M").to_string());
+            time = next;
+        }
+        out
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(runs("*/15 * * * *", 3), ["Sat 10-17 10:15", "Sat 10-17 10:30", "Sat 10-17 10:45"]);
+        // A start value with a step runs to the end of the field
+        assert_eq!(runs("5/20 8 * * *", 4), ["Sun 10-18 08:05", "Sun 10-18 08:25", "Sun 10-18 08:45", "Mon 10-19 08:05"]);
+        assert_eq!(CronExpression::parse("10-20/5 * * * *").unwrap().minutes, 1 << 10 | 1 << 15 | 1 << 20);
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("*/x * * * *").is_err());
+    }
+
+    #[test]
+    fn test_ranges_and_lists() {
+        assert_eq!(runs("30 3 * * 1-5", 2), ["Mon 10-19 03:30", "Tue 10-20 03:30"]);
+        assert_eq!(runs("0 9,17 * * *", 3), ["Sat 10-17 17:00", "Sun 10-18 09:00", "Sun 10-18 17:00"]);
+        assert!(CronExpression::parse("5-1 * * * *").is_err());
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("0 0 0 * *").is_err());
+        assert!(CronExpression::parse("* * *").is_err());
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(CronExpression::parse("30 3 * * mon-fri"), CronExpression::parse("30 3 * * 1-5"));
+        assert_eq!(CronExpression::parse("0 0 1 JAN,Jul *"), CronExpression::parse("0 0 1 1,7 *"));
+        assert_eq!(runs("0 0 1 nov *", 1), ["Sun 11-01 00:00"]);
+        // 0 and 7 are both Sunday
+        assert_eq!(CronExpression::parse("0 0 * * 7"), CronExpression::parse("0 0 * * sun"));
+        assert_eq!(CronExpression::parse("@weekly"), CronExpression::parse("0 0 * * 0"));
+        assert!(CronExpression::parse("0 0 * * funday").is_err());
+        assert!(CronExpression::parse("@often").is_err());
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // Both restricted: the 1st of the month or any Sunday
+        assert_eq!(runs("0 0 1 * sun", 4), ["Sun 10-18 00:00", "Sun 10-25 00:00", "Sun 11-01 00:00", "Sun 11-08 00:00"]);
+        assert_eq!(runs("0 0 20 * sun", 3), ["Sun 10-18 00:00", "Tue 10-20 00:00", "Sun 10-25 00:00"]);
+        // Only one restricted: the other field does not widen it
+        assert_eq!(runs("0 0 20 * *", 2), ["Tue 10-20 00:00", "Fri 11-20 00:00"]);
+        assert_eq!(runs("0 0 * * tue", 2), ["Tue 10-20 00:00", "Tue 10-27 00:00"]);
+    }
+
+    #[test]
+    fn test_impossible_dates() {
+        assert!(runs("0 9 30 feb *", 1).is_empty());
+        assert_eq!(runs("0 0 29 feb *", 1), ["Tue 02-29 00:00"]);
+    }
+}
@@ -0,0 +1,366 @@
+//! Scheduled tasks
+//!
+//! Runs a fixed set of maintenance tasks (workspace scan, dependency check,
+//! lab health check, index refresh) on cron expressions while the app is
+//! open. Schedules and their last results are kept in
+//! `~/.ctr/schedules.json`; runs missed while the app was closed are not
+//! made up, the next one simply happens at its next matching time.
+
+pub mod cron;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::services::ai::rag;
+use crate::services::audit;
+use crate::services::findings::{self, Finding, FindingSource};
+use crate::services::intel::cve;
+use crate::services::labs;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::project::{roots, todos};
+use crate::services::security::{self, dependencies, ScanOptions};
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+use crate::utils::time::now_millis;
+use cron::CronExpression;
+
+/// How often the scheduler looks for due schedules
+const TICK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    /// Security scan of every workspace root; results go to the findings store
+    WorkspaceScan,
+    /// Vulnerable dependency lookup; results go to the findings store
+    DependencyCheck,
+    /// Container state of the running labs
+    LabHealthCheck,
+    /// Rebuild the symbol and TODO indexes of a workspace
+    IndexRefresh,
+}
+
+impl TaskKind {
+    fn needs_workspace(self) -> bool {
+        self != TaskKind::LabHealthCheck
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResult {
+    /// Unix timestamps in milliseconds
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub success: bool,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub task: TaskKind,
+    pub cron: String,
+    #[serde(default)]
+    pub workspace_root: Option<String>,
+    pub enabled: bool,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_run: Option<RunResult>,
+    /// Unix timestamp in milliseconds; not set while disabled
+    #[serde(default)]
+    pub next_run: Option<u64>,
+}
+
+/// Fields a caller sets when creating or editing a schedule
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleInput {
+    pub name: String,
+    pub task: TaskKind,
+    pub cron: String,
+    #[serde(default)]
+    pub workspace_root: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RunCompletePayload {
+    schedule_id: String,
+    name: String,
+    result: RunResult,
+}
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles of the schedules file
+    static ref STORE_LOCK: Mutex<()> = Mutex::new(());
+    /// Schedules with a run in progress
+    static ref RUNNING: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("schedules.json"))
+}
+
+fn update_store<T>(change: impl FnOnce(&mut Vec<Schedule>) -> Result<T, String>) -> Result<T, String> {
+    let _guard = STORE_LOCK.lock().unwrap();
+    let path = store_path()?;
+    let mut schedules: Vec<Schedule> = load_json(&path);
+    let value = change(&mut schedules)?;
+    save_json(&path, &schedules)?;
+    Ok(value)
+}
+
+/// The next `count` times an expression matches, as Unix timestamps in
+/// milliseconds
+pub fn upcoming(expression: &str, count: usize) -> Result<Vec<u64>, String> {
+    let cron = CronExpression::parse(expression)?;
+    let mut times = Vec::new();
+    let mut after = Local::now();
+    while times.len() < count {
+        let Some(next) = cron.next_after(after) else { break };
+        times.push(next.timestamp_millis() as u64);
+        after = next;
+    }
+    Ok(times)
+}
+
+fn next_run(schedule: &Schedule) -> Option<u64> {
+    if !schedule.enabled {
+        return None;
+    }
+    let cron = CronExpression::parse(&schedule.cron).ok()?;
+    cron.next_after(Local::now()).map(|next| next.timestamp_millis() as u64)
+}
+
+fn validate(input: &ScheduleInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Schedule name is required".to_string());
+    }
+    if CronExpression::parse(&input.cron)?.next_after(Local::now()).is_none() {
+        return Err(format!("{} never matches a date", input.cron));
+    }
+    if input.task.needs_workspace() {
+        let root = input.workspace_root.as_deref().ok_or("This task needs a workspace")?;
+        if !Path::new(root).is_dir() {
+            return Err(format!("Workspace does not exist: {}", root));
+        }
+    }
+    Ok(())
+}
+
+pub fn list() -> Vec<Schedule> {
+    match store_path() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn create(input: ScheduleInput) -> Result<Schedule, String> {
+    validate(&input)?;
+    let mut schedule = Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: input.name.trim().to_string(),
+        task: input.task,
+        cron: input.cron.trim().to_string(),
+        workspace_root: input.workspace_root.filter(|_| input.task.needs_workspace()),
+        enabled: input.enabled,
+        created_at: now_millis(),
+        last_run: None,
+        next_run: None,
+    };
+    schedule.next_run = next_run(&schedule);
+    update_store(|schedules| {
+        schedules.push(schedule.clone());
+        Ok(schedule)
+    })
+}
+
+/// Replace a schedule's settings; its last result is kept
+pub fn update(id: &str, input: ScheduleInput) -> Result<Schedule, String> {
+    validate(&input)?;
+    update_store(|schedules| {
+        let schedule = schedules.iter_mut().find(|s| s.id == id).ok_or_else(|| format!("Schedule {} not found", id))?;
+        schedule.name = input.name.trim().to_string();
+        schedule.task = input.task;
+        schedule.cron = input.cron.trim().to_string();
+        schedule.workspace_root = input.workspace_root.filter(|_| input.task.needs_workspace());
+        schedule.enabled = input.enabled;
+        schedule.next_run = next_run(schedule);
+        Ok(schedule.clone())
+    })
+}
+
+pub fn set_enabled(id: &str, enabled: bool) -> Result<Schedule, String> {
+    update_store(|schedules| {
+        let schedule = schedules.iter_mut().find(|s| s.id == id).ok_or_else(|| format!("Schedule {} not found", id))?;
+        schedule.enabled = enabled;
+        schedule.next_run = next_run(schedule);
+        Ok(schedule.clone())
+    })
+}
+
+pub fn delete(id: &str) -> Result<(), String> {
+    update_store(|schedules| {
+        let before = schedules.len();
+        schedules.retain(|s| s.id != id);
+        if schedules.len() == before {
+            return Err(format!("Schedule {} not found", id));
+        }
+        Ok(())
+    })
+}
+
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| format!("Scheduled task failed: {}", e))?
+}
+
+/// Run a task once; `Ok` carries a summary, and a lab health check with an
+/// unhealthy lab counts as a failure
+async fn execute(task: TaskKind, workspace_root: Option<String>) -> Result<String, String> {
+    let workspace_root = match (task.needs_workspace(), workspace_root) {
+        (true, Some(root)) if Path::new(&root).is_dir() => root,
+        (true, Some(root)) => return Err(format!("Workspace does not exist: {}", root)),
+        (true, None) => return Err("No workspace set".to_string()),
+        (false, _) => String::new(),
+    };
+    match task {
+        TaskKind::WorkspaceScan => {
+            let report = blocking(move || {
+                let options = ScanOptions::default();
+                let workspace_roots = roots::list(&workspace_root)?;
+                let report = if workspace_roots.len() > 1 {
+                    security::scan_roots(&workspace_roots, &options)?
+                } else {
+                    security::scan_workspace(Path::new(&workspace_root), &options)?
+                };
+                let found = report.issues.iter().map(Finding::from_issue).collect();
+                findings::record_or_warn(&workspace_root, FindingSource::Scanner, None, found);
+                Ok(report)
+            })
+            .await?;
+            Ok(format!("{} issues in {} files", report.issues.len(), report.files_scanned))
+        }
+        TaskKind::DependencyCheck => {
+            let root = PathBuf::from(&workspace_root);
+            let collected = blocking(move || Ok(dependencies::collect_dependencies(&root))).await?;
+            let total = collected.len();
+            let scan = cve::link_dependencies(collected).await?;
+            findings::record_dependency_scan(&workspace_root, &scan);
+            let mut summary = format!("{} of {} dependencies vulnerable", scan.findings.len(), total);
+            if !scan.errors.is_empty() {
+                summary.push_str(&format!(", {} lookups failed", scan.errors.len()));
+            }
+            Ok(summary)
+        }
+        TaskKind::LabHealthCheck => {
+            let health = blocking(|| Ok(labs::health())).await?;
+            let unhealthy: Vec<String> = health
+                .iter()
+                .filter(|lab| !lab.healthy)
+                .map(|lab| format!("{}: {}", lab.name, lab.detail))
+                .collect();
+            if unhealthy.is_empty() {
+                Ok(format!("{} labs healthy", health.len()))
+            } else {
+                Err(format!("Unhealthy labs: {}", unhealthy.join("; ")))
+            }
+        }
+        TaskKind::IndexRefresh => blocking(move || {
+            let symbols = rag::refresh_index(&workspace_root)?;
+            let index = todos::full_index(Path::new(&workspace_root));
+            Ok(format!("{} files indexed, {} TODO files", symbols, index.files.len()))
+        })
+        .await,
+    }
+}
+
+/// Run a schedule now, record its result and emit `schedule-run-complete`
+pub async fn run(app_handle: &AppHandle, id: &str) -> Result<RunResult, String> {
+    let schedule = list()
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Schedule {} not found", id))?;
+    if !RUNNING.lock().unwrap().insert(schedule.id.clone()) {
+        return Err(format!("{} is already running", schedule.name));
+    }
+    audit::record("scheduler", Some(&schedule.id), &format!("{:?} ({})", schedule.task, schedule.name), schedule.workspace_root.as_deref());
+
+    let started_at = now_millis();
+    let outcome = execute(schedule.task, schedule.workspace_root.clone()).await;
+    RUNNING.lock().unwrap().remove(&schedule.id);
+    let result = RunResult {
+        started_at,
+        finished_at: now_millis(),
+        success: outcome.is_ok(),
+        summary: outcome.unwrap_or_else(|e| e),
+    };
+
+    let stored = result.clone();
+    update_store(move |schedules| {
+        // The schedule may have been deleted while it ran
+        if let Some(schedule) = schedules.iter_mut().find(|s| s.id == id) {
+            schedule.last_run = Some(stored);
+            schedule.next_run = next_run(schedule);
+        }
+        Ok(())
+    })?;
+
+    if !result.success {
+        notifications::notify_or_warn(
+            app_handle,
+            NotificationKind::JobFinished,
+            &format!("{} failed", schedule.name),
+            &result.summary,
+        );
+    }
+    let _ = app_handle.emit(
+        "schedule-run-complete",
+        RunCompletePayload { schedule_id: schedule.id, name: schedule.name, result: result.clone() },
+    );
+    Ok(result)
+}
+
+/// Start the scheduler loop for the lifetime of the app
+pub fn start(app_handle: AppHandle) {
+    // Times stored by an earlier session may have passed while it was closed
+    if let Err(e) = update_store(|schedules| {
+        for schedule in schedules.iter_mut() {
+            schedule.next_run = next_run(schedule);
+        }
+        Ok(())
+    }) {
+        log::warn!("Failed to load schedules: {}", e);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let now = Local::now().timestamp_millis() as u64;
+            let due = list()
+                .into_iter()
+                .filter(|s| s.enabled && s.next_run.is_some_and(|next| next <= now))
+                .filter(|s| !RUNNING.lock().unwrap().contains(&s.id));
+            for schedule in due {
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = run(&app_handle, &schedule.id).await {
+                        log::warn!("Scheduled run of {} failed: {}", schedule.name, e);
+                    }
+                });
+            }
+        }
+    });
+}
+
@@ -0,0 +1,149 @@
+//! Secret storage
+//!
+//! API keys, tokens and passphrases live in the OS keychain (macOS Keychain,
+//! Windows Credential Manager, the Secret Service on Linux), never in the
+//! JSON files under ~/.ctr. Each secret belongs to an integration, e.g.
+//! `ai/openai_api_key` or `git/github.com`. The keychain cannot be listed,
+//! so the names of stored secrets, without their values, are kept in
+//! ~/.ctr/secrets.json.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+
+/// Keychain service name all entries are stored under
+const SERVICE: &str = "cyber-threat-range-ide";
+
+/// Secrets the integrations read, as (integration, name, description)
+const KNOWN: &[(&str, &str, &str)] = &[
+    ("ai", "openai_api_key", "OpenAI API key"),
+    ("ai", "anthropic_api_key", "Anthropic API key"),
+    ("huggingface", "token", "Hugging Face token for gated model downloads"),
+    ("intel", "nvd_api_key", "NVD API key"),
+    ("intel", "github_token", "GitHub token for advisory lookups"),
+    ("intel", "virustotal_api_key", "VirusTotal API key"),
+    ("ssh", "passphrase", "Passphrase of the default SSH key"),
+    ("phishing", "smtp_password", "Password of the lab SMTP server"),
+];
+
+lazy_static::lazy_static! {
+    /// Serializes updates of the index file
+    static ref INDEX_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretInfo {
+    pub integration: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub stored: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretIndex {
+    #[serde(default)]
+    secrets: BTreeSet<String>,
+}
+
+fn index_path() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("secrets.json"))
+}
+
+fn account(integration: &str, name: &str) -> Result<String, String> {
+    let valid = |part: &str| !part.is_empty() && !part.contains('/') && part.trim() == part;
+    if !valid(integration) || !valid(name) {
+        return Err(format!("Invalid secret name: {}/{}", integration, name));
+    }
+    Ok(format!("{}/{}", integration, name))
+}
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| format!("Failed to open the keychain: {}", e))
+}
+
+fn update_index(account: &str, stored: bool) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let path = index_path()?;
+    let mut index: SecretIndex = load_json(&path);
+    let changed = if stored { index.secrets.insert(account.to_string()) } else { index.secrets.remove(account) };
+    if changed {
+        save_json(&path, &index)?;
+    }
+    Ok(())
+}
+
+/// The secret, or None when it is not stored
+pub fn get(integration: &str, name: &str) -> Result<Option<String>, String> {
+    let account = account(integration, name)?;
+    match entry(&account)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from the keychain: {}", account, e)),
+    }
+}
+
+/// `get` for integrations where a missing or unreadable secret only means
+/// going without it
+pub fn get_or_none(integration: &str, name: &str) -> Option<String> {
+    get(integration, name)
+        .unwrap_or_else(|e| {
+            log::warn!("{}", e);
+            None
+        })
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Store a secret; an empty value deletes it
+pub fn set(integration: &str, name: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return delete(integration, name).map(|_| ());
+    }
+    let account = account(integration, name)?;
+    entry(&account)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store {} in the keychain: {}", account, e))?;
+    update_index(&account, true)
+}
+
+/// Remove a secret; returns false when it was not stored
+pub fn delete(integration: &str, name: &str) -> Result<bool, String> {
+    let account = account(integration, name)?;
+    let deleted = match entry(&account)?.delete_credential() {
+        Ok(()) => true,
+        Err(keyring::Error::NoEntry) => false,
+        Err(e) => return Err(format!("Failed to delete {} from the keychain: {}", account, e)),
+    };
+    update_index(&account, false)?;
+    Ok(deleted)
+}
+
+/// The secrets integrations read and any others stored, without values
+pub fn list() -> Result<Vec<SecretInfo>, String> {
+    let index: SecretIndex = load_json(&index_path()?);
+    let mut secrets: Vec<SecretInfo> = KNOWN
+        .iter()
+        .map(|(integration, name, description)| SecretInfo {
+            integration: integration.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            stored: index.secrets.contains(&format!("{}/{}", integration, name)),
+        })
+        .collect();
+    for account in &index.secrets {
+        let Some((integration, name)) = account.split_once('/') else {
+            continue;
+        };
+        if !KNOWN.iter().any(|(i, n, _)| *i == integration && *n == name) {
+            secrets.push(SecretInfo {
+                integration: integration.to_string(),
+                name: name.to_string(),
+                description: None,
+                stored: true,
+            });
+        }
+    }
+    Ok(secrets)
+}
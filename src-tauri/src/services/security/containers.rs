@@ -0,0 +1,373 @@
+//! Container image scanning
+//!
+//! Scans an image, or the image built from a Dockerfile, with trivy or grype
+//! when one is installed. Without either, the image's package database
+//! (dpkg or apk) is copied out through Docker and the packages are looked up
+//! in OSV. Every scanner's output is normalized into `ContainerScan`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::Severity;
+use crate::services::intel::http_client;
+use crate::utils::blocking;
+
+const OSV_BATCH_URL: &str = "https://api.osv.dev/v1/querybatch";
+
+/// OSV accepts at most this many queries per batch
+const OSV_BATCH_SIZE: usize = 1000;
+
+/// Package databases copied out of the image, and the files holding them
+const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+const APK_INSTALLED: &str = "/lib/apk/db/installed";
+
+/// Installed package name and version
+type Package = (String, String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerScanner {
+    Trivy,
+    Grype,
+    /// The image's own package database, checked against OSV
+    PackageDb,
+}
+
+impl ContainerScanner {
+    fn program(self) -> Option<&'static str> {
+        match self {
+            Self::Trivy => Some("trivy"),
+            Self::Grype => Some("grype"),
+            Self::PackageDb => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerVulnerability {
+    /// CVE, GHSA or distribution advisory id
+    pub id: String,
+    pub package: String,
+    pub installed_version: String,
+    pub fixed_version: Option<String>,
+    pub severity: Severity,
+    pub title: String,
+    pub cwe: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerScan {
+    pub image: String,
+    /// The Dockerfile the image was built from
+    pub dockerfile: Option<String>,
+    pub scanner: ContainerScanner,
+    /// Distribution and version, e.g. "debian 12"
+    pub os: Option<String>,
+    /// Installed packages; only known to the package database scan
+    pub packages: Option<usize>,
+    pub vulnerabilities: Vec<ContainerVulnerability>,
+}
+
+fn program_available(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// The scanners that can run here, preferred first
+pub fn available_scanners() -> Vec<ContainerScanner> {
+    let mut scanners: Vec<ContainerScanner> = [ContainerScanner::Trivy, ContainerScanner::Grype]
+        .into_iter()
+        .filter(|s| s.program().is_some_and(program_available))
+        .collect();
+    if program_available("docker") {
+        scanners.push(ContainerScanner::PackageDb);
+    }
+    scanners
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Build a Dockerfile with its directory as the context; returns the tag
+pub fn build_image(dockerfile: &Path) -> Result<String, String> {
+    if !dockerfile.is_file() {
+        return Err(format!("{} is not a file", dockerfile.display()));
+    }
+    let context = dockerfile.parent().unwrap_or(Path::new("."));
+    let name: String = context
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.".contains(c) { c } else { '-' })
+        .collect();
+    let tag = format!("ctr-scan-{}:latest", name.trim_matches(|c| c == '-' || c == '.'));
+    run(
+        "docker",
+        &["build", "-t", &tag, "-f", &dockerfile.to_string_lossy(), &context.to_string_lossy()],
+    )?;
+    Ok(tag)
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+/// Scanner severities outside the usual four are reported as low
+fn severity(value: &str) -> Severity {
+    Severity::parse(value).unwrap_or(Severity::Low)
+}
+
+fn parse_trivy(output: &str) -> Result<(Option<String>, Vec<ContainerVulnerability>), String> {
+    let report: Value = serde_json::from_str(output).map_err(|e| format!("Invalid trivy output: {}", e))?;
+    let os = report
+        .pointer("/Metadata/OS")
+        .map(|os| format!("{} {}", str_field(os, "Family"), str_field(os, "Name")).trim().to_string());
+    let vulnerabilities = report["Results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|result| result["Vulnerabilities"].as_array().into_iter().flatten())
+        .map(|v| ContainerVulnerability {
+            id: str_field(v, "VulnerabilityID"),
+            package: str_field(v, "PkgName"),
+            installed_version: str_field(v, "InstalledVersion"),
+            fixed_version: v.get("FixedVersion").and_then(Value::as_str).filter(|f| !f.is_empty()).map(String::from),
+            severity: severity(&str_field(v, "Severity")),
+            title: v.get("Title").or_else(|| v.get("Description")).and_then(Value::as_str).unwrap_or_default().to_string(),
+            cwe: v.pointer("/CweIDs/0").and_then(Value::as_str).map(String::from),
+        })
+        .collect();
+    Ok((os, vulnerabilities))
+}
+
+fn parse_grype(output: &str) -> Result<(Option<String>, Vec<ContainerVulnerability>), String> {
+    let report: Value = serde_json::from_str(output).map_err(|e| format!("Invalid grype output: {}", e))?;
+    let os = report
+        .get("distro")
+        .map(|d| format!("{} {}", str_field(d, "name"), str_field(d, "version")).trim().to_string())
+        .filter(|os| !os.is_empty());
+    let vulnerabilities = report["matches"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|m| {
+            let vulnerability = &m["vulnerability"];
+            let artifact = &m["artifact"];
+            ContainerVulnerability {
+                id: str_field(vulnerability, "id"),
+                package: str_field(artifact, "name"),
+                installed_version: str_field(artifact, "version"),
+                fixed_version: vulnerability
+                    .pointer("/fix/versions")
+                    .and_then(Value::as_array)
+                    .and_then(|versions| versions.first())
+                    .and_then(Value::as_str)
+                    .map(String::from),
+                severity: severity(&str_field(vulnerability, "severity")),
+                title: str_field(vulnerability, "description"),
+                cwe: None,
+            }
+        })
+        .collect();
+    Ok((os, vulnerabilities))
+}
+
+/// Installed packages of the image, and its os-release
+fn read_package_db(image: &str) -> Result<(Option<String>, Vec<Package>), String> {
+    // A created container is never started, so images without a shell work too
+    let container = run("docker", &["create", image])?.trim().to_string();
+    let workdir = std::env::temp_dir().join(format!("ctr-image-{}", uuid::Uuid::new_v4()));
+    let result = (|| {
+        std::fs::create_dir_all(&workdir).map_err(|e| format!("Failed to create {}: {}", workdir.display(), e))?;
+        let copy = |path: &str, name: &str| -> Option<String> {
+            let target = workdir.join(name);
+            run("docker", &["cp", "-L", &format!("{}:{}", container, path), &target.to_string_lossy()]).ok()?;
+            std::fs::read_to_string(target).ok()
+        };
+        let os_release = copy("/etc/os-release", "os-release");
+        let packages = if let Some(status) = copy(DPKG_STATUS, "dpkg-status") {
+            parse_dpkg_status(&status)
+        } else if let Some(installed) = copy(APK_INSTALLED, "apk-installed") {
+            parse_apk_installed(&installed)
+        } else {
+            return Err(format!("{} has no dpkg or apk package database", image));
+        };
+        Ok((os_release, packages))
+    })();
+    let _ = run("docker", &["rm", &container]);
+    let _ = std::fs::remove_dir_all(&workdir);
+    result
+}
+
+/// Stanzas of `Package:`/`Version:` lines, keeping installed packages only
+fn parse_dpkg_status(status: &str) -> Vec<Package> {
+    status
+        .split("\n\n")
+        .filter_map(|stanza| {
+            let field = |name: &str| {
+                stanza
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name).and_then(|rest| rest.strip_prefix(':')))
+                    .map(|value| value.trim().to_string())
+            };
+            let installed = field("Status").map_or(true, |s| s.ends_with("installed") && !s.contains("not-installed"));
+            Some((field("Package")?, field("Version")?)).filter(|_| installed)
+        })
+        .collect()
+}
+
+/// `P:` and `V:` lines of the apk database
+fn parse_apk_installed(installed: &str) -> Vec<Package> {
+    installed
+        .split("\n\n")
+        .filter_map(|entry| {
+            let field = |prefix: &str| entry.lines().find_map(|l| l.strip_prefix(prefix)).map(String::from);
+            Some((field("P:")?, field("V:")?))
+        })
+        .collect()
+}
+
+/// The OSV ecosystem of an os-release file, e.g. "Debian:12" or "Alpine:v3.19"
+fn osv_ecosystem(os_release: &str) -> Option<(String, String)> {
+    let field = |name: &str| {
+        os_release
+            .lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix('='))
+            .map(|v| v.trim().trim_matches('"').to_string())
+    };
+    let id = field("ID")?;
+    let version = field("VERSION_ID").unwrap_or_default();
+    let ecosystem = match id.as_str() {
+        "debian" => format!("Debian:{}", version.split('.').next().unwrap_or(&version)),
+        "ubuntu" => {
+            // Even years' April releases are the LTS ones
+            let even_year = version.split('.').next().and_then(|y| y.parse::<u32>().ok()).is_some_and(|y| y % 2 == 0);
+            if even_year && version.ends_with(".04") {
+                format!("Ubuntu:{}:LTS", version)
+            } else {
+                format!("Ubuntu:{}", version)
+            }
+        }
+        "alpine" => {
+            let minor: Vec<&str> = version.split('.').take(2).collect();
+            format!("Alpine:v{}", minor.join("."))
+        }
+        _ => return None,
+    };
+    Some((ecosystem, format!("{} {}", id, version).trim().to_string()))
+}
+
+/// Look the packages up in OSV; vulnerabilities carry no severity there
+/// until fetched one by one, so they are reported as medium
+async fn osv_vulnerabilities(ecosystem: &str, packages: &[Package]) -> Result<Vec<ContainerVulnerability>, String> {
+    let client = http_client()?;
+    let mut vulnerabilities = Vec::new();
+    for batch in packages.chunks(OSV_BATCH_SIZE) {
+        let queries: Vec<Value> = batch
+            .iter()
+            .map(|(name, version)| json!({ "package": { "name": name, "ecosystem": ecosystem }, "version": version }))
+            .collect();
+        let response = client
+            .post(OSV_BATCH_URL)
+            .json(&json!({ "queries": queries }))
+            .send()
+            .await
+            .map_err(|e| format!("OSV query failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("OSV query failed: {}", response.status()));
+        }
+        let body: Value = response.json().await.map_err(|e| format!("Invalid OSV response: {}", e))?;
+        let results = body["results"].as_array().cloned().unwrap_or_default();
+        for ((name, version), result) in batch.iter().zip(results) {
+            for vuln in result["vulns"].as_array().into_iter().flatten() {
+                let id = str_field(vuln, "id");
+                vulnerabilities.push(ContainerVulnerability {
+                    title: format!("{} in {} {}, see https://osv.dev/vulnerability/{}", id, name, version, id),
+                    id,
+                    package: name.clone(),
+                    installed_version: version.clone(),
+                    fixed_version: None,
+                    severity: Severity::Medium,
+                    cwe: None,
+                });
+            }
+        }
+    }
+    Ok(vulnerabilities)
+}
+
+/// Scan `image`, or build `dockerfile` first and scan the result. Without a
+/// preferred scanner the first available one is used.
+pub async fn scan(image: Option<String>, dockerfile: Option<PathBuf>, preferred: Option<ContainerScanner>) -> Result<ContainerScan, String> {
+    let image = match (image, &dockerfile) {
+        (Some(image), None) => image,
+        (None, Some(dockerfile)) => {
+            let dockerfile = dockerfile.clone();
+            blocking::run(None, move |_| build_image(&dockerfile)).await?
+        }
+        _ => return Err("Give either an image or a Dockerfile".into()),
+    };
+
+    let available = blocking::run(None, |_| Ok(available_scanners())).await?;
+    let scanner = match preferred {
+        Some(scanner) if available.contains(&scanner) => scanner,
+        Some(scanner) => return Err(format!("{:?} is not available; install it or choose another scanner", scanner)),
+        None => *available.first().ok_or("No container scanner is available: install trivy, grype or Docker")?,
+    };
+    log::info!("Scanning container image {} with {:?}", image, scanner);
+
+    let target = image.clone();
+    let (os, packages, vulnerabilities) = match scanner {
+        ContainerScanner::Trivy => {
+            let output = blocking::run(None, move |_| run("trivy", &["image", "--format", "json", "--quiet", "--scanners", "vuln", &target])).await?;
+            let (os, vulnerabilities) = parse_trivy(&output)?;
+            (os, None, vulnerabilities)
+        }
+        ContainerScanner::Grype => {
+            let output = blocking::run(None, move |_| run("grype", &[&target, "-o", "json", "-q"])).await?;
+            let (os, vulnerabilities) = parse_grype(&output)?;
+            (os, None, vulnerabilities)
+        }
+        ContainerScanner::PackageDb => {
+            let (os_release, packages) = blocking::run(None, move |_| read_package_db(&target)).await?;
+            let ecosystem = os_release.as_deref().and_then(osv_ecosystem);
+            let vulnerabilities = match &ecosystem {
+                Some((ecosystem, _)) => osv_vulnerabilities(ecosystem, &packages).await?,
+                None => {
+                    log::warn!("No OSV ecosystem for the distribution of {}; only listing its packages", image);
+                    Vec::new()
+                }
+            };
+            (ecosystem.map(|(_, os)| os), Some(packages.len()), vulnerabilities)
+        }
+    };
+
+    Ok(ContainerScan {
+        image,
+        dockerfile: dockerfile.map(|d| d.to_string_lossy().to_string()),
+        scanner,
+        os,
+        packages,
+        vulnerabilities,
+    })
+}
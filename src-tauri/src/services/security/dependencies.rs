@@ -0,0 +1,141 @@
+//! Dependency inventory from package manifests (npm, PyPI, crates.io).
+//!
+//! Only direct dependencies declared in manifests are collected; version
+//! requirements are reduced to the bare version so they can be matched
+//! against advisory databases.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Ecosystem name as used by GitHub advisories: "npm", "pip" or "rust"
+    pub ecosystem: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub manifest: String,
+}
+
+const SKIP_DIRS: &[&str] = &["node_modules", ".git", "target", "build", "dist", "__pycache__", ".venv", "venv"];
+
+fn find_manifests(dir: &Path, out: &mut Vec<PathBuf>) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if path.is_dir() {
+                if !SKIP_DIRS.contains(&name) {
+                    find_manifests(&path, out);
+                }
+            } else if matches!(name, "package.json" | "requirements.txt" | "Cargo.toml") {
+                out.push(path);
+            }
+        }
+    }
+}
+
+/// Strip range operators (^, ~, >=, ==, ...) leaving a plain version
+fn clean_version(spec: &str) -> Option<String> {
+    let re = Regex::new(r"\d+(\.[0-9A-Za-z\-]+)*").unwrap();
+    re.find(spec).map(|m| m.as_str().to_string())
+}
+
+fn parse_package_json(path: &Path, manifest: &str) -> Vec<Dependency> {
+    let json: serde_json::Value = match fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+    {
+        Some(json) => json,
+        None => return Vec::new(),
+    };
+
+    ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|key| json.get(key).and_then(|v| v.as_object()))
+        .flat_map(|deps| deps.iter())
+        .map(|(name, spec)| Dependency {
+            ecosystem: "npm".to_string(),
+            name: name.clone(),
+            version: spec.as_str().and_then(clean_version),
+            manifest: manifest.to_string(),
+        })
+        .collect()
+}
+
+fn parse_requirements(path: &Path, manifest: &str) -> Vec<Dependency> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let re = Regex::new(r"^([A-Za-z0-9_.\-]+)(\[[^\]]*\])?\s*(.*)$").unwrap();
+
+    content
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('-'))
+        .filter_map(|l| re.captures(l))
+        .map(|caps| Dependency {
+            ecosystem: "pip".to_string(),
+            name: caps[1].to_string(),
+            version: caps.get(3).and_then(|v| clean_version(v.as_str())),
+            manifest: manifest.to_string(),
+        })
+        .collect()
+}
+
+fn parse_cargo_toml(path: &Path, manifest: &str) -> Vec<Dependency> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let entry = Regex::new(r#"^([A-Za-z0-9_\-]+)\s*=\s*(.+)$"#).unwrap();
+    let version = Regex::new(r#"version\s*=\s*"([^"]+)""#).unwrap();
+
+    let mut deps = Vec::new();
+    let mut in_deps = false;
+    for line in content.lines().map(|l| l.trim()) {
+        if line.starts_with('[') {
+            in_deps = line.ends_with("dependencies]");
+            continue;
+        }
+        if !in_deps {
+            continue;
+        }
+        if let Some(caps) = entry.captures(line) {
+            let spec = caps[2].trim();
+            let version = if spec.starts_with('"') {
+                clean_version(spec)
+            } else {
+                version
+                    .captures(spec)
+                    .and_then(|c| clean_version(&c[1]))
+            };
+            deps.push(Dependency {
+                ecosystem: "rust".to_string(),
+                name: caps[1].to_string(),
+                version,
+                manifest: manifest.to_string(),
+            });
+        }
+    }
+    deps
+}
+
+/// Collect declared dependencies from all manifests under `root`
+pub fn collect_dependencies(root: &Path) -> Vec<Dependency> {
+    let mut manifests = Vec::new();
+    find_manifests(root, &mut manifests);
+
+    manifests
+        .iter()
+        .flat_map(|path| {
+            let manifest = path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string();
+            match path.file_name().and_then(|n| n.to_str()) {
+                Some("package.json") => parse_package_json(path, &manifest),
+                Some("requirements.txt") => parse_requirements(path, &manifest),
+                Some("Cargo.toml") => parse_cargo_toml(path, &manifest),
+                _ => Vec::new(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,154 @@
+//! Incremental workspace scanning
+//!
+//! Keeps the findings of every scanned file of a workspace in memory so a
+//! save only rescans the files that changed. The background scan in
+//! `security_cmds` fills it from the file watcher and on-save notifications.
+//! Every update is also recorded in the workspace's findings store.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use super::{
+    excluded_dirs, in_scan_scope, max_file_size_kb, scan_file_with, skip_reason, workspace_files, ScanRules, SecurityIssue,
+    SeverityCounts,
+};
+use crate::services::findings::{self, Finding, FindingSource};
+use crate::services::triage;
+
+lazy_static::lazy_static! {
+    /// Workspace root -> file path -> findings of the file's last scan
+    static ref WORKSPACE_FINDINGS: Mutex<HashMap<String, HashMap<String, Vec<SecurityIssue>>>> = Mutex::new(HashMap::new());
+}
+
+/// Payload of the `findings-updated` event
+#[derive(Debug, Clone, Serialize)]
+pub struct FindingsUpdate {
+    pub workspace_root: String,
+    /// Files rescanned for this update
+    pub changed_files: Vec<String>,
+    /// Files whose findings were dropped because they were deleted or left
+    /// the scan
+    pub removed_files: Vec<String>,
+    /// Every finding of the workspace, most severe first
+    pub issues: Vec<SecurityIssue>,
+    pub by_severity: SeverityCounts,
+    pub duration_ms: u64,
+}
+
+fn workspace_key(root: &Path) -> String {
+    root.to_string_lossy().to_string()
+}
+
+/// Scan `files` of a workspace with its configured rules and triage
+fn scan_files(root: &Path, files: &[PathBuf]) -> Vec<(String, Vec<SecurityIssue>)> {
+    let rules = ScanRules::configured(Some(root));
+    let max_kb = max_file_size_kb(Some(root));
+    files
+        .iter()
+        .map(|file| {
+            let mut issues = match skip_reason(file, max_kb) {
+                Some(_) => Vec::new(),
+                None => scan_file_with(file, &rules),
+            };
+            if let Some(root) = root.to_str() {
+                triage::apply_to_issues(&mut issues, root);
+            }
+            (file.to_string_lossy().to_string(), issues)
+        })
+        .collect()
+}
+
+fn record(root: &Path, scope: Option<&[String]>, scanned: &[(String, Vec<SecurityIssue>)]) {
+    let Some(root) = root.to_str() else {
+        return;
+    };
+    let found = scanned.iter().flat_map(|(_, issues)| issues).map(Finding::from_issue).collect();
+    findings::record_or_warn(root, FindingSource::Scanner, scope, found);
+}
+
+fn update(
+    root: &Path,
+    files: &HashMap<String, Vec<SecurityIssue>>,
+    changed_files: Vec<String>,
+    removed_files: Vec<String>,
+    started: Instant,
+) -> FindingsUpdate {
+    let mut issues: Vec<SecurityIssue> = files.values().flatten().cloned().collect();
+    issues.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.file.cmp(&b.file))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    FindingsUpdate {
+        workspace_root: workspace_key(root),
+        changed_files,
+        removed_files,
+        by_severity: SeverityCounts::of(&issues),
+        issues,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Scan every file of a workspace, replacing what the store held for it
+pub fn full_scan(root: &Path) -> FindingsUpdate {
+    let started = Instant::now();
+    let scanned = scan_files(root, &workspace_files(root));
+    record(root, None, &scanned);
+    let scanned: HashMap<String, Vec<SecurityIssue>> = scanned.into_iter().collect();
+    let changed_files = scanned.keys().cloned().collect();
+
+    let mut store = WORKSPACE_FINDINGS.lock().unwrap();
+    let removed_files = store
+        .get(&workspace_key(root))
+        .map(|old| old.keys().filter(|f| !scanned.contains_key(*f)).cloned().collect())
+        .unwrap_or_default();
+    let result = update(root, &scanned, changed_files, removed_files, started);
+    store.insert(workspace_key(root), scanned);
+    result
+}
+
+/// Rescan the changed files of a workspace and drop the removed ones,
+/// keeping the findings of every other file. Files outside the scan, such
+/// as those in excluded directories, are ignored; None when nothing in
+/// scope changed.
+pub fn rescan(root: &Path, changed: &[PathBuf], removed: &[PathBuf]) -> Option<FindingsUpdate> {
+    let started = Instant::now();
+    let skip_dirs = excluded_dirs(root);
+    let changed: Vec<PathBuf> = changed.iter().filter(|p| in_scan_scope(root, p, &skip_dirs)).cloned().collect();
+    let scanned = scan_files(root, &changed);
+    if !scanned.is_empty() || !removed.is_empty() {
+        let scope: Vec<String> = changed.iter().chain(removed).map(|p| p.to_string_lossy().to_string()).collect();
+        record(root, Some(&scope), &scanned);
+    }
+
+    let mut store = WORKSPACE_FINDINGS.lock().unwrap();
+    let files = store.entry(workspace_key(root)).or_default();
+    let removed_files: Vec<String> = removed
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| files.remove(p).is_some())
+        .collect();
+    if scanned.is_empty() && removed_files.is_empty() {
+        return None;
+    }
+    let changed_files = scanned.iter().map(|(file, _)| file.clone()).collect();
+    files.extend(scanned);
+    Some(update(root, files, changed_files, removed_files, started))
+}
+
+/// The stored findings of a workspace, with no files changed
+pub fn snapshot(root: &Path) -> FindingsUpdate {
+    let store = WORKSPACE_FINDINGS.lock().unwrap();
+    let empty = HashMap::new();
+    let files = store.get(&workspace_key(root)).unwrap_or(&empty);
+    update(root, files, Vec::new(), Vec::new(), Instant::now())
+}
+
+/// Drop the stored findings of a workspace
+pub fn forget(root: &Path) {
+    WORKSPACE_FINDINGS.lock().unwrap().remove(&workspace_key(root));
+}
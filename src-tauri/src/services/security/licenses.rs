@@ -0,0 +1,401 @@
+//! License compliance of dependencies
+//!
+//! Resolves the license of each declared dependency: from the package
+//! installed next to the manifest when there is one (node_modules, the
+//! Cargo registry source cache), otherwise from the npm, PyPI or crates.io
+//! registry. Registry answers are cached in ~/.ctr/intel/licenses.json.
+//! Licenses are reduced to SPDX ids, sorted into categories and judged
+//! against the `licenses.*` settings of the workspace.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::dependencies::{collect_dependencies, Dependency};
+use crate::services::intel::{http_client, intel_dir};
+use crate::services::settings;
+use crate::utils::fs_utils::{load_json, save_json};
+
+const NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+const PYPI_URL: &str = "https://pypi.org/pypi";
+const CRATES_IO_URL: &str = "https://crates.io/api/v1/crates";
+
+/// Common spellings of licenses in package metadata, lowercased, and their SPDX id
+const LICENSE_ALIASES: &[(&str, &str)] = &[
+    ("mit license", "MIT"),
+    ("the mit license", "MIT"),
+    ("expat", "MIT"),
+    ("apache 2.0", "Apache-2.0"),
+    ("apache 2", "Apache-2.0"),
+    ("apache-2", "Apache-2.0"),
+    ("apache license 2.0", "Apache-2.0"),
+    ("apache license, version 2.0", "Apache-2.0"),
+    ("apache software license", "Apache-2.0"),
+    ("bsd", "BSD-3-Clause"),
+    ("bsd license", "BSD-3-Clause"),
+    ("new bsd license", "BSD-3-Clause"),
+    ("simplified bsd", "BSD-2-Clause"),
+    ("isc license", "ISC"),
+    ("isc license (iscl)", "ISC"),
+    ("python software foundation license", "PSF-2.0"),
+    ("mozilla public license 2.0 (mpl 2.0)", "MPL-2.0"),
+    ("gpl", "GPL-3.0"),
+    ("gplv2", "GPL-2.0"),
+    ("gplv3", "GPL-3.0"),
+    ("gnu general public license v2 (gplv2)", "GPL-2.0"),
+    ("gnu general public license v3 (gplv3)", "GPL-3.0"),
+    ("lgpl", "LGPL-3.0"),
+    ("lgplv3", "LGPL-3.0"),
+    ("gnu lesser general public license v3 (lgplv3)", "LGPL-3.0"),
+    ("gnu library or lesser general public license (lgpl)", "LGPL-2.1"),
+    ("agplv3", "AGPL-3.0"),
+    ("gnu affero general public license v3", "AGPL-3.0"),
+    ("public domain", "Unlicense"),
+];
+
+/// SPDX id prefixes of each category, checked in order
+const CATEGORY_PREFIXES: &[(LicenseCategory, &[&str])] = &[
+    (LicenseCategory::NetworkCopyleft, &["AGPL", "SSPL", "OSL", "RPL", "CPAL"]),
+    (LicenseCategory::WeakCopyleft, &["LGPL", "MPL", "EPL", "CDDL", "CPL", "MS-RL"]),
+    (LicenseCategory::StrongCopyleft, &["GPL", "EUPL", "CC-BY-SA", "Sleepycat", "QPL"]),
+    (
+        LicenseCategory::Permissive,
+        &[
+            "MIT", "Apache", "BSD", "0BSD", "ISC", "Zlib", "Unlicense", "CC0", "PSF", "Python", "BSL", "WTFPL", "Unicode",
+            "X11", "Artistic", "PostgreSQL", "NCSA", "MS-PL", "BlueOak", "CC-BY-", "OpenSSL",
+        ],
+    ),
+];
+
+/// Most restrictive last, so categories compare by restriction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseCategory {
+    Permissive,
+    WeakCopyleft,
+    StrongCopyleft,
+    NetworkCopyleft,
+    /// No license found, or one that is not recognized
+    Unknown,
+}
+
+impl LicenseCategory {
+    /// The name used in the `licenses.*` settings
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Permissive => "permissive",
+            Self::WeakCopyleft => "weak_copyleft",
+            Self::StrongCopyleft => "strong_copyleft",
+            Self::NetworkCopyleft => "network_copyleft",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyVerdict {
+    Allowed,
+    Review,
+    Denied,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseSource {
+    /// The package installed next to the manifest
+    Installed,
+    Registry,
+    /// Not found anywhere
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyLicense {
+    pub dependency: Dependency,
+    /// The license as published, e.g. "MIT OR Apache-2.0"
+    pub license: Option<String>,
+    pub category: LicenseCategory,
+    pub verdict: PolicyVerdict,
+    pub source: LicenseSource,
+}
+
+/// The `licenses.*` settings of a workspace
+#[derive(Debug, Clone)]
+pub struct LicensePolicy {
+    pub denied: Vec<String>,
+    pub review: Vec<String>,
+    pub allowed: Vec<String>,
+}
+
+impl LicensePolicy {
+    pub fn for_workspace(workspace_root: &str) -> Self {
+        let list = |key: &str, default: &[&str]| -> Vec<String> {
+            let values: Vec<String> = settings::get_as(key, Some(workspace_root), default.iter().map(|s| s.to_string()).collect());
+            values.into_iter().map(|v| v.to_ascii_lowercase()).collect()
+        };
+        Self {
+            denied: list("licenses.denied", &["network_copyleft", "strong_copyleft"]),
+            review: list("licenses.review", &["weak_copyleft", "unknown"]),
+            allowed: list("licenses.allowed", &[]),
+        }
+    }
+
+    /// Allowed ids win, then the denied and review lists, by SPDX id or category
+    pub fn judge(&self, ids: &[String], category: LicenseCategory) -> PolicyVerdict {
+        let ids: Vec<String> = ids.iter().map(|id| id.to_ascii_lowercase()).collect();
+        let listed = |list: &[String]| list.iter().any(|entry| entry == category.name() || ids.contains(entry));
+        if !ids.is_empty() && ids.iter().all(|id| self.allowed.contains(id)) {
+            PolicyVerdict::Allowed
+        } else if listed(&self.denied) {
+            PolicyVerdict::Denied
+        } else if listed(&self.review) {
+            PolicyVerdict::Review
+        } else {
+            PolicyVerdict::Allowed
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LicenseCache {
+    /// "ecosystem:name@version" to the registry's license; None when the
+    /// registry has none
+    #[serde(default)]
+    licenses: BTreeMap<String, Option<String>>,
+}
+
+fn cache_path() -> Result<PathBuf, String> {
+    Ok(intel_dir()?.join("licenses.json"))
+}
+
+fn cache_key(dependency: &Dependency) -> String {
+    format!(
+        "{}:{}@{}",
+        dependency.ecosystem,
+        dependency.name.to_ascii_lowercase(),
+        dependency.version.as_deref().unwrap_or("latest")
+    )
+}
+
+/// SPDX id for a license name, or the name itself when it is not a known alias
+fn normalize_id(name: &str) -> String {
+    let trimmed = name.trim().trim_matches(|c| c == '(' || c == ')').trim();
+    let lower = trimmed.to_ascii_lowercase();
+    LICENSE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == lower)
+        .map_or_else(|| trimmed.to_string(), |(_, id)| id.to_string())
+}
+
+fn id_category(id: &str) -> LicenseCategory {
+    let (base, exception) = match id.split_once(" WITH ") {
+        Some((base, exception)) => (base.trim(), Some(exception)),
+        None => (id, None),
+    };
+    // npm's marker for a package not licensed for use by others
+    if base.eq_ignore_ascii_case("UNLICENSED") {
+        return LicenseCategory::Unknown;
+    }
+    let category = CATEGORY_PREFIXES
+        .iter()
+        .find(|(_, prefixes)| prefixes.iter().any(|p| base.to_ascii_lowercase().starts_with(&p.to_ascii_lowercase())))
+        .map_or(LicenseCategory::Unknown, |(category, _)| *category);
+    // GPL with the Classpath or a linking exception binds only the library itself
+    if category == LicenseCategory::StrongCopyleft && exception.is_some_and(|e| e.to_ascii_lowercase().contains("exception")) {
+        return LicenseCategory::WeakCopyleft;
+    }
+    category
+}
+
+/// Category and SPDX ids of a license expression. Of alternatives joined by
+/// OR the least restrictive applies, of terms joined by AND the most; the
+/// ids are those of the chosen terms.
+pub fn classify(expression: &str) -> (LicenseCategory, Vec<String>) {
+    // Cargo's older "MIT/Apache-2.0" form means the same as OR
+    let expression = expression.replace('/', " OR ");
+    expression
+        .split(" OR ")
+        .map(|alternative| {
+            let ids: Vec<String> = alternative.split(" AND ").map(normalize_id).filter(|id| !id.is_empty()).collect();
+            let category = ids.iter().map(|id| id_category(id)).max().unwrap_or(LicenseCategory::Unknown);
+            (category, ids)
+        })
+        .min_by_key(|(category, _)| *category)
+        .unwrap_or((LicenseCategory::Unknown, Vec::new()))
+}
+
+/// The license of an npm package.json: `license` as a string or object,
+/// or the older `licenses` array
+fn npm_license(package: &Value) -> Option<String> {
+    match package.get("license") {
+        Some(Value::String(license)) => Some(license.clone()),
+        Some(license) => license.get("type").and_then(Value::as_str).map(String::from),
+        None => {
+            let types: Vec<&str> = package
+                .get("licenses")?
+                .as_array()?
+                .iter()
+                .filter_map(|l| l.get("type").and_then(Value::as_str))
+                .collect();
+            (!types.is_empty()).then(|| types.join(" OR "))
+        }
+    }
+    .filter(|l| !l.trim().is_empty() && !l.starts_with("SEE LICENSE"))
+}
+
+/// The `license` of a Cargo.toml
+fn cargo_license(manifest: &str) -> Option<String> {
+    let manifest: toml::Value = toml::from_str(manifest).ok()?;
+    manifest.get("package")?.get("license")?.as_str().map(String::from)
+}
+
+/// The license from package metadata found on disk
+fn installed_license(root: &Path, dependency: &Dependency) -> Option<String> {
+    let manifest_dir = root.join(&dependency.manifest);
+    let manifest_dir = manifest_dir.parent().unwrap_or(root);
+    match dependency.ecosystem.as_str() {
+        "npm" => {
+            let package = fs::read_to_string(manifest_dir.join("node_modules").join(&dependency.name).join("package.json")).ok()?;
+            npm_license(&serde_json::from_str(&package).ok()?)
+        }
+        "rust" => {
+            // ~/.cargo/registry/src/<index>/<name>-<version>/Cargo.toml
+            let registry = dirs::home_dir()?.join(".cargo").join("registry").join("src");
+            let prefix = format!("{}-{}", dependency.name, dependency.version.as_deref().unwrap_or(""));
+            // Without a version, "name-" must be followed by one rather than by more of a longer name
+            let is_release = |dir: &str| dir.strip_prefix(&prefix).is_some_and(|rest| dependency.version.is_some() || rest.starts_with(|c: char| c.is_ascii_digit()));
+            let mut sources: Vec<PathBuf> = fs::read_dir(registry)
+                .ok()?
+                .flatten()
+                .filter_map(|index| fs::read_dir(index.path()).ok())
+                .flat_map(|crates| crates.flatten().map(|c| c.path()))
+                .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(is_release))
+                .collect();
+            sources.sort();
+            let source = sources.pop()?;
+            cargo_license(&fs::read_to_string(source.join("Cargo.toml")).ok()?)
+        }
+        _ => None,
+    }
+}
+
+/// The license the registry publishes for a package
+async fn registry_license(client: &reqwest::Client, dependency: &Dependency) -> Result<Option<String>, String> {
+    let name = urlencoding::encode(&dependency.name);
+    let url = match (dependency.ecosystem.as_str(), &dependency.version) {
+        ("npm", Some(version)) => format!("{}/{}/{}", NPM_REGISTRY_URL, name, version),
+        ("npm", None) => format!("{}/{}/latest", NPM_REGISTRY_URL, name),
+        ("pip", Some(version)) => format!("{}/{}/{}/json", PYPI_URL, name, version),
+        ("pip", None) => format!("{}/{}/json", PYPI_URL, name),
+        ("rust", _) => format!("{}/{}", CRATES_IO_URL, name),
+        (ecosystem, _) => return Err(format!("No registry for {} packages", ecosystem)),
+    };
+    let response = client.get(&url).send().await.map_err(|e| format!("License lookup failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("License lookup for {} returned {}", dependency.name, response.status()));
+    }
+    let body: Value = response.json().await.map_err(|e| format!("Invalid registry response: {}", e))?;
+
+    Ok(match dependency.ecosystem.as_str() {
+        "npm" => npm_license(&body),
+        "pip" => {
+            let info = &body["info"];
+            let declared = ["license_expression", "license"]
+                .iter()
+                .filter_map(|key| info.get(key).and_then(Value::as_str))
+                .map(str::trim)
+                // Some packages paste the whole license text here
+                .find(|l| !l.is_empty() && !l.contains('\n') && l.len() < 100)
+                .map(String::from);
+            declared.or_else(|| {
+                let classifiers: Vec<&str> = info["classifiers"]
+                    .as_array()?
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(|c| c.strip_prefix("License :: "))
+                    .filter_map(|c| c.rsplit(" :: ").next())
+                    .filter(|c| *c != "OSI Approved")
+                    .collect();
+                (!classifiers.is_empty()).then(|| classifiers.join(" OR "))
+            })
+        }
+        // The newest version comes first; a requirement like "1.0" names no exact release
+        _ => {
+            let versions = body["versions"].as_array();
+            let wanted = dependency.version.as_deref().unwrap_or("");
+            versions
+                .and_then(|v| {
+                    v.iter()
+                        .find(|v| v["num"].as_str().is_some_and(|n| n.starts_with(wanted)))
+                        .or_else(|| v.first())
+                })
+                .and_then(|v| v["license"].as_str())
+                .map(String::from)
+        }
+    })
+}
+
+/// Resolve and judge the license of every dependency
+pub async fn check_licenses(root: &Path, dependencies: Vec<Dependency>, policy: &LicensePolicy) -> Result<Vec<DependencyLicense>, String> {
+    let client = http_client()?;
+    let cache_file = cache_path()?;
+    let mut cache: LicenseCache = load_json(&cache_file);
+    let mut cache_changed = false;
+
+    let mut results = Vec::new();
+    for dependency in dependencies {
+        let (license, source) = match installed_license(root, &dependency) {
+            Some(license) => (Some(license), LicenseSource::Installed),
+            None => {
+                let key = cache_key(&dependency);
+                let license = match cache.licenses.get(&key) {
+                    Some(license) => license.clone(),
+                    None => match registry_license(&client, &dependency).await {
+                        Ok(license) => {
+                            cache.licenses.insert(key, license.clone());
+                            cache_changed = true;
+                            license
+                        }
+                        Err(e) => {
+                            // Unresolved for now; the next check asks again
+                            log::warn!("{}", e);
+                            None
+                        }
+                    },
+                };
+                let source = if license.is_some() { LicenseSource::Registry } else { LicenseSource::None };
+                (license, source)
+            }
+        };
+
+        let (category, ids) = license.as_deref().map(classify).unwrap_or((LicenseCategory::Unknown, Vec::new()));
+        results.push(DependencyLicense {
+            verdict: policy.judge(&ids, category),
+            dependency,
+            license,
+            category,
+            source,
+        });
+    }
+
+    if cache_changed {
+        save_json(&cache_file, &cache)?;
+    }
+    Ok(results)
+}
+
+/// Check the dependencies declared in a workspace against its license policy
+pub async fn check_workspace(workspace_root: &str) -> Result<Vec<DependencyLicense>, String> {
+    let root = Path::new(workspace_root);
+    if !root.exists() {
+        return Err("Workspace path does not exist".into());
+    }
+    let policy = LicensePolicy::for_workspace(workspace_root);
+    check_licenses(root, collect_dependencies(root), &policy).await
+}
@@ -1,9 +1,33 @@
-use regex::Regex;
-use serde::Serialize;
+pub mod containers;
+pub mod dependencies;
+pub mod incremental;
+pub mod licenses;
+pub mod redos;
+pub mod rules;
+pub mod semgrep;
+pub mod structural;
+pub mod workflows;
+pub mod yara;
+
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-#[derive(Debug, Clone, Serialize)]
+use crate::analysis::notebook::{is_notebook, Notebook};
+use crate::services::langdetect::{self, LanguageId};
+use crate::services::project::roots::WorkspaceRoot;
+use crate::services::project::walker;
+use crate::services::{settings, triage};
+use rules::Rule;
+use semgrep::SemgrepRule;
+
+/// Declared from least to most severe, so severities compare by rank
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Severity {
     Low,
     Medium,
@@ -11,15 +35,77 @@ pub enum Severity {
     Critical,
 }
 
+impl Severity {
+    /// Parse a severity name in any case
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "critical" => Some(Severity::Critical),
+            "high" => Some(Severity::High),
+            "medium" | "moderate" => Some(Severity::Medium),
+            "low" => Some(Severity::Low),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SecurityIssue {
     pub file: String,
     pub line: usize,
     pub severity: Severity,
     pub kind: String,
+    /// Id of the rule that reported the finding
+    pub rule: String,
     pub message: String,
     pub cwe: Option<String>,
     pub fix_hint: Option<String>,
+    /// Notebook cell index for .ipynb files; `line` is then within the cell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell: Option<usize>,
+    /// Identity of the finding for false-positive triage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Who marked this finding as a false positive, and why
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triage: Option<String>,
+}
+
+const SCANNED_LANGUAGES: &[LanguageId] = &[
+    LanguageId::Python,
+    LanguageId::Notebook,
+    LanguageId::JavaScript,
+    LanguageId::TypeScript,
+    LanguageId::Rust,
+    LanguageId::C,
+    LanguageId::Cpp,
+    LanguageId::Java,
+    LanguageId::Go,
+    LanguageId::Ruby,
+    LanguageId::Php,
+    LanguageId::Html,
+];
+
+/// Bytes sniffed for NUL bytes to tell binary files from text
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// How many of the slowest files a scan report lists
+const SLOWEST_FILES: usize = 10;
+
+fn max_file_size_kb(workspace_root: Option<&Path>) -> u64 {
+    settings::get_as("scanner.maxFileSizeKb", workspace_root.and_then(|r| r.to_str()), 1024)
+}
+
+/// Why a file should not be scanned: larger than `max_kb` (typically a
+/// minified bundle) or binary content
+fn skip_reason(path: &Path, max_kb: u64) -> Option<String> {
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if max_kb > 0 && size > max_kb * 1024 {
+        return Some(format!("larger than {} KB ({} KB)", max_kb, size / 1024));
+    }
+
+    let mut head = vec![0; BINARY_SNIFF_BYTES];
+    let read = fs::File::open(path).and_then(|mut f| f.read(&mut head)).unwrap_or(0);
+    head[..read].contains(&0).then(|| "binary content".to_string())
 }
 
 fn read_file_lines(path: &Path) -> Vec<String> {
@@ -28,357 +114,407 @@ fn read_file_lines(path: &Path) -> Vec<String> {
         .unwrap_or_default()
 }
 
-struct VulnerabilityPattern {
-    name: &'static str,
-    pattern: &'static str,
-    severity: Severity,
-    message: &'static str,
-    cwe: Option<&'static str>,
-    fix_hint: Option<&'static str>,
-    file_extensions: Option<Vec<&'static str>>,
+/// Compiled sets kept between scans, keyed by their joined patterns
+const MAX_CACHED_SETS: usize = 16;
+
+lazy_static::lazy_static! {
+    static ref REGEX_SETS: Mutex<HashMap<String, Arc<RegexSet>>> = Mutex::new(HashMap::new());
 }
 
-fn get_vulnerability_patterns() -> Vec<VulnerabilityPattern> {
-    vec![
-        // === CRITICAL SEVERITY ===
-        
-        // AWS Keys
-        VulnerabilityPattern {
-            name: "AWS Access Key",
-            pattern: r"AKIA[0-9A-Z]{16}",
-            severity: Severity::Critical,
-            message: "AWS Access Key ID detected. This should never be in source code.",
-            cwe: Some("CWE-798"),
-            fix_hint: Some("Use AWS IAM roles or environment variables instead"),
-            file_extensions: None,
-        },
-        
-        // Private Keys
-        VulnerabilityPattern {
-            name: "Private Key",
-            pattern: r"-----BEGIN (RSA |EC |DSA |OPENSSH )?PRIVATE KEY-----",
-            severity: Severity::Critical,
-            message: "Private key detected in source code. This is a critical security risk.",
-            cwe: Some("CWE-321"),
-            fix_hint: Some("Store private keys in a secure key management system"),
-            file_extensions: None,
-        },
-        
-        // === HIGH SEVERITY ===
-        
-        // Hardcoded Secrets
-        VulnerabilityPattern {
-            name: "Hardcoded Secret",
-            pattern: r#"(?i)(api[_-]?key|secret[_-]?key|auth[_-]?token|access[_-]?token|private[_-]?key|password)\s*[:=]\s*["'][^"']{8,}["']"#,
-            severity: Severity::High,
-            message: "Possible hardcoded credential or API key detected. Store secrets in environment variables or a secure vault.",
-            cwe: Some("CWE-798"),
-            fix_hint: Some("Use environment variables like process.env.API_KEY"),
-            file_extensions: None,
-        },
-        
-        // Dynamic Code Execution
-        VulnerabilityPattern {
-            name: "Dynamic Code Execution",
-            pattern: r"\beval\s*\(",
-            severity: Severity::High,
-            message: "Use of eval() detected. This can lead to code injection vulnerabilities.",
-            cwe: Some("CWE-95"),
-            fix_hint: Some("Avoid eval. Use JSON.parse for data or safer alternatives"),
-            file_extensions: None,
-        },
-        
-        // SQL Injection
-        VulnerabilityPattern {
-            name: "SQL Injection Risk",
-            pattern: r"(?i)(SELECT|INSERT|UPDATE|DELETE|DROP)\s+.*(\+|%s|%d|\$\{)",
-            severity: Severity::High,
-            message: "Possible SQL injection vulnerability. User input may be concatenated into SQL query.",
-            cwe: Some("CWE-89"),
-            fix_hint: Some("Use parameterized queries or prepared statements"),
-            file_extensions: None,
-        },
-        
-        // Command Injection - Python
-        VulnerabilityPattern {
-            name: "Command Injection Risk",
-            pattern: r"(?i)os\.system\s*\([^)]*\+",
-            severity: Severity::High,
-            message: "Possible command injection. User input may be passed to shell commands.",
-            cwe: Some("CWE-78"),
-            fix_hint: Some("Use subprocess with shell=False and pass args as a list"),
-            file_extensions: Some(vec!["py"]),
-        },
-        
-        // Command Injection - subprocess
-        VulnerabilityPattern {
-            name: "Command Injection Risk",
-            pattern: r"(?i)subprocess\.(call|run|Popen)\s*\([^)]*shell\s*=\s*True",
-            severity: Severity::High,
-            message: "subprocess with shell=True is dangerous. Command injection is possible.",
-            cwe: Some("CWE-78"),
-            fix_hint: Some("Use shell=False and pass command as a list of arguments"),
-            file_extensions: Some(vec!["py"]),
-        },
-        
-        // XSS - innerHTML
-        VulnerabilityPattern {
-            name: "Cross-Site Scripting (XSS)",
-            pattern: r"\.innerHTML\s*=",
-            severity: Severity::High,
-            message: "Direct innerHTML assignment can lead to XSS vulnerabilities.",
-            cwe: Some("CWE-79"),
-            fix_hint: Some("Use textContent for plain text, or sanitize HTML with DOMPurify"),
-            file_extensions: Some(vec!["js", "ts", "jsx", "tsx"]),
-        },
-        
-        // XSS - document.write
-        VulnerabilityPattern {
-            name: "Cross-Site Scripting (XSS)",
-            pattern: r"document\.write\s*\(",
-            severity: Severity::High,
-            message: "document.write can lead to XSS vulnerabilities.",
-            cwe: Some("CWE-79"),
-            fix_hint: Some("Use DOM manipulation methods instead"),
-            file_extensions: Some(vec!["js", "ts", "jsx", "tsx", "html"]),
-        },
-        
-        // Insecure Deserialization - Python pickle
-        VulnerabilityPattern {
-            name: "Insecure Deserialization",
-            pattern: r"pickle\.(load|loads)\s*\(",
-            severity: Severity::High,
-            message: "Pickle deserialization can execute arbitrary code. Never unpickle untrusted data.",
-            cwe: Some("CWE-502"),
-            fix_hint: Some("Use JSON or another safe serialization format for untrusted data"),
-            file_extensions: Some(vec!["py"]),
-        },
-        
-        // Disabled TLS verification
-        VulnerabilityPattern {
-            name: "Disabled TLS Verification",
-            pattern: r"(?i)(verify\s*=\s*False|rejectUnauthorized\s*:\s*false)",
-            severity: Severity::High,
-            message: "TLS certificate verification is disabled. This allows man-in-the-middle attacks.",
-            cwe: Some("CWE-295"),
-            fix_hint: Some("Enable TLS verification in production"),
-            file_extensions: None,
-        },
-        
-        // === MEDIUM SEVERITY ===
-        
-        // Shell Command Execution
-        VulnerabilityPattern {
-            name: "Shell Command Execution",
-            pattern: r"\b(system|popen|exec|spawn|execSync|spawnSync)\s*\(",
-            severity: Severity::Medium,
-            message: "Shell/system command execution detected. Ensure inputs are validated.",
-            cwe: Some("CWE-78"),
-            fix_hint: Some("Validate and sanitize all inputs, use allowlists"),
-            file_extensions: None,
-        },
-        
-        // Dangerous React pattern
-        VulnerabilityPattern {
-            name: "Dangerous React Pattern",
-            pattern: r"dangerouslySetInnerHTML",
-            severity: Severity::Medium,
-            message: "dangerouslySetInnerHTML can lead to XSS if not properly sanitized.",
-            cwe: Some("CWE-79"),
-            fix_hint: Some("Sanitize HTML with DOMPurify before using dangerouslySetInnerHTML"),
-            file_extensions: Some(vec!["jsx", "tsx"]),
-        },
-        
-        // Path Traversal
-        VulnerabilityPattern {
-            name: "Path Traversal Risk",
-            pattern: r"(\.\./|\.\.\\)",
-            severity: Severity::Medium,
-            message: "Path traversal pattern detected. Validate file paths carefully.",
-            cwe: Some("CWE-22"),
-            fix_hint: Some("Use path.resolve and verify the path is within allowed directories"),
-            file_extensions: None,
-        },
-        
-        // Insecure YAML Loading
-        VulnerabilityPattern {
-            name: "Insecure YAML Loading",
-            pattern: r"yaml\.load\s*\(",
-            severity: Severity::Medium,
-            message: "yaml.load without Loader is unsafe. Use yaml.safe_load instead.",
-            cwe: Some("CWE-502"),
-            fix_hint: Some("Replace with yaml.safe_load or specify Loader=yaml.SafeLoader"),
-            file_extensions: Some(vec!["py"]),
-        },
-        
-        // Insecure Random - JS
-        VulnerabilityPattern {
-            name: "Insecure Randomness",
-            pattern: r"Math\.random\s*\(\)",
-            severity: Severity::Medium,
-            message: "Math.random is not cryptographically secure. Do not use for security purposes.",
-            cwe: Some("CWE-338"),
-            fix_hint: Some("Use crypto.getRandomValues or crypto.randomBytes for security"),
-            file_extensions: Some(vec!["js", "ts", "jsx", "tsx"]),
-        },
-        
-        // === LOW SEVERITY ===
-        
-        // Insecure Random - Python
-        VulnerabilityPattern {
-            name: "Insecure Randomness",
-            pattern: r"\brandom\.(random|randint|choice|shuffle)\s*\(",
-            severity: Severity::Low,
-            message: "Python random module is not cryptographically secure.",
-            cwe: Some("CWE-338"),
-            fix_hint: Some("Use secrets module for security-sensitive randomness"),
-            file_extensions: Some(vec!["py"]),
-        },
-        
-        // Weak Hash Functions
-        VulnerabilityPattern {
-            name: "Weak Hash Function",
-            pattern: r"(?i)\b(md5|sha1)\s*\(",
-            severity: Severity::Low,
-            message: "Weak hash function detected. MD5 and SHA1 are vulnerable to collision attacks.",
-            cwe: Some("CWE-328"),
-            fix_hint: Some("Use SHA-256 or SHA-3 for hashing, bcrypt or Argon2 for passwords"),
-            file_extensions: None,
-        },
-        
-        // HTTP URLs (not HTTPS)
-        VulnerabilityPattern {
-            name: "Insecure HTTP URL",
-            pattern: r#"["']http://[^"']*[^localhost][^127.0.0.1][^"']*["']"#,
-            severity: Severity::Low,
-            message: "HTTP URL detected. Consider using HTTPS for secure communication.",
-            cwe: Some("CWE-319"),
-            fix_hint: Some("Use HTTPS instead of HTTP"),
-            file_extensions: None,
-        },
-        
-        // Console.log with sensitive data
-        VulnerabilityPattern {
-            name: "Sensitive Data in Logs",
-            pattern: r"(?i)console\.(log|info|debug).*(?:password|secret|token|key)",
-            severity: Severity::Low,
-            message: "Possible sensitive data being logged. Remove before production.",
-            cwe: Some("CWE-532"),
-            fix_hint: Some("Remove sensitive data from log statements"),
-            file_extensions: Some(vec!["js", "ts", "jsx", "tsx"]),
-        },
-        
-        // TODO/FIXME security comments
-        VulnerabilityPattern {
-            name: "Security TODO",
-            pattern: r"(?i)(TODO|FIXME|HACK|XXX).*(?:security|auth|password|secret|vulnerability|unsafe)",
-            severity: Severity::Low,
-            message: "Security-related TODO comment found. Address before deployment.",
-            cwe: None,
-            fix_hint: Some("Address the security concern mentioned in the comment"),
-            file_extensions: None,
-        },
-    ]
+/// The line rules of a scan: rule pack rules and Semgrep rules, with one
+/// RegexSet over their patterns so each line is matched once and only the
+/// rules it hits run their own regexes
+pub struct ScanRules {
+    patterns: Vec<Rule>,
+    semgrep: Vec<SemgrepRule>,
+    /// None when the patterns are too large to combine; every rule then runs
+    set: Option<Arc<RegexSet>>,
+    /// Workspace the rules were configured for; fingerprints use paths relative to it
+    root: Option<PathBuf>,
 }
 
-fn scan_lines(path: &Path, lines: &[String]) -> Vec<SecurityIssue> {
-    let mut issues = Vec::new();
-    let patterns = get_vulnerability_patterns();
-    
-    let file_ext = path.extension()
-        .and_then(|e| e.to_str())
-        .map(|s| s.to_lowercase());
-
-    for pattern_def in &patterns {
-        // Check if this pattern applies to this file type
-        if let Some(ref exts) = pattern_def.file_extensions {
-            if let Some(ref ext) = file_ext {
-                if !exts.contains(&ext.as_str()) {
-                    continue;
+impl ScanRules {
+    pub fn new(patterns: Vec<Rule>, semgrep: Vec<SemgrepRule>) -> Self {
+        let sources: Vec<&str> = patterns
+            .iter()
+            .map(|r| r.regex.as_str())
+            .chain(semgrep.iter().map(|r| r.prefilter().as_str()))
+            .collect();
+        let key = sources.join("\u{0}");
+
+        let cached = REGEX_SETS.lock().unwrap().get(&key).cloned();
+        let set = cached.or_else(|| match RegexSet::new(&sources) {
+            Ok(set) => {
+                let set = Arc::new(set);
+                let mut sets = REGEX_SETS.lock().unwrap();
+                if sets.len() >= MAX_CACHED_SETS {
+                    sets.clear();
                 }
-            } else {
-                continue;
+                sets.insert(key, set.clone());
+                Some(set)
             }
+            Err(e) => {
+                log::warn!("Failed to combine scanner patterns: {}", e);
+                None
+            }
+        });
+        Self { patterns, semgrep, set, root: None }
+    }
+
+    /// Rule pack and Semgrep rules configured for a workspace
+    pub fn configured(workspace_root: Option<&Path>) -> Self {
+        Self {
+            root: workspace_root.map(Path::to_path_buf),
+            ..Self::new(
+                rules::configured_rules(workspace_root),
+                semgrep::configured_rules(workspace_root),
+            )
+        }
+    }
+
+    /// Only the rules with these ids
+    fn only(mut self, ids: &[String]) -> Self {
+        self.patterns.retain(|r| ids.contains(&r.id));
+        self.semgrep.retain(|r| ids.contains(&r.id));
+        Self { root: self.root, ..Self::new(self.patterns, self.semgrep) }
+    }
+
+    /// Indexes of the rules whose pattern may match a line: pack rules
+    /// first, then Semgrep rules
+    fn candidates(&self, line: &str) -> Vec<usize> {
+        match &self.set {
+            Some(set) => set.matches(line).into_iter().collect(),
+            None => (0..self.patterns.len() + self.semgrep.len()).collect(),
         }
-        
-        if let Ok(re) = Regex::new(pattern_def.pattern) {
-            for (idx, line) in lines.iter().enumerate() {
-                let line_no = idx + 1;
-                
-                if re.is_match(line) {
-                    issues.push(SecurityIssue {
-                        file: path.to_string_lossy().to_string(),
-                        line: line_no,
-                        severity: pattern_def.severity.clone(),
-                        kind: pattern_def.name.to_string(),
-                        message: pattern_def.message.to_string(),
-                        cwe: pattern_def.cwe.map(String::from),
-                        fix_hint: pattern_def.fix_hint.map(String::from),
-                    });
+    }
+}
+
+/// Rule pack rules plus imported Semgrep rules, structural checks for the
+/// languages that have them, ReDoS checks of regex literals and the checks
+/// of GitHub Actions workflows
+fn scan_lines(path: &Path, lines: &[String], rules: &ScanRules) -> Vec<SecurityIssue> {
+    let mut issues = Vec::new();
+
+    // Rules are chosen by extension; extensionless scripts use the usual
+    // extension of their detected language, and notebook cells are Python
+    let file_ext = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => Some(ext.to_lowercase()),
+        None => langdetect::detect(path, Some(&lines.join("\n"))).map(|d| d.language.extension().to_string()),
+    }
+    .map(|s| if s == "ipynb" { "py".to_string() } else { s });
+
+    let structural = file_ext
+        .as_deref()
+        .and_then(|ext| structural::scan_source(path, ext, &lines.join("\n")));
+
+    let ext = file_ext.as_deref();
+    for (idx, line) in lines.iter().enumerate() {
+        for i in rules.candidates(line) {
+            match rules.patterns.get(i) {
+                Some(rule) if rule.applies_to(ext) => {
+                    // Matches inside comments are noise, except for rules like
+                    // the security TODO one which look for exactly those
+                    let hit = match &structural {
+                        Some(scan) if !rule.in_comments => {
+                            rule.regex.find_iter(line).any(|m| !scan.in_comment(idx, m.start()))
+                        }
+                        _ => true,
+                    };
+                    if hit {
+                        issues.push(rule.issue(path, idx + 1));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    let rule = &rules.semgrep[i - rules.patterns.len()];
+                    if rule.applies_to(ext) && rule.matches(line) {
+                        issues.push(rule.issue(path, idx + 1));
+                    }
                 }
             }
         }
     }
 
+    if let Some(scan) = structural {
+        issues.extend(scan.issues);
+    }
+    if let Some(ext) = ext {
+        issues.extend(redos::scan_lines(path, ext, lines));
+    }
+    if workflows::is_workflow(path) {
+        issues.extend(workflows::scan_lines(path, lines));
+    }
+    for issue in &mut issues {
+        let code = lines.get(issue.line.saturating_sub(1)).map_or("", |l| l.as_str());
+        issue.fingerprint = Some(triage::fingerprint(&issue.kind, Some(path), rules.root.as_deref(), code));
+    }
+    issues
+}
+
+/// Scan one file with the rule packs and the Semgrep rules configured for
+/// the workspace, applying its false-positive triage
+pub fn scan_file(path: &Path, workspace_root: Option<&Path>) -> Vec<SecurityIssue> {
+    if let Some(reason) = skip_reason(path, max_file_size_kb(workspace_root)) {
+        log::info!("Skipped scanning {}: {}", path.display(), reason);
+        return Vec::new();
+    }
+    let mut issues = scan_file_with(path, &ScanRules::configured(workspace_root));
+    if let Some(root) = workspace_root.and_then(|r| r.to_str()) {
+        triage::apply_to_issues(&mut issues, root);
+    }
     issues
 }
 
-pub fn scan_file(path: &Path) -> Vec<SecurityIssue> {
+fn scan_file_with(path: &Path, rules: &ScanRules) -> Vec<SecurityIssue> {
+    if is_notebook(path) {
+        return scan_notebook(path, rules);
+    }
     let lines = read_file_lines(path);
-    scan_lines(path, &lines)
+    scan_lines(path, &lines, rules)
 }
 
-pub fn scan_workspace(root: &Path) -> Vec<SecurityIssue> {
-    let mut issues = Vec::new();
+/// Scan the code cells of a notebook, reporting cell-relative lines
+fn scan_notebook(path: &Path, rules: &ScanRules) -> Vec<SecurityIssue> {
+    let Ok(notebook) = Notebook::load(path) else {
+        return Vec::new();
+    };
+    let lines: Vec<String> = notebook.source.lines().map(String::from).collect();
+    scan_lines(path, &lines, rules)
+        .into_iter()
+        .filter_map(|mut issue| {
+            let location = notebook.location(issue.line)?;
+            issue.line = location.line;
+            issue.cell = Some(location.cell);
+            Some(issue)
+        })
+        .collect()
+}
 
-    fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
-        // Skip common directories that shouldn't be scanned
-        let skip_dirs = ["node_modules", ".git", "target", "build", "dist", "__pycache__", ".venv", "venv"];
-        
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    let dir_name = path.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("");
-                    
-                    if !skip_dirs.contains(&dir_name) {
-                        collect_files(&path, out);
-                    }
-                } else {
-                    out.push(path);
-                }
+/// Filters for a workspace scan; the defaults report everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// Drop findings below this severity
+    pub min_severity: Option<Severity>,
+    /// Only scan files matching one of these globs, relative to the workspace
+    pub include: Vec<String>,
+    /// Skip files matching any of these globs
+    pub exclude: Vec<String>,
+    /// Only report findings of these rule ids
+    pub rules: Vec<String>,
+    /// Keep at most this many findings, most severe first
+    pub max_findings: Option<usize>,
+}
+
+impl ScanOptions {
+    /// Whether a scan with these options reports every finding of the workspace
+    pub fn is_unfiltered(&self) -> bool {
+        self.min_severity.is_none()
+            && self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.rules.is_empty()
+            && self.max_findings.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SeverityCounts {
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+impl SeverityCounts {
+    pub fn of(issues: &[SecurityIssue]) -> Self {
+        let mut counts = Self::default();
+        for issue in issues {
+            match issue.severity {
+                Severity::Critical => counts.critical += 1,
+                Severity::High => counts.high += 1,
+                Severity::Medium => counts.medium += 1,
+                Severity::Low => counts.low += 1,
             }
         }
+        counts
     }
+}
 
-    let mut files: Vec<PathBuf> = Vec::new();
-    collect_files(root, &mut files);
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: String,
+}
 
-    for file in files.into_iter().filter(|p| {
-        if let Some(ext) = p.extension().and_then(|e| e.to_str()) {
-            matches!(
-                ext.to_ascii_lowercase().as_str(),
-                "ts" | "tsx" | "js" | "jsx" | "py" | "rs" | "c" | "cpp" | "java" | "go" | "rb" | "php" | "html"
-            )
-        } else {
-            false
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTiming {
+    pub file: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanReport {
+    pub issues: Vec<SecurityIssue>,
+    pub files_scanned: usize,
+    /// Files left out by the size and binary guards
+    pub skipped_files: Vec<SkippedFile>,
+    /// The files that took longest to scan, slowest first
+    pub slowest_files: Vec<FileTiming>,
+    pub duration_ms: u64,
+    /// Findings that passed the filters, before `max_findings` applied
+    pub total: usize,
+    pub truncated: bool,
+    pub by_severity: SeverityCounts,
+    /// Finding count per rule id
+    pub by_rule: BTreeMap<String, usize>,
+    /// Finding count per workspace root, for scans of several roots
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_root: BTreeMap<String, usize>,
+}
+
+fn compile_globs(patterns: &[String]) -> Result<Vec<glob::Pattern>, String> {
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| glob::Pattern::new(p.trim()).map_err(|e| format!("Invalid glob {}: {}", p, e)))
+        .collect()
+}
+
+/// Directory names a workspace scan does not descend into
+fn excluded_dirs(root: &Path) -> Vec<String> {
+    settings::get_as(
+        "scanner.excludeDirs",
+        root.to_str(),
+        walker::DEFAULT_SKIP_DIRS.iter().map(|d| d.to_string()).collect(),
+    )
+}
+
+/// Whether the scanner has rules for a file's language, or it is a CI workflow
+fn is_scannable(path: &Path) -> bool {
+    workflows::is_workflow(path) || langdetect::detect_file(path).is_some_and(|d| SCANNED_LANGUAGES.contains(&d.language))
+}
+
+/// Whether a file would be part of a scan of `root`
+fn in_scan_scope(root: &Path, path: &Path, skip_dirs: &[String]) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    is_scannable(path)
+        && !relative
+            .parent()
+            .is_some_and(|dir| dir.iter().any(|c| skip_dirs.iter().any(|d| c == d.as_str())))
+}
+
+/// Every file of a workspace the scanner has rules for, outside the
+/// directories excluded by `scanner.excludeDirs`
+pub fn workspace_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = walker::files(root, &excluded_dirs(root), false);
+    files.retain(|p| is_scannable(p));
+    files
+}
+
+pub fn scan_workspace(root: &Path, options: &ScanOptions) -> Result<ScanReport, String> {
+    let started = Instant::now();
+    let mut issues = Vec::new();
+    let include = compile_globs(&options.include)?;
+    let exclude = compile_globs(&options.exclude)?;
+
+    // Compiled once and shared by every file of the scan
+    let mut rules = ScanRules::configured(Some(root));
+    if !options.rules.is_empty() {
+        rules = rules.only(&options.rules);
+    }
+
+    let max_kb = max_file_size_kb(Some(root));
+    let mut skipped_files = Vec::new();
+    let mut timings = Vec::new();
+    let selected = |path: &Path| {
+        let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        (include.is_empty() || include.iter().any(|g| g.matches(&relative)))
+            && !exclude.iter().any(|g| g.matches(&relative))
+    };
+
+    for file in workspace_files(root).into_iter().filter(|p| selected(p)) {
+        if let Some(reason) = skip_reason(&file, max_kb) {
+            skipped_files.push(SkippedFile { file: file.to_string_lossy().to_string(), reason });
+            continue;
         }
-    }) {
-        issues.extend(scan_file(&file));
+        let file_started = Instant::now();
+        issues.extend(scan_file_with(&file, &rules));
+        timings.push(FileTiming {
+            file: file.to_string_lossy().to_string(),
+            duration_ms: file_started.elapsed().as_millis() as u64,
+        });
+    }
+    let files_scanned = timings.len();
+    timings.sort_by_key(|t| std::cmp::Reverse(t.duration_ms));
+    timings.truncate(SLOWEST_FILES);
+    if let Some(root) = root.to_str() {
+        triage::apply_to_issues(&mut issues, root);
+    }
+    // Structural findings are not in the rule lists filtered above
+    if !options.rules.is_empty() {
+        issues.retain(|i| options.rules.contains(&i.rule));
+    }
+    if let Some(min) = &options.min_severity {
+        issues.retain(|i| &i.severity >= min);
     }
 
     // Sort by severity (Critical > High > Medium > Low)
-    issues.sort_by(|a, b| {
-        let severity_order = |s: &Severity| match s {
-            Severity::Critical => 0,
-            Severity::High => 1,
-            Severity::Medium => 2,
-            Severity::Low => 3,
-        };
-        severity_order(&a.severity).cmp(&severity_order(&b.severity))
-    });
+    issues.sort_by(|a, b| b.severity.cmp(&a.severity));
 
-    issues
+    let by_severity = SeverityCounts::of(&issues);
+    let mut by_rule = BTreeMap::new();
+    for issue in &issues {
+        *by_rule.entry(issue.rule.clone()).or_insert(0) += 1;
+    }
+
+    let total = issues.len();
+    if let Some(max) = options.max_findings {
+        issues.truncate(max);
+    }
+    Ok(ScanReport {
+        truncated: issues.len() < total,
+        issues,
+        files_scanned,
+        skipped_files,
+        slowest_files: timings,
+        duration_ms: started.elapsed().as_millis() as u64,
+        total,
+        by_severity,
+        by_rule,
+        by_root: BTreeMap::new(),
+    })
+}
+
+/// Scan every root of a workspace and merge the reports; `max_findings`
+/// applies to the merged findings
+pub fn scan_roots(roots: &[WorkspaceRoot], options: &ScanOptions) -> Result<ScanReport, String> {
+    let started = Instant::now();
+    let per_root = ScanOptions { max_findings: None, ..options.clone() };
+    let mut merged = ScanReport::default();
+
+    for root in roots {
+        let report = scan_workspace(Path::new(&root.path), &per_root)?;
+        merged.by_root.insert(root.name.clone(), report.issues.len());
+        merged.issues.extend(report.issues);
+        merged.files_scanned += report.files_scanned;
+        merged.skipped_files.extend(report.skipped_files);
+        merged.slowest_files.extend(report.slowest_files);
+    }
+
+    merged.issues.sort_by(|a, b| b.severity.cmp(&a.severity));
+    merged.slowest_files.sort_by_key(|t| std::cmp::Reverse(t.duration_ms));
+    merged.slowest_files.truncate(SLOWEST_FILES);
+    merged.by_severity = SeverityCounts::of(&merged.issues);
+    for issue in &merged.issues {
+        *merged.by_rule.entry(issue.rule.clone()).or_insert(0) += 1;
+    }
+    merged.total = merged.issues.len();
+    if let Some(max) = options.max_findings {
+        merged.issues.truncate(max);
+    }
+    merged.truncated = merged.issues.len() < merged.total;
+    merged.duration_ms = started.elapsed().as_millis() as u64;
+    Ok(merged)
 }
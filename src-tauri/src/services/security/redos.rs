@@ -0,0 +1,713 @@
+//! ReDoS detection
+//!
+//! Finds regular expression literals in scanned code and checks them for
+//! constructs that take a backtracking engine exponential time on a failing
+//! input: a repeated group whose body holds another unbounded repetition of
+//! the same characters, as in `(a+)+` or `(\w+\s?)*`, and a repeated
+//! alternation whose branches match the same text, as in `(a|aa)*` or
+//! `(\w|\d)+`. A finding carries an input that triggers the blow-up. Go and
+//! Rust regexes are not checked; their engines run in linear time.
+
+use regex::Regex;
+use std::path::Path;
+
+use super::{SecurityIssue, Severity};
+
+/// Repetitions of the ambiguous text in the example input
+const PUMP_REPEATS: usize = 30;
+
+/// One bit per ASCII character; bit 0 stands for every non-ASCII character
+type CharSet = u128;
+
+const ALL: CharSet = u128::MAX;
+
+#[derive(Debug)]
+enum Node {
+    Empty,
+    /// One character out of the set
+    Char(CharSet),
+    /// Matches no text: anchors, word boundaries, lookarounds. `end` for
+    /// `$`, `\Z` and `\z`.
+    Assert { end: bool },
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat {
+        body: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        /// Possessive quantifiers and atomic groups never backtrack
+        possessive: bool,
+    },
+}
+
+/// A regex literal found in source code
+struct Literal {
+    pattern: String,
+    /// Whether the call matches the whole input (Java's `matches`, Python's
+    /// `fullmatch`), which makes a trailing loop fail on a bad last character
+    full_match: bool,
+}
+
+struct Blowup {
+    construct: &'static str,
+    example: String,
+}
+
+lazy_static::lazy_static! {
+    static ref PYTHON_CALL: Regex = Regex::new(
+        r#"\bre\.(compile|match|fullmatch|search|sub|subn|findall|finditer|split)\(\s*([rRbBuU]{0,2})("(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')"#
+    ).unwrap();
+    static ref SLASH_LITERAL: Regex = Regex::new(
+        r#"(?:^|[=(,:!&|?{};\[~]|\breturn)\s*/((?:[^/\\\[\n*]|\\.|\[(?:[^\]\\\n]|\\.)*\])(?:[^/\\\[\n]|\\.|\[(?:[^\]\\\n]|\\.)*\])*)/[a-z]*"#
+    ).unwrap();
+    static ref REGEXP_CONSTRUCTOR: Regex = Regex::new(
+        r#"\b(?:new\s+RegExp|RegExp\.new|Regexp\.new)\(\s*("(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')"#
+    ).unwrap();
+    static ref JAVA_CALL: Regex = Regex::new(
+        r#"(Pattern\.compile|Pattern\.matches|\.matches|\.replaceAll|\.replaceFirst|\.split)\(\s*("(?:[^"\\]|\\.)*")"#
+    ).unwrap();
+    static ref PHP_CALL: Regex = Regex::new(
+        r#"\bpreg_(?:match_all|match|replace_callback|replace|split|grep)\(\s*('(?:[^'\\]|\\.)*'|"(?:[^"\\]|\\.)*")"#
+    ).unwrap();
+    static ref CPP_REGEX: Regex = Regex::new(
+        r#"\b(?:std::)?regex\s*(?:\w+\s*)?[({]\s*("(?:[^"\\]|\\.)*")"#
+    ).unwrap();
+}
+
+fn bit(c: char) -> CharSet {
+    if c.is_ascii() { 1 << (c as u32) } else { 1 }
+}
+
+fn range(from: char, to: char) -> CharSet {
+    let mut set = 0;
+    for c in from..=to.min('\u{7f}') {
+        set |= bit(c);
+    }
+    if !to.is_ascii() {
+        set |= 1;
+    }
+    set
+}
+
+fn digits() -> CharSet {
+    range('0', '9')
+}
+
+fn word() -> CharSet {
+    range('a', 'z') | range('A', 'Z') | digits() | bit('_') | 1
+}
+
+fn space() -> CharSet {
+    [' ', '\t', '\n', '\r', '\u{b}', '\u{c}'].into_iter().map(bit).fold(0, |a, b| a | b)
+}
+
+/// Characters tried when building example input, most readable first;
+/// 'é' stands for the non-ASCII characters
+fn candidates() -> impl Iterator<Item = char> {
+    ('a'..='z')
+        .chain('0'..='9')
+        .chain('A'..='Z')
+        .chain((' '..='~').filter(|c| !c.is_ascii_alphanumeric()))
+        .chain(['\t', '\n', '\r', 'é'])
+}
+
+fn representative(set: CharSet) -> Option<char> {
+    candidates().find(|c| set & bit(*c) != 0)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    /// None for patterns this parser does not understand
+    fn parse(pattern: &str) -> Option<Node> {
+        let mut parser = Parser { chars: pattern.chars().collect(), pos: 0 };
+        let node = parser.alternation()?;
+        (parser.pos == parser.chars.len()).then_some(node)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn skip_past(&mut self, end: char) -> Option<()> {
+        while self.next()? != end {}
+        Some(())
+    }
+
+    fn alternation(&mut self) -> Option<Node> {
+        let mut branches = vec![self.sequence()?];
+        while self.eat('|') {
+            branches.push(self.sequence()?);
+        }
+        Some(if branches.len() == 1 { branches.remove(0) } else { Node::Alt(branches) })
+    }
+
+    fn sequence(&mut self) -> Option<Node> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            items.push(self.quantified(atom));
+        }
+        Some(match items.len() {
+            0 => Node::Empty,
+            1 => items.remove(0),
+            _ => Node::Concat(items),
+        })
+    }
+
+    /// `{n}`, `{n,}` or `{n,m}`; None (consuming nothing) for a literal brace
+    fn braces(&mut self) -> Option<(u32, Option<u32>)> {
+        let close = self.chars[self.pos..].iter().position(|c| *c == '}')? + self.pos;
+        let inner: String = self.chars[self.pos + 1..close].iter().collect();
+        let (min, max) = match inner.split_once(',') {
+            Some((min, "")) => (min.trim().parse().ok()?, None),
+            Some((min, max)) => (min.trim().parse().ok()?, Some(max.trim().parse().ok()?)),
+            None => {
+                let n = inner.trim().parse().ok()?;
+                (n, Some(n))
+            }
+        };
+        self.pos = close + 1;
+        Some((min, max))
+    }
+
+    fn quantified(&mut self, mut node: Node) -> Node {
+        loop {
+            let (min, max) = match self.peek() {
+                Some('*') => {
+                    self.pos += 1;
+                    (0, None)
+                }
+                Some('+') => {
+                    self.pos += 1;
+                    (1, None)
+                }
+                Some('?') => {
+                    self.pos += 1;
+                    (0, Some(1))
+                }
+                Some('{') => match self.braces() {
+                    Some(bounds) => bounds,
+                    None => return node,
+                },
+                _ => return node,
+            };
+            let possessive = self.eat('+');
+            if !possessive {
+                // Lazy quantifiers backtrack all the same
+                self.eat('?');
+            }
+            node = Node::Repeat { body: Box::new(node), min, max, possessive };
+        }
+    }
+
+    fn atom(&mut self) -> Option<Node> {
+        Some(match self.next()? {
+            '(' => return self.group(),
+            '[' => Node::Char(self.class()?),
+            '.' => Node::Char(ALL & !bit('\n')),
+            '^' => Node::Assert { end: false },
+            '$' => Node::Assert { end: true },
+            '\\' => self.escape(false)?,
+            c => Node::Char(bit(c)),
+        })
+    }
+
+    fn group(&mut self) -> Option<Node> {
+        let mut zero_width = false;
+        let mut atomic = false;
+        if self.eat('?') {
+            match self.next()? {
+                ':' => {}
+                '=' | '!' => zero_width = true,
+                '>' => atomic = true,
+                '#' => {
+                    self.skip_past(')')?;
+                    return Some(Node::Empty);
+                }
+                '<' if matches!(self.peek(), Some('=') | Some('!')) => {
+                    self.pos += 1;
+                    zero_width = true;
+                }
+                '<' => self.skip_past('>')?,
+                '\'' => self.skip_past('\'')?,
+                'P' => match self.next()? {
+                    '<' => self.skip_past('>')?,
+                    // (?P=name) refers back to a group
+                    _ => {
+                        self.skip_past(')')?;
+                        return Some(Node::Char(ALL));
+                    }
+                },
+                _ => {
+                    // Inline flags, either (?i) or (?i:...)
+                    self.pos -= 1;
+                    while self.peek().is_some_and(|c| c.is_ascii_alphabetic() || c == '-') {
+                        self.pos += 1;
+                    }
+                    if self.eat(')') {
+                        return Some(Node::Empty);
+                    }
+                    if !self.eat(':') {
+                        return None;
+                    }
+                }
+            }
+        }
+        let inner = self.alternation()?;
+        if !self.eat(')') {
+            return None;
+        }
+        Some(if zero_width {
+            Node::Assert { end: false }
+        } else if atomic {
+            Node::Repeat { body: Box::new(inner), min: 1, max: Some(1), possessive: true }
+        } else {
+            inner
+        })
+    }
+
+    fn class(&mut self) -> Option<CharSet> {
+        let negated = self.eat('^');
+        let mut set = 0;
+        let mut first = true;
+        loop {
+            let c = self.next()?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let from = match c {
+                '\\' => match self.escape(true)? {
+                    Node::Char(s) if s.count_ones() == 1 && s != 1 => {
+                        char::from_u32(s.trailing_zeros()).unwrap_or('\u{0}')
+                    }
+                    Node::Char(s) => {
+                        set |= s;
+                        continue;
+                    }
+                    _ => continue,
+                },
+                c => c,
+            };
+            let is_range = self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|c| *c != ']');
+            if is_range {
+                self.pos += 1;
+                let to = match self.next()? {
+                    '\\' => self.next()?,
+                    to => to,
+                };
+                set |= range(from, to);
+            } else {
+                set |= bit(from);
+            }
+        }
+        Some(if negated { ALL & !set } else { set })
+    }
+
+    fn hex(&mut self, len: usize) -> Option<char> {
+        let digits: String = if self.eat('{') {
+            let close = self.chars[self.pos..].iter().position(|c| *c == '}')? + self.pos;
+            let digits = self.chars[self.pos..close].iter().collect();
+            self.pos = close + 1;
+            digits
+        } else {
+            let digits = self.chars.get(self.pos..self.pos + len)?.iter().collect();
+            self.pos += len;
+            digits
+        };
+        char::from_u32(u32::from_str_radix(&digits, 16).ok()?)
+    }
+
+    fn escape(&mut self, in_class: bool) -> Option<Node> {
+        let c = self.next()?;
+        Some(match c {
+            'd' => Node::Char(digits()),
+            'D' => Node::Char(ALL & !digits()),
+            'w' => Node::Char(word()),
+            'W' => Node::Char(ALL & !word()),
+            's' => Node::Char(space()),
+            'S' => Node::Char(ALL & !space()),
+            'b' if in_class => Node::Char(bit('\u{8}')),
+            'b' | 'B' | 'A' | 'G' => Node::Assert { end: false },
+            'Z' | 'z' => Node::Assert { end: true },
+            'n' => Node::Char(bit('\n')),
+            't' => Node::Char(bit('\t')),
+            'r' => Node::Char(bit('\r')),
+            'f' => Node::Char(bit('\u{c}')),
+            'v' => Node::Char(bit('\u{b}')),
+            'x' => Node::Char(bit(self.hex(2)?)),
+            'u' => Node::Char(bit(self.hex(4)?)),
+            'p' | 'P' => {
+                if self.eat('{') {
+                    self.skip_past('}')?;
+                } else {
+                    self.next()?;
+                }
+                Node::Char(ALL)
+            }
+            // A back reference can match anything its group did
+            '1'..='9' if !in_class => Node::Char(ALL),
+            c => Node::Char(bit(c)),
+        })
+    }
+}
+
+fn nullable(node: &Node) -> bool {
+    match node {
+        Node::Empty | Node::Assert { .. } => true,
+        Node::Char(_) => false,
+        Node::Concat(items) => items.iter().all(nullable),
+        Node::Alt(branches) => branches.iter().any(nullable),
+        Node::Repeat { body, min, .. } => *min == 0 || nullable(body),
+    }
+}
+
+/// Every character the node can consume
+fn chars(node: &Node) -> CharSet {
+    match node {
+        Node::Char(set) => *set,
+        Node::Concat(items) | Node::Alt(items) => items.iter().map(chars).fold(0, |a, b| a | b),
+        Node::Repeat { max: Some(0), .. } => 0,
+        Node::Repeat { body, .. } => chars(body),
+        Node::Empty | Node::Assert { .. } => 0,
+    }
+}
+
+/// Characters a match of the node can start with
+fn first(node: &Node) -> CharSet {
+    match node {
+        Node::Char(set) => *set,
+        Node::Concat(items) => first_of(items.iter()),
+        Node::Alt(branches) => branches.iter().map(first).fold(0, |a, b| a | b),
+        Node::Repeat { max: Some(0), .. } => 0,
+        Node::Repeat { body, .. } => first(body),
+        Node::Empty | Node::Assert { .. } => 0,
+    }
+}
+
+fn first_of<'a>(items: impl Iterator<Item = &'a Node>) -> CharSet {
+    let mut set = 0;
+    for item in items {
+        set |= first(item);
+        if !nullable(item) {
+            break;
+        }
+    }
+    set
+}
+
+/// Whether the node can match a non-empty run of `c`
+fn run(node: &Node, c: char) -> bool {
+    match node {
+        Node::Char(set) => set & bit(c) != 0,
+        Node::Empty | Node::Assert { .. } => false,
+        Node::Concat(items) => {
+            items.iter().all(|i| nullable(i) || run(i, c)) && items.iter().any(|i| run(i, c))
+        }
+        Node::Alt(branches) => branches.iter().any(|b| run(b, c)),
+        Node::Repeat { body, max, .. } => *max != Some(0) && run(body, c),
+    }
+}
+
+/// The shortest text the node matches, taking the first branch of
+/// alternations
+fn sample(node: &Node) -> String {
+    match node {
+        Node::Char(set) => representative(*set).map(String::from).unwrap_or_default(),
+        Node::Concat(items) => items.iter().map(sample).collect(),
+        Node::Alt(branches) => branches.first().map(sample).unwrap_or_default(),
+        Node::Repeat { body, min, .. } => sample(body).repeat(*min as usize),
+        Node::Empty | Node::Assert { .. } => String::new(),
+    }
+}
+
+/// Whether the node holds an unbounded repetition of `c` that can backtrack
+fn has_loop(node: &Node, c: char) -> bool {
+    match node {
+        Node::Repeat { possessive: true, .. } => false,
+        Node::Repeat { body, max: None, .. } if run(body, c) => true,
+        Node::Repeat { body, .. } => has_loop(body, c),
+        Node::Concat(items) | Node::Alt(items) => items.iter().any(|i| has_loop(i, c)),
+        Node::Empty | Node::Char(_) | Node::Assert { .. } => false,
+    }
+}
+
+/// Whether the node holds an alternation with two branches matching runs of `c`
+fn has_ambiguous_alternation(node: &Node, c: char) -> bool {
+    match node {
+        Node::Alt(branches) => {
+            branches.iter().filter(|b| run(b, c)).count() >= 2
+                || branches.iter().any(|b| has_ambiguous_alternation(b, c))
+        }
+        Node::Concat(items) => items.iter().any(|i| has_ambiguous_alternation(i, c)),
+        Node::Repeat { possessive: true, .. } => false,
+        Node::Repeat { body, .. } => has_ambiguous_alternation(body, c),
+        Node::Empty | Node::Char(_) | Node::Assert { .. } => false,
+    }
+}
+
+/// Check an unbounded loop reached after matching `prefix` and followed by `tail`
+fn check_loop(body: &Node, prefix: &str, tail: &[&Node]) -> Option<Blowup> {
+    // With nothing left to fail, the engine accepts the first way it finds
+    let required = tail.iter().any(|n| !nullable(n) || matches!(n, Node::Assert { end: true }));
+    if !required {
+        return None;
+    }
+    // The input ends in a character neither the loop nor what follows it
+    // accepts, or simply ends when every character is accepted
+    let blocked = chars(body) | first_of(tail.iter().copied());
+    let suffix = std::iter::once('!')
+        .chain(candidates())
+        .find(|c| blocked & bit(*c) == 0)
+        .map(String::from)
+        .or_else(|| tail.iter().any(|n| !nullable(n)).then(String::new))?;
+    candidates().filter(|c| run(body, *c)).find_map(|c| {
+        let construct = if has_loop(body, c) {
+            "nested quantifiers"
+        } else if has_ambiguous_alternation(body, c) {
+            "overlapping alternation"
+        } else {
+            return None;
+        };
+        let example = format!("{}{}{}", prefix, c.to_string().repeat(PUMP_REPEATS), suffix);
+        Some(Blowup { construct, example })
+    })
+}
+
+fn find(node: &Node, prefix: &str, tail: &[&Node]) -> Option<Blowup> {
+    match node {
+        Node::Concat(items) => {
+            let mut prefix = prefix.to_string();
+            for (i, item) in items.iter().enumerate() {
+                let item_tail: Vec<&Node> = items[i + 1..].iter().chain(tail.iter().copied()).collect();
+                if let Some(blowup) = find(item, &prefix, &item_tail) {
+                    return Some(blowup);
+                }
+                prefix.push_str(&sample(item));
+            }
+            None
+        }
+        Node::Alt(branches) => branches.iter().find_map(|b| find(b, prefix, tail)),
+        Node::Repeat { possessive: true, .. } => None,
+        Node::Repeat { body, max, .. } => {
+            let outer = if max.is_none() { check_loop(body, prefix, tail) } else { None };
+            outer.or_else(|| find(body, prefix, tail))
+        }
+        Node::Empty | Node::Char(_) | Node::Assert { .. } => None,
+    }
+}
+
+fn analyze(literal: &Literal) -> Option<Blowup> {
+    let ast = Parser::parse(&literal.pattern)?;
+    let end = Node::Assert { end: true };
+    let tail: Vec<&Node> = if literal.full_match { vec![&end] } else { Vec::new() };
+    find(&ast, "", &tail)
+}
+
+/// The contents of a quoted string literal with its escapes resolved;
+/// unknown escapes such as `\d` are kept for the regex
+fn unquote(quoted: &str, raw: bool) -> String {
+    let inner = &quoted[1..quoted.len() - 1];
+    if raw {
+        return inner.to_string();
+    }
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some(q @ ('"' | '\'' | '`')) => out.push(q),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// The pattern between PHP's delimiters, e.g. `/.../i` or `#...#`
+fn strip_delimiters(pattern: &str) -> Option<String> {
+    let open = pattern.chars().next()?;
+    let close = match open {
+        '(' => ')',
+        '{' => '}',
+        '[' => ']',
+        '<' => '>',
+        c if c.is_alphanumeric() || c == '\\' || c.is_whitespace() => return None,
+        c => c,
+    };
+    let end = pattern.rfind(close).filter(|end| *end > 0)?;
+    Some(pattern[open.len_utf8()..end].to_string())
+}
+
+fn literals(line: &str, file_ext: &str) -> Vec<Literal> {
+    let literal = |pattern: String, full_match: bool| Literal { pattern, full_match };
+    match file_ext {
+        "py" => PYTHON_CALL
+            .captures_iter(line)
+            .map(|c| {
+                let raw = c[2].to_ascii_lowercase().contains('r');
+                literal(unquote(&c[3], raw), &c[1] == "fullmatch")
+            })
+            .collect(),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "rb" => SLASH_LITERAL
+            .captures_iter(line)
+            .map(|c| literal(c[1].to_string(), false))
+            .chain(REGEXP_CONSTRUCTOR.captures_iter(line).map(|c| literal(unquote(&c[1], false), false)))
+            .collect(),
+        "java" => JAVA_CALL
+            .captures_iter(line)
+            .map(|c| literal(unquote(&c[2], false), c[1].ends_with("matches")))
+            .collect(),
+        "php" => PHP_CALL
+            .captures_iter(line)
+            .filter_map(|c| strip_delimiters(&unquote(&c[1], false)))
+            .map(|pattern| literal(pattern, false))
+            .collect(),
+        "c" | "cc" | "cpp" | "cxx" | "h" | "hpp" => CPP_REGEX
+            .captures_iter(line)
+            .map(|c| literal(unquote(&c[1], false), false))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_comment(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    ["#", "//", "*", "/*"].iter().any(|marker| trimmed.starts_with(marker))
+}
+
+/// Regex literals in the file that can backtrack catastrophically
+pub fn scan_lines(path: &Path, file_ext: &str, lines: &[String]) -> Vec<SecurityIssue> {
+    let mut issues = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if is_comment(line) {
+            continue;
+        }
+        for literal in literals(line, file_ext) {
+            let Some(blowup) = analyze(&literal) else {
+                continue;
+            };
+            issues.push(SecurityIssue {
+                file: path.to_string_lossy().to_string(),
+                line: idx + 1,
+                severity: Severity::Medium,
+                kind: "ReDoS".to_string(),
+                rule: "redos-catastrophic-backtracking".to_string(),
+                message: format!(
+                    "Regex {:?} can backtrack catastrophically ({}); matching {:?} takes exponential time",
+                    literal.pattern, blowup.construct, blowup.example
+                ),
+                cwe: Some("CWE-1333".to_string()),
+                fix_hint: Some(
+                    "Remove the nested or overlapping repetition, make it possessive or atomic where the engine \
+                     supports it, or match with a linear-time engine such as RE2"
+                        .to_string(),
+                ),
+                cell: None,
+                fingerprint: None,
+                triage: None,
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blowup(pattern: &str, full_match: bool) -> Option<Blowup> {
+        analyze(&Literal { pattern: pattern.to_string(), full_match })
+    }
+
+    fn scan(file_ext: &str, source: &str) -> Vec<usize> {
+        let lines: Vec<String> = source.lines().map(String::from).collect();
+        scan_lines(Path::new("test"), file_ext, &lines).into_iter().map(|issue| issue.line).collect()
+    }
+
+    #[test]
+    fn test_nested_quantifiers() {
+        let found = blowup("^(a+)+$", false).unwrap();
+        assert_eq!(found.construct, "nested quantifiers");
+        assert_eq!(found.example, format!("{}!", "a".repeat(PUMP_REPEATS)));
+
+        assert_eq!(blowup(r"^(\w+\s?)*$", false).unwrap().construct, "nested quantifiers");
+        assert_eq!(blowup(r"^([a-z0-9]+)*@", false).unwrap().construct, "nested quantifiers");
+        // Java's matches() anchors the pattern at both ends
+        assert!(blowup("(a+)+", true).is_some());
+    }
+
+    #[test]
+    fn test_overlapping_alternation() {
+        assert_eq!(blowup("^(a|aa)*$", false).unwrap().construct, "overlapping alternation");
+        assert_eq!(blowup(r"^(\w|\d)+$", false).unwrap().construct, "overlapping alternation");
+        assert!(blowup("^(a|b)*$", false).is_none());
+    }
+
+    #[test]
+    fn test_safe_patterns() {
+        // Nothing after the loop can fail, so the first match is accepted
+        assert!(blowup("(a+)+", false).is_none());
+        assert!(blowup(r"^[a-z]+$", false).is_none());
+        assert!(blowup(r"^\d+-\d+$", false).is_none());
+        assert!(blowup(r"^(\d{1,3}\.){3}\d{1,3}$", false).is_none());
+        // Possessive quantifiers and atomic groups do not backtrack
+        assert!(blowup("^(a++)+$", false).is_none());
+        assert!(blowup("^(?>a+)+$", false).is_none());
+        // Unparseable patterns are left alone
+        assert!(blowup("(a+", false).is_none());
+    }
+
+    #[test]
+    fn test_literals_by_language() {
+        assert_eq!(scan("js", "const re = /^(a+)+$/;\nconst ok = /^[a-z]+$/i;\n"), vec![1]);
+        assert_eq!(scan("ts", r#"const re = new RegExp("^(\\w+\\s?)*$");"#), vec![1]);
+        assert_eq!(scan("py", "re.compile(r'^(a|aa)*$')\nre.match('^[a-z]+$', s)\n"), vec![1]);
+        assert_eq!(scan("java", r#"if (s.matches("(a+)+")) {}"#), vec![1]);
+        assert_eq!(scan("php", "preg_match('/^(a+)+$/i', $s);\npreg_match('#^\\d+$#', $s);\n"), vec![1]);
+        assert_eq!(scan("cpp", r#"std::regex re("^(a+)+$");"#), vec![1]);
+        // Go and Rust engines run in linear time
+        assert!(scan("go", r#"regexp.MustCompile("^(a+)+$")"#).is_empty());
+        assert!(scan("js", "// const re = /^(a+)+$/;\nconst half = total / 2 + x / 3;\n").is_empty());
+    }
+
+    #[test]
+    fn test_unquote_and_delimiters() {
+        assert_eq!(unquote(r#""a\\d\"b""#, false), r#"a\d"b"#);
+        assert_eq!(unquote(r#""\d+""#, false), r"\d+");
+        assert_eq!(unquote(r"'\\d'", true), r"\\d");
+        assert_eq!(strip_delimiters("/ab+/i").as_deref(), Some("ab+"));
+        assert_eq!(strip_delimiters("{a|b}").as_deref(), Some("a|b"));
+        assert_eq!(strip_delimiters("abc"), None);
+    }
+}
@@ -0,0 +1,308 @@
+//! Scanner Rule Packs
+//!
+//! Line rules for the regex scanner. The built-in pack is compiled into the
+//! binary; further packs, TOML or YAML files or directories of them, are
+//! listed in the `scanner.rulePacks` setting and replace built-in rules with
+//! the same id. Packs are compiled once and cached by path. A pack whose file
+//! changed is recompiled on next use, so new detections apply without a
+//! rebuild. `scanner.disabledRules` and `scanner.ruleSeverities` switch
+//! rules off or change their severity.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use super::{SecurityIssue, Severity};
+use crate::services::settings;
+
+const BUILTIN_RULES: &str = include_str!("builtin_rules.toml");
+
+/// A compiled line rule
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub id: String,
+    /// Finding kind shown to the user
+    pub name: String,
+    pub severity: Severity,
+    pub message: String,
+    pub cwe: Option<String>,
+    pub fix_hint: Option<String>,
+    /// File extensions the rule applies to; None for any file
+    pub extensions: Option<Vec<String>>,
+    /// Whether matches inside comments count
+    pub in_comments: bool,
+    pub enabled: bool,
+    pub regex: Regex,
+    /// "builtin" or the path of the pack file
+    pub pack: String,
+}
+
+impl Rule {
+    pub fn applies_to(&self, file_ext: Option<&str>) -> bool {
+        match (&self.extensions, file_ext) {
+            (None, _) => true,
+            (Some(exts), Some(ext)) => exts.iter().any(|e| e == ext),
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn issue(&self, path: &Path, line: usize) -> SecurityIssue {
+        SecurityIssue {
+            file: path.to_string_lossy().to_string(),
+            line,
+            severity: self.severity.clone(),
+            kind: self.name.clone(),
+            rule: self.id.clone(),
+            message: self.message.clone(),
+            cwe: self.cwe.clone(),
+            fix_hint: self.fix_hint.clone(),
+            cell: None,
+            fingerprint: None,
+            triage: None,
+        }
+    }
+
+    pub fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            severity: self.severity.clone(),
+            cwe: self.cwe.clone(),
+            extensions: self.extensions.clone(),
+            enabled: self.enabled,
+            pack: self.pack.clone(),
+        }
+    }
+}
+
+/// A rule as listed to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleInfo {
+    pub id: String,
+    pub name: String,
+    pub severity: Severity,
+    pub cwe: Option<String>,
+    pub extensions: Option<Vec<String>>,
+    pub enabled: bool,
+    pub pack: String,
+}
+
+/// Every rule configured for a workspace, after overrides
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleCatalog {
+    pub rules: Vec<RuleInfo>,
+    /// "<pack>: <reason>" for each pack or rule that could not be loaded
+    pub skipped: Vec<String>,
+}
+
+/// A rule as written in a pack file
+#[derive(Deserialize)]
+struct RuleDef {
+    id: String,
+    name: Option<String>,
+    pattern: String,
+    severity: String,
+    message: String,
+    cwe: Option<String>,
+    fix_hint: Option<String>,
+    extensions: Option<Vec<String>>,
+    #[serde(default)]
+    in_comments: bool,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct PackFile {
+    #[serde(default)]
+    rules: Vec<RuleDef>,
+}
+
+/// The rules of one pack file, with those that failed to compile
+#[derive(Debug, Default)]
+pub struct RulePack {
+    /// "builtin" or the path of the pack file
+    pub origin: String,
+    pub rules: Vec<Rule>,
+    pub skipped: Vec<String>,
+}
+
+struct CachedPack {
+    modified: SystemTime,
+    pack: Arc<RulePack>,
+}
+
+lazy_static::lazy_static! {
+    static ref BUILTIN: Arc<RulePack> = Arc::new(
+        parse_pack(BUILTIN_RULES, "toml", "builtin").expect("Built-in rule pack is invalid")
+    );
+    static ref PACKS: Mutex<HashMap<PathBuf, CachedPack>> = Mutex::new(HashMap::new());
+}
+
+/// Compile the rules of a pack in TOML or YAML (`format` is the file
+/// extension). Rules with an invalid pattern or severity are skipped.
+pub fn parse_pack(content: &str, format: &str, origin: &str) -> Result<RulePack, String> {
+    let file: PackFile = match format {
+        "toml" => toml::from_str(content).map_err(|e| e.to_string())?,
+        "yml" | "yaml" => serde_yaml::from_str(content).map_err(|e| e.to_string())?,
+        other => return Err(format!("unsupported rule pack format: {}", other)),
+    };
+
+    let mut pack = RulePack {
+        origin: origin.to_string(),
+        ..Default::default()
+    };
+    for def in file.rules {
+        let Some(severity) = Severity::parse(&def.severity) else {
+            pack.skipped.push(format!("{}: unknown severity {}", def.id, def.severity));
+            continue;
+        };
+        let regex = match Regex::new(&def.pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                pack.skipped.push(format!("{}: invalid pattern: {}", def.id, e));
+                continue;
+            }
+        };
+        pack.rules.push(Rule {
+            name: def.name.unwrap_or_else(|| def.id.clone()),
+            id: def.id,
+            severity,
+            message: def.message,
+            cwe: def.cwe,
+            fix_hint: def.fix_hint,
+            extensions: def
+                .extensions
+                .map(|exts| exts.iter().map(|e| e.trim_start_matches('.').to_ascii_lowercase()).collect()),
+            in_comments: def.in_comments,
+            enabled: def.enabled,
+            regex,
+            pack: origin.to_string(),
+        });
+    }
+    Ok(pack)
+}
+
+fn is_pack_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| matches!(ext, "toml" | "yml" | "yaml"))
+}
+
+/// A pack file, or every pack file under a directory, in name order
+fn pack_files(path: &Path, out: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        out.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    children.sort();
+    for child in children {
+        if child.is_dir() || is_pack_file(&child) {
+            pack_files(&child, out);
+        }
+    }
+}
+
+/// A pack from the cache, recompiled if its file changed since it was loaded
+fn load_pack(path: &Path) -> Result<Arc<RulePack>, String> {
+    let modified = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    if let Some(cached) = PACKS.lock().unwrap().get(path) {
+        if cached.modified == modified {
+            return Ok(cached.pack.clone());
+        }
+    }
+
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let format = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let origin = path.to_string_lossy();
+    let pack = Arc::new(parse_pack(&content, format, &origin).map_err(|e| format!("{}: {}", origin, e))?);
+    PACKS.lock().unwrap().insert(
+        path.to_path_buf(),
+        CachedPack { modified, pack: pack.clone() },
+    );
+    Ok(pack)
+}
+
+/// Built-in rules, then the packs in `scanner.rulePacks` in order, with the
+/// workspace's overrides applied. Disabled rules are included.
+fn all_rules(workspace_root: Option<&Path>) -> (Vec<Rule>, Vec<String>) {
+    let root = workspace_root.and_then(|r| r.to_str());
+    let entries: Vec<String> = settings::get_as("scanner.rulePacks", root, Vec::new());
+
+    let mut packs = vec![BUILTIN.clone()];
+    let mut skipped = Vec::new();
+    for entry in entries {
+        let path = match workspace_root {
+            Some(root) if Path::new(&entry).is_relative() => root.join(&entry),
+            _ => PathBuf::from(&entry),
+        };
+        let mut files = Vec::new();
+        pack_files(&path, &mut files);
+        for file in files {
+            match load_pack(&file) {
+                Ok(pack) => packs.push(pack),
+                Err(e) => {
+                    log::warn!("Failed to load rule pack {}", e);
+                    skipped.push(e);
+                }
+            }
+        }
+    }
+
+    let mut rules: Vec<Rule> = Vec::new();
+    for pack in packs {
+        skipped.extend(pack.skipped.iter().map(|s| format!("{}: {}", pack.origin, s)));
+        for rule in &pack.rules {
+            match rules.iter_mut().find(|r| r.id == rule.id) {
+                Some(existing) => *existing = rule.clone(),
+                None => rules.push(rule.clone()),
+            }
+        }
+    }
+
+    let disabled: Vec<String> = settings::get_as("scanner.disabledRules", root, Vec::new());
+    let severities: HashMap<String, String> = settings::get_as("scanner.ruleSeverities", root, HashMap::new());
+    for rule in &mut rules {
+        if disabled.contains(&rule.id) {
+            rule.enabled = false;
+        }
+        if let Some(severity) = severities.get(&rule.id).and_then(|s| Severity::parse(s)) {
+            rule.severity = severity;
+        }
+    }
+    (rules, skipped)
+}
+
+/// Enabled rules for scanning files of a workspace
+pub fn configured_rules(workspace_root: Option<&Path>) -> Vec<Rule> {
+    let (rules, _) = all_rules(workspace_root);
+    rules.into_iter().filter(|r| r.enabled).collect()
+}
+
+/// Every rule configured for a workspace, including disabled ones
+pub fn catalog(workspace_root: Option<&Path>) -> RuleCatalog {
+    let (rules, skipped) = all_rules(workspace_root);
+    RuleCatalog {
+        rules: rules.iter().map(Rule::info).collect(),
+        skipped,
+    }
+}
+
+/// Drop every compiled pack so the next scan reads them from disk again
+pub fn reload(workspace_root: Option<&Path>) -> RuleCatalog {
+    PACKS.lock().unwrap().clear();
+    catalog(workspace_root)
+}
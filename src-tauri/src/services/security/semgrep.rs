@@ -0,0 +1,414 @@
+//! Semgrep rule import
+//!
+//! Converts a practical subset of Semgrep YAML rules into line patterns for
+//! the scanner. `pattern`, `pattern-regex`, `pattern-either` and `patterns`
+//! (with `pattern-not`) are supported; code patterns become regexes where
+//! `...` matches anything and metavariables like `$X` match any expression,
+//! so metavariable equality is not enforced. Multi-statement patterns are
+//! matched by their last statement, and `pattern-inside` and
+//! `metavariable-*` constraints are ignored. Taint-mode rules are skipped.
+//!
+//! Rule files are listed in the `scanner.semgrepRules` setting.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use super::{SecurityIssue, Severity};
+use crate::services::langdetect::LanguageId;
+use crate::services::settings;
+
+pub struct SemgrepRule {
+    pub id: String,
+    pub message: String,
+    pub severity: Severity,
+    pub cwe: Option<String>,
+    pub fix: Option<String>,
+    /// File extensions the rule's languages cover; None for any file
+    pub extensions: Option<Vec<&'static str>>,
+    /// All of these must match a line
+    matchers: Vec<Regex>,
+    /// None of these may match it
+    excludes: Vec<Regex>,
+}
+
+/// Rules loaded from one or more files, with the ones that could not be used
+#[derive(Default)]
+pub struct RuleSet {
+    pub rules: Vec<SemgrepRule>,
+    /// "<id>: <reason>" for each rule that was left out
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleSummary {
+    pub id: String,
+    pub message: String,
+    pub severity: Severity,
+    pub cwe: Option<String>,
+    pub extensions: Option<Vec<String>>,
+}
+
+impl SemgrepRule {
+    pub fn summary(&self) -> RuleSummary {
+        RuleSummary {
+            id: self.id.clone(),
+            message: self.message.clone(),
+            severity: self.severity.clone(),
+            cwe: self.cwe.clone(),
+            extensions: self
+                .extensions
+                .as_ref()
+                .map(|exts| exts.iter().map(|e| e.to_string()).collect()),
+        }
+    }
+
+    pub fn applies_to(&self, file_ext: Option<&str>) -> bool {
+        match (&self.extensions, file_ext) {
+            (None, _) => true,
+            (Some(exts), Some(ext)) => exts.contains(&ext),
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        self.matchers.iter().all(|re| re.is_match(line)) && !self.excludes.iter().any(|re| re.is_match(line))
+    }
+
+    /// A regex every matching line matches, for prefiltering lines
+    pub fn prefilter(&self) -> &Regex {
+        &self.matchers[0]
+    }
+
+    pub fn issue(&self, path: &Path, line: usize) -> SecurityIssue {
+        SecurityIssue {
+            file: path.to_string_lossy().to_string(),
+            line,
+            severity: self.severity.clone(),
+            kind: self.id.clone(),
+            rule: self.id.clone(),
+            message: self.message.clone(),
+            cwe: self.cwe.clone(),
+            fix_hint: self.fix.clone(),
+            cell: None,
+            fingerprint: None,
+            triage: None,
+        }
+    }
+}
+
+/// Rules from the files and directories in `scanner.semgrepRules`; relative
+/// entries are resolved against the workspace. Unreadable files are logged
+/// and skipped so one bad pack does not disable scanning.
+pub fn configured_rules(workspace_root: Option<&Path>) -> Vec<SemgrepRule> {
+    let entries: Vec<String> = settings::get_as(
+        "scanner.semgrepRules",
+        workspace_root.and_then(|root| root.to_str()),
+        Vec::new(),
+    );
+
+    let mut rules = Vec::new();
+    for entry in entries {
+        let path = match workspace_root {
+            Some(root) if Path::new(&entry).is_relative() => root.join(&entry),
+            _ => PathBuf::from(&entry),
+        };
+        match load_rules(&path) {
+            Ok(set) => rules.extend(set.rules),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    rules
+}
+
+/// Load a rule file, or every .yml/.yaml file under a directory
+pub fn load_rules(path: &Path) -> Result<RuleSet, String> {
+    if path.is_dir() {
+        let mut files = Vec::new();
+        collect_rule_files(path, &mut files);
+        files.sort();
+
+        let mut set = RuleSet::default();
+        for file in files {
+            match load_rules(&file) {
+                Ok(loaded) => {
+                    set.rules.extend(loaded.rules);
+                    set.skipped.extend(loaded.skipped);
+                }
+                Err(e) => set.skipped.push(e),
+            }
+        }
+        return Ok(set);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read Semgrep rules {}: {}", path.display(), e))?;
+    parse_rules(&content).map_err(|e| format!("Failed to parse Semgrep rules {}: {}", path.display(), e))
+}
+
+fn collect_rule_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_rule_files(&path, out);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| ext == "yml" || ext == "yaml")
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// Convert the `rules:` of a Semgrep YAML document
+pub fn parse_rules(content: &str) -> Result<RuleSet, String> {
+    let document: Value = serde_yaml::from_str(content).map_err(|e| format!("Invalid YAML: {}", e))?;
+    let rules = document
+        .get("rules")
+        .and_then(Value::as_array)
+        .ok_or("No rules list found")?;
+
+    let mut set = RuleSet::default();
+    for (index, rule) in rules.iter().enumerate() {
+        let id = rule
+            .get("id")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .unwrap_or_else(|| format!("rule-{}", index + 1));
+        match convert_rule(&id, rule) {
+            Ok(converted) => set.rules.push(converted),
+            Err(reason) => set.skipped.push(format!("{}: {}", id, reason)),
+        }
+    }
+    Ok(set)
+}
+
+fn convert_rule(id: &str, rule: &Value) -> Result<SemgrepRule, String> {
+    if rule.get("mode").and_then(Value::as_str).is_some_and(|mode| mode != "search") {
+        return Err("only search-mode rules are supported".to_string());
+    }
+
+    let mut matchers = Vec::new();
+    let mut excludes = Vec::new();
+    add_matchers(rule, &mut matchers, &mut excludes)?;
+    if matchers.is_empty() {
+        return Err("no supported pattern".to_string());
+    }
+
+    let compile = |patterns: Vec<String>| -> Result<Vec<Regex>, String> {
+        patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("invalid pattern {}: {}", p, e)))
+            .collect()
+    };
+
+    Ok(SemgrepRule {
+        id: id.to_string(),
+        message: rule
+            .get("message")
+            .and_then(Value::as_str)
+            .map(|m| m.trim().to_string())
+            .unwrap_or_else(|| id.to_string()),
+        severity: match rule.get("severity").and_then(Value::as_str) {
+            Some("CRITICAL") => Severity::Critical,
+            Some("ERROR") | Some("HIGH") => Severity::High,
+            Some("INFO") | Some("LOW") => Severity::Low,
+            _ => Severity::Medium,
+        },
+        cwe: rule.get("metadata").and_then(|m| m.get("cwe")).and_then(cwe_id),
+        fix: rule.get("fix").and_then(Value::as_str).map(|f| f.trim().to_string()),
+        extensions: language_extensions(rule.get("languages")),
+        matchers: compile(matchers)?,
+        excludes: compile(excludes)?,
+    })
+}
+
+/// Collect regexes for the pattern operators of a rule or `patterns` entry
+fn add_matchers(node: &Value, matchers: &mut Vec<String>, excludes: &mut Vec<String>) -> Result<(), String> {
+    if let Some(pattern) = node.get("pattern").and_then(Value::as_str) {
+        matchers.push(pattern_regex(pattern));
+    }
+    if let Some(regex) = node.get("pattern-regex").and_then(Value::as_str) {
+        matchers.push(regex.to_string());
+    }
+    if let Some(either) = node.get("pattern-either").and_then(Value::as_array) {
+        let mut alternatives = Vec::new();
+        for alternative in either {
+            let mut nested = Vec::new();
+            add_matchers(alternative, &mut nested, &mut Vec::new())?;
+            alternatives.extend(nested.into_iter().map(|p| format!("(?:{})", p)));
+        }
+        if !alternatives.is_empty() {
+            matchers.push(alternatives.join("|"));
+        }
+    }
+    if let Some(pattern) = node.get("pattern-not").and_then(Value::as_str) {
+        excludes.push(pattern_regex(pattern));
+    }
+    if let Some(regex) = node.get("pattern-not-regex").and_then(Value::as_str) {
+        excludes.push(regex.to_string());
+    }
+    if let Some(all) = node.get("patterns").and_then(Value::as_array) {
+        for item in all {
+            add_matchers(item, matchers, excludes)?;
+        }
+    }
+    Ok(())
+}
+
+/// Regex for a Semgrep code pattern, matched against single lines
+fn pattern_regex(pattern: &str) -> String {
+    // A multi-statement pattern is represented by its last statement, usually the sink
+    let statement = pattern
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "...")
+        .next_back()
+        .unwrap_or("");
+
+    let chars: Vec<char> = statement.chars().collect();
+    let mut regex = String::new();
+    if chars.first().is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+        regex.push_str(r"\b");
+    }
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if chars[i..].starts_with(&['.', '.', '.']) {
+            regex.push_str(".*?");
+            i += 3;
+        } else if c == '$' && chars.get(i + 1).is_some_and(|n| n.is_ascii_uppercase() || *n == '_' || *n == '.') {
+            // $X, $FUNC or $...ARGS
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            regex.push_str(".+?");
+        } else if c.is_whitespace() {
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            regex.push_str(r"\s*");
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            regex.push_str(&regex::escape(&c.to_string()));
+            i += 1;
+        } else {
+            // Code may space punctuation differently than the pattern does
+            regex.push_str(r"\s*");
+            regex.push_str(&regex::escape(&c.to_string()));
+            regex.push_str(r"\s*");
+            i += 1;
+        }
+    }
+    regex
+}
+
+fn cwe_id(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Array(items) => items.first()?.as_str()?,
+        other => other.as_str()?,
+    };
+    let re = Regex::new(r"(?i)CWE-\d+").ok()?;
+    re.find(text).map(|m| m.as_str().to_uppercase())
+}
+
+fn language_extensions(languages: Option<&Value>) -> Option<Vec<&'static str>> {
+    let mut extensions = Vec::new();
+    for language in languages?.as_array()?.iter().filter_map(Value::as_str) {
+        // generic, regex and anything unknown apply everywhere
+        extensions.extend_from_slice(LanguageId::from_name(language)?.extensions());
+    }
+    Some(extensions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+rules:
+  - id: python-shell-true
+    message: >
+      Command run through a shell
+    severity: ERROR
+    languages: [python]
+    metadata:
+      cwe: ["CWE-78: OS Command Injection"]
+    patterns:
+      - pattern: subprocess.call(..., shell=True)
+      - pattern-not: subprocess.call("...", shell=True)
+  - id: weak-hash
+    message: "MD5 or SHA-1 used"   # comment after a value
+    severity: WARNING
+    languages:
+      - generic
+    pattern-either:
+      - pattern: hashlib.md5(...)
+      - pattern-regex: sha1\(
+  - id: tainted
+    mode: taint
+    message: Taint rule
+    pattern-sources:
+      - pattern: request.args
+"#;
+
+    #[test]
+    fn test_convert_rules() {
+        let set = parse_rules(RULES).unwrap();
+        assert_eq!(set.rules.len(), 2);
+        assert_eq!(set.skipped, vec!["tainted: only search-mode rules are supported".to_string()]);
+
+        let shell = &set.rules[0];
+        assert_eq!(shell.message, "Command run through a shell");
+        assert_eq!(shell.severity, Severity::High);
+        assert_eq!(shell.cwe.as_deref(), Some("CWE-78"));
+        assert!(shell.applies_to(Some("py")));
+        assert!(!shell.applies_to(Some("js")));
+        assert!(!shell.applies_to(None));
+
+        let weak = &set.rules[1];
+        assert_eq!(weak.message, "MD5 or SHA-1 used");
+        assert_eq!(weak.severity, Severity::Medium);
+        assert_eq!(weak.cwe, None);
+        assert!(weak.applies_to(Some("js")) && weak.applies_to(None));
+
+        assert!(parse_rules("version: 1").is_err());
+        assert!(parse_rules("rules:\n  - id: [unclosed").is_err());
+        let empty = parse_rules("rules:\n  - id: nothing\n    message: no patterns\n").unwrap();
+        assert_eq!(empty.skipped, vec!["nothing: no supported pattern".to_string()]);
+    }
+
+    #[test]
+    fn test_rule_matching() {
+        let set = parse_rules(RULES).unwrap();
+        let shell = &set.rules[0];
+        assert!(shell.matches("    subprocess.call(cmd, shell=True)"));
+        assert!(shell.matches("subprocess.call( user_input , cwd=d, shell = True )"));
+        assert!(!shell.matches("subprocess.call(cmd)"));
+        assert!(!shell.matches(r#"subprocess.call("ls -la", shell=True)"#));
+        assert!(!shell.matches("mysubprocess.call(cmd, shell=True)"));
+
+        let weak = &set.rules[1];
+        assert!(weak.matches("digest = hashlib.md5(data).hexdigest()"));
+        assert!(weak.matches("h = sha1(data)"));
+        assert!(!weak.matches("digest = hashlib.sha256(data).hexdigest()"));
+    }
+
+    #[test]
+    fn test_pattern_regex() {
+        // Multi-statement patterns match their last statement
+        let re = Regex::new(&pattern_regex("$X = input()\n...\neval($X)")).unwrap();
+        assert!(re.is_match("eval(user_code)"));
+        assert!(!re.is_match("x = input()"));
+
+        let re = Regex::new(&pattern_regex("os.system(...)")).unwrap();
+        assert!(re.is_match("os.system()"));
+        assert!(re.is_match("os.system('ls')"));
+        assert!(!re.is_match("os_system('ls')"));
+    }
+}
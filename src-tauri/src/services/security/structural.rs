@@ -0,0 +1,1661 @@
+//! Structural checks for JavaScript/TypeScript, Java, PHP and Python
+//!
+//! Parses the file with tree-sitter and looks at calls and assignments
+//! instead of raw text, so code in comments and strings is not reported and
+//! sinks fed only by literals are left alone. The comment ranges are also
+//! handed back so the regex patterns can drop matches that fall inside them.
+//! Python is covered by the cryptographic misuse checks only.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser, Point};
+
+use super::{SecurityIssue, Severity};
+
+/// child_process functions that run a command
+const CHILD_PROCESS_FUNCTIONS: &[&str] = &["exec", "execSync", "execFile", "execFileSync", "spawn", "spawnSync", "fork"];
+
+/// Request data in Node servers and browser code
+const JS_INPUT_SOURCES: &[&str] = &[
+    "req.body", "req.query", "req.params", "req.cookies", "req.headers",
+    "request.body", "request.query", "request.params",
+    "ctx.request.body", "ctx.query", "ctx.params",
+    "location.search", "location.hash", "process.argv",
+];
+
+/// Keys that lead from an object to a prototype
+const PROTOTYPE_KEYS: &[&str] = &["__proto__", "constructor", "prototype"];
+
+/// Payload that pollutes Object.prototype through an unsafe merge
+const PROTOTYPE_POLLUTION_POC: &str = r#"{"__proto__": {"polluted": true}}"#;
+
+/// Scope of names bound outside any function
+const ROOT_SCOPE: usize = usize::MAX;
+
+/// JDBC methods that take SQL text
+const JDBC_QUERY_METHODS: &[&str] = &["executeQuery", "executeUpdate", "executeLargeUpdate", "execute", "addBatch", "prepareStatement"];
+
+/// PHP query functions; the query is the last argument (mysqli_query and
+/// pg_query take the connection first)
+const PHP_QUERY_FUNCTIONS: &[&str] = &["mysql_query", "mysql_db_query", "mysqli_query", "mysqli_multi_query", "pg_query", "sqlite_query"];
+
+/// Query methods of PDO and mysqli objects
+const PHP_QUERY_METHODS: &[&str] = &["query", "exec", "prepare", "multi_query", "real_query"];
+
+/// PHP superglobals carrying request data
+const PHP_INPUT_GLOBALS: &[&str] = &["$_GET", "$_POST", "$_REQUEST", "$_COOKIE", "$_FILES", "$_SERVER"];
+
+/// OWASP's recommended PBKDF2-HMAC-SHA256 iteration count
+const PBKDF2_MIN_ITERATIONS: i64 = 600_000;
+
+/// Below this many iterations PBKDF2 hardly slows down guessing at all
+const PBKDF2_BROKEN_ITERATIONS: i64 = 10_000;
+
+/// pycryptodome's PBKDF2 `count` when none is given
+const PYCRYPTODOME_PBKDF2_COUNT: i64 = 1000;
+
+const RSA_MIN_BITS: i64 = 2048;
+
+/// Lowercase parts of names that hold secrets
+const SECRET_NAME_PARTS: &[&str] = &[
+    "token", "secret", "passw", "pwd", "apikey", "api_key", "session", "nonce", "salt", "otp", "csrf",
+    "reset", "verification", "authcode", "auth_code", "private_key", "privatekey",
+];
+
+/// Python random module functions
+const PYTHON_RANDOM_FUNCTIONS: &[&str] = &["random", "randint", "randrange", "choice", "choices", "getrandbits", "sample", "uniform"];
+
+/// PHP functions whose output is predictable
+const PHP_RANDOM_FUNCTIONS: &[&str] = &["rand", "mt_rand", "uniqid", "lcg_value", "str_shuffle", "array_rand"];
+
+/// pycryptodome ciphers created with `new(key, mode, iv)`
+const PYTHON_BLOCK_CIPHERS: &[&str] = &["AES", "DES", "DES3", "Blowfish", "ARC2", "CAST"];
+
+/// Modes of the cryptography package that take an IV or nonce
+const PYTHON_IV_MODES: &[&str] = &["CBC", "CFB", "CFB8", "OFB", "CTR", "GCM"];
+
+/// Java ciphers that the providers run in ECB mode when none is given
+const JAVA_ECB_DEFAULT_CIPHERS: &[&str] = &["AES", "DES", "DESEDE", "BLOWFISH", "RC2"];
+
+/// Calls that fill a buffer passed to them with random bytes
+const RANDOM_FILL_FUNCTIONS: &[&str] = &["nextBytes", "randomFill", "randomFillSync", "getRandomValues", "readinto"];
+
+/// Longest parameter value quoted in a message
+const MAX_QUOTED_CHARS: usize = 60;
+
+/// Result of a structural scan of one file
+pub struct StructuralScan {
+    pub issues: Vec<SecurityIssue>,
+    /// Start and end of every comment in the file
+    comments: Vec<(Point, Point)>,
+}
+
+impl StructuralScan {
+    /// Whether a 0-indexed row and byte column lie inside a comment
+    pub fn in_comment(&self, row: usize, column: usize) -> bool {
+        let point = Point { row, column };
+        self.comments.iter().any(|(start, end)| *start <= point && point < *end)
+    }
+}
+
+fn language_for(file_ext: &str) -> Option<Language> {
+    match file_ext {
+        "js" | "jsx" | "mjs" | "cjs" => Some(tree_sitter_javascript::language()),
+        "ts" => Some(tree_sitter_typescript::language_typescript()),
+        "tsx" => Some(tree_sitter_typescript::language_tsx()),
+        "java" => Some(tree_sitter_java::language()),
+        "php" => Some(tree_sitter_php::language()),
+        "py" => Some(tree_sitter_python::language()),
+        _ => None,
+    }
+}
+
+/// Parse and check a file; None when the language has no structural checks
+/// or the source does not parse
+pub fn scan_source(path: &Path, file_ext: &str, source: &str) -> Option<StructuralScan> {
+    let language = language_for(file_ext)?;
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(source, None)?;
+    let root = tree.root_node();
+
+    let mut checker = Checker {
+        path,
+        source: source.as_bytes(),
+        issues: Vec::new(),
+    };
+    let lang = match file_ext {
+        "java" => Lang::Java,
+        "php" => Lang::Php,
+        "py" => Lang::Python,
+        _ => Lang::JavaScript,
+    };
+    match lang {
+        Lang::Java => checker.check_java(root),
+        Lang::Php => checker.check_php(root),
+        Lang::JavaScript => checker.check_javascript(root),
+        // Python only has the cryptographic checks
+        Lang::Python => {}
+    }
+    checker.check_crypto(root, lang);
+
+    let mut comments = Vec::new();
+    walk(root, &mut |node| {
+        if node.kind().ends_with("comment") {
+            comments.push((node.start_position(), node.end_position()));
+        }
+    });
+
+    Some(StructuralScan {
+        issues: checker.issues,
+        comments,
+    })
+}
+
+/// Visit every node, parents before children
+fn walk<'a>(node: Node<'a>, visit: &mut impl FnMut(Node<'a>)) {
+    visit(node);
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk(child, visit);
+    }
+}
+
+fn any_descendant(node: Node, predicate: &impl Fn(Node) -> bool) -> bool {
+    if predicate(node) {
+        return true;
+    }
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|child| any_descendant(child, predicate));
+    found
+}
+
+fn first_argument(arguments: Node) -> Option<Node> {
+    let mut cursor = arguments.walk();
+    let first = arguments.named_children(&mut cursor).find(|n| !n.kind().ends_with("comment"));
+    first
+}
+
+fn last_argument(arguments: Node) -> Option<Node> {
+    let mut cursor = arguments.walk();
+    let last = arguments.named_children(&mut cursor).filter(|n| !n.kind().ends_with("comment")).last();
+    last
+}
+
+struct Checker<'a> {
+    path: &'a Path,
+    source: &'a [u8],
+    issues: Vec<SecurityIssue>,
+}
+
+impl<'a> Checker<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        node.utf8_text(self.source).unwrap_or("")
+    }
+
+    fn report(&mut self, node: Node, kind: &str, severity: Severity, message: String, cwe: &str, fix_hint: &str) {
+        self.issues.push(SecurityIssue {
+            file: self.path.to_string_lossy().to_string(),
+            line: node.start_position().row + 1,
+            severity,
+            kind: kind.to_string(),
+            rule: format!("structural-{}", kind.to_ascii_lowercase().replace(' ', "-")),
+            message,
+            cwe: Some(cwe.to_string()),
+            fix_hint: Some(fix_hint.to_string()),
+            cell: None,
+            fingerprint: None,
+            triage: None,
+        });
+    }
+
+    /// A string or number with nothing interpolated, or a concatenation of them
+    fn is_literal(&self, node: Node) -> bool {
+        match node.kind() {
+            "string" | "string_literal" | "number" | "decimal_integer_literal" | "integer" | "float" | "true" | "false" | "null" => {
+                !any_descendant(node, &|n| {
+                    matches!(n.kind(), "template_substitution" | "variable_name" | "interpolation")
+                })
+            }
+            "template_string" | "encapsed_string" | "text_block" => !any_descendant(node, &|n| {
+                matches!(n.kind(), "template_substitution" | "variable_name" | "member_access_expression")
+            }),
+            "binary_expression" | "parenthesized_expression" => {
+                let mut cursor = node.walk();
+                let literal = node.named_children(&mut cursor).all(|child| self.is_literal(child));
+                literal
+            }
+            _ => false,
+        }
+    }
+
+    /// A string assembled at runtime: concatenation, interpolation or formatting
+    fn is_string_building(&self, node: Node) -> bool {
+        if self.is_literal(node) {
+            return false;
+        }
+        match node.kind() {
+            "binary_expression" => {
+                let operator = node.child_by_field_name("operator").map(|op| self.text(op));
+                matches!(operator, Some("+") | Some("."))
+            }
+            "template_string" | "encapsed_string" => true,
+            "parenthesized_expression" => node.named_child(0).is_some_and(|inner| self.is_string_building(inner)),
+            "method_invocation" => node
+                .child_by_field_name("name")
+                .is_some_and(|name| matches!(self.text(name), "format" | "formatted" | "concat")),
+            "function_call_expression" => node
+                .child_by_field_name("function")
+                .is_some_and(|function| matches!(self.text(function), "sprintf" | "vsprintf" | "implode")),
+            _ => false,
+        }
+    }
+
+    // === JavaScript / TypeScript ===
+
+    fn check_javascript(&mut self, root: Node) {
+        let (modules, functions) = self.child_process_bindings(root);
+        let tainted = self.js_tainted_names(root);
+
+        walk(root, &mut |node| match node.kind() {
+            "call_expression" => self.check_js_call(node, &modules, &functions),
+            "function_declaration" | "generator_function_declaration" | "method_definition" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.check_js_merge_function(node, self.text(name));
+                }
+            }
+            "variable_declarator" => {
+                let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value")) else {
+                    return;
+                };
+                if is_js_function(value) {
+                    self.check_js_merge_function(value, self.text(name));
+                }
+            }
+            "new_expression" => {
+                let constructor = node.child_by_field_name("constructor").map(|c| self.text(c));
+                if constructor == Some("Function") {
+                    self.report(
+                        node,
+                        "Dynamic Code Evaluation",
+                        Severity::High,
+                        "new Function compiles a string into code. Any user input in it runs as JavaScript.".to_string(),
+                        "CWE-95",
+                        "Replace generated code with data-driven logic",
+                    );
+                }
+            }
+            "assignment_expression" => {
+                self.check_js_html_assignment(node);
+                self.check_js_prototype_assignment(node, &tainted);
+                // module.exports.merge = function (...) { ... }
+                let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) else {
+                    return;
+                };
+                if left.kind() == "member_expression" && is_js_function(right) {
+                    if let Some(property) = left.child_by_field_name("property") {
+                        self.check_js_merge_function(right, self.text(property));
+                    }
+                }
+            }
+            "augmented_assignment_expression" => self.check_js_html_assignment(node),
+            _ => {}
+        });
+    }
+
+    fn check_js_call(&mut self, node: Node, modules: &HashSet<String>, functions: &HashSet<String>) {
+        let (Some(function), Some(arguments)) = (node.child_by_field_name("function"), node.child_by_field_name("arguments")) else {
+            return;
+        };
+        let argument = first_argument(arguments);
+        let dynamic_argument = argument.is_some_and(|arg| !self.is_literal(arg));
+
+        match function.kind() {
+            "identifier" => {
+                let name = self.text(function);
+                if name == "eval" && dynamic_argument {
+                    self.report(
+                        node,
+                        "Dynamic Code Evaluation",
+                        Severity::High,
+                        "eval runs a string built at runtime. Any user input in it runs as JavaScript.".to_string(),
+                        "CWE-95",
+                        "Use JSON.parse for data, or a lookup table instead of generated code",
+                    );
+                } else if matches!(name, "setTimeout" | "setInterval")
+                    && argument.is_some_and(|arg| self.is_string_building(arg))
+                {
+                    self.report(
+                        node,
+                        "Dynamic Code Evaluation",
+                        Severity::Medium,
+                        format!("{} with a string argument evaluates it as code.", name),
+                        "CWE-95",
+                        "Pass a function instead of a string",
+                    );
+                } else if functions.contains(name) && dynamic_argument {
+                    self.report_child_process(node, name);
+                }
+            }
+            "member_expression" => {
+                let (Some(object), Some(property)) = (function.child_by_field_name("object"), function.child_by_field_name("property")) else {
+                    return;
+                };
+                let method = self.text(property);
+                if method == "insertAdjacentHTML" && arguments.named_child_count() > 1 {
+                    let html = last_argument(arguments);
+                    if html.is_some_and(|arg| !self.is_literal(arg)) {
+                        self.report_html_sink(node, method);
+                    }
+                } else if CHILD_PROCESS_FUNCTIONS.contains(&method)
+                    && dynamic_argument
+                    && (modules.contains(self.text(object)) || self.is_child_process_require(object))
+                {
+                    self.report_child_process(node, method);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_js_html_assignment(&mut self, node: Node) {
+        let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) else {
+            return;
+        };
+        if left.kind() != "member_expression" || self.is_literal(right) {
+            return;
+        }
+        let property = left.child_by_field_name("property").map(|p| self.text(p));
+        if let Some(property @ ("innerHTML" | "outerHTML")) = property {
+            self.report_html_sink(node, property);
+        }
+    }
+
+    /// Whether an expression reads request data or a name bound to it in
+    /// an enclosing scope
+    fn js_uses_input(&self, node: Node, tainted: &HashSet<(usize, String)>) -> bool {
+        any_descendant(node, &|n| match n.kind() {
+            "member_expression" => JS_INPUT_SOURCES.contains(&self.text(n)),
+            "identifier" => {
+                let name = self.text(n).to_string();
+                std::iter::successors(n.parent(), |p| p.parent())
+                    .filter(|p| is_js_scope(*p))
+                    .map(|p| p.id())
+                    .chain([ROOT_SCOPE])
+                    .any(|scope| tainted.contains(&(scope, name.clone())))
+            }
+            _ => false,
+        })
+    }
+
+    /// Names bound, directly or through other names, to request data, with
+    /// the function they are bound in; the keys of a request object iterated
+    /// with `for...in` count too
+    fn js_tainted_names(&self, root: Node) -> HashSet<(usize, String)> {
+        let mut bindings = Vec::new();
+        walk(root, &mut |node| {
+            let (target, value) = match node.kind() {
+                "variable_declarator" => (node.child_by_field_name("name"), node.child_by_field_name("value")),
+                "assignment_expression" => (node.child_by_field_name("left"), node.child_by_field_name("right")),
+                "for_in_statement" => (node.child_by_field_name("left"), node.child_by_field_name("right")),
+                _ => return,
+            };
+            let (Some(target), Some(value)) = (target, value) else {
+                return;
+            };
+            let scope = js_scope(node);
+            let mut names = Vec::new();
+            walk(target, &mut |part| {
+                if matches!(part.kind(), "identifier" | "shorthand_property_identifier_pattern") {
+                    names.push((scope, self.text(part).to_string()));
+                }
+            });
+            bindings.extend(names.into_iter().map(|name| (name, value)));
+        });
+
+        let mut tainted = HashSet::new();
+        loop {
+            let before = tainted.len();
+            for (name, value) in &bindings {
+                if !tainted.contains(name) && self.js_uses_input(*value, &tainted) {
+                    tainted.insert(name.clone());
+                }
+            }
+            if tainted.len() == before {
+                return tainted;
+            }
+        }
+    }
+
+    /// Whether the code compares keys against `__proto__`, `constructor` or
+    /// `prototype`, taken as a guard against prototype pollution
+    fn guards_prototype_keys(&self, node: Node) -> bool {
+        any_descendant(node, &|n| {
+            n.kind() == "string" && PROTOTYPE_KEYS.contains(&self.text(n).trim_matches(|c| c == '\'' || c == '"' || c == '`'))
+        })
+    }
+
+    /// A function that walks the keys of one object and copies them into
+    /// another, calling itself for nested objects, without skipping the keys
+    /// that reach a prototype
+    fn check_js_merge_function(&mut self, function: Node, name: &str) {
+        let Some(body) = function.child_by_field_name("body") else {
+            return;
+        };
+        let recursive = any_descendant(body, &|n| {
+            n.kind() == "call_expression"
+                && n.child_by_field_name("function").is_some_and(|f| {
+                    let callee = self.text(f);
+                    callee == name || callee.ends_with(&format!(".{}", name))
+                })
+        });
+        if !recursive || self.guards_prototype_keys(body) {
+            return;
+        }
+
+        // Loop variables of `for (key in source)`, `for (const [key] of
+        // Object.entries(source))` and `Object.keys(source).forEach(key => ...)`
+        let mut keys = HashSet::new();
+        walk(body, &mut |node| {
+            let key = match node.kind() {
+                "for_in_statement" => node.child_by_field_name("left"),
+                "call_expression" => {
+                    let iterates_keys = node
+                        .child_by_field_name("function")
+                        .is_some_and(|f| self.text(f).starts_with("Object.keys(") && self.text(f).ends_with(".forEach"));
+                    let callback = node.child_by_field_name("arguments").and_then(first_argument);
+                    callback
+                        .filter(|c| iterates_keys && is_js_function(*c))
+                        .and_then(|c| c.child_by_field_name("parameter").or_else(|| c.child_by_field_name("parameters")))
+                }
+                _ => None,
+            };
+            let first_name = key.and_then(|key| {
+                let mut found = None;
+                walk(key, &mut |part| {
+                    if found.is_none() && part.kind() == "identifier" {
+                        found = Some(self.text(part).to_string());
+                    }
+                });
+                found
+            });
+            keys.extend(first_name);
+        });
+        let copies_keys = !keys.is_empty()
+            && any_descendant(body, &|n| {
+                n.kind() == "assignment_expression"
+                    && n.child_by_field_name("left").is_some_and(|left| {
+                        left.kind() == "subscript_expression"
+                            && left.child_by_field_name("index").is_some_and(|index| keys.contains(self.text(index)))
+                    })
+            });
+        if copies_keys {
+            self.report(
+                function,
+                "Prototype Pollution",
+                Severity::High,
+                format!(
+                    "{} merges objects recursively without skipping \"__proto__\". Merging {} into an object adds \"polluted\" to every object through Object.prototype.",
+                    name, PROTOTYPE_POLLUTION_POC
+                ),
+                "CWE-1321",
+                "Skip the __proto__, constructor and prototype keys, copy only own properties, or merge into Object.create(null) or a Map",
+            );
+        }
+    }
+
+    /// `target[key] = value` with the key taken from request data
+    fn check_js_prototype_assignment(&mut self, node: Node, tainted: &HashSet<(usize, String)>) {
+        let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) else {
+            return;
+        };
+        let (Some(object), Some(index)) = (left.child_by_field_name("object"), left.child_by_field_name("index")) else {
+            return;
+        };
+        if left.kind() != "subscript_expression" || !self.js_uses_input(index, tainted) {
+            return;
+        }
+        let function = std::iter::successors(node.parent(), |n| n.parent()).find(|n| is_js_scope(*n));
+        if self.guards_prototype_keys(function.unwrap_or(node)) {
+            return;
+        }
+
+        // obj[a][b] = v: with a = "__proto__" the write lands on Object.prototype
+        let outer_index = (object.kind() == "subscript_expression")
+            .then(|| object.child_by_field_name("index"))
+            .flatten()
+            .filter(|outer| !self.is_literal(*outer));
+        if let Some(outer) = outer_index {
+            self.report(
+                node,
+                "Prototype Pollution",
+                Severity::High,
+                format!(
+                    "{} is written with keys from request data. With {} = \"__proto__\" and {} = \"polluted\" the value is set on Object.prototype.",
+                    self.text(left), self.text(outer), self.text(index)
+                ),
+                "CWE-1321",
+                "Reject the __proto__, constructor and prototype keys, or store request-keyed data in a Map or Object.create(null)",
+            );
+        } else if self.js_uses_input(right, tainted) {
+            self.report(
+                node,
+                "Prototype Pollution",
+                Severity::Medium,
+                format!(
+                    "{} is written with a key and value from request data. With {} = \"__proto__\" the value replaces the object's prototype, e.g. {}.",
+                    self.text(left), self.text(index), PROTOTYPE_POLLUTION_POC
+                ),
+                "CWE-1321",
+                "Reject the __proto__, constructor and prototype keys, or store request-keyed data in a Map or Object.create(null)",
+            );
+        }
+    }
+
+    fn report_html_sink(&mut self, node: Node, sink: &str) {
+        self.report(
+            node,
+            "Unsafe HTML Assignment",
+            Severity::High,
+            format!("{} receives a value built at runtime. Unescaped input here is XSS.", sink),
+            "CWE-79",
+            "Use textContent, or sanitize the HTML with DOMPurify first",
+        );
+    }
+
+    fn report_child_process(&mut self, node: Node, function: &str) {
+        self.report(
+            node,
+            "Command Injection",
+            Severity::High,
+            format!("child_process.{} runs a command built at runtime.", function),
+            "CWE-78",
+            "Use execFile/spawn with a fixed command and an argument array, without a shell",
+        );
+    }
+
+    /// `require('child_process')`, with or without the `node:` prefix
+    fn is_child_process_require(&self, node: Node) -> bool {
+        if node.kind() != "call_expression" {
+            return false;
+        }
+        let callee = node.child_by_field_name("function").map(|f| self.text(f));
+        let module = node
+            .child_by_field_name("arguments")
+            .and_then(first_argument)
+            .map(|arg| self.text(arg));
+        callee == Some("require") && module.is_some_and(is_child_process_module)
+    }
+
+    /// Names bound to the child_process module and to its functions, from
+    /// `require` declarations and `import` statements
+    fn child_process_bindings(&self, root: Node) -> (HashSet<String>, HashSet<String>) {
+        let mut modules = HashSet::new();
+        let mut functions = HashSet::new();
+
+        walk(root, &mut |node| match node.kind() {
+            "variable_declarator" => {
+                let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value")) else {
+                    return;
+                };
+                if !self.is_child_process_require(value) {
+                    return;
+                }
+                match name.kind() {
+                    "identifier" => {
+                        modules.insert(self.text(name).to_string());
+                    }
+                    "object_pattern" => {
+                        let mut cursor = name.walk();
+                        for property in name.named_children(&mut cursor) {
+                            let local = match property.kind() {
+                                "shorthand_property_identifier_pattern" => Some(property),
+                                "pair_pattern" => property.child_by_field_name("value"),
+                                _ => None,
+                            };
+                            if let Some(local) = local {
+                                functions.insert(self.text(local).to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            "import_statement" => {
+                let from_child_process = node
+                    .child_by_field_name("source")
+                    .is_some_and(|source| is_child_process_module(self.text(source)));
+                if !from_child_process {
+                    return;
+                }
+                walk(node, &mut |part| match part.kind() {
+                    "import_specifier" => {
+                        let local = part.child_by_field_name("alias").or_else(|| part.child_by_field_name("name"));
+                        if let Some(local) = local {
+                            functions.insert(self.text(local).to_string());
+                        }
+                    }
+                    "import_clause" | "namespace_import" => {
+                        let mut cursor = part.walk();
+                        for child in part.named_children(&mut cursor).filter(|c| c.kind() == "identifier") {
+                            modules.insert(self.text(child).to_string());
+                        }
+                    }
+                    _ => {}
+                });
+            }
+            _ => {}
+        });
+
+        (modules, functions)
+    }
+
+    // === Java ===
+
+    fn check_java(&mut self, root: Node) {
+        let runtimes = self.java_runtime_variables(root);
+
+        walk(root, &mut |node| {
+            if node.kind() != "method_invocation" {
+                return;
+            }
+            let (Some(name), Some(arguments)) = (node.child_by_field_name("name"), node.child_by_field_name("arguments")) else {
+                return;
+            };
+            let method = self.text(name);
+            let Some(argument) = first_argument(arguments) else {
+                return;
+            };
+
+            if method == "exec" {
+                let on_runtime = node.child_by_field_name("object").is_some_and(|object| {
+                    runtimes.contains(self.text(object))
+                        || (object.kind() == "method_invocation"
+                            && object.child_by_field_name("name").is_some_and(|n| self.text(n) == "getRuntime"))
+                });
+                if on_runtime && !self.is_literal(argument) {
+                    self.report(
+                        node,
+                        "Command Injection",
+                        Severity::High,
+                        "Runtime.exec runs a command built at runtime.".to_string(),
+                        "CWE-78",
+                        "Use ProcessBuilder with a fixed command and separate, validated arguments",
+                    );
+                }
+            }
+            if JDBC_QUERY_METHODS.contains(&method) && self.is_string_building(argument) {
+                self.report(
+                    node,
+                    "SQL Injection",
+                    Severity::Critical,
+                    format!("{} is called with SQL assembled by string concatenation.", method),
+                    "CWE-89",
+                    "Use a PreparedStatement with ? placeholders and bind the values",
+                );
+            }
+        });
+    }
+
+    /// Variables and fields declared with type `Runtime`
+    fn java_runtime_variables(&self, root: Node) -> HashSet<String> {
+        let mut names = HashSet::new();
+        walk(root, &mut |node| {
+            if !matches!(node.kind(), "local_variable_declaration" | "field_declaration") {
+                return;
+            }
+            if node.child_by_field_name("type").map(|t| self.text(t)) != Some("Runtime") {
+                return;
+            }
+            let mut cursor = node.walk();
+            for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                if let Some(name) = declarator.child_by_field_name("name") {
+                    names.insert(self.text(name).to_string());
+                }
+            }
+        });
+        names
+    }
+
+    // === PHP ===
+
+    fn check_php(&mut self, root: Node) {
+        let tainted = self.php_tainted_variables(root);
+
+        walk(root, &mut |node| match node.kind() {
+            "include_expression" | "include_once_expression" | "require_expression" | "require_once_expression" => {
+                let Some(target) = node.named_child(0) else {
+                    return;
+                };
+                if self.php_uses_input(target, &tainted) {
+                    self.report(
+                        node,
+                        "File Inclusion",
+                        Severity::Critical,
+                        format!("{} loads a path taken from request data.", node.kind().trim_end_matches("_expression")),
+                        "CWE-98",
+                        "Map the request value to a fixed allowlist of files instead of building the path",
+                    );
+                }
+            }
+            "function_call_expression" => {
+                let function = node.child_by_field_name("function").map(|f| self.text(f).to_ascii_lowercase());
+                let Some(function) = function.filter(|f| PHP_QUERY_FUNCTIONS.contains(&f.as_str())) else {
+                    return;
+                };
+                let query = node.child_by_field_name("arguments").and_then(last_argument);
+                self.check_php_query(node, &function, query, &tainted);
+            }
+            "member_call_expression" => {
+                let method = node.child_by_field_name("name").map(|n| self.text(n));
+                let Some(method) = method.filter(|m| PHP_QUERY_METHODS.contains(m)) else {
+                    return;
+                };
+                let query = node.child_by_field_name("arguments").and_then(first_argument);
+                self.check_php_query(node, method, query, &tainted);
+            }
+            _ => {}
+        });
+    }
+
+    fn check_php_query(&mut self, node: Node, function: &str, query: Option<Node>, tainted: &HashSet<String>) {
+        // `argument` wraps the expression in this grammar
+        let Some(query) = query.map(|q| if q.kind() == "argument" { q.named_child(0).unwrap_or(q) } else { q }) else {
+            return;
+        };
+        if !self.is_string_building(query) && !self.php_uses_input(query, tainted) {
+            return;
+        }
+        let from_input = self.php_uses_input(query, tainted);
+        self.report(
+            node,
+            "SQL Injection",
+            if from_input { Severity::Critical } else { Severity::High },
+            if from_input {
+                format!("{} runs SQL built from request data.", function)
+            } else {
+                format!("{} runs SQL assembled by string concatenation.", function)
+            },
+            "CWE-89",
+            "Use PDO or mysqli prepared statements with bound parameters",
+        );
+    }
+
+    /// Whether an expression reads a superglobal or a variable assigned from one
+    fn php_uses_input(&self, node: Node, tainted: &HashSet<String>) -> bool {
+        any_descendant(node, &|n| {
+            n.kind() == "variable_name" && {
+                let name = self.text(n);
+                PHP_INPUT_GLOBALS.contains(&name) || tainted.contains(name)
+            }
+        })
+    }
+
+    /// Variables assigned, directly or through other variables, from request data
+    fn php_tainted_variables(&self, root: Node) -> HashSet<String> {
+        let mut assignments = Vec::new();
+        walk(root, &mut |node| {
+            if !matches!(node.kind(), "assignment_expression" | "augmented_assignment_expression") {
+                return;
+            }
+            if let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) {
+                if left.kind() == "variable_name" {
+                    assignments.push((self.text(left).to_string(), right));
+                }
+            }
+        });
+
+        let mut tainted = HashSet::new();
+        loop {
+            let before = tainted.len();
+            for (name, value) in &assignments {
+                if !tainted.contains(name) && self.php_uses_input(*value, &tainted) {
+                    tainted.insert(name.clone());
+                }
+            }
+            if tainted.len() == before {
+                return tainted;
+            }
+        }
+    }
+    // === Cryptographic misuse ===
+
+    /// ECB mode, IVs written in the source, PBKDF2 with too few iterations,
+    /// predictable random numbers used for secrets and RSA keys under 2048
+    /// bits. Parameters are read from the call, or from a name bound once
+    /// to a literal, and quoted in the finding.
+    fn check_crypto(&mut self, root: Node, lang: Lang) {
+        let bindings = self.crypto_bindings(root);
+
+        walk(root, &mut |node| {
+            // AES.MODE_ECB, CryptoJS.mode.ECB and MCRYPT_MODE_ECB outside of any known call
+            let ecb_constant = match node.kind() {
+                "attribute" | "member_expression" => {
+                    let text = self.text(node);
+                    text.ends_with(".MODE_ECB") || text.ends_with("mode.ECB")
+                }
+                "name" => self.text(node) == "MCRYPT_MODE_ECB",
+                _ => false,
+            };
+            if ecb_constant {
+                self.report_ecb(node, lang, self.text(node).to_string());
+            }
+            if let Some(call) = self.crypto_call(node) {
+                match lang {
+                    Lang::Python => self.check_python_crypto(&call, &bindings),
+                    Lang::Java => self.check_java_crypto(&call, &bindings),
+                    Lang::JavaScript => self.check_js_crypto(&call, &bindings),
+                    Lang::Php => self.check_php_crypto(&call, &bindings),
+                }
+            }
+        });
+    }
+
+    /// Names assigned exactly once, and names later filled with random bytes
+    fn crypto_bindings<'t>(&self, root: Node<'t>) -> CryptoBindings<'t> {
+        let mut assigned: HashMap<String, (usize, Node<'t>)> = HashMap::new();
+        let mut filled = HashSet::new();
+        walk(root, &mut |node| {
+            if let Some(call) = self.crypto_call(node) {
+                if RANDOM_FILL_FUNCTIONS.contains(&call.name()) {
+                    filled.extend(call.args.iter().map(|arg| self.text(*arg).to_string()));
+                }
+                return;
+            }
+            let (name, value) = match node.kind() {
+                "variable_declarator" => (node.child_by_field_name("name"), node.child_by_field_name("value")),
+                "assignment" | "assignment_expression" => (node.child_by_field_name("left"), node.child_by_field_name("right")),
+                _ => return,
+            };
+            if let (Some(name), Some(value)) = (name, value) {
+                if matches!(name.kind(), "identifier" | "variable_name") {
+                    assigned.entry(self.text(name).to_string()).or_insert((0, value)).0 += 1;
+                }
+            }
+        });
+        let values = assigned
+            .into_iter()
+            .filter(|(name, (count, _))| *count == 1 && !filled.contains(name))
+            .map(|(name, (_, value))| (name, value))
+            .collect();
+        CryptoBindings { values }
+    }
+
+    /// The value behind a name bound once, or the node itself
+    fn resolve<'t>(&self, node: Node<'t>, bindings: &CryptoBindings<'t>) -> Node<'t> {
+        let mut node = node;
+        // A few hops cover `IV = DEFAULT_IV` without looping on cycles
+        for _ in 0..3 {
+            if !matches!(node.kind(), "identifier" | "variable_name") {
+                break;
+            }
+            match bindings.values.get(self.text(node)) {
+                Some(value) => node = *value,
+                None => break,
+            }
+        }
+        node
+    }
+
+    fn int_value(&self, node: Node, bindings: &CryptoBindings) -> Option<i64> {
+        let node = self.resolve(node, bindings);
+        if !matches!(node.kind(), "number" | "integer" | "decimal_integer_literal") {
+            return None;
+        }
+        let digits: String = self.text(node).chars().filter(|c| *c != '_').collect();
+        digits.trim_end_matches(['L', 'l', 'n']).parse().ok()
+    }
+
+    fn string_value(&self, node: Node, bindings: &CryptoBindings) -> Option<String> {
+        let node = self.resolve(node, bindings);
+        if !matches!(node.kind(), "string" | "string_literal" | "encapsed_string" | "template_string") || !self.is_literal(node) {
+            return None;
+        }
+        // Python prefixes such as b'' and r''
+        let text = self.text(node).trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        Some(text.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string())
+    }
+
+    /// Literals, arrays and repetitions of literals, and arrays allocated
+    /// without contents
+    fn is_constant(&self, node: Node) -> bool {
+        match node.kind() {
+            "array" | "list" | "array_initializer" | "array_creation_expression" | "array_element_initializer" | "binary_operator" => {
+                let mut cursor = node.walk();
+                let constant = node.named_children(&mut cursor).all(|child| self.is_constant(child));
+                constant
+            }
+            "integral_type" | "dimensions_expr" | "dimensions" => true,
+            _ => self.is_literal(node),
+        }
+    }
+
+    /// Bytes fixed in the source: a literal, a constant array, a buffer of
+    /// zeros, or a literal converted to bytes
+    fn static_bytes(&self, node: Node, bindings: &CryptoBindings) -> Option<String> {
+        let node = self.resolve(node, bindings);
+        // ECB and stream ciphers take no IV at all
+        if matches!(node.kind(), "null" | "none" | "undefined") {
+            return None;
+        }
+        let fixed = self.is_constant(node)
+            || self.crypto_call(node).is_some_and(|call| {
+                let constant_args = call.args.iter().all(|arg| self.is_constant(self.resolve(*arg, bindings)));
+                // "...".getBytes() in Java, "...".encode() in Python
+                let receiver = call
+                    .node
+                    .child_by_field_name("object")
+                    .or_else(|| call.node.child_by_field_name("function").and_then(|f| f.child_by_field_name("object")));
+                match call.name() {
+                    "getBytes" | "encode" => receiver.is_some_and(|r| self.is_literal(self.resolve(r, bindings))),
+                    "from" | "alloc" | "bytes" | "bytearray" | "fromhex" | "Uint8Array" | "str_repeat" | "hex2bin" => {
+                        !call.args.is_empty() && constant_args
+                    }
+                    _ => false,
+                }
+            });
+        fixed.then(|| quote(self.text(node)))
+    }
+
+    /// The value of a key in a JavaScript object or PHP array literal
+    fn entry_value<'t>(&self, node: Node<'t>, key: &str) -> Option<Node<'t>> {
+        let same_key = |name: Node| self.text(name).trim_matches(|c| c == '"' || c == '\'') == key;
+        let mut cursor = node.walk();
+        let entries: Vec<Node<'t>> = node.named_children(&mut cursor).collect();
+        entries.into_iter().find_map(|entry| match entry.kind() {
+            "pair" => entry.child_by_field_name("key").filter(|k| same_key(*k)).and(entry.child_by_field_name("value")),
+            "array_element_initializer" if entry.named_child_count() == 2 => {
+                entry.named_child(0).filter(|k| same_key(*k)).and(entry.named_child(1))
+            }
+            _ => None,
+        })
+    }
+
+    /// A call or constructor invocation in any of the languages
+    fn crypto_call<'t>(&self, node: Node<'t>) -> Option<CryptoCall<'t>> {
+        let field = |name: &str| node.child_by_field_name(name);
+        let (callee, arguments) = match node.kind() {
+            "call" | "call_expression" | "function_call_expression" => (self.text(field("function")?).to_string(), field("arguments")),
+            "new_expression" => (self.text(field("constructor")?).to_string(), field("arguments")),
+            "method_invocation" | "member_call_expression" => {
+                let name = self.text(field("name")?);
+                let callee = match field("object") {
+                    Some(object) => format!("{}.{}", self.text(object), name),
+                    None => name.to_string(),
+                };
+                (callee, field("arguments"))
+            }
+            "scoped_call_expression" => (format!("{}::{}", self.text(field("scope")?), self.text(field("name")?)), field("arguments")),
+            "object_creation_expression" => {
+                // The PHP grammar has no fields here
+                let mut cursor = node.walk();
+                let children: Vec<Node<'t>> = node.named_children(&mut cursor).collect();
+                let class = field("type").or_else(|| children.iter().copied().find(|c| matches!(c.kind(), "name" | "qualified_name")))?;
+                let arguments = field("arguments").or_else(|| children.into_iter().find(|c| c.kind() == "arguments"));
+                (self.text(class).to_string(), arguments)
+            }
+            _ => return None,
+        };
+
+        let mut args = Vec::new();
+        let mut named = Vec::new();
+        if let Some(arguments) = arguments {
+            let mut cursor = arguments.walk();
+            for argument in arguments.named_children(&mut cursor) {
+                match argument.kind() {
+                    "keyword_argument" => {
+                        if let (Some(name), Some(value)) = (argument.child_by_field_name("name"), argument.child_by_field_name("value")) {
+                            named.push((self.text(name).to_string(), value));
+                        }
+                    }
+                    // `argument` wraps the expression in the PHP grammar, after an optional name
+                    "argument" => {
+                        let value = argument.named_child(argument.named_child_count().saturating_sub(1)).unwrap_or(argument);
+                        match argument.child_by_field_name("name") {
+                            Some(name) => named.push((self.text(name).to_string(), value)),
+                            None => args.push(value),
+                        }
+                    }
+                    kind if kind.ends_with("comment") || kind.ends_with("splat") || kind == "spread_element" => {}
+                    _ => args.push(argument),
+                }
+            }
+        }
+        Some(CryptoCall { node, callee, args, named })
+    }
+
+    fn check_python_crypto(&mut self, call: &CryptoCall, bindings: &CryptoBindings) {
+        let lang = Lang::Python;
+        let (callee, name) = (call.callee.as_str(), call.name());
+        if callee == "modes.ECB" || callee == "ECB" {
+            self.report_ecb(call.node, lang, callee.to_string());
+        }
+
+        // AES.new(key, AES.MODE_CBC, iv) and modes.CBC(iv)
+        let iv = if name == "new" && PYTHON_BLOCK_CIPHERS.iter().any(|c| callee.ends_with(&format!("{}.new", c))) {
+            call.arg("iv", 2).or_else(|| call.named_arg("nonce"))
+        } else if PYTHON_IV_MODES.contains(&name) && (callee == name || callee.ends_with(&format!("modes.{}", name))) {
+            call.arg("initialization_vector", 0).or_else(|| call.arg("nonce", 0))
+        } else {
+            None
+        };
+        if let Some(iv) = iv.and_then(|iv| self.static_bytes(iv, bindings)) {
+            self.report_static_iv(call.node, lang, callee, iv);
+        }
+
+        match name {
+            "pbkdf2_hmac" | "PBKDF2HMAC" => self.check_pbkdf2_iterations(call, lang, call.arg("iterations", 3), None, bindings),
+            "PBKDF2" => self.check_pbkdf2_iterations(call, lang, call.arg("count", 3), Some(PYCRYPTODOME_PBKDF2_COUNT), bindings),
+            "generate" if callee.ends_with("RSA.generate") => self.check_rsa_bits(call, lang, call.arg("bits", 0), bindings),
+            "generate_private_key" if callee.starts_with("rsa.") => {
+                self.check_rsa_bits(call, lang, call.arg("key_size", 1), bindings)
+            }
+            "newkeys" if callee.starts_with("rsa.") => self.check_rsa_bits(call, lang, call.arg("nbits", 0), bindings),
+            _ if callee.starts_with("random.") && !callee.contains("SystemRandom") && PYTHON_RANDOM_FUNCTIONS.contains(&name) => {
+                self.check_weak_random(call, lang)
+            }
+            _ => {}
+        }
+    }
+
+    fn check_java_crypto(&mut self, call: &CryptoCall, bindings: &CryptoBindings) {
+        let lang = Lang::Java;
+        match call.name() {
+            "getInstance" if call.callee.ends_with("Cipher.getInstance") => {
+                let Some(transformation) = call.args.first().and_then(|t| self.string_value(*t, bindings)) else {
+                    return;
+                };
+                let upper = transformation.to_ascii_uppercase();
+                if upper.contains("/ECB/") {
+                    self.report_ecb(call.node, lang, format!("\"{}\"", transformation));
+                } else if JAVA_ECB_DEFAULT_CIPHERS.contains(&upper.as_str()) {
+                    self.report_ecb(call.node, lang, format!("\"{}\", which defaults to ECB", transformation));
+                }
+            }
+            name @ ("IvParameterSpec" | "GCMParameterSpec") => {
+                let iv = call.args.get(if name == "IvParameterSpec" { 0 } else { 1 });
+                if let Some(iv) = iv.and_then(|iv| self.static_bytes(*iv, bindings)) {
+                    self.report_static_iv(call.node, lang, name, iv);
+                }
+            }
+            "PBEKeySpec" => self.check_pbkdf2_iterations(call, lang, call.args.get(2).copied(), None, bindings),
+            "RSAKeyGenParameterSpec" => self.check_rsa_bits(call, lang, call.args.first().copied(), bindings),
+            "initialize" => {
+                // keyPairGenerator.initialize(1024) on a generator created for RSA
+                let generator = call.node.child_by_field_name("object").map(|o| self.resolve(o, bindings));
+                if generator.is_some_and(|g| self.text(g).contains("\"RSA\"")) {
+                    self.check_rsa_bits(call, lang, call.args.first().copied(), bindings);
+                }
+            }
+            "random" if call.callee == "Math.random" => self.check_weak_random(call, lang),
+            "Random" if call.node.kind() == "object_creation_expression" => self.check_weak_random(call, lang),
+            _ => {}
+        }
+    }
+
+    fn check_js_crypto(&mut self, call: &CryptoCall, bindings: &CryptoBindings) {
+        let lang = Lang::JavaScript;
+        match call.name() {
+            name @ ("createCipheriv" | "createDecipheriv" | "createCipher" | "createDecipher") => {
+                let algorithm = call.args.first().and_then(|a| self.string_value(*a, bindings));
+                if let Some(algorithm) = algorithm.filter(|a| a.to_ascii_lowercase().contains("ecb")) {
+                    self.report_ecb(call.node, lang, format!("'{}'", algorithm));
+                }
+                if name == "createCipheriv" {
+                    if let Some(iv) = call.args.get(2).and_then(|iv| self.static_bytes(*iv, bindings)) {
+                        self.report_static_iv(call.node, lang, name, iv);
+                    }
+                }
+            }
+            "pbkdf2" | "pbkdf2Sync" => self.check_pbkdf2_iterations(call, lang, call.args.get(2).copied(), None, bindings),
+            "generateKeyPair" | "generateKeyPairSync" => {
+                let first = call.args.first().map(|a| self.resolve(*a, bindings));
+                let bits = if first.is_some_and(|f| self.string_value(f, bindings).as_deref() == Some("rsa")) {
+                    // crypto.generateKeyPairSync('rsa', { modulusLength: 1024 })
+                    call.args.get(1).and_then(|o| self.entry_value(self.resolve(*o, bindings), "modulusLength"))
+                } else if call.callee.ends_with("rsa.generateKeyPair") {
+                    // node-forge: pki.rsa.generateKeyPair(1024) or ({ bits: 1024 })
+                    first.and_then(|f| if f.kind() == "object" { self.entry_value(f, "bits") } else { Some(f) })
+                } else {
+                    None
+                };
+                self.check_rsa_bits(call, lang, bits, bindings);
+            }
+            "random" if call.callee == "Math.random" => self.check_weak_random(call, lang),
+            _ => {}
+        }
+    }
+
+    fn check_php_crypto(&mut self, call: &CryptoCall, bindings: &CryptoBindings) {
+        let lang = Lang::Php;
+        let name = call.name().to_ascii_lowercase();
+        match name.as_str() {
+            "openssl_encrypt" | "openssl_decrypt" => {
+                let method = call.arg("cipher_algo", 1).and_then(|m| self.string_value(m, bindings));
+                if let Some(method) = method.filter(|m| m.to_ascii_lowercase().contains("ecb")) {
+                    self.report_ecb(call.node, lang, format!("'{}'", method));
+                }
+                if name == "openssl_encrypt" {
+                    if let Some(iv) = call.arg("iv", 4).and_then(|iv| self.static_bytes(iv, bindings)) {
+                        self.report_static_iv(call.node, lang, &name, iv);
+                    }
+                }
+            }
+            "hash_pbkdf2" | "openssl_pbkdf2" => {
+                self.check_pbkdf2_iterations(call, lang, call.arg("iterations", 3), None, bindings)
+            }
+            "openssl_pkey_new" => {
+                let options = call.arg("options", 0).map(|o| self.resolve(o, bindings));
+                let bits = options.and_then(|o| self.entry_value(o, "private_key_bits"));
+                self.check_rsa_bits(call, lang, bits, bindings);
+            }
+            function if PHP_RANDOM_FUNCTIONS.contains(&function) => self.check_weak_random(call, lang),
+            _ => {}
+        }
+    }
+
+    fn report_ecb(&mut self, node: Node, lang: Lang, mode: String) {
+        let fix_hint = match lang {
+            Lang::Python => "Use an authenticated mode: cipher = AES.new(key, AES.MODE_GCM); ciphertext, tag = cipher.encrypt_and_digest(data)",
+            Lang::Java => "Use Cipher.getInstance(\"AES/GCM/NoPadding\") with a random 12-byte IV: cipher.init(Cipher.ENCRYPT_MODE, key, new GCMParameterSpec(128, iv))",
+            Lang::JavaScript => "Use crypto.createCipheriv('aes-256-gcm', key, crypto.randomBytes(12)) and store cipher.getAuthTag() with the ciphertext",
+            Lang::Php => "Use openssl_encrypt($data, 'aes-256-gcm', $key, OPENSSL_RAW_DATA, $iv = random_bytes(12), $tag)",
+        };
+        self.report(
+            node,
+            "Insecure Cipher Mode",
+            Severity::High,
+            format!("ECB mode ({}) encrypts equal blocks to equal ciphertext, so the structure of the data shows through.", mode),
+            "CWE-327",
+            fix_hint,
+        );
+    }
+
+    fn report_static_iv(&mut self, node: Node, lang: Lang, function: &str, iv: String) {
+        let fix_hint = match lang {
+            Lang::Python => "Generate an IV per message and store it with the ciphertext: iv = os.urandom(16)",
+            Lang::Java => "Generate an IV per message and store it with the ciphertext: byte[] iv = new byte[16]; new SecureRandom().nextBytes(iv);",
+            Lang::JavaScript => "Generate an IV per message and store it with the ciphertext: const iv = crypto.randomBytes(16);",
+            Lang::Php => "Generate an IV per message and store it with the ciphertext: $iv = random_bytes(openssl_cipher_iv_length($cipher));",
+        };
+        self.report(
+            node,
+            "Static IV",
+            Severity::High,
+            format!(
+                "{} gets an IV fixed in the source ({}). Reusing an IV with the same key reveals repeated plaintext, and breaks CTR and GCM completely.",
+                function, iv
+            ),
+            "CWE-329",
+            fix_hint,
+        );
+    }
+
+    /// `iterations` is the argument with the count; `default` is what the
+    /// function uses when it is left out
+    fn check_pbkdf2_iterations(&mut self, call: &CryptoCall, lang: Lang, iterations: Option<Node>, default: Option<i64>, bindings: &CryptoBindings) {
+        let (count, described) = match (iterations, default) {
+            (Some(iterations), _) => match self.int_value(iterations, bindings) {
+                Some(count) => (count, count.to_string()),
+                None => return,
+            },
+            (None, Some(count)) => (count, format!("the default {}", count)),
+            (None, None) => return,
+        };
+        if count >= PBKDF2_MIN_ITERATIONS {
+            return;
+        }
+        let fix_hint = match lang {
+            Lang::Python => "Use at least 600000 iterations: hashlib.pbkdf2_hmac(\"sha256\", password, salt, 600_000), or argon2-cffi for new code",
+            Lang::Java => "Use at least 600000 iterations: new PBEKeySpec(password, salt, 600_000, 256)",
+            Lang::JavaScript => "Use at least 600000 iterations: crypto.pbkdf2Sync(password, salt, 600000, 32, 'sha256'), or crypto.scryptSync",
+            Lang::Php => "Use password_hash($password, PASSWORD_ARGON2ID), or hash_pbkdf2('sha256', $password, $salt, 600000)",
+        };
+        self.report(
+            call.node,
+            "Weak Key Derivation",
+            if count < PBKDF2_BROKEN_ITERATIONS { Severity::High } else { Severity::Medium },
+            format!(
+                "{} derives the key with {} PBKDF2 iterations; at least {} are needed to slow down offline password guessing.",
+                call.callee,
+                described,
+                PBKDF2_MIN_ITERATIONS
+            ),
+            "CWE-916",
+            fix_hint,
+        );
+    }
+
+    fn check_rsa_bits(&mut self, call: &CryptoCall, lang: Lang, bits: Option<Node>, bindings: &CryptoBindings) {
+        let Some(bits) = bits.and_then(|b| self.int_value(b, bindings)).filter(|b| *b < RSA_MIN_BITS) else {
+            return;
+        };
+        let fix_hint = match lang {
+            Lang::Python => "Generate 3072-bit keys: rsa.generate_private_key(public_exponent=65537, key_size=3072)",
+            Lang::Java => "Generate 3072-bit keys: keyPairGenerator.initialize(3072)",
+            Lang::JavaScript => "Generate 3072-bit keys: crypto.generateKeyPairSync('rsa', { modulusLength: 3072 })",
+            Lang::Php => "Generate 3072-bit keys: openssl_pkey_new(['private_key_bits' => 3072, 'private_key_type' => OPENSSL_KEYTYPE_RSA])",
+        };
+        self.report(
+            call.node,
+            "Weak RSA Key",
+            if bits <= 1024 { Severity::High } else { Severity::Medium },
+            format!("{} generates a {}-bit RSA key; keys under {} bits are within reach of factoring.", call.callee, bits, RSA_MIN_BITS),
+            "CWE-326",
+            fix_hint,
+        );
+    }
+
+    /// A predictable random call whose result lands in a secret: assigned
+    /// to or passed as a secret-looking name, or returned from a function
+    /// named like one
+    fn check_weak_random(&mut self, call: &CryptoCall, lang: Lang) {
+        let mut secret = None;
+        for ancestor in std::iter::successors(call.node.parent(), |n| n.parent()) {
+            let is_function = matches!(
+                ancestor.kind(),
+                "function_definition" | "function_declaration" | "method_declaration" | "method_definition"
+            );
+            let name = match ancestor.kind() {
+                "variable_declarator" | "keyword_argument" | "function_definition" | "function_declaration" | "method_declaration"
+                | "method_definition" => ancestor.child_by_field_name("name"),
+                "assignment" | "assignment_expression" | "augmented_assignment_expression" => ancestor.child_by_field_name("left"),
+                "pair" => ancestor.child_by_field_name("key"),
+                _ => None,
+            };
+            if let Some(name) = name.map(|n| self.text(n)).filter(|n| is_secret_name(n)) {
+                secret = Some(name);
+                break;
+            }
+            if is_function {
+                break;
+            }
+        }
+        let Some(secret) = secret else {
+            return;
+        };
+        let fix_hint = match lang {
+            Lang::Python => "Use the secrets module: secrets.token_urlsafe(32), secrets.token_hex(32) or secrets.choice(alphabet)",
+            Lang::Java => "Use SecureRandom: byte[] bytes = new byte[32]; new SecureRandom().nextBytes(bytes);",
+            Lang::JavaScript => "Use crypto.randomBytes(32).toString('hex') in Node, or crypto.getRandomValues(new Uint8Array(32)) in browsers",
+            Lang::Php => "Use bin2hex(random_bytes(32)), or random_int($min, $max) for numbers",
+        };
+        self.report(
+            call.node,
+            "Insecure Randomness",
+            Severity::High,
+            format!(
+                "{} is not cryptographically secure but generates {}; its output can be predicted from earlier values.",
+                call.callee, secret
+            ),
+            "CWE-338",
+            fix_hint,
+        );
+    }
+}
+
+fn is_js_function(node: Node) -> bool {
+    matches!(node.kind(), "function" | "function_expression" | "arrow_function" | "generator_function")
+}
+
+/// Functions, methods and declarations, each of which scopes its bindings
+fn is_js_scope(node: Node) -> bool {
+    is_js_function(node) || node.kind().ends_with("function_declaration") || node.kind() == "method_definition"
+}
+
+/// The innermost function around a node
+fn js_scope(node: Node) -> usize {
+    std::iter::successors(node.parent(), |p| p.parent())
+        .find(|p| is_js_scope(*p))
+        .map_or(ROOT_SCOPE, |p| p.id())
+}
+
+fn is_child_process_module(literal: &str) -> bool {
+    matches!(
+        literal.trim_matches(|c| c == '\'' || c == '"' || c == '`'),
+        "child_process" | "node:child_process"
+    )
+}
+
+/// Languages the cryptographic checks understand
+#[derive(Clone, Copy)]
+enum Lang {
+    JavaScript,
+    Java,
+    Php,
+    Python,
+}
+
+struct CryptoBindings<'t> {
+    /// Names assigned exactly once, and never filled with random bytes
+    values: HashMap<String, Node<'t>>,
+}
+
+/// A call or constructor invocation, in any of the languages
+struct CryptoCall<'t> {
+    node: Node<'t>,
+    /// The callee as written, e.g. `crypto.createCipheriv` or `AES.new`
+    callee: String,
+    args: Vec<Node<'t>>,
+    /// Python keyword arguments and PHP named arguments
+    named: Vec<(String, Node<'t>)>,
+}
+
+impl<'t> CryptoCall<'t> {
+    /// The last segment of the callee, e.g. `createCipheriv`
+    fn name(&self) -> &str {
+        self.callee.rsplit(['.', ':', '>']).next().unwrap_or(&self.callee)
+    }
+
+    fn named_arg(&self, name: &str) -> Option<Node<'t>> {
+        self.named.iter().find(|(n, _)| n == name).map(|(_, value)| *value)
+    }
+
+    /// A named argument, or else the positional one at `index`
+    fn arg(&self, name: &str, index: usize) -> Option<Node<'t>> {
+        self.named_arg(name).or_else(|| self.args.get(index).copied())
+    }
+}
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// A parameter value on one line, shortened for a message
+fn quote(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_QUOTED_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (line, kind) of every issue found in `source`
+    fn findings(file_ext: &str, source: &str) -> Vec<(usize, String)> {
+        scan_source(Path::new("test"), file_ext, source)
+            .expect("language has structural checks")
+            .issues
+            .into_iter()
+            .map(|issue| (issue.line, issue.kind))
+            .collect()
+    }
+
+    fn expect(found: &[(usize, String)], expected: &[(usize, &str)]) {
+        let found: Vec<(usize, &str)> = found.iter().map(|(line, kind)| (*line, kind.as_str())).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_javascript_sinks() {
+        let found = findings(
+            "js",
+            r#"const { exec } = require('child_process');
+const cp = require('child_process');
+exec('ls ' + req.query.dir);
+cp.execSync(`cat ${req.body.file}`);
+exec('ls -la');
+// exec('rm ' + req.query.x)
+eval(req.body.code);
+eval("1 + 1");
+new Function(userCode)();
+el.innerHTML = req.query.name;
+el.innerHTML = '<b>static</b>';
+const message = "exec('rm ' + req.query.x)";
+"#,
+        );
+        expect(
+            &found,
+            &[
+                (3, "Command Injection"),
+                (4, "Command Injection"),
+                (7, "Dynamic Code Evaluation"),
+                (9, "Dynamic Code Evaluation"),
+                (10, "Unsafe HTML Assignment"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_javascript_prototype_pollution() {
+        let found = findings(
+            "js",
+            r#"function merge(target, source) {
+  for (const key in source) {
+    if (typeof source[key] === 'object') merge(target[key], source[key]);
+    else target[key] = source[key];
+  }
+}
+function safeMerge(target, source) {
+  for (const key in source) {
+    if (key === '__proto__' || key === 'constructor') continue;
+    if (typeof source[key] === 'object') safeMerge(target[key], source[key]);
+    else target[key] = source[key];
+  }
+}
+const q = req.body;
+obj[q.a][q.b] = q.c;
+obj[name][field] = value;
+"#,
+        );
+        expect(&found, &[(1, "Prototype Pollution"), (15, "Prototype Pollution")]);
+    }
+
+    #[test]
+    fn test_java_sinks() {
+        let found = findings(
+            "java",
+            r#"class A {
+  void f(HttpServletRequest request, Connection conn) throws Exception {
+    String id = request.getParameter("id");
+    Statement st = conn.createStatement();
+    st.executeQuery("SELECT * FROM users WHERE id = " + id);
+    st.executeQuery("SELECT * FROM users");
+    PreparedStatement ps = conn.prepareStatement("SELECT * FROM users WHERE id = ?");
+    Runtime.getRuntime().exec("ping " + request.getParameter("host"));
+    Runtime.getRuntime().exec("uptime");
+  }
+}
+"#,
+        );
+        expect(&found, &[(5, "SQL Injection"), (8, "Command Injection")]);
+    }
+
+    #[test]
+    fn test_php_sinks() {
+        let found = findings(
+            "php",
+            r#"<?php
+$id = $_GET['id'];
+$r = mysqli_query($conn, "SELECT * FROM users WHERE id = " . $id);
+$r2 = mysqli_query($conn, "SELECT * FROM users");
+$pdo->query("SELECT * FROM t WHERE name = '$id'");
+include $_GET['page'] . '.php';
+include 'header.php';
+$name = 'static';
+$pdo->query("SELECT * FROM t WHERE name = '$name'");
+"#,
+        );
+        expect(
+            &found,
+            &[(3, "SQL Injection"), (5, "SQL Injection"), (6, "File Inclusion"), (9, "SQL Injection")],
+        );
+
+        // Request data makes it critical; other string building is still reported
+        let scan = scan_source(
+            Path::new("test"),
+            "php",
+            "<?php\nmysqli_query($c, 'SELECT ' . $_POST['col']);\nmysqli_query($c, 'SELECT ' . $col);\n",
+        )
+        .unwrap();
+        let severities: Vec<Severity> = scan.issues.into_iter().map(|issue| issue.severity).collect();
+        assert_eq!(severities, vec![Severity::Critical, Severity::High]);
+    }
+
+    #[test]
+    fn test_comment_ranges() {
+        let scan = scan_source(Path::new("test"), "js", "let a = 1; // eval(x)\n/* exec(y)\n */ run();\n").unwrap();
+        assert!(scan.issues.is_empty());
+        assert!(!scan.in_comment(0, 4));
+        assert!(scan.in_comment(0, 14));
+        assert!(scan.in_comment(1, 5));
+        assert!(!scan.in_comment(2, 5));
+        assert!(scan_source(Path::new("test"), "rb", "eval(params[:x])").is_none());
+    }
+
+    #[test]
+    fn test_python_crypto() {
+        let found = findings(
+            "py",
+            r#"from Crypto.Cipher import AES
+import hashlib, random, os
+IV = b"0123456789abcdef"
+c = AES.new(key, AES.MODE_ECB)
+c2 = AES.new(key, AES.MODE_CBC, IV)
+c3 = AES.new(key, AES.MODE_CBC, os.urandom(16))
+m = modes.CBC(b"\x00" * 16)
+e = Cipher(algorithms.AES(k), modes.ECB())
+ITER = 1000
+dk = hashlib.pbkdf2_hmac('sha256', pw, salt, ITER)
+dk2 = hashlib.pbkdf2_hmac('sha256', pw, salt, 600_000)
+kdf = PBKDF2HMAC(algorithm=hashes.SHA256(), length=32, salt=salt, iterations=100_000)
+k3 = PBKDF2(pw, salt, dkLen=32)
+token = ''.join(random.choice(chars) for _ in range(32))
+dice = random.randint(1, 6)
+def make_reset_code():
+    return random.randint(100000, 999999)
+s = random.SystemRandom().choice(chars)
+key = RSA.generate(1024)
+ok = rsa.generate_private_key(public_exponent=65537, key_size=4096)
+# AES.new(key, AES.MODE_ECB)
+"#,
+        );
+        expect(
+            &found,
+            &[
+                (4, "Insecure Cipher Mode"),
+                (5, "Static IV"),
+                (7, "Static IV"),
+                (8, "Insecure Cipher Mode"),
+                (10, "Weak Key Derivation"),
+                (12, "Weak Key Derivation"),
+                (13, "Weak Key Derivation"),
+                (14, "Insecure Randomness"),
+                (17, "Insecure Randomness"),
+                (19, "Weak RSA Key"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_severity_by_iterations() {
+        let scan = scan_source(
+            Path::new("test"),
+            "py",
+            "a = hashlib.pbkdf2_hmac('sha256', pw, salt, 1000)\nb = hashlib.pbkdf2_hmac('sha256', pw, salt, 100000)\n",
+        )
+        .unwrap();
+        let severities: Vec<Severity> = scan.issues.into_iter().map(|issue| issue.severity).collect();
+        assert_eq!(severities, vec![Severity::High, Severity::Medium]);
+    }
+
+    #[test]
+    fn test_java_crypto() {
+        let found = findings(
+            "java",
+            r#"class A {
+  static final String ALG = "AES/ECB/PKCS5Padding";
+  void f() throws Exception {
+    Cipher c = Cipher.getInstance(ALG);
+    Cipher d = Cipher.getInstance("AES");
+    Cipher g = Cipher.getInstance("AES/GCM/NoPadding");
+    byte[] iv = new byte[16];
+    IvParameterSpec s = new IvParameterSpec(iv);
+    byte[] iv2 = new byte[16]; new SecureRandom().nextBytes(iv2);
+    IvParameterSpec s2 = new IvParameterSpec(iv2);
+    PBEKeySpec spec = new PBEKeySpec(pw, salt, 1000, 256);
+    PBEKeySpec strong = new PBEKeySpec(pw, salt, 600000, 256);
+    KeyPairGenerator kpg = KeyPairGenerator.getInstance("RSA");
+    kpg.initialize(1024);
+    String sessionId = Long.toHexString(new Random().nextLong());
+    double x = Math.random();
+  }
+}
+"#,
+        );
+        expect(
+            &found,
+            &[
+                (4, "Insecure Cipher Mode"),
+                (5, "Insecure Cipher Mode"),
+                (8, "Static IV"),
+                (11, "Weak Key Derivation"),
+                (14, "Weak RSA Key"),
+                (15, "Insecure Randomness"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_javascript_crypto() {
+        let found = findings(
+            "js",
+            r#"const iv = Buffer.alloc(16, 0);
+const c = crypto.createCipheriv('aes-128-ecb', key, null);
+const d = crypto.createCipheriv('aes-256-cbc', key, iv);
+const e = crypto.createCipheriv('aes-256-cbc', key, crypto.randomBytes(16));
+const k = crypto.pbkdf2Sync(pw, salt, 10000, 32, 'sha256');
+const { publicKey } = crypto.generateKeyPairSync('rsa', { modulusLength: 1024 });
+const strong = crypto.generateKeyPairSync('rsa', { modulusLength: 4096 });
+const apiKey = Math.random().toString(36).slice(2);
+const enc = CryptoJS.AES.encrypt(msg, key, { mode: CryptoJS.mode.ECB });
+const x = Math.random();
+"#,
+        );
+        expect(
+            &found,
+            &[
+                (2, "Insecure Cipher Mode"),
+                (3, "Static IV"),
+                (5, "Weak Key Derivation"),
+                (6, "Weak RSA Key"),
+                (8, "Insecure Randomness"),
+                (9, "Insecure Cipher Mode"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_php_crypto() {
+        let found = findings(
+            "php",
+            r#"<?php
+$iv = "1234567890123456";
+$c = openssl_encrypt($data, 'aes-128-ecb', $key);
+$d = openssl_encrypt($data, 'aes-256-cbc', $key, 0, $iv);
+$e = openssl_encrypt($data, 'aes-256-cbc', $key, 0, random_bytes(16));
+$h = hash_pbkdf2('sha256', $pw, $salt, 1000);
+$k = openssl_pkey_new(['private_key_bits' => 1024]);
+$token = md5(uniqid());
+$n = mt_rand(1, 6);
+$m = mcrypt_encrypt(MCRYPT_RIJNDAEL_128, $key, $data, MCRYPT_MODE_ECB);
+$secure = bin2hex(random_bytes(32));
+"#,
+        );
+        expect(
+            &found,
+            &[
+                (3, "Insecure Cipher Mode"),
+                (4, "Static IV"),
+                (6, "Weak Key Derivation"),
+                (7, "Weak RSA Key"),
+                (8, "Insecure Randomness"),
+                (10, "Insecure Cipher Mode"),
+            ],
+        );
+    }
+}
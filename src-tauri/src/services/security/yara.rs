@@ -0,0 +1,333 @@
+//! YARA Rule Scanning
+//!
+//! YARA rule files live in `~/.ctr/yara` for every workspace and in
+//! `<workspace>/.ctr/yara` for one workspace. Each file compiles into its own
+//! namespace, named after its scope and file, so rule names only need to be
+//! unique within a file. Scans cover chosen paths or every file of the workspace,
+//! binaries included, and report each matching rule with its metadata.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::Severity;
+use crate::services::project::walker;
+use crate::utils::fs_utils::{ctr_dir, workspace_ctr_dir};
+
+/// Larger files are skipped
+const MAX_SCAN_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Per-file scan time limit, for rules with slow loops
+const SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Matched bytes shown per pattern match
+const MAX_MATCH_DATA: usize = 64;
+
+/// Matches listed per pattern
+const MAX_MATCHES_PER_PATTERN: usize = 10;
+
+const RULE_EXTENSIONS: &[&str] = &["yar", "yara"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleScope {
+    /// ~/.ctr/yara, used by every workspace
+    Global,
+    /// <workspace>/.ctr/yara
+    Workspace,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct YaraRuleFile {
+    pub name: String,
+    pub path: String,
+    pub scope: RuleScope,
+    /// Identifiers of the rules the file defines
+    pub rules: Vec<String>,
+    /// Why the file does not compile
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternMatch {
+    /// `$name` of the pattern
+    pub pattern: String,
+    pub offset: usize,
+    pub length: usize,
+    /// Matched bytes, escaped and truncated
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct YaraMatch {
+    pub file: String,
+    pub rule: String,
+    /// The rule file the rule comes from, as "global/<stem>" or
+    /// "workspace/<stem>"
+    pub namespace: String,
+    pub tags: Vec<String>,
+    pub metadata: Map<String, Value>,
+    /// From the `severity` or `threat_level` metadata; medium otherwise
+    pub severity: Severity,
+    pub matches: Vec<PatternMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct YaraScanReport {
+    pub files_scanned: usize,
+    /// The files scanned, for reconciling stored findings
+    #[serde(skip)]
+    pub scanned: Vec<String>,
+    pub matches: Vec<YaraMatch>,
+    /// "<file>: <reason>" for files and rule files that were skipped
+    pub skipped: Vec<String>,
+}
+
+fn rules_dir(scope: RuleScope, workspace_root: Option<&str>) -> Result<PathBuf, String> {
+    let dir = match (scope, workspace_root) {
+        (RuleScope::Global, _) => ctr_dir()?.join("yara"),
+        (RuleScope::Workspace, Some(root)) => workspace_ctr_dir(root)?.join("yara"),
+        (RuleScope::Workspace, None) => return Err("Workspace rules need a workspace".into()),
+    };
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+fn is_rule_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| RULE_EXTENSIONS.contains(&e))
+}
+
+/// Rule file names are plain file names with a YARA extension
+fn rule_file_path(scope: RuleScope, workspace_root: Option<&str>, name: &str) -> Result<PathBuf, String> {
+    let valid = !name.is_empty() && Path::new(name).file_name().is_some_and(|f| f == name) && is_rule_file(Path::new(name));
+    if !valid {
+        return Err(format!("Invalid rule file name {}: use a file name ending in .yar or .yara", name));
+    }
+    Ok(rules_dir(scope, workspace_root)?.join(name))
+}
+
+/// "global/<stem>" or "workspace/<stem>", so equally named files of the two
+/// scopes do not clash
+fn namespace_of(path: &Path, scope: RuleScope) -> String {
+    let scope = match scope {
+        RuleScope::Global => "global",
+        RuleScope::Workspace => "workspace",
+    };
+    format!("{}/{}", scope, path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default())
+}
+
+/// Compile one rule file's source; returns the identifiers of its rules
+pub fn check_source(source: &str) -> Result<Vec<String>, String> {
+    let mut compiler = yara_x::Compiler::new();
+    compiler.colorize_errors(false);
+    compiler.add_source(source).map_err(|e| e.to_string())?;
+    let rules = compiler.build();
+    Ok(rules.iter().map(|r| r.identifier().to_string()).collect())
+}
+
+fn describe(path: &Path, scope: RuleScope) -> YaraRuleFile {
+    let checked = std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|s| check_source(&s));
+    let (rules, error) = match checked {
+        Ok(rules) => (rules, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    YaraRuleFile {
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        scope,
+        rules,
+        error,
+    }
+}
+
+fn rule_file_paths(workspace_root: Option<&str>) -> Vec<(PathBuf, RuleScope)> {
+    let mut scopes = vec![RuleScope::Global];
+    if workspace_root.is_some() {
+        scopes.push(RuleScope::Workspace);
+    }
+    let mut files = Vec::new();
+    for scope in scopes {
+        let Ok(dir) = rules_dir(scope, workspace_root) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && is_rule_file(path))
+            .collect();
+        paths.sort();
+        files.extend(paths.into_iter().map(|path| (path, scope)));
+    }
+    files
+}
+
+/// The global rule files, and the workspace's when a workspace is given
+pub fn list_rule_files(workspace_root: Option<&str>) -> Vec<YaraRuleFile> {
+    rule_file_paths(workspace_root).into_iter().map(|(path, scope)| describe(&path, scope)).collect()
+}
+
+/// Create or replace a rule file; the source must compile
+pub fn save_rule_file(scope: RuleScope, workspace_root: Option<&str>, name: &str, source: &str) -> Result<YaraRuleFile, String> {
+    check_source(source)?;
+    let path = rule_file_path(scope, workspace_root, name)?;
+    std::fs::write(&path, source).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    log::info!("Saved YARA rule file {}", path.display());
+    Ok(describe(&path, scope))
+}
+
+/// Copy a rule file from disk into the rule directory of `scope`
+pub fn import_rule_file(scope: RuleScope, workspace_root: Option<&str>, source_path: &Path) -> Result<YaraRuleFile, String> {
+    let source = std::fs::read_to_string(source_path).map_err(|e| format!("Failed to read {}: {}", source_path.display(), e))?;
+    let name = source_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    save_rule_file(scope, workspace_root, &name, &source)
+}
+
+pub fn delete_rule_file(scope: RuleScope, workspace_root: Option<&str>, name: &str) -> Result<(), String> {
+    let path = rule_file_path(scope, workspace_root, name)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+/// Every rule file that compiles, each in its own namespace
+fn compile_rules(workspace_root: Option<&str>, skipped: &mut Vec<String>) -> Result<yara_x::Rules, String> {
+    let mut compiler = yara_x::Compiler::new();
+    compiler.colorize_errors(false);
+    let mut compiled = 0;
+    for (path, scope) in rule_file_paths(workspace_root) {
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                skipped.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+        compiler.new_namespace(&namespace_of(&path, scope));
+        match compiler.add_source(source.as_str()) {
+            Ok(_) => compiled += 1,
+            Err(e) => skipped.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    if compiled == 0 {
+        return Err("No YARA rule files compile; add rules first".into());
+    }
+    Ok(compiler.build())
+}
+
+fn meta_value(value: yara_x::MetaValue) -> Value {
+    match value {
+        yara_x::MetaValue::Integer(i) => Value::from(i),
+        yara_x::MetaValue::Float(f) => Value::from(f),
+        yara_x::MetaValue::Bool(b) => Value::from(b),
+        yara_x::MetaValue::String(s) => Value::from(s),
+        yara_x::MetaValue::Bytes(b) => Value::from(escape(b)),
+    }
+}
+
+/// Printable ASCII as is, other bytes as \xNN
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| if b.is_ascii_graphic() || b == b' ' { (b as char).to_string() } else { format!("\\x{:02x}", b) })
+        .collect()
+}
+
+/// `severity` metadata as a severity name, or `threat_level` from 0 to 10
+fn severity_of(metadata: &Map<String, Value>) -> Severity {
+    if let Some(severity) = metadata.get("severity").and_then(Value::as_str).and_then(Severity::parse) {
+        return severity;
+    }
+    match metadata.get("threat_level").and_then(Value::as_i64) {
+        Some(level) if level >= 8 => Severity::Critical,
+        Some(level) if level >= 5 => Severity::High,
+        Some(level) if level >= 3 => Severity::Medium,
+        Some(_) => Severity::Low,
+        None => Severity::Medium,
+    }
+}
+
+fn scan_one(scanner: &mut yara_x::Scanner, path: &Path) -> Result<Vec<YaraMatch>, String> {
+    let results = scanner.scan_file(path).map_err(|e| e.to_string())?;
+    let file = path.to_string_lossy().to_string();
+    Ok(results
+        .matching_rules()
+        .map(|rule| {
+            let metadata: Map<String, Value> = rule.metadata().map(|(k, v)| (k.to_string(), meta_value(v))).collect();
+            let matches = rule
+                .patterns()
+                .flat_map(|pattern| {
+                    let name = pattern.identifier().to_string();
+                    pattern.matches().take(MAX_MATCHES_PER_PATTERN).map(move |m| {
+                        let range = m.range();
+                        PatternMatch {
+                            pattern: name.clone(),
+                            offset: range.start,
+                            length: range.len(),
+                            data: escape(&m.data()[..m.data().len().min(MAX_MATCH_DATA)]),
+                        }
+                    })
+                })
+                .collect();
+            YaraMatch {
+                file: file.clone(),
+                rule: rule.identifier().to_string(),
+                namespace: rule.namespace().to_string(),
+                tags: rule.tags().map(|t| t.identifier().to_string()).collect(),
+                severity: severity_of(&metadata),
+                metadata,
+                matches,
+            }
+        })
+        .collect())
+}
+
+/// Files under `paths`, or of the whole workspace outside its excluded
+/// directories when no paths are given
+fn target_files(workspace_root: &Path, paths: &[String]) -> Vec<PathBuf> {
+    // The IDE's own data, workspace rule files included, is never scanned
+    let mut skip_dirs = super::excluded_dirs(workspace_root);
+    skip_dirs.push(".ctr".to_string());
+    if paths.is_empty() {
+        return walker::files(workspace_root, &skip_dirs, false);
+    }
+    let mut files = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        let path = if path.is_relative() { workspace_root.join(path) } else { path };
+        if path.is_dir() {
+            files.extend(walker::files(&path, &skip_dirs, false));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Scan chosen paths, or the whole workspace, with every rule file that
+/// compiles
+pub fn scan(workspace_root: &str, paths: &[String]) -> Result<YaraScanReport, String> {
+    let root = Path::new(workspace_root);
+    let mut skipped = Vec::new();
+    let rules = compile_rules(Some(workspace_root), &mut skipped)?;
+    let mut scanner = yara_x::Scanner::new(&rules);
+    scanner.set_timeout(SCAN_TIMEOUT);
+
+    let mut scanned = Vec::new();
+    let mut matches = Vec::new();
+    for path in target_files(root, paths) {
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if size > MAX_SCAN_BYTES {
+            skipped.push(format!("{}: larger than {} MB", path.display(), MAX_SCAN_BYTES / (1024 * 1024)));
+            continue;
+        }
+        match scan_one(&mut scanner, &path) {
+            Ok(found) => {
+                scanned.push(path.to_string_lossy().to_string());
+                matches.extend(found);
+            }
+            Err(e) => skipped.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    log::info!("YARA scan of {} files found {} matches", scanned.len(), matches.len());
+    Ok(YaraScanReport { files_scanned: scanned.len(), scanned, matches, skipped })
+}
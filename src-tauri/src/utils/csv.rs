@@ -0,0 +1,8 @@
+/// Quote a CSV field when it contains a delimiter, quote or newline
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,49 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get the CTR data directory (~/.ctr), creating it if needed
+pub fn ctr_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    let dir = home.join(".ctr");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create .ctr directory: {}", e))?;
+    }
+
+    Ok(dir)
+}
+
+/// Read a JSON file, falling back to the default value if it is missing or invalid
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Serialize a value as pretty JSON and write it to disk
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Get the per-workspace CTR directory (<workspace>/.ctr), creating it if needed
+pub fn workspace_ctr_dir(workspace_root: &str) -> Result<PathBuf, String> {
+    let root = Path::new(workspace_root);
+    if !root.is_dir() {
+        return Err("Workspace path does not exist".to_string());
+    }
+
+    let dir = root.join(".ctr");
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create workspace .ctr directory: {}", e))?;
+    }
+
+    Ok(dir)
+}
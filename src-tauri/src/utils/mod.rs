@@ -0,0 +1,5 @@
+pub mod blocking;
+pub mod csv;
+pub mod fs_utils;
+pub mod telementry;
+pub mod time;